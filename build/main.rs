@@ -1,4 +1,4 @@
-use gl_generator::{Registry, Api, Profile, Fallbacks};
+use gl_generator::{Registry, Api, Profile, Fallbacks, Binding, Cmd};
 use std::env;
 use std::fs::File;
 use std::io::Write;
@@ -18,7 +18,7 @@ fn main() {
 }
 
 fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
-    let gl_registry = Registry::new(
+    let mut gl_registry = Registry::new(
         Api::Gl,
         (4, 5),
         Profile::Compatibility,
@@ -34,6 +34,7 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_ARB_depth_texture",
             "GL_ARB_direct_state_access",
             "GL_ARB_draw_buffers",
+            "GL_ARB_draw_buffers_blend",
             "GL_ARB_ES2_compatibility",
             "GL_ARB_ES3_compatibility",
             "GL_ARB_ES3_1_compatibility",
@@ -42,25 +43,36 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_ARB_geometry_shader4",
             "GL_ARB_gpu_shader_fp64",
             "GL_ARB_gpu_shader_int64",
+            "GL_ARB_indirect_parameters",
             "GL_ARB_invalidate_subdata",
             "GL_ARB_multi_draw_indirect",
             "GL_ARB_occlusion_query",
+            "GL_ARB_parallel_shader_compile",
             "GL_ARB_pixel_buffer_object",
+            "GL_ARB_polygon_offset_clamp",
             "GL_ARB_robustness",
+            "GL_ARB_sample_locations",
+            "GL_ARB_sample_shading",
             "GL_ARB_seamless_cube_map",
+            "GL_ARB_separate_shader_objects",
             "GL_ARB_shader_image_load_store",
             "GL_ARB_shader_objects",
+            "GL_ARB_shading_language_include",
+            "GL_ARB_sparse_texture",
             "GL_ARB_texture_buffer_object",
+            "GL_ARB_texture_buffer_range",
             "GL_ARB_texture_float",
             "GL_ARB_texture_multisample",
             "GL_ARB_texture_rg",
             "GL_ARB_texture_rgb10_a2ui",
             "GL_ARB_transform_feedback3",
+            "GL_ARB_vertex_attrib_binding",
             "GL_ARB_vertex_buffer_object",
             "GL_ARB_vertex_shader",
             "GL_ATI_draw_buffers",
             "GL_ATI_meminfo",
             "GL_EXT_debug_marker",
+            "GL_EXT_depth_bounds_test",
             "GL_EXT_direct_state_access",
             "GL_EXT_framebuffer_blit",
             "GL_EXT_framebuffer_multisample",
@@ -68,6 +80,7 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_EXT_framebuffer_sRGB",
             "GL_EXT_gpu_shader4",
             "GL_EXT_packed_depth_stencil",
+            "GL_EXT_polygon_offset_clamp",
             "GL_EXT_provoking_vertex",
             "GL_EXT_texture_array",
             "GL_EXT_texture_buffer_object",
@@ -77,13 +90,22 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_EXT_texture_sRGB",
             "GL_EXT_transform_feedback",
             "GL_GREMEDY_string_marker",
+            "GL_KHR_blend_equation_advanced",
+            "GL_KHR_blend_equation_advanced_coherent",
+            "GL_KHR_parallel_shader_compile",
             "GL_KHR_robustness",
             "GL_NVX_gpu_memory_info",
             "GL_NV_conditional_render",
+            "GL_NV_mesh_shader",
+            "GL_NV_sample_locations",
+            "GL_NV_texture_barrier",
             "GL_NV_vertex_attrib_integer_64bit",
+            "GL_OVR_multiview",
         ],
     );
 
+    add_ext_mesh_shader(&mut gl_registry);
+
     let gles_registry = Registry::new(
         Api::Gles2,
         (3, 2),
@@ -95,13 +117,17 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_APPLE_sync",
             "GL_ARM_rgba8",
             "GL_EXT_buffer_storage",
+            "GL_EXT_clip_control",
+            "GL_EXT_discard_framebuffer",
             "GL_EXT_disjoint_timer_query",
             "GL_EXT_multi_draw_indirect",
             "GL_EXT_multisampled_render_to_texture",
             "GL_EXT_occlusion_query_boolean",
+            "GL_EXT_polygon_offset_clamp",
             "GL_EXT_primitive_bounding_box",
             "GL_EXT_robustness",
             "GL_KHR_debug",
+            "GL_KHR_parallel_shader_compile",
             "GL_NV_copy_buffer",
             "GL_NV_framebuffer_multisample",
             "GL_NV_internalformat_sample_query",
@@ -111,14 +137,98 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_OES_packed_depth_stencil",
             "GL_OES_primitive_bounding_box",
             "GL_OES_rgb8_rgba8",
+            "GL_OES_sample_shading",
             "GL_OES_texture_buffer",
             "GL_OES_texture_npot",
             "GL_OES_vertex_array_object",
             "GL_OES_vertex_type_10_10_10_2",
+            "GL_OES_viewport_array",
+            "GL_OVR_multiview",
         ],
     );
 
+    let mut bindings = Vec::new();
     (gl_registry + gles_registry)
-        .write_bindings(gl_generator::StructGenerator, dest)
+        .write_bindings(gl_generator::StructGenerator, &mut bindings)
         .unwrap();
+
+    let mut bindings = String::from_utf8(bindings).unwrap();
+    if env::var("CARGO_FEATURE_GL_CALL_LOG").is_ok() {
+        bindings = add_call_logging(&bindings);
+    }
+
+    dest.write_all(bindings.as_bytes()).unwrap();
+}
+
+/// Adds the commands of `GL_EXT_mesh_shader`, which is more recent than the `gl.xml` of
+/// `khronos_api`. Its tokens have the same values as the ones of `GL_NV_mesh_shader`.
+fn add_ext_mesh_shader(registry: &mut Registry) {
+    let binding = |ident: &str, ty: &'static str| Binding {
+        ident: ident.to_owned(),
+        ty: ty.into(),
+        group: None,
+    };
+
+    registry.cmds.insert(Cmd {
+        proto: binding("DrawMeshTasksEXT", "()"),
+        params: vec![
+            binding("num_groups_x", "types::GLuint"),
+            binding("num_groups_y", "types::GLuint"),
+            binding("num_groups_z", "types::GLuint"),
+        ],
+        alias: None,
+        vecequiv: None,
+        glx: None,
+    });
+}
+
+/// Modifies the bindings generated by `StructGenerator` so that each function records its name
+/// and its parameters in `crate::call_log` before calling the OpenGL function.
+fn add_call_logging(bindings: &str) -> String {
+    let mut output = String::with_capacity(bindings.len() * 2);
+
+    for line in bindings.lines() {
+        let prefix = "#[inline] pub unsafe fn ";
+        let start = match line.find(prefix) {
+            Some(start) => start + prefix.len(),
+            None => {
+                output.push_str(line);
+                output.push('\n');
+                continue;
+            }
+        };
+
+        // the line has the format `name(&self, params) -> ret { body }`
+        let signature = &line[start ..];
+        let name = &signature[.. signature.find('(').unwrap()];
+        let params_start = signature.find("(&self").unwrap() + "(&self".len();
+        let params_end = signature.find(") -> ").unwrap();
+        let params = signature[params_start .. params_end].trim_start_matches(", ");
+
+        let args = params.split(", ").filter(|p| !p.is_empty()).map(|param| {
+            let mut parts = param.splitn(2, ": ");
+            let ident = parts.next().unwrap();
+            let ty = parts.next().unwrap_or("");
+
+            // pointers change between runs and can't be compared
+            if ty.contains('*') || ty.contains("GLsync") || ty.contains("GLDEBUGPROC") ||
+               ty.contains("GLeglImageOES")
+            {
+                "String::from(\"<ptr>\")".to_owned()
+            } else {
+                format!("format!(\"{{:?}}\", {})", ident)
+            }
+        }).collect::<Vec<_>>().join(", ");
+
+        let body_start = start + params_end;
+        let body_start = body_start + line[body_start ..].find('{').unwrap() + 1;
+
+        output.push_str(&line[.. body_start]);
+        output.push_str(&format!(" if crate::call_log::is_recording() {{ \
+                                     crate::call_log::record(\"{}\", vec![{}]); }}", name, args));
+        output.push_str(&line[body_start ..]);
+        output.push('\n');
+    }
+
+    output
 }
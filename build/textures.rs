@@ -305,15 +305,18 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
             #![allow(unused_imports)]
 
             use std::borrow::Cow;
+            use std::rc::Rc;
 
             use crate::texture::any::{{self, TextureAny, TextureAnyLayer, TextureAnyMipmap}};
             use crate::texture::any::{{TextureAnyLayerMipmap, TextureAnyImage, Dimensions}};
+            use crate::texture::any::DetachedTexture;
             use crate::texture::bindless::{{ResidentTexture, BindlessTexturesNotSupportedError}};
             use crate::texture::get_format::{{InternalFormat, InternalFormatType, GetFormatError}};
             use crate::texture::pixel_buffer::PixelBuffer;
             use crate::texture::{{TextureCreationError, Texture1dDataSource, Texture2dDataSource}};
             use crate::texture::{{Texture3dDataSource, Texture2dDataSink, MipmapsOption, CompressedMipmapsOption}};
             use crate::texture::{{RawImage1d, RawImage2d, RawImage3d, CubeLayer}};
+            use crate::texture::{{MipmappedImage, MipmapLevel}};
             use crate::texture::pixel::PixelValue;
 
             use crate::image_format::{{ClientFormatAny, TextureFormatRequest}};
@@ -322,10 +325,11 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
             use crate::image_format::{{CompressedSrgbFormat, SrgbFormat, UncompressedUintFormat}};
 
             use crate::backend::Facade;
-            use crate::uniforms::{{UniformValue, AsUniformValue, Sampler}};
+            use crate::uniforms::{{UniformValue, AsUniformValue, Sampler, OwnedSampler}};
             use crate::framebuffer;
 
             use crate::Rect;
+            use crate::transfer::Transferable;
 
             use crate::GlObject;
             use crate::TextureExt;
@@ -411,12 +415,32 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                 }}
             ", name)).unwrap();
 
+    // `Transferable` trait impl
+    (writeln!(dest, "
+                impl Transferable for {name} {{
+                    type Detached = DetachedTexture;
+
+                    #[inline]
+                    fn detach(self) -> DetachedTexture {{
+                        self.0.detach()
+                    }}
+
+                    #[inline]
+                    unsafe fn attach<F: ?Sized>(facade: &F, detached: DetachedTexture) -> {name}
+                                                where F: Facade
+                    {{
+                        {name}(TextureAny::attach(facade, detached))
+                    }}
+                }}
+            ", name = name)).unwrap();
+
     // `UniformValue` trait impl
     {
         match ty {
             TextureType::Regular | TextureType::Compressed |
             TextureType::Srgb | TextureType::CompressedSrgb |
-            TextureType::Integral | TextureType::Unsigned | TextureType::Depth => {
+            TextureType::Integral | TextureType::Unsigned | TextureType::Depth |
+            TextureType::DepthStencil => {
                 (writeln!(dest, "
                             impl<'a> AsUniformValue for &'a {myname} {{
                                 #[inline]
@@ -432,6 +456,20 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                                 }}
                             }}
 
+                            impl AsUniformValue for Rc<{myname}> {{
+                                #[inline]
+                                fn as_uniform_value(&self) -> UniformValue {{
+                                    UniformValue::{myname}(&**self, None)
+                                }}
+                            }}
+
+                            impl AsUniformValue for OwnedSampler<{myname}> {{
+                                #[inline]
+                                fn as_uniform_value(&self) -> UniformValue {{
+                                    UniformValue::{myname}(&*self.0, Some(self.1))
+                                }}
+                            }}
+
                             impl {myname} {{
                                 /// Builds a `Sampler` marker object that allows you to indicate
                                 /// how the texture should be sampled from inside a shader.
@@ -640,6 +678,202 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                mipmaps = mipmaps_option_ty)).unwrap();
     }
 
+    // writing the `from_linear_bytes` and `from_srgb_bytes` functions
+    if dimensions == TextureDimensions::Texture2d &&
+       (ty == TextureType::Regular || ty == TextureType::Srgb)
+    {
+        let (function_name, format, color_space_doc) = if ty == TextureType::Srgb {
+            ("from_srgb_bytes", "SrgbFormat::U8U8U8U8",
+             "/// The data is considered to be in the sRGB color space, which is the case of most images
+                /// produced by artists (albedo maps, UI elements, etc.). Sampling the texture from
+                /// a shader returns values that have been converted to linear color space.")
+        } else {
+            ("from_linear_bytes", "UncompressedFloatFormat::U8U8U8U8",
+             "/// The data is considered to be in linear color space, which is the case of images that
+                /// don't contain colors (normal maps, height maps, masks, etc.). Sampling the texture
+                /// from a shader returns the values as they are stored.")
+        };
+
+        (writeln!(dest, "
+                /// Builds a new texture from tightly-packed 8-bits RGBA data.
+                ///
+                {color_space_doc}
+                ///
+                /// This function will automatically generate all mipmaps of the texture.
+                ///
+                /// ## Panic
+                ///
+                /// Panics if the length of `data` is not `4 * width * height`.
+                #[inline]
+                pub fn {function_name}<F: ?Sized>(facade: &F, data: &[u8], dimensions: (u32, u32))
+                                                  -> Result<{name}, TextureCreationError>
+                                                  where F: Facade
+                {{
+                    assert_eq!(data.len(), 4 * dimensions.0 as usize * dimensions.1 as usize);
+
+                    let image = RawImage2d {{
+                        data: Cow::Borrowed(data),
+                        width: dimensions.0,
+                        height: dimensions.1,
+                        format: crate::image_format::ClientFormat::U8U8U8U8,
+                    }};
+
+                    {name}::new_impl(facade, image, Some({format}), MipmapsOption::AutoGeneratedMipmaps)
+                }}
+            ", function_name = function_name, format = format, name = name,
+               color_space_doc = color_space_doc)).unwrap();
+    }
+
+    // writing the `with_mipmapped_image` functions
+    if !dimensions.is_multisample() && !dimensions.is_cube() {
+        let (level_ty, param) = match dimensions {
+            TextureDimensions::Texture1d => ("RawImage1d", "MipmappedImage<RawImage1d<'a, P>>"),
+            TextureDimensions::Texture2d => ("RawImage2d", "MipmappedImage<RawImage2d<'a, P>>"),
+            TextureDimensions::Texture3d => ("RawImage3d", "MipmappedImage<RawImage3d<'a, P>>"),
+            TextureDimensions::Texture1dArray =>
+                ("RawImage1d", "Vec<MipmappedImage<RawImage1d<'a, P>>>"),
+            TextureDimensions::Texture2dArray =>
+                ("RawImage2d", "Vec<MipmappedImage<RawImage2d<'a, P>>>"),
+            _ => unreachable!()
+        };
+
+        let layers_doc = if dimensions.is_array() {
+            "/// There is one image for each layer of the texture. All the images must have the same
+                /// number of levels, the same dimensions and the same format, otherwise
+                /// `DimensionsNotSupported` is returned.
+                ///"
+        } else {
+            "///"
+        };
+
+        (writeln!(dest, "
+                /// Builds a new texture and uploads all the levels of a mipmapped image.
+                ///
+                {layers_doc}
+                /// The texture has exactly as many levels as the image. No mipmap is generated.
+                #[inline]
+                pub fn with_mipmapped_image<'a, F: ?Sized, P>(facade: &F, image: {param})
+                                                              -> Result<{name}, TextureCreationError>
+                                                              where P: PixelValue + Clone, F: Facade
+                {{
+                    {name}::with_mipmapped_image_impl(facade, image, None)
+                }}
+
+                /// Builds a new texture with a specific format and uploads all the levels of a
+                /// mipmapped image.
+                ///
+                {layers_doc}
+                /// The texture has exactly as many levels as the image. No mipmap is generated.
+                #[inline]
+                pub fn with_format_and_mipmapped_image<'a, F: ?Sized, P>(facade: &F,
+                                                       image: {param}, format: {format})
+                                                       -> Result<{name}, TextureCreationError>
+                                                       where P: PixelValue + Clone, F: Facade
+                {{
+                    {name}::with_mipmapped_image_impl(facade, image, Some(format))
+                }}
+
+                fn with_mipmapped_image_impl<'a, F: ?Sized, P>(facade: &F, image: {param},
+                                                               format: Option<{format}>)
+                                                               -> Result<{name}, TextureCreationError>
+                                                               where P: PixelValue + Clone, F: Facade
+                {{
+            ", format = relevant_format, name = name, param = param,
+               layers_doc = layers_doc)).unwrap();
+
+        // building `main_level`, the data passed to `new_impl`, and `levels`, an iterator over
+        // the data of the other levels
+        if dimensions.is_array() {
+            (writeln!(dest, "
+                    let num_levels = match image.first() {{
+                        Some(layer) => layer.get_levels_count(),
+                        None => return Err(TextureCreationError::DimensionsNotSupported),
+                    }};
+
+                    let main_image = image[0].get_levels();
+                    for layer in image.iter() {{
+                        let consistent = layer.get_levels_count() == num_levels &&
+                            layer.get_levels().iter().zip(main_image).all(|(a, b)| {{
+                                a.dimensions() == b.dimensions() &&
+                                a.client_format() == b.client_format()
+                            }});
+
+                        if !consistent {{
+                            return Err(TextureCreationError::DimensionsNotSupported);
+                        }}
+                    }}
+
+                    let mut layers = image.into_iter().map(|layer| layer.into_levels().into_iter())
+                                          .collect::<Vec<_>>();
+                    let mut next_level = move || {{
+                        // the layers have been checked to have the same number of levels
+                        layers.iter_mut().map(|layer| layer.next().unwrap()).collect::<Vec<_>>()
+                    }};
+
+                    let main_level = next_level();
+                    let levels = (1 .. num_levels).map(|_| next_level());
+                ")).unwrap();
+        } else {
+            (writeln!(dest, "
+                    let mut levels = image.into_levels().into_iter();
+                    // a `MipmappedImage` always has a main level
+                    let main_level = levels.next().unwrap();
+                ")).unwrap();
+        }
+
+        (writeln!(dest, "
+                    let mipmaps = {mipmaps}::EmptyMipmapsMax(levels.len() as u32);
+                    let texture = {name}::new_impl(facade, main_level, format, mipmaps)?;
+
+                    for (level, image) in levels.enumerate() {{
+            ", name = name, mipmaps = mipmaps_option_ty)).unwrap();
+
+        // destructuring `image` into `data`, `client_format` and the dimensions to upload
+        match dimensions {
+            TextureDimensions::Texture1d => (writeln!(dest, "
+                    let {level_ty} {{ data, width, format: client_format }} = image;
+                    let (height, depth) = (None, None);
+                ", level_ty = level_ty)).unwrap(),
+
+            TextureDimensions::Texture2d => (writeln!(dest, "
+                    let {level_ty} {{ data, width, height, format: client_format }} = image;
+                    let (height, depth) = (Some(height), None);
+                ", level_ty = level_ty)).unwrap(),
+
+            TextureDimensions::Texture3d => (writeln!(dest, "
+                    let {level_ty} {{ data, width, height, depth, format: client_format }} = image;
+                    let (height, depth) = (Some(height), Some(depth));
+                ", level_ty = level_ty)).unwrap(),
+
+            TextureDimensions::Texture1dArray => (writeln!(dest, "
+                    let RawImage2d {{ data, width, height: array_size, format: client_format }} =
+                                                                RawImage2d::from_vec_raw1d(&image);
+                    let (height, depth) = (Some(array_size), None);
+                ")).unwrap(),
+
+            TextureDimensions::Texture2dArray => (writeln!(dest, "
+                    let RawImage3d {{ data, width, height, depth: array_size, format: client_format }} =
+                                                                RawImage3d::from_vec_raw2d(&image);
+                    let (height, depth) = (Some(height), Some(array_size));
+                ")).unwrap(),
+
+            _ => unreachable!()
+        }
+
+        (writeln!(dest, "
+                        let client_format = ClientFormatAny::ClientFormat(client_format);
+                        let mipmap = texture.mipmap(level as u32 + 1)
+                                            .ok_or(TextureCreationError::DimensionsNotSupported)?;
+                        mipmap.upload_texture(0, 0, 0, (client_format, data), width, height, depth,
+                                              true)
+                              .map_err(|_| TextureCreationError::FormatNotSupported)?;
+                    }}
+
+                    Ok(texture)
+                }}
+            ")).unwrap();
+    }
+
     // writing the `new_impl` function
     if !dimensions.is_multisample() && !dimensions.is_cube() {
         let param = match dimensions {
@@ -870,6 +1104,23 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                 }}
             "#)).unwrap();
 
+        (write!(dest, r#"
+                /// Starts reading the content of the texture, and returns without waiting for
+                /// the GPU. This method may only read `U8U8U8U8` data.
+                ///
+                /// The data is copied into a pixel buffer. The returned object can be polled,
+                /// or used as a `Future`, to obtain the data once the copy has finished.
+                #[inline]
+                pub fn read_async<T>(&self) -> crate::PendingRead<T>
+                                     where T: Texture2dDataSink<(u8, u8, u8, u8)>
+                {{
+                    let rect = Rect {{ left: 0, bottom: 0, width: self.get_width(),
+                                       height: self.get_height().unwrap_or(1) }};
+                    self.0.main_level().first_layer().into_image(None).unwrap()
+                          .raw_read_async(&rect)
+                }}
+            "#)).unwrap();
+
         (write!(dest, r#"
                 /// Unsafely reads the content of the texture to RAM in the specified pixel format.
                 /// It is possible that the current OpenGL context does not support the given
@@ -921,6 +1172,22 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                 pub fn read_compressed_data(&self) -> Option<({format}, Vec<u8>)> {{
                     self.main_level().read_compressed_data()
                 }}
+
+                /// Reads the content of a mipmap level of the texture to RAM without
+                /// decompressing it before.
+                ///
+                /// You should avoid doing this at all cost during performance-critical
+                /// operations (for example, while you're drawing).
+                ///
+                /// Returns the compressed format of the texture and the compressed data, gives
+                /// `None` when the level doesn't exist or when the internal compression format
+                /// is generic or unknown.
+                #[inline]
+                pub fn read_compressed_data_level(&self, level: u32)
+                                                  -> Option<({format}, Vec<u8>)>
+                {{
+                    self.mipmap(level).and_then(|mipmap| mipmap.read_compressed_data())
+                }}
             "#, format = relevant_format)).unwrap();
     }
 
@@ -961,6 +1228,66 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                 compressed_restrictions = compressed_restrictions)).unwrap();
     }
 
+    // writing the `write_async` function
+    if dimensions == TextureDimensions::Texture2d &&
+            (ty == TextureType::Regular || ty == TextureType::Srgb)
+    {
+        (write!(dest, r#"
+                /// Uploads some data in the texture through a pixel buffer of `uploader`, without
+                /// waiting for the transfer to finish.
+                ///
+                /// The texture can be used immediately. The returned ticket tells whether the
+                /// transfer has finished.
+                ///
+                /// ## Panic
+                ///
+                /// Panics if the the dimensions of `data` don't match the `Rect`.
+                #[inline]
+                pub fn write_async<'a, T>(&self, uploader: &mut crate::texture::async_upload::AsyncUploader,
+                                          rect: Rect, data: T)
+                                          -> Result<crate::texture::async_upload::UploadTicket,
+                                                    crate::texture::async_upload::AsyncUploadError>
+                                          where T: {data_source_trait}<'a>
+                {{
+                    uploader.write(self.0.main_level(), rect, data)
+                }}
+            "#, data_source_trait = data_source_trait)).unwrap();
+    }
+
+    // writing the `resolve_to` function
+    if dimensions == TextureDimensions::Texture2dMultisample ||
+       dimensions == TextureDimensions::Texture2dMultisampleArray
+    {
+        let what = match ty {
+            TextureType::Depth => "The depth value of one of the samples of each texel is kept.",
+            TextureType::Stencil => "The stencil value of one of the samples of each texel is kept.",
+            TextureType::DepthStencil =>
+                "The depth and stencil values of one of the samples of each texel are kept.",
+            TextureType::Integral | TextureType::Unsigned =>
+                "The value of one of the samples of each texel is kept.",
+            _ => "The samples of each texel are averaged.",
+        };
+        let layers = if dimensions == TextureDimensions::Texture2dMultisampleArray {
+            "\n                /// Each layer is resolved into the layer with the same index."
+        } else {
+            ""
+        };
+
+        (write!(dest, r#"
+                /// Resolves the texture into a non-multisample texture with the same dimensions.
+                /// {what}{layers}
+                ///
+                /// Returns an error if the dimensions or the formats of the two textures are not
+                /// compatible.
+                #[inline]
+                pub fn resolve_to(&self, target: &crate::texture::{target})
+                                  -> Result<(), crate::texture::CopyImageError>
+                {{
+                    self.0.main_level().resolve_to(&(**target).main_level())
+                }}
+            "#, what = what, layers = layers, target = name.replace("Multisample", ""))).unwrap();
+    }
+
     // writing the `write_compressed_data` function
     // TODO: implement for other types too
     if dimensions == TextureDimensions::Texture2d && is_compressed
@@ -1401,6 +1728,21 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
             },
             _ => ()
         }
+
+        // all the layers of a mipmap level can be attached at once
+        let layered = dimensions.is_array() || dimensions.is_cube() ||
+                      dimensions == TextureDimensions::Texture3d;
+        let compressed = ty == TextureType::Compressed || ty == TextureType::CompressedSrgb;
+        if layered && !compressed {
+            (writeln!(dest, "
+                    impl<'t> Into<crate::framebuffer::LayeredAttachment<'t>> for {name}Mipmap<'t> {{
+                        #[inline]
+                        fn into(self) -> crate::framebuffer::LayeredAttachment<'t> {{
+                            crate::framebuffer::LayeredAttachment::new(self.0)
+                        }}
+                    }}
+                ", name = name)).unwrap();
+        }
     }
 
     // closing `mod module {`
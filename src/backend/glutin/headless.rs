@@ -9,7 +9,7 @@ use std::cell::RefCell;
 use std::ops::Deref;
 use std::os::raw::c_void;
 use super::glutin;
-use super::glutin::{PossiblyCurrent as Pc, ContextCurrentState};
+use super::glutin::{PossiblyCurrent as Pc, NotCurrent, ContextCurrentState};
 use takeable_option::Takeable;
 
 /// A headless glutin context.
@@ -18,6 +18,45 @@ pub struct Headless {
     glutin: Rc<RefCell<Takeable<glutin::Context<Pc>>>>,
 }
 
+/// A headless context whose objects are shared with a `Display`, and that can be sent to another
+/// thread to create resources in the background.
+///
+/// Build it with `Display::create_resource_context`, send it to a thread, then call
+/// `make_current` on this thread. The objects created there can be moved to the display with a
+/// `transfer::TransferTicket`.
+pub struct ResourceContext {
+    context: glutin::Context<NotCurrent>,
+    share_group: context::ShareGroupId,
+}
+
+impl ResourceContext {
+    /// Builds a `ResourceContext` from a glutin context whose lists are shared with the
+    /// contexts of a group.
+    ///
+    /// # Unsafety
+    ///
+    /// The glutin context must have been created with its lists shared with the OpenGL
+    /// contexts of the group.
+    pub unsafe fn new(context: glutin::Context<NotCurrent>, share_group: context::ShareGroupId)
+                      -> ResourceContext
+    {
+        ResourceContext { context, share_group }
+    }
+
+    /// Makes the context current on the calling thread, and returns a `Headless` that can be
+    /// used to create objects.
+    pub fn make_current(self) -> Result<Headless, IncompatibleOpenGl> {
+        let context = unsafe { self.context.treat_as_current() };
+        let glutin_context = Rc::new(RefCell::new(Takeable::new(context)));
+        let glutin_backend = GlutinBackend(glutin_context.clone());
+        let context = unsafe {
+            context::Context::new_in_share_group(glutin_backend, true, Default::default(),
+                                                 self.share_group)
+        }?;
+        Ok(Headless { context, glutin: glutin_context })
+    }
+}
+
 /// An implementation of the `Backend` trait for a glutin headless context.
 pub struct GlutinBackend(Rc<RefCell<Takeable<glutin::Context<Pc>>>>);
 
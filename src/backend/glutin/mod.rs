@@ -12,9 +12,12 @@ pub use glutin;
 
 pub mod headless;
 
+mod platform;
+
 use crate::backend;
 use crate::backend::Backend;
 use crate::backend::Context;
+use crate::CapabilitiesSource;
 use crate::context;
 use crate::debug;
 use crate::glutin::{ContextCurrentState, PossiblyCurrent as Pc};
@@ -25,7 +28,7 @@ use std::ops::Deref;
 use std::os::raw::c_void;
 use std::rc::Rc;
 use takeable_option::Takeable;
-use crate::{Frame, IncompatibleOpenGl, SwapBuffersError};
+use crate::{Frame, IncompatibleOpenGl, Rect, SwapBuffersError, SwapInterval, SwapIntervalError};
 
 /// A GL context combined with a facade for drawing upon.
 ///
@@ -43,6 +46,75 @@ pub struct Display {
     last_framebuffer_dimensions: Cell<(u32, u32)>,
 }
 
+/// Format of the color buffer of the default framebuffer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColorBufferFormat {
+    /// 8 bits for each color component and 8 bits of alpha.
+    Rgba8,
+
+    /// 10 bits for each color component and 2 bits of alpha. Used by deep-color displays.
+    Rgb10A2,
+
+    /// 16-bit floating-point components. Used by HDR displays, as values can be outside of
+    /// the `[0.0, 1.0]` range.
+    Rgba16F,
+}
+
+/// Extension trait for glutin's `ContextBuilder`.
+pub trait ContextBuilderExt {
+    /// Requests a color format for the default framebuffer.
+    ///
+    /// sRGB is disabled for `Rgb10A2` and `Rgba16F`, as these formats don't have an sRGB
+    /// variant. Use `Display::get_framebuffer_pixel_format` to know the format that has
+    /// actually been obtained.
+    fn with_color_buffer_format(self, format: ColorBufferFormat) -> Self;
+}
+
+impl<'a, T: ContextCurrentState> ContextBuilderExt for glutin::ContextBuilder<'a, T> {
+    fn with_color_buffer_format(self, format: ColorBufferFormat) -> Self {
+        let mut builder = match format {
+            ColorBufferFormat::Rgba8 => self.with_pixel_format(24, 8),
+            ColorBufferFormat::Rgb10A2 => self.with_pixel_format(30, 2).with_srgb(false),
+            ColorBufferFormat::Rgba16F => self.with_pixel_format(48, 16).with_srgb(false),
+        };
+
+        builder.pf_reqs.float_color_buffer = format == ColorBufferFormat::Rgba16F;
+        builder
+    }
+}
+
+/// Pixel format of the default framebuffer of a `Display`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FramebufferPixelFormat {
+    /// Number of bits of the color components, not including alpha.
+    pub color_bits: u8,
+    /// Number of bits of the alpha component.
+    pub alpha_bits: u8,
+    /// True if the color components are floating-point values.
+    pub float_color_buffer: bool,
+    /// True if the color buffer is in sRGB.
+    pub srgb: bool,
+    /// Number of bits of the depth buffer.
+    pub depth_bits: u8,
+    /// Number of bits of the stencil buffer.
+    pub stencil_bits: u8,
+    /// Number of samples, or `None` if the framebuffer isn't multisampled.
+    pub multisampling: Option<u16>,
+}
+
+impl FramebufferPixelFormat {
+    /// Returns the format of the color buffer, or `None` if it isn't one of the formats of
+    /// `ColorBufferFormat`.
+    pub fn color_buffer_format(&self) -> Option<ColorBufferFormat> {
+        match (self.color_bits, self.alpha_bits, self.float_color_buffer) {
+            (24, 8, false) => Some(ColorBufferFormat::Rgba8),
+            (30, 2, false) => Some(ColorBufferFormat::Rgb10A2),
+            (48, 16, true) => Some(ColorBufferFormat::Rgba16F),
+            _ => None,
+        }
+    }
+}
+
 /// An implementation of the `Backend` trait for glutin.
 #[derive(Clone)]
 pub struct GlutinBackend(Rc<RefCell<Takeable<glutin::WindowedContext<Pc>>>>);
@@ -67,6 +139,11 @@ impl Display {
     ///
     /// Performs a compatibility check to make sure that all core elements of glium are supported
     /// by the implementation.
+    ///
+    /// To draw on a stereo display, request a stereo pixel format with
+    /// `ContextBuilder::with_stereoscopy` and choose the back buffer of each eye with
+    /// `Frame::draw_to_buffer`. You can check whether the pixel format is stereo with
+    /// `Capabilities::stereo`.
     pub fn new<T: ContextCurrentState, E>(
         wb: glutin::window::WindowBuilder,
         cb: glutin::ContextBuilder<'_, T>,
@@ -129,6 +206,78 @@ impl Display {
         })
     }
 
+    /// Creates a new window whose OpenGL context shares its objects with this display.
+    ///
+    /// Buffers, textures, renderbuffers and programs created with any display of the group can
+    /// be used with the other ones, for example to share a texture atlas between the viewports
+    /// of an editor. Each display has its own `draw` method. Query objects, as well as the
+    /// framebuffer objects and vertex array objects that glium creates internally, aren't shared.
+    ///
+    /// See `Context::new_shared`.
+    pub fn new_shared<T: ContextCurrentState, E>(
+        &self,
+        wb: glutin::window::WindowBuilder,
+        cb: glutin::ContextBuilder<'_, T>,
+        events_loop: &glutin::event_loop::EventLoop<E>,
+    ) -> Result<Display, DisplayCreationError> {
+        let gl_window = {
+            let gl_window = self.gl_window.borrow();
+            let cb = cb.with_shared_lists(gl_window.context());
+            cb.build_windowed(wb, events_loop)?
+        };
+
+        let gl_window = unsafe { gl_window.treat_as_current() };
+        let gl_window = Rc::new(RefCell::new(Takeable::new(gl_window)));
+        let glutin_backend = GlutinBackend(gl_window.clone());
+        let framebuffer_dimensions = glutin_backend.get_framebuffer_dimensions();
+        let context = unsafe {
+            context::Context::new_shared(glutin_backend, true, Default::default(), &self.context)
+        }?;
+
+        Ok(Display {
+            gl_window,
+            context,
+            last_framebuffer_dimensions: Cell::new(framebuffer_dimensions),
+        })
+    }
+
+    /// Creates a headless context whose objects are shared with this display, and that can be
+    /// sent to another thread to create textures and buffers in the background.
+    ///
+    /// See the `transfer` module.
+    pub fn create_resource_context<E>(
+        &self,
+        events_loop: &glutin::event_loop::EventLoopWindowTarget<E>,
+    ) -> Result<headless::ResourceContext, DisplayCreationError> {
+        // the API and the version of the contexts must match for them to share their objects
+        let version = self.context.get_opengl_version();
+        let api = match version.0 {
+            crate::Api::Gl => glutin::Api::OpenGl,
+            crate::Api::GlEs => glutin::Api::OpenGlEs,
+        };
+
+        let mut cb = glutin::ContextBuilder::new()
+            .with_gl(glutin::GlRequest::Specific(api, (version.1, version.2)));
+
+        if let Some(profile) = self.context.get_capabilities().profile {
+            cb = cb.with_gl_profile(match profile {
+                crate::Profile::Core => glutin::GlProfile::Core,
+                crate::Profile::Compatibility => glutin::GlProfile::Compatibility,
+            });
+        }
+
+        let context = {
+            let gl_window = self.gl_window.borrow();
+            let cb = cb.with_shared_lists(gl_window.context());
+            cb.build_headless(events_loop, (1, 1).into())?
+        };
+
+        // the display may still have commands that create objects
+        self.context.flush();
+
+        Ok(unsafe { headless::ResourceContext::new(context, self.context.get_share_group_id()) })
+    }
+
     /// Rebuilds the Display's `WindowedContext` with the given window and context builders.
     ///
     /// This method ensures that the new `WindowedContext`'s `Context` will share the display lists of the
@@ -160,6 +309,24 @@ impl Display {
         Ok(())
     }
 
+    /// Returns the pixel format of the default framebuffer that has actually been obtained.
+    ///
+    /// See `ContextBuilderExt::with_color_buffer_format` to request a format for HDR or
+    /// deep-color displays.
+    pub fn get_framebuffer_pixel_format(&self) -> FramebufferPixelFormat {
+        let pixel_format = self.gl_window.borrow().get_pixel_format();
+
+        FramebufferPixelFormat {
+            color_bits: pixel_format.color_bits,
+            alpha_bits: pixel_format.alpha_bits,
+            float_color_buffer: self.context.get_capabilities().float_color_buffer,
+            srgb: pixel_format.srgb,
+            depth_bits: pixel_format.depth_bits,
+            stencil_bits: pixel_format.stencil_bits,
+            multisampling: pixel_format.multisampling,
+        }
+    }
+
     /// Borrow the inner glutin WindowedContext.
     #[inline]
     pub fn gl_window(&self) -> Ref<'_, Takeable<glutin::WindowedContext<Pc>>> {
@@ -267,6 +434,27 @@ unsafe impl Backend for GlutinBackend {
         }
     }
 
+    fn swap_buffers_with_damage(&self, damage: &[Rect]) -> Result<(), SwapBuffersError> {
+        let gl_window = self.borrow();
+        if !gl_window.swap_buffers_with_damage_supported() {
+            drop(gl_window);
+            return self.swap_buffers();
+        }
+
+        let damage = damage.iter().map(|rect| glutin::Rect {
+            x: rect.left,
+            y: rect.bottom,
+            width: rect.width,
+            height: rect.height,
+        }).collect::<Vec<_>>();
+
+        match gl_window.swap_buffers_with_damage(&damage) {
+            Ok(()) => Ok(()),
+            Err(glutin::ContextError::ContextLost) => Err(SwapBuffersError::ContextLost),
+            Err(e) => panic!("Error while swapping buffers with damage: {:?}", e),
+        }
+    }
+
     #[inline]
     unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
         self.borrow().get_proc_address(symbol) as *const _
@@ -292,4 +480,24 @@ unsafe impl Backend for GlutinBackend {
         let new_gl_window = gl_window.make_current().unwrap();
         Takeable::insert(&mut gl_window_takeable, new_gl_window);
     }
+
+    #[inline]
+    fn get_swapchain_image_count(&self) -> Option<u32> {
+        if self.borrow().get_pixel_format().double_buffer {
+            Some(2)
+        } else {
+            Some(1)
+        }
+    }
+
+    #[inline]
+    fn get_buffer_age(&self) -> Option<u32> {
+        // glutin doesn't give access to the buffer age, so we query it from EGL or GLX
+        unsafe { platform::get_buffer_age(&self.borrow()) }
+    }
+
+    #[inline]
+    fn set_swap_interval(&self, interval: SwapInterval) -> Result<(), SwapIntervalError> {
+        unsafe { platform::set_swap_interval(&self.borrow(), interval) }
+    }
 }
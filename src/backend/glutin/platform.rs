@@ -0,0 +1,263 @@
+//! Calls functions of the windowing system that glutin doesn't expose.
+//!
+//! glutin only lets us choose vsync when the context is created and doesn't give access to the
+//! buffer age, so we load the EGL, GLX and WGL functions ourselves. The context must be current.
+
+use std::ffi::CStr;
+use std::mem;
+use std::os::raw::{c_char, c_void};
+
+use crate::glutin::platform::ContextTraitExt;
+use crate::glutin::{PossiblyCurrent as Pc, WindowedContext};
+use crate::{SwapInterval, SwapIntervalError};
+
+/// Changes the swap interval of the context.
+pub unsafe fn set_swap_interval(gl_window: &WindowedContext<Pc>, interval: SwapInterval)
+                                -> Result<(), SwapIntervalError>
+{
+    // a negative interval means adaptive vsync
+    let interval = match interval {
+        SwapInterval::DontWait => 0,
+        SwapInterval::Wait(n) => n as i32,
+        SwapInterval::AdaptiveWait(n) => -(n as i32),
+    };
+
+    if let Some(display) = gl_window.get_egl_display() {
+        return set_egl_swap_interval(gl_window, display, interval);
+    }
+
+    set_native_swap_interval(gl_window, interval)
+}
+
+/// Returns the age of the back buffer, or `None` if it can't be queried.
+pub unsafe fn get_buffer_age(gl_window: &WindowedContext<Pc>) -> Option<u32> {
+    if let Some(display) = gl_window.get_egl_display() {
+        return get_egl_buffer_age(gl_window, display);
+    }
+
+    get_native_buffer_age(gl_window)
+}
+
+/// Returns the address of a function, or `None` if it isn't available.
+unsafe fn load(gl_window: &WindowedContext<Pc>, name: &str) -> Option<*const c_void> {
+    let ptr = gl_window.get_proc_address(name) as *const c_void;
+    if ptr.is_null() { None } else { Some(ptr) }
+}
+
+/// Returns true if `name` is in the space-separated list of extensions.
+unsafe fn has_extension(extensions: *const c_char, name: &str) -> bool {
+    if extensions.is_null() {
+        return false;
+    }
+
+    CStr::from_ptr(extensions).to_string_lossy().split(' ').any(|e| e == name)
+}
+
+unsafe fn set_egl_swap_interval(gl_window: &WindowedContext<Pc>, display: *const c_void,
+                                interval: i32) -> Result<(), SwapIntervalError>
+{
+    type SwapIntervalFn = unsafe extern "system" fn(*const c_void, i32) -> u32;
+
+    // EGL doesn't have an equivalent of `EXT_swap_control_tear`
+    if interval < 0 {
+        return Err(SwapIntervalError::AdaptiveNotSupported);
+    }
+
+    let swap_interval: SwapIntervalFn = match load(gl_window, "eglSwapInterval") {
+        Some(f) => mem::transmute(f),
+        None => return Err(SwapIntervalError::NotSupported),
+    };
+
+    if swap_interval(display, interval) != 0 {
+        Ok(())
+    } else {
+        Err(SwapIntervalError::NotSupported)
+    }
+}
+
+unsafe fn get_egl_buffer_age(gl_window: &WindowedContext<Pc>, display: *const c_void)
+                             -> Option<u32>
+{
+    type QueryStringFn = unsafe extern "system" fn(*const c_void, i32) -> *const c_char;
+    type GetCurrentSurfaceFn = unsafe extern "system" fn(i32) -> *const c_void;
+    type QuerySurfaceFn = unsafe extern "system" fn(*const c_void, *const c_void, i32,
+                                                    *mut i32) -> u32;
+
+    const EGL_EXTENSIONS: i32 = 0x3055;
+    const EGL_DRAW: i32 = 0x3059;
+    const EGL_BUFFER_AGE_EXT: i32 = 0x313D;
+
+    let query_string: QueryStringFn = mem::transmute(load(gl_window, "eglQueryString")?);
+    if !has_extension(query_string(display, EGL_EXTENSIONS), "EGL_EXT_buffer_age") {
+        return None;
+    }
+
+    let get_current_surface: GetCurrentSurfaceFn =
+        mem::transmute(load(gl_window, "eglGetCurrentSurface")?);
+    let query_surface: QuerySurfaceFn = mem::transmute(load(gl_window, "eglQuerySurface")?);
+
+    let mut age = 0;
+    if query_surface(display, get_current_surface(EGL_DRAW), EGL_BUFFER_AGE_EXT, &mut age) != 0 {
+        Some(age as u32)
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "windows")]
+unsafe fn set_native_swap_interval(gl_window: &WindowedContext<Pc>, interval: i32)
+                                   -> Result<(), SwapIntervalError>
+{
+    type SwapIntervalFn = unsafe extern "system" fn(i32) -> i32;
+
+    let swap_interval: SwapIntervalFn = match load(gl_window, "wglSwapIntervalEXT") {
+        Some(f) => mem::transmute(f),
+        None => return Err(SwapIntervalError::NotSupported),
+    };
+
+    // negative intervals fail without `WGL_EXT_swap_control_tear`
+    if swap_interval(interval) != 0 {
+        Ok(())
+    } else if interval < 0 {
+        Err(SwapIntervalError::AdaptiveNotSupported)
+    } else {
+        Err(SwapIntervalError::NotSupported)
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+          target_os = "netbsd", target_os = "openbsd"))]
+mod glx {
+    use std::mem;
+    use std::os::raw::{c_char, c_int, c_ulong, c_void};
+
+    use super::load;
+    use crate::glutin::{PossiblyCurrent as Pc, WindowedContext};
+
+    type GetCurrentDisplayFn = unsafe extern "C" fn() -> *mut c_void;
+    type GetCurrentDrawableFn = unsafe extern "C" fn() -> c_ulong;
+    type GetCurrentContextFn = unsafe extern "C" fn() -> *mut c_void;
+    type QueryContextFn = unsafe extern "C" fn(*mut c_void, *mut c_void, c_int,
+                                               *mut c_int) -> c_int;
+    type QueryExtensionsStringFn = unsafe extern "C" fn(*mut c_void, c_int) -> *const c_char;
+
+    const GLX_SCREEN: c_int = 0x800C;
+
+    /// The X display, the drawable and the extensions of the current GLX context.
+    pub struct Current {
+        pub display: *mut c_void,
+        pub drawable: c_ulong,
+        pub extensions: *const c_char,
+    }
+
+    pub unsafe fn current(gl_window: &WindowedContext<Pc>) -> Option<Current> {
+        let get_current_display: GetCurrentDisplayFn =
+            mem::transmute(load(gl_window, "glXGetCurrentDisplay")?);
+        let get_current_drawable: GetCurrentDrawableFn =
+            mem::transmute(load(gl_window, "glXGetCurrentDrawable")?);
+        let get_current_context: GetCurrentContextFn =
+            mem::transmute(load(gl_window, "glXGetCurrentContext")?);
+        let query_context: QueryContextFn = mem::transmute(load(gl_window, "glXQueryContext")?);
+        let query_extensions_string: QueryExtensionsStringFn =
+            mem::transmute(load(gl_window, "glXQueryExtensionsString")?);
+
+        let display = get_current_display();
+        if display.is_null() {
+            return None;
+        }
+
+        let mut screen = 0;
+        query_context(display, get_current_context(), GLX_SCREEN, &mut screen);
+
+        Some(Current {
+            display,
+            drawable: get_current_drawable(),
+            extensions: query_extensions_string(display, screen),
+        })
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+          target_os = "netbsd", target_os = "openbsd"))]
+unsafe fn set_native_swap_interval(gl_window: &WindowedContext<Pc>, interval: i32)
+                                   -> Result<(), SwapIntervalError>
+{
+    use std::os::raw::{c_int, c_uint, c_ulong};
+
+    type SwapIntervalExtFn = unsafe extern "C" fn(*mut c_void, c_ulong, c_int);
+    type SwapIntervalMesaFn = unsafe extern "C" fn(c_uint) -> c_int;
+
+    let glx = match glx::current(gl_window) {
+        Some(glx) => glx,
+        None => return Err(SwapIntervalError::NotSupported),
+    };
+
+    if has_extension(glx.extensions, "GLX_EXT_swap_control") {
+        // negative intervals generate an X error without `GLX_EXT_swap_control_tear`
+        if interval < 0 && !has_extension(glx.extensions, "GLX_EXT_swap_control_tear") {
+            return Err(SwapIntervalError::AdaptiveNotSupported);
+        }
+
+        let swap_interval: SwapIntervalExtFn = match load(gl_window, "glXSwapIntervalEXT") {
+            Some(f) => mem::transmute(f),
+            None => return Err(SwapIntervalError::NotSupported),
+        };
+
+        swap_interval(glx.display, glx.drawable, interval);
+        return Ok(());
+    }
+
+    if has_extension(glx.extensions, "GLX_MESA_swap_control") {
+        if interval < 0 {
+            return Err(SwapIntervalError::AdaptiveNotSupported);
+        }
+
+        let swap_interval: SwapIntervalMesaFn = match load(gl_window, "glXSwapIntervalMESA") {
+            Some(f) => mem::transmute(f),
+            None => return Err(SwapIntervalError::NotSupported),
+        };
+
+        return if swap_interval(interval as c_uint) == 0 {
+            Ok(())
+        } else {
+            Err(SwapIntervalError::NotSupported)
+        };
+    }
+
+    Err(SwapIntervalError::NotSupported)
+}
+
+#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+          target_os = "netbsd", target_os = "openbsd"))]
+unsafe fn get_native_buffer_age(gl_window: &WindowedContext<Pc>) -> Option<u32> {
+    use std::os::raw::{c_int, c_uint, c_ulong};
+
+    type QueryDrawableFn = unsafe extern "C" fn(*mut c_void, c_ulong, c_int, *mut c_uint);
+
+    const GLX_BACK_BUFFER_AGE_EXT: c_int = 0x20F4;
+
+    let glx = glx::current(gl_window)?;
+    if !has_extension(glx.extensions, "GLX_EXT_buffer_age") {
+        return None;
+    }
+
+    let query_drawable: QueryDrawableFn = mem::transmute(load(gl_window, "glXQueryDrawable")?);
+
+    let mut age = 0;
+    query_drawable(glx.display, glx.drawable, GLX_BACK_BUFFER_AGE_EXT, &mut age);
+    Some(age)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "dragonfly",
+              target_os = "freebsd", target_os = "netbsd", target_os = "openbsd")))]
+unsafe fn set_native_swap_interval(_: &WindowedContext<Pc>, _: i32)
+                                   -> Result<(), SwapIntervalError>
+{
+    Err(SwapIntervalError::NotSupported)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+              target_os = "netbsd", target_os = "openbsd")))]
+unsafe fn get_native_buffer_age(_: &WindowedContext<Pc>) -> Option<u32> {
+    None
+}
@@ -0,0 +1,142 @@
+//! Loads the functions of `libEGL` at runtime.
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_void};
+
+use libloading::Library;
+
+pub type EGLint = i32;
+pub type EGLenum = u32;
+pub type EGLBoolean = u32;
+pub type EGLDisplay = *mut c_void;
+pub type EGLConfig = *mut c_void;
+pub type EGLContext = *mut c_void;
+pub type EGLSurface = *mut c_void;
+pub type EGLDeviceEXT = *mut c_void;
+
+pub const EGL_SUCCESS: EGLint = 0x3000;
+pub const EGL_ALPHA_SIZE: EGLint = 0x3021;
+pub const EGL_BLUE_SIZE: EGLint = 0x3022;
+pub const EGL_GREEN_SIZE: EGLint = 0x3023;
+pub const EGL_RED_SIZE: EGLint = 0x3024;
+pub const EGL_DEPTH_SIZE: EGLint = 0x3025;
+pub const EGL_STENCIL_SIZE: EGLint = 0x3026;
+pub const EGL_SURFACE_TYPE: EGLint = 0x3033;
+pub const EGL_NONE: EGLint = 0x3038;
+pub const EGL_RENDERABLE_TYPE: EGLint = 0x3040;
+pub const EGL_EXTENSIONS: EGLint = 0x3055;
+pub const EGL_HEIGHT: EGLint = 0x3056;
+pub const EGL_WIDTH: EGLint = 0x3057;
+pub const EGL_CONTEXT_CLIENT_VERSION: EGLint = 0x3098;
+pub const EGL_PBUFFER_BIT: EGLint = 0x0001;
+pub const EGL_OPENGL_ES2_BIT: EGLint = 0x0004;
+pub const EGL_OPENGL_BIT: EGLint = 0x0008;
+pub const EGL_OPENGL_ES_API: EGLenum = 0x30A0;
+pub const EGL_OPENGL_API: EGLenum = 0x30A2;
+pub const EGL_PLATFORM_DEVICE_EXT: EGLenum = 0x313F;
+pub const EGL_PLATFORM_SURFACELESS_MESA: EGLenum = 0x31DD;
+
+type GetProcAddressFn = unsafe extern "system" fn(*const c_char) -> *const c_void;
+type GetErrorFn = unsafe extern "system" fn() -> EGLint;
+type QueryStringFn = unsafe extern "system" fn(EGLDisplay, EGLint) -> *const c_char;
+type InitializeFn = unsafe extern "system" fn(EGLDisplay, *mut EGLint, *mut EGLint) -> EGLBoolean;
+type TerminateFn = unsafe extern "system" fn(EGLDisplay) -> EGLBoolean;
+type BindApiFn = unsafe extern "system" fn(EGLenum) -> EGLBoolean;
+type ChooseConfigFn = unsafe extern "system" fn(EGLDisplay, *const EGLint, *mut EGLConfig,
+                                                EGLint, *mut EGLint) -> EGLBoolean;
+type CreateContextFn = unsafe extern "system" fn(EGLDisplay, EGLConfig, EGLContext,
+                                                 *const EGLint) -> EGLContext;
+type DestroyContextFn = unsafe extern "system" fn(EGLDisplay, EGLContext) -> EGLBoolean;
+type CreatePbufferSurfaceFn = unsafe extern "system" fn(EGLDisplay, EGLConfig,
+                                                        *const EGLint) -> EGLSurface;
+type DestroySurfaceFn = unsafe extern "system" fn(EGLDisplay, EGLSurface) -> EGLBoolean;
+type MakeCurrentFn = unsafe extern "system" fn(EGLDisplay, EGLSurface, EGLSurface,
+                                               EGLContext) -> EGLBoolean;
+type GetCurrentContextFn = unsafe extern "system" fn() -> EGLContext;
+type QueryDevicesFn = unsafe extern "system" fn(EGLint, *mut EGLDeviceEXT,
+                                                *mut EGLint) -> EGLBoolean;
+type GetPlatformDisplayFn = unsafe extern "system" fn(EGLenum, *mut c_void,
+                                                      *const EGLint) -> EGLDisplay;
+
+#[cfg(not(target_os = "windows"))]
+const LIBRARY_NAMES: &[&str] = &["libEGL.so.1", "libEGL.so"];
+#[cfg(target_os = "windows")]
+const LIBRARY_NAMES: &[&str] = &["libEGL.dll"];
+
+/// The EGL functions used by the headless backend.
+pub struct Egl {
+    pub get_proc_address: GetProcAddressFn,
+    pub get_error: GetErrorFn,
+    pub query_string: QueryStringFn,
+    pub initialize: InitializeFn,
+    pub terminate: TerminateFn,
+    pub bind_api: BindApiFn,
+    pub choose_config: ChooseConfigFn,
+    pub create_context: CreateContextFn,
+    pub destroy_context: DestroyContextFn,
+    pub create_pbuffer_surface: CreatePbufferSurfaceFn,
+    pub destroy_surface: DestroySurfaceFn,
+    pub make_current: MakeCurrentFn,
+    pub get_current_context: GetCurrentContextFn,
+    pub query_devices: Option<QueryDevicesFn>,
+    pub get_platform_display: Option<GetPlatformDisplayFn>,
+
+    // must outlive the function pointers
+    _library: Library,
+}
+
+impl Egl {
+    /// Loads `libEGL`. Returns `None` if it isn't installed.
+    pub fn load() -> Option<Egl> {
+        let library = LIBRARY_NAMES.iter().filter_map(|name| Library::new(name).ok()).next()?;
+
+        unsafe {
+            let get_proc_address: GetProcAddressFn =
+                *library.get(b"eglGetProcAddress\0").ok()?;
+
+            macro_rules! load {
+                ($name:expr) => (*library.get(concat!($name, "\0").as_bytes()).ok()?);
+            }
+
+            // the extension functions can only be loaded with `eglGetProcAddress`
+            macro_rules! load_ext {
+                ($name:expr) => ({
+                    let ptr = get_proc_address(concat!($name, "\0").as_ptr() as *const _);
+                    if ptr.is_null() { None } else { Some(std::mem::transmute(ptr)) }
+                });
+            }
+
+            Some(Egl {
+                get_proc_address,
+                get_error: load!("eglGetError"),
+                query_string: load!("eglQueryString"),
+                initialize: load!("eglInitialize"),
+                terminate: load!("eglTerminate"),
+                bind_api: load!("eglBindAPI"),
+                choose_config: load!("eglChooseConfig"),
+                create_context: load!("eglCreateContext"),
+                destroy_context: load!("eglDestroyContext"),
+                create_pbuffer_surface: load!("eglCreatePbufferSurface"),
+                destroy_surface: load!("eglDestroySurface"),
+                make_current: load!("eglMakeCurrent"),
+                get_current_context: load!("eglGetCurrentContext"),
+                query_devices: load_ext!("eglQueryDevicesEXT"),
+                get_platform_display: load_ext!("eglGetPlatformDisplayEXT"),
+                _library: library,
+            })
+        }
+    }
+
+    /// Returns true if `name` is in the list of extensions of the display, or in the list of
+    /// client extensions if `display` is null.
+    pub unsafe fn has_extension(&self, display: EGLDisplay, name: &str) -> bool {
+        let extensions = (self.query_string)(display, EGL_EXTENSIONS);
+        if extensions.is_null() {
+            // `EGL_EXT_client_extensions` isn't supported
+            (self.get_error)();
+            return false;
+        }
+
+        CStr::from_ptr(extensions).to_string_lossy().split(' ').any(|e| e == name)
+    }
+}
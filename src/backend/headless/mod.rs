@@ -0,0 +1,361 @@
+#![cfg(feature = "headless-egl")]
+/*!
+
+Backend implementation that renders without any window system, with EGL.
+
+The context is created on an EGL device (`EGL_EXT_platform_device`), or on the surfaceless
+platform of Mesa (`EGL_MESA_platform_surfaceless`), which means that neither X11 nor Wayland is
+needed. This is useful for rendering on servers and for running tests on CI machines.
+
+```no_run
+use glium::Surface;
+use glium::backend::headless::Headless;
+
+let headless = Headless::new(1024, 768).unwrap();
+
+let mut frame = headless.draw();
+frame.clear_color(0.0, 0.0, 1.0, 1.0);
+frame.finish().unwrap();
+
+let image: glium::texture::RawImage2d<'_, u8> = headless.read_front_buffer().unwrap();
+```
+
+The default framebuffer is a pbuffer of the requested dimensions. If the EGL implementation only
+supports surfaceless contexts, there is no default framebuffer and you have to draw on textures
+instead. See `Headless::has_default_framebuffer`.
+
+# Features
+
+Only available if the 'headless-egl' feature is enabled. `libEGL` is loaded at runtime.
+
+*/
+use std::error::Error;
+use std::ffi::CString;
+use std::fmt;
+use std::ops::Deref;
+use std::os::raw::c_void;
+use std::ptr;
+use std::rc::Rc;
+
+use crate::backend::{self, Backend};
+use crate::context;
+use crate::debug;
+use crate::{Frame, IncompatibleOpenGl, SwapBuffersError};
+
+use self::egl::*;
+
+mod egl;
+
+/// A context that renders without any window system.
+pub struct Headless {
+    context: Rc<context::Context>,
+    default_framebuffer: bool,
+}
+
+/// An implementation of the `Backend` trait for an EGL context without a window.
+pub struct EglBackend {
+    egl: Egl,
+    display: EGLDisplay,
+    surface: EGLSurface,
+    context: EGLContext,
+    dimensions: (u32, u32),
+}
+
+/// Error that can happen while creating a headless context.
+#[derive(Debug)]
+pub enum HeadlessCreationError {
+    /// `libEGL` couldn't be loaded.
+    EglNotFound,
+    /// No EGL device or surfaceless platform could be initialized.
+    NoDisplay,
+    /// No configuration of the display supports OpenGL or OpenGL ES.
+    NoConfig,
+    /// An EGL function has failed.
+    EglError {
+        /// Name of the function.
+        function: &'static str,
+        /// Value returned by `eglGetError`.
+        error: i32,
+    },
+    /// The OpenGL implementation is too old.
+    IncompatibleOpenGl(IncompatibleOpenGl),
+}
+
+impl fmt::Display for HeadlessCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        use self::HeadlessCreationError::*;
+        match self {
+            EglNotFound => fmt.write_str("libEGL couldn't be loaded"),
+            NoDisplay => fmt.write_str("No EGL device or surfaceless platform is available"),
+            NoConfig => fmt.write_str("No EGL configuration supports OpenGL or OpenGL ES"),
+            EglError { function, error } => write!(fmt, "{} has failed with error {:#x}",
+                                                   function, error),
+            IncompatibleOpenGl(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for HeadlessCreationError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            HeadlessCreationError::IncompatibleOpenGl(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<IncompatibleOpenGl> for HeadlessCreationError {
+    #[inline]
+    fn from(err: IncompatibleOpenGl) -> HeadlessCreationError {
+        HeadlessCreationError::IncompatibleOpenGl(err)
+    }
+}
+
+impl Headless {
+    /// Creates a context whose default framebuffer has the given dimensions.
+    ///
+    /// Performs a compatibility check to make sure that all core elements of glium are supported
+    /// by the implementation.
+    pub fn new(width: u32, height: u32) -> Result<Headless, HeadlessCreationError> {
+        Headless::with_debug(width, height, Default::default())
+    }
+
+    /// The same as the `new` constructor, but allows for specifying debug callback behaviour.
+    pub fn with_debug(width: u32, height: u32, debug: debug::DebugCallbackBehavior)
+                      -> Result<Headless, HeadlessCreationError>
+    {
+        let backend = EglBackend::new(width, height)?;
+        let default_framebuffer = !backend.surface.is_null();
+        let context = unsafe { context::Context::new(backend, true, debug) }?;
+        Ok(Headless { context, default_framebuffer })
+    }
+
+    /// Returns true if the context has a default framebuffer, in other words if `draw` can be
+    /// used. Surfaceless contexts can only draw on textures and renderbuffers.
+    #[inline]
+    pub fn has_default_framebuffer(&self) -> bool {
+        self.default_framebuffer
+    }
+
+    /// Start drawing on the default framebuffer.
+    ///
+    /// This function returns a `Frame`, which can be used to draw on it. Its content can be
+    /// read with `read_front_buffer` once the `Frame` has been finished.
+    #[inline]
+    pub fn draw(&self) -> Frame {
+        Frame::new(self.context.clone(), self.get_framebuffer_dimensions())
+    }
+}
+
+impl Deref for Headless {
+    type Target = context::Context;
+
+    #[inline]
+    fn deref(&self) -> &context::Context {
+        &self.context
+    }
+}
+
+impl backend::Facade for Headless {
+    #[inline]
+    fn get_context(&self) -> &Rc<context::Context> {
+        &self.context
+    }
+}
+
+impl fmt::Debug for Headless {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[glium::backend::headless::Headless]")
+    }
+}
+
+impl EglBackend {
+    fn new(width: u32, height: u32) -> Result<EglBackend, HeadlessCreationError> {
+        let egl = Egl::load().ok_or(HeadlessCreationError::EglNotFound)?;
+
+        unsafe {
+            let display = get_displays(&egl).into_iter()
+                                            .find(|&d| (egl.initialize)(d, ptr::null_mut(),
+                                                                        ptr::null_mut()) != 0)
+                                            .ok_or(HeadlessCreationError::NoDisplay)?;
+
+            let (config, context, pbuffer) = create_context(&egl, display)?;
+
+            let surface = if pbuffer {
+                let attributes = [EGL_WIDTH, width as EGLint, EGL_HEIGHT, height as EGLint,
+                                  EGL_NONE];
+                let surface = (egl.create_pbuffer_surface)(display, config, attributes.as_ptr());
+                if surface.is_null() {
+                    let error = (egl.get_error)();
+                    (egl.destroy_context)(display, context);
+                    return Err(HeadlessCreationError::EglError {
+                        function: "eglCreatePbufferSurface",
+                        error,
+                    });
+                }
+                surface
+            } else {
+                ptr::null_mut()
+            };
+
+            if (egl.make_current)(display, surface, surface, context) == 0 {
+                let error = (egl.get_error)();
+                if !surface.is_null() {
+                    (egl.destroy_surface)(display, surface);
+                }
+                (egl.destroy_context)(display, context);
+                return Err(HeadlessCreationError::EglError { function: "eglMakeCurrent", error });
+            }
+
+            Ok(EglBackend {
+                egl,
+                display,
+                surface,
+                context,
+                dimensions: (width, height),
+            })
+        }
+    }
+}
+
+/// Returns the displays of the EGL devices, then the display of the surfaceless platform.
+unsafe fn get_displays(egl: &Egl) -> Vec<EGLDisplay> {
+    let get_platform_display = match egl.get_platform_display {
+        Some(f) if egl.has_extension(ptr::null_mut(), "EGL_EXT_platform_base") => f,
+        _ => return Vec::new(),
+    };
+
+    let mut displays = Vec::new();
+
+    if egl.has_extension(ptr::null_mut(), "EGL_EXT_platform_device") {
+        if let Some(query_devices) = egl.query_devices {
+            let mut devices = [ptr::null_mut(); 16];
+            let mut num_devices = 0;
+            if query_devices(devices.len() as EGLint, devices.as_mut_ptr(), &mut num_devices) != 0 {
+                for &device in &devices[.. num_devices as usize] {
+                    displays.push(get_platform_display(EGL_PLATFORM_DEVICE_EXT, device,
+                                                       ptr::null()));
+                }
+            }
+        }
+    }
+
+    if egl.has_extension(ptr::null_mut(), "EGL_MESA_platform_surfaceless") {
+        displays.push(get_platform_display(EGL_PLATFORM_SURFACELESS_MESA, ptr::null_mut(),
+                                           ptr::null()));
+    }
+
+    displays.retain(|d| !d.is_null());
+    displays
+}
+
+/// Creates an OpenGL context, or an OpenGL ES context if desktop OpenGL isn't supported.
+///
+/// Returns the configuration, the context, and whether the configuration supports pbuffers.
+unsafe fn create_context(egl: &Egl, display: EGLDisplay)
+                         -> Result<(EGLConfig, EGLContext, bool), HeadlessCreationError>
+{
+    let apis: &[(EGLenum, EGLint, &[EGLint])] = &[
+        (EGL_OPENGL_API, EGL_OPENGL_BIT, &[EGL_NONE]),
+        (EGL_OPENGL_ES_API, EGL_OPENGL_ES2_BIT, &[EGL_CONTEXT_CLIENT_VERSION, 3, EGL_NONE]),
+        (EGL_OPENGL_ES_API, EGL_OPENGL_ES2_BIT, &[EGL_CONTEXT_CLIENT_VERSION, 2, EGL_NONE]),
+    ];
+
+    let surfaceless = egl.has_extension(display, "EGL_KHR_surfaceless_context");
+    let mut last_error = HeadlessCreationError::NoConfig;
+
+    for &(api, renderable_type, context_attributes) in apis {
+        if (egl.bind_api)(api) == 0 {
+            continue;
+        }
+
+        // a surface type of `0` matches all configurations, including those without pbuffers
+        for &(surface_type, pbuffer) in &[(EGL_PBUFFER_BIT, true), (0, false)] {
+            if !pbuffer && !surfaceless {
+                continue;
+            }
+
+            let attributes = [
+                EGL_SURFACE_TYPE, surface_type,
+                EGL_RENDERABLE_TYPE, renderable_type,
+                EGL_RED_SIZE, 8, EGL_GREEN_SIZE, 8, EGL_BLUE_SIZE, 8, EGL_ALPHA_SIZE, 8,
+                EGL_DEPTH_SIZE, 24, EGL_STENCIL_SIZE, 8,
+                EGL_NONE,
+            ];
+
+            let mut config = ptr::null_mut();
+            let mut num_configs = 0;
+            if (egl.choose_config)(display, attributes.as_ptr(), &mut config, 1,
+                                   &mut num_configs) == 0 || num_configs == 0
+            {
+                continue;
+            }
+
+            let context = (egl.create_context)(display, config, ptr::null_mut(),
+                                               context_attributes.as_ptr());
+            if context.is_null() {
+                last_error = HeadlessCreationError::EglError {
+                    function: "eglCreateContext",
+                    error: (egl.get_error)(),
+                };
+                continue;
+            }
+
+            return Ok((config, context, pbuffer));
+        }
+    }
+
+    Err(last_error)
+}
+
+unsafe impl Backend for EglBackend {
+    #[inline]
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        // pbuffers are single-buffered
+        Ok(())
+    }
+
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        let symbol = CString::new(symbol).unwrap();
+        (self.egl.get_proc_address)(symbol.as_ptr())
+    }
+
+    #[inline]
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        self.dimensions
+    }
+
+    #[inline]
+    fn is_current(&self) -> bool {
+        unsafe { (self.egl.get_current_context)() == self.context }
+    }
+
+    #[inline]
+    unsafe fn make_current(&self) {
+        let result = (self.egl.make_current)(self.display, self.surface, self.surface,
+                                             self.context);
+        assert!(result != 0, "eglMakeCurrent has failed with error {:#x}",
+                (self.egl.get_error)());
+    }
+}
+
+impl Drop for EglBackend {
+    fn drop(&mut self) {
+        unsafe {
+            if self.is_current() {
+                (self.egl.make_current)(self.display, ptr::null_mut(), ptr::null_mut(),
+                                        ptr::null_mut());
+            }
+
+            if !self.surface.is_null() {
+                (self.egl.destroy_surface)(self.display, self.surface);
+            }
+
+            (self.egl.destroy_context)(self.display, self.context);
+
+            // the display isn't terminated, as it is shared with the other contexts of the
+            // process that use the same device
+        }
+    }
+}
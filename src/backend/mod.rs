@@ -17,7 +17,9 @@ use std::ops::Deref;
 use std::os::raw::c_void;
 
 use crate::CapabilitiesSource;
+use crate::Rect;
 use crate::SwapBuffersError;
+use crate::{SwapInterval, SwapIntervalError};
 
 use crate::context::Capabilities;
 use crate::context::ExtensionsList;
@@ -25,9 +27,17 @@ use crate::version::Version;
 
 pub use crate::context::Context;
 pub use crate::context::ReleaseBehavior;
+pub use crate::context::FlushPolicy;
+pub use crate::context::ShareGroupId;
 
 #[cfg(feature = "glutin")]
 pub mod glutin;
+#[cfg(feature = "headless-egl")]
+pub mod headless;
+#[cfg(feature = "osmesa")]
+pub mod osmesa;
+#[cfg(all(feature = "webgl", target_arch = "wasm32"))]
+pub mod webgl;
 
 /// Trait for types that can be used as a backend for a glium context.
 ///
@@ -37,6 +47,19 @@ pub unsafe trait Backend {
     /// Swaps buffers at the end of a frame.
     fn swap_buffers(&self) -> Result<(), SwapBuffersError>;
 
+    /// Swaps buffers at the end of a frame, indicating that only the given rectangles of the
+    /// frame have changed since the previous one. The rectangles use the bottom-left hand corner
+    /// of the window as origin.
+    ///
+    /// This is usually done with `EGL_KHR_swap_buffers_with_damage`, and lets the compositor
+    /// avoid composing the whole window again.
+    ///
+    /// The default implementation calls `swap_buffers`.
+    #[inline]
+    fn swap_buffers_with_damage(&self, _: &[Rect]) -> Result<(), SwapBuffersError> {
+        self.swap_buffers()
+    }
+
     /// Returns the address of an OpenGL function.
     ///
     /// Supposes that the context has been made current before this function is called.
@@ -50,6 +73,37 @@ pub unsafe trait Backend {
 
     /// Makes the OpenGL context the current context in the current thread.
     unsafe fn make_current(&self);
+
+    /// Returns the number of color buffers of the surface, for example `2` for a double-buffered
+    /// window, or `None` if it isn't known.
+    ///
+    /// The default implementation returns `None`.
+    #[inline]
+    fn get_swapchain_image_count(&self) -> Option<u32> {
+        None
+    }
+
+    /// Returns the age of the back buffer, in other words the number of frames since its
+    /// current content was presented, or `None` if the backend can't query it.
+    ///
+    /// An age of `0` means that the content of the back buffer is undefined. The age is
+    /// usually obtained with the `EGL_EXT_buffer_age` or `GLX_EXT_buffer_age` extensions.
+    ///
+    /// The default implementation returns `None`.
+    #[inline]
+    fn get_buffer_age(&self) -> Option<u32> {
+        None
+    }
+
+    /// Changes the number of vertical blanks to wait for when swapping the buffers.
+    ///
+    /// Supposes that the context has been made current before this function is called.
+    ///
+    /// The default implementation returns `SwapIntervalError::NotSupported`.
+    #[inline]
+    fn set_swap_interval(&self, _: SwapInterval) -> Result<(), SwapIntervalError> {
+        Err(SwapIntervalError::NotSupported)
+    }
 }
 
 unsafe impl<T> Backend for Rc<T> where T: Backend {
@@ -57,6 +111,10 @@ unsafe impl<T> Backend for Rc<T> where T: Backend {
         self.deref().swap_buffers()
     }
 
+    fn swap_buffers_with_damage(&self, damage: &[Rect]) -> Result<(), SwapBuffersError> {
+        self.deref().swap_buffers_with_damage(damage)
+    }
+
     unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
         self.deref().get_proc_address(symbol)
     }
@@ -72,6 +130,18 @@ unsafe impl<T> Backend for Rc<T> where T: Backend {
     unsafe fn make_current(&self) {
         self.deref().make_current();
     }
+
+    fn get_swapchain_image_count(&self) -> Option<u32> {
+        self.deref().get_swapchain_image_count()
+    }
+
+    fn get_buffer_age(&self) -> Option<u32> {
+        self.deref().get_buffer_age()
+    }
+
+    fn set_swap_interval(&self, interval: SwapInterval) -> Result<(), SwapIntervalError> {
+        self.deref().set_swap_interval(interval)
+    }
 }
 
 /// Trait for types that provide a safe access for glium functions.
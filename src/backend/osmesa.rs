@@ -0,0 +1,277 @@
+#![cfg(feature = "osmesa")]
+/*!
+
+Backend implementation that renders on the CPU with OSMesa.
+
+OSMesa is the off-screen interface of Mesa. It renders in a buffer in memory with a software
+rasterizer (llvmpipe or softpipe), so it doesn't need a GPU or a window system, and the same
+commands produce the same images on every machine that uses the same version of Mesa. This makes
+it a good fit for unit tests and for generating thumbnails on servers.
+
+```no_run
+use glium::Surface;
+use glium::backend::osmesa::OsMesa;
+
+let osmesa = OsMesa::new(256, 256).unwrap();
+
+let mut frame = osmesa.draw();
+frame.clear_color(0.0, 0.0, 1.0, 1.0);
+frame.finish().unwrap();
+
+let image: glium::texture::RawImage2d<'_, u8> = osmesa.read_front_buffer().unwrap();
+```
+
+# Features
+
+Only available if the 'osmesa' feature is enabled. `libOSMesa` is loaded at runtime.
+
+*/
+use std::cell::UnsafeCell;
+use std::error::Error;
+use std::ffi::CString;
+use std::fmt;
+use std::ops::Deref;
+use std::os::raw::{c_char, c_int, c_uint, c_void};
+use std::ptr;
+use std::rc::Rc;
+
+use libloading::Library;
+
+use crate::backend::{self, Backend};
+use crate::context;
+use crate::debug;
+use crate::{Frame, IncompatibleOpenGl, SwapBuffersError};
+
+type OSMesaContext = *mut c_void;
+type GLenum = c_uint;
+type GLboolean = u8;
+
+const OSMESA_RGBA: GLenum = 0x1908;
+const GL_UNSIGNED_BYTE: GLenum = 0x1401;
+
+type CreateContextExtFn = unsafe extern "C" fn(GLenum, c_int, c_int, c_int,
+                                               OSMesaContext) -> OSMesaContext;
+type DestroyContextFn = unsafe extern "C" fn(OSMesaContext);
+type MakeCurrentFn = unsafe extern "C" fn(OSMesaContext, *mut c_void, GLenum, c_int,
+                                          c_int) -> GLboolean;
+type GetCurrentContextFn = unsafe extern "C" fn() -> OSMesaContext;
+type GetProcAddressFn = unsafe extern "C" fn(*const c_char) -> *const c_void;
+
+#[cfg(not(target_os = "windows"))]
+const LIBRARY_NAMES: &[&str] = &["libOSMesa.so.8", "libOSMesa.so.6", "libOSMesa.so"];
+#[cfg(target_os = "windows")]
+const LIBRARY_NAMES: &[&str] = &["osmesa.dll"];
+
+/// The OSMesa functions used by the backend.
+struct Functions {
+    create_context_ext: CreateContextExtFn,
+    destroy_context: DestroyContextFn,
+    make_current: MakeCurrentFn,
+    get_current_context: GetCurrentContextFn,
+    get_proc_address: GetProcAddressFn,
+
+    // must outlive the function pointers
+    _library: Library,
+}
+
+impl Functions {
+    fn load() -> Option<Functions> {
+        let library = LIBRARY_NAMES.iter().filter_map(|name| Library::new(name).ok()).next()?;
+
+        unsafe {
+            macro_rules! load {
+                ($name:expr) => (*library.get(concat!($name, "\0").as_bytes()).ok()?);
+            }
+
+            Some(Functions {
+                create_context_ext: load!("OSMesaCreateContextExt"),
+                destroy_context: load!("OSMesaDestroyContext"),
+                make_current: load!("OSMesaMakeCurrent"),
+                get_current_context: load!("OSMesaGetCurrentContext"),
+                get_proc_address: load!("OSMesaGetProcAddress"),
+                _library: library,
+            })
+        }
+    }
+}
+
+/// A context that renders on the CPU, in a buffer in memory.
+pub struct OsMesa {
+    context: Rc<context::Context>,
+}
+
+/// An implementation of the `Backend` trait for an OSMesa context.
+pub struct OsMesaBackend {
+    functions: Functions,
+    context: OSMesaContext,
+    // RGBA pixels written by OSMesa, whose address must not change
+    buffer: Box<[UnsafeCell<u8>]>,
+    dimensions: (u32, u32),
+}
+
+/// Error that can happen while creating an OSMesa context.
+#[derive(Debug)]
+pub enum OsMesaCreationError {
+    /// `libOSMesa` couldn't be loaded.
+    OsMesaNotFound,
+    /// `OSMesaCreateContextExt` has failed.
+    ContextCreationFailed,
+    /// `OSMesaMakeCurrent` has failed, for example because the dimensions are too large.
+    MakeCurrentFailed,
+    /// The OpenGL implementation is too old.
+    IncompatibleOpenGl(IncompatibleOpenGl),
+}
+
+impl fmt::Display for OsMesaCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        use self::OsMesaCreationError::*;
+        match self {
+            OsMesaNotFound => fmt.write_str("libOSMesa couldn't be loaded"),
+            ContextCreationFailed => fmt.write_str("Failed to create the OSMesa context"),
+            MakeCurrentFailed => fmt.write_str("Failed to make the OSMesa context current"),
+            IncompatibleOpenGl(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for OsMesaCreationError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            OsMesaCreationError::IncompatibleOpenGl(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<IncompatibleOpenGl> for OsMesaCreationError {
+    #[inline]
+    fn from(err: IncompatibleOpenGl) -> OsMesaCreationError {
+        OsMesaCreationError::IncompatibleOpenGl(err)
+    }
+}
+
+impl OsMesa {
+    /// Creates a context whose default framebuffer has the given dimensions.
+    ///
+    /// Performs a compatibility check to make sure that all core elements of glium are supported
+    /// by the implementation.
+    pub fn new(width: u32, height: u32) -> Result<OsMesa, OsMesaCreationError> {
+        OsMesa::with_debug(width, height, Default::default())
+    }
+
+    /// The same as the `new` constructor, but allows for specifying debug callback behaviour.
+    pub fn with_debug(width: u32, height: u32, debug: debug::DebugCallbackBehavior)
+                      -> Result<OsMesa, OsMesaCreationError>
+    {
+        let backend = OsMesaBackend::new(width, height)?;
+        let context = unsafe { context::Context::new(backend, true, debug) }?;
+        Ok(OsMesa { context })
+    }
+
+    /// Start drawing on the default framebuffer.
+    ///
+    /// This function returns a `Frame`, which can be used to draw on it. Its content can be
+    /// read with `read_front_buffer` once the `Frame` has been finished.
+    #[inline]
+    pub fn draw(&self) -> Frame {
+        Frame::new(self.context.clone(), self.get_framebuffer_dimensions())
+    }
+}
+
+impl Deref for OsMesa {
+    type Target = context::Context;
+
+    #[inline]
+    fn deref(&self) -> &context::Context {
+        &self.context
+    }
+}
+
+impl backend::Facade for OsMesa {
+    #[inline]
+    fn get_context(&self) -> &Rc<context::Context> {
+        &self.context
+    }
+}
+
+impl fmt::Debug for OsMesa {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[glium::backend::osmesa::OsMesa]")
+    }
+}
+
+impl OsMesaBackend {
+    fn new(width: u32, height: u32) -> Result<OsMesaBackend, OsMesaCreationError> {
+        let functions = Functions::load().ok_or(OsMesaCreationError::OsMesaNotFound)?;
+
+        let context = unsafe {
+            (functions.create_context_ext)(OSMESA_RGBA, 24, 8, 0, ptr::null_mut())
+        };
+        if context.is_null() {
+            return Err(OsMesaCreationError::ContextCreationFailed);
+        }
+
+        let buffer = (0 .. width as usize * height as usize * 4).map(|_| UnsafeCell::new(0))
+                                                                 .collect();
+
+        let backend = OsMesaBackend {
+            functions,
+            context,
+            buffer,
+            dimensions: (width, height),
+        };
+
+        if !unsafe { backend.try_make_current() } {
+            return Err(OsMesaCreationError::MakeCurrentFailed);
+        }
+
+        Ok(backend)
+    }
+
+    unsafe fn try_make_current(&self) -> bool {
+        (self.functions.make_current)(self.context, self.buffer.as_ptr() as *mut c_void,
+                                      GL_UNSIGNED_BYTE, self.dimensions.0 as c_int,
+                                      self.dimensions.1 as c_int) != 0
+    }
+}
+
+unsafe impl Backend for OsMesaBackend {
+    #[inline]
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        // OSMesa contexts are single-buffered
+        Ok(())
+    }
+
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        let symbol = CString::new(symbol).unwrap();
+        (self.functions.get_proc_address)(symbol.as_ptr())
+    }
+
+    #[inline]
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        self.dimensions
+    }
+
+    #[inline]
+    fn is_current(&self) -> bool {
+        unsafe { (self.functions.get_current_context)() == self.context }
+    }
+
+    #[inline]
+    unsafe fn make_current(&self) {
+        assert!(self.try_make_current(), "OSMesaMakeCurrent has failed");
+    }
+}
+
+impl Drop for OsMesaBackend {
+    fn drop(&mut self) {
+        unsafe {
+            if self.is_current() {
+                (self.functions.make_current)(ptr::null_mut(), ptr::null_mut(), 0, 0, 0);
+            }
+
+            (self.functions.destroy_context)(self.context);
+        }
+    }
+}
@@ -0,0 +1,1674 @@
+//! The OpenGL functions that glium calls, implemented with the WebGL API.
+//!
+//! glium loads them with `get_proc_address`, and they call the `WebGl2RenderingContext` of the
+//! context that is current on the thread. WebGL uses objects instead of integer names, so each
+//! kind of object has a table that gives names to its objects.
+#![allow(non_snake_case)]
+
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_void;
+use std::ptr;
+use std::rc::Rc;
+use std::slice;
+
+use js_sys::{Array, ArrayBuffer, Float32Array, Float64Array, Int16Array, Int32Array, Int8Array};
+use js_sys::{Object, Uint16Array, Uint32Array, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{WebGl2RenderingContext, WebGlBuffer, WebGlFramebuffer, WebGlProgram, WebGlQuery};
+use web_sys::{WebGlRenderbuffer, WebGlSampler, WebGlShader, WebGlSync, WebGlTexture};
+use web_sys::{WebGlUniformLocation, WebGlVertexArrayObject};
+
+use crate::gl;
+use crate::gl::types::*;
+
+/// The WebGL extensions that are enabled when they are available, and the OpenGL ES extensions
+/// that they are reported as.
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("EXT_texture_compression_bptc", "GL_EXT_texture_compression_bptc"),
+    ("EXT_texture_filter_anisotropic", "GL_EXT_texture_filter_anisotropic"),
+    ("KHR_parallel_shader_compile", "GL_KHR_parallel_shader_compile"),
+    ("WEBGL_compressed_texture_astc", "GL_KHR_texture_compression_astc_ldr"),
+    ("WEBGL_compressed_texture_s3tc", "GL_EXT_texture_compression_s3tc"),
+];
+
+thread_local! {
+    /// The context that is current on this thread.
+    static CURRENT: RefCell<Option<Rc<State>>> = const { RefCell::new(None) };
+}
+
+/// Makes a context current on this thread, or no context if `None`.
+pub fn make_current(state: Option<Rc<State>>) {
+    CURRENT.with(|current| *current.borrow_mut() = state);
+}
+
+/// Returns true if the context is current on this thread.
+pub fn is_current(state: &Rc<State>) -> bool {
+    CURRENT.with(|current| current.borrow().as_ref().is_some_and(|c| Rc::ptr_eq(c, state)))
+}
+
+/// Calls `f` with the context that is current on this thread.
+///
+/// ## Panic
+///
+/// Panics if there is no current context.
+fn with<R>(f: impl FnOnce(&State) -> R) -> R {
+    let state = CURRENT.with(|current| current.borrow().clone())
+                       .expect("No WebGL context is current on this thread");
+    f(&state)
+}
+
+/// A WebGL context, and what the OpenGL functions need to remember about it.
+pub struct State {
+    gl: WebGl2RenderingContext,
+    extensions: Vec<&'static str>,
+
+    buffers: Objects<WebGlBuffer>,
+    textures: Objects<WebGlTexture>,
+    framebuffers: Objects<WebGlFramebuffer>,
+    renderbuffers: Objects<WebGlRenderbuffer>,
+    vertex_arrays: Objects<WebGlVertexArrayObject>,
+    samplers: Objects<WebGlSampler>,
+    queries: Objects<WebGlQuery>,
+    syncs: Objects<WebGlSync>,
+    // shaders and programs share their names, like in OpenGL
+    programs: Objects<ProgramObject>,
+
+    current_program: Cell<GLuint>,
+    pixel_pack_buffer: Cell<GLuint>,
+    pixel_unpack_buffer: Cell<GLuint>,
+    pack_alignment: Cell<GLint>,
+    unpack_alignment: Cell<GLint>,
+
+    // the buffers that are mapped, by name
+    mappings: RefCell<HashMap<GLuint, Mapping>>,
+    // the strings returned by `glGetString` and `glGetStringi`, which must stay alive
+    strings: RefCell<HashMap<(GLenum, Option<GLuint>), CString>>,
+}
+
+impl State {
+    /// Builds the state of a context, and enables the extensions that glium can use.
+    pub fn new(gl: WebGl2RenderingContext) -> State {
+        let extensions = EXTENSIONS.iter()
+                                   .filter(|&&(name, _)| gl.get_extension(name).ok().flatten()
+                                                           .is_some())
+                                   .map(|&(_, gl_name)| gl_name)
+                                   .collect();
+
+        State {
+            gl,
+            extensions,
+            buffers: Objects::new(),
+            textures: Objects::new(),
+            framebuffers: Objects::new(),
+            renderbuffers: Objects::new(),
+            vertex_arrays: Objects::new(),
+            samplers: Objects::new(),
+            queries: Objects::new(),
+            syncs: Objects::new(),
+            programs: Objects::new(),
+            current_program: Cell::new(0),
+            pixel_pack_buffer: Cell::new(0),
+            pixel_unpack_buffer: Cell::new(0),
+            pack_alignment: Cell::new(4),
+            unpack_alignment: Cell::new(4),
+            mappings: RefCell::new(HashMap::new()),
+            strings: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the WebGL context.
+    #[inline]
+    pub fn gl(&self) -> &WebGl2RenderingContext {
+        &self.gl
+    }
+
+    fn shader(&self, name: GLuint) -> Option<WebGlShader> {
+        match self.programs.get(name) {
+            Some(ProgramObject::Shader(shader)) => Some(shader),
+            _ => None,
+        }
+    }
+
+    fn program(&self, name: GLuint) -> Option<Rc<Program>> {
+        match self.programs.get(name) {
+            Some(ProgramObject::Program(program)) => Some(program),
+            _ => None,
+        }
+    }
+
+    /// Returns a location of the current program.
+    fn uniform_location(&self, location: GLint) -> Option<WebGlUniformLocation> {
+        let program = self.program(self.current_program.get())?;
+        let locations = program.locations.borrow();
+        if location < 0 { None } else { locations.get(location as usize).cloned() }
+    }
+
+    /// Returns the name of an object returned by WebGL, or 0 if it's unknown.
+    fn name_of(&self, object: &JsValue) -> GLuint {
+        if object.is_instance_of::<WebGlBuffer>() {
+            self.buffers.name_of(object)
+        } else if object.is_instance_of::<WebGlTexture>() {
+            self.textures.name_of(object)
+        } else if object.is_instance_of::<WebGlFramebuffer>() {
+            self.framebuffers.name_of(object)
+        } else if object.is_instance_of::<WebGlRenderbuffer>() {
+            self.renderbuffers.name_of(object)
+        } else if object.is_instance_of::<WebGlVertexArrayObject>() {
+            self.vertex_arrays.name_of(object)
+        } else if object.is_instance_of::<WebGlSampler>() {
+            self.samplers.name_of(object)
+        } else if object.is_instance_of::<WebGlQuery>() {
+            self.queries.name_of(object)
+        } else if object.is_instance_of::<WebGlProgram>() {
+            self.programs.find(|program| match program {
+                ProgramObject::Program(program) => {
+                    AsRef::<JsValue>::as_ref(&program.program) == object
+                },
+                ProgramObject::Shader(_) => false,
+            })
+        } else {
+            0
+        }
+    }
+
+    /// Converts a value returned by one of the `get*Parameter` functions of WebGL to the
+    /// numbers that the OpenGL functions return.
+    fn to_numbers(&self, value: &JsValue) -> Vec<f64> {
+        if let Some(value) = value.as_f64() {
+            vec![value]
+        } else if let Some(value) = value.as_bool() {
+            vec![value as u8 as f64]
+        } else if value.is_null() || value.is_undefined() {
+            Vec::new()
+        } else if Array::is_array(value) {
+            Array::from(value).iter().flat_map(|value| self.to_numbers(&value)).collect()
+        } else if ArrayBuffer::is_view(value) {
+            Float64Array::new(value).to_vec()
+        } else {
+            vec![self.name_of(value) as f64]
+        }
+    }
+
+    /// Implementation of `glGetIntegerv`, `glGetFloatv` and `glGetBooleanv`.
+    fn get_parameter(&self, pname: GLenum) -> Vec<f64> {
+        match pname {
+            gl::NUM_EXTENSIONS => vec![self.extensions.len() as f64],
+            gl::NUM_PROGRAM_BINARY_FORMATS => vec![0.0],
+            gl::SHADER_COMPILER => vec![1.0],
+            _ => self.gl.get_parameter(pname).map(|value| self.to_numbers(&value))
+                        .unwrap_or_default(),
+        }
+    }
+
+    /// Implementation of `glGetString` and `glGetStringi`.
+    fn get_string(&self, name: GLenum, index: Option<GLuint>) -> *const GLubyte {
+        let mut strings = self.strings.borrow_mut();
+        if let Some(string) = strings.get(&(name, index)) {
+            return string.as_ptr() as *const GLubyte;
+        }
+
+        let value = match (name, index) {
+            (gl::EXTENSIONS, None) => Some(self.extensions.join(" ")),
+            (gl::EXTENSIONS, Some(index)) => {
+                self.extensions.get(index as usize).map(|name| name.to_string())
+            },
+            (_, None) => self.gl.get_parameter(name).ok().and_then(|value| value.as_string()),
+            (_, Some(_)) => None,
+        };
+
+        match value.and_then(|value| CString::new(value).ok()) {
+            Some(value) => strings.entry((name, index)).or_insert(value).as_ptr() as *const _,
+            None => ptr::null(),
+        }
+    }
+
+    /// Returns the name of the buffer bound to a target.
+    fn bound_buffer(&self, target: GLenum) -> GLuint {
+        let binding = match target {
+            gl::ARRAY_BUFFER => gl::ARRAY_BUFFER_BINDING,
+            gl::ELEMENT_ARRAY_BUFFER => gl::ELEMENT_ARRAY_BUFFER_BINDING,
+            gl::COPY_READ_BUFFER => gl::COPY_READ_BUFFER_BINDING,
+            gl::COPY_WRITE_BUFFER => gl::COPY_WRITE_BUFFER_BINDING,
+            gl::PIXEL_PACK_BUFFER => return self.pixel_pack_buffer.get(),
+            gl::PIXEL_UNPACK_BUFFER => return self.pixel_unpack_buffer.get(),
+            gl::TRANSFORM_FEEDBACK_BUFFER => gl::TRANSFORM_FEEDBACK_BUFFER_BINDING,
+            gl::UNIFORM_BUFFER => gl::UNIFORM_BUFFER_BINDING,
+            _ => return 0,
+        };
+
+        self.gl.get_parameter(binding).map_or(0, |buffer| self.name_of(&buffer))
+    }
+}
+
+/// Gives names to WebGL objects. The names start at 1, as 0 means that there is no object.
+struct Objects<T> {
+    objects: RefCell<Vec<Option<T>>>,
+}
+
+impl<T> Objects<T> where T: Clone {
+    fn new() -> Objects<T> {
+        Objects { objects: RefCell::new(Vec::new()) }
+    }
+
+    /// Stores an object and returns its name. Names of deleted objects are reused.
+    fn insert(&self, object: T) -> GLuint {
+        let mut objects = self.objects.borrow_mut();
+        match objects.iter().position(Option::is_none) {
+            Some(index) => {
+                objects[index] = Some(object);
+                index as GLuint + 1
+            },
+            None => {
+                objects.push(Some(object));
+                objects.len() as GLuint
+            },
+        }
+    }
+
+    fn get(&self, name: GLuint) -> Option<T> {
+        let index = (name as usize).checked_sub(1)?;
+        self.objects.borrow().get(index).cloned().flatten()
+    }
+
+    fn remove(&self, name: GLuint) -> Option<T> {
+        let index = (name as usize).checked_sub(1)?;
+        self.objects.borrow_mut().get_mut(index).and_then(Option::take)
+    }
+
+    /// Returns the name of the first object for which `f` returns true, or 0.
+    fn find(&self, f: impl Fn(&T) -> bool) -> GLuint {
+        self.objects.borrow().iter()
+                    .position(|object| object.as_ref().is_some_and(&f))
+                    .map_or(0, |index| index as GLuint + 1)
+    }
+}
+
+impl<T> Objects<T> where T: Clone + AsRef<JsValue> {
+    /// Returns the name of an object, or 0 if it isn't in the table.
+    fn name_of(&self, object: &JsValue) -> GLuint {
+        self.find(|o| o.as_ref() == object)
+    }
+}
+
+#[derive(Clone)]
+enum ProgramObject {
+    Shader(WebGlShader),
+    Program(Rc<Program>),
+}
+
+struct Program {
+    program: WebGlProgram,
+    // the locations returned by `glGetUniformLocation` are indices in this list, which is
+    // cleared when the program is linked
+    locations: RefCell<Vec<WebGlUniformLocation>>,
+    location_names: RefCell<HashMap<String, GLint>>,
+}
+
+impl Program {
+    fn location(&self, gl: &WebGl2RenderingContext, name: &str) -> GLint {
+        if let Some(&location) = self.location_names.borrow().get(name) {
+            return location;
+        }
+
+        let location = match gl.get_uniform_location(&self.program, name) {
+            Some(location) => {
+                let mut locations = self.locations.borrow_mut();
+                locations.push(location);
+                locations.len() as GLint - 1
+            },
+            None => -1,
+        };
+
+        self.location_names.borrow_mut().insert(name.to_owned(), location);
+        location
+    }
+}
+
+/// A buffer mapped with `glMapBufferRange`. WebGL can't map buffers, so the content is copied
+/// to `data` and written back when it is flushed or unmapped.
+struct Mapping {
+    offset: GLintptr,
+    data: Box<[u8]>,
+    access: GLbitfield,
+}
+
+/// Builds a slice from a pointer given to an OpenGL function, which can be null if `len` is 0.
+unsafe fn slice<'a, T>(data: *const T, len: usize) -> &'a [T] {
+    if len == 0 || data.is_null() { &[] } else { slice::from_raw_parts(data, len) }
+}
+
+/// Writes values to an array given to an OpenGL function.
+unsafe fn write<T>(out: *mut T, values: impl IntoIterator<Item = T>) {
+    if out.is_null() {
+        return;
+    }
+
+    for (index, value) in values.into_iter().enumerate() {
+        *out.add(index) = value;
+    }
+}
+
+/// Writes a string like the `glGet*Name` and `glGet*InfoLog` functions: truncated to
+/// `buf_size - 1` bytes and followed by a nul byte. `length` receives the number of bytes
+/// written, without the nul byte.
+unsafe fn write_string(value: &str, buf_size: GLsizei, length: *mut GLsizei, out: *mut GLchar) {
+    let len = value.len().min((buf_size.max(1) - 1) as usize);
+
+    if buf_size > 0 && !out.is_null() {
+        ptr::copy_nonoverlapping(value.as_ptr(), out as *mut u8, len);
+        *out.add(len) = 0;
+    }
+
+    write(length, Some(len as GLsizei));
+}
+
+unsafe fn to_str<'a>(value: *const GLchar) -> Cow<'a, str> {
+    CStr::from_ptr(value).to_string_lossy()
+}
+
+/// Length of a string returned by OpenGL, including the nul byte.
+fn name_length(value: Option<String>) -> GLint {
+    value.map_or(0, |value| value.len() as GLint + 1)
+}
+
+/// Returns the number of bytes of an image, following the alignment of its rows.
+fn image_size(format: GLenum, ty: GLenum, width: GLsizei, height: GLsizei, depth: GLsizei,
+              alignment: GLint) -> usize
+{
+    if width <= 0 || height <= 0 || depth <= 0 {
+        return 0;
+    }
+
+    let pixel_size = match ty {
+        gl::UNSIGNED_SHORT_5_6_5 | gl::UNSIGNED_SHORT_4_4_4_4 | gl::UNSIGNED_SHORT_5_5_5_1 => 2,
+        gl::UNSIGNED_INT_2_10_10_10_REV | gl::UNSIGNED_INT_10F_11F_11F_REV |
+        gl::UNSIGNED_INT_5_9_9_9_REV | gl::UNSIGNED_INT_24_8 => 4,
+        gl::FLOAT_32_UNSIGNED_INT_24_8_REV => 8,
+        _ => {
+            let components = match format {
+                gl::RED | gl::RED_INTEGER | gl::ALPHA | gl::LUMINANCE | gl::DEPTH_COMPONENT => 1,
+                gl::RG | gl::RG_INTEGER | gl::LUMINANCE_ALPHA => 2,
+                gl::RGB | gl::RGB_INTEGER => 3,
+                _ => 4,
+            };
+
+            let component_size = match ty {
+                gl::BYTE | gl::UNSIGNED_BYTE => 1,
+                gl::SHORT | gl::UNSIGNED_SHORT | gl::HALF_FLOAT => 2,
+                _ => 4,
+            };
+
+            components * component_size
+        },
+    };
+
+    let alignment = alignment.max(1) as usize;
+    let row_size = width as usize * pixel_size;
+    let aligned_row_size = row_size.div_ceil(alignment) * alignment;
+
+    // the last row isn't padded
+    aligned_row_size * (height as usize * depth as usize - 1) + row_size
+}
+
+/// Returns a view of `buffer` whose type matches `ty`, as WebGL requires for pixels.
+fn typed_view(ty: GLenum, buffer: &ArrayBuffer) -> Object {
+    let len = buffer.byte_length();
+
+    match ty {
+        gl::BYTE => Int8Array::new(buffer).into(),
+        gl::UNSIGNED_BYTE => Uint8Array::new(buffer).into(),
+        gl::SHORT => Int16Array::new_with_byte_offset_and_length(buffer, 0, len / 2).into(),
+        gl::UNSIGNED_SHORT | gl::HALF_FLOAT | gl::UNSIGNED_SHORT_5_6_5 |
+        gl::UNSIGNED_SHORT_4_4_4_4 | gl::UNSIGNED_SHORT_5_5_5_1 => {
+            Uint16Array::new_with_byte_offset_and_length(buffer, 0, len / 2).into()
+        },
+        gl::INT => Int32Array::new_with_byte_offset_and_length(buffer, 0, len / 4).into(),
+        gl::FLOAT => Float32Array::new_with_byte_offset_and_length(buffer, 0, len / 4).into(),
+        _ => Uint32Array::new_with_byte_offset_and_length(buffer, 0, len / 4).into(),
+    }
+}
+
+/// Copies the pixels given to an OpenGL function to a typed array.
+unsafe fn pixels(ty: GLenum, data: *const c_void, size: usize) -> Object {
+    let bytes = Uint8Array::from(slice(data as *const u8, size));
+    typed_view(ty, &bytes.buffer())
+}
+
+fn numbers_array<T>(values: &[T]) -> Array where T: Copy + Into<JsValue> {
+    values.iter().map(|&value| value.into()).collect()
+}
+
+/// Returns the address of one of the functions of this module from its name in OpenGL, or null
+/// if it isn't implemented.
+pub fn get_proc_address(symbol: &str) -> *const c_void {
+    macro_rules! functions {
+        ($($name:ident),+ $(,)?) => (
+            match symbol {
+                $(concat!("gl", stringify!($name)) => $name as *const c_void,)+
+                _ => ptr::null(),
+            }
+        );
+    }
+
+    functions!(
+        ActiveTexture, AttachShader, BeginQuery, BeginTransformFeedback, BindBuffer,
+        BindBufferRange, BindFramebuffer, BindRenderbuffer, BindSampler, BindTexture,
+        BindVertexArray, BlendColor, BlendEquation, BlendEquationSeparate, BlendFuncSeparate,
+        BlitFramebuffer, BufferData, BufferSubData, Clear, ClearBufferfv, ClearBufferiv,
+        ClearBufferuiv, ClearColor, ClearDepthf, ClearStencil, ClientWaitSync, ColorMask,
+        CompileShader, CompressedTexImage2D, CompressedTexImage3D, CompressedTexSubImage2D,
+        CompressedTexSubImage3D, CopyBufferSubData, CreateProgram, CreateShader, CullFace,
+        DeleteBuffers, DeleteFramebuffers, DeleteProgram, DeleteQueries, DeleteRenderbuffers,
+        DeleteSamplers, DeleteShader, DeleteSync, DeleteTextures, DeleteVertexArrays, DepthFunc,
+        DepthMask, DepthRange, Disable, DisableVertexAttribArray, DrawArrays,
+        DrawArraysInstanced, DrawBuffers, DrawElements, DrawElementsInstanced, Enable,
+        EnableVertexAttribArray, EndQuery, EndTransformFeedback, FenceSync, Finish, Flush,
+        FlushMappedBufferRange, FramebufferRenderbuffer, FramebufferTexture2D,
+        FramebufferTextureLayer, GenBuffers, GenFramebuffers, GenQueries, GenRenderbuffers,
+        GenSamplers, GenTextures, GenVertexArrays, GenerateMipmap, GetActiveAttrib,
+        GetActiveUniform, GetActiveUniformBlockName, GetActiveUniformBlockiv,
+        GetActiveUniformName, GetActiveUniformsiv, GetAttribLocation, GetBooleanv,
+        GetBufferParameteriv, GetError, GetFloatv, GetFragDataLocation,
+        GetFramebufferAttachmentParameteriv, GetIntegerv, GetInternalformativ,
+        GetProgramInfoLog, GetProgramiv, GetQueryObjectuiv, GetRenderbufferParameteriv,
+        GetShaderInfoLog, GetShaderSource, GetShaderiv, GetString, GetStringi,
+        GetTexParameteriv, GetTransformFeedbackVarying, GetUniformBlockIndex,
+        GetUniformLocation, Hint, InvalidateFramebuffer, InvalidateSubFramebuffer, IsEnabled,
+        LineWidth, LinkProgram, MapBufferRange, MaxShaderCompilerThreadsKHR, PixelStorei,
+        PolygonOffset, ReadBuffer, ReadPixels, ReleaseShaderCompiler, RenderbufferStorage,
+        RenderbufferStorageMultisample, SamplerParameterf, SamplerParameteri, Scissor,
+        ShaderSource, StencilFunc, StencilFuncSeparate, StencilMask, StencilMaskSeparate,
+        StencilOp, StencilOpSeparate, TexImage2D, TexImage3D, TexParameteri, TexStorage2D,
+        TexStorage3D, TexSubImage2D, TexSubImage3D, TransformFeedbackVaryings, Uniform1f,
+        Uniform1i, Uniform1ui, Uniform2fv, Uniform2iv, Uniform2uiv, Uniform3fv, Uniform3iv,
+        Uniform3uiv, Uniform4fv, Uniform4iv, Uniform4uiv, UniformBlockBinding,
+        UniformMatrix2fv, UniformMatrix3fv, UniformMatrix4fv, UnmapBuffer, UseProgram,
+        ValidateProgram, VertexAttrib4fv, VertexAttribDivisor, VertexAttribIPointer,
+        VertexAttribPointer, Viewport,
+    )
+}
+
+/// Implements functions that forward their parameters to WebGL without changing them.
+macro_rules! forward {
+    ($($name:ident => $method:ident($($param:ident: $ty:ty),*);)+) => ($(
+        unsafe extern "system" fn $name($($param: $ty),*) {
+            with(|state| state.gl.$method($($param),*))
+        }
+    )+);
+}
+
+forward! {
+    ActiveTexture => active_texture(texture: GLenum);
+    BeginTransformFeedback => begin_transform_feedback(mode: GLenum);
+    BlendColor => blend_color(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat);
+    BlendEquation => blend_equation(mode: GLenum);
+    BlendEquationSeparate => blend_equation_separate(mode_rgb: GLenum, mode_alpha: GLenum);
+    BlendFuncSeparate => blend_func_separate(src_rgb: GLenum, dst_rgb: GLenum,
+                                             src_alpha: GLenum, dst_alpha: GLenum);
+    BlitFramebuffer => blit_framebuffer(src_x0: GLint, src_y0: GLint, src_x1: GLint,
+                                        src_y1: GLint, dst_x0: GLint, dst_y0: GLint,
+                                        dst_x1: GLint, dst_y1: GLint, mask: GLbitfield,
+                                        filter: GLenum);
+    Clear => clear(mask: GLbitfield);
+    ClearColor => clear_color(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat);
+    ClearDepthf => clear_depth(depth: GLfloat);
+    ClearStencil => clear_stencil(s: GLint);
+    CullFace => cull_face(mode: GLenum);
+    DepthFunc => depth_func(func: GLenum);
+    DisableVertexAttribArray => disable_vertex_attrib_array(index: GLuint);
+    DrawArrays => draw_arrays(mode: GLenum, first: GLint, count: GLsizei);
+    DrawArraysInstanced => draw_arrays_instanced(mode: GLenum, first: GLint, count: GLsizei,
+                                                 instance_count: GLsizei);
+    EnableVertexAttribArray => enable_vertex_attrib_array(index: GLuint);
+    EndQuery => end_query(target: GLenum);
+    EndTransformFeedback => end_transform_feedback();
+    Finish => finish();
+    Flush => flush();
+    GenerateMipmap => generate_mipmap(target: GLenum);
+    Hint => hint(target: GLenum, mode: GLenum);
+    LineWidth => line_width(width: GLfloat);
+    PolygonOffset => polygon_offset(factor: GLfloat, units: GLfloat);
+    ReadBuffer => read_buffer(src: GLenum);
+    RenderbufferStorage => renderbuffer_storage(target: GLenum, internal_format: GLenum,
+                                                width: GLsizei, height: GLsizei);
+    RenderbufferStorageMultisample => renderbuffer_storage_multisample(
+        target: GLenum, samples: GLsizei, internal_format: GLenum, width: GLsizei,
+        height: GLsizei);
+    Scissor => scissor(x: GLint, y: GLint, width: GLsizei, height: GLsizei);
+    StencilFunc => stencil_func(func: GLenum, reference: GLint, mask: GLuint);
+    StencilFuncSeparate => stencil_func_separate(face: GLenum, func: GLenum, reference: GLint,
+                                                 mask: GLuint);
+    StencilMask => stencil_mask(mask: GLuint);
+    StencilMaskSeparate => stencil_mask_separate(face: GLenum, mask: GLuint);
+    StencilOp => stencil_op(fail: GLenum, depth_fail: GLenum, depth_pass: GLenum);
+    StencilOpSeparate => stencil_op_separate(face: GLenum, fail: GLenum, depth_fail: GLenum,
+                                             depth_pass: GLenum);
+    TexParameteri => tex_parameteri(target: GLenum, pname: GLenum, param: GLint);
+    TexStorage2D => tex_storage_2d(target: GLenum, levels: GLsizei, internal_format: GLenum,
+                                   width: GLsizei, height: GLsizei);
+    TexStorage3D => tex_storage_3d(target: GLenum, levels: GLsizei, internal_format: GLenum,
+                                   width: GLsizei, height: GLsizei, depth: GLsizei);
+    VertexAttribDivisor => vertex_attrib_divisor(index: GLuint, divisor: GLuint);
+    Viewport => viewport(x: GLint, y: GLint, width: GLsizei, height: GLsizei);
+}
+
+/// Implements `glGen*` and `glDelete*` for a kind of object.
+macro_rules! objects {
+    ($($gen:ident, $delete:ident => $table:ident, $create:ident, $destroy:ident;)+) => ($(
+        unsafe extern "system" fn $gen(n: GLsizei, names: *mut GLuint) {
+            with(|state| {
+                write(names, (0 .. n.max(0)).map(|_| {
+                    state.gl.$create().map_or(0, |object| state.$table.insert(object))
+                }))
+            })
+        }
+
+        unsafe extern "system" fn $delete(n: GLsizei, names: *const GLuint) {
+            with(|state| {
+                for &name in slice(names, n.max(0) as usize) {
+                    if let Some(object) = state.$table.remove(name) {
+                        state.gl.$destroy(Some(&object));
+                    }
+                }
+            })
+        }
+    )+);
+}
+
+objects! {
+    GenTextures, DeleteTextures => textures, create_texture, delete_texture;
+    GenFramebuffers, DeleteFramebuffers => framebuffers, create_framebuffer, delete_framebuffer;
+    GenRenderbuffers, DeleteRenderbuffers => renderbuffers, create_renderbuffer,
+                                             delete_renderbuffer;
+    GenVertexArrays, DeleteVertexArrays => vertex_arrays, create_vertex_array,
+                                           delete_vertex_array;
+    GenSamplers, DeleteSamplers => samplers, create_sampler, delete_sampler;
+    GenQueries, DeleteQueries => queries, create_query, delete_query;
+}
+
+/// Implements `glBind*` for a kind of object.
+macro_rules! bind {
+    ($($name:ident => $table:ident, $method:ident($($param:ident: $ty:ty),*);)+) => ($(
+        unsafe extern "system" fn $name($($param: $ty,)* name: GLuint) {
+            with(|state| state.gl.$method($($param,)* state.$table.get(name).as_ref()))
+        }
+    )+);
+}
+
+bind! {
+    BindFramebuffer => framebuffers, bind_framebuffer(target: GLenum);
+    BindRenderbuffer => renderbuffers, bind_renderbuffer(target: GLenum);
+    BindSampler => samplers, bind_sampler(unit: GLuint);
+    BindTexture => textures, bind_texture(target: GLenum);
+    BindVertexArray => vertex_arrays, bind_vertex_array();
+}
+
+unsafe extern "system" fn GenBuffers(n: GLsizei, names: *mut GLuint) {
+    with(|state| {
+        write(names, (0 .. n.max(0)).map(|_| {
+            state.gl.create_buffer().map_or(0, |buffer| state.buffers.insert(buffer))
+        }))
+    })
+}
+
+unsafe extern "system" fn DeleteBuffers(n: GLsizei, names: *const GLuint) {
+    with(|state| {
+        for &name in slice(names, n.max(0) as usize) {
+            if let Some(buffer) = state.buffers.remove(name) {
+                state.gl.delete_buffer(Some(&buffer));
+                state.mappings.borrow_mut().remove(&name);
+
+                for binding in &[&state.pixel_pack_buffer, &state.pixel_unpack_buffer] {
+                    if binding.get() == name {
+                        binding.set(0);
+                    }
+                }
+            }
+        }
+    })
+}
+
+unsafe extern "system" fn BindBuffer(target: GLenum, name: GLuint) {
+    with(|state| {
+        state.gl.bind_buffer(target, state.buffers.get(name).as_ref());
+
+        match target {
+            gl::PIXEL_PACK_BUFFER => state.pixel_pack_buffer.set(name),
+            gl::PIXEL_UNPACK_BUFFER => state.pixel_unpack_buffer.set(name),
+            _ => (),
+        }
+    })
+}
+
+unsafe extern "system" fn BindBufferRange(target: GLenum, index: GLuint, name: GLuint,
+                                          offset: GLintptr, size: GLsizeiptr)
+{
+    with(|state| {
+        state.gl.bind_buffer_range_with_i32_and_i32(target, index,
+                                                    state.buffers.get(name).as_ref(),
+                                                    offset as i32, size as i32)
+    })
+}
+
+unsafe extern "system" fn BufferData(target: GLenum, size: GLsizeiptr, data: *const c_void,
+                                     usage: GLenum)
+{
+    with(|state| {
+        if data.is_null() {
+            state.gl.buffer_data_with_i32(target, size as i32, usage);
+        } else {
+            let data = slice(data as *const u8, size as usize);
+            state.gl.buffer_data_with_u8_array(target, data, usage);
+        }
+    })
+}
+
+unsafe extern "system" fn BufferSubData(target: GLenum, offset: GLintptr, size: GLsizeiptr,
+                                        data: *const c_void)
+{
+    with(|state| {
+        let data = slice(data as *const u8, size as usize);
+        state.gl.buffer_sub_data_with_i32_and_u8_array(target, offset as i32, data);
+    })
+}
+
+unsafe extern "system" fn CopyBufferSubData(read_target: GLenum, write_target: GLenum,
+                                            read_offset: GLintptr, write_offset: GLintptr,
+                                            size: GLsizeiptr)
+{
+    with(|state| {
+        state.gl.copy_buffer_sub_data_with_i32_and_i32_and_i32(read_target, write_target,
+                                                               read_offset as i32,
+                                                               write_offset as i32, size as i32)
+    })
+}
+
+unsafe extern "system" fn GetBufferParameteriv(target: GLenum, pname: GLenum,
+                                               params: *mut GLint)
+{
+    with(|state| {
+        let value = state.gl.get_buffer_parameter(target, pname);
+        write(params, state.to_numbers(&value).into_iter().map(|value| value as GLint))
+    })
+}
+
+unsafe extern "system" fn MapBufferRange(target: GLenum, offset: GLintptr, length: GLsizeiptr,
+                                         access: GLbitfield) -> *mut c_void
+{
+    with(|state| {
+        let name = state.bound_buffer(target);
+        if name == 0 {
+            return ptr::null_mut();
+        }
+
+        let mut data = vec![0; length as usize].into_boxed_slice();
+
+        // the parts of the range that aren't written must keep their content
+        if access & (gl::MAP_INVALIDATE_RANGE_BIT | gl::MAP_INVALIDATE_BUFFER_BIT) == 0 {
+            state.gl.get_buffer_sub_data_with_i32_and_u8_array(target, offset as i32,
+                                                               &mut data);
+        }
+
+        let pointer = data.as_mut_ptr() as *mut c_void;
+        state.mappings.borrow_mut().insert(name, Mapping { offset, data, access });
+        pointer
+    })
+}
+
+unsafe extern "system" fn FlushMappedBufferRange(target: GLenum, offset: GLintptr,
+                                                 length: GLsizeiptr)
+{
+    with(|state| {
+        let name = state.bound_buffer(target);
+        let mappings = state.mappings.borrow();
+        if let Some(mapping) = mappings.get(&name) {
+            let range = offset as usize .. (offset + length) as usize;
+            if let Some(data) = mapping.data.get(range) {
+                state.gl.buffer_sub_data_with_i32_and_u8_array(target,
+                                                               (mapping.offset + offset) as i32,
+                                                               data);
+            }
+        }
+    })
+}
+
+unsafe extern "system" fn UnmapBuffer(target: GLenum) -> GLboolean {
+    with(|state| {
+        let name = state.bound_buffer(target);
+        let mapping = match state.mappings.borrow_mut().remove(&name) {
+            Some(mapping) => mapping,
+            None => return gl::FALSE,
+        };
+
+        if mapping.access & gl::MAP_WRITE_BIT != 0 &&
+           mapping.access & gl::MAP_FLUSH_EXPLICIT_BIT == 0
+        {
+            state.gl.buffer_sub_data_with_i32_and_u8_array(target, mapping.offset as i32,
+                                                           &mapping.data);
+        }
+
+        gl::TRUE
+    })
+}
+
+unsafe extern "system" fn CreateShader(ty: GLenum) -> GLuint {
+    with(|state| {
+        state.gl.create_shader(ty)
+                .map_or(0, |shader| state.programs.insert(ProgramObject::Shader(shader)))
+    })
+}
+
+unsafe extern "system" fn CreateProgram() -> GLuint {
+    with(|state| {
+        state.gl.create_program().map_or(0, |program| {
+            state.programs.insert(ProgramObject::Program(Rc::new(Program {
+                program,
+                locations: RefCell::new(Vec::new()),
+                location_names: RefCell::new(HashMap::new()),
+            })))
+        })
+    })
+}
+
+unsafe extern "system" fn DeleteShader(name: GLuint) {
+    with(|state| {
+        if let Some(shader) = state.shader(name) {
+            state.programs.remove(name);
+            state.gl.delete_shader(Some(&shader));
+        }
+    })
+}
+
+unsafe extern "system" fn DeleteProgram(program: GLuint) {
+    with(|state| {
+        if let Some(ProgramObject::Program(program)) = state.programs.remove(program) {
+            state.gl.delete_program(Some(&program.program));
+        }
+    })
+}
+
+unsafe extern "system" fn AttachShader(program: GLuint, shader: GLuint) {
+    with(|state| {
+        if let (Some(program), Some(shader)) = (state.program(program), state.shader(shader)) {
+            state.gl.attach_shader(&program.program, &shader);
+        }
+    })
+}
+
+unsafe extern "system" fn ShaderSource(shader: GLuint, count: GLsizei,
+                                       strings: *const *const GLchar, lengths: *const GLint)
+{
+    with(|state| {
+        let strings = slice(strings, count.max(0) as usize);
+        let source = strings.iter().enumerate().map(|(index, &string)| {
+            // a negative length means that the string is nul-terminated
+            let length = if lengths.is_null() { -1 } else { *lengths.add(index) };
+            if length >= 0 {
+                String::from_utf8_lossy(slice(string as *const u8, length as usize))
+            } else {
+                to_str(string)
+            }
+        }).collect::<String>();
+
+        if let Some(shader) = state.shader(shader) {
+            state.gl.shader_source(&shader, &source);
+        }
+    })
+}
+
+unsafe extern "system" fn CompileShader(shader: GLuint) {
+    with(|state| {
+        if let Some(shader) = state.shader(shader) {
+            state.gl.compile_shader(&shader);
+        }
+    })
+}
+
+unsafe extern "system" fn GetShaderiv(shader: GLuint, pname: GLenum, params: *mut GLint) {
+    with(|state| {
+        let shader = match state.shader(shader) {
+            Some(shader) => shader,
+            None => return,
+        };
+
+        let values = match pname {
+            gl::INFO_LOG_LENGTH => vec![name_length(state.gl.get_shader_info_log(&shader))],
+            gl::SHADER_SOURCE_LENGTH => vec![name_length(state.gl.get_shader_source(&shader))],
+            _ => {
+                let value = state.gl.get_shader_parameter(&shader, pname);
+                state.to_numbers(&value).into_iter().map(|value| value as GLint).collect()
+            },
+        };
+
+        write(params, values)
+    })
+}
+
+unsafe extern "system" fn GetShaderInfoLog(shader: GLuint, buf_size: GLsizei,
+                                           length: *mut GLsizei, info_log: *mut GLchar)
+{
+    with(|state| {
+        let log = state.shader(shader).and_then(|shader| state.gl.get_shader_info_log(&shader));
+        write_string(&log.unwrap_or_default(), buf_size, length, info_log)
+    })
+}
+
+unsafe extern "system" fn GetShaderSource(shader: GLuint, buf_size: GLsizei,
+                                          length: *mut GLsizei, source: *mut GLchar)
+{
+    with(|state| {
+        let value = state.shader(shader).and_then(|shader| state.gl.get_shader_source(&shader));
+        write_string(&value.unwrap_or_default(), buf_size, length, source)
+    })
+}
+
+unsafe extern "system" fn LinkProgram(program: GLuint) {
+    with(|state| {
+        if let Some(program) = state.program(program) {
+            state.gl.link_program(&program.program);
+            program.locations.borrow_mut().clear();
+            program.location_names.borrow_mut().clear();
+        }
+    })
+}
+
+unsafe extern "system" fn ValidateProgram(program: GLuint) {
+    with(|state| {
+        if let Some(program) = state.program(program) {
+            state.gl.validate_program(&program.program);
+        }
+    })
+}
+
+unsafe extern "system" fn UseProgram(name: GLuint) {
+    with(|state| {
+        let program = state.program(name);
+        state.gl.use_program(program.as_ref().map(|program| &program.program));
+        state.current_program.set(name);
+    })
+}
+
+/// Returns the length of the longest name of the `count` first variables of a program,
+/// including the nul byte.
+fn max_name_length(state: &State, program: &WebGlProgram, count: GLenum,
+                   name: impl Fn(u32) -> Option<String>) -> GLint
+{
+    let count = state.gl.get_program_parameter(program, count).as_f64().unwrap_or(0.0) as u32;
+    (0 .. count).map(|index| name_length(name(index))).max().unwrap_or(0)
+}
+
+unsafe extern "system" fn GetProgramiv(program: GLuint, pname: GLenum, params: *mut GLint) {
+    with(|state| {
+        let program = match state.program(program) {
+            Some(program) => program,
+            None => return,
+        };
+
+        let gl = &state.gl;
+        let program = &program.program;
+
+        let value = match pname {
+            gl::INFO_LOG_LENGTH => name_length(gl.get_program_info_log(program)),
+            gl::ACTIVE_UNIFORM_MAX_LENGTH => {
+                max_name_length(state, program, gl::ACTIVE_UNIFORMS, |index| {
+                    gl.get_active_uniform(program, index).map(|info| info.name())
+                })
+            },
+            gl::ACTIVE_ATTRIBUTE_MAX_LENGTH => {
+                max_name_length(state, program, gl::ACTIVE_ATTRIBUTES, |index| {
+                    gl.get_active_attrib(program, index).map(|info| info.name())
+                })
+            },
+            gl::TRANSFORM_FEEDBACK_VARYING_MAX_LENGTH => {
+                max_name_length(state, program, gl::TRANSFORM_FEEDBACK_VARYINGS, |index| {
+                    gl.get_transform_feedback_varying(program, index).map(|info| info.name())
+                })
+            },
+            gl::ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH => {
+                max_name_length(state, program, gl::ACTIVE_UNIFORM_BLOCKS, |index| {
+                    gl.get_active_uniform_block_name(program, index)
+                })
+            },
+            _ => {
+                let value = gl.get_program_parameter(program, pname);
+                match state.to_numbers(&value).first() {
+                    Some(&value) => value as GLint,
+                    None => return,
+                }
+            },
+        };
+
+        write(params, Some(value))
+    })
+}
+
+unsafe extern "system" fn GetProgramInfoLog(program: GLuint, buf_size: GLsizei,
+                                            length: *mut GLsizei, info_log: *mut GLchar)
+{
+    with(|state| {
+        let log = state.program(program)
+                       .and_then(|program| state.gl.get_program_info_log(&program.program));
+        write_string(&log.unwrap_or_default(), buf_size, length, info_log)
+    })
+}
+
+unsafe extern "system" fn GetAttribLocation(program: GLuint, name: *const GLchar) -> GLint {
+    with(|state| {
+        state.program(program)
+             .map_or(-1, |program| state.gl.get_attrib_location(&program.program, &to_str(name)))
+    })
+}
+
+unsafe extern "system" fn GetFragDataLocation(program: GLuint, name: *const GLchar) -> GLint {
+    with(|state| {
+        state.program(program).map_or(-1, |program| {
+            state.gl.get_frag_data_location(&program.program, &to_str(name))
+        })
+    })
+}
+
+unsafe extern "system" fn GetUniformLocation(program: GLuint, name: *const GLchar) -> GLint {
+    with(|state| {
+        state.program(program).map_or(-1, |program| program.location(&state.gl, &to_str(name)))
+    })
+}
+
+/// Implements the `glGetActive*` functions that return the name, the size and the type of
+/// a variable.
+macro_rules! active_variable {
+    ($($name:ident => $method:ident;)+) => ($(
+        unsafe extern "system" fn $name(program: GLuint, index: GLuint, buf_size: GLsizei,
+                                        length: *mut GLsizei, size: *mut GLint,
+                                        ty: *mut GLenum, name: *mut GLchar)
+        {
+            with(|state| {
+                let info = match state.program(program) {
+                    Some(program) => state.gl.$method(&program.program, index),
+                    None => None,
+                };
+
+                if let Some(info) = info {
+                    write(size, Some(info.size()));
+                    write(ty, Some(info.type_()));
+                    write_string(&info.name(), buf_size, length, name);
+                }
+            })
+        }
+    )+);
+}
+
+active_variable! {
+    GetActiveAttrib => get_active_attrib;
+    GetActiveUniform => get_active_uniform;
+    GetTransformFeedbackVarying => get_transform_feedback_varying;
+}
+
+unsafe extern "system" fn GetActiveUniformName(program: GLuint, index: GLuint,
+                                               buf_size: GLsizei, length: *mut GLsizei,
+                                               name: *mut GLchar)
+{
+    with(|state| {
+        let info = state.program(program)
+                        .and_then(|program| state.gl.get_active_uniform(&program.program, index));
+        if let Some(info) = info {
+            write_string(&info.name(), buf_size, length, name);
+        }
+    })
+}
+
+unsafe extern "system" fn GetActiveUniformsiv(program: GLuint, count: GLsizei,
+                                              indices: *const GLuint, pname: GLenum,
+                                              params: *mut GLint)
+{
+    with(|state| {
+        let program = match state.program(program) {
+            Some(program) => program,
+            None => return,
+        };
+
+        let indices = slice(indices, count.max(0) as usize);
+
+        if pname == gl::UNIFORM_NAME_LENGTH {
+            write(params, indices.iter().map(|&index| {
+                name_length(state.gl.get_active_uniform(&program.program, index)
+                                    .map(|info| info.name()))
+            }));
+        } else {
+            let value = state.gl.get_active_uniforms(&program.program, &numbers_array(indices),
+                                                     pname);
+            write(params, state.to_numbers(&value).into_iter().map(|value| value as GLint));
+        }
+    })
+}
+
+unsafe extern "system" fn GetActiveUniformBlockiv(program: GLuint, index: GLuint,
+                                                  pname: GLenum, params: *mut GLint)
+{
+    with(|state| {
+        let program = match state.program(program) {
+            Some(program) => program,
+            None => return,
+        };
+
+        if pname == gl::UNIFORM_BLOCK_NAME_LENGTH {
+            let name = state.gl.get_active_uniform_block_name(&program.program, index);
+            write(params, Some(name_length(name)));
+        } else {
+            let value = state.gl.get_active_uniform_block_parameter(&program.program, index,
+                                                                    pname)
+                                .unwrap_or(JsValue::NULL);
+            write(params, state.to_numbers(&value).into_iter().map(|value| value as GLint));
+        }
+    })
+}
+
+unsafe extern "system" fn GetActiveUniformBlockName(program: GLuint, index: GLuint,
+                                                    buf_size: GLsizei, length: *mut GLsizei,
+                                                    name: *mut GLchar)
+{
+    with(|state| {
+        let value = state.program(program).and_then(|program| {
+            state.gl.get_active_uniform_block_name(&program.program, index)
+        });
+
+        if let Some(value) = value {
+            write_string(&value, buf_size, length, name);
+        }
+    })
+}
+
+unsafe extern "system" fn GetUniformBlockIndex(program: GLuint, name: *const GLchar) -> GLuint {
+    with(|state| {
+        state.program(program).map_or(gl::INVALID_INDEX, |program| {
+            state.gl.get_uniform_block_index(&program.program, &to_str(name))
+        })
+    })
+}
+
+unsafe extern "system" fn UniformBlockBinding(program: GLuint, index: GLuint, binding: GLuint) {
+    with(|state| {
+        if let Some(program) = state.program(program) {
+            state.gl.uniform_block_binding(&program.program, index, binding);
+        }
+    })
+}
+
+unsafe extern "system" fn TransformFeedbackVaryings(program: GLuint, count: GLsizei,
+                                                    varyings: *const *const GLchar,
+                                                    buffer_mode: GLenum)
+{
+    with(|state| {
+        let varyings = slice(varyings, count.max(0) as usize).iter()
+                                                              .map(|&name| to_str(name))
+                                                              .map(|name| JsValue::from(&*name))
+                                                              .collect::<Array>();
+
+        if let Some(program) = state.program(program) {
+            state.gl.transform_feedback_varyings(&program.program, &varyings, buffer_mode);
+        }
+    })
+}
+
+unsafe extern "system" fn Uniform1f(location: GLint, v0: GLfloat) {
+    with(|state| state.gl.uniform1f(state.uniform_location(location).as_ref(), v0))
+}
+
+unsafe extern "system" fn Uniform1i(location: GLint, v0: GLint) {
+    with(|state| state.gl.uniform1i(state.uniform_location(location).as_ref(), v0))
+}
+
+unsafe extern "system" fn Uniform1ui(location: GLint, v0: GLuint) {
+    with(|state| state.gl.uniform1ui(state.uniform_location(location).as_ref(), v0))
+}
+
+/// Implements the `glUniform*v` functions.
+macro_rules! uniform_vectors {
+    ($($name:ident => $method:ident($ty:ty, $components:expr);)+) => ($(
+        unsafe extern "system" fn $name(location: GLint, count: GLsizei, value: *const $ty) {
+            with(|state| {
+                let value = slice(value, count.max(0) as usize * $components);
+                state.gl.$method(state.uniform_location(location).as_ref(), value)
+            })
+        }
+    )+);
+}
+
+uniform_vectors! {
+    Uniform2fv => uniform2fv_with_f32_array(GLfloat, 2);
+    Uniform3fv => uniform3fv_with_f32_array(GLfloat, 3);
+    Uniform4fv => uniform4fv_with_f32_array(GLfloat, 4);
+    Uniform2iv => uniform2iv_with_i32_array(GLint, 2);
+    Uniform3iv => uniform3iv_with_i32_array(GLint, 3);
+    Uniform4iv => uniform4iv_with_i32_array(GLint, 4);
+    Uniform2uiv => uniform2uiv_with_u32_array(GLuint, 2);
+    Uniform3uiv => uniform3uiv_with_u32_array(GLuint, 3);
+    Uniform4uiv => uniform4uiv_with_u32_array(GLuint, 4);
+}
+
+/// Implements the `glUniformMatrix*v` functions.
+macro_rules! uniform_matrices {
+    ($($name:ident => $method:ident($components:expr);)+) => ($(
+        unsafe extern "system" fn $name(location: GLint, count: GLsizei, transpose: GLboolean,
+                                        value: *const GLfloat)
+        {
+            with(|state| {
+                let value = slice(value, count.max(0) as usize * $components);
+                state.gl.$method(state.uniform_location(location).as_ref(), transpose != 0,
+                                 value)
+            })
+        }
+    )+);
+}
+
+uniform_matrices! {
+    UniformMatrix2fv => uniform_matrix2fv_with_f32_array(4);
+    UniformMatrix3fv => uniform_matrix3fv_with_f32_array(9);
+    UniformMatrix4fv => uniform_matrix4fv_with_f32_array(16);
+}
+
+unsafe extern "system" fn VertexAttrib4fv(index: GLuint, value: *const GLfloat) {
+    with(|state| state.gl.vertex_attrib4fv_with_f32_array(index, slice(value, 4)))
+}
+
+unsafe extern "system" fn VertexAttribPointer(index: GLuint, size: GLint, ty: GLenum,
+                                              normalized: GLboolean, stride: GLsizei,
+                                              pointer: *const c_void)
+{
+    with(|state| {
+        state.gl.vertex_attrib_pointer_with_i32(index, size, ty, normalized != 0, stride,
+                                                pointer as i32)
+    })
+}
+
+unsafe extern "system" fn VertexAttribIPointer(index: GLuint, size: GLint, ty: GLenum,
+                                               stride: GLsizei, pointer: *const c_void)
+{
+    with(|state| state.gl.vertex_attrib_i_pointer_with_i32(index, size, ty, stride,
+                                                           pointer as i32))
+}
+
+unsafe extern "system" fn DrawElements(mode: GLenum, count: GLsizei, ty: GLenum,
+                                       indices: *const c_void)
+{
+    with(|state| state.gl.draw_elements_with_i32(mode, count, ty, indices as i32))
+}
+
+unsafe extern "system" fn DrawElementsInstanced(mode: GLenum, count: GLsizei, ty: GLenum,
+                                                indices: *const c_void,
+                                                instance_count: GLsizei)
+{
+    with(|state| {
+        state.gl.draw_elements_instanced_with_i32(mode, count, ty, indices as i32,
+                                                  instance_count)
+    })
+}
+
+/// Returns true for the capabilities of OpenGL ES that WebGL always enables.
+fn always_enabled(cap: GLenum) -> bool {
+    cap == gl::PROGRAM_POINT_SIZE || cap == gl::PRIMITIVE_RESTART_FIXED_INDEX
+}
+
+unsafe extern "system" fn Enable(cap: GLenum) {
+    if !always_enabled(cap) {
+        with(|state| state.gl.enable(cap))
+    }
+}
+
+unsafe extern "system" fn Disable(cap: GLenum) {
+    if !always_enabled(cap) {
+        with(|state| state.gl.disable(cap))
+    }
+}
+
+unsafe extern "system" fn IsEnabled(cap: GLenum) -> GLboolean {
+    (always_enabled(cap) || with(|state| state.gl.is_enabled(cap))) as GLboolean
+}
+
+unsafe extern "system" fn ColorMask(red: GLboolean, green: GLboolean, blue: GLboolean,
+                                    alpha: GLboolean)
+{
+    with(|state| state.gl.color_mask(red != 0, green != 0, blue != 0, alpha != 0))
+}
+
+unsafe extern "system" fn DepthMask(flag: GLboolean) {
+    with(|state| state.gl.depth_mask(flag != 0))
+}
+
+unsafe extern "system" fn DepthRange(near: GLdouble, far: GLdouble) {
+    with(|state| state.gl.depth_range(near as f32, far as f32))
+}
+
+/// Number of values that `glClearBuffer*v` reads for a buffer.
+fn clear_buffer_len(buffer: GLenum) -> usize {
+    if buffer == gl::COLOR { 4 } else { 1 }
+}
+
+unsafe extern "system" fn ClearBufferfv(buffer: GLenum, draw_buffer: GLint,
+                                        value: *const GLfloat)
+{
+    with(|state| {
+        let value = slice(value, clear_buffer_len(buffer));
+        state.gl.clear_bufferfv_with_f32_array(buffer, draw_buffer, value)
+    })
+}
+
+unsafe extern "system" fn ClearBufferiv(buffer: GLenum, draw_buffer: GLint,
+                                        value: *const GLint)
+{
+    with(|state| {
+        let value = slice(value, clear_buffer_len(buffer));
+        state.gl.clear_bufferiv_with_i32_array(buffer, draw_buffer, value)
+    })
+}
+
+unsafe extern "system" fn ClearBufferuiv(buffer: GLenum, draw_buffer: GLint,
+                                         value: *const GLuint)
+{
+    with(|state| {
+        let value = slice(value, clear_buffer_len(buffer));
+        state.gl.clear_bufferuiv_with_u32_array(buffer, draw_buffer, value)
+    })
+}
+
+unsafe extern "system" fn FramebufferTexture2D(target: GLenum, attachment: GLenum,
+                                               tex_target: GLenum, texture: GLuint,
+                                               level: GLint)
+{
+    with(|state| {
+        state.gl.framebuffer_texture_2d(target, attachment, tex_target,
+                                        state.textures.get(texture).as_ref(), level)
+    })
+}
+
+unsafe extern "system" fn FramebufferTextureLayer(target: GLenum, attachment: GLenum,
+                                                  texture: GLuint, level: GLint, layer: GLint)
+{
+    with(|state| {
+        state.gl.framebuffer_texture_layer(target, attachment,
+                                           state.textures.get(texture).as_ref(), level, layer)
+    })
+}
+
+unsafe extern "system" fn FramebufferRenderbuffer(target: GLenum, attachment: GLenum,
+                                                  renderbuffer_target: GLenum,
+                                                  renderbuffer: GLuint)
+{
+    with(|state| {
+        state.gl.framebuffer_renderbuffer(target, attachment, renderbuffer_target,
+                                          state.renderbuffers.get(renderbuffer).as_ref())
+    })
+}
+
+unsafe extern "system" fn DrawBuffers(n: GLsizei, buffers: *const GLenum) {
+    with(|state| state.gl.draw_buffers(&numbers_array(slice(buffers, n.max(0) as usize))))
+}
+
+unsafe extern "system" fn InvalidateFramebuffer(target: GLenum, count: GLsizei,
+                                                attachments: *const GLenum)
+{
+    with(|state| {
+        let attachments = numbers_array(slice(attachments, count.max(0) as usize));
+        let _ = state.gl.invalidate_framebuffer(target, &attachments);
+    })
+}
+
+unsafe extern "system" fn InvalidateSubFramebuffer(target: GLenum, count: GLsizei,
+                                                   attachments: *const GLenum, x: GLint,
+                                                   y: GLint, width: GLsizei, height: GLsizei)
+{
+    with(|state| {
+        let attachments = numbers_array(slice(attachments, count.max(0) as usize));
+        let _ = state.gl.invalidate_sub_framebuffer(target, &attachments, x, y, width, height);
+    })
+}
+
+unsafe extern "system" fn GetFramebufferAttachmentParameteriv(target: GLenum,
+                                                              attachment: GLenum,
+                                                              pname: GLenum, params: *mut GLint)
+{
+    with(|state| {
+        let value = state.gl.get_framebuffer_attachment_parameter(target, attachment, pname)
+                            .unwrap_or(JsValue::NULL);
+        write(params, state.to_numbers(&value).into_iter().map(|value| value as GLint))
+    })
+}
+
+unsafe extern "system" fn GetRenderbufferParameteriv(target: GLenum, pname: GLenum,
+                                                     params: *mut GLint)
+{
+    with(|state| {
+        let value = state.gl.get_renderbuffer_parameter(target, pname);
+        write(params, state.to_numbers(&value).into_iter().map(|value| value as GLint))
+    })
+}
+
+unsafe extern "system" fn GetTexParameteriv(target: GLenum, pname: GLenum, params: *mut GLint) {
+    with(|state| {
+        let value = state.gl.get_tex_parameter(target, pname);
+        write(params, state.to_numbers(&value).into_iter().map(|value| value as GLint))
+    })
+}
+
+unsafe extern "system" fn GetInternalformativ(target: GLenum, internal_format: GLenum,
+                                              pname: GLenum, buf_size: GLsizei,
+                                              params: *mut GLint)
+{
+    with(|state| {
+        // WebGL only has `GL_SAMPLES`, whose length gives `GL_NUM_SAMPLE_COUNTS`
+        let query = if pname == gl::NUM_SAMPLE_COUNTS { gl::SAMPLES } else { pname };
+        let value = state.gl.get_internalformat_parameter(target, internal_format, query)
+                            .unwrap_or(JsValue::NULL);
+        let values = state.to_numbers(&value);
+
+        if pname == gl::NUM_SAMPLE_COUNTS {
+            write(params, Some(values.len() as GLint));
+        } else {
+            write(params, values.into_iter().take(buf_size.max(0) as usize)
+                                .map(|value| value as GLint));
+        }
+    })
+}
+
+unsafe extern "system" fn SamplerParameteri(sampler: GLuint, pname: GLenum, param: GLint) {
+    with(|state| {
+        if let Some(sampler) = state.samplers.get(sampler) {
+            state.gl.sampler_parameteri(&sampler, pname, param);
+        }
+    })
+}
+
+unsafe extern "system" fn SamplerParameterf(sampler: GLuint, pname: GLenum, param: GLfloat) {
+    with(|state| {
+        if let Some(sampler) = state.samplers.get(sampler) {
+            state.gl.sampler_parameterf(&sampler, pname, param);
+        }
+    })
+}
+
+unsafe extern "system" fn PixelStorei(pname: GLenum, param: GLint) {
+    with(|state| {
+        state.gl.pixel_storei(pname, param);
+
+        match pname {
+            gl::PACK_ALIGNMENT => state.pack_alignment.set(param),
+            gl::UNPACK_ALIGNMENT => state.unpack_alignment.set(param),
+            _ => (),
+        }
+    })
+}
+
+unsafe extern "system" fn TexImage2D(target: GLenum, level: GLint, internal_format: GLint,
+                                     width: GLsizei, height: GLsizei, border: GLint,
+                                     format: GLenum, ty: GLenum, data: *const c_void)
+{
+    with(|state| {
+        let gl = &state.gl;
+
+        // with a pixel unpack buffer, `data` is an offset in the buffer
+        if state.pixel_unpack_buffer.get() != 0 {
+            let _ = gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_i32(
+                target, level, internal_format, width, height, border, format, ty, data as i32);
+            return;
+        }
+
+        let size = image_size(format, ty, width, height, 1, state.unpack_alignment.get());
+        let pixels = if data.is_null() { None } else { Some(pixels(ty, data, size)) };
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_array_buffer_view(
+            target, level, internal_format, width, height, border, format, ty, pixels.as_ref()
+        ).ok();
+    })
+}
+
+unsafe extern "system" fn TexImage3D(target: GLenum, level: GLint, internal_format: GLint,
+                                     width: GLsizei, height: GLsizei, depth: GLsizei,
+                                     border: GLint, format: GLenum, ty: GLenum,
+                                     data: *const c_void)
+{
+    with(|state| {
+        let _ = if state.pixel_unpack_buffer.get() != 0 {
+            state.gl.tex_image_3d_with_i32(target, level, internal_format, width, height, depth,
+                                           border, format, ty, data as i32)
+        } else {
+            let size = image_size(format, ty, width, height, depth,
+                                  state.unpack_alignment.get());
+            let pixels = if data.is_null() { None } else { Some(pixels(ty, data, size)) };
+            state.gl.tex_image_3d_with_opt_array_buffer_view(target, level, internal_format,
+                                                             width, height, depth, border,
+                                                             format, ty, pixels.as_ref())
+        };
+    })
+}
+
+unsafe extern "system" fn TexSubImage2D(target: GLenum, level: GLint, x_offset: GLint,
+                                        y_offset: GLint, width: GLsizei, height: GLsizei,
+                                        format: GLenum, ty: GLenum, data: *const c_void)
+{
+    with(|state| {
+        let _ = if state.pixel_unpack_buffer.get() != 0 {
+            state.gl.tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_i32(
+                target, level, x_offset, y_offset, width, height, format, ty, data as i32)
+        } else {
+            let size = image_size(format, ty, width, height, 1, state.unpack_alignment.get());
+            let pixels = pixels(ty, data, size);
+            state.gl.tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_opt_array_buffer_view(
+                target, level, x_offset, y_offset, width, height, format, ty, Some(&pixels))
+        };
+    })
+}
+
+unsafe extern "system" fn TexSubImage3D(target: GLenum, level: GLint, x_offset: GLint,
+                                        y_offset: GLint, z_offset: GLint, width: GLsizei,
+                                        height: GLsizei, depth: GLsizei, format: GLenum,
+                                        ty: GLenum, data: *const c_void)
+{
+    with(|state| {
+        let _ = if state.pixel_unpack_buffer.get() != 0 {
+            state.gl.tex_sub_image_3d_with_i32(target, level, x_offset, y_offset, z_offset,
+                                               width, height, depth, format, ty, data as i32)
+        } else {
+            let size = image_size(format, ty, width, height, depth,
+                                  state.unpack_alignment.get());
+            let pixels = pixels(ty, data, size);
+            state.gl.tex_sub_image_3d_with_opt_array_buffer_view(target, level, x_offset,
+                                                                 y_offset, z_offset, width,
+                                                                 height, depth, format, ty,
+                                                                 Some(&pixels))
+        };
+    })
+}
+
+unsafe extern "system" fn CompressedTexImage2D(target: GLenum, level: GLint,
+                                               internal_format: GLenum, width: GLsizei,
+                                               height: GLsizei, border: GLint,
+                                               image_size: GLsizei, data: *const c_void)
+{
+    with(|state| {
+        if state.pixel_unpack_buffer.get() != 0 {
+            state.gl.compressed_tex_image_2d_with_i32_and_i32(target, level, internal_format,
+                                                              width, height, border, image_size,
+                                                              data as i32);
+        } else {
+            let data = slice(data as *const u8, image_size.max(0) as usize);
+            state.gl.compressed_tex_image_2d_with_u8_array(target, level, internal_format,
+                                                           width, height, border, data);
+        }
+    })
+}
+
+unsafe extern "system" fn CompressedTexImage3D(target: GLenum, level: GLint,
+                                               internal_format: GLenum, width: GLsizei,
+                                               height: GLsizei, depth: GLsizei, border: GLint,
+                                               image_size: GLsizei, data: *const c_void)
+{
+    with(|state| {
+        if state.pixel_unpack_buffer.get() != 0 {
+            state.gl.compressed_tex_image_3d_with_i32_and_i32(target, level, internal_format,
+                                                              width, height, depth, border,
+                                                              image_size, data as i32);
+        } else {
+            let data = slice(data as *const u8, image_size.max(0) as usize);
+            state.gl.compressed_tex_image_3d_with_u8_array(target, level, internal_format,
+                                                           width, height, depth, border, data);
+        }
+    })
+}
+
+unsafe extern "system" fn CompressedTexSubImage2D(target: GLenum, level: GLint,
+                                                  x_offset: GLint, y_offset: GLint,
+                                                  width: GLsizei, height: GLsizei,
+                                                  format: GLenum, image_size: GLsizei,
+                                                  data: *const c_void)
+{
+    with(|state| {
+        if state.pixel_unpack_buffer.get() != 0 {
+            state.gl.compressed_tex_sub_image_2d_with_i32_and_i32(target, level, x_offset,
+                                                                  y_offset, width, height,
+                                                                  format, image_size,
+                                                                  data as i32);
+        } else {
+            let mut data = slice(data as *const u8, image_size.max(0) as usize).to_vec();
+            state.gl.compressed_tex_sub_image_2d_with_u8_array(target, level, x_offset,
+                                                               y_offset, width, height, format,
+                                                               &mut data);
+        }
+    })
+}
+
+unsafe extern "system" fn CompressedTexSubImage3D(target: GLenum, level: GLint,
+                                                  x_offset: GLint, y_offset: GLint,
+                                                  z_offset: GLint, width: GLsizei,
+                                                  height: GLsizei, depth: GLsizei,
+                                                  format: GLenum, image_size: GLsizei,
+                                                  data: *const c_void)
+{
+    with(|state| {
+        if state.pixel_unpack_buffer.get() != 0 {
+            state.gl.compressed_tex_sub_image_3d_with_i32_and_i32(target, level, x_offset,
+                                                                  y_offset, z_offset, width,
+                                                                  height, depth, format,
+                                                                  image_size, data as i32);
+        } else {
+            let mut data = slice(data as *const u8, image_size.max(0) as usize).to_vec();
+            state.gl.compressed_tex_sub_image_3d_with_u8_array(target, level, x_offset,
+                                                               y_offset, z_offset, width,
+                                                               height, depth, format,
+                                                               &mut data);
+        }
+    })
+}
+
+unsafe extern "system" fn ReadPixels(x: GLint, y: GLint, width: GLsizei, height: GLsizei,
+                                     format: GLenum, ty: GLenum, data: *mut c_void)
+{
+    with(|state| {
+        // with a pixel pack buffer, `data` is an offset in the buffer
+        if state.pixel_pack_buffer.get() != 0 {
+            let _ = state.gl.read_pixels_with_i32(x, y, width, height, format, ty, data as i32);
+            return;
+        }
+
+        let size = image_size(format, ty, width, height, 1, state.pack_alignment.get());
+        let buffer = ArrayBuffer::new(size as u32);
+        let view = typed_view(ty, &buffer);
+
+        if state.gl.read_pixels_with_opt_array_buffer_view(x, y, width, height, format, ty,
+                                                           Some(&view)).is_ok() && size != 0
+        {
+            let out = slice::from_raw_parts_mut(data as *mut u8, size);
+            Uint8Array::new(&buffer).copy_to(out);
+        }
+    })
+}
+
+unsafe extern "system" fn BeginQuery(target: GLenum, query: GLuint) {
+    with(|state| {
+        if let Some(query) = state.queries.get(query) {
+            state.gl.begin_query(target, &query);
+        }
+    })
+}
+
+unsafe extern "system" fn GetQueryObjectuiv(query: GLuint, pname: GLenum, params: *mut GLuint) {
+    with(|state| {
+        if let Some(query) = state.queries.get(query) {
+            let value = state.gl.get_query_parameter(&query, pname);
+            write(params, state.to_numbers(&value).into_iter().map(|value| value as GLuint));
+        }
+    })
+}
+
+unsafe extern "system" fn FenceSync(condition: GLenum, flags: GLbitfield) -> GLsync {
+    with(|state| {
+        let name = state.gl.fence_sync(condition, flags)
+                           .map_or(0, |sync| state.syncs.insert(sync));
+        name as usize as GLsync
+    })
+}
+
+unsafe extern "system" fn ClientWaitSync(sync: GLsync, flags: GLbitfield, timeout: GLuint64)
+                                         -> GLenum
+{
+    with(|state| {
+        let sync = match state.syncs.get(sync as usize as GLuint) {
+            Some(sync) => sync,
+            None => return gl::WAIT_FAILED,
+        };
+
+        // WebGL can't block, so waiting for the fence waits for all the commands instead
+        match state.gl.client_wait_sync_with_u32(&sync, flags, 0) {
+            gl::TIMEOUT_EXPIRED if timeout != 0 => {
+                state.gl.finish();
+                gl::CONDITION_SATISFIED
+            },
+            result => result,
+        }
+    })
+}
+
+unsafe extern "system" fn DeleteSync(sync: GLsync) {
+    with(|state| {
+        if let Some(sync) = state.syncs.remove(sync as usize as GLuint) {
+            state.gl.delete_sync(Some(&sync));
+        }
+    })
+}
+
+unsafe extern "system" fn GetError() -> GLenum {
+    with(|state| state.gl.get_error())
+}
+
+unsafe extern "system" fn GetIntegerv(pname: GLenum, data: *mut GLint) {
+    with(|state| {
+        write(data, state.get_parameter(pname).into_iter().map(|value| value.round() as GLint))
+    })
+}
+
+unsafe extern "system" fn GetFloatv(pname: GLenum, data: *mut GLfloat) {
+    with(|state| write(data, state.get_parameter(pname).into_iter().map(|value| value as f32)))
+}
+
+unsafe extern "system" fn GetBooleanv(pname: GLenum, data: *mut GLboolean) {
+    with(|state| {
+        write(data, state.get_parameter(pname).into_iter().map(|value| {
+            (value != 0.0) as GLboolean
+        }))
+    })
+}
+
+unsafe extern "system" fn GetString(name: GLenum) -> *const GLubyte {
+    with(|state| state.get_string(name, None))
+}
+
+unsafe extern "system" fn GetStringi(name: GLenum, index: GLuint) -> *const GLubyte {
+    with(|state| state.get_string(name, Some(index)))
+}
+
+unsafe extern "system" fn ReleaseShaderCompiler() {
+}
+
+unsafe extern "system" fn MaxShaderCompilerThreadsKHR(_: GLuint) {
+    // the WebGL extension doesn't have this function, the browser chooses the number of threads
+}
@@ -0,0 +1,211 @@
+/*!
+
+Backend implementation that renders in an HTML canvas with WebGL 2.0.
+
+WebGL 2.0 is based on OpenGL ES 3.0 and the context is reported as such, so the features that
+WebGL lacks, like geometry shaders or shader storage buffers, are unsupported. The OpenGL
+functions that glium calls are implemented on top of the `WebGl2RenderingContext` of `web-sys`.
+
+```no_run
+use glium::Surface;
+use glium::backend::webgl::WebGl;
+use wasm_bindgen::JsCast;
+
+let canvas = web_sys::window().unwrap().document().unwrap()
+                              .get_element_by_id("canvas").unwrap()
+                              .dyn_into::<web_sys::HtmlCanvasElement>().unwrap();
+let display = WebGl::new(&canvas).unwrap();
+
+let mut frame = display.draw();
+frame.clear_color(0.0, 0.0, 1.0, 1.0);
+frame.finish().unwrap();
+```
+
+The browser presents the content of the canvas once the current task returns to the event loop,
+so a frame is usually drawn in the callback of `requestAnimationFrame`. Swapping the buffers
+doesn't do anything else than checking whether the context has been lost.
+
+There are a few limitations compared to a native OpenGL ES 3.0 context:
+
+ - Mapping a buffer copies its content to and from the memory of the program.
+ - The main thread of a browser can't be blocked, so waiting for a fence calls `glFinish`
+   instead, and the result of a query is only available once control has returned to the
+   browser.
+ - Programs can't be retrieved or loaded as binaries.
+
+# Features
+
+Only available if the 'webgl' feature is enabled and when compiling for `wasm32`.
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+use std::os::raw::c_void;
+use std::rc::Rc;
+
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlCanvasElement, WebGl2RenderingContext, WebGlContextAttributes};
+
+use crate::backend::{self, Backend};
+use crate::context;
+use crate::debug;
+use crate::{Frame, IncompatibleOpenGl, SwapBuffersError};
+
+mod functions;
+
+/// A context that renders in an HTML canvas.
+pub struct WebGl {
+    context: Rc<context::Context>,
+}
+
+/// An implementation of the `Backend` trait for a WebGL 2.0 context.
+pub struct WebGlBackend {
+    state: Rc<functions::State>,
+}
+
+/// Error that can happen while creating a WebGL context.
+#[derive(Debug)]
+pub enum WebGlCreationError {
+    /// The browser doesn't support WebGL 2.0, or the canvas already has a context of another kind.
+    NotSupported,
+    /// The OpenGL implementation is too old.
+    IncompatibleOpenGl(IncompatibleOpenGl),
+}
+
+impl fmt::Display for WebGlCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        use self::WebGlCreationError::*;
+        match self {
+            NotSupported => fmt.write_str("Failed to create a WebGL 2.0 context on the canvas"),
+            IncompatibleOpenGl(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for WebGlCreationError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            WebGlCreationError::IncompatibleOpenGl(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<IncompatibleOpenGl> for WebGlCreationError {
+    #[inline]
+    fn from(err: IncompatibleOpenGl) -> WebGlCreationError {
+        WebGlCreationError::IncompatibleOpenGl(err)
+    }
+}
+
+impl WebGl {
+    /// Creates a WebGL 2.0 context on the canvas.
+    ///
+    /// The default framebuffer has a depth buffer and a stencil buffer, and is multisampled if
+    /// the browser supports it.
+    ///
+    /// Performs a compatibility check to make sure that all core elements of glium are supported
+    /// by the implementation.
+    pub fn new(canvas: &HtmlCanvasElement) -> Result<WebGl, WebGlCreationError> {
+        WebGl::with_debug(canvas, Default::default())
+    }
+
+    /// The same as the `new` constructor, but allows for specifying debug callback behaviour.
+    pub fn with_debug(canvas: &HtmlCanvasElement, debug: debug::DebugCallbackBehavior)
+                      -> Result<WebGl, WebGlCreationError>
+    {
+        let attributes = WebGlContextAttributes::new();
+        attributes.set_stencil(true);
+
+        let gl = canvas.get_context_with_context_options("webgl2", &attributes)
+                       .ok().flatten()
+                       .and_then(|context| context.dyn_into::<WebGl2RenderingContext>().ok())
+                       .ok_or(WebGlCreationError::NotSupported)?;
+
+        WebGl::from_webgl_context(gl, debug)
+    }
+
+    /// Builds a context from a `WebGl2RenderingContext` that has already been created, for
+    /// example with other attributes than the ones that `new` requests.
+    pub fn from_webgl_context(gl: WebGl2RenderingContext, debug: debug::DebugCallbackBehavior)
+                              -> Result<WebGl, WebGlCreationError>
+    {
+        let backend = WebGlBackend { state: Rc::new(functions::State::new(gl)) };
+        let context = unsafe { context::Context::new(backend, true, debug) }?;
+        Ok(WebGl { context })
+    }
+
+    /// Start drawing on the canvas.
+    ///
+    /// This function returns a `Frame`, which can be used to draw on it. The browser displays
+    /// its content once the `Frame` has been finished and control has returned to the browser.
+    #[inline]
+    pub fn draw(&self) -> Frame {
+        Frame::new(self.context.clone(), self.get_framebuffer_dimensions())
+    }
+}
+
+impl Deref for WebGl {
+    type Target = context::Context;
+
+    #[inline]
+    fn deref(&self) -> &context::Context {
+        &self.context
+    }
+}
+
+impl backend::Facade for WebGl {
+    #[inline]
+    fn get_context(&self) -> &Rc<context::Context> {
+        &self.context
+    }
+}
+
+impl fmt::Debug for WebGl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[glium::backend::webgl::WebGl]")
+    }
+}
+
+unsafe impl Backend for WebGlBackend {
+    #[inline]
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        // the browser presents the canvas by itself
+        if self.state.gl().is_context_lost() {
+            return Err(SwapBuffersError::ContextLost);
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        functions::get_proc_address(symbol)
+    }
+
+    #[inline]
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        let gl = self.state.gl();
+        (gl.drawing_buffer_width() as u32, gl.drawing_buffer_height() as u32)
+    }
+
+    #[inline]
+    fn is_current(&self) -> bool {
+        functions::is_current(&self.state)
+    }
+
+    #[inline]
+    unsafe fn make_current(&self) {
+        functions::make_current(Some(self.state.clone()));
+    }
+}
+
+impl Drop for WebGlBackend {
+    fn drop(&mut self) {
+        if self.is_current() {
+            functions::make_current(None);
+        }
+    }
+}
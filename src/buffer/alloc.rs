@@ -168,6 +168,62 @@ impl Alloc {
         self.persistent_mapping.is_some()
     }
 
+    /// Detaches the buffer from its context without destroying it. See `transfer::Transferable`.
+    pub fn detach(self) -> DetachedBuffer {
+        {
+            let id = self.id;
+            self.context.for_each_shared_context(&mut |ctxt| {
+                VertexAttributesSystem::purge_buffer(ctxt, id);
+                forget_buffer_bindings(ctxt, id);
+            });
+
+            let mut ctxt = self.context.make_current();
+            self.assert_unmapped(&mut ctxt);
+            self.assert_not_transform_feedback(&mut ctxt);
+            VertexAttributesSystem::purge_buffer(&mut ctxt, self.id);
+            forget_buffer_bindings(&mut ctxt, self.id);
+        }
+
+        let detached = DetachedBuffer {
+            id: self.id,
+            ty: self.ty,
+            size: self.size,
+            persistent_mapping: self.persistent_mapping,
+            immutable: self.immutable,
+            creation_mode: self.creation_mode,
+            created_with_buffer_storage: self.created_with_buffer_storage,
+        };
+
+        // skipping the destructor, which would destroy the buffer
+        let context = unsafe { ptr::read(&self.context) };
+        mem::forget(self);
+        drop(context);
+
+        detached
+    }
+
+    /// Attaches a detached buffer to the context of `facade`.
+    ///
+    /// # Unsafety
+    ///
+    /// The context must share its objects with the one the buffer has been detached from.
+    pub unsafe fn attach<F: ?Sized>(facade: &F, detached: DetachedBuffer) -> Alloc
+                                    where F: Facade
+    {
+        Alloc {
+            context: facade.get_context().clone(),
+            id: detached.id,
+            ty: detached.ty,
+            size: detached.size,
+            persistent_mapping: detached.persistent_mapping,
+            immutable: detached.immutable,
+            creation_mode: detached.creation_mode,
+            created_with_buffer_storage: detached.created_with_buffer_storage,
+            mapped: Cell::new(false),
+            latest_shader_write: Cell::new(0),
+        }
+    }
+
     /// Changes the type of the buffer. Returns `Err` if this is forbidden.
     pub fn set_type(mut self, ty: BufferType) -> Result<Alloc, Alloc> {
         // FIXME: return Err for GLES2
@@ -312,6 +368,20 @@ impl Alloc {
         unsafe { bind_buffer(ctxt, self.id, BufferType::DrawIndirectBuffer); }
     }
 
+    /// Makes sure that the buffer is bound to the `GL_PARAMETER_BUFFER` and calls
+    /// `glMemoryBarrier(GL_COMMAND_BARRIER_BIT)` if necessary.
+    pub fn prepare_and_bind_for_parameter(&self, ctxt: &mut CommandContext<'_>) {
+        self.assert_unmapped(ctxt);
+        self.assert_not_transform_feedback(ctxt);
+
+        if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_command {
+            unsafe { ctxt.gl.MemoryBarrier(gl::COMMAND_BARRIER_BIT); }
+            ctxt.state.latest_memory_barrier_command = ctxt.state.next_draw_call_id;
+        }
+
+        unsafe { bind_buffer(ctxt, self.id, BufferType::ParameterBuffer); }
+    }
+
     /// Makes sure that the buffer is bound to the `GL_DISPATCH_INDIRECT_BUFFER` and calls
     /// `glMemoryBarrier(GL_COMMAND_BARRIER_BIT)` if necessary.
     pub fn prepare_and_bind_for_dispatch_indirect(&self, ctxt: &mut CommandContext<'_>) {
@@ -378,6 +448,17 @@ impl Alloc {
         self.latest_shader_write.set(ctxt.state.next_draw_call_id);        // TODO: put this somewhere else
     }
 
+    /// Must be called when the buffer is about to be accessed through an image unit by the
+    /// next draw or compute command.
+    pub fn prepare_for_image_unit(&self, ctxt: &mut CommandContext<'_>, write: bool) {
+        self.assert_unmapped(ctxt);
+        self.assert_not_transform_feedback(ctxt);
+
+        if write {
+            self.latest_shader_write.set(ctxt.state.next_draw_call_id);
+        }
+    }
+
     /// Binds the buffer to `GL_TRANSFORM_FEEDBACk_BUFFER` regardless of the current transform
     /// feedback object.
     #[inline]
@@ -437,7 +518,7 @@ impl Alloc {
         assert!(offset_bytes + mem::size_of_val(data) <= self.size);
 
         if self.persistent_mapping.is_some() {
-            let mapping = Mapping { mapping: self.map_shared(offset_bytes .. offset_bytes + mem::size_of_val(data), false, true) };
+            let mapping = Mapping { mapping: self.map_shared(offset_bytes .. offset_bytes + mem::size_of_val(data), false, true).unwrap() };
             ptr::copy_nonoverlapping(data.to_void_ptr() as *const u8, <D as Content>::to_void_ptr(&mapping) as *mut u8, mem::size_of_val(data));
 
         } else if self.immutable {
@@ -528,30 +609,60 @@ impl Alloc {
             }
 
         } else if !self.created_with_buffer_storage && is_whole_buffer {
-            let flags = match self.creation_mode {
-                BufferMode::Default | BufferMode::Immutable => gl::STATIC_DRAW,
-                BufferMode::Persistent | BufferMode::Dynamic => gl::DYNAMIC_DRAW,
-            };
+            unsafe { self.reallocate_storage(&mut ctxt); }
+        }
+    }
 
-            if ctxt.version >= &Version(Api::Gl, 1, 5) ||
-                ctxt.version >= &Version(Api::GlEs, 2, 0)
-            {
-                unsafe {
-                    let bind = bind_buffer(&mut ctxt, self.id, self.ty);
-                    ctxt.gl.BufferData(bind, size as gl::types::GLsizeiptr,
-                                       ptr::null(), flags);
-                }
+    /// Orphans the buffer. The data becomes undefined.
+    ///
+    /// Contrary to `invalidate`, this always gives a new storage to the buffer if the buffer
+    /// was not created with `glBufferStorage`. The old storage is kept alive by the driver for
+    /// as long as it is used by the GPU, which means that this never waits.
+    ///
+    /// If the buffer was created with `glBufferStorage`, this is the same as invalidating the
+    /// whole buffer.
+    pub fn orphan(&self) {
+        if self.created_with_buffer_storage || self.persistent_mapping.is_some() ||
+           self.size == 0
+        {
+            self.invalidate(0, self.size);
+            return;
+        }
 
-            } else if ctxt.extensions.gl_arb_vertex_buffer_object {
-                unsafe {
-                    let bind = bind_buffer(&mut ctxt, self.id, self.ty);
-                    ctxt.gl.BufferDataARB(bind, size as gl::types::GLsizeiptr,
-                                          ptr::null(), flags);
-                }
+        let mut ctxt = self.context.make_current();
+        self.assert_unmapped(&mut ctxt);
+        self.assert_not_transform_feedback(&mut ctxt);
 
-            } else {
-                unreachable!();
-            }
+        unsafe { self.reallocate_storage(&mut ctxt); }
+    }
+
+    /// Calls `glBufferData` with a null pointer for data.
+    ///
+    /// # Safety
+    ///
+    /// The buffer must not have been created with `glBufferStorage`.
+    unsafe fn reallocate_storage(&self, mut ctxt: &mut CommandContext<'_>) {
+        debug_assert!(!self.created_with_buffer_storage);
+
+        let flags = match self.creation_mode {
+            BufferMode::Default | BufferMode::Immutable => gl::STATIC_DRAW,
+            BufferMode::Persistent | BufferMode::Dynamic => gl::DYNAMIC_DRAW,
+        };
+
+        if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+            ctxt.version >= &Version(Api::GlEs, 2, 0)
+        {
+            let bind = bind_buffer(&mut ctxt, self.id, self.ty);
+            ctxt.gl.BufferData(bind, self.size as gl::types::GLsizeiptr,
+                               ptr::null(), flags);
+
+        } else if ctxt.extensions.gl_arb_vertex_buffer_object {
+            let bind = bind_buffer(&mut ctxt, self.id, self.ty);
+            ctxt.gl.BufferDataARB(bind, self.size as gl::types::GLsizeiptr,
+                                  ptr::null(), flags);
+
+        } else {
+            unreachable!();
         }
     }
 
@@ -584,8 +695,10 @@ impl Alloc {
     /// If you pass `false` for `read`, you **must not** read the returned buffer. If you pass
     /// `false` for `write`, you **must not** write the returned buffer.
     ///
+    /// Returns `None` if the temporary buffer couldn't be created or mapped.
+    ///
     unsafe fn map_shared<D: ?Sized>(&self, bytes_range: Range<usize>, read: bool, write: bool)
-                                    -> MappingImpl<'_, D> where D: Content
+                                    -> Option<MappingImpl<'_, D>> where D: Content
     {
         if let Some(existing_mapping) = self.persistent_mapping {
             // TODO: optimize so that it's not always necessary to make the context current
@@ -596,12 +709,12 @@ impl Alloc {
             let data = Content::ref_from_ptr(data as *mut (),
                                              bytes_range.end - bytes_range.start).unwrap();
 
-            MappingImpl::PersistentMapping {
+            Some(MappingImpl::PersistentMapping {
                 buffer: self,
                 offset_bytes: bytes_range.start,
                 data,
                 needs_flushing: write,
-            }
+            })
 
         } else {
             let size_bytes = bytes_range.end - bytes_range.start;
@@ -614,7 +727,7 @@ impl Alloc {
             let temporary_buffer = {
                 let (temporary_buffer, _, _, _) = create_buffer::<D>(&mut ctxt, size_bytes,
                                                                      None, BufferType::CopyWriteBuffer,
-                                                                     BufferMode::Dynamic).unwrap();
+                                                                     BufferMode::Dynamic).ok()?;
                 temporary_buffer
             };
 
@@ -627,8 +740,15 @@ impl Alloc {
                                 temporary_buffer, 0, size_bytes).unwrap();
                 }
 
-                map_buffer(&mut ctxt, temporary_buffer, self.ty, 0 .. size_bytes, true, true)
-                                    .expect("Buffer mapping is not supported by the backend")
+                match map_buffer(&mut ctxt, temporary_buffer, self.ty, 0 .. size_bytes, true, true,
+                                 false)
+                {
+                    Some(ptr) if !ptr.is_null() => ptr,
+                    _ => {
+                        destroy_buffer(&mut ctxt, temporary_buffer);
+                        return None;
+                    }
+                }
             };
 
             let data = match Content::ref_from_ptr(ptr, bytes_range.end - bytes_range.start) {
@@ -639,13 +759,13 @@ impl Alloc {
                 }
             };
 
-            MappingImpl::TemporaryBuffer {
+            Some(MappingImpl::TemporaryBuffer {
                 original_buffer: self,
                 original_buffer_offset: bytes_range.start,
                 temporary_buffer,
                 temporary_buffer_data: data,
                 needs_flushing: write,
-            }
+            })
         }
     }
 
@@ -672,8 +792,13 @@ impl Alloc {
     /// If you pass `false` for `read`, you **must not** read the returned buffer. If you pass
     /// `false` for `write`, you **must not** write the returned buffer.
     ///
-    unsafe fn map_impl<D: ?Sized>(&mut self, bytes_range: Range<usize>, read: bool, write: bool)
-                                  -> MappingImpl<'_, D> where D: Content
+    /// If you pass `true` for `unsynchronized`, the buffer is mapped with
+    /// `GL_MAP_UNSYNCHRONIZED_BIT` and you must make sure that the GPU is not accessing the
+    /// range. Returns `None` if the mapping failed, for example because the context was lost.
+    ///
+    unsafe fn map_impl<D: ?Sized>(&mut self, bytes_range: Range<usize>, read: bool, write: bool,
+                                  unsynchronized: bool) -> Option<MappingImpl<'_, D>>
+                                  where D: Content
     {
         if self.persistent_mapping.is_some() || self.immutable {
             self.map_shared(bytes_range, read, write)
@@ -687,8 +812,12 @@ impl Alloc {
                     self.assert_not_transform_feedback(&mut ctxt);
                     self.barrier_for_buffer_update(&mut ctxt);
                     let ptr = map_buffer(&mut ctxt, self.id, self.ty, bytes_range.clone(),
-                                         read, write)
-                                        .expect("Buffer mapping is not supported by the backend");
+                                         read, write, unsynchronized)?;
+
+                    if ptr.is_null() {
+                        return None;
+                    }
+
                     self.mapped.set(true);
                     ptr
                 };
@@ -697,16 +826,17 @@ impl Alloc {
                     Some(data) => data,
                     None => {
                         unmap_buffer(&mut ctxt, self.id, self.ty);
+                        self.mapped.set(false);
                         panic!("Wrong bytes range");
                     }
                 }
             };
 
-            MappingImpl::RegularMapping {
+            Some(MappingImpl::RegularMapping {
                 buffer: self,
                 data,
                 needs_flushing: write,
-            }
+            })
         }
     }
 
@@ -726,7 +856,7 @@ impl Alloc {
                                  -> Mapping<'_, D> where D: Content
     {
         Mapping {
-            mapping: self.map_impl(bytes_range, true, true)
+            mapping: self.map_impl(bytes_range, true, true, false).expect("Failed to map the buffer")
         }
     }
 
@@ -746,7 +876,7 @@ impl Alloc {
                                       -> ReadMapping<'_, D> where D: Content
     {
         ReadMapping {
-            mapping: self.map_impl(bytes_range, true, false)
+            mapping: self.map_impl(bytes_range, true, false, false).expect("Failed to map the buffer")
         }
     }
 
@@ -766,10 +896,37 @@ impl Alloc {
                                        -> WriteMapping<'_, D> where D: Content
     {
         WriteMapping {
-            mapping: self.map_impl(bytes_range, false, true)
+            mapping: self.map_impl(bytes_range, false, true, false).expect("Failed to map the buffer")
         }
     }
 
+    /// Returns a write-only mapping in memory of the content of the buffer, without any implicit
+    /// synchronization with the GPU.
+    ///
+    /// Returns `None` if the context has been lost or if the mapping failed.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the `bytes_range` is not aligned to a mappable slice.
+    ///
+    /// # Unsafety
+    ///
+    /// The caller must make sure that the GPU is not accessing the range.
+    ///
+    #[inline]
+    pub unsafe fn map_write_unsynchronized<D: ?Sized>(&mut self, bytes_range: Range<usize>)
+                                                      -> Option<WriteMapping<'_, D>>
+                                                      where D: Content
+    {
+        if self.context.is_context_lost() {
+            return None;
+        }
+
+        Some(WriteMapping {
+            mapping: self.map_impl(bytes_range, false, true, true)?
+        })
+    }
+
     /// Reads the content of the buffer.
     ///
     /// # Panic
@@ -788,7 +945,7 @@ impl Alloc {
         let size_to_read = range.end - range.start;
 
         if self.persistent_mapping.is_some() {
-            let mapping = ReadMapping { mapping: self.map_shared(range, true, false) };
+            let mapping = ReadMapping { mapping: self.map_shared(range, true, false).unwrap() };
             <D as Content>::read(size_to_read, |output| {
                 ptr::copy_nonoverlapping(<D as Content>::to_void_ptr(&mapping) as *const u8, output as *mut D as *mut u8, size_to_read);
                 Ok(())
@@ -861,6 +1018,28 @@ impl Alloc {
     }
 }
 
+/// A buffer that has been detached from its context, and that can be sent to another thread.
+///
+/// See `transfer::Transferable`.
+pub struct DetachedBuffer {
+    id: gl::types::GLuint,
+    ty: BufferType,
+    size: usize,
+    persistent_mapping: Option<*mut raw::c_void>,
+    immutable: bool,
+    creation_mode: BufferMode,
+    created_with_buffer_storage: bool,
+}
+
+// the persistent mapping is valid in all the contexts that share the buffer
+unsafe impl Send for DetachedBuffer {}
+
+impl fmt::Debug for DetachedBuffer {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(fmt, "Detached buffer #{} (size: {} bytes)", self.id, self.size)
+    }
+}
+
 impl fmt::Debug for Alloc {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         write!(fmt, "Buffer #{} (size: {} bytes)", self.id, self.size)
@@ -870,6 +1049,12 @@ impl fmt::Debug for Alloc {
 impl Drop for Alloc {
     fn drop(&mut self) {
         unsafe {
+            let id = self.id;
+            self.context.for_each_shared_context(&mut |ctxt| {
+                VertexAttributesSystem::purge_buffer(ctxt, id);
+                forget_buffer_bindings(ctxt, id);
+            });
+
             let mut ctxt = self.context.make_current();
             self.assert_unmapped(&mut ctxt);
             self.assert_not_transform_feedback(&mut ctxt);
@@ -1341,6 +1526,10 @@ fn is_buffer_type_supported(ctxt: &mut CommandContext<'_>, ty: BufferType) -> bo
             ctxt.extensions.gl_ext_multi_draw_indirect
         },
 
+        BufferType::ParameterBuffer => {
+            ctxt.extensions.gl_arb_indirect_parameters
+        },
+
         BufferType::DispatchIndirectBuffer => {
             ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
             ctxt.extensions.gl_arb_compute_shader
@@ -1420,6 +1609,7 @@ unsafe fn bind_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint, ty:
     check!(ctxt, id, ty, CopyWriteBuffer, copy_write_buffer_binding);
     check!(ctxt, id, ty, DispatchIndirectBuffer, dispatch_indirect_buffer_binding);
     check!(ctxt, id, ty, DrawIndirectBuffer, draw_indirect_buffer_binding);
+    check!(ctxt, id, ty, ParameterBuffer, parameter_buffer_binding);
     check!(ctxt, id, ty, QueryBuffer, query_buffer_binding);
     check!(ctxt, id, ty, TextureBuffer, texture_buffer_binding);
     check!(ctxt, id, ty, AtomicCounterBuffer, atomic_counter_buffer_binding);
@@ -1622,6 +1812,24 @@ unsafe fn destroy_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
     // FIXME: uncomment this and move it from Buffer's destructor
     //self.context.vertex_array_objects.purge_buffer(&mut ctxt, id);
 
+    forget_buffer_bindings(ctxt, id);
+
+    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+        ctxt.version >= &Version(Api::GlEs, 2, 0)
+    {
+        ctxt.gl.DeleteBuffers(1, [id].as_ptr());
+    } else if ctxt.extensions.gl_arb_vertex_buffer_object {
+        ctxt.gl.DeleteBuffersARB(1, [id].as_ptr());
+    } else {
+        unreachable!();
+    }
+}
+
+/// Removes a buffer from the cached bindings of the context.
+///
+/// Also used with the contexts that share the buffer, as deleting it doesn't unbind it from
+/// them and its name can be reused.
+fn forget_buffer_bindings(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
     if ctxt.state.array_buffer_binding == id {
         ctxt.state.array_buffer_binding = 0;
     }
@@ -1654,6 +1862,10 @@ unsafe fn destroy_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
         ctxt.state.draw_indirect_buffer_binding = 0;
     }
 
+    if ctxt.state.parameter_buffer_binding == id {
+        ctxt.state.parameter_buffer_binding = 0;
+    }
+
     if ctxt.state.query_buffer_binding == id {
         ctxt.state.query_buffer_binding = 0;
     }
@@ -1694,16 +1906,6 @@ unsafe fn destroy_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
             point.buffer = 0;
         }
     }
-
-    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
-        ctxt.version >= &Version(Api::GlEs, 2, 0)
-    {
-        ctxt.gl.DeleteBuffers(1, [id].as_ptr());
-    } else if ctxt.extensions.gl_arb_vertex_buffer_object {
-        ctxt.gl.DeleteBuffersARB(1, [id].as_ptr());
-    } else {
-        unreachable!();
-    }
 }
 
 /// Flushes a range of a mapped buffer.
@@ -1734,8 +1936,12 @@ unsafe fn flush_range(mut ctxt: &mut CommandContext<'_>, id: gl::types::GLuint,
 /// Maps a range of a buffer.
 ///
 /// *Warning*: always passes `GL_MAP_FLUSH_EXPLICIT_BIT`.
+///
+/// `unsynchronized` is ignored if `read` is true, as `GL_MAP_UNSYNCHRONIZED_BIT` can't be used
+/// for reading.
 unsafe fn map_buffer(mut ctxt: &mut CommandContext<'_>, id: gl::types::GLuint, ty: BufferType,
-                     range: Range<usize>, read: bool, write: bool) -> Option<*mut ()>
+                     range: Range<usize>, read: bool, write: bool, unsynchronized: bool)
+                     -> Option<*mut ()>
 {
     let flags = match (read, write) {
         (true, true) => gl::MAP_FLUSH_EXPLICIT_BIT | gl::MAP_READ_BIT | gl::MAP_WRITE_BIT,
@@ -1744,6 +1950,12 @@ unsafe fn map_buffer(mut ctxt: &mut CommandContext<'_>, id: gl::types::GLuint, t
         (false, false) => 0,
     };
 
+    let flags = if unsynchronized && !read {
+        flags | gl::MAP_UNSYNCHRONIZED_BIT
+    } else {
+        flags
+    };
+
     if ctxt.version >= &Version(Api::Gl, 4, 5) {
         Some(ctxt.gl.MapNamedBufferRange(id, range.start as gl::types::GLintptr,
                                          (range.end - range.start) as gl::types::GLsizeiptr,
@@ -0,0 +1,413 @@
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::backend::Facade;
+use crate::buffer::{Buffer, BufferSlice, BufferMutSlice, BufferType, BufferMode};
+use crate::buffer::{BufferCreationError, Content, CopyError};
+use crate::index::{Index, IndicesSource, PrimitiveType};
+use crate::vertex::{Vertex, VertexFormat, VerticesSource};
+
+/// A large buffer from which smaller slices are sub-allocated.
+///
+/// Creating thousands of small vertex or index buffers is slow and fragments the memory of the
+/// driver. Instead, a `BufferArena` creates a single buffer and hands out `ArenaAllocation`s that
+/// each correspond to a range of this buffer. Freed ranges are put in a free list and reused by
+/// the next allocations.
+///
+/// Over time, the free space can get split in many small ranges. Call `defragment` to move all
+/// the allocations at the start of the buffer. This doesn't invalidate the `ArenaAllocation`s.
+///
+/// # Example
+///
+/// ```no_run
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// # let program: glium::Program = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// # let mut frame = display.draw();
+/// use glium::Surface;
+/// use glium::buffer::BufferArena;
+///
+/// #[derive(Copy, Clone)]
+/// struct Vertex { position: [f32; 2] }
+/// implement_vertex!(Vertex, position);
+///
+/// let mut arena = BufferArena::<Vertex>::vertex(&display, 4096).unwrap();
+/// let triangle = arena.allocate_with_data(&[
+///     Vertex { position: [-0.5, -0.5] },
+///     Vertex { position: [ 0.0,  0.5] },
+///     Vertex { position: [ 0.5, -0.5] },
+/// ]).unwrap();
+///
+/// frame.draw(arena.vertices(&triangle),
+///            glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList),
+///            &program, &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+///
+/// arena.free(triangle);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct BufferArena<T> where T: Copy {
+    buffer: Buffer<[T]>,
+
+    // Identifier of the arena, stored in its allocations.
+    arena_id: usize,
+
+    // Only set if the arena has been created with `vertex`.
+    bindings: Option<VertexFormat>,
+
+    // Range of elements of each allocation, indexed by the id of the allocation. `None` if
+    // the allocation has been freed and the id can be reused.
+    allocations: Vec<Option<Range<usize>>>,
+
+    // Free ranges of elements, sorted by start and never adjacent to each other.
+    free: FreeList,
+}
+
+/// A range of elements allocated from a `BufferArena`.
+///
+/// This object doesn't borrow the arena. Pass it to the methods of the arena that created it
+/// in order to access the corresponding slice of the buffer.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ArenaAllocation {
+    arena_id: usize,
+    id: usize,
+}
+
+impl<T> BufferArena<T> where [T]: Content, T: Copy {
+    /// Builds a new arena that can hold `capacity` elements.
+    pub fn new<F: ?Sized>(facade: &F, ty: BufferType, capacity: usize)
+                          -> Result<BufferArena<T>, BufferCreationError>
+                          where F: Facade
+    {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        let buffer = Buffer::empty_array(facade, ty, capacity, BufferMode::Default)?;
+
+        Ok(BufferArena {
+            buffer,
+            arena_id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            bindings: None,
+            allocations: Vec::new(),
+            free: FreeList::new(capacity),
+        })
+    }
+
+    /// Returns the number of elements that the arena can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the total number of elements that are not allocated.
+    #[inline]
+    pub fn get_free_len(&self) -> usize {
+        self.free.total_len()
+    }
+
+    /// Returns the number of elements of the largest free range.
+    ///
+    /// This is the largest allocation that can succeed without calling `defragment` first.
+    #[inline]
+    pub fn get_largest_free_len(&self) -> usize {
+        self.free.largest_len()
+    }
+
+    /// Allocates a range of `len` elements with an undefined content.
+    ///
+    /// Returns `None` if there is no free range big enough. Calling `defragment` may help if
+    /// the total free space is large enough.
+    pub fn allocate(&mut self, len: usize) -> Option<ArenaAllocation> {
+        let start = self.free.allocate(len)?;
+        let range = start .. start + len;
+
+        let id = match self.allocations.iter().position(|a| a.is_none()) {
+            Some(id) => {
+                self.allocations[id] = Some(range);
+                id
+            },
+            None => {
+                self.allocations.push(Some(range));
+                self.allocations.len() - 1
+            },
+        };
+
+        Some(ArenaAllocation { arena_id: self.arena_id, id })
+    }
+
+    /// Allocates a range of elements and writes `data` in it.
+    ///
+    /// Returns `None` if there is no free range big enough.
+    pub fn allocate_with_data(&mut self, data: &[T]) -> Option<ArenaAllocation> {
+        let allocation = self.allocate(data.len())?;
+        if !data.is_empty() {
+            self.slice(&allocation).write(data);
+        }
+        Some(allocation)
+    }
+
+    /// Frees an allocation. Its range can be reused by the next allocations.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the allocation doesn't belong to this arena.
+    pub fn free(&mut self, allocation: ArenaAllocation) {
+        self.check_allocation(&allocation);
+        let range = self.allocations[allocation.id].take().unwrap();
+        self.free.free(range);
+    }
+
+    /// Returns the range of elements of the buffer that corresponds to an allocation.
+    ///
+    /// This range can change when calling `defragment`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the allocation doesn't belong to this arena.
+    #[inline]
+    pub fn get_range(&self, allocation: &ArenaAllocation) -> Range<usize> {
+        self.check_allocation(allocation);
+        self.allocations[allocation.id].clone().unwrap()
+    }
+
+    fn check_allocation(&self, allocation: &ArenaAllocation) {
+        assert_eq!(allocation.arena_id, self.arena_id,
+                   "The allocation doesn't belong to this arena");
+    }
+
+    /// Returns the slice of the buffer that corresponds to an allocation.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the allocation doesn't belong to this arena.
+    #[inline]
+    pub fn slice(&self, allocation: &ArenaAllocation) -> BufferSlice<'_, [T]> {
+        let range = self.get_range(allocation);
+        self.buffer.slice(range).unwrap()
+    }
+
+    /// Returns the slice of the buffer that corresponds to an allocation.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the allocation doesn't belong to this arena.
+    #[inline]
+    pub fn slice_mut(&mut self, allocation: &ArenaAllocation) -> BufferMutSlice<'_, [T]> {
+        let range = self.get_range(allocation);
+        self.buffer.slice_mut(range).unwrap()
+    }
+
+    /// Moves all the allocations at the start of the buffer, so that the free space is a
+    /// single range at the end of the buffer.
+    ///
+    /// The content of the allocations is copied by the GPU, and the `ArenaAllocation`s stay
+    /// valid. Only their ranges are modified.
+    pub fn defragment(&mut self) -> Result<(), CopyError> {
+        let mut order = (0 .. self.allocations.len())
+                            .filter(|&id| self.allocations[id].is_some())
+                            .collect::<Vec<_>>();
+        order.sort_by_key(|&id| self.allocations[id].as_ref().unwrap().start);
+
+        let mut dest = 0;
+        for id in order {
+            let range = self.allocations[id].clone().unwrap();
+            let len = range.end - range.start;
+            debug_assert!(dest <= range.start);
+
+            if dest != range.start {
+                // the source and the destination can overlap, which is forbidden by OpenGL,
+                // so we copy chunks that are no larger than the distance between them
+                let step = range.start - dest;
+                let mut offset = 0;
+                while offset < len {
+                    let chunk = step.min(len - offset);
+                    let src = range.start + offset;
+                    self.buffer.slice(src .. src + chunk).unwrap()
+                               .copy_to(self.buffer.slice(dest + offset .. dest + offset + chunk)
+                                                   .unwrap())?;
+                    offset += chunk;
+                }
+
+                self.allocations[id] = Some(dest .. dest + len);
+            }
+
+            dest += len;
+        }
+
+        self.free = FreeList::new(self.capacity());
+        if dest != 0 {
+            self.free.allocate(dest).unwrap();
+        }
+
+        Ok(())
+    }
+
+    /// Returns the underlying buffer.
+    #[inline]
+    pub fn get_buffer(&self) -> &Buffer<[T]> {
+        &self.buffer
+    }
+}
+
+impl<T> BufferArena<T> where [T]: Content, T: Vertex {
+    /// Builds a new arena of vertices that can hold `capacity` elements.
+    ///
+    /// The allocations of this arena can be used as a source of vertices with `vertices`.
+    pub fn vertex<F: ?Sized>(facade: &F, capacity: usize)
+                             -> Result<BufferArena<T>, BufferCreationError>
+                             where F: Facade
+    {
+        let mut arena = BufferArena::new(facade, BufferType::ArrayBuffer, capacity)?;
+        arena.bindings = Some(<T as Vertex>::build_bindings());
+        Ok(arena)
+    }
+
+    /// Returns a source of vertices that corresponds to an allocation.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the allocation doesn't belong to this arena, or if the arena hasn't been
+    /// created with `BufferArena::vertex`.
+    pub fn vertices(&self, allocation: &ArenaAllocation) -> VerticesSource<'_> {
+        let bindings = self.bindings.as_ref()
+                           .expect("The arena hasn't been created with `BufferArena::vertex`");
+        VerticesSource::VertexBuffer(self.slice(allocation).as_slice_any(), bindings, false)
+    }
+
+    /// Returns a source of per-instance attributes that corresponds to an allocation.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the allocation doesn't belong to this arena, or if the arena hasn't been
+    /// created with `BufferArena::vertex`.
+    pub fn per_instance(&self, allocation: &ArenaAllocation) -> VerticesSource<'_> {
+        let bindings = self.bindings.as_ref()
+                           .expect("The arena hasn't been created with `BufferArena::vertex`");
+        VerticesSource::VertexBuffer(self.slice(allocation).as_slice_any(), bindings, true)
+    }
+}
+
+impl<T> BufferArena<T> where [T]: Content, T: Index {
+    /// Builds a new arena of indices that can hold `capacity` elements.
+    ///
+    /// The allocations of this arena can be used as a source of indices with `indices`.
+    #[inline]
+    pub fn index<F: ?Sized>(facade: &F, capacity: usize)
+                            -> Result<BufferArena<T>, BufferCreationError>
+                            where F: Facade
+    {
+        BufferArena::new(facade, BufferType::ElementArrayBuffer, capacity)
+    }
+
+    /// Returns a source of indices that corresponds to an allocation.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the allocation doesn't belong to this arena.
+    #[inline]
+    pub fn indices(&self, allocation: &ArenaAllocation, primitives: PrimitiveType)
+                   -> IndicesSource<'_>
+    {
+        IndicesSource::IndexBuffer {
+            buffer: self.slice(allocation).as_slice_any(),
+            data_type: <T as Index>::get_type(),
+            primitives,
+        }
+    }
+}
+
+/// List of the free ranges of a `BufferArena`.
+#[derive(Debug, Clone)]
+struct FreeList {
+    ranges: Vec<Range<usize>>,
+}
+
+impl FreeList {
+    #[inline]
+    fn new(capacity: usize) -> FreeList {
+        let mut ranges = Vec::new();
+        if capacity != 0 {
+            ranges.push(0 .. capacity);
+        }
+
+        FreeList { ranges }
+    }
+
+    fn total_len(&self) -> usize {
+        self.ranges.iter().map(|r| r.end - r.start).sum()
+    }
+
+    fn largest_len(&self) -> usize {
+        self.ranges.iter().map(|r| r.end - r.start).max().unwrap_or(0)
+    }
+
+    /// Finds the first range that is large enough and returns the start of the allocation.
+    fn allocate(&mut self, len: usize) -> Option<usize> {
+        if len == 0 {
+            return Some(0);
+        }
+
+        let index = self.ranges.iter().position(|r| r.end - r.start >= len)?;
+        let start = self.ranges[index].start;
+
+        if self.ranges[index].end - start == len {
+            self.ranges.remove(index);
+        } else {
+            self.ranges[index].start += len;
+        }
+
+        Some(start)
+    }
+
+    /// Puts a range back in the list and merges it with its neighbours.
+    fn free(&mut self, range: Range<usize>) {
+        if range.start == range.end {
+            return;
+        }
+
+        let index = self.ranges.iter().position(|r| r.start > range.start)
+                               .unwrap_or(self.ranges.len());
+        debug_assert!(index == self.ranges.len() || range.end <= self.ranges[index].start);
+        debug_assert!(index == 0 || self.ranges[index - 1].end <= range.start);
+
+        let merge_prev = index > 0 && self.ranges[index - 1].end == range.start;
+        let merge_next = index < self.ranges.len() && self.ranges[index].start == range.end;
+
+        match (merge_prev, merge_next) {
+            (true, true) => {
+                let next = self.ranges.remove(index);
+                self.ranges[index - 1].end = next.end;
+            },
+            (true, false) => self.ranges[index - 1].end = range.end,
+            (false, true) => self.ranges[index].start = range.start,
+            (false, false) => self.ranges.insert(index, range),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FreeList;
+
+    #[test]
+    fn free_list_reuse_and_merge() {
+        let mut list = FreeList::new(100);
+        assert_eq!(list.allocate(10), Some(0));
+        assert_eq!(list.allocate(20), Some(10));
+        assert_eq!(list.allocate(30), Some(30));
+        assert_eq!(list.allocate(50), None);
+        assert_eq!(list.total_len(), 40);
+
+        list.free(10 .. 30);
+        assert_eq!(list.largest_len(), 40);
+        assert_eq!(list.allocate(15), Some(10));
+
+        list.free(0 .. 10);
+        list.free(30 .. 60);
+        assert_eq!(list.ranges, vec![0 .. 10, 25 .. 100]);
+
+        list.free(10 .. 25);
+        assert_eq!(list.ranges, vec![0 .. 100]);
+    }
+}
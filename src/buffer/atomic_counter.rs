@@ -0,0 +1,140 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::backend::Facade;
+use crate::buffer::{Buffer, BufferSlice, BufferMutSlice, BufferType, BufferMode};
+use crate::buffer::BufferCreationError;
+use crate::program::{self, BlockLayout};
+use crate::uniforms::{AsUniformValue, LayoutMismatchError, UniformType, UniformValue};
+use crate::version::{Api, Version};
+use crate::CapabilitiesSource;
+
+/// A buffer containing a list of `u32` counters, to bind to the `atomic_uint` uniforms of a
+/// program.
+///
+/// Each `atomic_uint` of a program is associated with a binding point and an offset, that are
+/// declared in the shader with `layout(binding = N, offset = M)`. Pass the buffer to
+/// `uniform!` under the name of each counter, and the counter at offset `M` of the buffer can
+/// then be incremented by the GPU.
+///
+/// ```no_run
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// // layout(binding = 0, offset = 0) uniform atomic_uint fragments;
+/// // layout(binding = 0, offset = 4) uniform atomic_uint discarded;
+/// let counters = glium::buffer::AtomicCounterBuffer::new(&display, 2).unwrap();
+///
+/// let uniforms = uniform! {
+///     fragments: &counters,
+///     discarded: &counters,
+/// };
+///
+/// // ... draw ...
+///
+/// let values = counters.read().unwrap();
+/// counters.reset();
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct AtomicCounterBuffer {
+    buffer: Buffer<[u32]>,
+}
+
+impl AtomicCounterBuffer {
+    /// Returns true if atomic counters are supported by the backend.
+    #[inline]
+    pub fn is_supported<C: ?Sized>(caps: &C) -> bool where C: CapabilitiesSource {
+        caps.get_version() >= &Version(Api::Gl, 4, 2) ||
+        caps.get_version() >= &Version(Api::GlEs, 3, 1) ||
+        caps.get_extensions().gl_arb_shader_atomic_counters
+    }
+
+    /// Builds a new buffer containing `len` counters initialized to zero.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F, len: usize)
+                          -> Result<AtomicCounterBuffer, BufferCreationError>
+                          where F: Facade
+    {
+        AtomicCounterBuffer::with_values(facade, &vec![0; len])
+    }
+
+    /// Builds a new buffer containing counters initialized to the given values.
+    #[inline]
+    pub fn with_values<F: ?Sized>(facade: &F, values: &[u32])
+                                  -> Result<AtomicCounterBuffer, BufferCreationError>
+                                  where F: Facade
+    {
+        if !AtomicCounterBuffer::is_supported(facade.get_context()) {
+            return Err(BufferCreationError::BufferTypeNotSupported);
+        }
+
+        let buffer = Buffer::new(facade, values, BufferType::AtomicCounterBuffer,
+                                 BufferMode::Dynamic)?;
+        Ok(AtomicCounterBuffer { buffer })
+    }
+
+    /// Sets all the counters to zero.
+    ///
+    /// This doesn't wait for the GPU to finish using the previous values, which makes it
+    /// suitable for resetting the counters at the start of each frame.
+    #[inline]
+    pub fn reset(&self) {
+        self.buffer.write_discard(&vec![0; self.buffer.len()]);
+    }
+}
+
+impl Deref for AtomicCounterBuffer {
+    type Target = Buffer<[u32]>;
+
+    #[inline]
+    fn deref(&self) -> &Buffer<[u32]> {
+        &self.buffer
+    }
+}
+
+impl DerefMut for AtomicCounterBuffer {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Buffer<[u32]> {
+        &mut self.buffer
+    }
+}
+
+impl<'a> From<&'a AtomicCounterBuffer> for BufferSlice<'a, [u32]> {
+    #[inline]
+    fn from(b: &'a AtomicCounterBuffer) -> BufferSlice<'a, [u32]> {
+        b.buffer.as_slice()
+    }
+}
+
+impl<'a> From<&'a mut AtomicCounterBuffer> for BufferMutSlice<'a, [u32]> {
+    #[inline]
+    fn from(b: &'a mut AtomicCounterBuffer) -> BufferMutSlice<'a, [u32]> {
+        b.buffer.as_mut_slice()
+    }
+}
+
+impl<'a> AsUniformValue for &'a AtomicCounterBuffer {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        // the offset of the counter is checked against the size of the buffer when binding
+        fn f(block: &program::UniformBlock) -> Result<(), LayoutMismatchError> {
+            match block.layout {
+                BlockLayout::BasicType { ty: UniformType::UnsignedInt, .. } => Ok(()),
+                BlockLayout::BasicType { ty, .. } => Err(LayoutMismatchError::TypeMismatch {
+                    expected: ty,
+                    obtained: UniformType::UnsignedInt,
+                }),
+                ref layout => Err(LayoutMismatchError::LayoutMismatch {
+                    expected: layout.clone(),
+                    obtained: BlockLayout::BasicType {
+                        ty: UniformType::UnsignedInt,
+                        offset_in_buffer: 0,
+                    },
+                }),
+            }
+        }
+
+        UniformValue::Block(self.buffer.as_slice_any(), f)
+    }
+}
@@ -38,9 +38,7 @@ impl Fences {
         let mut new_fences = SmallVec::new();
 
         for existing in existing_fences.drain(..) {
-            if (existing.0.start >= range.start && existing.0.start < range.end) ||
-               (existing.0.end > range.start && existing.0.end < range.end)
-            {
+            if existing.0.start < range.end && existing.0.end > range.start {
                 unsafe { sync::wait_linear_sync_fence_and_drop(existing.1, ctxt) };
             } else {
                 new_fences.push(existing);
@@ -48,7 +48,12 @@ pub use self::view::{Buffer, BufferAny, BufferMutSlice};
 pub use self::view::{BufferSlice, BufferAnySlice};
 pub use self::alloc::{Mapping, WriteMapping, ReadMapping, ReadError, CopyError};
 pub use self::alloc::{is_buffer_read_supported};
+pub use self::alloc::DetachedBuffer;
 pub use self::fences::Inserter;
+pub use self::arena::{BufferArena, ArenaAllocation};
+pub use self::atomic_counter::AtomicCounterBuffer;
+pub use self::parameter::ParameterBuffer;
+pub use self::ring::{PersistentRingBuffer, RingBufferRegion};
 
 /// DEPRECATED. Only here for backwards compatibility.
 #[deprecated(note = "Only here for backwards compatibility")]
@@ -73,7 +78,11 @@ use std::mem;
 use std::slice;
 
 mod alloc;
+mod arena;
+mod atomic_counter;
 mod fences;
+mod parameter;
+mod ring;
 mod view;
 
 /// Trait for types of data that can be put inside buffers.
@@ -295,6 +304,7 @@ pub enum BufferType {
     AtomicCounterBuffer,
     DispatchIndirectBuffer,
     DrawIndirectBuffer,
+    ParameterBuffer,
     QueryBuffer,
     ShaderStorageBuffer,
     TextureBuffer,
@@ -314,6 +324,7 @@ impl BufferType {
             BufferType::AtomicCounterBuffer => gl::ATOMIC_COUNTER_BUFFER,
             BufferType::DispatchIndirectBuffer => gl::DISPATCH_INDIRECT_BUFFER,
             BufferType::DrawIndirectBuffer => gl::DRAW_INDIRECT_BUFFER,
+            BufferType::ParameterBuffer => gl::PARAMETER_BUFFER_ARB,
             BufferType::QueryBuffer => gl::QUERY_BUFFER,
             BufferType::ShaderStorageBuffer => gl::SHADER_STORAGE_BUFFER,
             BufferType::TextureBuffer => gl::TEXTURE_BUFFER,
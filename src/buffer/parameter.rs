@@ -0,0 +1,81 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::backend::Facade;
+use crate::buffer::{Buffer, BufferSlice, BufferMutSlice, BufferType, BufferMode};
+use crate::buffer::BufferCreationError;
+
+/// A buffer containing a single `u32` that holds the number of draw commands to execute.
+///
+/// This buffer is meant to be written by the GPU, for example by a compute shader that culls
+/// objects and builds a list of draw commands. Pass it to
+/// `DrawCommandsNoIndicesBuffer::with_primitive_type_and_count` or
+/// `DrawCommandsIndicesBuffer::with_index_buffer_and_count` in order to draw without ever
+/// reading the count back on the CPU.
+///
+/// Drawing with a parameter buffer requires the `GL_ARB_indirect_parameters` extension.
+#[derive(Debug)]
+pub struct ParameterBuffer {
+    buffer: Buffer<u32>,
+}
+
+impl ParameterBuffer {
+    /// Builds a new buffer containing the given number of commands.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F, count: u32) -> Result<ParameterBuffer, BufferCreationError>
+                          where F: Facade
+    {
+        let buffer = Buffer::new(facade, &count, BufferType::ParameterBuffer,
+                                 BufferMode::Default)?;
+        Ok(ParameterBuffer { buffer })
+    }
+
+    /// Builds a new buffer containing the given number of commands.
+    #[inline]
+    pub fn dynamic<F: ?Sized>(facade: &F, count: u32)
+                              -> Result<ParameterBuffer, BufferCreationError>
+                              where F: Facade
+    {
+        let buffer = Buffer::new(facade, &count, BufferType::ParameterBuffer,
+                                 BufferMode::Dynamic)?;
+        Ok(ParameterBuffer { buffer })
+    }
+
+    /// Builds a new buffer with undefined content.
+    #[inline]
+    pub fn empty<F: ?Sized>(facade: &F) -> Result<ParameterBuffer, BufferCreationError>
+                            where F: Facade
+    {
+        let buffer = Buffer::empty(facade, BufferType::ParameterBuffer, BufferMode::Default)?;
+        Ok(ParameterBuffer { buffer })
+    }
+}
+
+impl Deref for ParameterBuffer {
+    type Target = Buffer<u32>;
+
+    #[inline]
+    fn deref(&self) -> &Buffer<u32> {
+        &self.buffer
+    }
+}
+
+impl DerefMut for ParameterBuffer {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Buffer<u32> {
+        &mut self.buffer
+    }
+}
+
+impl<'a> From<&'a ParameterBuffer> for BufferSlice<'a, u32> {
+    #[inline]
+    fn from(b: &'a ParameterBuffer) -> BufferSlice<'a, u32> {
+        b.buffer.as_slice()
+    }
+}
+
+impl<'a> From<&'a mut ParameterBuffer> for BufferMutSlice<'a, u32> {
+    #[inline]
+    fn from(b: &'a mut ParameterBuffer) -> BufferMutSlice<'a, u32> {
+        b.buffer.as_mut_slice()
+    }
+}
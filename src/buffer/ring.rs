@@ -0,0 +1,151 @@
+use std::ops::Range;
+
+use crate::backend::Facade;
+use crate::buffer::{Buffer, BufferSlice, BufferType, BufferMode, BufferCreationError};
+use crate::buffer::{Content, WriteMapping};
+
+/// A persistent-mapped buffer that is used as a ring of regions, in order to stream data to
+/// the GPU every frame without stalling.
+///
+/// Each call to `allocate` hands out the region of the buffer that follows the previous one,
+/// and goes back to the start of the buffer when the end is reached. Using a region in a draw
+/// call or any other command automatically inserts a fence, and a region is only handed out
+/// again once the GPU has finished using it. As long as the buffer is large enough to hold the
+/// data of several frames, the CPU never has to wait.
+///
+/// If the backend doesn't support persistent mapping (`GL_ARB_buffer_storage`), a regular
+/// dynamic buffer is used instead and mapping a region relies on the implicit synchronization
+/// of the driver.
+///
+/// # Example
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// use glium::buffer::{BufferType, PersistentRingBuffer};
+///
+/// let mut ring = PersistentRingBuffer::<f32>::new(&display, BufferType::UniformBuffer,
+///                                                  4096).unwrap();
+///
+/// let range = {
+///     let mut region = ring.allocate(16).unwrap();
+///     for (i, value) in region.iter_mut().enumerate() {
+///         *value = i as f32;
+///     }
+///     region.get_range()
+/// };
+///
+/// // `ring.slice(range)` can now be used by the GPU
+/// let slice = ring.slice(range);
+/// ```
+#[derive(Debug)]
+pub struct PersistentRingBuffer<T> where T: Copy {
+    buffer: Buffer<[T]>,
+
+    // Index of the element where the next region starts.
+    head: usize,
+}
+
+impl<T> PersistentRingBuffer<T> where [T]: Content, T: Copy {
+    /// Builds a new ring buffer that can hold `len` elements.
+    pub fn new<F: ?Sized>(facade: &F, ty: BufferType, len: usize)
+                          -> Result<PersistentRingBuffer<T>, BufferCreationError>
+                          where F: Facade
+    {
+        let buffer = Buffer::empty_array(facade, ty, len, BufferMode::Persistent)?;
+
+        Ok(PersistentRingBuffer {
+            buffer,
+            head: 0,
+        })
+    }
+
+    /// Returns true if the underlying buffer uses persistent mapping.
+    #[inline]
+    pub fn is_persistent(&self) -> bool {
+        self.buffer.is_persistent()
+    }
+
+    /// Returns the number of elements that the ring buffer can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Hands out the next region of `len` elements.
+    ///
+    /// If the GPU is still using this region, waits until it is no longer the case. Returns
+    /// `None` if `len` is larger than the capacity of the buffer.
+    ///
+    /// The content of the region is undefined and should be entirely written. The region is
+    /// mapped for writing only, so its content can't be read.
+    pub fn allocate(&mut self, len: usize) -> Option<RingBufferRegion<'_, T>> {
+        if len > self.capacity() {
+            return None;
+        }
+
+        if self.head + len > self.capacity() {
+            self.head = 0;
+        }
+
+        let range = self.head .. self.head + len;
+        self.head += len;
+
+        let mapping = self.buffer.slice_mut(range.clone()).unwrap().map_write();
+
+        Some(RingBufferRegion {
+            mapping,
+            range,
+        })
+    }
+
+    /// Returns a slice of the buffer, for example a region that has been written with
+    /// `allocate`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the range is out of bounds.
+    #[inline]
+    pub fn slice(&self, range: Range<usize>) -> BufferSlice<'_, [T]> {
+        self.buffer.slice(range).expect("Range out of bounds")
+    }
+
+    /// Returns the underlying buffer.
+    #[inline]
+    pub fn get_buffer(&self) -> &Buffer<[T]> {
+        &self.buffer
+    }
+
+    /// Destroys the ring buffer and returns the underlying buffer.
+    #[inline]
+    pub fn into_buffer(self) -> Buffer<[T]> {
+        self.buffer
+    }
+}
+
+/// A region of a `PersistentRingBuffer` that is being written.
+///
+/// The data is made visible to the GPU when this object is destroyed.
+pub struct RingBufferRegion<'a, T> where [T]: Content {
+    mapping: WriteMapping<'a, [T]>,
+    range: Range<usize>,
+}
+
+impl<'a, T> RingBufferRegion<'a, T> where [T]: Content, T: Copy {
+    /// Returns the range of elements of the buffer that corresponds to this region.
+    ///
+    /// Pass it to `PersistentRingBuffer::slice` once the region has been written.
+    #[inline]
+    pub fn get_range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Changes an element of the region.
+    ///
+    /// # Panic
+    ///
+    /// Panics if out of range.
+    #[inline]
+    pub fn set(&mut self, index: usize, value: T) {
+        self.mapping.set(index, value);
+    }
+}
@@ -1,5 +1,6 @@
 use std::fmt;
 use std::mem;
+use std::ptr;
 use std::borrow::Cow;
 use crate::utils::range::RangeArgument;
 use std::marker::PhantomData;
@@ -29,6 +30,8 @@ use crate::buffer::alloc::ReadMapping;
 use crate::buffer::alloc::WriteMapping;
 use crate::buffer::alloc::ReadError;
 use crate::buffer::alloc::CopyError;
+use crate::buffer::alloc::DetachedBuffer;
+use crate::transfer::Transferable;
 use crate::field::Field;
 
 /// Represents a view of a buffer.
@@ -140,6 +143,38 @@ impl<T: ?Sized> Buffer<T> where T: Content {
         self.alloc.as_ref().unwrap().invalidate(0, self.get_size());
     }
 
+    /// Orphans the buffer. The data becomes undefined.
+    ///
+    /// The buffer is given a new storage, while the old one is kept alive by the driver for as
+    /// long as the GPU is using it. Contrary to writing to a buffer that is still in use, this
+    /// never waits for the GPU. This is the usual way to stream data that changes every frame.
+    ///
+    /// # Implementation
+    ///
+    /// Calls `glBufferData` with a null pointer for data. If `glBufferStorage` has been used to
+    /// create the buffer, this is the same as `invalidate`.
+    ///
+    #[inline]
+    pub fn orphan(&self) {
+        self.alloc.as_ref().unwrap().orphan();
+    }
+
+    /// Orphans the buffer, then uploads some data in it.
+    ///
+    /// This is the same as calling `orphan` then `write`, and is faster than `write` if the
+    /// buffer may still be in use by the GPU. For persistent-mapped buffers, this is the same
+    /// as `write`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `data` is different from the length of this buffer.
+    pub fn write_discard(&self, data: &T) {
+        assert!(mem::size_of_val(data) == self.get_size());
+
+        self.orphan();
+        self.write(data);
+    }
+
     /// Reads the content of the buffer.
     pub fn read(&self) -> Result<T::Owned, ReadError> {
         self.fence.as_ref().unwrap().wait(&mut self.alloc.as_ref().unwrap().get_context().make_current(),
@@ -203,6 +238,33 @@ impl<T: ?Sized> Buffer<T> where T: Content {
         unsafe { self.alloc.as_mut().unwrap().map_write(0 .. size) }
     }
 
+    /// Maps the buffer in memory for writing only, without waiting for the GPU to stop using it.
+    ///
+    /// This is the fastest way to stream data to the GPU, but the synchronization has to be done
+    /// manually, for example with a `SyncFence` inserted after the last command that uses the
+    /// buffer. The returned mapping is write-only and borrows the buffer mutably, which means
+    /// that the buffer can't be used by the GPU for as long as it is alive.
+    ///
+    /// Returns `None` if the context has been lost or if the mapping failed.
+    ///
+    /// # Implementation
+    ///
+    /// - For persistent-mapped buffers, returns a pointer to the existing mapping without
+    ///   waiting for the fences.
+    /// - For immutable buffers, behaves like `map_write` except that it doesn't wait for the
+    ///   GPU.
+    /// - For other types, calls `glMapBufferRange` with `GL_MAP_UNSYNCHRONIZED_BIT`.
+    ///
+    /// # Safety
+    ///
+    /// The GPU must not be reading from or writing to the buffer. Commands that were submitted
+    /// before and that use the buffer must have finished executing.
+    ///
+    pub unsafe fn map_write_unsynchronized(&mut self) -> Option<WriteMapping<'_, T>> {
+        let size = self.get_size();
+        self.alloc.as_mut().unwrap().map_write_unsynchronized(0 .. size)
+    }
+
     /// Copies the content of the buffer to another buffer.
     ///
     /// # Panic
@@ -347,6 +409,74 @@ impl<T> Buffer<[T]> where [T]: Content, T: Copy {
             })
     }
 
+    /// Builds a new buffer containing the elements produced by an iterator.
+    ///
+    /// The elements are written directly to a mapping of the buffer, without being collected
+    /// in a temporary container first.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the iterator doesn't produce exactly as many elements as its `len()`.
+    pub fn from_exact_iter<F: ?Sized, I>(facade: &F, iter: I, ty: BufferType, mode: BufferMode)
+                                         -> Result<Buffer<[T]>, BufferCreationError>
+                                         where F: Facade, I: IntoIterator<Item = T>,
+                                               I::IntoIter: ExactSizeIterator
+    {
+        let iter = iter.into_iter();
+        let len = iter.len();
+        Buffer::from_iter_impl(facade, iter, len, ty, mode)
+    }
+
+    /// Builds a new buffer containing the elements produced by an iterator.
+    ///
+    /// If the iterator knows exactly how many elements it will produce (according to its
+    /// `size_hint`), the elements are written directly to a mapping of the buffer. Otherwise
+    /// they are first collected in a `Vec`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the lower and upper bounds of the `size_hint` of the iterator are equal but the
+    /// iterator doesn't produce exactly this number of elements.
+    pub fn from_iter<F: ?Sized, I>(facade: &F, iter: I, ty: BufferType, mode: BufferMode)
+                                   -> Result<Buffer<[T]>, BufferCreationError>
+                                   where F: Facade, I: IntoIterator<Item = T>
+    {
+        let iter = iter.into_iter();
+
+        match iter.size_hint() {
+            (min, Some(max)) if min == max => Buffer::from_iter_impl(facade, iter, min, ty, mode),
+            _ => {
+                let data = iter.collect::<Vec<_>>();
+                Buffer::new(facade, &data[..], ty, mode)
+            },
+        }
+    }
+
+    fn from_iter_impl<F: ?Sized, I>(facade: &F, iter: I, len: usize, ty: BufferType,
+                                    mode: BufferMode)
+                                    -> Result<Buffer<[T]>, BufferCreationError>
+                                    where F: Facade, I: Iterator<Item = T>
+    {
+        let mut buffer = Buffer::empty_array(facade, ty, len, mode)?;
+
+        if len != 0 {
+            let mut mapping = buffer.map_write();
+            let mut written = 0;
+
+            for value in iter {
+                assert!(written < len, "The iterator produced more elements than expected");
+                mapping.set(written, value);
+                written += 1;
+            }
+
+            assert_eq!(written, len, "The iterator produced less elements than expected");
+        } else {
+            assert!(iter.count() == 0, "The iterator produced more elements than expected");
+        }
+
+        Ok(buffer)
+    }
+
     /// Returns the number of elements in this buffer.
     #[inline]
     pub fn len(&self) -> usize {
@@ -388,6 +518,30 @@ impl<T: ?Sized> fmt::Debug for Buffer<T> where T: Content {
     }
 }
 
+impl<T: ?Sized> Transferable for Buffer<T> where T: Content {
+    type Detached = DetachedBuffer;
+
+    fn detach(mut self) -> DetachedBuffer {
+        let alloc = self.alloc.take().unwrap();
+        let mut fence = self.fence.take().unwrap();
+
+        // the fence of the transfer replaces the fences of the buffer
+        fence.clean(&mut alloc.get_context().make_current());
+        alloc.detach()
+    }
+
+    #[inline]
+    unsafe fn attach<F: ?Sized>(facade: &F, detached: DetachedBuffer) -> Buffer<T>
+                                where F: Facade
+    {
+        Buffer {
+            alloc: Some(Alloc::attach(facade, detached)),
+            fence: Some(Fences::new()),
+            marker: PhantomData,
+        }
+    }
+}
+
 impl<T: ?Sized> Drop for Buffer<T> where T: Content {
     #[inline]
     fn drop(&mut self) {
@@ -460,6 +614,12 @@ impl<T: ?Sized> BufferExt for Buffer<T> where T: Content {
         alloc.prepare_and_bind_for_draw_indirect(ctxt);
     }
 
+    #[inline]
+    fn prepare_and_bind_for_parameter(&self, ctxt: &mut CommandContext<'_>) {
+        let alloc = self.alloc.as_ref().unwrap();
+        alloc.prepare_and_bind_for_parameter(ctxt);
+    }
+
     #[inline]
     fn prepare_and_bind_for_dispatch_indirect(&self, ctxt: &mut CommandContext<'_>) {
         let alloc = self.alloc.as_ref().unwrap();
@@ -514,6 +674,12 @@ impl<'a, T: ?Sized> BufferSlice<'a, T> where T: Content + 'a {
         self.alloc.get_context()
     }
 
+    /// Returns true if this slice covers the whole buffer.
+    #[inline]
+    pub(crate) fn is_whole_buffer(&self) -> bool {
+        self.bytes_start == 0 && self.bytes_end == self.alloc.get_size()
+    }
+
     /// Uploads some data in this buffer.
     ///
     /// # Implementation
@@ -535,6 +701,22 @@ impl<'a, T: ?Sized> BufferSlice<'a, T> where T: Content + 'a {
         unsafe { self.alloc.upload(self.bytes_start, data); }
     }
 
+    /// Invalidates the content of the slice, then uploads some data in it.
+    ///
+    /// This is the same as calling `invalidate` then `write`. If the backend supports
+    /// `glInvalidateBufferSubData`, the driver doesn't need to preserve the old content and can
+    /// avoid waiting for the GPU.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `data` is different from the length of this slice.
+    pub fn write_discard(&self, data: &T) {
+        assert_eq!(mem::size_of_val(data), self.get_size());
+
+        self.invalidate();
+        self.write(data);
+    }
+
     /// Invalidates the content of the slice. The data becomes undefined.
     ///
     /// This operation is a no-op if the backend doesn't support it and for persistent-mapped
@@ -775,6 +957,11 @@ impl<'a, T: ?Sized> BufferExt for BufferSlice<'a, T> where T: Content {
         self.alloc.prepare_and_bind_for_draw_indirect(ctxt);
     }
 
+    #[inline]
+    fn prepare_and_bind_for_parameter(&self, ctxt: &mut CommandContext<'_>) {
+        self.alloc.prepare_and_bind_for_parameter(ctxt);
+    }
+
     #[inline]
     fn prepare_and_bind_for_dispatch_indirect(&self, ctxt: &mut CommandContext<'_>) {
         self.alloc.prepare_and_bind_for_dispatch_indirect(ctxt);
@@ -782,17 +969,17 @@ impl<'a, T: ?Sized> BufferExt for BufferSlice<'a, T> where T: Content {
 
     #[inline]
     fn prepare_and_bind_for_uniform(&self, ctxt: &mut CommandContext<'_>, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_uniform(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.prepare_and_bind_for_uniform(ctxt, index, self.bytes_start .. self.bytes_end);
     }
 
     #[inline]
     fn prepare_and_bind_for_shared_storage(&self, ctxt: &mut CommandContext<'_>, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_shared_storage(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.prepare_and_bind_for_shared_storage(ctxt, index, self.bytes_start .. self.bytes_end);
     }
 
     #[inline]
     fn prepare_and_bind_for_atomic_counter(&self, ctxt: &mut CommandContext<'_>, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_atomic_counter(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.prepare_and_bind_for_atomic_counter(ctxt, index, self.bytes_start .. self.bytes_end);
     }
 
     #[inline]
@@ -869,6 +1056,19 @@ impl<'a, T: ?Sized> BufferMutSlice<'a, T> where T: Content + 'a {
         unsafe { self.alloc.map_write(self.bytes_start .. self.bytes_end) }
     }
 
+    /// Maps the buffer in memory for writing only, without waiting for the GPU to stop using it.
+    ///
+    /// See `Buffer::map_write_unsynchronized`.
+    ///
+    /// # Safety
+    ///
+    /// The GPU must not be reading from or writing to this slice of the buffer.
+    ///
+    #[inline]
+    pub unsafe fn map_write_unsynchronized(self) -> Option<WriteMapping<'a, T>> {
+        self.alloc.map_write_unsynchronized(self.bytes_start .. self.bytes_end)
+    }
+
     /// Uploads some data in this buffer.
     ///
     /// # Implementation
@@ -1088,6 +1288,21 @@ impl BufferAny {
         }
     }
 
+    /// Turns the buffer back into a typed buffer, without checking the type.
+    #[inline]
+    pub unsafe fn into_typed<T: ?Sized + Content>(self) -> Buffer<T> {
+        assert_eq!(<T as Content>::get_elements_size(), self.elements_size);
+
+        // we can't move out of `self` because of the destructor
+        let this = mem::ManuallyDrop::new(self);
+
+        Buffer {
+            alloc: Some(ptr::read(&this.alloc)),
+            fence: Some(ptr::read(&this.fence)),
+            marker: PhantomData,
+        }
+    }
+
     /// Builds a typed slice containing the whole subbuffer, without checking the type.
     #[inline]
     pub unsafe fn as_typed_slice<T: ?Sized + Content>(&self) -> BufferSlice<'_, T> {
@@ -1237,6 +1452,11 @@ impl BufferExt for BufferAny {
         self.alloc.prepare_and_bind_for_draw_indirect(ctxt);
     }
 
+    #[inline]
+    fn prepare_and_bind_for_parameter(&self, ctxt: &mut CommandContext<'_>) {
+        self.alloc.prepare_and_bind_for_parameter(ctxt);
+    }
+
     #[inline]
     fn prepare_and_bind_for_dispatch_indirect(&self, ctxt: &mut CommandContext<'_>) {
         self.alloc.prepare_and_bind_for_dispatch_indirect(ctxt);
@@ -1312,6 +1532,22 @@ impl<'a> BufferAnySlice<'a> {
         self.alloc.invalidate(self.bytes_start, self.get_size());
     }
 
+    /// Must be called when the slice is about to be accessed through an image unit.
+    #[inline]
+    pub(crate) fn prepare_for_image_unit(&self, ctxt: &mut CommandContext<'_>, write: bool) {
+        self.alloc.prepare_for_image_unit(ctxt, write);
+    }
+
+    /// Reads the content of the slice as raw bytes.
+    pub(crate) fn read_bytes(&self) -> Result<Vec<u8>, ReadError> {
+        self.fence.wait(&mut self.alloc.get_context().make_current(),
+                        self.bytes_start .. self.bytes_end);
+
+        unsafe {
+            self.alloc.read::<[u8]>(self.bytes_start .. self.bytes_end)
+        }
+    }
+
     /// Returns the context corresponding to this buffer.
     #[inline]
     pub fn get_context(&self) -> &Rc<Context> {
@@ -1393,6 +1629,11 @@ impl<'a> BufferExt for BufferAnySlice<'a> {
         self.alloc.prepare_and_bind_for_draw_indirect(ctxt);
     }
 
+    #[inline]
+    fn prepare_and_bind_for_parameter(&self, ctxt: &mut CommandContext<'_>) {
+        self.alloc.prepare_and_bind_for_parameter(ctxt);
+    }
+
     #[inline]
     fn prepare_and_bind_for_dispatch_indirect(&self, ctxt: &mut CommandContext<'_>) {
         self.alloc.prepare_and_bind_for_dispatch_indirect(ctxt);
@@ -1400,17 +1641,17 @@ impl<'a> BufferExt for BufferAnySlice<'a> {
 
     #[inline]
     fn prepare_and_bind_for_uniform(&self, ctxt: &mut CommandContext<'_>, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_uniform(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.prepare_and_bind_for_uniform(ctxt, index, self.bytes_start .. self.bytes_end);
     }
 
     #[inline]
     fn prepare_and_bind_for_shared_storage(&self, ctxt: &mut CommandContext<'_>, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_shared_storage(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.prepare_and_bind_for_shared_storage(ctxt, index, self.bytes_start .. self.bytes_end);
     }
 
     #[inline]
     fn prepare_and_bind_for_atomic_counter(&self, ctxt: &mut CommandContext<'_>, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_atomic_counter(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.prepare_and_bind_for_atomic_counter(ctxt, index, self.bytes_start .. self.bytes_end);
     }
 
     #[inline]
@@ -0,0 +1,124 @@
+/*!
+Records the OpenGL calls issued by glium.
+
+This module is only available with the `gl_call_log` feature. It is meant to be used by the
+tests of glium and of libraries built on top of it, in order to check that a change (for
+example to the state cache) doesn't modify the sequence of commands sent to the driver.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+# use glium::Surface;
+glium::call_log::start_recording();
+
+let mut frame = display.draw();
+frame.clear_color(0.0, 0.0, 0.0, 1.0);
+frame.finish().unwrap();
+
+let frames = glium::call_log::stop_recording();
+assert!(frames[0].iter().any(|call| call.name == "Clear"));
+```
+
+The calls are recorded per thread. Swapping the buffers of a context ends the current frame.
+
+Parameters are recorded with their `Debug` representation, except pointers which are
+replaced with `<ptr>` since their value changes between runs.
+*/
+use std::cell::{Cell, RefCell};
+use std::fmt;
+
+/// An OpenGL call issued by glium.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GlCall {
+    /// Name of the function, without the `gl` prefix. For example `DrawArrays`.
+    pub name: &'static str,
+
+    /// The parameters of the call.
+    pub parameters: Vec<String>,
+}
+
+impl fmt::Display for GlCall {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "gl{}({})", self.name, self.parameters.join(", "))
+    }
+}
+
+thread_local! {
+    static RECORDING: Cell<bool> = Cell::new(false);
+    static FRAMES: RefCell<Vec<Vec<GlCall>>> = RefCell::new(Vec::new());
+}
+
+/// Starts recording the OpenGL calls issued by the current thread.
+///
+/// Any call previously recorded and not yet retrieved is discarded.
+pub fn start_recording() {
+    FRAMES.with(|frames| {
+        let mut frames = frames.borrow_mut();
+        frames.clear();
+        frames.push(Vec::new());
+    });
+    RECORDING.with(|r| r.set(true));
+}
+
+/// Stops recording and returns the calls that have been recorded, grouped by frame.
+///
+/// The last element contains the calls issued since the last buffers swap. It is empty if the
+/// recording stopped right after swapping the buffers.
+pub fn stop_recording() -> Vec<Vec<GlCall>> {
+    RECORDING.with(|r| r.set(false));
+    take_frames()
+}
+
+/// Returns the calls that have been recorded so far, grouped by frame, and continues recording.
+pub fn take_frames() -> Vec<Vec<GlCall>> {
+    FRAMES.with(|frames| {
+        let mut frames = frames.borrow_mut();
+        let result = frames.drain(..).collect();
+        frames.push(Vec::new());
+        result
+    })
+}
+
+/// Returns true if the OpenGL calls issued by the current thread are being recorded.
+#[inline]
+pub fn is_recording() -> bool {
+    RECORDING.with(|r| r.get())
+}
+
+/// Called by the OpenGL bindings.
+#[doc(hidden)]
+pub fn record(name: &'static str, parameters: Vec<String>) {
+    FRAMES.with(|frames| {
+        if let Some(frame) = frames.borrow_mut().last_mut() {
+            frame.push(GlCall { name, parameters });
+        }
+    });
+}
+
+/// Called after swapping the buffers.
+pub(crate) fn end_frame() {
+    if is_recording() {
+        FRAMES.with(|frames| frames.borrow_mut().push(Vec::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames() {
+        start_recording();
+        record("Clear", vec!["16384".to_owned()]);
+        end_frame();
+        record("Flush", vec![]);
+
+        let frames = stop_recording();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0][0].to_string(), "glClear(16384)");
+        assert_eq!(frames[1][0].name, "Flush");
+
+        assert!(!is_recording());
+        end_frame();
+        assert_eq!(take_frames(), vec![Vec::new()]);
+    }
+}
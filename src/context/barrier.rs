@@ -0,0 +1,183 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::{BitAnd, BitOr, BitOrAssign};
+
+use crate::gl;
+use crate::context::GlState;
+
+/// A set of memory barriers, passed to `Context::memory_barrier`.
+///
+/// After a shader has written to a buffer or a texture through a shader storage block, an atomic
+/// counter or an image unit, the written values are not guaranteed to be visible to the
+/// operations that follow. A barrier makes the writes visible to the operations of a specific
+/// kind. Sets can be combined with the `|` operator.
+///
+/// Glium inserts the barriers automatically when a buffer that has been written by a shader is
+/// used again, but doesn't track the textures written through an `ImageUnit`. In that case you
+/// must insert the barriers yourself.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MemoryBarriers(gl::types::GLbitfield);
+
+impl MemoryBarriers {
+    /// Reading vertex attributes from buffers.
+    pub const VERTEX_ATTRIB_ARRAY: MemoryBarriers =
+        MemoryBarriers(gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT);
+
+    /// Reading indices from buffers.
+    pub const ELEMENT_ARRAY: MemoryBarriers = MemoryBarriers(gl::ELEMENT_ARRAY_BARRIER_BIT);
+
+    /// Reading uniform blocks from buffers.
+    pub const UNIFORM: MemoryBarriers = MemoryBarriers(gl::UNIFORM_BARRIER_BIT);
+
+    /// Sampling textures, including buffer textures.
+    pub const TEXTURE_FETCH: MemoryBarriers = MemoryBarriers(gl::TEXTURE_FETCH_BARRIER_BIT);
+
+    /// Accessing textures through image units.
+    pub const SHADER_IMAGE_ACCESS: MemoryBarriers =
+        MemoryBarriers(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT);
+
+    /// Reading indirect commands from buffers.
+    pub const COMMAND: MemoryBarriers = MemoryBarriers(gl::COMMAND_BARRIER_BIT);
+
+    /// Reading and writing pixel buffers.
+    pub const PIXEL_BUFFER: MemoryBarriers = MemoryBarriers(gl::PIXEL_BUFFER_BARRIER_BIT);
+
+    /// Reading and writing textures from the CPU, or copying them.
+    pub const TEXTURE_UPDATE: MemoryBarriers = MemoryBarriers(gl::TEXTURE_UPDATE_BARRIER_BIT);
+
+    /// Reading, writing, mapping and copying buffers.
+    pub const BUFFER_UPDATE: MemoryBarriers = MemoryBarriers(gl::BUFFER_UPDATE_BARRIER_BIT);
+
+    /// Drawing to, reading from, clearing or blitting framebuffers.
+    pub const FRAMEBUFFER: MemoryBarriers = MemoryBarriers(gl::FRAMEBUFFER_BARRIER_BIT);
+
+    /// Writing transform feedback buffers.
+    pub const TRANSFORM_FEEDBACK: MemoryBarriers =
+        MemoryBarriers(gl::TRANSFORM_FEEDBACK_BARRIER_BIT);
+
+    /// Accessing atomic counters.
+    pub const ATOMIC_COUNTER: MemoryBarriers = MemoryBarriers(gl::ATOMIC_COUNTER_BARRIER_BIT);
+
+    /// Accessing shader storage blocks.
+    pub const SHADER_STORAGE: MemoryBarriers = MemoryBarriers(gl::SHADER_STORAGE_BARRIER_BIT);
+
+    /// Writing the results of queries to buffers.
+    pub const QUERY_BUFFER: MemoryBarriers = MemoryBarriers(gl::QUERY_BUFFER_BARRIER_BIT);
+
+    /// All the barriers.
+    pub const ALL: MemoryBarriers = MemoryBarriers(gl::ALL_BARRIER_BITS);
+
+    /// The barriers that can be passed to `Context::memory_barrier_by_region`.
+    pub const BY_REGION: MemoryBarriers =
+        MemoryBarriers(gl::ATOMIC_COUNTER_BARRIER_BIT | gl::FRAMEBUFFER_BARRIER_BIT |
+                       gl::SHADER_IMAGE_ACCESS_BARRIER_BIT | gl::SHADER_STORAGE_BARRIER_BIT |
+                       gl::TEXTURE_FETCH_BARRIER_BIT | gl::UNIFORM_BARRIER_BIT);
+
+    /// Returns an empty set.
+    #[inline]
+    pub fn empty() -> MemoryBarriers {
+        MemoryBarriers(0)
+    }
+
+    /// Returns true if the set is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns true if all the barriers of `other` are in this set.
+    #[inline]
+    pub fn contains(&self, other: MemoryBarriers) -> bool {
+        (self.0 & other.0) == other.0
+    }
+
+    /// Returns the value to pass to `glMemoryBarrier`.
+    #[inline]
+    pub fn bits(&self) -> gl::types::GLbitfield {
+        self.0
+    }
+}
+
+impl BitOr for MemoryBarriers {
+    type Output = MemoryBarriers;
+
+    #[inline]
+    fn bitor(self, other: MemoryBarriers) -> MemoryBarriers {
+        MemoryBarriers(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for MemoryBarriers {
+    #[inline]
+    fn bitor_assign(&mut self, other: MemoryBarriers) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitAnd for MemoryBarriers {
+    type Output = MemoryBarriers;
+
+    #[inline]
+    fn bitand(self, other: MemoryBarriers) -> MemoryBarriers {
+        MemoryBarriers(self.0 & other.0)
+    }
+}
+
+/// Records in the state that the barriers have been inserted, so that glium doesn't insert
+/// them again automatically for the writes that happened before.
+pub(super) fn record_barriers(state: &mut GlState, barriers: MemoryBarriers) {
+    let id = state.next_draw_call_id;
+    let fields = [
+        (MemoryBarriers::VERTEX_ATTRIB_ARRAY, &mut state.latest_memory_barrier_vertex_attrib_array),
+        (MemoryBarriers::ELEMENT_ARRAY, &mut state.latest_memory_barrier_element_array),
+        (MemoryBarriers::UNIFORM, &mut state.latest_memory_barrier_uniform),
+        (MemoryBarriers::TEXTURE_FETCH, &mut state.latest_memory_barrier_texture_fetch),
+        (MemoryBarriers::SHADER_IMAGE_ACCESS,
+         &mut state.latest_memory_barrier_shader_image_access),
+        (MemoryBarriers::COMMAND, &mut state.latest_memory_barrier_command),
+        (MemoryBarriers::PIXEL_BUFFER, &mut state.latest_memory_barrier_pixel_buffer),
+        (MemoryBarriers::TEXTURE_UPDATE, &mut state.latest_memory_barrier_texture_update),
+        (MemoryBarriers::BUFFER_UPDATE, &mut state.latest_memory_barrier_buffer_update),
+        (MemoryBarriers::FRAMEBUFFER, &mut state.latest_memory_barrier_framebuffer),
+        (MemoryBarriers::TRANSFORM_FEEDBACK, &mut state.latest_memory_barrier_transform_feedback),
+        (MemoryBarriers::ATOMIC_COUNTER, &mut state.latest_memory_barrier_atomic_counter),
+        (MemoryBarriers::SHADER_STORAGE, &mut state.latest_memory_barrier_shader_storage),
+        (MemoryBarriers::QUERY_BUFFER, &mut state.latest_memory_barrier_query_buffer),
+    ];
+
+    for (barrier, latest) in fields {
+        if barriers.contains(barrier) {
+            *latest = id;
+        }
+    }
+}
+
+/// Error that happens when memory barriers are not supported by the backend.
+#[derive(Copy, Clone, Debug)]
+pub struct MemoryBarriersNotSupportedError;
+
+impl fmt::Display for MemoryBarriersNotSupportedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "Memory barriers are not supported by the backend")
+    }
+}
+
+impl Error for MemoryBarriersNotSupportedError {}
+
+#[cfg(test)]
+mod tests {
+    use super::MemoryBarriers;
+
+    #[test]
+    fn combine() {
+        let barriers = MemoryBarriers::SHADER_STORAGE | MemoryBarriers::TEXTURE_FETCH;
+        assert!(barriers.contains(MemoryBarriers::SHADER_STORAGE));
+        assert!(barriers.contains(MemoryBarriers::TEXTURE_FETCH));
+        assert!(!barriers.contains(MemoryBarriers::COMMAND));
+        assert!(MemoryBarriers::ALL.contains(barriers));
+        assert!(MemoryBarriers::BY_REGION.contains(barriers));
+        assert!((barriers & MemoryBarriers::COMMAND).is_empty());
+        assert!(MemoryBarriers::default().is_empty());
+    }
+}
@@ -73,12 +73,18 @@ pub struct Capabilities {
     /// True if the default framebuffer is in sRGB.
     pub srgb: bool,
 
+    /// True if the color buffer of the default framebuffer has a floating-point format.
+    pub float_color_buffer: bool,
+
     /// Number of bits in the default framebuffer's depth buffer
     pub depth_bits: Option<u16>,
 
     /// Number of bits in the default framebuffer's stencil buffer
     pub stencil_bits: Option<u16>,
 
+    /// Number of samples of the default framebuffer, or `None` if it isn't multisampled.
+    pub samples: Option<u16>,
+
     /// Informations about formats when used to create textures.
     pub internal_formats_textures: HashMap<TextureFormat, FormatInfos, BuildHasherDefault<FnvHasher>>,
 
@@ -90,6 +96,10 @@ pub struct Capabilities {
     /// `glActiveTexture` must be between `GL_TEXTURE0` and `GL_TEXTURE0` + this value - 1.
     pub max_combined_texture_image_units: gl::types::GLint,
 
+    /// Maximum number of image units that can be bound to a program. `None` if image load/store
+    /// is not supported.
+    pub max_image_units: Option<gl::types::GLint>,
+
     /// Maximum value for `GL_TEXTURE_MAX_ANISOTROPY_EXT​`.
     ///
     /// `None` if the extension is not supported by the hardware.
@@ -101,30 +111,85 @@ pub struct Capabilities {
     /// Maximum size of a buffer texture. `None` if this is not supported.
     pub max_texture_buffer_size: Option<gl::types::GLint>,
 
+    /// Alignment in bytes of the offset of a buffer texture that only covers a range of its
+    /// buffer. `None` if ranged buffer textures are not supported.
+    pub texture_buffer_offset_alignment: Option<usize>,
+
     /// Maximum width and height of `glViewport`.
     pub max_viewport_dims: (gl::types::GLint, gl::types::GLint),
 
+    /// Maximum number of viewports that can be used with `DrawParameters::viewports`. `1` if
+    /// viewport arrays are not supported.
+    pub max_viewports: gl::types::GLint,
+
+    /// Maximum number of views of a multiview framebuffer. `1` if multiview rendering is not
+    /// supported.
+    pub max_views: gl::types::GLint,
+
     /// Maximum number of elements that can be passed with `glDrawBuffers`.
     pub max_draw_buffers: gl::types::GLint,
 
+    /// Maximum number of `gl_ClipDistance` values that can be enabled. `0` if clip distances
+    /// are not supported.
+    pub max_clip_distances: gl::types::GLint,
+
     /// Maximum number of vertices per patch. `None` if tessellation is not supported.
     pub max_patch_vertices: Option<gl::types::GLint>,
 
+    /// Maximum number of work groups that can be launched by `Surface::draw_mesh_tasks`.
+    /// `None` if `GL_NV_mesh_shader` is not supported.
+    ///
+    /// The limits of `GL_EXT_mesh_shader` can't be queried yet, so they are unknown if it is the
+    /// only mesh shader extension. This is also the case of the two values below.
+    pub max_draw_mesh_tasks_count: Option<gl::types::GLint>,
+
+    /// Maximum number of vertices that a mesh shader can output. `None` if
+    /// `GL_NV_mesh_shader` is not supported.
+    pub max_mesh_output_vertices: Option<gl::types::GLint>,
+
+    /// Maximum number of primitives that a mesh shader can output. `None` if
+    /// `GL_NV_mesh_shader` is not supported.
+    pub max_mesh_output_primitives: Option<gl::types::GLint>,
+
     /// Number of available buffer bind points for `GL_ATOMIC_COUNTER_BUFFER`.
     pub max_indexed_atomic_counter_buffer: gl::types::GLint,
 
     /// Number of available buffer bind points for `GL_SHADER_STORAGE_BUFFER`.
     pub max_indexed_shader_storage_buffer: gl::types::GLint,
 
+    /// Alignment in bytes of the offset of a buffer bound to a `GL_SHADER_STORAGE_BUFFER` bind
+    /// point. `None` if shader storage buffers are not supported.
+    pub shader_storage_buffer_offset_alignment: Option<usize>,
+
     /// Number of available buffer bind points for `GL_TRANSFORM_FEEDBACK_BUFFER`.
     pub max_indexed_transform_feedback_buffer: gl::types::GLint,
 
     /// Number of available buffer bind points for `GL_UNIFORM_BUFFER`.
     pub max_indexed_uniform_buffer: gl::types::GLint,
 
+    /// Alignment in bytes of the offset of a buffer bound to a `GL_UNIFORM_BUFFER` bind point.
+    /// `None` if uniform buffers are not supported.
+    pub uniform_buffer_offset_alignment: Option<usize>,
+
+    /// Maximum size in bytes of a uniform block. `None` if uniform buffers are not supported.
+    pub max_uniform_block_size: Option<gl::types::GLint>,
+
     /// Number of work groups for compute shaders.
     pub max_compute_work_group_count: (gl::types::GLint, gl::types::GLint, gl::types::GLint),
 
+    /// Maximum value of `local_size_x`, `local_size_y` and `local_size_z` in compute shaders.
+    /// `(0, 0, 0)` if compute shaders are not supported.
+    pub max_compute_work_group_size: (gl::types::GLint, gl::types::GLint, gl::types::GLint),
+
+    /// Maximum number of invocations in a work group of a compute shader, in other words the
+    /// maximum value of `local_size_x * local_size_y * local_size_z`. `0` if compute shaders
+    /// are not supported.
+    pub max_compute_work_group_invocations: gl::types::GLint,
+
+    /// Maximum size in bytes of the `shared` variables of a compute shader. `0` if compute
+    /// shaders are not supported.
+    pub max_compute_shared_memory_size: gl::types::GLint,
+
     /// Maximum number of color attachment bind points.
     pub max_color_attachments: gl::types::GLint,
 
@@ -317,6 +382,22 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        float_color_buffer: {
+            let mut value = 0;
+
+            if version >= &Version(Api::Gl, 3, 0) {
+                gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, gl::FRONT_LEFT,
+                                                       gl::FRAMEBUFFER_ATTACHMENT_COMPONENT_TYPE,
+                                                       &mut value);
+            } else if version >= &Version(Api::GlEs, 3, 0) {
+                gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, gl::BACK,
+                                                       gl::FRAMEBUFFER_ATTACHMENT_COMPONENT_TYPE,
+                                                       &mut value);
+            }
+
+            value as gl::types::GLenum == gl::FLOAT
+        },
+
         depth_bits: {
             let mut value = 0;
 
@@ -383,6 +464,16 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        samples: {
+            let mut value = 0;
+            gl.GetIntegerv(gl::SAMPLES, &mut value);
+
+            match value {
+                0 => None,
+                v => Some(v as u16),
+            }
+        },
+
         internal_formats_textures: get_internal_formats(gl, version, extensions, false),
         internal_formats_renderbuffers: get_internal_formats(gl, version, extensions, true),
 
@@ -399,6 +490,21 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             val
         },
 
+        max_image_units: {
+            if version >= &Version(Api::Gl, 4, 2) || version >= &Version(Api::GlEs, 3, 1) ||
+               extensions.gl_arb_shader_image_load_store
+            {
+                Some({
+                    let mut val = 0;
+                    gl.GetIntegerv(gl::MAX_IMAGE_UNITS, &mut val);
+                    val
+                })
+
+            } else {
+                None
+            }
+        },
+
         max_texture_max_anisotropy: if !extensions.gl_ext_texture_filter_anisotropic {
             None
 
@@ -419,7 +525,7 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
         max_texture_buffer_size: {
             if version >= &Version(Api::Gl, 3, 0) || extensions.gl_arb_texture_buffer_object ||
                extensions.gl_ext_texture_buffer_object || extensions.gl_oes_texture_buffer ||
-               extensions.gl_ext_texture_buffer
+               extensions.gl_ext_texture_buffer || version >= &Version(Api::GlEs, 3, 2)
             {
                 Some({
                     let mut val = 0;
@@ -432,12 +538,50 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        texture_buffer_offset_alignment: {
+            if version >= &Version(Api::Gl, 4, 3) || version >= &Version(Api::GlEs, 3, 2) ||
+               extensions.gl_arb_texture_buffer_range || extensions.gl_oes_texture_buffer
+            {
+                Some({
+                    let mut val = 0;
+                    gl.GetIntegerv(gl::TEXTURE_BUFFER_OFFSET_ALIGNMENT, &mut val);
+                    val as usize
+                })
+
+            } else {
+                None
+            }
+        },
+
         max_viewport_dims: {
             let mut val: [gl::types::GLint; 2] = [ 0, 0 ];
             gl.GetIntegerv(gl::MAX_VIEWPORT_DIMS, val.as_mut_ptr());
             (val[0], val[1])
         },
 
+        max_viewports: {
+            // the value of `GL_MAX_VIEWPORTS_OES` is the same
+            if version >= &Version(Api::Gl, 4, 1) || extensions.gl_arb_viewport_array ||
+                extensions.gl_oes_viewport_array
+            {
+                let mut val = 1;
+                gl.GetIntegerv(gl::MAX_VIEWPORTS, &mut val);
+                val
+            } else {
+                1
+            }
+        },
+
+        max_views: {
+            if extensions.gl_ovr_multiview {
+                let mut val = 1;
+                gl.GetIntegerv(gl::MAX_VIEWS_OVR, &mut val);
+                val
+            } else {
+                1
+            }
+        },
+
         max_draw_buffers: {
             if version >= &Version(Api::Gl, 2, 0) ||
                 version >= &Version(Api::GlEs, 3, 0) ||
@@ -451,6 +595,17 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        max_clip_distances: {
+            // the value of `GL_MAX_CLIP_DISTANCES_EXT` is the same
+            if version.0 == Api::Gl || extensions.gl_ext_clip_cull_distance {
+                let mut val = 0;
+                gl.GetIntegerv(gl::MAX_CLIP_DISTANCES, &mut val);
+                val
+            } else {
+                0
+            }
+        },
+
         max_patch_vertices: if version >= &Version(Api::Gl, 4, 0) ||
             extensions.gl_arb_tessellation_shader
         {
@@ -464,6 +619,31 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             None
         },
 
+        // the limits of `GL_EXT_mesh_shader` aren't in the bindings
+        max_draw_mesh_tasks_count: if extensions.gl_nv_mesh_shader {
+            let mut val = 0;
+            gl.GetIntegerv(gl::MAX_DRAW_MESH_TASKS_COUNT_NV, &mut val);
+            Some(val)
+        } else {
+            None
+        },
+
+        max_mesh_output_vertices: if extensions.gl_nv_mesh_shader {
+            let mut val = 0;
+            gl.GetIntegerv(gl::MAX_MESH_OUTPUT_VERTICES_NV, &mut val);
+            Some(val)
+        } else {
+            None
+        },
+
+        max_mesh_output_primitives: if extensions.gl_nv_mesh_shader {
+            let mut val = 0;
+            gl.GetIntegerv(gl::MAX_MESH_OUTPUT_PRIMITIVES_NV, &mut val);
+            Some(val)
+        } else {
+            None
+        },
+
         max_indexed_atomic_counter_buffer: if version >= &Version(Api::Gl, 4, 2) {      // TODO: ARB_shader_atomic_counters   // TODO: GLES
             let mut val = 0;
             gl.GetIntegerv(gl::MAX_ATOMIC_COUNTER_BUFFER_BINDINGS, &mut val);
@@ -482,6 +662,18 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        shader_storage_buffer_offset_alignment: {
+            if version >= &Version(Api::Gl, 4, 3) || version >= &Version(Api::GlEs, 3, 1) ||
+               extensions.gl_arb_shader_storage_buffer_object
+            {
+                let mut val = 0;
+                gl.GetIntegerv(gl::SHADER_STORAGE_BUFFER_OFFSET_ALIGNMENT, &mut val);
+                Some(val as usize)
+            } else {
+                None
+            }
+        },
+
         max_indexed_transform_feedback_buffer: {
             if version >= &Version(Api::Gl, 4, 0) || extensions.gl_arb_transform_feedback3 {      // TODO: GLES
                 let mut val = 0;
@@ -506,6 +698,33 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        uniform_buffer_offset_alignment: {
+            if version >= &Version(Api::Gl, 3, 1) || version >= &Version(Api::GlEs, 3, 0) ||
+               extensions.gl_arb_uniform_buffer_object
+            {
+                let mut val = 0;
+                gl.GetIntegerv(gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT, &mut val);
+                Some(val as usize)
+            } else {
+                None
+            }
+        },
+
+        max_uniform_block_size: {
+            if version >= &Version(Api::Gl, 3, 1) || version >= &Version(Api::GlEs, 3, 0) ||
+               extensions.gl_arb_uniform_buffer_object
+            {
+                Some({
+                    let mut val = 0;
+                    gl.GetIntegerv(gl::MAX_UNIFORM_BLOCK_SIZE, &mut val);
+                    val
+                })
+
+            } else {
+                None
+            }
+        },
+
         max_compute_work_group_count: if version >= &Version(Api::Gl, 4, 3) ||
                                          version >= &Version(Api::GlEs, 3, 1) ||
                                          extensions.gl_arb_compute_shader
@@ -522,6 +741,44 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             (0, 0, 0)
         },
 
+        max_compute_work_group_size: if version >= &Version(Api::Gl, 4, 3) ||
+                                        version >= &Version(Api::GlEs, 3, 1) ||
+                                        extensions.gl_arb_compute_shader
+        {
+            let mut val1 = 0;
+            let mut val2 = 0;
+            let mut val3 = 0;
+            gl.GetIntegeri_v(gl::MAX_COMPUTE_WORK_GROUP_SIZE, 0, &mut val1);
+            gl.GetIntegeri_v(gl::MAX_COMPUTE_WORK_GROUP_SIZE, 1, &mut val2);
+            gl.GetIntegeri_v(gl::MAX_COMPUTE_WORK_GROUP_SIZE, 2, &mut val3);
+            (val1, val2, val3)
+
+        } else {
+            (0, 0, 0)
+        },
+
+        max_compute_work_group_invocations: if version >= &Version(Api::Gl, 4, 3) ||
+                                               version >= &Version(Api::GlEs, 3, 1) ||
+                                               extensions.gl_arb_compute_shader
+        {
+            let mut val = 0;
+            gl.GetIntegerv(gl::MAX_COMPUTE_WORK_GROUP_INVOCATIONS, &mut val);
+            val
+        } else {
+            0
+        },
+
+        max_compute_shared_memory_size: if version >= &Version(Api::Gl, 4, 3) ||
+                                           version >= &Version(Api::GlEs, 3, 1) ||
+                                           extensions.gl_arb_compute_shader
+        {
+            let mut val = 0;
+            gl.GetIntegerv(gl::MAX_COMPUTE_SHARED_MEMORY_SIZE, &mut val);
+            val
+        } else {
+            0
+        },
+
         max_color_attachments: {
             if version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
                extensions.gl_arb_framebuffer_object || extensions.gl_ext_framebuffer_object ||
@@ -56,13 +56,17 @@ extensions! {
     "GL_APPLE_vertex_array_object" => gl_apple_vertex_array_object,
     "GL_ARB_bindless_texture" => gl_arb_bindless_texture,
     "GL_ARB_buffer_storage" => gl_arb_buffer_storage,
+    "GL_ARB_clear_texture" => gl_arb_clear_texture,
+    "GL_ARB_clip_control" => gl_arb_clip_control,
     "GL_ARB_compute_shader" => gl_arb_compute_shader,
     "GL_ARB_copy_buffer" => gl_arb_copy_buffer,
+    "GL_ARB_copy_image" => gl_arb_copy_image,
     "GL_ARB_debug_output" => gl_arb_debug_output,
     "GL_ARB_depth_clamp" => gl_arb_depth_clamp,
     "GL_ARB_depth_texture" => gl_arb_depth_texture,
     "GL_ARB_direct_state_access" => gl_arb_direct_state_access,
     "GL_ARB_draw_buffers" => gl_arb_draw_buffers,
+    "GL_ARB_draw_buffers_blend" => gl_arb_draw_buffers_blend,
     "GL_ARB_draw_elements_base_vertex" => gl_arb_draw_elements_base_vertex,
     "GL_ARB_compatibility" => gl_arb_compatibility,
     "GL_ARB_ES2_compatibility" => gl_arb_es2_compatibility,
@@ -82,25 +86,38 @@ extensions! {
     "GL_ARB_invalidate_subdata" => gl_arb_invalidate_subdata,
     "GL_ARB_occlusion_query" => gl_arb_occlusion_query,
     "GL_ARB_occlusion_query2" => gl_arb_occlusion_query2,
+    "GL_ARB_parallel_shader_compile" => gl_arb_parallel_shader_compile,
     "GL_ARB_pixel_buffer_object" => gl_arb_pixel_buffer_object,
+    "GL_ARB_polygon_offset_clamp" => gl_arb_polygon_offset_clamp,
     "GL_ARB_program_interface_query" => gl_arb_program_interface_query,
     "GL_ARB_query_buffer_object" => gl_arb_query_buffer_object,
+    "GL_ARB_indirect_parameters" => gl_arb_indirect_parameters,
     "GL_ARB_map_buffer_range" => gl_arb_map_buffer_range,
     "GL_ARB_multi_draw_indirect" => gl_arb_multi_draw_indirect,
     "GL_ARB_provoking_vertex" => gl_arb_provoking_vertex,
     "GL_ARB_robustness" => gl_arb_robustness,
     "GL_ARB_robust_buffer_access_behavior" => gl_arb_robust_buffer_access_behavior,
+    "GL_ARB_sample_locations" => gl_arb_sample_locations,
+    "GL_ARB_sample_shading" => gl_arb_sample_shading,
     "GL_ARB_sampler_objects" => gl_arb_sampler_objects,
     "GL_ARB_seamless_cube_map" => gl_arb_seamless_cube_map,
+    "GL_ARB_seamless_cubemap_per_texture" => gl_arb_seamless_cubemap_per_texture,
+    "GL_ARB_separate_shader_objects" => gl_arb_separate_shader_objects,
     "GL_ARB_shader_atomic_counters" => gl_arb_shader_atomic_counters,
+    "GL_ARB_shader_draw_parameters" => gl_arb_shader_draw_parameters,
     "GL_ARB_shader_image_load_store" => gl_arb_shader_image_load_store,
     "GL_ARB_shader_objects" => gl_arb_shader_objects,
     "GL_ARB_shader_storage_buffer_object" => gl_arb_shader_storage_buffer_object,
     "GL_ARB_shader_subroutine" => gl_arb_shader_subroutine,
+    "GL_ARB_shading_language_include" => gl_arb_shading_language_include,
+    "GL_ARB_sparse_texture" => gl_arb_sparse_texture,
+    "GL_ARB_stencil_texturing" => gl_arb_stencil_texturing,
     "GL_ARB_sync" => gl_arb_sync,
     "GL_ARB_tessellation_shader" => gl_arb_tessellation_shader,
+    "GL_ARB_texture_barrier" => gl_arb_texture_barrier,
     "GL_ARB_texture_buffer_object" => gl_arb_texture_buffer_object,
     "GL_ARB_texture_buffer_object_rgb32" => gl_arb_texture_buffer_object_rgb32,
+    "GL_ARB_texture_buffer_range" => gl_arb_texture_buffer_range,
     "GL_ARB_texture_compression_bptc" => gl_arb_texture_compression_bptc,
     "GL_ARB_texture_cube_map" => gl_arb_texture_cube_map,
     "GL_ARB_texture_cube_map_array" => gl_arb_texture_cube_map_array,
@@ -111,23 +128,31 @@ extensions! {
     "GL_ARB_texture_rgb10_a2ui" => gl_arb_texture_rgb10_a2ui,
     "GL_ARB_texture_stencil8" => gl_arb_texture_stencil8,
     "GL_ARB_texture_storage" => gl_arb_texture_storage,
+    "GL_ARB_texture_swizzle" => gl_arb_texture_swizzle,
+    "GL_ARB_texture_view" => gl_arb_texture_view,
     "GL_ARB_timer_query" => gl_arb_timer_query,
     "GL_ARB_transform_feedback3" => gl_arb_transform_feedback3,
     "GL_ARB_uniform_buffer_object" => gl_arb_uniform_buffer_object,
     "GL_ARB_vertex_array_object" => gl_arb_vertex_array_object,
+    "GL_ARB_vertex_attrib_binding" => gl_arb_vertex_attrib_binding,
     "GL_ARB_vertex_buffer_object" => gl_arb_vertex_buffer_object,
     "GL_ARB_vertex_half_float" => gl_arb_vertex_half_float,
     "GL_ARB_vertex_shader" => gl_arb_vertex_shader,
     "GL_ARB_vertex_type_10f_11f_11f_rev" => gl_arb_vertex_type_10f_11f_11f_rev,
     "GL_ARB_vertex_type_2_10_10_10_rev" => gl_arb_vertex_type_2_10_10_10_rev,
+    "GL_ARB_viewport_array" => gl_arb_viewport_array,
     "GL_ARM_rgba8" => gl_arm_rgba8,
     "GL_ATI_meminfo" => gl_ati_meminfo,
     "GL_ATI_draw_buffers" => gl_ati_draw_buffers,
     "GL_ATI_texture_float" => gl_ati_texture_float,
     "GL_EXT_blend_minmax" => gl_ext_blend_minmax,
     "GL_EXT_buffer_storage" => gl_ext_buffer_storage,
+    "GL_EXT_clip_control" => gl_ext_clip_control,
+    "GL_EXT_clip_cull_distance" => gl_ext_clip_cull_distance,
     "GL_EXT_debug_marker" => gl_ext_debug_marker,
+    "GL_EXT_depth_bounds_test" => gl_ext_depth_bounds_test,
     "GL_EXT_direct_state_access" => gl_ext_direct_state_access,
+    "GL_EXT_discard_framebuffer" => gl_ext_discard_framebuffer,
     "GL_EXT_disjoint_timer_query" => gl_ext_disjoint_timer_query,
     "GL_EXT_framebuffer_blit" => gl_ext_framebuffer_blit,
     "GL_EXT_framebuffer_object" => gl_ext_framebuffer_object,
@@ -136,11 +161,13 @@ extensions! {
     "GL_EXT_geometry_shader" => gl_ext_geometry_shader,
     "GL_EXT_geometry_shader4" => gl_ext_geometry_shader4,
     "GL_EXT_gpu_shader4" => gl_ext_gpu_shader4,
+    "GL_EXT_mesh_shader" => gl_ext_mesh_shader,
     "GL_EXT_multi_draw_indirect" => gl_ext_multi_draw_indirect,
     "GL_EXT_multisampled_render_to_texture" => gl_ext_multisampled_render_to_texture,
     "GL_EXT_occlusion_query_boolean" => gl_ext_occlusion_query_boolean,
     "GL_EXT_packed_depth_stencil" => gl_ext_packed_depth_stencil,
     "GL_EXT_packed_float" => gl_ext_packed_float,
+    "GL_EXT_polygon_offset_clamp" => gl_ext_polygon_offset_clamp,
     "GL_EXT_primitive_bounding_box" => gl_ext_primitive_bounding_box,
     "GL_EXT_provoking_vertex" => gl_ext_provoking_vertex,
     "GL_EXT_robustness" => gl_ext_robustness,
@@ -149,6 +176,7 @@ extensions! {
     "GL_EXT_texture_array" => gl_ext_texture_array,
     "GL_EXT_texture_buffer" => gl_ext_texture_buffer,
     "GL_EXT_texture_buffer_object" => gl_ext_texture_buffer_object,
+    "GL_EXT_texture_compression_bptc" => gl_ext_texture_compression_bptc,
     "GL_EXT_texture_compression_s3tc" => gl_ext_texture_compression_s3tc,
     "GL_EXT_texture_cube_map" => gl_ext_texture_cube_map,
     "GL_EXT_texture_cube_map_array" => gl_ext_texture_cube_map_array,
@@ -156,13 +184,18 @@ extensions! {
     "GL_EXT_texture_integer" => gl_ext_texture_integer,
     "GL_EXT_texture_shared_exponent" => gl_ext_texture_shared_exponent,
     "GL_EXT_texture_snorm" => gl_ext_texture_snorm,
+    "GL_EXT_texture_swizzle" => gl_ext_texture_swizzle,
     "GL_EXT_texture_sRGB" => gl_ext_texture_srgb,
     "GL_EXT_transform_feedback" => gl_ext_transform_feedback,
     "GL_GREMEDY_string_marker" => gl_gremedy_string_marker,
+    "GL_KHR_blend_equation_advanced" => gl_khr_blend_equation_advanced,
+    "GL_KHR_blend_equation_advanced_coherent" => gl_khr_blend_equation_advanced_coherent,
     "GL_KHR_debug" => gl_khr_debug,
     "GL_KHR_context_flush_control" => gl_khr_context_flush_control,
+    "GL_KHR_parallel_shader_compile" => gl_khr_parallel_shader_compile,
     "GL_KHR_robustness" => gl_khr_robustness,
     "GL_KHR_robust_buffer_access_behavior" => gl_khr_robust_buffer_access_behavior,
+    "GL_KHR_texture_compression_astc_ldr" => gl_khr_texture_compression_astc_ldr,
     "GL_NV_fbo_color_attachments" => gl_nv_fbo_color_attachments,
     "GL_NV_conditional_render" => gl_nv_conditional_render,
     "GL_NV_copy_buffer" => gl_nv_copy_buffer,
@@ -170,13 +203,16 @@ extensions! {
     "GL_NV_framebuffer_multisample" => gl_nv_framebuffer_multisample,
     "GL_NV_half_float" => gl_nv_half_float,
     "GL_NV_internalformat_sample_query" => gl_nv_internalformat_sample_query,
+    "GL_NV_mesh_shader" => gl_nv_mesh_shader,
     "GL_NV_pixel_buffer_object" => gl_nv_pixel_buffer_object,
+    "GL_NV_sample_locations" => gl_nv_sample_locations,
     "GL_NV_read_depth" => gl_nv_read_depth,
     "GL_NV_read_stencil" => gl_nv_read_stencil,
     "GL_NV_read_depth_stencil" => gl_nv_read_depth_stencil,
     "GL_NV_shader_atomic_counters" => gl_nv_shader_atomic_counters,
     "GL_NV_shader_storage_buffer_object" => gl_nv_shader_storage_buffer_object,
     "GL_NV_texture_array" => gl_nv_texture_array,
+    "GL_NV_texture_barrier" => gl_nv_texture_barrier,
     "GL_NV_transform_feedback" => gl_nv_transform_feedback,
     "GL_NV_vertex_attrib_integer_64bit" => gl_nv_vertex_attrib_integer_64bit,
     "GL_NVX_gpu_memory_info" => gl_nvx_gpu_memory_info,
@@ -188,17 +224,22 @@ extensions! {
     "GL_OES_packed_depth_stencil" => gl_oes_packed_depth_stencil,
     "GL_OES_primitive_bounding_box" => gl_oes_primitive_bounding_box,
     "GL_OES_rgb8_rgba8" => gl_oes_rgb8_rgba8,
+    "GL_OES_sample_shading" => gl_oes_sample_shading,
     "GL_OES_stencil1" => gl_oes_stencil1,
     "GL_OES_stencil4" => gl_oes_stencil4,
     "GL_OES_tessellation_shader" => gl_oes_tessellation_shader,
     "GL_OES_texture_3D" => gl_oes_texture_3d,
     "GL_OES_texture_buffer" => gl_oes_texture_buffer,
+    "GL_OES_texture_compression_astc" => gl_oes_texture_compression_astc,
     "GL_OES_texture_cube_map_array" => gl_oes_texture_cube_map_array,
     "GL_OES_texture_stencil8" => gl_oes_texture_stencil8,
     "GL_OES_texture_storage_multisample_2d_array" => gl_oes_texture_storage_multisample_2d_array,
     "GL_OES_vertex_array_object" => gl_oes_vertex_array_object,
     "GL_OES_vertex_half_float" => gl_oes_vertex_half_float,
     "GL_OES_vertex_type_10_10_10_2" => gl_oes_vertex_type_10_10_10_2,
+    "GL_OES_viewport_array" => gl_oes_viewport_array,
+    "GL_OVR_multiview" => gl_ovr_multiview,
+    "GL_OVR_multiview2" => gl_ovr_multiview2,
 }
 
 /// Returns the list of all extension names supported by the OpenGL implementation.
@@ -7,17 +7,22 @@ use std::mem;
 use std::ptr;
 use std::str;
 use std::borrow::Cow;
-use std::cell::{Cell, RefCell, RefMut};
+use std::cell::{Cell, Ref, RefCell, RefMut};
 use std::marker::PhantomData;
 use std::ffi::CStr;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::os::raw;
+use std::task::Waker;
 use std::hash::BuildHasherDefault;
 
 use fnv::FnvHasher;
+use smallvec::SmallVec;
 
 use crate::IncompatibleOpenGl;
+use crate::Rect;
 use crate::SwapBuffersError;
+use crate::{SwapInterval, SwapIntervalError};
 use crate::CapabilitiesSource;
 use crate::ContextExt;
 use crate::backend::Backend;
@@ -28,15 +33,18 @@ use crate::version::Version;
 use crate::debug;
 use crate::fbo;
 use crate::ops;
+use crate::program;
 use crate::sampler_object;
 use crate::texture;
 use crate::uniforms;
 use crate::vertex_array_object;
 
+pub use self::barrier::{MemoryBarriers, MemoryBarriersNotSupportedError};
 pub use self::capabilities::{ReleaseBehavior, Capabilities, Profile};
 pub use self::extensions::ExtensionsList;
 pub use self::state::GlState;
 
+mod barrier;
 mod capabilities;
 mod extensions;
 mod state;
@@ -77,6 +85,15 @@ pub struct Context {
     /// be set to `false` in some situations, like compiling/linking shaders.
     report_debug_output_errors: Cell<bool>,
 
+    /// Whether or not glium should warn about textures used in the wrong color space.
+    color_space_validation: Cell<bool>,
+
+    /// Whether or not instancing should be emulated if the backend doesn't support it.
+    instancing_emulation: Cell<bool>,
+
+    /// When glium submits the commands queue to the GPU.
+    flush_policy: Cell<FlushPolicy>,
+
     /// We maintain a cache of FBOs.
     /// The `Option` is here in order to destroy the container. It must be filled at all time
     /// is a normal situation.
@@ -95,6 +112,69 @@ pub struct Context {
     /// List of images handles that are resident. We need to call `MakeImageHandleResidentARB`
     /// when rebuilding the context.
     resident_image_handles: RefCell<Vec<(gl::types::GLuint64, gl::types::GLenum)>>,
+
+    /// GLSL chunks that can be imported by the programs created with this context.
+    shader_modules: program::ShaderModuleRegistry,
+
+    /// Thread used by `Program::new_async` when parallel shader compilation isn't supported.
+    compiler_thread: RefCell<Option<program::CompilerThread>>,
+
+    /// Tasks waiting for the GPU, that are woken up after the buffers have been swapped.
+    pending_wakers: RefCell<Vec<Waker>>,
+
+    /// The contexts whose objects are shared with this one, including this one.
+    share_group: Rc<ShareGroup>,
+}
+
+/// Identifies a group of contexts whose objects are shared.
+///
+/// Contrary to the contexts themselves, it can be sent to other threads. See
+/// `Context::new_in_share_group`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ShareGroupId(usize);
+
+impl ShareGroupId {
+    /// Returns a new identifier that isn't used by any other group.
+    fn new() -> ShareGroupId {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        ShareGroupId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// List of contexts whose OpenGL contexts share their objects.
+struct ShareGroup {
+    id: ShareGroupId,
+
+    /// The contexts of the group that live on the same thread.
+    contexts: RefCell<Vec<Weak<Context>>>,
+
+    /// The context of the group that has been made current most recently. Its commands must be
+    /// flushed before another context of the group uses the objects that they modify.
+    current: Cell<*const Context>,
+}
+
+/// Describes when glium submits the commands queue to the GPU.
+///
+/// OpenGL commands are not executed immediately. Instead they are put in a queue that the driver
+/// submits to the GPU from time to time. Submitting often reduces the latency at the cost of
+/// throughput.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FlushPolicy {
+    /// glium never flushes the queue itself. The queue is submitted when the driver decides to,
+    /// when the buffers are swapped at the end of a frame, and when glium waits for the GPU, for
+    /// example before reading back the content of a buffer or of a texture.
+    ///
+    /// This is the default.
+    Implicit,
+
+    /// Same as `Implicit`, but glium also calls `glFlush()` after each draw and compute command,
+    /// so that the GPU starts working on them as soon as possible.
+    FlushAfterCommands,
+
+    /// Same as `Implicit`, but glium also calls `glFinish()` after swapping the buffers. The CPU
+    /// can't prepare a frame before the GPU has finished the previous one, which minimizes the
+    /// latency between the input and the display at the cost of throughput.
+    FinishAfterSwap,
 }
 
 /// This struct is a guard that is returned when you want to access the OpenGL backend.
@@ -134,6 +214,10 @@ pub struct CommandContext<'a> {
     /// List of image handles and their access that need to be made resident.
     pub resident_image_handles: RefMut<'a, Vec<(gl::types::GLuint64, gl::types::GLenum)>>,
 
+    /// The textures attached to the framebuffer of the draw command that is being prepared,
+    /// with their attached mipmap level. Sampling them during this command is an error.
+    pub framebuffer_textures: SmallVec<[(gl::types::GLuint, u32); 4]>,
+
     /// This marker is here to prevent `CommandContext` from implementing `Send`
     // TODO: use this when possible
     //impl<'a, 'b> !Send for CommandContext<'a, 'b> {}
@@ -159,6 +243,70 @@ impl Context {
         callback_behavior: DebugCallbackBehavior,
     ) -> Result<Rc<Context>, IncompatibleOpenGl>
         where B: Backend + 'static
+    {
+        Context::new_in_share_group(backend, check_current_context, callback_behavior,
+                                    ShareGroupId::new())
+    }
+
+    /// Builds a new context whose objects are shared with the contexts of a group that live on
+    /// other threads, for example to create resources in the background.
+    ///
+    /// Contrary to `new_shared`, glium doesn't maintain the caches of contexts that live on
+    /// different threads, and objects can only be moved between them with a
+    /// `transfer::TransferTicket`. Use `new_shared` for contexts that live on the same thread.
+    ///
+    /// The OpenGL context of the backend **must** have been created with its lists shared
+    /// with the OpenGL contexts of the group.
+    pub unsafe fn new_in_share_group<B>(
+        backend: B,
+        check_current_context: bool,
+        callback_behavior: DebugCallbackBehavior,
+        share_group: ShareGroupId,
+    ) -> Result<Rc<Context>, IncompatibleOpenGl>
+        where B: Backend + 'static
+    {
+        let share_group = Rc::new(ShareGroup {
+            id: share_group,
+            contexts: RefCell::new(Vec::new()),
+            current: Cell::new(ptr::null()),
+        });
+
+        Context::new_inner(backend, check_current_context, callback_behavior, share_group)
+    }
+
+    /// Builds a new context whose objects are shared with an existing one.
+    ///
+    /// The OpenGL context of the backend **must** have been created with its lists shared
+    /// with the OpenGL context of `shared_with`. Buffers, textures, renderbuffers and programs
+    /// created with any of the two contexts can then be used with the other one. Framebuffer
+    /// objects and vertex array objects aren't shared, and glium maintains them for each
+    /// context.
+    ///
+    /// glium flushes the commands of a context before switching to another context of the same
+    /// group, so that the modifications made by a context are visible to the others. For this
+    /// reason, `check_current_context` should be `true`.
+    pub unsafe fn new_shared<B>(
+        backend: B,
+        check_current_context: bool,
+        callback_behavior: DebugCallbackBehavior,
+        shared_with: &Context,
+    ) -> Result<Rc<Context>, IncompatibleOpenGl>
+        where B: Backend + 'static
+    {
+        // the other context may still have commands that create objects
+        shared_with.flush();
+
+        Context::new_inner(backend, check_current_context, callback_behavior,
+                           shared_with.share_group.clone())
+    }
+
+    unsafe fn new_inner<B>(
+        backend: B,
+        check_current_context: bool,
+        callback_behavior: DebugCallbackBehavior,
+        share_group: Rc<ShareGroup>,
+    ) -> Result<Rc<Context>, IncompatibleOpenGl>
+        where B: Backend + 'static
     {
         backend.make_current();
 
@@ -203,6 +351,9 @@ impl Context {
             capabilities,
             debug_callback,
             report_debug_output_errors,
+            color_space_validation: Cell::new(false),
+            instancing_emulation: Cell::new(false),
+            flush_policy: Cell::new(FlushPolicy::Implicit),
             backend: RefCell::new(Box::new(backend)),
             check_current_context,
             framebuffer_objects: Some(framebuffer_objects),
@@ -210,8 +361,15 @@ impl Context {
             samplers,
             resident_texture_handles,
             resident_image_handles,
+            shader_modules: program::ShaderModuleRegistry::new(),
+            compiler_thread: RefCell::new(None),
+            pending_wakers: RefCell::new(Vec::new()),
+            share_group,
         });
 
+        context.share_group.contexts.borrow_mut().push(Rc::downgrade(&context));
+        context.share_group.current.set(&*context);
+
         if context.debug_callback.is_some() {
             init_debug_callback(&context, synchronous);
         }
@@ -226,20 +384,87 @@ impl Context {
             /*assert!(::get_gl_error(&mut ctxt).is_none(),
                     "glium has triggered an OpenGL error during initialization. Please report \
                      this error: https://github.com/tomaka/glium/issues");*/
-            if ctxt.version >= &Version(Api::Gl, 3, 2) && ctxt.extensions.gl_arb_seamless_cube_map {
-                ctxt.gl.Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
-            }
         }
 
+        context.init_seamless_cube_maps();
+
         Ok(context)
     }
 
+    /// Enables seamless filtering of cubemaps for the whole context if it is supported, unless
+    /// it can be controlled per sampler.
+    fn init_seamless_cube_maps(&self) {
+        let ctxt = self.make_current();
+
+        // with `GL_ARB_seamless_cubemap_per_texture`, seamless filtering is a parameter of
+        // the samplers, but it is ignored if the global switch is enabled
+        let seamless_supported = ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                                 ctxt.extensions.gl_arb_seamless_cube_map;
+        if seamless_supported && !ctxt.extensions.gl_arb_seamless_cubemap_per_texture {
+            unsafe { ctxt.gl.Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS) };
+        }
+    }
+
     /// Calls `get_framebuffer_dimensions` on the backend object stored by this context.
     #[inline]
     pub fn get_framebuffer_dimensions(&self) -> (u32, u32) {
         self.backend.borrow().get_framebuffer_dimensions()
     }
 
+    /// Returns the number of color buffers of the surface, or `None` if the backend doesn't
+    /// know it.
+    ///
+    /// See `Backend::get_swapchain_image_count`.
+    #[inline]
+    pub fn get_swapchain_image_count(&self) -> Option<u32> {
+        self.backend.borrow().get_swapchain_image_count()
+    }
+
+    /// Returns the age of the back buffer, or `None` if the backend can't query it.
+    ///
+    /// The age is the number of frames since the current content of the back buffer was
+    /// presented, and `0` means that its content is undefined. Temporal techniques and
+    /// damage tracking can use it to know whether the previous frames are still available.
+    ///
+    /// See `Backend::get_buffer_age`.
+    #[inline]
+    pub fn get_buffer_age(&self) -> Option<u32> {
+        let backend = self.backend.borrow();
+        if self.check_current_context && !backend.is_current() {
+            unsafe { backend.make_current() };
+        }
+
+        backend.get_buffer_age()
+    }
+
+    /// Changes the number of vertical blanks to wait for when swapping the buffers.
+    ///
+    /// Contrary to the vsync option of the backend, this can be called at any time, for example
+    /// to let the user toggle vsync without recreating the window.
+    ///
+    /// See `Backend::set_swap_interval`.
+    pub fn set_swap_interval(&self, interval: SwapInterval) -> Result<(), SwapIntervalError> {
+        let backend = self.backend.borrow();
+        if self.check_current_context && !backend.is_current() {
+            unsafe { backend.make_current() };
+        }
+
+        backend.set_swap_interval(interval)
+    }
+
+    /// Returns true if the objects of this context are shared with `other`, in other words if
+    /// the two contexts are the same or if they belong to the same share group.
+    #[inline]
+    pub fn is_sharing_lists_with(&self, other: &Context) -> bool {
+        self.share_group.id == other.share_group.id
+    }
+
+    /// Returns the identifier of the group of contexts whose objects are shared with this one.
+    #[inline]
+    pub fn get_share_group_id(&self) -> ShareGroupId {
+        self.share_group.id
+    }
+
     /// Changes the OpenGL context associated with this context.
     ///
     /// The new context **must** have lists shared with the old one.
@@ -272,11 +497,27 @@ impl Context {
             self.gl.MakeImageHandleResidentARB(image, access);
         }
 
+        self.init_seamless_cube_maps();
+
         Ok(())
     }
 
     /// Swaps the buffers in the backend.
+    #[inline]
     pub fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        self.swap_buffers_impl(None)
+    }
+
+    /// Swaps the buffers in the backend, indicating that only the given rectangles have
+    /// changed since the previous frame.
+    ///
+    /// See `Backend::swap_buffers_with_damage`.
+    #[inline]
+    pub fn swap_buffers_with_damage(&self, damage: &[Rect]) -> Result<(), SwapBuffersError> {
+        self.swap_buffers_impl(Some(damage))
+    }
+
+    fn swap_buffers_impl(&self, damage: Option<&[Rect]>) -> Result<(), SwapBuffersError> {
         if self.state.borrow().lost_context {
             return Err(SwapBuffersError::ContextLost);
         }
@@ -313,10 +554,28 @@ impl Context {
         }
 
         // swapping
-        let err = backend.swap_buffers();
+        let err = match damage {
+            Some(damage) => backend.swap_buffers_with_damage(damage),
+            None => backend.swap_buffers(),
+        };
         if let Err(SwapBuffersError::ContextLost) = err {
             self.state.borrow_mut().lost_context = true;
         }
+        drop(backend);
+
+        #[cfg(feature = "gl_call_log")]
+        crate::call_log::end_frame();
+
+        if err.is_ok() && self.flush_policy.get() == FlushPolicy::FinishAfterSwap {
+            self.finish();
+        }
+
+        // the wakers are taken out first, as waking a task may poll it immediately
+        let wakers = mem::take(&mut *self.pending_wakers.borrow_mut());
+        for waker in wakers {
+            waker.wake();
+        }
+
         err
     }
 
@@ -590,6 +849,73 @@ impl Context {
         unsafe { ctxt.gl.Flush(); }
     }
 
+    /// Calls `glMemoryBarrier`, which makes the memory writes done by the previous shader
+    /// invocations visible to the following operations of the given kinds.
+    ///
+    /// Glium automatically inserts the barriers required by the buffers that were written
+    /// through shader storage blocks, atomic counters and buffer textures bound to image units.
+    /// You only need to call this function after writing a texture through an `ImageUnit`, or
+    /// if a shader reads values written by a previous draw or dispatch with `imageLoad`.
+    ///
+    /// ```no_run
+    /// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// use glium::MemoryBarriers;
+    ///
+    /// // the texture written by a compute shader will be sampled by the next draw
+    /// display.memory_barrier(MemoryBarriers::TEXTURE_FETCH).unwrap();
+    /// ```
+    ///
+    /// Returns `Err` if the backend doesn't support memory barriers, which means that it
+    /// doesn't support writing memory from shaders either.
+    pub fn memory_barrier(&self, barriers: MemoryBarriers)
+                          -> Result<(), MemoryBarriersNotSupportedError>
+    {
+        let mut ctxt = self.make_current();
+
+        let supported = ctxt.version >= &Version(Api::Gl, 4, 2) ||
+                        ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+                        ctxt.extensions.gl_arb_shader_image_load_store;
+        if !supported {
+            return Err(MemoryBarriersNotSupportedError);
+        }
+
+        if !barriers.is_empty() {
+            unsafe { ctxt.gl.MemoryBarrier(barriers.bits()); }
+            barrier::record_barriers(&mut ctxt.state, barriers);
+        }
+
+        Ok(())
+    }
+
+    /// Calls `glMemoryBarrierByRegion`. This is the same as `memory_barrier`, except that the
+    /// barrier only applies to the writes of the fragment shader invocations of the same
+    /// framebuffer region, which can be cheaper on tiled GPUs.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if `barriers` contains barriers that are not in `MemoryBarriers::BY_REGION`.
+    pub fn memory_barrier_by_region(&self, barriers: MemoryBarriers)
+                                    -> Result<(), MemoryBarriersNotSupportedError>
+    {
+        assert!(MemoryBarriers::BY_REGION.contains(barriers),
+                "Only the barriers of `MemoryBarriers::BY_REGION` can be used by region");
+
+        let ctxt = self.make_current();
+
+        let supported = ctxt.version >= &Version(Api::Gl, 4, 5) ||
+                        ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+                        ctxt.extensions.gl_arb_es3_1_compatibility;
+        if !supported {
+            return Err(MemoryBarriersNotSupportedError);
+        }
+
+        if !barriers.is_empty() {
+            unsafe { ctxt.gl.MemoryBarrierByRegion(barriers.bits()); }
+        }
+
+        Ok(())
+    }
+
     /// Inserts a debugging string in the commands queue. If you use an OpenGL debugger, you will
     /// be able to see that string.
     ///
@@ -617,6 +943,114 @@ impl Context {
         }
     }
 
+    /// Enables or disables the color space validation.
+    ///
+    /// When enabled, drawing returns `DrawError::ColorSpaceMismatch` if the draw command writes
+    /// into an sRGB texture with a program that declares that it already outputs sRGB values (see
+    /// `Program::has_srgb_output`). Such a program expects its output to be stored as it is, in
+    /// other words it expects a linear target. Depending on the backend, the values will either
+    /// be stored without conversion or be converted a second time. The usual approach is to
+    /// output linear values and let OpenGL do the conversion.
+    ///
+    /// This is meant to be used during development to track color space mistakes. It is disabled
+    /// by default.
+    #[inline]
+    pub fn set_color_space_validation(&self, enabled: bool) {
+        self.color_space_validation.set(enabled);
+    }
+
+    /// Returns true if the color space validation is enabled.
+    ///
+    /// See `set_color_space_validation`.
+    #[inline]
+    pub fn is_color_space_validation_enabled(&self) -> bool {
+        self.color_space_validation.get()
+    }
+
+    /// Enables or disables the emulation of instancing.
+    ///
+    /// When enabled and if the backend supports neither OpenGL 3.3 nor `GL_ARB_instanced_arrays`,
+    /// `per_instance()` no longer returns an error. Instead, draw commands that use per-instance
+    /// attributes read back the per-instance buffers and issue one draw call per instance, with
+    /// the attributes of the instance passed as constant vertex attributes. The shaders don't
+    /// need to be modified.
+    ///
+    /// This is very slow and only meant to run the same code on old hardware. Only attributes
+    /// whose type is a float, a float vector or a float matrix in the shader can be emulated,
+    /// and `gl_InstanceID` is not available. The emulation is disabled by default.
+    #[inline]
+    pub fn set_instancing_emulation(&self, enabled: bool) {
+        self.instancing_emulation.set(enabled);
+    }
+
+    /// Returns true if the emulation of instancing is enabled.
+    ///
+    /// See `set_instancing_emulation`.
+    #[inline]
+    pub fn is_instancing_emulation_enabled(&self) -> bool {
+        self.instancing_emulation.get()
+    }
+
+    /// Changes when glium submits the commands queue to the GPU.
+    ///
+    /// See the documentation of `FlushPolicy`. The default value is `FlushPolicy::Implicit`.
+    /// In addition to this, `flush` and `finish` can be called at any time.
+    #[inline]
+    pub fn set_flush_policy(&self, policy: FlushPolicy) {
+        self.flush_policy.set(policy);
+    }
+
+    /// Returns the current flush policy.
+    ///
+    /// See `set_flush_policy`.
+    #[inline]
+    pub fn get_flush_policy(&self) -> FlushPolicy {
+        self.flush_policy.get()
+    }
+
+    /// Returns the registry of the GLSL modules that can be imported by the programs created
+    /// with this context.
+    ///
+    /// See the documentation of `ShaderModuleRegistry`.
+    #[inline]
+    pub fn get_shader_module_registry(&self) -> &program::ShaderModuleRegistry {
+        &self.shader_modules
+    }
+
+    /// Sets the thread that builds the programs of `Program::new_async` if the backend doesn't
+    /// support parallel shader compilation. Pass `None` to compile them on the thread of this
+    /// context again.
+    ///
+    /// See the documentation of `CompilerThread`.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if the context of the thread doesn't share its objects with this context.
+    pub fn set_compiler_thread(&self, thread: Option<program::CompilerThread>) {
+        if let Some(ref thread) = thread {
+            assert!(thread.get_share_group_id() == self.get_share_group_id(),
+                    "The context of the compiler thread doesn't share its objects with this \
+                     context");
+        }
+
+        *self.compiler_thread.borrow_mut() = thread;
+    }
+
+    /// Returns the thread set with `set_compiler_thread`.
+    #[inline]
+    pub(crate) fn get_compiler_thread(&self) -> Ref<'_, Option<program::CompilerThread>> {
+        self.compiler_thread.borrow()
+    }
+
+    /// Registers a task that waits for the GPU. It is woken up the next time the buffers are
+    /// swapped.
+    pub(crate) fn wake_after_swap(&self, waker: &Waker) {
+        let mut wakers = self.pending_wakers.borrow_mut();
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+
     /// Same as `insert_debug_marker`, except that if you don't compile with `debug_assertions`
     /// it is a no-op and returns `Ok`.
     #[inline]
@@ -639,6 +1073,7 @@ impl ContextExt for Context {
         if self.check_current_context {
             let backend = self.backend.borrow();
             if !backend.is_current() {
+                self.flush_previous_shared_context();
                 unsafe { backend.make_current() };
                 debug_assert!(backend.is_current());
             }
@@ -656,6 +1091,7 @@ impl ContextExt for Context {
             samplers: self.samplers.borrow_mut(),
             resident_texture_handles: self.resident_texture_handles.borrow_mut(),
             resident_image_handles: self.resident_image_handles.borrow_mut(),
+            framebuffer_textures: SmallVec::new(),
             marker: PhantomData,
         }
     }
@@ -664,6 +1100,39 @@ impl ContextExt for Context {
     fn capabilities(&self) -> &Capabilities {
         &self.capabilities
     }
+
+    fn for_each_shared_context(&self, callback: &mut dyn FnMut(&mut CommandContext<'_>)) {
+        let contexts = self.share_group.contexts.borrow().iter()
+                                                .filter_map(Weak::upgrade)
+                                                .filter(|c| !ptr::eq(&**c, self))
+                                                .collect::<Vec<_>>();
+
+        for context in contexts {
+            let mut ctxt = context.make_current();
+            callback(&mut ctxt);
+        }
+    }
+}
+
+impl Context {
+    /// If another context of the share group was current, flushes its commands so that the
+    /// objects it modified are up to date before this context is made current.
+    fn flush_previous_shared_context(&self) {
+        let previous = self.share_group.current.replace(self);
+        if previous == self as *const Context {
+            return;
+        }
+
+        let contexts = self.share_group.contexts.borrow();
+        let previous = contexts.iter().filter_map(Weak::upgrade)
+                                      .find(|c| ptr::eq(&**c, previous));
+
+        if let Some(previous) = previous {
+            if previous.backend.borrow().is_current() {
+                unsafe { previous.gl.Flush(); }
+            }
+        }
+    }
 }
 
 impl CapabilitiesSource for Context {
@@ -685,6 +1154,12 @@ impl CapabilitiesSource for Context {
 
 impl Drop for Context {
     fn drop(&mut self) {
+        // the weak pointer to this context can no longer be upgraded
+        self.share_group.contexts.borrow_mut().retain(|c| c.strong_count() != 0);
+        if self.share_group.current.get() == self as *const Context {
+            self.share_group.current.set(ptr::null());
+        }
+
         unsafe {
             // this is the code of make_current duplicated here because we can't borrow
             // `self` twice
@@ -707,6 +1182,7 @@ impl Drop for Context {
                 samplers: self.samplers.borrow_mut(),
                 resident_texture_handles: self.resident_texture_handles.borrow_mut(),
                 resident_image_handles: self.resident_image_handles.borrow_mut(),
+                framebuffer_textures: SmallVec::new(),
                 marker: PhantomData,
             };
 
@@ -33,6 +33,12 @@ pub struct GlState {
     /// Whether DEPTH_CLAMP_FAR is enabled.
     pub enabled_depth_clamp_far: bool,
 
+    /// Whether GL_COLOR_LOGIC_OP is enabled
+    pub enabled_color_logic_op: bool,
+
+    /// Whether GL_DEPTH_BOUNDS_TEST_EXT is enabled
+    pub enabled_depth_bounds_test: bool,
+
     /// Whether GL_DITHER is enabled
     pub enabled_dither: bool,
 
@@ -54,6 +60,9 @@ pub struct GlState {
     /// Whether GL_PRIMITIVE_RESTART_FIXED_INDEX is enabled
     pub enabled_primitive_fixed_restart: bool,
 
+    /// Whether GL_PRIMITIVE_RESTART is enabled
+    pub enabled_primitive_restart: bool,
+
     /// Whether GL_RASTERIZER_DISCARD is enabled
     pub enabled_rasterizer_discard: bool,
 
@@ -63,6 +72,9 @@ pub struct GlState {
     /// Whether GL_SAMPLE_COVERAGE is enabled
     pub enabled_sample_coverage: bool,
 
+    /// Whether GL_SAMPLE_SHADING is enabled
+    pub enabled_sample_shading: bool,
+
     /// Whether GL_SCISSOR_TEST is enabled
     pub enabled_scissor_test: bool,
 
@@ -84,6 +96,9 @@ pub struct GlState {
     /// The latest value passed to `glUseProgram`.
     pub program: Handle,
 
+    /// The latest value passed to `glBindProgramPipeline`.
+    pub program_pipeline: gl::types::GLuint,
+
     /// The latest value passed to `glBindVertexArray`.
     pub vertex_array: gl::types::GLuint,
 
@@ -128,6 +143,9 @@ pub struct GlState {
     /// The latest buffer bound to `GL_DRAW_INDIRECT_BUFFER`.
     pub draw_indirect_buffer_binding: gl::types::GLuint,
 
+    /// The latest buffer bound to `GL_PARAMETER_BUFFER`.
+    pub parameter_buffer_binding: gl::types::GLuint,
+
     /// The latest buffer bound to `GL_QUERY_BUFFER`.
     pub query_buffer_binding: gl::types::GLuint,
 
@@ -159,6 +177,9 @@ pub struct GlState {
     /// `None` means "unknown".
     pub default_framebuffer_read: Option<gl::types::GLenum>,
 
+    /// The latest value passed to `glDrawBuffer` with the default framebuffer.
+    pub default_framebuffer_draw: gl::types::GLenum,
+
     /// The latest render buffer bound with `glBindRenderbuffer`.
     pub renderbuffer: gl::types::GLuint,
 
@@ -169,6 +190,14 @@ pub struct GlState {
     pub blend_func: (gl::types::GLenum, gl::types::GLenum,
                      gl::types::GLenum, gl::types::GLenum),
 
+    /// The latest value passed to `glLogicOp`.
+    pub logic_op: gl::types::GLenum,
+
+    /// Whether the blending state of some draw buffers has been changed with the indexed
+    /// functions, in which case `enabled_blend`, `blend_equation` and `blend_func` may not
+    /// match the state of all the draw buffers.
+    pub blend_per_attachment: bool,
+
     /// The latest value passed to `glBlendColor`.
     pub blend_color: (gl::types::GLclampf, gl::types::GLclampf,
                       gl::types::GLclampf, gl::types::GLclampf),
@@ -182,6 +211,9 @@ pub struct GlState {
     /// The latest values passed to `glDepthRange`.
     pub depth_range: (f32, f32),
 
+    /// The latest values passed to `glDepthBoundsEXT`.
+    pub depth_bounds: (f32, f32),
+
     /// The latest values passed to `glStencilFuncSeparate` with face `GL_FRONT`.
     pub stencil_func_front: (gl::types::GLenum, gl::types::GLint, gl::types::GLuint),
 
@@ -208,6 +240,11 @@ pub struct GlState {
     pub scissor: Option<(gl::types::GLint, gl::types::GLint,
                          gl::types::GLsizei, gl::types::GLsizei)>,
 
+    /// Whether the viewports or scissor boxes have been changed with the indexed functions, in
+    /// which case `viewport`, `scissor` and `enabled_scissor_test` may not match the state of
+    /// all the viewports.
+    pub viewport_array: bool,
+
     /// The latest value passed to `glLineWidth`.
     pub line_width: gl::types::GLfloat,
 
@@ -220,8 +257,8 @@ pub struct GlState {
     /// The latest value passed to `glPolygonMode`.
     pub polygon_mode: gl::types::GLenum,
 
-    /// The latest values passed to `glPolygonOffset`.
-    pub polygon_offset: (gl::types::GLfloat, gl::types::GLfloat),
+    /// The latest values passed to `glPolygonOffset` or `glPolygonOffsetClamp`.
+    pub polygon_offset: (gl::types::GLfloat, gl::types::GLfloat, gl::types::GLfloat),
 
     /// The latest value passed to `glHint` for smoothing.
     pub smooth: (gl::types::GLenum, gl::types::GLenum),
@@ -229,6 +266,9 @@ pub struct GlState {
     /// The latest value passed to `glProvokingVertex`.
     pub provoking_vertex: gl::types::GLenum,
 
+    /// The latest values passed to `glClipControl`.
+    pub clip_control: (gl::types::GLenum, gl::types::GLenum),
+
     /// The latest value passed to `glPixelStore` with `GL_UNPACK_ALIGNMENT`.
     pub pixel_store_unpack_alignment: gl::types::GLint,
 
@@ -238,9 +278,18 @@ pub struct GlState {
     /// The latest value passed to `glClampColor`.
     pub clamp_color: gl::types::GLenum,
 
+    /// The latest value passed to `glMinSampleShading`.
+    pub min_sample_shading: f32,
+
     /// The latest value passed to `glPatchParameter` with `GL_PATCH_VERTICES`.
     pub patch_patch_vertices: gl::types::GLint,
 
+    /// The latest value passed to `glPatchParameter` with `GL_PATCH_DEFAULT_OUTER_LEVEL`.
+    pub patch_default_outer_level: [f32; 4],
+
+    /// The latest value passed to `glPatchParameter` with `GL_PATCH_DEFAULT_INNER_LEVEL`.
+    pub patch_default_inner_level: [f32; 2],
+
     /// The id of the active texture unit.
     /// IMPORTANT: this is a raw number (0, 1, 2, ...), not an
     ///            enumeration (GL_TEXTURE0, GL_TEXTURE1, ...).
@@ -281,6 +330,9 @@ pub struct GlState {
     /// The latest value passed to `glPrimitiveBoundingBox`.
     pub primitive_bounding_box: (f32, f32, f32, f32, f32, f32, f32, f32),
 
+    /// The latest value passed to `glPrimitiveRestartIndex`.
+    pub primitive_restart_index: gl::types::GLuint,
+
     /// Current draw call ID.
     /// We maintain a counter that is incremented at each draw call.
     pub next_draw_call_id: u64,
@@ -384,6 +436,8 @@ impl Default for GlState {
             enabled_depth_test: false,
             enabled_depth_clamp_near: false,
             enabled_depth_clamp_far: false,
+            enabled_color_logic_op: false,
+            enabled_depth_bounds_test: false,
             enabled_dither: false,
             enabled_framebuffer_srgb: false,
             enabled_multisample: true,
@@ -393,15 +447,18 @@ impl Default for GlState {
             enabled_rasterizer_discard: false,
             enabled_sample_alpha_to_coverage: false,
             enabled_sample_coverage: false,
+            enabled_sample_shading: false,
             enabled_scissor_test: false,
             enabled_stencil_test: false,
             enabled_line_smooth: false,
             enabled_polygon_smooth: false,
             enabled_primitive_fixed_restart: false,
+            enabled_primitive_restart: false,
             enabled_program_point_size: false,
             enabled_clip_planes: 0,
 
             program: Handle::Id(0),
+            program_pipeline: 0,
             vertex_array: 0,
             clear_color: (0.0, 0.0, 0.0, 0.0),
             clear_depth: 1.0,
@@ -416,6 +473,7 @@ impl Default for GlState {
             copy_write_buffer_binding: 0,
             dispatch_indirect_buffer_binding: 0,
             draw_indirect_buffer_binding: 0,
+            parameter_buffer_binding: 0,
             query_buffer_binding: 0,
             texture_buffer_binding: 0,
             atomic_counter_buffer_binding: 0,
@@ -426,10 +484,12 @@ impl Default for GlState {
             read_framebuffer: 0,
             draw_framebuffer: 0,
             default_framebuffer_read: None,
+            default_framebuffer_draw: gl::BACK,
             renderbuffer: 0,
             depth_func: gl::LESS,
             depth_mask: true,
             depth_range: (0.0, 1.0),
+            depth_bounds: (0.0, 1.0),
             stencil_func_front: (gl::ALWAYS, 0, 0xffffffff),
             stencil_func_back: (gl::ALWAYS, 0, 0xffffffff),
             stencil_mask_front: 0xffffffff,
@@ -438,19 +498,26 @@ impl Default for GlState {
             stencil_op_back: (gl::KEEP, gl::KEEP, gl::KEEP),
             blend_equation: (gl::FUNC_ADD, gl::FUNC_ADD),
             blend_func: (gl::ONE, gl::ZERO, gl::ONE, gl::ZERO),
+            logic_op: gl::COPY,
+            blend_per_attachment: false,
             blend_color: (0.0, 0.0, 0.0, 0.0),
             viewport: None,
             scissor: None,
+            viewport_array: false,
             line_width: 1.0,
             point_size: 1.0,
             cull_face: gl::BACK,
             polygon_mode: gl::FILL,
             smooth: (gl::DONT_CARE, gl::DONT_CARE),
             provoking_vertex: gl::LAST_VERTEX_CONVENTION,
+            clip_control: (gl::LOWER_LEFT, gl::NEGATIVE_ONE_TO_ONE),
             pixel_store_unpack_alignment: 4,
             pixel_store_pack_alignment: 4,
             clamp_color: gl::FIXED_ONLY,
+            min_sample_shading: 1.0,
             patch_patch_vertices: 3,
+            patch_default_outer_level: [1.0; 4],
+            patch_default_inner_level: [1.0; 2],
             active_texture: 0,
             texture_units: small_vec_one(),
             samples_passed_query: 0,
@@ -463,7 +530,8 @@ impl Default for GlState {
             transform_feedback_enabled: None,
             transform_feedback_paused: false,
             primitive_bounding_box: (-1.0, -1.0, -1.0, -1.0, 1.0, 1.0, 1.0, 1.0),
-            polygon_offset: (0.0, 0.0),
+            primitive_restart_index: 0,
+            polygon_offset: (0.0, 0.0, 0.0),
 
             next_draw_call_id: 1,
             latest_memory_barrier_vertex_attrib_array: 1,
@@ -2,9 +2,34 @@ use crate::context::CommandContext;
 use crate::version::Api;
 use crate::version::Version;
 
+use crate::CapabilitiesSource;
 use crate::DrawError;
 use crate::gl;
 
+/// Returns true if the backend supports `BlendingFunction::Advanced`.
+///
+/// Advanced blend equations are supported on OpenGL ES 3.2 and above, or with
+/// `GL_KHR_blend_equation_advanced`.
+#[inline]
+pub fn is_advanced_blending_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+    context.get_version() >= &Version(Api::GlEs, 3, 2) ||
+    context.get_extensions().gl_khr_blend_equation_advanced
+}
+
+/// Returns true if the backend supports `BlendingFunction::Advanced` with coherent blending,
+/// in other words with `GL_KHR_blend_equation_advanced_coherent`.
+///
+/// With non-coherent blending, the result is undefined if a sample is written more than once
+/// by the same draw command. Glium inserts a blend barrier before each draw command that uses
+/// an advanced blend equation, so you only need to care about the primitives that overlap
+/// within a draw command.
+#[inline]
+pub fn is_coherent_advanced_blending_supported<C: ?Sized>(context: &C) -> bool
+                                                         where C: CapabilitiesSource
+{
+    context.get_extensions().gl_khr_blend_equation_advanced_coherent
+}
+
 /// Blend effect that the GPU will use for blending.
 ///
 /// Blending happens at the end of the rendering process, when the GPU wants to write the
@@ -38,6 +63,16 @@ impl Blend {
             constant_value: (0.0, 0.0, 0.0, 0.0)
         }
     }
+
+    /// Returns a blend effect that uses an advanced blend equation for both the color and the
+    /// alpha channels.
+    pub fn advanced(equation: AdvancedBlendEquation) -> Blend {
+        Blend {
+            color: BlendingFunction::Advanced(equation),
+            alpha: BlendingFunction::Advanced(equation),
+            constant_value: (0.0, 0.0, 0.0, 0.0)
+        }
+    }
 }
 
 impl Default for Blend {
@@ -116,6 +151,81 @@ pub enum BlendingFunction {
         /// The factor to apply to the destination pixel.
         destination: LinearBlendingFactor,
     },
+
+    /// One of the blend equations of image editors, like multiply or overlay.
+    ///
+    /// The color and alpha channels are always blended together, which means that
+    /// `Blend::color` and `Blend::alpha` must both be set to the same equation. See
+    /// `Blend::advanced`.
+    ///
+    /// The source and destination colors are expected to be premultiplied by their alpha. The
+    /// fragment shader must declare that it supports the equation, for example with
+    /// `layout(blend_support_multiply) out;` or `layout(blend_support_all_equations) out;`,
+    /// and the framebuffer must have a single color attachment.
+    ///
+    /// Requires `is_advanced_blending_supported`, otherwise drawing returns an error of type
+    /// `BlendingParameterNotSupported`.
+    Advanced(AdvancedBlendEquation),
+}
+
+/// Blend equation of `BlendingFunction::Advanced`.
+///
+/// In the descriptions, `s` is the source color and `d` is the destination color, without
+/// their alpha.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdvancedBlendEquation {
+    /// `s * d`.
+    Multiply,
+    /// `s + d - s * d`.
+    Screen,
+    /// `HardLight` with the source and destination swapped.
+    Overlay,
+    /// The minimum of `s` and `d`.
+    Darken,
+    /// The maximum of `s` and `d`.
+    Lighten,
+    /// Brightens the destination depending on the source.
+    ColorDodge,
+    /// Darkens the destination depending on the source.
+    ColorBurn,
+    /// `Multiply` or `Screen` depending on the source.
+    HardLight,
+    /// A softer version of `HardLight`.
+    SoftLight,
+    /// The absolute value of `s - d`.
+    Difference,
+    /// `s + d - 2 * s * d`.
+    Exclusion,
+    /// The hue of the source with the saturation and luminosity of the destination.
+    HslHue,
+    /// The saturation of the source with the hue and luminosity of the destination.
+    HslSaturation,
+    /// The hue and saturation of the source with the luminosity of the destination.
+    HslColor,
+    /// The luminosity of the source with the hue and saturation of the destination.
+    HslLuminosity,
+}
+
+impl AdvancedBlendEquation {
+    fn to_glenum(self) -> gl::types::GLenum {
+        match self {
+            AdvancedBlendEquation::Multiply => gl::MULTIPLY_KHR,
+            AdvancedBlendEquation::Screen => gl::SCREEN_KHR,
+            AdvancedBlendEquation::Overlay => gl::OVERLAY_KHR,
+            AdvancedBlendEquation::Darken => gl::DARKEN_KHR,
+            AdvancedBlendEquation::Lighten => gl::LIGHTEN_KHR,
+            AdvancedBlendEquation::ColorDodge => gl::COLORDODGE_KHR,
+            AdvancedBlendEquation::ColorBurn => gl::COLORBURN_KHR,
+            AdvancedBlendEquation::HardLight => gl::HARDLIGHT_KHR,
+            AdvancedBlendEquation::SoftLight => gl::SOFTLIGHT_KHR,
+            AdvancedBlendEquation::Difference => gl::DIFFERENCE_KHR,
+            AdvancedBlendEquation::Exclusion => gl::EXCLUSION_KHR,
+            AdvancedBlendEquation::HslHue => gl::HSL_HUE_KHR,
+            AdvancedBlendEquation::HslSaturation => gl::HSL_SATURATION_KHR,
+            AdvancedBlendEquation::HslColor => gl::HSL_COLOR_KHR,
+            AdvancedBlendEquation::HslLuminosity => gl::HSL_LUMINOSITY_KHR,
+        }
+    }
 }
 
 /// Indicates which value to multiply each component with.
@@ -202,113 +312,226 @@ impl LinearBlendingFactor {
     }
 }
 
-pub fn sync_blending(ctxt: &mut CommandContext<'_>, blend: Blend) -> Result<(), DrawError> {
-    #[inline(always)]
-    fn blend_eq(ctxt: &mut CommandContext<'_>, blending_function: BlendingFunction)
-                -> Result<gl::types::GLenum, DrawError>
-    {
-        match blending_function {
-            BlendingFunction::AlwaysReplace |
-            BlendingFunction::Addition { .. } => Ok(gl::FUNC_ADD),
-            BlendingFunction::Subtraction { .. } => Ok(gl::FUNC_SUBTRACT),
-            BlendingFunction::ReverseSubtraction { .. } => Ok(gl::FUNC_REVERSE_SUBTRACT),
-
-            BlendingFunction::Min => {
-                if ctxt.version <= &Version(Api::GlEs, 2, 0) &&
-                   !ctxt.extensions.gl_ext_blend_minmax
-                {
-                    Err(DrawError::BlendingParameterNotSupported)
-                } else {
-                    Ok(gl::MIN)
-                }
-            },
+#[inline(always)]
+fn blend_eq(ctxt: &mut CommandContext<'_>, blending_function: BlendingFunction)
+            -> Result<gl::types::GLenum, DrawError>
+{
+    match blending_function {
+        BlendingFunction::AlwaysReplace |
+        BlendingFunction::Addition { .. } => Ok(gl::FUNC_ADD),
+        BlendingFunction::Subtraction { .. } => Ok(gl::FUNC_SUBTRACT),
+        BlendingFunction::ReverseSubtraction { .. } => Ok(gl::FUNC_REVERSE_SUBTRACT),
+        BlendingFunction::Advanced(_) => Err(DrawError::BlendingParameterNotSupported),
+
+        BlendingFunction::Min => {
+            if ctxt.version <= &Version(Api::GlEs, 2, 0) &&
+               !ctxt.extensions.gl_ext_blend_minmax
+            {
+                Err(DrawError::BlendingParameterNotSupported)
+            } else {
+                Ok(gl::MIN)
+            }
+        },
+
+        BlendingFunction::Max => {
+            if ctxt.version <= &Version(Api::GlEs, 2, 0) &&
+               !ctxt.extensions.gl_ext_blend_minmax
+            {
+                Err(DrawError::BlendingParameterNotSupported)
+            } else {
+                Ok(gl::MAX)
+            }
+        },
+    }
+}
 
-            BlendingFunction::Max => {
-                if ctxt.version <= &Version(Api::GlEs, 2, 0) &&
-                   !ctxt.extensions.gl_ext_blend_minmax
-                {
-                    Err(DrawError::BlendingParameterNotSupported)
-                } else {
-                    Ok(gl::MAX)
-                }
-            },
-        }
+/// Returns the source and destination factors of the function. Functions that don't use
+/// factors are mapped to dummy factors.
+#[inline(always)]
+fn blending_factors(blending_function: BlendingFunction)
+                    -> (LinearBlendingFactor, LinearBlendingFactor)
+{
+    match blending_function {
+        BlendingFunction::AlwaysReplace |
+        BlendingFunction::Min |
+        BlendingFunction::Max |
+        BlendingFunction::Advanced(_) => (LinearBlendingFactor::One, LinearBlendingFactor::Zero),
+        BlendingFunction::Addition { source, destination } =>
+            (source, destination),
+        BlendingFunction::Subtraction { source, destination } =>
+            (source, destination),
+        BlendingFunction::ReverseSubtraction { source, destination } =>
+            (source, destination),
+    }
+}
+
+/// Returns true if one of the factors of the blend effect uses `Blend::constant_value`.
+fn uses_constant_value(blend: &Blend) -> bool {
+    let (color_factor_src, color_factor_dst) = blending_factors(blend.color);
+    let (alpha_factor_src, alpha_factor_dst) = blending_factors(blend.alpha);
+
+    [color_factor_src, color_factor_dst, alpha_factor_src, alpha_factor_dst].iter().any(|f| {
+        matches!(*f, LinearBlendingFactor::ConstantColor |
+                     LinearBlendingFactor::OneMinusConstantColor |
+                     LinearBlendingFactor::ConstantAlpha |
+                     LinearBlendingFactor::OneMinusConstantAlpha)
+    })
+}
+
+/// Returns true if the blend effect is equivalent to no blending.
+#[inline]
+fn is_disabled(blend: &Blend) -> bool {
+    blend.color == BlendingFunction::AlwaysReplace && blend.alpha == BlendingFunction::AlwaysReplace
+}
+
+pub fn sync_blending(ctxt: &mut CommandContext<'_>, blend: Blend,
+                     per_attachment: &[Option<Blend>]) -> Result<(), DrawError>
+{
+    // the draw buffers that were overridden by the previous draw command may not match the
+    // cached state, in which case every value must be set again
+    let force = ctxt.state.blend_per_attachment;
+    ctxt.state.blend_per_attachment = false;
+
+    sync_global_blending(ctxt, blend, force)?;
+
+    if per_attachment.iter().all(|b| b.is_none()) {
+        return Ok(());
+    }
+
+    let supported = ctxt.version >= &Version(Api::Gl, 4, 0) ||
+                    ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+                    (ctxt.version >= &Version(Api::Gl, 3, 0) &&
+                     ctxt.extensions.gl_arb_draw_buffers_blend);
+    if !supported || per_attachment.len() > ctxt.capabilities.max_draw_buffers as usize {
+        return Err(DrawError::BlendingParameterNotSupported);
+    }
+
+    if let BlendingFunction::Advanced(_) = blend.color {
+        return Err(DrawError::BlendingParameterNotSupported);
     }
 
-    #[inline(always)]
-    fn blending_factors(blending_function: BlendingFunction)
-                        -> Option<(LinearBlendingFactor, LinearBlendingFactor)>
-    {
-        match blending_function {
-            BlendingFunction::AlwaysReplace |
-            BlendingFunction::Min |
-            BlendingFunction::Max => None,
-            BlendingFunction::Addition { source, destination } =>
-                Some((source, destination)),
-            BlendingFunction::Subtraction { source, destination } =>
-                Some((source, destination)),
-            BlendingFunction::ReverseSubtraction { source, destination } =>
-                Some((source, destination)),
+    ctxt.state.blend_per_attachment = true;
+
+    for (index, attachment_blend) in per_attachment.iter().enumerate() {
+        let attachment_blend = match *attachment_blend {
+            Some(b) => b,
+            None => continue,
+        };
+
+        let index = index as gl::types::GLuint;
+
+        if is_disabled(&attachment_blend) {
+            unsafe { ctxt.gl.Disablei(gl::BLEND, index); }
+            continue;
+        }
+
+        let (color_eq, alpha_eq) = (blend_eq(ctxt, attachment_blend.color)?,
+                                    blend_eq(ctxt, attachment_blend.alpha)?);
+        let (color_factor_src, color_factor_dst) = blending_factors(attachment_blend.color);
+        let (alpha_factor_src, alpha_factor_dst) = blending_factors(attachment_blend.alpha);
+
+        if uses_constant_value(&attachment_blend) &&
+           ctxt.state.blend_color != blend.constant_value
+        {
+            let (r, g, b, a) = blend.constant_value;
+            unsafe { ctxt.gl.BlendColor(r, g, b, a); }
+            ctxt.state.blend_color = blend.constant_value;
+        }
+
+        unsafe {
+            ctxt.gl.Enablei(gl::BLEND, index);
+
+            if ctxt.version >= &Version(Api::Gl, 4, 0) ||
+               ctxt.version >= &Version(Api::GlEs, 3, 2)
+            {
+                ctxt.gl.BlendEquationSeparatei(index, color_eq, alpha_eq);
+                ctxt.gl.BlendFuncSeparatei(index, color_factor_src.to_glenum(),
+                                           color_factor_dst.to_glenum(),
+                                           alpha_factor_src.to_glenum(),
+                                           alpha_factor_dst.to_glenum());
+            } else {
+                ctxt.gl.BlendEquationSeparateiARB(index, color_eq, alpha_eq);
+                ctxt.gl.BlendFuncSeparateiARB(index, color_factor_src.to_glenum(),
+                                              color_factor_dst.to_glenum(),
+                                              alpha_factor_src.to_glenum(),
+                                              alpha_factor_dst.to_glenum());
+            }
         }
     }
 
-    if let (BlendingFunction::AlwaysReplace, BlendingFunction::AlwaysReplace) =
-           (blend.color, blend.alpha)
-    {
+    Ok(())
+}
+
+/// Sets the blending state of all the draw buffers. If `force` is true, the cached state is
+/// ignored.
+fn sync_global_blending(ctxt: &mut CommandContext<'_>, blend: Blend, force: bool)
+                        -> Result<(), DrawError>
+{
+    if is_disabled(&blend) {
         // Both color and alpha always replace. This equals no blending.
-        if ctxt.state.enabled_blend {
+        if ctxt.state.enabled_blend || force {
             unsafe { ctxt.gl.Disable(gl::BLEND); }
             ctxt.state.enabled_blend = false;
         }
 
+    } else if let BlendingFunction::Advanced(equation) = blend.color {
+        if blend.alpha != blend.color || !is_advanced_blending_supported(ctxt) {
+            return Err(DrawError::BlendingParameterNotSupported);
+        }
+
+        if !ctxt.state.enabled_blend || force {
+            unsafe { ctxt.gl.Enable(gl::BLEND); }
+            ctxt.state.enabled_blend = true;
+        }
+
+        // advanced equations can't be set with `glBlendEquationSeparate`
+        let equation = equation.to_glenum();
+        if ctxt.state.blend_equation != (equation, equation) || force {
+            unsafe { ctxt.gl.BlendEquation(equation); }
+            ctxt.state.blend_equation = (equation, equation);
+        }
+
+        // `GL_BLEND_ADVANCED_COHERENT_KHR` is enabled by default, otherwise the writes of the
+        // previous draw commands must be made visible
+        if !is_coherent_advanced_blending_supported(ctxt) {
+            unsafe {
+                if ctxt.version >= &Version(Api::GlEs, 3, 2) {
+                    ctxt.gl.BlendBarrier();
+                } else {
+                    ctxt.gl.BlendBarrierKHR();
+                }
+            }
+        }
+
     } else {
-        if !ctxt.state.enabled_blend {
+        if !ctxt.state.enabled_blend || force {
             unsafe { ctxt.gl.Enable(gl::BLEND); }
             ctxt.state.enabled_blend = true;
         }
 
         let (color_eq, alpha_eq) = (blend_eq(ctxt, blend.color)?,
                                     blend_eq(ctxt, blend.alpha)?);
-        if ctxt.state.blend_equation != (color_eq, alpha_eq) {
+        if ctxt.state.blend_equation != (color_eq, alpha_eq) || force {
             unsafe { ctxt.gl.BlendEquationSeparate(color_eq, alpha_eq); }
             ctxt.state.blend_equation = (color_eq, alpha_eq);
         }
 
-        // Map to dummy factors if the blending equation does not use the factors.
-        let (color_factor_src, color_factor_dst) = blending_factors(blend.color)
-            .unwrap_or((LinearBlendingFactor::One, LinearBlendingFactor::Zero));
-        let (alpha_factor_src, alpha_factor_dst) = blending_factors(blend.alpha)
-            .unwrap_or((LinearBlendingFactor::One, LinearBlendingFactor::Zero));
-
         // Updating the blending color if necessary.
-        if (color_factor_src == LinearBlendingFactor::ConstantColor ||
-           color_factor_src == LinearBlendingFactor::OneMinusConstantColor ||
-           color_factor_dst == LinearBlendingFactor::ConstantColor ||
-           color_factor_dst == LinearBlendingFactor::OneMinusConstantColor ||
-           alpha_factor_src == LinearBlendingFactor::ConstantColor ||
-           alpha_factor_src == LinearBlendingFactor::OneMinusConstantColor ||
-           alpha_factor_dst == LinearBlendingFactor::ConstantColor ||
-           alpha_factor_dst == LinearBlendingFactor::OneMinusConstantColor ||
-           color_factor_src == LinearBlendingFactor::ConstantAlpha ||
-           color_factor_src == LinearBlendingFactor::OneMinusConstantAlpha ||
-           color_factor_dst == LinearBlendingFactor::ConstantAlpha ||
-           color_factor_dst == LinearBlendingFactor::OneMinusConstantAlpha ||
-           alpha_factor_src == LinearBlendingFactor::ConstantAlpha ||
-           alpha_factor_src == LinearBlendingFactor::OneMinusConstantAlpha ||
-           alpha_factor_dst == LinearBlendingFactor::ConstantAlpha ||
-           alpha_factor_dst == LinearBlendingFactor::OneMinusConstantAlpha) && ctxt.state.blend_color != blend.constant_value {
+        if uses_constant_value(&blend) && ctxt.state.blend_color != blend.constant_value {
             let (r, g, b, a) = blend.constant_value;
             unsafe { ctxt.gl.BlendColor(r, g, b, a); }
             ctxt.state.blend_color = blend.constant_value;
         }
 
         // Updating the blending function if necessary.
+        let (color_factor_src, color_factor_dst) = blending_factors(blend.color);
+        let (alpha_factor_src, alpha_factor_dst) = blending_factors(blend.alpha);
         let color_factor_src = color_factor_src.to_glenum();
         let color_factor_dst = color_factor_dst.to_glenum();
         let alpha_factor_src = alpha_factor_src.to_glenum();
         let alpha_factor_dst = alpha_factor_dst.to_glenum();
         if ctxt.state.blend_func != (color_factor_src, color_factor_dst,
-                                     alpha_factor_src, alpha_factor_dst)
+                                     alpha_factor_src, alpha_factor_dst) || force
         {
             unsafe {
                 ctxt.gl.BlendFuncSeparate(color_factor_src, color_factor_dst,
@@ -41,6 +41,10 @@ pub struct Depth {
 
     /// Sets whether the depth values of samples should be clamped to `0.0` and `1.0`.
     ///
+    /// Clamping prevents geometry that is in front of the near plane or behind the far plane
+    /// from being clipped. This is useful for example when rendering shadow maps, as objects
+    /// between the light and the near plane still cast shadows.
+    ///
     /// The default value is `NoClamp`.
     pub clamp: DepthClamp,
 }
@@ -149,17 +153,20 @@ pub enum DepthClamp {
 
     /// Clamp the depth values. All samples will always be drawn.
     ///
-    /// This value is only supported on OpenGL.
+    /// This value is only supported on OpenGL 3.2 or with `GL_ARB_depth_clamp` or
+    /// `GL_NV_depth_clamp`.
     Clamp,
 
     /// Depth values inferior to `0.0` will be clamped to `0.0`.
     ///
-    /// **This option is supported only by very few OpenGL devices**.
+    /// **This option is supported only by very few OpenGL devices**. It requires
+    /// `GL_AMD_depth_clamp_separate`.
     ClampNear,
 
     /// Depth values superior to `1.0` will be clamped to `1.0`.
     ///
-    /// **This option is supported only by very few OpenGL devices**.
+    /// **This option is supported only by very few OpenGL devices**. It requires
+    /// `GL_AMD_depth_clamp_separate`.
     ClampFar,
 }
 
@@ -174,7 +181,7 @@ pub fn sync_depth(ctxt: &mut CommandContext<'_>, depth: &Depth) -> Result<(), Dr
             (DepthClamp::Clamp, &mut true, &mut true) => (),
 
             (DepthClamp::NoClamp, near, far) => {
-                if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_depth_clamp ||
+                if ctxt.version >= &Version(Api::Gl, 3, 2) || ctxt.extensions.gl_arb_depth_clamp ||
                    ctxt.extensions.gl_nv_depth_clamp
                 {
                     unsafe { ctxt.gl.Disable(gl::DEPTH_CLAMP) };
@@ -186,7 +193,7 @@ pub fn sync_depth(ctxt: &mut CommandContext<'_>, depth: &Depth) -> Result<(), Dr
             },
 
             (DepthClamp::Clamp, near, far) => {
-                if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_depth_clamp ||
+                if ctxt.version >= &Version(Api::Gl, 3, 2) || ctxt.extensions.gl_arb_depth_clamp ||
                    ctxt.extensions.gl_nv_depth_clamp
                 {
                     unsafe { ctxt.gl.Enable(gl::DEPTH_CLAMP) };
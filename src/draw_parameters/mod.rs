@@ -78,7 +78,8 @@ use crate::vertex::TransformFeedbackSession;
 
 use std::ops::Range;
 
-pub use self::blend::{Blend, BlendingFunction, LinearBlendingFactor};
+pub use self::blend::{Blend, BlendingFunction, LinearBlendingFactor, AdvancedBlendEquation};
+pub use self::blend::{is_advanced_blending_supported, is_coherent_advanced_blending_supported};
 pub use self::depth::{Depth, DepthTest, DepthClamp};
 pub use self::query::{QueryCreationError};
 pub use self::query::{SamplesPassedQuery, TimeElapsedQuery, PrimitivesGeneratedQuery};
@@ -189,6 +190,70 @@ impl ToGlEnum for PolygonMode {
     }
 }
 
+/// Logical operation between the bits of the source and destination colors.
+///
+/// In the descriptions, `s` is the value written by the fragment shader and `d` is the value
+/// already in the framebuffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogicOp {
+    /// `0`.
+    Clear,
+    /// `s & d`.
+    And,
+    /// `s & !d`.
+    AndReverse,
+    /// `s`.
+    Copy,
+    /// `!s & d`.
+    AndInverted,
+    /// `d`.
+    Noop,
+    /// `s ^ d`.
+    Xor,
+    /// `s | d`.
+    Or,
+    /// `!(s | d)`.
+    Nor,
+    /// `!(s ^ d)`.
+    Equiv,
+    /// `!d`.
+    Invert,
+    /// `s | !d`.
+    OrReverse,
+    /// `!s`.
+    CopyInverted,
+    /// `!s | d`.
+    OrInverted,
+    /// `!(s & d)`.
+    Nand,
+    /// All the bits set to 1.
+    Set,
+}
+
+impl ToGlEnum for LogicOp {
+    #[inline]
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            LogicOp::Clear => gl::CLEAR,
+            LogicOp::And => gl::AND,
+            LogicOp::AndReverse => gl::AND_REVERSE,
+            LogicOp::Copy => gl::COPY,
+            LogicOp::AndInverted => gl::AND_INVERTED,
+            LogicOp::Noop => gl::NOOP,
+            LogicOp::Xor => gl::XOR,
+            LogicOp::Or => gl::OR,
+            LogicOp::Nor => gl::NOR,
+            LogicOp::Equiv => gl::EQUIV,
+            LogicOp::Invert => gl::INVERT,
+            LogicOp::OrReverse => gl::OR_REVERSE,
+            LogicOp::CopyInverted => gl::COPY_INVERTED,
+            LogicOp::OrInverted => gl::OR_INVERTED,
+            LogicOp::Nand => gl::NAND,
+            LogicOp::Set => gl::SET,
+        }
+    }
+}
+
 /// Specifies a hint for the smoothing.
 ///
 /// Note that this is just a hint and the driver may disregard it.
@@ -227,6 +292,33 @@ pub enum ProvokingVertex {
     FirstVertex,
 }
 
+/// The origin of the window coordinates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClipOrigin {
+    /// A Y coordinate of `-1.0` in clip space corresponds to the bottom of the viewport.
+    LowerLeft,
+
+    /// A Y coordinate of `-1.0` in clip space corresponds to the top of the viewport.
+    UpperLeft,
+}
+
+/// The range of the Z coordinate in normalized device coordinates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClipDepthMode {
+    /// The Z coordinate of the samples must be between `-1.0` and `1.0`, and is mapped to the
+    /// depth range.
+    NegativeOneToOne,
+
+    /// The Z coordinate of the samples must be between `0.0` and `1.0`, and is mapped to the
+    /// depth range.
+    ///
+    /// This avoids losing half of the precision of floating-point depth buffers. Combined
+    /// with a projection matrix that maps the far plane to `0.0` and the near plane to `1.0`
+    /// (also known as "reversed Z"), and a depth test of `IfMore`, this gives a nearly uniform
+    /// precision over the whole view distance.
+    ZeroToOne,
+}
+
 /// Represents the parameters to use when drawing.
 ///
 /// Example:
@@ -247,6 +339,18 @@ pub struct DrawParameters<'a> {
     /// How the fragment will interact with the depth buffer.
     pub depth: Depth,
 
+    /// If `Some`, fragments are discarded if the depth value already in the depth buffer at
+    /// their location is outside of the `(min, max)` range. This doesn't depend on the depth
+    /// of the fragment itself, and allows for example lights or shadow volumes to skip the
+    /// pixels that they can't affect. The default value is `None`.
+    ///
+    /// This has no effect if there is no depth buffer. The bounds are clamped between `0.0`
+    /// and `1.0`.
+    ///
+    /// Requires `GL_EXT_depth_bounds_test`. If the backend does not support it, an error of
+    /// type `DepthBoundsNotSupported` will be returned.
+    pub depth_bounds: Option<(f32, f32)>,
+
     /// How the fragment will interact with the stencil buffer.
     pub stencil: Stencil,
 
@@ -254,6 +358,31 @@ pub struct DrawParameters<'a> {
     /// being written.
     pub blend: Blend,
 
+    /// Overrides `blend` for some color attachments. The element at index `i` applies to the
+    /// attachment of the fragment output at location `i`, and `None` means that `blend` is
+    /// used. The default value is an empty slice.
+    ///
+    /// The `constant_value` of `blend` is used by all the attachments, and advanced blend
+    /// equations can't be used with this parameter.
+    ///
+    /// Supported on OpenGL 4.0 and above, OpenGL ES 3.2 and above, or with
+    /// `GL_ARB_draw_buffers_blend`. If the backend does not support it, or if there are more
+    /// elements than draw buffers, an error of type `BlendingParameterNotSupported` will be
+    /// returned.
+    pub blend_per_attachment: &'a [Option<Blend>],
+
+    /// If `Some`, the value written by the fragment shader is combined with the value of the
+    /// framebuffer with a bitwise operation. This is mostly useful with integral formats. For
+    /// normalized formats, the operation applies to the stored bits. The default value is
+    /// `None`.
+    ///
+    /// Blending is disabled while a logical operation is enabled, and floating-point or sRGB
+    /// attachments are not affected.
+    ///
+    /// Not supported on OpenGL ES. If the backend does not support it, an error of type
+    /// `LogicOpNotSupported` will be returned.
+    pub color_logic_op: Option<LogicOp>,
+
     /// Allows you to disable some color components.
     ///
     /// This affects all attachments to the framebuffer. It's at the same level as the
@@ -277,10 +406,27 @@ pub struct DrawParameters<'a> {
     /// If the bit corresponding to 2^i is 1 in the bitmask, then GL_CLIP_DISTANCEi is enabled.
     ///
     /// The most common value for GL_MAX_CLIP_DISTANCES is 8, so 32 bits in the mask is plenty.
+    /// The maximum is available in `Capabilities::max_clip_distances`. Enabling a clip distance
+    /// beyond the maximum triggers a `ClipPlaneIndexOutOfBounds` error. On OpenGL ES, clip
+    /// distances require `GL_EXT_clip_cull_distance`.
     ///
     /// See `https://www.khronos.org/registry/OpenGL-Refpages/gl4/html/gl_ClipDistance.xhtml`.
     pub clip_planes_bitmask: u32,
 
+    /// The origin of the window coordinates. The default value is `LowerLeft`, as this is the
+    /// default in OpenGL.
+    ///
+    /// Any other value requires OpenGL 4.5, `GL_ARB_clip_control` or `GL_EXT_clip_control`,
+    /// and can otherwise trigger a `ClipControlNotSupported` error.
+    pub clip_origin: ClipOrigin,
+
+    /// The range of the Z coordinate after the perspective division. The default value is
+    /// `NegativeOneToOne`, as this is the default in OpenGL.
+    ///
+    /// Any other value requires OpenGL 4.5, `GL_ARB_clip_control` or `GL_EXT_clip_control`,
+    /// and can otherwise trigger a `ClipControlNotSupported` error.
+    pub clip_depth_mode: ClipDepthMode,
+
     /// Whether or not the GPU should filter out some faces.
     ///
     /// After the vertex shader stage, the GPU will try to remove the faces that aren't facing
@@ -301,6 +447,19 @@ pub struct DrawParameters<'a> {
     /// creating the window.
     pub multisampling: bool,
 
+    /// If `Some`, the fragment shader is executed at least once for the given fraction of the
+    /// samples of each pixel, instead of once per pixel. A value of `1.0` shades every sample
+    /// separately, which for example removes the aliasing of alpha-tested geometry. The
+    /// default value is `None`.
+    ///
+    /// This has no effect if `multisampling` is `false` or if the framebuffer isn't
+    /// multisampled. The value is clamped between `0.0` and `1.0`.
+    ///
+    /// Supported on OpenGL 4.0 and above, OpenGL ES 3.2 and above, or with
+    /// `GL_ARB_sample_shading` or `GL_OES_sample_shading`. If the backend does not support it,
+    /// an error of type `SampleShadingNotSupported` will be returned.
+    pub min_sample_shading: Option<f32>,
+
     /// Whether dithering is activated. Default value is `true`.
     ///
     /// Dithering will smoothen the transition between colors in your color buffer.
@@ -323,6 +482,17 @@ pub struct DrawParameters<'a> {
     /// not the scissor box.
     pub scissor: Option<Rect>,
 
+    /// If not empty, replaces `viewport` and `scissor` with one viewport and an optional
+    /// scissor box for each element. A geometry shader chooses the viewport of each primitive
+    /// by writing to `gl_ViewportIndex`, which allows for example drawing all the cascades of
+    /// a shadow map in a single pass. The default value is `&[]`.
+    ///
+    /// Supported on OpenGL 4.1 and above, or with `GL_ARB_viewport_array` or
+    /// `GL_OES_viewport_array`. The number of elements must not exceed
+    /// `Capabilities::max_viewports`. Otherwise an error of type `ViewportArrayNotSupported`
+    /// will be returned.
+    pub viewports: &'a [(Rect, Option<Rect>)],
+
     /// If `false`, the pipeline will stop after the primitives generation stage. The default
     /// value is `true`.
     ///
@@ -383,7 +553,8 @@ pub struct DrawParameters<'a> {
     /// This field is useless if you're not using a geometry shader or tessellation shader.
     ///
     /// Since this is purely an optimization, this parameter is ignored if the backend doesn't
-    /// support it.
+    /// support it. You can check whether it is supported with
+    /// `is_primitive_bounding_box_supported`.
     pub primitive_bounding_box: (Range<f32>, Range<f32>, Range<f32>, Range<f32>),
 
     /// If enabled, will split the index buffer (if any is used in the draw call)
@@ -393,9 +564,50 @@ pub struct DrawParameters<'a> {
     /// of type `FixedIndexRestartingNotSupported` will be returned.
     pub primitive_restart_index: bool,
 
+    /// If `Some`, will split the index buffer (if any is used in the draw call) at each index
+    /// equal to this value and start a new primitive of the same type. This is the same as
+    /// `primitive_restart_index`, except that you can choose the value of the index.
+    ///
+    /// Supported on OpenGL 3.1 and above, but not on OpenGL ES. If the backend does not support
+    /// it, an error of type `CustomIndexRestartingNotSupported` will be returned.
+    ///
+    /// If `primitive_restart_index` is enabled as well, it takes precedence over this value.
+    pub primitive_restart_custom_index: Option<u32>,
+
     /// If enabled, shifts the depth value of towards of away from the camera. This is useful for
     /// drawing decals and wireframes, for example.
     pub polygon_offset: PolygonOffset,
+
+    /// If true, a texture barrier is issued before drawing.
+    ///
+    /// Sampling a texture that is attached to the framebuffer normally returns an error of
+    /// type `TextureFeedbackLoop`, since the result would be undefined. A texture barrier makes
+    /// the texels written by the previous draw commands visible to this one, and allows the
+    /// textures of the framebuffer to be sampled. The result is still undefined if a texel
+    /// written by this draw command is read by another fragment, or written more than once.
+    ///
+    /// Supported on OpenGL 4.5 and above, or with `GL_ARB_texture_barrier` or
+    /// `GL_NV_texture_barrier`. If the backend does not support it, an error of type
+    /// `TextureBarrierNotSupported` will be returned.
+    pub texture_barrier: bool,
+
+    /// The outer tessellation levels to use when drawing patches with a program that has a
+    /// tessellation evaluation shader but no tessellation control shader.
+    ///
+    /// This avoids having to write a tessellation control shader that only sets
+    /// `gl_TessLevelOuter` and `gl_TessLevelInner`. The default value is `[1.0; 4]`, as this is
+    /// the default in OpenGL.
+    ///
+    /// Supported on OpenGL 4.0 and above, or with `GL_ARB_tessellation_shader`, but not on
+    /// OpenGL ES. If the backend does not support it, any other value than the default one
+    /// returns an error of type `PatchDefaultLevelsNotSupported`.
+    pub patch_default_outer_level: [f32; 4],
+
+    /// The inner tessellation levels to use when drawing patches with a program that has a
+    /// tessellation evaluation shader but no tessellation control shader.
+    ///
+    /// See `patch_default_outer_level`. The default value is `[1.0; 2]`.
+    pub patch_default_inner_level: [f32; 2],
 }
 
 /// Condition whether to render or not.
@@ -443,9 +655,20 @@ pub struct PolygonOffset {
     pub factor: f32,
     /// Add a constant value to polygon depth
     pub units: f32,
+    /// If different from `0.0`, the offset is clamped so that its absolute value doesn't
+    /// exceed the absolute value of `clamp`. This avoids large offsets for the polygons that
+    /// are nearly parallel to the view direction.
+    ///
+    /// Requires OpenGL 4.6, `GL_ARB_polygon_offset_clamp` or `GL_EXT_polygon_offset_clamp`,
+    /// otherwise a `PolygonOffsetNotSupported` error is returned.
+    pub clamp: f32,
     /// If true, the depth offset is enabled for points
+    ///
+    /// Not supported on OpenGL ES, where a `PolygonOffsetNotSupported` error is returned.
     pub point: bool,
     /// If true, the depth offset is enabled for lines
+    ///
+    /// Not supported on OpenGL ES, where a `PolygonOffsetNotSupported` error is returned.
     pub line: bool,
     /// If true, the depth offset is enabled for triangles
     pub fill: bool,
@@ -456,6 +679,7 @@ impl Default for PolygonOffset {
         PolygonOffset{
             factor: 0.0,
             units: 0.0,
+            clamp: 0.0,
             point: false,
             line: false,
             fill: false
@@ -467,18 +691,25 @@ impl<'a> Default for DrawParameters<'a> {
     fn default() -> DrawParameters<'a> {
         DrawParameters {
             depth: Depth::default(),
+            depth_bounds: None,
             stencil: Default::default(),
             blend: Default::default(),
+            blend_per_attachment: &[],
+            color_logic_op: None,
             color_mask: (true, true, true, true),
             line_width: None,
             point_size: None,
             backface_culling: BackfaceCullingMode::CullingDisabled,
             polygon_mode: PolygonMode::Fill,
             clip_planes_bitmask: 0,
+            clip_origin: ClipOrigin::LowerLeft,
+            clip_depth_mode: ClipDepthMode::NegativeOneToOne,
             multisampling: true,
+            min_sample_shading: None,
             dithering: true,
             viewport: None,
             scissor: None,
+            viewports: &[],
             draw_primitives: true,
             samples_passed_query: None,
             time_elapsed_query: None,
@@ -490,11 +721,30 @@ impl<'a> Default for DrawParameters<'a> {
             provoking_vertex: ProvokingVertex::LastVertex,
             primitive_bounding_box: (-1.0 .. 1.0, -1.0 .. 1.0, -1.0 .. 1.0, -1.0 .. 1.0),
             primitive_restart_index: false,
+            primitive_restart_custom_index: None,
             polygon_offset: Default::default(),
+            texture_barrier: false,
+            patch_default_outer_level: [1.0; 4],
+            patch_default_inner_level: [1.0; 2],
         }
     }
 }
 
+/// Returns true if the backend takes `DrawParameters::primitive_bounding_box` into account.
+///
+/// The bounding box is supported on OpenGL ES 3.2 and above, or with
+/// `GL_ARB_ES3_2_compatibility`, `GL_OES_primitive_bounding_box` or
+/// `GL_EXT_primitive_bounding_box`.
+#[inline]
+pub fn is_primitive_bounding_box_supported<C: ?Sized>(context: &C) -> bool
+                                           where C: CapabilitiesSource
+{
+    context.get_version() >= &Version(Api::GlEs, 3, 2) ||
+    context.get_extensions().gl_arb_es3_2_compatibility ||
+    context.get_extensions().gl_oes_primitive_bounding_box ||
+    context.get_extensions().gl_ext_primitive_bounding_box
+}
+
 /// DEPRECATED. Checks parameters and returns an error if something is wrong.
 pub fn validate(context: &Context, params: &DrawParameters<'_>) -> Result<(), DrawError> {
     if params.depth.range.0 < 0.0 || params.depth.range.0 > 1.0 ||
@@ -517,17 +767,25 @@ pub fn sync(ctxt: &mut context::CommandContext<'_>, draw_parameters: &DrawParame
             dimensions: (u32, u32), primitives_types: PrimitiveType) -> Result<(), DrawError>
 {
     depth::sync_depth(ctxt, &draw_parameters.depth)?;
+    sync_depth_bounds(ctxt, draw_parameters.depth_bounds)?;
     stencil::sync_stencil(ctxt, &draw_parameters.stencil);
-    blend::sync_blending(ctxt, draw_parameters.blend)?;
+    blend::sync_blending(ctxt, draw_parameters.blend, draw_parameters.blend_per_attachment)?;
+    sync_color_logic_op(ctxt, draw_parameters.color_logic_op)?;
     sync_color_mask(ctxt, draw_parameters.color_mask);
     sync_line_width(ctxt, draw_parameters.line_width);
     sync_point_size(ctxt, draw_parameters.point_size);
     sync_polygon_mode(ctxt, draw_parameters.backface_culling, draw_parameters.polygon_mode);
     sync_clip_planes_bitmask(ctxt, draw_parameters.clip_planes_bitmask)?;
+    sync_clip_control(ctxt, draw_parameters.clip_origin, draw_parameters.clip_depth_mode)?;
     sync_multisampling(ctxt, draw_parameters.multisampling);
+    sync_min_sample_shading(ctxt, draw_parameters.min_sample_shading)?;
     sync_dithering(ctxt, draw_parameters.dithering);
-    sync_viewport_scissor(ctxt, draw_parameters.viewport, draw_parameters.scissor,
-                          dimensions);
+    if draw_parameters.viewports.is_empty() {
+        sync_viewport_scissor(ctxt, draw_parameters.viewport, draw_parameters.scissor,
+                              dimensions);
+    } else {
+        sync_viewport_array(ctxt, draw_parameters.viewports)?;
+    }
     sync_rasterizer_discard(ctxt, draw_parameters.draw_primitives)?;
     sync_queries(ctxt, draw_parameters.samples_passed_query,
                       draw_parameters.time_elapsed_query,
@@ -538,7 +796,75 @@ pub fn sync(ctxt: &mut context::CommandContext<'_>, draw_parameters: &DrawParame
     sync_provoking_vertex(ctxt, draw_parameters.provoking_vertex)?;
     sync_primitive_bounding_box(ctxt, &draw_parameters.primitive_bounding_box);
     sync_primitive_restart_index(ctxt, draw_parameters.primitive_restart_index)?;
-    sync_polygon_offset(ctxt, draw_parameters.polygon_offset);
+    sync_primitive_restart_custom_index(ctxt, draw_parameters.primitive_restart_custom_index)?;
+    sync_polygon_offset(ctxt, draw_parameters.polygon_offset)?;
+    sync_texture_barrier(ctxt, draw_parameters.texture_barrier)?;
+    sync_patch_default_levels(ctxt, draw_parameters.patch_default_outer_level,
+                              draw_parameters.patch_default_inner_level)?;
+
+    Ok(())
+}
+
+fn sync_depth_bounds(ctxt: &mut context::CommandContext<'_>, bounds: Option<(f32, f32)>)
+                     -> Result<(), DrawError>
+{
+    let (min, max) = match bounds {
+        Some(bounds) => bounds,
+        None => {
+            if ctxt.state.enabled_depth_bounds_test {
+                unsafe { ctxt.gl.Disable(gl::DEPTH_BOUNDS_TEST_EXT); }
+                ctxt.state.enabled_depth_bounds_test = false;
+            }
+            return Ok(());
+        },
+    };
+
+    if !ctxt.extensions.gl_ext_depth_bounds_test {
+        return Err(DrawError::DepthBoundsNotSupported);
+    }
+
+    if !ctxt.state.enabled_depth_bounds_test {
+        unsafe { ctxt.gl.Enable(gl::DEPTH_BOUNDS_TEST_EXT); }
+        ctxt.state.enabled_depth_bounds_test = true;
+    }
+
+    let bounds = (min.clamp(0.0, 1.0), max.clamp(0.0, 1.0));
+    if ctxt.state.depth_bounds != bounds {
+        unsafe { ctxt.gl.DepthBoundsEXT(bounds.0 as gl::types::GLclampd,
+                                        bounds.1 as gl::types::GLclampd); }
+        ctxt.state.depth_bounds = bounds;
+    }
+
+    Ok(())
+}
+
+fn sync_color_logic_op(ctxt: &mut context::CommandContext<'_>, logic_op: Option<LogicOp>)
+                       -> Result<(), DrawError>
+{
+    let logic_op = match logic_op {
+        Some(logic_op) => logic_op.to_glenum(),
+        None => {
+            if ctxt.state.enabled_color_logic_op {
+                unsafe { ctxt.gl.Disable(gl::COLOR_LOGIC_OP); }
+                ctxt.state.enabled_color_logic_op = false;
+            }
+            return Ok(());
+        },
+    };
+
+    if ctxt.version.0 == Api::GlEs {
+        return Err(DrawError::LogicOpNotSupported);
+    }
+
+    if !ctxt.state.enabled_color_logic_op {
+        unsafe { ctxt.gl.Enable(gl::COLOR_LOGIC_OP); }
+        ctxt.state.enabled_color_logic_op = true;
+    }
+
+    if ctxt.state.logic_op != logic_op {
+        unsafe { ctxt.gl.LogicOp(logic_op); }
+        ctxt.state.logic_op = logic_op;
+    }
 
     Ok(())
 }
@@ -629,9 +955,12 @@ fn sync_polygon_mode(ctxt: &mut context::CommandContext<'_>, backface_culling: B
 
 fn sync_clip_planes_bitmask(ctxt: &mut context::CommandContext<'_>, clip_planes_bitmask: u32)
                             -> Result<(), DrawError> {
+    if ctxt.state.enabled_clip_planes == clip_planes_bitmask {
+        return Ok(());
+    }
+
     unsafe {
-        let mut max_clip_planes: gl::types::GLint = 0;
-        ctxt.gl.GetIntegerv(gl::MAX_CLIP_DISTANCES, &mut max_clip_planes);
+        let max_clip_planes = ctxt.capabilities.max_clip_distances;
         for i in 0..32 {
             if clip_planes_bitmask & (1 << i) != ctxt.state.enabled_clip_planes & (1 << i) {
                 if clip_planes_bitmask & (1 << i) != 0 {
@@ -651,6 +980,39 @@ fn sync_clip_planes_bitmask(ctxt: &mut context::CommandContext<'_>, clip_planes_
     }
 }
 
+fn sync_clip_control(ctxt: &mut context::CommandContext<'_>, origin: ClipOrigin,
+                     depth_mode: ClipDepthMode) -> Result<(), DrawError>
+{
+    let origin = match origin {
+        ClipOrigin::LowerLeft => gl::LOWER_LEFT,
+        ClipOrigin::UpperLeft => gl::UPPER_LEFT,
+    };
+
+    let depth_mode = match depth_mode {
+        ClipDepthMode::NegativeOneToOne => gl::NEGATIVE_ONE_TO_ONE,
+        ClipDepthMode::ZeroToOne => gl::ZERO_TO_ONE,
+    };
+
+    if ctxt.state.clip_control == (origin, depth_mode) {
+        return Ok(());
+    }
+
+    // the values of the `_EXT` enums are the same
+    if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_clip_control {
+        unsafe { ctxt.gl.ClipControl(origin, depth_mode); }
+        ctxt.state.clip_control = (origin, depth_mode);
+
+    } else if ctxt.extensions.gl_ext_clip_control {
+        unsafe { ctxt.gl.ClipControlEXT(origin, depth_mode); }
+        ctxt.state.clip_control = (origin, depth_mode);
+
+    } else {
+        return Err(DrawError::ClipControlNotSupported);
+    }
+
+    Ok(())
+}
+
 fn sync_multisampling(ctxt: &mut context::CommandContext<'_>, multisampling: bool) {
     if ctxt.state.enabled_multisample != multisampling {
         unsafe {
@@ -665,6 +1027,49 @@ fn sync_multisampling(ctxt: &mut context::CommandContext<'_>, multisampling: boo
     }
 }
 
+fn sync_min_sample_shading(ctxt: &mut context::CommandContext<'_>, value: Option<f32>)
+                           -> Result<(), DrawError>
+{
+    let value = match value {
+        Some(value) => value,
+        None => {
+            if ctxt.state.enabled_sample_shading {
+                unsafe { ctxt.gl.Disable(gl::SAMPLE_SHADING); }
+                ctxt.state.enabled_sample_shading = false;
+            }
+            return Ok(());
+        },
+    };
+
+    let core = ctxt.version >= &Version(Api::Gl, 4, 0) ||
+               ctxt.version >= &Version(Api::GlEs, 3, 2);
+    if !core && !ctxt.extensions.gl_arb_sample_shading && !ctxt.extensions.gl_oes_sample_shading {
+        return Err(DrawError::SampleShadingNotSupported);
+    }
+
+    // the values of `GL_SAMPLE_SHADING_ARB` and `GL_SAMPLE_SHADING_OES` are the same
+    if !ctxt.state.enabled_sample_shading {
+        unsafe { ctxt.gl.Enable(gl::SAMPLE_SHADING); }
+        ctxt.state.enabled_sample_shading = true;
+    }
+
+    let value = value.clamp(0.0, 1.0);
+    if ctxt.state.min_sample_shading != value {
+        unsafe {
+            if core {
+                ctxt.gl.MinSampleShading(value);
+            } else if ctxt.extensions.gl_arb_sample_shading {
+                ctxt.gl.MinSampleShadingARB(value);
+            } else {
+                ctxt.gl.MinSampleShadingOES(value);
+            }
+        }
+        ctxt.state.min_sample_shading = value;
+    }
+
+    Ok(())
+}
+
 fn sync_dithering(ctxt: &mut context::CommandContext<'_>, dithering: bool) {
     if ctxt.state.enabled_dither != dithering {
         unsafe {
@@ -682,6 +1087,14 @@ fn sync_dithering(ctxt: &mut context::CommandContext<'_>, dithering: bool) {
 fn sync_viewport_scissor(ctxt: &mut context::CommandContext<'_>, viewport: Option<Rect>,
                          scissor: Option<Rect>, surface_dimensions: (u32, u32))
 {
+    // the non-indexed functions change the state of all the viewports
+    let force = ctxt.state.viewport_array;
+    if force {
+        ctxt.state.viewport = None;
+        ctxt.state.scissor = None;
+        ctxt.state.viewport_array = false;
+    }
+
     // viewport
     if let Some(viewport) = viewport {
         assert!(viewport.width <= ctxt.capabilities.max_viewport_dims.0 as u32,
@@ -725,14 +1138,14 @@ fn sync_viewport_scissor(ctxt: &mut context::CommandContext<'_>, viewport: Optio
                 ctxt.state.scissor = Some(scissor);
             }
 
-            if !ctxt.state.enabled_scissor_test {
+            if !ctxt.state.enabled_scissor_test || force {
                 ctxt.gl.Enable(gl::SCISSOR_TEST);
                 ctxt.state.enabled_scissor_test = true;
             }
         }
     } else {
         unsafe {
-            if ctxt.state.enabled_scissor_test {
+            if ctxt.state.enabled_scissor_test || force {
                 ctxt.gl.Disable(gl::SCISSOR_TEST);
                 ctxt.state.enabled_scissor_test = false;
             }
@@ -740,6 +1153,70 @@ fn sync_viewport_scissor(ctxt: &mut context::CommandContext<'_>, viewport: Optio
     }
 }
 
+fn sync_viewport_array(ctxt: &mut context::CommandContext<'_>,
+                       viewports: &[(Rect, Option<Rect>)]) -> Result<(), DrawError>
+{
+    let core = ctxt.version >= &Version(Api::Gl, 4, 1) || ctxt.extensions.gl_arb_viewport_array;
+    if (!core && !ctxt.extensions.gl_oes_viewport_array) ||
+       viewports.len() > ctxt.capabilities.max_viewports as usize
+    {
+        return Err(DrawError::ViewportArrayNotSupported);
+    }
+
+    // the indexed values are not cached and are always set
+    for (index, &(viewport, scissor)) in viewports.iter().enumerate() {
+        assert!(viewport.width <= ctxt.capabilities.max_viewport_dims.0 as u32,
+                "Viewport dimensions are too large");
+        assert!(viewport.height <= ctxt.capabilities.max_viewport_dims.1 as u32,
+                "Viewport dimensions are too large");
+
+        let index = index as gl::types::GLuint;
+
+        unsafe {
+            if core {
+                ctxt.gl.ViewportIndexedf(index, viewport.left as gl::types::GLfloat,
+                                         viewport.bottom as gl::types::GLfloat,
+                                         viewport.width as gl::types::GLfloat,
+                                         viewport.height as gl::types::GLfloat);
+            } else {
+                ctxt.gl.ViewportIndexedfOES(index, viewport.left as gl::types::GLfloat,
+                                            viewport.bottom as gl::types::GLfloat,
+                                            viewport.width as gl::types::GLfloat,
+                                            viewport.height as gl::types::GLfloat);
+            }
+
+            match (scissor, core) {
+                (Some(scissor), true) => {
+                    ctxt.gl.ScissorIndexed(index, scissor.left as gl::types::GLint,
+                                           scissor.bottom as gl::types::GLint,
+                                           scissor.width as gl::types::GLsizei,
+                                           scissor.height as gl::types::GLsizei);
+                    ctxt.gl.Enablei(gl::SCISSOR_TEST, index);
+                },
+                (Some(scissor), false) => {
+                    ctxt.gl.ScissorIndexedOES(index, scissor.left as gl::types::GLint,
+                                              scissor.bottom as gl::types::GLint,
+                                              scissor.width as gl::types::GLsizei,
+                                              scissor.height as gl::types::GLsizei);
+                    ctxt.gl.EnableiOES(gl::SCISSOR_TEST, index);
+                },
+                (None, true) => ctxt.gl.Disablei(gl::SCISSOR_TEST, index),
+                (None, false) => ctxt.gl.DisableiOES(gl::SCISSOR_TEST, index),
+            }
+        }
+    }
+
+    // `enabled_scissor_test` is used by the functions that only depend on the first viewport,
+    // such as clearing and blitting, and `glIsEnabled` returns the state of the first viewport
+    ctxt.state.viewport = None;
+    ctxt.state.scissor = None;
+    ctxt.state.enabled_scissor_test = viewports.first()
+                                               .map_or(false, |&(_, scissor)| scissor.is_some());
+    ctxt.state.viewport_array = true;
+
+    Ok(())
+}
+
 fn sync_rasterizer_discard(ctxt: &mut context::CommandContext<'_>, draw_primitives: bool)
                            -> Result<(), DrawError>
 {
@@ -984,6 +1461,33 @@ fn sync_primitive_restart_index(ctxt: &mut context::CommandContext<'_>,
     Ok(())
 }
 
+fn sync_primitive_restart_custom_index(ctxt: &mut context::CommandContext<'_>,
+                                       index: Option<u32>)
+                                       -> Result<(), DrawError>
+{
+    if !(ctxt.version >= &Version(Api::Gl, 3, 1)) {
+        if index.is_some() {
+            return Err(DrawError::CustomIndexRestartingNotSupported);
+        }
+
+        return Ok(());
+    }
+
+    if let Some(index) = index {
+        if ctxt.state.primitive_restart_index != index {
+            unsafe { ctxt.gl.PrimitiveRestartIndex(index); }
+            ctxt.state.primitive_restart_index = index;
+        }
+    }
+
+    if ctxt.state.enabled_primitive_restart != index.is_some() {
+        set_flag_enabled(ctxt, gl::PRIMITIVE_RESTART, index.is_some());
+        ctxt.state.enabled_primitive_restart = index.is_some();
+    }
+
+    Ok(())
+}
+
 fn set_flag_enabled(ctxt: &mut context::CommandContext<'_>, cap: gl::types::GLenum, enabled: bool) {
     if enabled {
         unsafe { ctxt.gl.Enable(cap); }
@@ -992,14 +1496,30 @@ fn set_flag_enabled(ctxt: &mut context::CommandContext<'_>, cap: gl::types::GLen
     }
 }
 
-fn sync_polygon_offset(ctxt: &mut context::CommandContext<'_>, offset: PolygonOffset) {
-    let (factor, units) = ctxt.state.polygon_offset;
+fn sync_polygon_offset(ctxt: &mut context::CommandContext<'_>, offset: PolygonOffset)
+                       -> Result<(), DrawError>
+{
+    if ctxt.version.0 == Api::GlEs && (offset.point || offset.line) {
+        return Err(DrawError::PolygonOffsetNotSupported);
+    }
+
+    let value = (offset.factor, offset.units, offset.clamp);
 
-    if ctxt.state.polygon_offset != (offset.factor, offset.units) {
+    if ctxt.state.polygon_offset != value {
         unsafe {
-            ctxt.gl.PolygonOffset(offset.factor, offset.units);
+            if offset.clamp == 0.0 {
+                ctxt.gl.PolygonOffset(offset.factor, offset.units);
+            } else if ctxt.version >= &Version(Api::Gl, 4, 6) ||
+                      ctxt.extensions.gl_arb_polygon_offset_clamp
+            {
+                ctxt.gl.PolygonOffsetClamp(offset.factor, offset.units, offset.clamp);
+            } else if ctxt.extensions.gl_ext_polygon_offset_clamp {
+                ctxt.gl.PolygonOffsetClampEXT(offset.factor, offset.units, offset.clamp);
+            } else {
+                return Err(DrawError::PolygonOffsetNotSupported);
+            }
         }
-        ctxt.state.polygon_offset = (offset.factor, offset.units);
+        ctxt.state.polygon_offset = value;
     }
 
     if offset.point != ctxt.state.enabled_polygon_offset_point {
@@ -1016,5 +1536,52 @@ fn sync_polygon_offset(ctxt: &mut context::CommandContext<'_>, offset: PolygonOf
         ctxt.state.enabled_polygon_offset_fill = offset.fill;
         set_flag_enabled(ctxt, gl::POLYGON_OFFSET_FILL, offset.fill);
     }
+
+    Ok(())
+}
+
+fn sync_texture_barrier(ctxt: &mut context::CommandContext<'_>, texture_barrier: bool)
+                        -> Result<(), DrawError>
+{
+    if !texture_barrier {
+        return Ok(());
+    }
+
+    if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_texture_barrier {
+        unsafe { ctxt.gl.TextureBarrier(); }
+    } else if ctxt.extensions.gl_nv_texture_barrier {
+        unsafe { ctxt.gl.TextureBarrierNV(); }
+    } else {
+        return Err(DrawError::TextureBarrierNotSupported);
+    }
+
+    Ok(())
 }
 
+fn sync_patch_default_levels(ctxt: &mut context::CommandContext<'_>, outer: [f32; 4],
+                             inner: [f32; 2]) -> Result<(), DrawError>
+{
+    if ctxt.state.patch_default_outer_level == outer &&
+       ctxt.state.patch_default_inner_level == inner
+    {
+        return Ok(());
+    }
+
+    let supported = ctxt.version >= &Version(Api::Gl, 4, 0) ||
+                    ctxt.extensions.gl_arb_tessellation_shader;
+    if !supported {
+        return Err(DrawError::PatchDefaultLevelsNotSupported);
+    }
+
+    if ctxt.state.patch_default_outer_level != outer {
+        unsafe { ctxt.gl.PatchParameterfv(gl::PATCH_DEFAULT_OUTER_LEVEL, outer.as_ptr()); }
+        ctxt.state.patch_default_outer_level = outer;
+    }
+
+    if ctxt.state.patch_default_inner_level != inner {
+        unsafe { ctxt.gl.PatchParameterfv(gl::PATCH_DEFAULT_INNER_LEVEL, inner.as_ptr()); }
+        ctxt.state.patch_default_inner_level = inner;
+    }
+
+    Ok(())
+}
@@ -64,6 +64,7 @@ use crate::GlObject;
 use crate::TextureExt;
 
 use crate::texture::CubeLayer;
+use crate::texture::Dimensions;
 use crate::texture::TextureAnyImage;
 use crate::texture::TextureAnyMipmap;
 use crate::texture::TextureKind;
@@ -85,6 +86,28 @@ pub fn is_dimensions_mismatch_supported<C: ?Sized>(context: &C) -> bool where C:
     context.get_extensions().gl_arb_framebuffer_object
 }
 
+/// Returns true if the backend supports layered framebuffers, in other words framebuffers whose
+/// attachments are all the layers of a mipmap level of an array texture, a cubemap or a 3D
+/// texture.
+///
+/// Layered framebuffers are supported on OpenGL 3.2 and above, and on OpenGL ES 3.2 and above.
+#[inline]
+pub fn is_layered_attachments_supported<C: ?Sized>(context: &C) -> bool
+                                        where C: CapabilitiesSource
+{
+    context.get_version() >= &Version(Api::Gl, 3, 2) ||
+    context.get_version() >= &Version(Api::GlEs, 3, 2)
+}
+
+/// Returns true if the backend supports multiview framebuffers, in other words framebuffers
+/// whose attachments are several layers of a 2D array texture that are drawn to at once.
+///
+/// Multiview framebuffers require `GL_OVR_multiview`.
+#[inline]
+pub fn is_multiview_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+    context.get_extensions().gl_ovr_multiview
+}
+
 /// Represents the attachments to use for an OpenGL framebuffer.
 #[derive(Clone)]
 pub enum FramebufferAttachments<'a> {
@@ -94,6 +117,13 @@ pub enum FramebufferAttachments<'a> {
     /// Each attachment is a layer of images.
     Layered(FramebufferSpecificAttachments<LayeredAttachment<'a>>),
 
+    /// Each attachment is the first `num_views` layers of a 2D array texture, and each layer
+    /// is a view.
+    Multiview {
+        attachments: FramebufferSpecificAttachments<LayeredAttachment<'a>>,
+        num_views: u32,
+    },
+
     /// An empty framebuffer.
     Empty {
         width: u32,
@@ -122,12 +152,105 @@ impl<'a> RegularAttachment<'a> {
             &RegularAttachment::RenderBuffer(rb) => rb.kind(),
         }
     }
+
+    /// Returns true if the attachment stores sRGB values.
+    #[inline]
+    pub fn is_srgb(&self) -> bool {
+        match self {
+            &RegularAttachment::Texture(t) => t.get_texture().is_srgb(),
+            &RegularAttachment::RenderBuffer(_) => false,
+        }
+    }
+
+    /// Returns the number of samples of the attachment, or `None` if it isn't multisampled.
+    #[inline]
+    pub fn get_samples(&self) -> Option<u32> {
+        match self {
+            &RegularAttachment::Texture(t) => t.get_samples(),
+            &RegularAttachment::RenderBuffer(rb) => rb.get_samples(),
+        }
+    }
 }
 
 /// Describes a single layered framebuffer attachment.
 #[derive(Copy, Clone)]
 pub struct LayeredAttachment<'a>(TextureAnyMipmap<'a>);
 
+impl<'a> LayeredAttachment<'a> {
+    /// Builds an attachment made of all the layers of a mipmap level.
+    #[inline]
+    pub fn new(mipmap: TextureAnyMipmap<'a>) -> LayeredAttachment<'a> {
+        LayeredAttachment(mipmap)
+    }
+
+    /// Returns the kind of attachment (float, integral, unsigned, depth, stencil, depthstencil).
+    #[inline]
+    pub fn kind(&self) -> TextureKind {
+        self.0.get_texture().kind()
+    }
+
+    /// Returns the number of layers of the attachment, or `None` if the texture can't be
+    /// attached as a layered attachment. Each face of a cubemap is a layer.
+    #[inline]
+    pub fn get_layers(&self) -> Option<u32> {
+        match self.0.get_texture().get_texture_type() {
+            Dimensions::Texture1dArray { array_size, .. } => Some(array_size),
+            Dimensions::Texture2dArray { array_size, .. } => Some(array_size),
+            Dimensions::Texture2dMultisampleArray { array_size, .. } => Some(array_size),
+            Dimensions::Texture3d { .. } => self.0.get_depth(),
+            Dimensions::Cubemap { .. } => Some(6),
+            Dimensions::CubemapArray { array_size, .. } => Some(6 * array_size),
+            _ => None,
+        }
+    }
+
+    /// Returns the first layer of the attachment. This is the layer that is read when reading
+    /// from or blitting a layered framebuffer.
+    #[inline]
+    pub fn first_image(&self) -> TextureAnyImage<'a> {
+        let cube_layer = match self.0.get_texture().get_texture_type() {
+            Dimensions::Cubemap { .. } | Dimensions::CubemapArray { .. } =>
+                Some(CubeLayer::PositiveX),
+            _ => None,
+        };
+
+        self.0.first_layer().into_image(cube_layer).unwrap()
+    }
+
+    /// Checks that the attachment can be layered and has one of the given kinds, and updates
+    /// the number of layers of the framebuffer. Multiview attachments must also be 2D array
+    /// textures.
+    fn check(&self, kinds: &[TextureKind], multiview: bool, layers: &mut Option<u32>)
+             -> Result<(), ValidationError>
+    {
+        if multiview {
+            match self.0.get_texture().get_texture_type() {
+                Dimensions::Texture2dArray { .. } => (),
+                _ => return Err(ValidationError::InvalidMultiviewAttachment),
+            }
+        }
+
+        let num_layers = match self.get_layers() {
+            Some(l) => l,
+            None => return Err(ValidationError::InvalidLayeredAttachment),
+        };
+
+        if !kinds.contains(&self.kind()) {
+            return Err(ValidationError::InvalidLayeredAttachment);
+        }
+
+        *layers = Some(layers.map_or(num_layers, |l| cmp::min(l, num_layers)));
+        Ok(())
+    }
+}
+
+impl<'a> From<TextureAnyMipmap<'a>> for LayeredAttachment<'a> {
+    #[inline]
+    fn from(mipmap: TextureAnyMipmap<'a>) -> LayeredAttachment<'a> {
+        LayeredAttachment(mipmap)
+    }
+}
+
 /// Depth and/or stencil attachment to use.
 #[derive(Copy, Clone)]
 pub enum DepthStencilAttachments<T> {
@@ -167,7 +290,10 @@ impl<'a> FramebufferAttachments<'a> {
     {
         match self {
             FramebufferAttachments::Regular(a) => FramebufferAttachments::validate_regular(context, a),
-            FramebufferAttachments::Layered(a) => FramebufferAttachments::validate_layered(context, a),
+            FramebufferAttachments::Layered(a) =>
+                FramebufferAttachments::validate_layered(context, a, None),
+            FramebufferAttachments::Multiview { attachments, num_views } =>
+                FramebufferAttachments::validate_layered(context, attachments, Some(num_views)),
 
             FramebufferAttachments::Empty { width, height, layers, samples, fixed_samples } => {
                 if context.get_version() >= &Version(Api::Gl, 4, 3) ||
@@ -203,8 +329,12 @@ impl<'a> FramebufferAttachments<'a> {
                         },
                         dimensions: (width, height),
                         layers,
+                        num_views: None,
+                        samples,
+                        color_kinds: SmallVec::new(),
                         depth_buffer_bits: None,
                         stencil_buffer_bits: None,
+                        srgb_color_attachments: false,
                         marker: PhantomData,
                     })
 
@@ -216,11 +346,53 @@ impl<'a> FramebufferAttachments<'a> {
     }
 
     fn validate_layered<C: ?Sized>(context: &C, FramebufferSpecificAttachments { colors, depth_stencil }:
-                           FramebufferSpecificAttachments<LayeredAttachment<'a>>)
+                           FramebufferSpecificAttachments<LayeredAttachment<'a>>,
+                           num_views: Option<u32>)
                            -> Result<ValidatedAttachments<'a>, ValidationError>
                            where C: CapabilitiesSource
     {
-        // TODO: make sure that all attachments are layered
+        let multiview = num_views.is_some();
+
+        if multiview {
+            if !is_multiview_supported(context) {
+                return Err(ValidationError::MultiviewNotSupported);
+            }
+        } else if !is_layered_attachments_supported(context) {
+            return Err(ValidationError::LayeredAttachmentsNotSupported);
+        }
+
+        // the framebuffer has as many layers as the attachment with the fewest layers
+        let mut layers = None;
+        for &(_, ref attachment) in colors.iter() {
+            attachment.check(&[TextureKind::Float, TextureKind::Integral, TextureKind::Unsigned],
+                             multiview, &mut layers)?;
+        }
+        match depth_stencil {
+            DepthStencilAttachments::None => (),
+            DepthStencilAttachments::DepthAttachment(ref d) => {
+                d.check(&[TextureKind::Depth], multiview, &mut layers)?;
+            },
+            DepthStencilAttachments::StencilAttachment(ref s) => {
+                s.check(&[TextureKind::Stencil], multiview, &mut layers)?;
+            },
+            DepthStencilAttachments::DepthAndStencilAttachments(ref d, ref s) => {
+                d.check(&[TextureKind::Depth], multiview, &mut layers)?;
+                s.check(&[TextureKind::Stencil], multiview, &mut layers)?;
+            },
+            DepthStencilAttachments::DepthStencilAttachment(ref ds) => {
+                ds.check(&[TextureKind::DepthStencil], multiview, &mut layers)?;
+            },
+        }
+
+        // the views are the first layers of each attachment
+        if let Some(num_views) = num_views {
+            if num_views == 0 ||
+               num_views > context.get_capabilities().max_views as u32 ||
+               num_views > layers.unwrap_or(0)
+            {
+                return Err(ValidationError::UnsupportedNumViews);
+            }
+        }
 
         macro_rules! handle_tex {
             ($tex:ident, $dim:ident, $samples:ident, $num_bits:ident) => ({
@@ -263,12 +435,20 @@ impl<'a> FramebufferAttachments<'a> {
                     },
                 }
 
-                RawAttachment::Texture {
-                    texture: $tex.get_texture().get_id(),
-                    bind_point: $tex.get_texture().get_bind_point(),
-                    layer: None,
-                    level: $tex.get_level(),
-                    cubemap_layer: None,
+                if let Some(num_views) = num_views {
+                    RawAttachment::MultiviewTexture {
+                        texture: $tex.get_texture().get_id(),
+                        level: $tex.get_level(),
+                        num_views,
+                    }
+                } else {
+                    RawAttachment::Texture {
+                        texture: $tex.get_texture().get_id(),
+                        bind_point: $tex.get_texture().get_bind_point(),
+                        layer: None,
+                        level: $tex.get_level(),
+                        cubemap_layer: None,
+                    }
                 }
             });
         }
@@ -337,12 +517,21 @@ impl<'a> FramebufferAttachments<'a> {
             return Err(ValidationError::EmptyFramebufferObjectsNotSupported);
         };
 
+        let srgb_color_attachments = colors.iter()
+                                           .any(|&(_, LayeredAttachment(ref a))| a.get_texture().is_srgb());
+        let color_kinds = colors.iter().map(|&(_, LayeredAttachment(ref a))| a.get_texture().kind())
+                                .collect();
+
         Ok(ValidatedAttachments {
             raw: raw_attachments,
             dimensions,
-            layers: None,       // FIXME: count layers
+            layers: if multiview { None } else { layers },
+            num_views,
+            samples: samples.filter(|&s| s != 0),
+            color_kinds,
             depth_buffer_bits: depth_bits,
             stencil_buffer_bits: stencil_bits,
+            srgb_color_attachments,
             marker: PhantomData,
         })
     }
@@ -405,7 +594,11 @@ impl<'a> FramebufferAttachments<'a> {
 
         macro_rules! handle_rb {
             ($rb:ident, $dim:ident, $samples:ident, $num_bits:ident) => ({
-                $num_bits = Some(24);       // FIXME: totally arbitrary
+                let (depth_bits, stencil_bits) = $rb.get_depth_stencil_bits();
+                $num_bits = Some(match $rb.kind() {
+                    TextureKind::Stencil => stencil_bits,
+                    _ => depth_bits,
+                });
                 handle_rb!($rb, $dim, $samples)
             });
 
@@ -523,12 +716,19 @@ impl<'a> FramebufferAttachments<'a> {
             return Err(ValidationError::EmptyFramebufferObjectsNotSupported);
         };
 
+        let srgb_color_attachments = colors.iter().any(|&(_, ref a)| a.is_srgb());
+        let color_kinds = colors.iter().map(|&(_, ref a)| a.kind()).collect();
+
         Ok(ValidatedAttachments {
             raw: raw_attachments,
             dimensions,
             layers: None,
+            num_views: None,
+            samples: samples.filter(|&s| s != 0),
+            color_kinds,
             depth_buffer_bits: depth_bits,
             stencil_buffer_bits: stencil_bits,
+            srgb_color_attachments,
             marker: PhantomData,
         })
     }
@@ -540,8 +740,12 @@ pub struct ValidatedAttachments<'a> {
     raw: RawAttachments,
     dimensions: (u32, u32),
     layers: Option<u32>,
+    num_views: Option<u32>,
+    samples: Option<u32>,
+    color_kinds: SmallVec<[TextureKind; 4]>,
     depth_buffer_bits: Option<u16>,
     stencil_buffer_bits: Option<u16>,
+    srgb_color_attachments: bool,
     marker: PhantomData<&'a ()>,
 }
 
@@ -552,12 +756,38 @@ impl<'a> ValidatedAttachments<'a> {
         self.layers.is_some()
     }
 
+    /// Returns the number of views if the framebuffer is a multiview framebuffer.
+    #[inline]
+    pub fn get_num_views(&self) -> Option<u32> {
+        self.num_views
+    }
+
     /// Returns the dimensions that the framebuffer will have if you use these attachments.
     #[inline]
     pub fn get_dimensions(&self) -> (u32, u32) {
         self.dimensions
     }
 
+    /// Returns the indices of the color attachments, for example `0` for
+    /// `GL_COLOR_ATTACHMENT0`.
+    #[inline]
+    pub fn get_color_attachment_indices(&self) -> impl Iterator<Item = u32> + '_ {
+        self.raw.color.iter().map(|&(index, _)| index)
+    }
+
+    /// Returns the kind of each color attachment, in the same order as
+    /// `get_color_attachment_indices`.
+    #[inline]
+    pub fn get_color_attachment_kinds(&self) -> &[TextureKind] {
+        &self.color_kinds
+    }
+
+    /// Returns the number of samples of the attachments, or `None` if they aren't multisampled.
+    #[inline]
+    pub fn get_samples(&self) -> Option<u32> {
+        self.samples
+    }
+
     /// Returns the number of bits of precision of the depth buffer, or `None` if there is no
     /// depth buffer. Also works for depth-stencil buffers.
     #[inline]
@@ -571,6 +801,26 @@ impl<'a> ValidatedAttachments<'a> {
     pub fn get_stencil_buffer_bits(&self) -> Option<u16> {
         self.stencil_buffer_bits
     }
+
+    /// Returns `true` if at least one of the color attachments is an sRGB texture.
+    #[inline]
+    pub fn has_srgb_color_attachments(&self) -> bool {
+        self.srgb_color_attachments
+    }
+
+    /// Returns the ids of the textures that are attached, with their attached mipmap level.
+    pub(crate) fn get_attached_textures(&self) -> SmallVec<[(gl::types::GLuint, u32); 4]> {
+        self.raw.color.iter().map(|(_, attachment)| attachment)
+            .chain(self.raw.depth.iter())
+            .chain(self.raw.stencil.iter())
+            .chain(self.raw.depth_stencil.iter())
+            .filter_map(|attachment| match *attachment {
+                RawAttachment::Texture { texture, level, .. } => Some((texture, level)),
+                RawAttachment::MultiviewTexture { texture, level, .. } => Some((texture, level)),
+                RawAttachment::RenderBuffer(_) => None,
+            })
+            .collect()
+    }
 }
 
 /// An error that can happen while validating attachments.
@@ -598,6 +848,23 @@ pub enum ValidationError {
         /// Number of attachments that were given.
         obtained: usize,
     },
+
+    /// You requested a layered framebuffer, but they are not supported.
+    LayeredAttachmentsNotSupported,
+
+    /// A layered attachment is not an array texture, a cubemap or a 3D texture, or its format
+    /// can't be used for the attachment point.
+    InvalidLayeredAttachment,
+
+    /// You requested a multiview framebuffer, but they are not supported.
+    MultiviewNotSupported,
+
+    /// An attachment of a multiview framebuffer is not a 2D array texture.
+    InvalidMultiviewAttachment,
+
+    /// The number of views of a multiview framebuffer is 0, is greater than `max_views`, or
+    /// is greater than the number of layers of an attachment.
+    UnsupportedNumViews,
 }
 
 impl fmt::Display for ValidationError {
@@ -614,6 +881,16 @@ impl fmt::Display for ValidationError {
                 "All attachments must have the same number of samples",
             TooManyColorAttachments {..} =>
                 "Backends only support a certain number of color attachments",
+            LayeredAttachmentsNotSupported =>
+                "You requested a layered framebuffer, but they are not supported",
+            InvalidLayeredAttachment =>
+                "A layered attachment can't be layered or can't be used for its attachment point",
+            MultiviewNotSupported =>
+                "You requested a multiview framebuffer, but they are not supported",
+            InvalidMultiviewAttachment =>
+                "An attachment of a multiview framebuffer is not a 2D array texture",
+            UnsupportedNumViews =>
+                "The number of views of the multiview framebuffer is not supported",
         };
         match self {
             TooManyColorAttachments{ ref maximum, ref obtained } =>
@@ -626,6 +903,73 @@ impl fmt::Display for ValidationError {
 
 impl Error for ValidationError {}
 
+/// Returns true if the backend supports programmable sample locations, in other words
+/// `GL_ARB_sample_locations` or `GL_NV_sample_locations`.
+#[inline]
+pub fn is_sample_locations_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+    context.get_extensions().gl_arb_sample_locations ||
+    context.get_extensions().gl_nv_sample_locations
+}
+
+/// Describes the table of programmable sample locations of a framebuffer.
+///
+/// When the table is larger than the number of samples, the locations are repeated over a grid
+/// of `pixel_grid_width * pixel_grid_height` pixels. The location of sample `s` of the pixel at
+/// `(x, y)` is then at index
+/// `((x % pixel_grid_width) + (y % pixel_grid_height) * pixel_grid_width) * samples + s`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SampleLocationsInfo {
+    /// Number of samples per pixel of the framebuffer.
+    pub samples: u32,
+
+    /// Number of bits of sub-pixel precision of the locations.
+    pub subpixel_bits: u32,
+
+    /// Width of the grid of pixels over which the table is repeated.
+    pub pixel_grid_width: u32,
+
+    /// Height of the grid of pixels over which the table is repeated.
+    pub pixel_grid_height: u32,
+
+    /// Maximum number of locations in the table.
+    pub table_size: u32,
+}
+
+/// Error that can happen when changing the sample locations of a framebuffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SampleLocationsError {
+    /// Programmable sample locations are not supported by the backend.
+    NotSupported,
+
+    /// More locations were given than the table can hold.
+    TooManyLocations {
+        /// Size of the table.
+        maximum: usize,
+        /// Number of locations that were given.
+        obtained: usize,
+    },
+}
+
+impl fmt::Display for SampleLocationsError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::SampleLocationsError::*;
+        let desc = match self {
+            NotSupported =>
+                "Programmable sample locations are not supported by the backend",
+            TooManyLocations {..} =>
+                "More locations were given than the table can hold",
+        };
+        match self {
+            TooManyLocations { ref maximum, ref obtained } =>
+                write!(fmt, "{}: found {}, maximum: {}", desc, obtained, maximum),
+            _ =>
+                fmt.write_str(desc),
+        }
+    }
+}
+
+impl Error for SampleLocationsError {}
+
 /// Data structure stored in the hashmap.
 ///
 /// These attachments are guaranteed to be valid.
@@ -663,6 +1007,16 @@ enum RawAttachment {
         cubemap_layer: Option<CubeLayer>,
     },
 
+    /// The first layers of a 2D array texture, each layer being a view.
+    MultiviewTexture {
+        // id of the texture
+        texture: gl::types::GLuint,
+        // mipmap level
+        level: u32,
+        // number of views, starting from the first layer
+        num_views: u32,
+    },
+
     /// A renderbuffer with its ID.
     RenderBuffer(gl::types::GLuint),
 }
@@ -735,7 +1089,8 @@ impl FramebuffersContainer {
     #[inline]
     pub fn purge_texture(ctxt: &mut CommandContext<'_>, texture: gl::types::GLuint) {
         FramebuffersContainer::purge_if(ctxt, |a| {
-            matches!(a, &RawAttachment::Texture { texture: id, .. } if id == texture)
+            matches!(a, &RawAttachment::Texture { texture: id, .. } |
+                        &RawAttachment::MultiviewTexture { texture: id, .. } if id == texture)
         });
     }
 
@@ -826,6 +1181,23 @@ impl FramebuffersContainer {
         unsafe { ctxt.gl.ReadBuffer(read_buffer) };     // TODO: cache
     }
 
+    /// Changes the buffers of the default framebuffer that are the target of the draw commands,
+    /// for example `GL_BACK_LEFT` or `GL_BACK_RIGHT` with a stereo context.
+    #[inline]
+    pub fn set_default_framebuffer_draw_buffer(ctxt: &mut CommandContext<'_>,
+                                               draw_buffer: gl::types::GLenum)
+    {
+        if ctxt.state.default_framebuffer_draw == draw_buffer {
+            return;
+        }
+
+        // only desktop OpenGL supports stereo contexts, which are the only ones where we
+        // use something else than `GL_BACK`
+        unsafe { bind_framebuffer(ctxt, 0, true, false) };
+        unsafe { ctxt.gl.DrawBuffer(draw_buffer) };
+        ctxt.state.default_framebuffer_draw = draw_buffer;
+    }
+
     /// Binds a framebuffer to `GL_READ_FRAMEBUFFER` or `GL_FRAMEBUFFER` so that it becomes the
     /// target of `glReadPixels`, `glCopyTexImage2D`, etc.
     ///
@@ -926,6 +1298,95 @@ impl FramebuffersContainer {
         framebuffers.insert(attachments.raw.clone(), new_fbo);
         new_fbo_id
     }
+
+    /// Queries the table of programmable sample locations of the framebuffer that corresponds
+    /// to these attachments.
+    ///
+    /// # Unsafety
+    ///
+    /// After calling this function, you **must** make sure to call `purge_texture`
+    /// and/or `purge_renderbuffer` when one of the attachment is destroyed.
+    pub unsafe fn get_sample_locations_info(ctxt: &mut CommandContext<'_>,
+                                            attachments: &ValidatedAttachments<'_>)
+                                            -> Result<SampleLocationsInfo, SampleLocationsError>
+    {
+        if !ctxt.extensions.gl_arb_sample_locations && !ctxt.extensions.gl_nv_sample_locations {
+            return Err(SampleLocationsError::NotSupported);
+        }
+
+        let fb = FramebuffersContainer::get_framebuffer_for_drawing(ctxt, Some(attachments));
+        bind_framebuffer(ctxt, fb, true, false);
+
+        // the values of the `_ARB` and `_NV` constants are the same
+        let get = |ctxt: &mut CommandContext<'_>, name| {
+            let mut value = 0;
+            ctxt.gl.GetIntegerv(name, &mut value);
+            value as u32
+        };
+
+        Ok(SampleLocationsInfo {
+            samples: get(ctxt, gl::SAMPLES),
+            subpixel_bits: get(ctxt, gl::SAMPLE_LOCATION_SUBPIXEL_BITS_ARB),
+            pixel_grid_width: get(ctxt, gl::SAMPLE_LOCATION_PIXEL_GRID_WIDTH_ARB),
+            pixel_grid_height: get(ctxt, gl::SAMPLE_LOCATION_PIXEL_GRID_HEIGHT_ARB),
+            table_size: get(ctxt, gl::PROGRAMMABLE_SAMPLE_LOCATION_TABLE_SIZE_ARB),
+        })
+    }
+
+    /// Sets the programmable sample locations of the framebuffer that corresponds to these
+    /// attachments, starting at the first entry of the table. If `locations` is empty, the
+    /// framebuffer goes back to the default locations.
+    ///
+    /// # Unsafety
+    ///
+    /// After calling this function, you **must** make sure to call `purge_texture`
+    /// and/or `purge_renderbuffer` when one of the attachment is destroyed.
+    pub unsafe fn set_sample_locations(ctxt: &mut CommandContext<'_>,
+                                       attachments: &ValidatedAttachments<'_>,
+                                       locations: &[(f32, f32)])
+                                       -> Result<(), SampleLocationsError>
+    {
+        let info = FramebuffersContainer::get_sample_locations_info(ctxt, attachments)?;
+
+        if locations.len() > info.table_size as usize {
+            return Err(SampleLocationsError::TooManyLocations {
+                maximum: info.table_size as usize,
+                obtained: locations.len(),
+            });
+        }
+
+        // `get_sample_locations_info` has bound the framebuffer
+        let programmable = if locations.is_empty() { gl::FALSE } else { gl::TRUE };
+        let pixel_grid = if locations.len() > info.samples as usize { gl::TRUE } else { gl::FALSE };
+        ctxt.gl.FramebufferParameteri(gl::DRAW_FRAMEBUFFER,
+                                      gl::FRAMEBUFFER_PROGRAMMABLE_SAMPLE_LOCATIONS_ARB,
+                                      programmable as gl::types::GLint);
+        ctxt.gl.FramebufferParameteri(gl::DRAW_FRAMEBUFFER,
+                                      gl::FRAMEBUFFER_SAMPLE_LOCATION_PIXEL_GRID_ARB,
+                                      pixel_grid as gl::types::GLint);
+
+        if locations.is_empty() {
+            return Ok(());
+        }
+
+        let mut values = Vec::with_capacity(locations.len() * 2);
+        for &(x, y) in locations {
+            values.push(x);
+            values.push(y);
+        }
+
+        if ctxt.extensions.gl_arb_sample_locations {
+            ctxt.gl.FramebufferSampleLocationsfvARB(gl::DRAW_FRAMEBUFFER, 0,
+                                                    locations.len() as gl::types::GLsizei,
+                                                    values.as_ptr());
+        } else {
+            ctxt.gl.FramebufferSampleLocationsfvNV(gl::DRAW_FRAMEBUFFER, 0,
+                                                   locations.len() as gl::types::GLsizei,
+                                                   values.as_ptr());
+        }
+
+        Ok(())
+    }
 }
 
 impl Drop for FramebuffersContainer {
@@ -1407,7 +1868,9 @@ unsafe fn attach(ctxt: &mut CommandContext<'_>, slot: gl::types::GLenum,
                         ctxt.gl.NamedFramebufferTextureEXT(id, slot, tex_id,
                                                            level as gl::types::GLint);
 
-                    } else if ctxt.version >= &Version(Api::Gl, 3, 2) {
+                    } else if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                              ctxt.version >= &Version(Api::GlEs, 3, 2)
+                    {
                         bind_framebuffer(ctxt, id, true, false);
                         ctxt.gl.FramebufferTexture(gl::DRAW_FRAMEBUFFER,
                                                    slot, tex_id, level as gl::types::GLint);
@@ -1462,7 +1925,9 @@ unsafe fn attach(ctxt: &mut CommandContext<'_>, slot: gl::types::GLenum,
                         ctxt.gl.NamedFramebufferTextureEXT(id, slot, tex_id,
                                                            level as gl::types::GLint);
 
-                    } else if ctxt.version >= &Version(Api::Gl, 3, 2) {
+                    } else if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                              ctxt.version >= &Version(Api::GlEs, 3, 2)
+                    {
                         bind_framebuffer(ctxt, id, true, false);
                         ctxt.gl.FramebufferTexture(gl::DRAW_FRAMEBUFFER,
                                                    slot, tex_id, level as gl::types::GLint);
@@ -1477,6 +1942,13 @@ unsafe fn attach(ctxt: &mut CommandContext<'_>, slot: gl::types::GLenum,
             }
         },
 
+        RawAttachment::MultiviewTexture { texture: tex_id, level, num_views } => {
+            bind_framebuffer(ctxt, id, true, false);
+            ctxt.gl.FramebufferTextureMultiviewOVR(gl::DRAW_FRAMEBUFFER, slot, tex_id,
+                                                   level as gl::types::GLint, 0,
+                                                   num_views as gl::types::GLsizei);
+        },
+
         // renderbuffers are straight-forward
         RawAttachment::RenderBuffer(renderbuffer) => {
             if ctxt.version >= &Version(Api::Gl, 4, 5) ||
@@ -8,13 +8,17 @@ use crate::context::Context;
 use crate::DrawParameters;
 use crate::FboAttachments;
 use crate::Rect;
+use crate::{BlitError, BlitMask, CopyToCpuOptions, CpuImage, PendingRead, ReadError};
+use crate::texture::Texture2dDataSink;
 use crate::BlitTarget;
 use crate::ContextExt;
 use crate::ToGlEnum;
 use crate::ops;
 use crate::uniforms;
 
-use crate::{Program, Surface};
+use crate::Surface;
+use crate::Program;
+use crate::program::ProgramRef;
 use crate::DrawError;
 
 use crate::{fbo, gl};
@@ -74,11 +78,11 @@ impl Surface for DefaultFramebuffer {
         self.context.capabilities().stencil_bits
     }
 
-    fn draw<'a, 'b, V, I, U>(&mut self, vertex_buffer: V,
-                         index_buffer: I, program: &Program, uniforms: &U,
+    fn draw<'a, 'b, 'p, V, I, U, P>(&mut self, vertex_buffer: V,
+                         index_buffer: I, program: P, uniforms: &U,
                          draw_parameters: &DrawParameters<'_>) -> Result<(), DrawError>
                          where I: Into<index::IndicesSource<'a>>, U: uniforms::Uniforms,
-                         V: vertex::MultiVerticesSource<'b>
+                         V: vertex::MultiVerticesSource<'b>, P: Into<ProgramRef<'p>>
     {
         if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
                 draw_parameters.depth.write)
@@ -100,10 +104,24 @@ impl Surface for DefaultFramebuffer {
         }
 
         // TODO: wrong attachment
-        ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program,
+        ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program.into(),
                   uniforms, draw_parameters, self.get_dimensions())
     }
 
+    fn draw_mesh_tasks<U>(&mut self, count: u32, program: &Program, uniforms: &U,
+                          draw_parameters: &DrawParameters<'_>) -> Result<(), DrawError>
+                          where U: uniforms::Uniforms
+    {
+        if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
+                draw_parameters.depth.write)
+        {
+            return Err(DrawError::NoDepthBuffer);
+        }
+
+        ops::draw_mesh_tasks(&self.context, None, count, program, uniforms,
+                             draw_parameters, self.get_dimensions())
+    }
+
     #[inline]
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
                      filter: uniforms::MagnifySamplerFilter) where S: Surface
@@ -136,6 +154,73 @@ impl Surface for DefaultFramebuffer {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
     }
+
+    #[inline]
+    fn blit<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget, mask: BlitMask,
+               filter: uniforms::MagnifySamplerFilter) -> Result<(), BlitError> where S: Surface
+    {
+        target.blit_buffers_from_frame(source_rect, target_rect, mask, filter)
+    }
+
+    #[inline]
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               mask: BlitMask, filter: uniforms::MagnifySamplerFilter)
+                               -> Result<(), BlitError>
+    {
+        ops::blit_buffers(&self.context, None, self.get_attachments(), source_rect, target_rect,
+                          mask, filter)
+    }
+
+    #[inline]
+    fn blit_buffers_from_simple_framebuffer(&self, source: &framebuffer::SimpleFrameBuffer<'_>,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            mask: BlitMask, filter: uniforms::MagnifySamplerFilter)
+                                            -> Result<(), BlitError>
+    {
+        ops::blit_buffers(&self.context, source.get_attachments(), self.get_attachments(),
+                          source_rect, target_rect, mask, filter)
+    }
+
+    #[inline]
+    fn blit_buffers_from_multioutput_framebuffer(&self,
+                                                 source: &framebuffer::MultiOutputFrameBuffer<'_>,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 mask: BlitMask,
+                                                 filter: uniforms::MagnifySamplerFilter)
+                                                 -> Result<(), BlitError>
+    {
+        ops::blit_buffers(&self.context, source.get_attachments(), self.get_attachments(),
+                          source_rect, target_rect, mask, filter)
+    }
+
+    fn copy_region_to_cpu_with_options(&self, rect: &Rect, options: &CopyToCpuOptions)
+                                       -> Result<CpuImage, ReadError>
+    {
+        let read_buffer = match self.attachment {
+            DefaultFramebufferAttachment::BackLeft => gl::BACK_LEFT,
+            DefaultFramebufferAttachment::BackRight => gl::BACK_RIGHT,
+            DefaultFramebufferAttachment::FrontLeft => gl::FRONT_LEFT,
+            DefaultFramebufferAttachment::FrontRight => gl::FRONT_RIGHT,
+        };
+
+        let mut ctxt = self.context.make_current();
+        ops::copy_to_cpu(&mut ctxt, ops::Source::DefaultFramebuffer(read_buffer),
+                         self.context.capabilities().srgb, self.get_dimensions(), rect, options)
+    }
+
+    fn read_async<T>(&self, rect: &Rect) -> Result<PendingRead<T>, ReadError>
+                     where T: Texture2dDataSink<(u8, u8, u8, u8)>
+    {
+        let read_buffer = match self.attachment {
+            DefaultFramebufferAttachment::BackLeft => gl::BACK_LEFT,
+            DefaultFramebufferAttachment::BackRight => gl::BACK_RIGHT,
+            DefaultFramebufferAttachment::FrontLeft => gl::FRONT_LEFT,
+            DefaultFramebufferAttachment::FrontRight => gl::FRONT_RIGHT,
+        };
+
+        ops::read_async(&self.context, ops::Source::DefaultFramebuffer(read_buffer),
+                        self.get_dimensions(), rect)
+    }
 }
 
 impl FboAttachments for DefaultFramebuffer {
@@ -54,6 +54,12 @@ Some restrictions apply when you use framebuffers:
    framebuffer that you are using. This is not enforced by glium as it depends on your shader's
    source code.
 
+# Render targets
+
+A common pattern is to render a scene from a secondary camera into a texture, and then to sample
+this texture when drawing the main scene. The `RenderTarget` struct owns a color texture and a
+depth buffer whose dimensions follow the dimensions of the window.
+
 # Empty framebuffers
 
 Modern OpenGL implementations support empty framebuffers. This is handled by glium with the
@@ -61,15 +67,76 @@ Modern OpenGL implementations support empty framebuffers. This is handled by gli
 
 You can check whether they are supported by calling `EmptyFrameBuffer::is_supported(&display)`.
 
+An empty framebuffer has no attachment, but its dimensions, number of layers and number of
+samples are chosen when it is created. Drawing to it still runs the fragment shader, which is
+useful for passes that only write to images or buffers, or that only count samples with a
+`SamplesPassedQuery`.
+
+# Sample locations
+
+With `GL_ARB_sample_locations` or `GL_NV_sample_locations`, the locations of the samples of a
+multisample `SimpleFrameBuffer` or `EmptyFrameBuffer` can be chosen with
+`set_sample_locations`. Use `is_sample_locations_supported` to check for support.
+
 # Layered framebuffers
 
-Not yet supported
+A layered framebuffer uses all the layers of a mipmap level of an array texture, a cubemap, a
+cubemap array or a 3D texture. A geometry shader chooses the layer of each primitive by writing
+to `gl_Layer`, which allows for example drawing the six faces of the shadow map of a point
+light in a single draw command. Each face of a cubemap is a layer.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+# let shadow_map: glium::texture::DepthCubemap = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+let framebuffer = glium::framebuffer::SimpleFrameBuffer::layered_depth_only(&display,
+                                                            shadow_map.main_level()).unwrap();
+```
+
+All the attachments of a layered framebuffer must be layered, and the framebuffer has as many
+layers as the attachment with the fewest layers. Reading from or blitting a layered framebuffer
+uses its first layer.
+
+Layered framebuffers require OpenGL 3.2 or OpenGL ES 3.2. You can check whether they are
+supported with `is_layered_attachments_supported`.
+
+# Multiview framebuffers
+
+A multiview framebuffer uses the first layers of a 2D array texture as views. Each draw
+command is executed once per view, and the vertex shader can use `gl_ViewID_OVR` to compute a
+different position for each view, for example one per eye of a VR headset. The vertex shader
+must declare the number of views, which must match the framebuffer.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+# let eyes: glium::texture::Texture2dArray = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+let vertex_shader = "
+    #version 300 es
+    #extension GL_OVR_multiview2 : require
+    layout(num_views = 2) in;
+
+    uniform mat4 matrices[2];
+    in vec3 position;
+
+    void main() {
+        gl_Position = matrices[gl_ViewID_OVR] * vec4(position, 1.0);
+    }
+";
+
+let framebuffer = glium::framebuffer::SimpleFrameBuffer::multiview(&display, eyes.main_level(),
+                                                                   2).unwrap();
+```
+
+Multiview framebuffers require `GL_OVR_multiview`, and using `gl_ViewID_OVR` for anything else
+than `gl_Position` requires `GL_OVR_multiview2`. You can check whether they are supported with
+`is_multiview_supported`.
 
 */
+use std::error::Error;
+use std::fmt;
 use std::rc::Rc;
 use smallvec::SmallVec;
 
-use crate::texture::TextureAnyImage;
+use crate::texture::{Texture2dDataSink, TextureAnyImage, TextureKind};
 
 use crate::backend::Facade;
 use crate::context::Context;
@@ -79,6 +146,7 @@ use crate::version::Api;
 
 use crate::FboAttachments;
 use crate::Rect;
+use crate::{BlitError, BlitMask, CopyToCpuOptions, CpuImage, InvalidateBuffers, PendingRead, ReadError};
 use crate::BlitTarget;
 use crate::ContextExt;
 use crate::ToGlEnum;
@@ -86,6 +154,7 @@ use crate::ops;
 use crate::uniforms;
 
 use crate::{Program, Surface};
+use crate::program::ProgramRef;
 use crate::DrawError;
 
 use crate::{fbo, gl};
@@ -94,16 +163,22 @@ pub use self::default_fb::{DefaultFramebufferAttachment, DefaultFramebuffer};
 pub use self::render_buffer::{RenderBuffer, RenderBufferAny, DepthRenderBuffer};
 pub use self::render_buffer::{StencilRenderBuffer, DepthStencilRenderBuffer};
 pub use self::render_buffer::CreationError as RenderBufferCreationError;
+pub use self::render_target::{RenderTarget, RenderTargetCreationError};
 pub use crate::fbo::is_dimensions_mismatch_supported;
 pub use crate::fbo::ValidationError;
+pub use crate::fbo::{is_sample_locations_supported, SampleLocationsError, SampleLocationsInfo};
+pub use crate::fbo::{is_layered_attachments_supported, LayeredAttachment};
+pub use crate::fbo::is_multiview_supported;
 
 mod default_fb;
 mod render_buffer;
+mod render_target;
 
 /// A framebuffer which has only one color attachment.
 pub struct SimpleFrameBuffer<'a> {
     context: Rc<Context>,
     attachments: fbo::ValidatedAttachments<'a>,
+    color: Option<fbo::RegularAttachment<'a>>,
 }
 
 impl<'a> SimpleFrameBuffer<'a> {
@@ -168,6 +243,9 @@ impl<'a> SimpleFrameBuffer<'a> {
 
     /// Creates a `SimpleFrameBuffer` with a single color attachment and a stencil
     /// buffer, but no depth buffer.
+    ///
+    /// The stencil buffer can be a `StencilRenderBuffer` or a stencil texture, for example
+    /// with `StencilFormat::I8`. Use `clear_stencil` to clear it.
     #[inline]
     pub fn with_stencil_buffer<F: ?Sized, C, S>(facade: &F, color: C, stencil: S)
                                         -> Result<SimpleFrameBuffer<'a>, ValidationError>
@@ -211,6 +289,145 @@ impl<'a> SimpleFrameBuffer<'a> {
     }
 
 
+    /// Returns the limits of the table of programmable sample locations of this framebuffer.
+    ///
+    /// Returns an error if programmable sample locations are not supported.
+    pub fn get_sample_locations_info(&self)
+                                     -> Result<SampleLocationsInfo, SampleLocationsError>
+    {
+        let mut ctxt = self.context.make_current();
+        unsafe { fbo::FramebuffersContainer::get_sample_locations_info(&mut ctxt, &self.attachments) }
+    }
+
+    /// Sets the locations of the samples, starting at the first entry of the table described by
+    /// `get_sample_locations_info`. Each location is between `(0.0, 0.0)` and `(1.0, 1.0)`,
+    /// relative to the bottom-left corner of the pixel. Passing an empty slice restores the
+    /// default locations.
+    ///
+    /// If there are more locations than samples, the table is repeated over the pixel grid,
+    /// which allows for example to jitter the samples for temporal anti-aliasing.
+    ///
+    /// The locations are stored in the framebuffer object that glium associates with these
+    /// attachments, and also apply to the other framebuffers that use the same attachments.
+    pub fn set_sample_locations(&mut self, locations: &[(f32, f32)])
+                                -> Result<(), SampleLocationsError>
+    {
+        let mut ctxt = self.context.make_current();
+        unsafe {
+            fbo::FramebuffersContainer::set_sample_locations(&mut ctxt, &self.attachments, locations)
+        }
+    }
+
+    /// Indicates that the content of some attachments is no longer needed. Their content is
+    /// undefined afterwards.
+    ///
+    /// See `Frame::invalidate`.
+    #[inline]
+    pub fn invalidate(&mut self, buffers: InvalidateBuffers) {
+        ops::invalidate(&self.context, Some(&self.attachments), None, buffers);
+    }
+
+    /// Same as `invalidate`, but only for a part of the attachments.
+    #[inline]
+    pub fn invalidate_rect(&mut self, rect: &Rect, buffers: InvalidateBuffers) {
+        ops::invalidate(&self.context, Some(&self.attachments), Some(rect), buffers);
+    }
+
+    /// Creates a layered `SimpleFrameBuffer` with a single color attachment and no depth
+    /// nor stencil buffer.
+    ///
+    /// See the documentation of the module about layered framebuffers.
+    #[inline]
+    pub fn layered<F: ?Sized, C>(facade: &F, color: C)
+                                 -> Result<SimpleFrameBuffer<'a>, ValidationError>
+                                 where C: Into<LayeredAttachment<'a>>, F: Facade
+    {
+        SimpleFrameBuffer::layered_impl(facade, Some(color.into()), None, None)
+    }
+
+    /// Creates a layered `SimpleFrameBuffer` with a single color attachment and a depth
+    /// buffer. The depth buffer can also be a depth-stencil texture.
+    ///
+    /// See the documentation of the module about layered framebuffers.
+    #[inline]
+    pub fn layered_with_depth_buffer<F: ?Sized, C, D>(facade: &F, color: C, depth: D)
+                                                      -> Result<SimpleFrameBuffer<'a>, ValidationError>
+                                                      where C: Into<LayeredAttachment<'a>>,
+                                                            D: Into<LayeredAttachment<'a>>,
+                                                            F: Facade
+    {
+        SimpleFrameBuffer::layered_impl(facade, Some(color.into()), Some(depth.into()), None)
+    }
+
+    /// Creates a layered `SimpleFrameBuffer` with only a depth buffer. The depth buffer can
+    /// also be a depth-stencil texture.
+    ///
+    /// See the documentation of the module about layered framebuffers.
+    #[inline]
+    pub fn layered_depth_only<F: ?Sized, D>(facade: &F, depth: D)
+                                            -> Result<SimpleFrameBuffer<'a>, ValidationError>
+                                            where D: Into<LayeredAttachment<'a>>, F: Facade
+    {
+        SimpleFrameBuffer::layered_impl(facade, None, Some(depth.into()), None)
+    }
+
+    /// Creates a multiview `SimpleFrameBuffer` with a single color attachment and no depth
+    /// nor stencil buffer. The first `num_views` layers of the 2D array texture are the views.
+    ///
+    /// See the documentation of the module about multiview framebuffers.
+    #[inline]
+    pub fn multiview<F: ?Sized, C>(facade: &F, color: C, num_views: u32)
+                                   -> Result<SimpleFrameBuffer<'a>, ValidationError>
+                                   where C: Into<LayeredAttachment<'a>>, F: Facade
+    {
+        SimpleFrameBuffer::layered_impl(facade, Some(color.into()), None, Some(num_views))
+    }
+
+    /// Creates a multiview `SimpleFrameBuffer` with a single color attachment and a depth
+    /// buffer. The depth buffer can also be a depth-stencil texture. The first `num_views`
+    /// layers of the 2D array textures are the views.
+    ///
+    /// See the documentation of the module about multiview framebuffers.
+    #[inline]
+    pub fn multiview_with_depth_buffer<F: ?Sized, C, D>(facade: &F, color: C, depth: D,
+                                                        num_views: u32)
+                                                        -> Result<SimpleFrameBuffer<'a>,
+                                                                  ValidationError>
+                                                        where C: Into<LayeredAttachment<'a>>,
+                                                              D: Into<LayeredAttachment<'a>>,
+                                                              F: Facade
+    {
+        SimpleFrameBuffer::layered_impl(facade, Some(color.into()), Some(depth.into()),
+                                        Some(num_views))
+    }
+
+    fn layered_impl<F: ?Sized>(facade: &F, color: Option<LayeredAttachment<'a>>,
+                               depth: Option<LayeredAttachment<'a>>, num_views: Option<u32>)
+                               -> Result<SimpleFrameBuffer<'a>, ValidationError> where F: Facade
+    {
+        let attachments = fbo::FramebufferSpecificAttachments {
+            colors: if let Some(color) = color {
+                let mut v = SmallVec::new(); v.push((0, color)); v
+            } else {
+                SmallVec::new()
+            },
+            depth_stencil: layered_depth_stencil(depth),
+        };
+
+        let attachments = match num_views {
+            Some(num_views) => fbo::FramebufferAttachments::Multiview { attachments, num_views },
+            None => fbo::FramebufferAttachments::Layered(attachments),
+        };
+
+        let attachments = attachments.validate(facade)?;
+
+        Ok(SimpleFrameBuffer {
+            context: facade.get_context().clone(),
+            attachments,
+            color: color.map(|color| fbo::RegularAttachment::Texture(color.first_image())),
+        })
+    }
+
     fn new_impl<F: ?Sized>(facade: &F, color: Option<ColorAttachment<'a>>,
                    depth: Option<DepthAttachment<'a>>, stencil: Option<StencilAttachment<'a>>,
                    depthstencil: Option<DepthStencilAttachment<'a>>)
@@ -260,6 +477,7 @@ impl<'a> SimpleFrameBuffer<'a> {
         Ok(SimpleFrameBuffer {
             context: facade.get_context().clone(),
             attachments,
+            color,
         })
     }
 }
@@ -287,10 +505,10 @@ impl<'a> Surface for SimpleFrameBuffer<'a> {
         self.attachments.get_stencil_buffer_bits()
     }
 
-    fn draw<'b, 'v, V, I, U>(&mut self, vb: V, ib: I, program: &crate::Program,
+    fn draw<'b, 'v, 'p, V, I, U, P>(&mut self, vb: V, ib: I, program: P,
         uniforms: &U, draw_parameters: &crate::DrawParameters<'_>) -> Result<(), DrawError>
         where I: Into<crate::index::IndicesSource<'b>>, U: crate::uniforms::Uniforms,
-        V: crate::vertex::MultiVerticesSource<'v>
+        V: crate::vertex::MultiVerticesSource<'v>, P: Into<ProgramRef<'p>>
     {
         if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
                         draw_parameters.depth.write)
@@ -312,7 +530,21 @@ impl<'a> Surface for SimpleFrameBuffer<'a> {
         }
 
         ops::draw(&self.context, Some(&self.attachments), vb,
-                  ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
+                  ib.into(), program.into(), uniforms, draw_parameters, self.get_dimensions())
+    }
+
+    fn draw_mesh_tasks<U>(&mut self, count: u32, program: &Program, uniforms: &U,
+                          draw_parameters: &crate::DrawParameters<'_>) -> Result<(), DrawError>
+                          where U: crate::uniforms::Uniforms
+    {
+        if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
+                draw_parameters.depth.write)
+        {
+            return Err(DrawError::NoDepthBuffer);
+        }
+
+        ops::draw_mesh_tasks(&self.context, Some(&self.attachments), count, program, uniforms,
+                             draw_parameters, self.get_dimensions())
     }
 
     #[inline]
@@ -347,6 +579,68 @@ impl<'a> Surface for SimpleFrameBuffer<'a> {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
     }
+
+    #[inline]
+    fn blit<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget, mask: BlitMask,
+               filter: uniforms::MagnifySamplerFilter) -> Result<(), BlitError> where S: Surface
+    {
+        target.blit_buffers_from_simple_framebuffer(self, source_rect, target_rect, mask, filter)
+    }
+
+    #[inline]
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               mask: BlitMask, filter: uniforms::MagnifySamplerFilter)
+                               -> Result<(), BlitError>
+    {
+        ops::blit_buffers(&self.context, None, self.get_attachments(), source_rect, target_rect,
+                          mask, filter)
+    }
+
+    #[inline]
+    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            mask: BlitMask, filter: uniforms::MagnifySamplerFilter)
+                                            -> Result<(), BlitError>
+    {
+        ops::blit_buffers(&self.context, source.get_attachments(), self.get_attachments(),
+                          source_rect, target_rect, mask, filter)
+    }
+
+    #[inline]
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 mask: BlitMask,
+                                                 filter: uniforms::MagnifySamplerFilter)
+                                                 -> Result<(), BlitError>
+    {
+        ops::blit_buffers(&self.context, source.get_attachments(), self.get_attachments(),
+                          source_rect, target_rect, mask, filter)
+    }
+
+    fn copy_region_to_cpu_with_options(&self, rect: &Rect, options: &CopyToCpuOptions)
+                                       -> Result<CpuImage, ReadError>
+    {
+        let color = match self.color {
+            Some(ref color) => color,
+            None => return Err(ReadError::NoColorAttachment),
+        };
+
+        let mut ctxt = self.context.make_current();
+        ops::copy_to_cpu(&mut ctxt, ops::Source::Attachment(color), color.is_srgb(), self.attachments.get_dimensions(),
+                         rect, options)
+    }
+
+    fn read_async<T>(&self, rect: &Rect) -> Result<PendingRead<T>, ReadError>
+                     where T: Texture2dDataSink<(u8, u8, u8, u8)>
+    {
+        let color = match self.color {
+            Some(ref color) => color,
+            None => return Err(ReadError::NoColorAttachment),
+        };
+
+        ops::read_async(&self.context, ops::Source::Attachment(color),
+                        self.attachments.get_dimensions(), rect)
+    }
 }
 
 impl<'a> FboAttachments for SimpleFrameBuffer<'a> {
@@ -362,6 +656,11 @@ pub struct MultiOutputFrameBuffer<'a> {
     example_attachments: fbo::ValidatedAttachments<'a>,
     color_attachments: Vec<(String, fbo::RegularAttachment<'a>)>,
     depth_stencil_attachments: fbo::DepthStencilAttachments<fbo::RegularAttachment<'a>>,
+
+    /// If the framebuffer is layered, contains the attachments to use when drawing. The index
+    /// of each color attachment is its position in `color_attachments`, which contains the
+    /// first layers.
+    layered: Option<fbo::FramebufferSpecificAttachments<LayeredAttachment<'a>>>,
 }
 
 impl<'a> MultiOutputFrameBuffer<'a> {
@@ -450,6 +749,65 @@ impl<'a> MultiOutputFrameBuffer<'a> {
                                     Some(depthstencil.to_depth_stencil_attachment()))
     }
 
+    /// Creates a layered `MultiOutputFrameBuffer`.
+    ///
+    /// See the documentation of the module about layered framebuffers.
+    #[inline]
+    pub fn layered<F: ?Sized, I, A>(facade: &F, color_attachments: I)
+                                    -> Result<MultiOutputFrameBuffer<'a>, ValidationError>
+        where F: Facade,
+              I: IntoIterator<Item = (&'a str, A)>,
+              A: Into<LayeredAttachment<'a>>,
+    {
+        MultiOutputFrameBuffer::layered_impl(facade, color_attachments, None)
+    }
+
+    /// Creates a layered `MultiOutputFrameBuffer` with a depth buffer. The depth buffer can
+    /// also be a depth-stencil texture.
+    ///
+    /// See the documentation of the module about layered framebuffers.
+    #[inline]
+    pub fn layered_with_depth_buffer<F: ?Sized, D, I, A>(facade: &F, color_attachments: I,
+                                                         depth: D)
+                                                         -> Result<MultiOutputFrameBuffer<'a>,
+                                                                   ValidationError>
+        where F: Facade,
+              D: Into<LayeredAttachment<'a>>,
+              I: IntoIterator<Item = (&'a str, A)>,
+              A: Into<LayeredAttachment<'a>>,
+    {
+        MultiOutputFrameBuffer::layered_impl(facade, color_attachments, Some(depth.into()))
+    }
+
+    fn layered_impl<F: ?Sized, I, A>(facade: &F, color: I, depth: Option<LayeredAttachment<'a>>)
+                                     -> Result<MultiOutputFrameBuffer<'a>, ValidationError>
+        where F: Facade,
+              I: IntoIterator<Item = (&'a str, A)>,
+              A: Into<LayeredAttachment<'a>>,
+    {
+        let color = color.into_iter().map(|(name, tex)| (name.to_owned(), tex.into()))
+                         .collect::<Vec<(String, LayeredAttachment<'a>)>>();
+
+        let layered = fbo::FramebufferSpecificAttachments {
+            colors: color.iter().enumerate().map(|(index, &(_, tex))| (index as u32, tex))
+                         .collect(),
+            depth_stencil: layered_depth_stencil(depth),
+        };
+
+        let example_attachments = fbo::FramebufferAttachments::Layered(layered.clone())
+                                                                .validate(facade)?;
+
+        Ok(MultiOutputFrameBuffer {
+            context: facade.get_context().clone(),
+            example_attachments,
+            color_attachments: color.into_iter().map(|(name, tex)| {
+                (name, fbo::RegularAttachment::Texture(tex.first_image()))
+            }).collect(),
+            depth_stencil_attachments: first_images(&layered.depth_stencil),
+            layered: Some(layered),
+        })
+    }
+
     fn new_impl<F: ?Sized, I, A>(facade: &F, color: I, depth: Option<DepthAttachment<'a>>,
                          stencil: Option<StencilAttachment<'a>>,
                          depthstencil: Option<DepthStencilAttachment<'a>>)
@@ -509,10 +867,28 @@ impl<'a> MultiOutputFrameBuffer<'a> {
             example_attachments,
             color_attachments: color,
             depth_stencil_attachments,
+            layered: None,
         })
     }
 
     fn build_attachments(&self, program: &Program) -> fbo::ValidatedAttachments<'_> {
+        if let Some(ref layered) = self.layered {
+            let colors = layered.colors.iter().map(|&(index, attachment)| {
+                let name = &self.color_attachments[index as usize].0;
+                let location = match program.get_frag_data_location(name) {
+                    Some(l) => l,
+                    None => panic!("The fragment output `{}` was not found in the program", name)
+                };
+
+                (location, attachment)
+            }).collect();
+
+            return fbo::FramebufferAttachments::Layered(fbo::FramebufferSpecificAttachments {
+                colors,
+                depth_stencil: layered.depth_stencil,
+            }).validate(&self.context).unwrap();
+        }
+
         let mut colors = SmallVec::new();
 
         for &(ref name, attachment) in self.color_attachments.iter() {
@@ -529,6 +905,225 @@ impl<'a> MultiOutputFrameBuffer<'a> {
             depth_stencil: self.depth_stencil_attachments,
         }).validate(&self.context).unwrap()
     }
+
+    /// Copies some color attachments of this framebuffer to the attachments of `target` that
+    /// have the same name or the same index.
+    ///
+    /// Each attachment is blitted separately, which means that multisampled attachments are
+    /// resolved in the corresponding attachments of `target`. In this situation `source_rect`
+    /// and `target_rect` must have the same dimensions.
+    ///
+    /// Nothing is copied if an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// # let gbuffer: glium::framebuffer::MultiOutputFrameBuffer = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// # let resized: glium::framebuffer::MultiOutputFrameBuffer = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// # let source_rect: glium::Rect = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// # let target_rect: glium::BlitTarget = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// gbuffer.blit_attachments(&resized, ["albedo", "normal", "position"].iter().cloned(),
+    ///                          &source_rect, &target_rect,
+    ///                          glium::uniforms::MagnifySamplerFilter::Nearest).unwrap();
+    /// ```
+    pub fn blit_attachments<'n, I, S>(&self, target: &MultiOutputFrameBuffer<'_>, attachments: I,
+                                      source_rect: &Rect, target_rect: &BlitTarget,
+                                      filter: uniforms::MagnifySamplerFilter)
+                                      -> Result<(), BlitAttachmentsError>
+        where I: IntoIterator<Item = S>, S: Into<AttachmentSelector<'n>>
+    {
+        let mut pairs = Vec::new();
+
+        for selector in attachments {
+            let selector = selector.into();
+
+            let source = match self.find_attachment(selector) {
+                Some(a) => a,
+                None => return Err(BlitAttachmentsError::NotFoundInSource(selector.to_string())),
+            };
+
+            let destination = match target.find_attachment(selector) {
+                Some(a) => a,
+                None => return Err(BlitAttachmentsError::NotFoundInTarget(selector.to_string())),
+            };
+
+            check_blit(source, destination, source_rect, target_rect, &selector.to_string())?;
+            pairs.push((source, destination));
+        }
+
+        for (source, destination) in pairs {
+            self.blit_regular(single_color(source), single_color(destination),
+                              gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum());
+        }
+
+        Ok(())
+    }
+
+    /// Copies all the color attachments of this framebuffer to the attachments of `target` that
+    /// have the same name, and the depth and stencil attachments to the depth and stencil
+    /// attachments of `target`.
+    ///
+    /// The attachments that don't exist in `target` are ignored. The depth and stencil
+    /// attachments are always copied with the `Nearest` filter.
+    ///
+    /// See `blit_attachments` for the handling of multisampling.
+    pub fn blit_all_attachments(&self, target: &MultiOutputFrameBuffer<'_>, source_rect: &Rect,
+                                target_rect: &BlitTarget, filter: uniforms::MagnifySamplerFilter)
+                                -> Result<(), BlitAttachmentsError>
+    {
+        let names = self.color_attachments.iter()
+                        .map(|(name, _)| &name[..])
+                        .filter(|name| target.find_attachment(AttachmentSelector::Name(name)).is_some())
+                        .collect::<Vec<_>>();
+
+        let (source_depth, source_stencil) = depth_stencil_of(&self.depth_stencil_attachments);
+        let (target_depth, target_stencil) = depth_stencil_of(&target.depth_stencil_attachments);
+
+        let mut mask = 0;
+        if let (Some(source), Some(destination)) = (source_depth, target_depth) {
+            check_blit(source, destination, source_rect, target_rect, "<depth>")?;
+            mask |= gl::DEPTH_BUFFER_BIT;
+        }
+        if let (Some(source), Some(destination)) = (source_stencil, target_stencil) {
+            check_blit(source, destination, source_rect, target_rect, "<stencil>")?;
+            mask |= gl::STENCIL_BUFFER_BIT;
+        }
+
+        self.blit_attachments(target, names, source_rect, target_rect, filter)?;
+
+        if mask != 0 {
+            let source = fbo::FramebufferSpecificAttachments {
+                colors: SmallVec::new(),
+                depth_stencil: self.depth_stencil_attachments,
+            };
+
+            let destination = fbo::FramebufferSpecificAttachments {
+                colors: SmallVec::new(),
+                depth_stencil: target.depth_stencil_attachments,
+            };
+
+            self.blit_regular(source, destination, mask, source_rect, target_rect, gl::NEAREST);
+        }
+
+        Ok(())
+    }
+
+    /// Indicates that the content of some attachments is no longer needed. Their content is
+    /// undefined afterwards. `color` applies to all the color attachments.
+    ///
+    /// See `Frame::invalidate`.
+    #[inline]
+    pub fn invalidate(&mut self, buffers: InvalidateBuffers) {
+        ops::invalidate(&self.context, Some(&self.example_attachments), None, buffers);
+    }
+
+    /// Same as `invalidate`, but only for a part of the attachments.
+    #[inline]
+    pub fn invalidate_rect(&mut self, rect: &Rect, buffers: InvalidateBuffers) {
+        ops::invalidate(&self.context, Some(&self.example_attachments), Some(rect), buffers);
+    }
+
+    fn find_attachment(&self, selector: AttachmentSelector<'_>) -> Option<fbo::RegularAttachment<'a>> {
+        match selector {
+            AttachmentSelector::Name(name) => {
+                self.color_attachments.iter().find(|(n, _)| n == name).map(|&(_, a)| a)
+            },
+            AttachmentSelector::Index(index) => {
+                self.color_attachments.get(index).map(|&(_, a)| a)
+            },
+        }
+    }
+
+    fn blit_regular(&self, source: fbo::FramebufferSpecificAttachments<fbo::RegularAttachment<'_>>,
+                    destination: fbo::FramebufferSpecificAttachments<fbo::RegularAttachment<'_>>,
+                    mask: gl::types::GLbitfield, source_rect: &Rect, target_rect: &BlitTarget,
+                    filter: gl::types::GLenum)
+    {
+        // these attachments are a subset of attachments that have already been validated
+        let source = fbo::FramebufferAttachments::Regular(source).validate(&self.context).unwrap();
+        let destination = fbo::FramebufferAttachments::Regular(destination)
+                                                    .validate(&self.context).unwrap();
+
+        ops::blit(&self.context, Some(&source), Some(&destination), mask, source_rect,
+                  target_rect, filter)
+    }
+}
+
+/// Builds attachments made of a single color attachment.
+fn single_color(attachment: fbo::RegularAttachment<'_>)
+                -> fbo::FramebufferSpecificAttachments<fbo::RegularAttachment<'_>>
+{
+    let mut colors = SmallVec::new();
+    colors.push((0, attachment));
+
+    fbo::FramebufferSpecificAttachments {
+        colors,
+        depth_stencil: fbo::DepthStencilAttachments::None,
+    }
+}
+
+/// Turns the depth attachment of a layered framebuffer into a depth or a depth-stencil
+/// attachment depending on its format.
+fn layered_depth_stencil(depth: Option<LayeredAttachment<'_>>)
+                         -> fbo::DepthStencilAttachments<LayeredAttachment<'_>>
+{
+    match depth {
+        Some(depth) if depth.kind() == TextureKind::DepthStencil =>
+            fbo::DepthStencilAttachments::DepthStencilAttachment(depth),
+        Some(depth) => fbo::DepthStencilAttachments::DepthAttachment(depth),
+        None => fbo::DepthStencilAttachments::None,
+    }
+}
+
+/// Returns the first layer of each attachment of a layered framebuffer.
+fn first_images<'a>(attachments: &fbo::DepthStencilAttachments<LayeredAttachment<'a>>)
+                    -> fbo::DepthStencilAttachments<fbo::RegularAttachment<'a>>
+{
+    let image = |a: &LayeredAttachment<'a>| fbo::RegularAttachment::Texture(a.first_image());
+
+    match *attachments {
+        fbo::DepthStencilAttachments::None => fbo::DepthStencilAttachments::None,
+        fbo::DepthStencilAttachments::DepthAttachment(ref d) =>
+            fbo::DepthStencilAttachments::DepthAttachment(image(d)),
+        fbo::DepthStencilAttachments::StencilAttachment(ref s) =>
+            fbo::DepthStencilAttachments::StencilAttachment(image(s)),
+        fbo::DepthStencilAttachments::DepthAndStencilAttachments(ref d, ref s) =>
+            fbo::DepthStencilAttachments::DepthAndStencilAttachments(image(d), image(s)),
+        fbo::DepthStencilAttachments::DepthStencilAttachment(ref ds) =>
+            fbo::DepthStencilAttachments::DepthStencilAttachment(image(ds)),
+    }
+}
+
+/// Returns the attachments that contain the depth and the stencil values.
+fn depth_stencil_of<'a>(attachments: &fbo::DepthStencilAttachments<fbo::RegularAttachment<'a>>)
+                        -> (Option<fbo::RegularAttachment<'a>>, Option<fbo::RegularAttachment<'a>>)
+{
+    match *attachments {
+        fbo::DepthStencilAttachments::None => (None, None),
+        fbo::DepthStencilAttachments::DepthAttachment(d) => (Some(d), None),
+        fbo::DepthStencilAttachments::StencilAttachment(s) => (None, Some(s)),
+        fbo::DepthStencilAttachments::DepthAndStencilAttachments(d, s) => (Some(d), Some(s)),
+        fbo::DepthStencilAttachments::DepthStencilAttachment(ds) => (Some(ds), Some(ds)),
+    }
+}
+
+/// Checks that an attachment can be blitted to another one.
+fn check_blit(source: fbo::RegularAttachment<'_>, destination: fbo::RegularAttachment<'_>,
+              source_rect: &Rect, target_rect: &BlitTarget, name: &str)
+              -> Result<(), BlitAttachmentsError>
+{
+    if destination.get_samples().is_some() {
+        return Err(BlitAttachmentsError::MultisampledTarget(name.to_owned()));
+    }
+
+    if source.get_samples().is_some() && (source_rect.width as i32 != target_rect.width ||
+                                          source_rect.height as i32 != target_rect.height)
+    {
+        return Err(BlitAttachmentsError::ResolveDimensionsMismatch(name.to_owned()));
+    }
+
+    Ok(())
 }
 
 impl<'a> Surface for MultiOutputFrameBuffer<'a> {
@@ -555,10 +1150,11 @@ impl<'a> Surface for MultiOutputFrameBuffer<'a> {
         self.example_attachments.get_stencil_buffer_bits()
     }
 
-    fn draw<'i, 'v, V, I, U>(&mut self, vb: V, ib: I, program: &crate::Program,
+    fn draw<'i, 'v, 'p, V, I, U, P>(&mut self, vb: V, ib: I, program: P,
         uniforms: &U, draw_parameters: &crate::DrawParameters<'_>) -> Result<(), DrawError>
         where I: Into<crate::index::IndicesSource<'i>>,
-        U: crate::uniforms::Uniforms, V: crate::vertex::MultiVerticesSource<'v>
+        U: crate::uniforms::Uniforms, V: crate::vertex::MultiVerticesSource<'v>,
+        P: Into<ProgramRef<'p>>
     {
         if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
                 draw_parameters.depth.write)
@@ -579,10 +1175,27 @@ impl<'a> Surface for MultiOutputFrameBuffer<'a> {
             }
         }
 
-        ops::draw(&self.context, Some(&self.build_attachments(program)), vb,
+        let program = program.into();
+        let attachments = self.build_attachments(program.get_fragment_program());
+        ops::draw(&self.context, Some(&attachments), vb,
                   ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
     }
 
+    fn draw_mesh_tasks<U>(&mut self, count: u32, program: &Program, uniforms: &U,
+                          draw_parameters: &crate::DrawParameters<'_>) -> Result<(), DrawError>
+                          where U: crate::uniforms::Uniforms
+    {
+        if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
+                draw_parameters.depth.write)
+        {
+            return Err(DrawError::NoDepthBuffer);
+        }
+
+        let attachments = self.build_attachments(program);
+        ops::draw_mesh_tasks(&self.context, Some(&attachments), count, program, uniforms,
+                             draw_parameters, self.get_dimensions())
+    }
+
     #[inline]
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
                      filter: uniforms::MagnifySamplerFilter) where S: Surface
@@ -615,6 +1228,69 @@ impl<'a> Surface for MultiOutputFrameBuffer<'a> {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
     }
+
+    #[inline]
+    fn blit<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget, mask: BlitMask,
+               filter: uniforms::MagnifySamplerFilter) -> Result<(), BlitError> where S: Surface
+    {
+        target.blit_buffers_from_multioutput_framebuffer(self, source_rect, target_rect, mask,
+                                                         filter)
+    }
+
+    #[inline]
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               mask: BlitMask, filter: uniforms::MagnifySamplerFilter)
+                               -> Result<(), BlitError>
+    {
+        ops::blit_buffers(&self.context, None, self.get_attachments(), source_rect, target_rect,
+                          mask, filter)
+    }
+
+    #[inline]
+    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            mask: BlitMask, filter: uniforms::MagnifySamplerFilter)
+                                            -> Result<(), BlitError>
+    {
+        ops::blit_buffers(&self.context, source.get_attachments(), self.get_attachments(),
+                          source_rect, target_rect, mask, filter)
+    }
+
+    #[inline]
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 mask: BlitMask,
+                                                 filter: uniforms::MagnifySamplerFilter)
+                                                 -> Result<(), BlitError>
+    {
+        ops::blit_buffers(&self.context, source.get_attachments(), self.get_attachments(),
+                          source_rect, target_rect, mask, filter)
+    }
+
+    fn copy_region_to_cpu_with_options(&self, rect: &Rect, options: &CopyToCpuOptions)
+                                       -> Result<CpuImage, ReadError>
+    {
+        let color = match self.color_attachments.first() {
+            Some(&(_, ref color)) => color,
+            None => return Err(ReadError::NoColorAttachment),
+        };
+
+        let mut ctxt = self.context.make_current();
+        ops::copy_to_cpu(&mut ctxt, ops::Source::Attachment(color), color.is_srgb(),
+                         self.example_attachments.get_dimensions(), rect, options)
+    }
+
+    fn read_async<T>(&self, rect: &Rect) -> Result<PendingRead<T>, ReadError>
+                     where T: Texture2dDataSink<(u8, u8, u8, u8)>
+    {
+        let color = match self.color_attachments.first() {
+            Some((_, color)) => color,
+            None => return Err(ReadError::NoColorAttachment),
+        };
+
+        ops::read_async(&self.context, ops::Source::Attachment(color),
+                        self.example_attachments.get_dimensions(), rect)
+    }
 }
 
 impl<'a> FboAttachments for MultiOutputFrameBuffer<'a> {
@@ -624,6 +1300,77 @@ impl<'a> FboAttachments for MultiOutputFrameBuffer<'a> {
     }
 }
 
+/// Designates a color attachment of a `MultiOutputFrameBuffer`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AttachmentSelector<'a> {
+    /// The attachment bound to the fragment output with this name.
+    Name(&'a str),
+    /// The attachment at this position in the list passed when creating the framebuffer.
+    Index(usize),
+}
+
+impl<'a> From<&'a str> for AttachmentSelector<'a> {
+    #[inline]
+    fn from(name: &'a str) -> AttachmentSelector<'a> {
+        AttachmentSelector::Name(name)
+    }
+}
+
+impl<'a> From<usize> for AttachmentSelector<'a> {
+    #[inline]
+    fn from(index: usize) -> AttachmentSelector<'a> {
+        AttachmentSelector::Index(index)
+    }
+}
+
+impl<'a> fmt::Display for AttachmentSelector<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            AttachmentSelector::Name(name) => write!(fmt, "{}", name),
+            AttachmentSelector::Index(index) => write!(fmt, "#{}", index),
+        }
+    }
+}
+
+/// Error that can happen when blitting the attachments of a `MultiOutputFrameBuffer`.
+///
+/// Each variant contains the name or the index of the attachment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlitAttachmentsError {
+    /// The attachment doesn't exist in the source framebuffer.
+    NotFoundInSource(String),
+
+    /// The attachment doesn't exist in the target framebuffer.
+    NotFoundInTarget(String),
+
+    /// The source attachment is multisampled but the source and target rectangles don't have
+    /// the same dimensions. A multisampled attachment can only be resolved without scaling.
+    ResolveDimensionsMismatch(String),
+
+    /// The target attachment is multisampled. It is not possible to blit to a multisampled
+    /// attachment.
+    MultisampledTarget(String),
+}
+
+impl fmt::Display for BlitAttachmentsError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::BlitAttachmentsError::*;
+        let (desc, name) = match self {
+            NotFoundInSource(name) =>
+                ("The attachment doesn't exist in the source framebuffer", name),
+            NotFoundInTarget(name) =>
+                ("The attachment doesn't exist in the target framebuffer", name),
+            ResolveDimensionsMismatch(name) =>
+                ("A multisampled attachment can only be resolved without scaling", name),
+            MultisampledTarget(name) =>
+                ("It is not possible to blit to a multisampled attachment", name),
+        };
+        write!(fmt, "{}: {}", desc, name)
+    }
+}
+
+impl Error for BlitAttachmentsError {}
+
 /// A framebuffer with no attachment at all.
 ///
 /// Note that this is only supported on recent hardware.
@@ -699,6 +1446,28 @@ impl<'a> EmptyFrameBuffer {
             attachments,
         })
     }
+
+    /// Returns the limits of the table of programmable sample locations of this framebuffer.
+    ///
+    /// See `SimpleFrameBuffer::get_sample_locations_info`.
+    pub fn get_sample_locations_info(&self)
+                                     -> Result<SampleLocationsInfo, SampleLocationsError>
+    {
+        let mut ctxt = self.context.make_current();
+        unsafe { fbo::FramebuffersContainer::get_sample_locations_info(&mut ctxt, &self.attachments) }
+    }
+
+    /// Sets the locations of the samples.
+    ///
+    /// See `SimpleFrameBuffer::set_sample_locations`.
+    pub fn set_sample_locations(&mut self, locations: &[(f32, f32)])
+                                -> Result<(), SampleLocationsError>
+    {
+        let mut ctxt = self.context.make_current();
+        unsafe {
+            fbo::FramebuffersContainer::set_sample_locations(&mut ctxt, &self.attachments, locations)
+        }
+    }
 }
 
 impl Surface for EmptyFrameBuffer {
@@ -724,10 +1493,10 @@ impl Surface for EmptyFrameBuffer {
         None
     }
 
-    fn draw<'b, 'v, V, I, U>(&mut self, vb: V, ib: I, program: &crate::Program,
+    fn draw<'b, 'v, 'p, V, I, U, P>(&mut self, vb: V, ib: I, program: P,
         uniforms: &U, draw_parameters: &crate::DrawParameters<'_>) -> Result<(), DrawError>
         where I: Into<crate::index::IndicesSource<'b>>, U: crate::uniforms::Uniforms,
-        V: crate::vertex::MultiVerticesSource<'v>
+        V: crate::vertex::MultiVerticesSource<'v>, P: Into<ProgramRef<'p>>
     {
         if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
                         draw_parameters.depth.write)
@@ -749,7 +1518,21 @@ impl Surface for EmptyFrameBuffer {
         }
 
         ops::draw(&self.context, Some(&self.attachments), vb,
-                  ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
+                  ib.into(), program.into(), uniforms, draw_parameters, self.get_dimensions())
+    }
+
+    fn draw_mesh_tasks<U>(&mut self, count: u32, program: &Program, uniforms: &U,
+                          draw_parameters: &crate::DrawParameters<'_>) -> Result<(), DrawError>
+                          where U: crate::uniforms::Uniforms
+    {
+        if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
+                draw_parameters.depth.write)
+        {
+            return Err(DrawError::NoDepthBuffer);
+        }
+
+        ops::draw_mesh_tasks(&self.context, Some(&self.attachments), count, program, uniforms,
+                             draw_parameters, self.get_dimensions())
     }
 
     #[inline]
@@ -784,6 +1567,66 @@ impl Surface for EmptyFrameBuffer {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
     }
+
+    #[inline]
+    fn blit<S>(&self, _: &Rect, _: &S, _: &BlitTarget, mask: BlitMask,
+               _: uniforms::MagnifySamplerFilter) -> Result<(), BlitError> where S: Surface
+    {
+        // an empty framebuffer doesn't have any buffer to copy
+        if mask.color {
+            Err(BlitError::NoColorBuffer)
+        } else if mask.depth {
+            Err(BlitError::NoDepthBuffer)
+        } else if mask.stencil {
+            Err(BlitError::NoStencilBuffer)
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               mask: BlitMask, filter: uniforms::MagnifySamplerFilter)
+                               -> Result<(), BlitError>
+    {
+        ops::blit_buffers(&self.context, None, self.get_attachments(), source_rect, target_rect,
+                          mask, filter)
+    }
+
+    #[inline]
+    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            mask: BlitMask, filter: uniforms::MagnifySamplerFilter)
+                                            -> Result<(), BlitError>
+    {
+        ops::blit_buffers(&self.context, source.get_attachments(), self.get_attachments(),
+                          source_rect, target_rect, mask, filter)
+    }
+
+    #[inline]
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 mask: BlitMask,
+                                                 filter: uniforms::MagnifySamplerFilter)
+                                                 -> Result<(), BlitError>
+    {
+        ops::blit_buffers(&self.context, source.get_attachments(), self.get_attachments(),
+                          source_rect, target_rect, mask, filter)
+    }
+
+    #[inline]
+    fn copy_region_to_cpu_with_options(&self, _: &Rect, _: &CopyToCpuOptions)
+                                       -> Result<CpuImage, ReadError>
+    {
+        Err(ReadError::NoColorAttachment)
+    }
+
+    #[inline]
+    fn read_async<T>(&self, _: &Rect) -> Result<PendingRead<T>, ReadError>
+                     where T: Texture2dDataSink<(u8, u8, u8, u8)>
+    {
+        Err(ReadError::NoColorAttachment)
+    }
 }
 
 impl FboAttachments for EmptyFrameBuffer {
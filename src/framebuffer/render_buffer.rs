@@ -273,6 +273,7 @@ pub struct RenderBufferAny {
     height: u32,
     samples: Option<u32>,
     kind: TextureKind,
+    depth_stencil_bits: (u16, u16),
 }
 
 impl RenderBufferAny {
@@ -390,6 +391,36 @@ impl RenderBufferAny {
                 unreachable!();
             }
 
+            // the number of bits is needed every time the render buffer is attached to a
+            // framebuffer, so we query it only once
+            let mut depth_bits: gl::types::GLint = 0;
+            let mut stencil_bits: gl::types::GLint = 0;
+
+            if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+               ctxt.extensions.gl_arb_direct_state_access
+            {
+                ctxt.gl.GetNamedRenderbufferParameteriv(id, gl::RENDERBUFFER_DEPTH_SIZE,
+                                                        &mut depth_bits);
+                ctxt.gl.GetNamedRenderbufferParameteriv(id, gl::RENDERBUFFER_STENCIL_SIZE,
+                                                        &mut stencil_bits);
+
+            } else if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+                      ctxt.version >= &Version(Api::GlEs, 2, 0)
+            {
+                ctxt.gl.GetRenderbufferParameteriv(gl::RENDERBUFFER, gl::RENDERBUFFER_DEPTH_SIZE,
+                                                   &mut depth_bits);
+                ctxt.gl.GetRenderbufferParameteriv(gl::RENDERBUFFER, gl::RENDERBUFFER_STENCIL_SIZE,
+                                                   &mut stencil_bits);
+
+            } else {
+                ctxt.gl.GetRenderbufferParameterivEXT(gl::RENDERBUFFER_EXT,
+                                                      gl::RENDERBUFFER_DEPTH_SIZE_EXT,
+                                                      &mut depth_bits);
+                ctxt.gl.GetRenderbufferParameterivEXT(gl::RENDERBUFFER_EXT,
+                                                      gl::RENDERBUFFER_STENCIL_SIZE_EXT,
+                                                      &mut stencil_bits);
+            }
+
             RenderBufferAny {
                 context: facade.get_context().clone(),
                 id,
@@ -397,6 +428,7 @@ impl RenderBufferAny {
                 height,
                 samples,
                 kind,
+                depth_stencil_bits: (depth_bits as u16, stencil_bits as u16),
             }
         }
     }
@@ -427,24 +459,23 @@ impl RenderBufferAny {
     }
 
     /// Determines the number of depth and stencil bits in the format of this render buffer.
+    #[inline]
     pub fn get_depth_stencil_bits(&self) -> (u16, u16) {
-        unsafe {
-            let ctxt = self.context.make_current();
-            let mut depth_bits: gl::types::GLint = 0;
-            let mut stencil_bits: gl::types::GLint = 0;
-            ctxt.gl.BindRenderbuffer(gl::RENDERBUFFER, self.id);
-            // FIXME: GL version considerations
-            ctxt.gl.GetRenderbufferParameteriv(gl::RENDERBUFFER, gl::RENDERBUFFER_DEPTH_SIZE, &mut depth_bits);
-            ctxt.gl.GetRenderbufferParameteriv(gl::RENDERBUFFER, gl::RENDERBUFFER_STENCIL_SIZE, &mut stencil_bits);
-            ctxt.gl.BindRenderbuffer(gl::RENDERBUFFER, 0);
-            (depth_bits as u16, stencil_bits as u16)
-        }
+        self.depth_stencil_bits
     }
 }
 
 impl Drop for RenderBufferAny {
     fn drop(&mut self) {
         unsafe {
+            let id = self.id;
+            self.context.for_each_shared_context(&mut |ctxt| {
+                FramebuffersContainer::purge_renderbuffer(ctxt, id);
+                if ctxt.state.renderbuffer == id {
+                    ctxt.state.renderbuffer = 0;
+                }
+            });
+
             let mut ctxt = self.context.make_current();
 
             // removing FBOs which contain this buffer
@@ -0,0 +1,225 @@
+/*!
+
+A render target is a color texture and a depth buffer whose dimensions follow the dimensions of
+the window.
+
+This is what you need in order to render a scene from a secondary camera (a planar reflection,
+a security camera, a minimap, etc.) and then sample the result when drawing the main scene.
+
+*/
+use std::rc::Rc;
+use std::fmt;
+use std::error::Error;
+
+use crate::framebuffer::{SimpleFrameBuffer, DepthRenderBuffer};
+use crate::framebuffer::RenderBufferCreationError;
+use crate::texture::{Texture2d, TextureCreationError, MipmapsOption};
+use crate::texture::{UncompressedFloatFormat, DepthFormat};
+
+use crate::backend::Facade;
+use crate::context::Context;
+
+/// Error that can happen while creating or resizing a `RenderTarget`.
+#[derive(Copy, Clone, Debug)]
+pub enum RenderTargetCreationError {
+    /// Error while creating the color texture.
+    ColorTexture(TextureCreationError),
+
+    /// Error while creating the depth buffer.
+    DepthBuffer(RenderBufferCreationError),
+}
+
+impl fmt::Display for RenderTargetCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::RenderTargetCreationError::*;
+        match *self {
+            ColorTexture(ref err) => write!(fmt, "Error while creating the color texture: {}", err),
+            DepthBuffer(ref err) => write!(fmt, "Error while creating the depth buffer: {}", err),
+        }
+    }
+}
+
+impl Error for RenderTargetCreationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use self::RenderTargetCreationError::*;
+        match *self {
+            ColorTexture(ref err) => Some(err),
+            DepthBuffer(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<TextureCreationError> for RenderTargetCreationError {
+    #[inline]
+    fn from(err: TextureCreationError) -> RenderTargetCreationError {
+        RenderTargetCreationError::ColorTexture(err)
+    }
+}
+
+impl From<RenderBufferCreationError> for RenderTargetCreationError {
+    #[inline]
+    fn from(err: RenderBufferCreationError) -> RenderTargetCreationError {
+        RenderTargetCreationError::DepthBuffer(err)
+    }
+}
+
+/// A color texture and a depth buffer whose dimensions are a fraction of the dimensions of the
+/// default framebuffer.
+///
+/// The attachments are recreated whenever the dimensions of the window change, the next time
+/// you call `as_surface` or `resize`. The content of the target is lost when this happens.
+///
+/// The color texture stores linear values. Since it isn't an sRGB texture, no conversion
+/// happens when drawing to it or when sampling from it, and the conversion to sRGB is only
+/// done once, when you draw the result to the default framebuffer. This is why the default
+/// format is a floating-point format: eight bits per channel are not enough to store linear
+/// colors without visible banding.
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// use glium::Surface;
+/// use glium::framebuffer::RenderTarget;
+///
+/// let mut reflection = RenderTarget::new(&display, 0.5).unwrap();
+///
+/// // each frame
+/// let mut surface = reflection.as_surface();
+/// surface.clear_color_and_depth((0.0, 0.0, 0.0, 1.0), 1.0);
+/// // surface.draw(...);
+/// drop(surface);
+///
+/// // and then use `reflection.get_color_texture().sampled()` as a uniform
+/// ```
+pub struct RenderTarget {
+    context: Rc<Context>,
+    scale: f32,
+    format: UncompressedFloatFormat,
+    depth_format: DepthFormat,
+    color: Texture2d,
+    depth: DepthRenderBuffer,
+}
+
+impl RenderTarget {
+    /// Builds a new render target whose dimensions are the dimensions of the default
+    /// framebuffer multiplied by `scale`.
+    ///
+    /// The color texture has the format `F16F16F16F16` and the depth buffer has the
+    /// format `I24`.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F, scale: f32) -> Result<RenderTarget, RenderTargetCreationError>
+                          where F: Facade
+    {
+        RenderTarget::with_formats(facade, scale, UncompressedFloatFormat::F16F16F16F16,
+                                   DepthFormat::I24)
+    }
+
+    /// Builds a new render target with specific formats for the color texture and the
+    /// depth buffer.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if `scale` is not strictly positive.
+    pub fn with_formats<F: ?Sized>(facade: &F, scale: f32, format: UncompressedFloatFormat,
+                                   depth_format: DepthFormat)
+                                   -> Result<RenderTarget, RenderTargetCreationError>
+                                   where F: Facade
+    {
+        assert!(scale > 0.0, "The scale of a render target must be strictly positive");
+
+        let context = facade.get_context().clone();
+        let (width, height) = scaled_dimensions(&context, scale);
+        let (color, depth) = build_attachments(&context, format, depth_format, width, height)?;
+
+        Ok(RenderTarget {
+            context,
+            scale,
+            format,
+            depth_format,
+            color,
+            depth,
+        })
+    }
+
+    /// Returns the fraction of the dimensions of the default framebuffer used by this target.
+    #[inline]
+    pub fn get_scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Changes the fraction of the dimensions of the default framebuffer used by this target.
+    ///
+    /// The attachments are recreated the next time you call `as_surface` or `resize`.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if `scale` is not strictly positive.
+    #[inline]
+    pub fn set_scale(&mut self, scale: f32) {
+        assert!(scale > 0.0, "The scale of a render target must be strictly positive");
+        self.scale = scale;
+    }
+
+    /// Returns the current dimensions of the attachments.
+    #[inline]
+    pub fn get_dimensions(&self) -> (u32, u32) {
+        self.color.dimensions()
+    }
+
+    /// Recreates the attachments if the dimensions of the default framebuffer have changed.
+    ///
+    /// Returns `true` if the attachments have been recreated. If an error happens, the
+    /// previous attachments are kept.
+    pub fn resize(&mut self) -> Result<bool, RenderTargetCreationError> {
+        let dimensions = scaled_dimensions(&self.context, self.scale);
+        if dimensions == self.color.dimensions() {
+            return Ok(false);
+        }
+
+        let (color, depth) = build_attachments(&self.context, self.format, self.depth_format,
+                                               dimensions.0, dimensions.1)?;
+        self.color = color;
+        self.depth = depth;
+        Ok(true)
+    }
+
+    /// Returns the color texture, in order to sample it.
+    #[inline]
+    pub fn get_color_texture(&self) -> &Texture2d {
+        &self.color
+    }
+
+    /// Returns the depth buffer.
+    #[inline]
+    pub fn get_depth_buffer(&self) -> &DepthRenderBuffer {
+        &self.depth
+    }
+
+    /// Resizes the target if necessary, then starts drawing on it.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if the attachments need to be recreated and their creation fails. Call
+    /// `resize` first if you want to handle this error.
+    pub fn as_surface(&mut self) -> SimpleFrameBuffer<'_> {
+        self.resize().unwrap();
+        SimpleFrameBuffer::with_depth_buffer(&self.context, &self.color, &self.depth).unwrap()
+    }
+}
+
+/// Returns the dimensions of the default framebuffer multiplied by `scale`, with a minimum of 1.
+fn scaled_dimensions(context: &Rc<Context>, scale: f32) -> (u32, u32) {
+    let (width, height) = context.get_framebuffer_dimensions();
+    let width = ((width as f32 * scale).round() as u32).max(1);
+    let height = ((height as f32 * scale).round() as u32).max(1);
+    (width, height)
+}
+
+fn build_attachments(context: &Rc<Context>, format: UncompressedFloatFormat,
+                     depth_format: DepthFormat, width: u32, height: u32)
+                     -> Result<(Texture2d, DepthRenderBuffer), RenderTargetCreationError>
+{
+    let color = Texture2d::empty_with_format(context, format, MipmapsOption::NoMipmap,
+                                             width, height)?;
+    let depth = DepthRenderBuffer::new(context, depth_format, width, height)?;
+    Ok((color, depth))
+}
@@ -0,0 +1,223 @@
+/*!
+Controlling the latency between building a frame and showing it, and measuring frame times.
+
+Drivers usually let the CPU build several frames in advance while the GPU is still busy with
+the previous ones. This maximizes the throughput, but the frames that are shown on the screen
+are late compared to the inputs of the user. A `FramePacer` limits how far ahead the CPU can go,
+and measures how long each frame takes on the CPU and on the GPU.
+
+```no_run
+# use glium::Surface;
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+use glium::framepacing::{FramePacer, LatencyMode};
+
+let mut pacer = FramePacer::new(LatencyMode::MaxFramesInFlight(2));
+
+loop {
+    pacer.begin_frame(&display);
+
+    let mut frame = display.draw();
+    frame.clear_color(0.0, 0.0, 0.0, 0.0);
+    frame.finish().unwrap();
+
+    pacer.end_frame(&display).unwrap();
+
+    let stats = pacer.get_last_frame_stats();
+    println!("CPU: {:?}, GPU: {:?}", stats.cpu_time, stats.gpu_time);
+}
+```
+
+The GPU time of a frame is only known once the GPU has finished the frame. To avoid waiting,
+`FrameStats::gpu_time` is the GPU time of the most recent frame whose timings are available,
+which is usually a frame or two late. It requires OpenGL 3.2 or `GL_EXT_disjoint_timer_query`.
+
+*/
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::backend::Facade;
+use crate::debug::TimestampQuery;
+use crate::sync::{FrameSync, SyncNotSupportedError};
+
+/// Maximum number of frames whose GPU timings can be pending. Beyond that, we wait for the
+/// timings of the oldest frame.
+const MAX_PENDING_TIMINGS: usize = 8;
+
+/// How a `FramePacer` limits the latency.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LatencyMode {
+    /// The latency isn't limited. The driver chooses how many frames can be queued.
+    Unlimited,
+
+    /// Calls `glFinish` at the end of each frame. The next frame only starts once the GPU has
+    /// finished the current one, which gives the lowest latency and the lowest throughput.
+    Finish,
+
+    /// Inserts a fence at the end of each frame, and waits if more than this number of frames
+    /// are being processed. Includes the frame that is about to be built. See `FrameSync`.
+    ///
+    /// Requires fences, in other words OpenGL 3.2, OpenGL ES 3.0 or `GL_ARB_sync`.
+    MaxFramesInFlight(usize),
+}
+
+/// Timings of a frame measured by a `FramePacer`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    /// Time between `begin_frame` and `end_frame`, without the time spent waiting for the GPU.
+    pub cpu_time: Duration,
+
+    /// Time spent by `end_frame` waiting for the GPU.
+    pub wait_time: Duration,
+
+    /// Time between the two last calls to `end_frame`.
+    pub frame_time: Duration,
+
+    /// Time spent by the GPU on the most recent frame whose timings are available, or `None`
+    /// if it isn't known yet or if timer queries aren't supported.
+    pub gpu_time: Option<Duration>,
+}
+
+/// Limits the latency of the frames and measures their timings.
+///
+/// Call `begin_frame` before drawing a frame, and `end_frame` after `Frame::finish`.
+pub struct FramePacer {
+    mode: LatencyMode,
+    frame_sync: Option<FrameSync>,
+    frame_begin: Option<(Instant, Option<TimestampQuery>)>,
+    last_frame_end: Option<Instant>,
+    pending_timings: VecDeque<(TimestampQuery, TimestampQuery)>,
+    stats: FrameStats,
+}
+
+impl FramePacer {
+    /// Builds a new `FramePacer`.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if the mode is `MaxFramesInFlight(0)`.
+    pub fn new(mode: LatencyMode) -> FramePacer {
+        let mut pacer = FramePacer {
+            mode: LatencyMode::Unlimited,
+            frame_sync: None,
+            frame_begin: None,
+            last_frame_end: None,
+            pending_timings: VecDeque::with_capacity(MAX_PENDING_TIMINGS),
+            stats: FrameStats::default(),
+        };
+
+        pacer.set_latency_mode(mode);
+        pacer
+    }
+
+    /// Returns the way the latency is limited.
+    #[inline]
+    pub fn get_latency_mode(&self) -> LatencyMode {
+        self.mode
+    }
+
+    /// Changes the way the latency is limited. Takes effect at the next call to `end_frame`.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if the mode is `MaxFramesInFlight(0)`.
+    pub fn set_latency_mode(&mut self, mode: LatencyMode) {
+        match (mode, &mut self.frame_sync) {
+            (LatencyMode::MaxFramesInFlight(max), &mut Some(ref mut frame_sync)) => {
+                frame_sync.set_max_frames_in_flight(max);
+            },
+            (LatencyMode::MaxFramesInFlight(max), frame_sync) => {
+                *frame_sync = Some(FrameSync::new(max));
+            },
+            // the previous fences are dropped, as we don't wait for them anymore
+            (_, frame_sync) => *frame_sync = None,
+        }
+
+        self.mode = mode;
+    }
+
+    /// Returns the timings of the last frame.
+    #[inline]
+    pub fn get_last_frame_stats(&self) -> FrameStats {
+        self.stats
+    }
+
+    /// Marks the beginning of a frame.
+    pub fn begin_frame<F: ?Sized>(&mut self, facade: &F) where F: Facade {
+        if let Some((_, Some(query))) = self.frame_begin.take() {
+            // `begin_frame` has been called twice in a row
+            query.get();
+        }
+
+        self.frame_begin = Some((Instant::now(), TimestampQuery::new(facade)));
+    }
+
+    /// Marks the end of a frame. Call this after `Frame::finish`.
+    ///
+    /// Blocks according to the latency mode, then updates the statistics of the frame.
+    /// Returns an error if the mode is `MaxFramesInFlight` and fences aren't supported.
+    pub fn end_frame<F: ?Sized>(&mut self, facade: &F) -> Result<(), SyncNotSupportedError>
+                                where F: Facade
+    {
+        let end = Instant::now();
+        let (begin, begin_query) = match self.frame_begin.take() {
+            Some((begin, query)) => (begin, query),
+            None => (end, None),
+        };
+
+        if let Some(begin_query) = begin_query {
+            match TimestampQuery::new(facade) {
+                Some(end_query) => self.pending_timings.push_back((begin_query, end_query)),
+                None => { begin_query.get(); },
+            }
+        }
+
+        let wait_time = match self.mode {
+            LatencyMode::Unlimited => Duration::new(0, 0),
+            LatencyMode::Finish => {
+                facade.get_context().finish();
+                end.elapsed()
+            },
+            LatencyMode::MaxFramesInFlight(_) => {
+                self.frame_sync.as_mut().unwrap().end_frame(facade)?
+            },
+        };
+
+        self.stats.cpu_time = end - begin;
+        self.stats.wait_time = wait_time;
+        self.stats.frame_time = self.last_frame_end.map(|last| end - last)
+                                                   .unwrap_or(Duration::new(0, 0));
+        self.last_frame_end = Some(end);
+
+        self.collect_gpu_timings();
+        Ok(())
+    }
+
+    /// Retrieves the GPU timings of the frames that have been processed.
+    fn collect_gpu_timings(&mut self) {
+        while let Some(&(ref begin, ref end)) = self.pending_timings.front() {
+            if self.pending_timings.len() <= MAX_PENDING_TIMINGS &&
+               !(begin.is_ready() && end.is_ready())
+            {
+                break;
+            }
+
+            let (begin, end) = self.pending_timings.pop_front().unwrap();
+            let (begin, end) = (begin.get(), end.get());
+            self.stats.gpu_time = Some(Duration::from_nanos(end.saturating_sub(begin)));
+        }
+    }
+}
+
+impl Drop for FramePacer {
+    fn drop(&mut self) {
+        // timestamp queries are only destroyed when their value is retrieved
+        if let Some((_, Some(query))) = self.frame_begin.take() {
+            query.get();
+        }
+
+        for (begin, end) in self.pending_timings.drain(..) {
+            begin.get();
+            end.get();
+        }
+    }
+}
@@ -1024,11 +1024,14 @@ pub enum CompressedFormat {
     /// Red/green compressed texture with two signed components.
     RgtcFormatII,
 
-    /// BPTC format with four components represented as integers.
+    /// BPTC format with four components represented as integers. Also called `BC7` by
+    /// DirectX.
     BptcUnorm4,
-    /// BPTC format with three components (no alpha) represented as signed floats.
+    /// BPTC format with three components (no alpha) represented as signed floats. Also called
+    /// `BC6H` by DirectX, this is the usual format of compressed HDR images.
     BptcSignedFloat3,
-    /// BPTC format with three components (no alpha) represented as unsigned floats.
+    /// BPTC format with three components (no alpha) represented as unsigned floats. Also called
+    /// `BC6H` by DirectX, this is the usual format of compressed HDR images.
     BptcUnsignedFloat3,
 
     /// S3TC DXT1 without alpha, see https://www.opengl.org/wiki/S3_Texture_Compression.
@@ -1039,6 +1042,50 @@ pub enum CompressedFormat {
     S3tcDxt3Alpha,
     /// S3TC DXT5, see https://www.opengl.org/wiki/S3_Texture_Compression.
     S3tcDxt5Alpha,
+
+    /// ETC2 format with three components. Also decodes ETC1 data.
+    Etc2Rgb8,
+    /// ETC2 format with three components and a 1-bit alpha.
+    Etc2Rgb8A1,
+    /// ETC2 format with four components, whose alpha is compressed with EAC.
+    Etc2Rgba8,
+    /// EAC format with one unsigned component.
+    EacR11U,
+    /// EAC format with one signed component.
+    EacR11I,
+    /// EAC format with two unsigned components.
+    EacRg11U,
+    /// EAC format with two signed components.
+    EacRg11I,
+
+    /// ASTC format with four components and blocks of 4x4 texels.
+    Astc4x4,
+    /// ASTC format with four components and blocks of 5x4 texels.
+    Astc5x4,
+    /// ASTC format with four components and blocks of 5x5 texels.
+    Astc5x5,
+    /// ASTC format with four components and blocks of 6x5 texels.
+    Astc6x5,
+    /// ASTC format with four components and blocks of 6x6 texels.
+    Astc6x6,
+    /// ASTC format with four components and blocks of 8x5 texels.
+    Astc8x5,
+    /// ASTC format with four components and blocks of 8x6 texels.
+    Astc8x6,
+    /// ASTC format with four components and blocks of 8x8 texels.
+    Astc8x8,
+    /// ASTC format with four components and blocks of 10x5 texels.
+    Astc10x5,
+    /// ASTC format with four components and blocks of 10x6 texels.
+    Astc10x6,
+    /// ASTC format with four components and blocks of 10x8 texels.
+    Astc10x8,
+    /// ASTC format with four components and blocks of 10x10 texels.
+    Astc10x10,
+    /// ASTC format with four components and blocks of 12x10 texels.
+    Astc12x10,
+    /// ASTC format with four components and blocks of 12x12 texels.
+    Astc12x12,
 }
 
 impl CompressedFormat {
@@ -1057,6 +1104,27 @@ impl CompressedFormat {
             CompressedFormat::S3tcDxt1Alpha,
             CompressedFormat::S3tcDxt3Alpha,
             CompressedFormat::S3tcDxt5Alpha,
+            CompressedFormat::Etc2Rgb8,
+            CompressedFormat::Etc2Rgb8A1,
+            CompressedFormat::Etc2Rgba8,
+            CompressedFormat::EacR11U,
+            CompressedFormat::EacR11I,
+            CompressedFormat::EacRg11U,
+            CompressedFormat::EacRg11I,
+            CompressedFormat::Astc4x4,
+            CompressedFormat::Astc5x4,
+            CompressedFormat::Astc5x5,
+            CompressedFormat::Astc6x5,
+            CompressedFormat::Astc6x6,
+            CompressedFormat::Astc8x5,
+            CompressedFormat::Astc8x6,
+            CompressedFormat::Astc8x8,
+            CompressedFormat::Astc10x5,
+            CompressedFormat::Astc10x6,
+            CompressedFormat::Astc10x8,
+            CompressedFormat::Astc10x10,
+            CompressedFormat::Astc12x10,
+            CompressedFormat::Astc12x12,
         ]
     }
 
@@ -1085,13 +1153,13 @@ impl CompressedFormat {
                 version >= &Version(Api::Gl, 3, 0)
             },
             &CompressedFormat::BptcUnorm4 => {
-                version >= &Version(Api::Gl, 4, 2) || extensions.gl_arb_texture_compression_bptc
+                is_bptc_supported(context)
             },
             &CompressedFormat::BptcSignedFloat3 => {
-                version >= &Version(Api::Gl, 4, 2) || extensions.gl_arb_texture_compression_bptc
+                is_bptc_supported(context)
             },
             &CompressedFormat::BptcUnsignedFloat3 => {
-                version >= &Version(Api::Gl, 4, 2) || extensions.gl_arb_texture_compression_bptc
+                is_bptc_supported(context)
             },
             &CompressedFormat::S3tcDxt1NoAlpha => {
                 extensions.gl_ext_texture_compression_s3tc
@@ -1105,6 +1173,16 @@ impl CompressedFormat {
             &CompressedFormat::S3tcDxt5Alpha => {
                 extensions.gl_ext_texture_compression_s3tc
             },
+            &CompressedFormat::Etc2Rgb8 | &CompressedFormat::Etc2Rgb8A1 |
+            &CompressedFormat::Etc2Rgba8 | &CompressedFormat::EacR11U |
+            &CompressedFormat::EacR11I | &CompressedFormat::EacRg11U |
+            &CompressedFormat::EacRg11I => is_etc2_supported(context),
+            &CompressedFormat::Astc4x4 | &CompressedFormat::Astc5x4 | &CompressedFormat::Astc5x5 |
+            &CompressedFormat::Astc6x5 | &CompressedFormat::Astc6x6 | &CompressedFormat::Astc8x5 |
+            &CompressedFormat::Astc8x6 | &CompressedFormat::Astc8x8 | &CompressedFormat::Astc10x5 |
+            &CompressedFormat::Astc10x6 | &CompressedFormat::Astc10x8 |
+            &CompressedFormat::Astc10x10 | &CompressedFormat::Astc12x10 |
+            &CompressedFormat::Astc12x12 => is_astc_supported(context),
         }
     }
 
@@ -1121,6 +1199,57 @@ impl CompressedFormat {
             &CompressedFormat::S3tcDxt1Alpha => gl::COMPRESSED_RGBA_S3TC_DXT1_EXT,
             &CompressedFormat::S3tcDxt3Alpha => gl::COMPRESSED_RGBA_S3TC_DXT3_EXT,
             &CompressedFormat::S3tcDxt5Alpha => gl::COMPRESSED_RGBA_S3TC_DXT5_EXT,
+            &CompressedFormat::Etc2Rgb8 => gl::COMPRESSED_RGB8_ETC2,
+            &CompressedFormat::Etc2Rgb8A1 => gl::COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2,
+            &CompressedFormat::Etc2Rgba8 => gl::COMPRESSED_RGBA8_ETC2_EAC,
+            &CompressedFormat::EacR11U => gl::COMPRESSED_R11_EAC,
+            &CompressedFormat::EacR11I => gl::COMPRESSED_SIGNED_R11_EAC,
+            &CompressedFormat::EacRg11U => gl::COMPRESSED_RG11_EAC,
+            &CompressedFormat::EacRg11I => gl::COMPRESSED_SIGNED_RG11_EAC,
+            &CompressedFormat::Astc4x4 => gl::COMPRESSED_RGBA_ASTC_4x4,
+            &CompressedFormat::Astc5x4 => gl::COMPRESSED_RGBA_ASTC_5x4,
+            &CompressedFormat::Astc5x5 => gl::COMPRESSED_RGBA_ASTC_5x5,
+            &CompressedFormat::Astc6x5 => gl::COMPRESSED_RGBA_ASTC_6x5,
+            &CompressedFormat::Astc6x6 => gl::COMPRESSED_RGBA_ASTC_6x6,
+            &CompressedFormat::Astc8x5 => gl::COMPRESSED_RGBA_ASTC_8x5,
+            &CompressedFormat::Astc8x6 => gl::COMPRESSED_RGBA_ASTC_8x6,
+            &CompressedFormat::Astc8x8 => gl::COMPRESSED_RGBA_ASTC_8x8,
+            &CompressedFormat::Astc10x5 => gl::COMPRESSED_RGBA_ASTC_10x5,
+            &CompressedFormat::Astc10x6 => gl::COMPRESSED_RGBA_ASTC_10x6,
+            &CompressedFormat::Astc10x8 => gl::COMPRESSED_RGBA_ASTC_10x8,
+            &CompressedFormat::Astc10x10 => gl::COMPRESSED_RGBA_ASTC_10x10,
+            &CompressedFormat::Astc12x10 => gl::COMPRESSED_RGBA_ASTC_12x10,
+            &CompressedFormat::Astc12x12 => gl::COMPRESSED_RGBA_ASTC_12x12,
+        }
+    }
+
+    /// Returns the width and height of the blocks of texels of this format, if it is an ASTC
+    /// format.
+    pub(crate) fn get_astc_block_size(&self) -> Option<(u32, u32)> {
+        match self {
+            &CompressedFormat::Astc4x4 => Some((4, 4)),
+            &CompressedFormat::Astc5x4 => Some((5, 4)),
+            &CompressedFormat::Astc5x5 => Some((5, 5)),
+            &CompressedFormat::Astc6x5 => Some((6, 5)),
+            &CompressedFormat::Astc6x6 => Some((6, 6)),
+            &CompressedFormat::Astc8x5 => Some((8, 5)),
+            &CompressedFormat::Astc8x6 => Some((8, 6)),
+            &CompressedFormat::Astc8x8 => Some((8, 8)),
+            &CompressedFormat::Astc10x5 => Some((10, 5)),
+            &CompressedFormat::Astc10x6 => Some((10, 6)),
+            &CompressedFormat::Astc10x8 => Some((10, 8)),
+            &CompressedFormat::Astc10x10 => Some((10, 10)),
+            &CompressedFormat::Astc12x10 => Some((12, 10)),
+            &CompressedFormat::Astc12x12 => Some((12, 12)),
+            &CompressedFormat::RgtcFormatU | &CompressedFormat::RgtcFormatI |
+            &CompressedFormat::RgtcFormatUU | &CompressedFormat::RgtcFormatII |
+            &CompressedFormat::BptcUnorm4 | &CompressedFormat::BptcSignedFloat3 |
+            &CompressedFormat::BptcUnsignedFloat3 | &CompressedFormat::S3tcDxt1NoAlpha |
+            &CompressedFormat::S3tcDxt1Alpha | &CompressedFormat::S3tcDxt3Alpha |
+            &CompressedFormat::S3tcDxt5Alpha | &CompressedFormat::Etc2Rgb8 |
+            &CompressedFormat::Etc2Rgb8A1 | &CompressedFormat::Etc2Rgba8 |
+            &CompressedFormat::EacR11U | &CompressedFormat::EacR11I | &CompressedFormat::EacRg11U |
+            &CompressedFormat::EacRg11I => None,
         }
     }
 }
@@ -1135,6 +1264,40 @@ pub enum CompressedSrgbFormat {
     S3tcDxt1Alpha,
     S3tcDxt3Alpha,
     S3tcDxt5Alpha,
+    /// ETC2 format. sRGB without alpha.
+    Etc2Rgb8,
+    /// ETC2 format. sRGB with a 1-bit alpha.
+    Etc2Rgb8A1,
+    /// ETC2 format. sRGB with an alpha compressed with EAC.
+    Etc2Rgba8,
+    /// ASTC format with blocks of 4x4 texels. sRGB with alpha.
+    Astc4x4,
+    /// ASTC format with blocks of 5x4 texels. sRGB with alpha.
+    Astc5x4,
+    /// ASTC format with blocks of 5x5 texels. sRGB with alpha.
+    Astc5x5,
+    /// ASTC format with blocks of 6x5 texels. sRGB with alpha.
+    Astc6x5,
+    /// ASTC format with blocks of 6x6 texels. sRGB with alpha.
+    Astc6x6,
+    /// ASTC format with blocks of 8x5 texels. sRGB with alpha.
+    Astc8x5,
+    /// ASTC format with blocks of 8x6 texels. sRGB with alpha.
+    Astc8x6,
+    /// ASTC format with blocks of 8x8 texels. sRGB with alpha.
+    Astc8x8,
+    /// ASTC format with blocks of 10x5 texels. sRGB with alpha.
+    Astc10x5,
+    /// ASTC format with blocks of 10x6 texels. sRGB with alpha.
+    Astc10x6,
+    /// ASTC format with blocks of 10x8 texels. sRGB with alpha.
+    Astc10x8,
+    /// ASTC format with blocks of 10x10 texels. sRGB with alpha.
+    Astc10x10,
+    /// ASTC format with blocks of 12x10 texels. sRGB with alpha.
+    Astc12x10,
+    /// ASTC format with blocks of 12x12 texels. sRGB with alpha.
+    Astc12x12,
 }
 
 impl CompressedSrgbFormat {
@@ -1147,6 +1310,23 @@ impl CompressedSrgbFormat {
             CompressedSrgbFormat::S3tcDxt1Alpha,
             CompressedSrgbFormat::S3tcDxt3Alpha,
             CompressedSrgbFormat::S3tcDxt5Alpha,
+            CompressedSrgbFormat::Etc2Rgb8,
+            CompressedSrgbFormat::Etc2Rgb8A1,
+            CompressedSrgbFormat::Etc2Rgba8,
+            CompressedSrgbFormat::Astc4x4,
+            CompressedSrgbFormat::Astc5x4,
+            CompressedSrgbFormat::Astc5x5,
+            CompressedSrgbFormat::Astc6x5,
+            CompressedSrgbFormat::Astc6x6,
+            CompressedSrgbFormat::Astc8x5,
+            CompressedSrgbFormat::Astc8x6,
+            CompressedSrgbFormat::Astc8x8,
+            CompressedSrgbFormat::Astc10x5,
+            CompressedSrgbFormat::Astc10x6,
+            CompressedSrgbFormat::Astc10x8,
+            CompressedSrgbFormat::Astc10x10,
+            CompressedSrgbFormat::Astc12x10,
+            CompressedSrgbFormat::Astc12x12,
         ]
     }
 
@@ -1158,12 +1338,11 @@ impl CompressedSrgbFormat {
 
     /// Returns true if this format is supported by the backend.
     pub fn is_supported<C: ?Sized>(&self, context: &C) -> bool where C: CapabilitiesSource {
-        let version = context.get_version();
         let extensions = context.get_extensions();
 
         match self {
             &CompressedSrgbFormat::Bptc => {
-                version >= &Version(Api::Gl, 4, 2) || extensions.gl_arb_texture_compression_bptc
+                is_bptc_supported(context)
             },
             &CompressedSrgbFormat::S3tcDxt1NoAlpha => {
                 extensions.gl_ext_texture_compression_s3tc && extensions.gl_ext_texture_srgb
@@ -1177,6 +1356,16 @@ impl CompressedSrgbFormat {
             &CompressedSrgbFormat::S3tcDxt5Alpha => {
                 extensions.gl_ext_texture_compression_s3tc && extensions.gl_ext_texture_srgb
             },
+            &CompressedSrgbFormat::Etc2Rgb8 | &CompressedSrgbFormat::Etc2Rgb8A1 |
+            &CompressedSrgbFormat::Etc2Rgba8 => is_etc2_supported(context),
+            &CompressedSrgbFormat::Astc4x4 | &CompressedSrgbFormat::Astc5x4 |
+            &CompressedSrgbFormat::Astc5x5 | &CompressedSrgbFormat::Astc6x5 |
+            &CompressedSrgbFormat::Astc6x6 | &CompressedSrgbFormat::Astc8x5 |
+            &CompressedSrgbFormat::Astc8x6 | &CompressedSrgbFormat::Astc8x8 |
+            &CompressedSrgbFormat::Astc10x5 | &CompressedSrgbFormat::Astc10x6 |
+            &CompressedSrgbFormat::Astc10x8 | &CompressedSrgbFormat::Astc10x10 |
+            &CompressedSrgbFormat::Astc12x10 |
+            &CompressedSrgbFormat::Astc12x12 => is_astc_supported(context),
         }
     }
 
@@ -1187,10 +1376,76 @@ impl CompressedSrgbFormat {
             &CompressedSrgbFormat::S3tcDxt1Alpha => gl::COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT,
             &CompressedSrgbFormat::S3tcDxt3Alpha => gl::COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT,
             &CompressedSrgbFormat::S3tcDxt5Alpha => gl::COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT,
+            &CompressedSrgbFormat::Etc2Rgb8 => gl::COMPRESSED_SRGB8_ETC2,
+            &CompressedSrgbFormat::Etc2Rgb8A1 => gl::COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2,
+            &CompressedSrgbFormat::Etc2Rgba8 => gl::COMPRESSED_SRGB8_ALPHA8_ETC2_EAC,
+            &CompressedSrgbFormat::Astc4x4 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_4x4,
+            &CompressedSrgbFormat::Astc5x4 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_5x4,
+            &CompressedSrgbFormat::Astc5x5 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_5x5,
+            &CompressedSrgbFormat::Astc6x5 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_6x5,
+            &CompressedSrgbFormat::Astc6x6 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_6x6,
+            &CompressedSrgbFormat::Astc8x5 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_8x5,
+            &CompressedSrgbFormat::Astc8x6 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_8x6,
+            &CompressedSrgbFormat::Astc8x8 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_8x8,
+            &CompressedSrgbFormat::Astc10x5 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_10x5,
+            &CompressedSrgbFormat::Astc10x6 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_10x6,
+            &CompressedSrgbFormat::Astc10x8 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_10x8,
+            &CompressedSrgbFormat::Astc10x10 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_10x10,
+            &CompressedSrgbFormat::Astc12x10 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_12x10,
+            &CompressedSrgbFormat::Astc12x12 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_12x12,
+        }
+    }
+
+    /// Returns the width and height of the blocks of texels of this format, if it is an ASTC
+    /// format.
+    pub(crate) fn get_astc_block_size(&self) -> Option<(u32, u32)> {
+        match self {
+            &CompressedSrgbFormat::Astc4x4 => Some((4, 4)),
+            &CompressedSrgbFormat::Astc5x4 => Some((5, 4)),
+            &CompressedSrgbFormat::Astc5x5 => Some((5, 5)),
+            &CompressedSrgbFormat::Astc6x5 => Some((6, 5)),
+            &CompressedSrgbFormat::Astc6x6 => Some((6, 6)),
+            &CompressedSrgbFormat::Astc8x5 => Some((8, 5)),
+            &CompressedSrgbFormat::Astc8x6 => Some((8, 6)),
+            &CompressedSrgbFormat::Astc8x8 => Some((8, 8)),
+            &CompressedSrgbFormat::Astc10x5 => Some((10, 5)),
+            &CompressedSrgbFormat::Astc10x6 => Some((10, 6)),
+            &CompressedSrgbFormat::Astc10x8 => Some((10, 8)),
+            &CompressedSrgbFormat::Astc10x10 => Some((10, 10)),
+            &CompressedSrgbFormat::Astc12x10 => Some((12, 10)),
+            &CompressedSrgbFormat::Astc12x12 => Some((12, 12)),
+            &CompressedSrgbFormat::Bptc | &CompressedSrgbFormat::S3tcDxt1NoAlpha |
+            &CompressedSrgbFormat::S3tcDxt1Alpha | &CompressedSrgbFormat::S3tcDxt3Alpha |
+            &CompressedSrgbFormat::S3tcDxt5Alpha | &CompressedSrgbFormat::Etc2Rgb8 |
+            &CompressedSrgbFormat::Etc2Rgb8A1 | &CompressedSrgbFormat::Etc2Rgba8 => None,
         }
     }
 }
 
+/// Returns true if the BPTC compressed formats are supported by the backend.
+#[inline]
+fn is_bptc_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+    context.get_version() >= &Version(Api::Gl, 4, 2) ||
+    context.get_extensions().gl_arb_texture_compression_bptc ||
+    context.get_extensions().gl_ext_texture_compression_bptc
+}
+
+/// Returns true if the ETC2 and EAC compressed formats are supported by the backend.
+#[inline]
+fn is_etc2_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+    context.get_version() >= &Version(Api::Gl, 4, 3) ||
+    context.get_version() >= &Version(Api::GlEs, 3, 0) ||
+    context.get_extensions().gl_arb_es3_compatibility
+}
+
+/// Returns true if the LDR profile of the ASTC compressed formats is supported by the backend.
+#[inline]
+fn is_astc_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+    context.get_version() >= &Version(Api::GlEs, 3, 2) ||
+    context.get_extensions().gl_khr_texture_compression_astc_ldr ||
+    context.get_extensions().gl_oes_texture_compression_astc
+}
+
 /// List of formats available for depth textures.
 ///
 /// `I16`, `I24` and `I32` are still treated as if they were floating points.
@@ -1258,8 +1513,6 @@ impl DepthFormat {
 }
 
 /// List of formats available for depth-stencil textures.
-// TODO: If OpenGL 4.3 or ARB_stencil_texturing is not available, then depth/stencil
-//       textures are treated by samplers exactly like depth-only textures
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 pub enum DepthStencilFormat {
@@ -1532,16 +1785,22 @@ impl ClientFormatAny {
             ClientFormatAny::CompressedFormat(CompressedFormat::S3tcDxt1NoAlpha) |
             ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt1NoAlpha) |
             ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatU) |
-            ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatI) => {
+            ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatI) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::Etc2Rgb8) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Etc2Rgb8) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::Etc2Rgb8A1) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Etc2Rgb8A1) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::EacR11U) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::EacR11I) => {
 
                 let width = if width < 4 { 4 } else { width as usize };
                 let height = height.map(|height| if height < 4 { 4 } else { height as usize })
-                                   .expect("ST3C, RGTC and BPTC textures must have 2 dimensions");
+                                   .expect("ST3C, RGTC, BPTC and ETC2 textures must have 2 dimensions");
                 if (width % 4) != 0 || (height % 4) != 0 {
-                    panic!("ST3C, RGTC and BPTC textures must have a width and height multiple of 4.");
+                    panic!("ST3C, RGTC, BPTC and ETC2 textures must have a width and height multiple of 4.");
                 }
                 if depth.is_some() { // allow `array_size` (2D textures arrays) but not depth (3D textures)
-                    panic!("ST3C, RGTC and BPTC textures are 2 dimension only.")
+                    panic!("ST3C, RGTC, BPTC and ETC2 textures are 2 dimension only.")
                 }
 
                 let uncompressed_bit_size =  4 * width as usize * height as usize *
@@ -1559,22 +1818,72 @@ impl ClientFormatAny {
             ClientFormatAny::CompressedFormat(CompressedFormat::BptcSignedFloat3) |
             ClientFormatAny::CompressedFormat(CompressedFormat::BptcUnsignedFloat3) |
             ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatUU) |
-            ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatII) => {
+            ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatII) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::Etc2Rgba8) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Etc2Rgba8) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::EacRg11U) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::EacRg11I) => {
 
                 let width = if width < 4 { 4 } else { width as usize };
                 let height = height.map(|height| if height < 4 { 4 } else { height as usize })
-                                   .expect("ST3C, RGTC and BPTC textures must have 2 dimensions");
+                                   .expect("ST3C, RGTC, BPTC and ETC2 textures must have 2 dimensions");
                 if (width % 4) != 0 || (height % 4) != 0 {
-                    panic!("ST3C, RGTC and BPTC textures must have a width and height multiple of 4.");
+                    panic!("ST3C, RGTC, BPTC and ETC2 textures must have a width and height multiple of 4.");
                 }
                 if depth.is_some() { // allow `array_size` (2D textures arrays) but not depth (3D textures)
-                    panic!("ST3C, RGTC and BPTC textures are 2 dimension only.")
+                    panic!("ST3C, RGTC, BPTC and ETC2 textures are 2 dimension only.")
                 }
 
                 let uncompressed_bit_size =  4 * width as usize * height as usize *
                                             depth.unwrap_or(1) as usize * array_size.unwrap_or(1) as usize;
                 uncompressed_bit_size / 4   // Apply 4:1 compression ratio
             },
+
+            // 16 bytes per block, whose size depends on the format
+            ClientFormatAny::CompressedFormat(CompressedFormat::Astc4x4) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::Astc5x4) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::Astc5x5) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::Astc6x5) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::Astc6x6) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::Astc8x5) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::Astc8x6) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::Astc8x8) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::Astc10x5) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::Astc10x6) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::Astc10x8) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::Astc10x10) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::Astc12x10) |
+            ClientFormatAny::CompressedFormat(CompressedFormat::Astc12x12) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc4x4) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc5x4) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc5x5) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc6x5) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc6x6) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc8x5) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc8x6) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc8x8) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc10x5) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc10x6) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc10x8) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc10x10) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc12x10) |
+            ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc12x12) => {
+                let (block_width, block_height) = match *self {
+                    ClientFormatAny::CompressedFormat(format) => format.get_astc_block_size(),
+                    ClientFormatAny::CompressedSrgbFormat(format) => format.get_astc_block_size(),
+                    ClientFormatAny::ClientFormat(_) => None,
+                }.unwrap();
+
+                let height = height.expect("ASTC textures must have 2 dimensions");
+                if depth.is_some() { // allow `array_size` (2D textures arrays) but not depth (3D textures)
+                    panic!("ASTC textures are 2 dimension only.")
+                }
+
+                // the last blocks of each row and column can be partially used
+                let blocks_x = width.div_ceil(block_width) as usize;
+                let blocks_y = height.div_ceil(block_height) as usize;
+                blocks_x * blocks_y * array_size.unwrap_or(1) as usize * 16
+            },
         }
     }
 
@@ -1605,6 +1914,44 @@ impl ClientFormatAny {
             gl::COMPRESSED_SIGNED_RED_RGTC1 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatI)),
             gl::COMPRESSED_RG_RGTC2 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatUU)),
             gl::COMPRESSED_SIGNED_RG_RGTC2 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatII)),
+            gl::COMPRESSED_RGB8_ETC2 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Etc2Rgb8)),
+            gl::COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Etc2Rgb8A1)),
+            gl::COMPRESSED_RGBA8_ETC2_EAC => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Etc2Rgba8)),
+            gl::COMPRESSED_R11_EAC => Some(ClientFormatAny::CompressedFormat(CompressedFormat::EacR11U)),
+            gl::COMPRESSED_SIGNED_R11_EAC => Some(ClientFormatAny::CompressedFormat(CompressedFormat::EacR11I)),
+            gl::COMPRESSED_RG11_EAC => Some(ClientFormatAny::CompressedFormat(CompressedFormat::EacRg11U)),
+            gl::COMPRESSED_SIGNED_RG11_EAC => Some(ClientFormatAny::CompressedFormat(CompressedFormat::EacRg11I)),
+            gl::COMPRESSED_SRGB8_ETC2 => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Etc2Rgb8)),
+            gl::COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2 => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Etc2Rgb8A1)),
+            gl::COMPRESSED_SRGB8_ALPHA8_ETC2_EAC => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Etc2Rgba8)),
+            gl::COMPRESSED_RGBA_ASTC_4x4 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Astc4x4)),
+            gl::COMPRESSED_RGBA_ASTC_5x4 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Astc5x4)),
+            gl::COMPRESSED_RGBA_ASTC_5x5 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Astc5x5)),
+            gl::COMPRESSED_RGBA_ASTC_6x5 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Astc6x5)),
+            gl::COMPRESSED_RGBA_ASTC_6x6 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Astc6x6)),
+            gl::COMPRESSED_RGBA_ASTC_8x5 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Astc8x5)),
+            gl::COMPRESSED_RGBA_ASTC_8x6 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Astc8x6)),
+            gl::COMPRESSED_RGBA_ASTC_8x8 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Astc8x8)),
+            gl::COMPRESSED_RGBA_ASTC_10x5 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Astc10x5)),
+            gl::COMPRESSED_RGBA_ASTC_10x6 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Astc10x6)),
+            gl::COMPRESSED_RGBA_ASTC_10x8 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Astc10x8)),
+            gl::COMPRESSED_RGBA_ASTC_10x10 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Astc10x10)),
+            gl::COMPRESSED_RGBA_ASTC_12x10 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Astc12x10)),
+            gl::COMPRESSED_RGBA_ASTC_12x12 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::Astc12x12)),
+            gl::COMPRESSED_SRGB8_ALPHA8_ASTC_4x4 => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc4x4)),
+            gl::COMPRESSED_SRGB8_ALPHA8_ASTC_5x4 => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc5x4)),
+            gl::COMPRESSED_SRGB8_ALPHA8_ASTC_5x5 => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc5x5)),
+            gl::COMPRESSED_SRGB8_ALPHA8_ASTC_6x5 => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc6x5)),
+            gl::COMPRESSED_SRGB8_ALPHA8_ASTC_6x6 => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc6x6)),
+            gl::COMPRESSED_SRGB8_ALPHA8_ASTC_8x5 => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc8x5)),
+            gl::COMPRESSED_SRGB8_ALPHA8_ASTC_8x6 => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc8x6)),
+            gl::COMPRESSED_SRGB8_ALPHA8_ASTC_8x8 => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc8x8)),
+            gl::COMPRESSED_SRGB8_ALPHA8_ASTC_10x5 => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc10x5)),
+            gl::COMPRESSED_SRGB8_ALPHA8_ASTC_10x6 => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc10x6)),
+            gl::COMPRESSED_SRGB8_ALPHA8_ASTC_10x8 => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc10x8)),
+            gl::COMPRESSED_SRGB8_ALPHA8_ASTC_10x10 => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc10x10)),
+            gl::COMPRESSED_SRGB8_ALPHA8_ASTC_12x10 => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc12x10)),
+            gl::COMPRESSED_SRGB8_ALPHA8_ASTC_12x12 => Some(ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc12x12)),
             _ => None,
         }
     }
@@ -1,5 +1,5 @@
 use crate::buffer::{Buffer, BufferSlice, BufferMutSlice, BufferAny, BufferType};
-use crate::buffer::{BufferMode, BufferCreationError};
+use crate::buffer::{BufferMode, BufferCreationError, DetachedBuffer};
 use crate::gl;
 use crate::GlObject;
 
@@ -9,6 +9,7 @@ use crate::index::IndicesSource;
 use crate::index::Index;
 use crate::index::IndexType;
 use crate::index::PrimitiveType;
+use crate::transfer::Transferable;
 
 use std::ops::{Deref, DerefMut};
 use std::fmt;
@@ -104,6 +105,64 @@ impl<T> IndexBuffer<T> where T: Index {
         IndexBuffer::new_impl(facade, prim, data, BufferMode::Immutable)
     }
 
+    /// Builds a new index buffer from the indices produced by an iterator.
+    ///
+    /// The indices are written directly to the buffer, without being collected in a temporary
+    /// container first.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the iterator doesn't produce exactly as many elements as its `len()`.
+    #[inline]
+    pub fn from_exact_iter<F: ?Sized, I>(facade: &F, prim: PrimitiveType, iter: I)
+                                         -> Result<IndexBuffer<T>, CreationError>
+                                         where F: Facade, I: IntoIterator<Item = T>,
+                                               I::IntoIter: ExactSizeIterator
+    {
+        if !prim.is_supported(facade) {
+            return Err(CreationError::PrimitiveTypeNotSupported);
+        }
+
+        if !T::is_supported(facade) {
+            return Err(CreationError::IndexTypeNotSupported);
+        }
+
+        Ok(IndexBuffer {
+            buffer: Buffer::from_exact_iter(facade, iter, BufferType::ElementArrayBuffer,
+                                            BufferMode::Default)?,
+            primitives: prim,
+        })
+    }
+
+    /// Builds a new index buffer from the indices produced by an iterator.
+    ///
+    /// Contrary to `from_exact_iter`, the iterator doesn't need to know its length in advance.
+    /// If it doesn't, the indices are first collected in a `Vec`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the lower and upper bounds of the `size_hint` of the iterator are equal but the
+    /// iterator doesn't produce exactly this number of elements.
+    #[inline]
+    pub fn from_iter<F: ?Sized, I>(facade: &F, prim: PrimitiveType, iter: I)
+                                   -> Result<IndexBuffer<T>, CreationError>
+                                   where F: Facade, I: IntoIterator<Item = T>
+    {
+        if !prim.is_supported(facade) {
+            return Err(CreationError::PrimitiveTypeNotSupported);
+        }
+
+        if !T::is_supported(facade) {
+            return Err(CreationError::IndexTypeNotSupported);
+        }
+
+        Ok(IndexBuffer {
+            buffer: Buffer::from_iter(facade, iter, BufferType::ElementArrayBuffer,
+                                      BufferMode::Default)?,
+            primitives: prim,
+        })
+    }
+
     #[inline]
     fn new_impl<F: ?Sized>(facade: &F, prim: PrimitiveType, data: &[T], mode: BufferMode)
                    -> Result<IndexBuffer<T>, CreationError>
@@ -203,6 +262,25 @@ impl<T> IndexBuffer<T> where T: Index {
     }
 }
 
+impl<T> Transferable for IndexBuffer<T> where T: Index {
+    type Detached = (DetachedBuffer, PrimitiveType);
+
+    #[inline]
+    fn detach(self) -> (DetachedBuffer, PrimitiveType) {
+        (self.buffer.detach(), self.primitives)
+    }
+
+    #[inline]
+    unsafe fn attach<F: ?Sized>(facade: &F, (buffer, primitives): (DetachedBuffer, PrimitiveType))
+                                -> IndexBuffer<T> where F: Facade
+    {
+        IndexBuffer {
+            buffer: Buffer::attach(facade, buffer),
+            primitives,
+        }
+    }
+}
+
 impl<T> Deref for IndexBuffer<T> where T: Index {
     type Target = Buffer<[T]>;
 
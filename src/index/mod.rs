@@ -28,6 +28,12 @@ In addition to indices, you can also use **multidraw indirect** rendering.
 
 The idea is to put a list of things to render in a buffer, and pass that buffer to OpenGL.
 
+If the `GL_ARB_indirect_parameters` extension is available, the number of commands to execute
+can itself be read from a buffer in video memory. This allows for example a compute shader to
+cull objects and write the list of commands and their number without the CPU ever having to
+read the count back. See `DrawCommandsNoIndicesBuffer::with_primitive_type_and_count` and
+`DrawCommandsIndicesBuffer::with_index_buffer_and_count`.
+
 */
 use crate::gl;
 use crate::ToGlEnum;
@@ -80,6 +86,36 @@ pub enum IndicesSource<'a> {
         primitives: PrimitiveType,
     },
 
+    /// Use a multidraw indirect buffer without indices, and read the number of commands to
+    /// execute from another buffer.
+    MultidrawArrayCount {
+        /// The buffer of the commands.
+        buffer: BufferAnySlice<'a>,
+        /// The buffer containing the number of commands to execute, as a single `u32`.
+        count: BufferAnySlice<'a>,
+        /// Maximum number of commands to execute, regardless of the value in `count`.
+        max_count: u32,
+        /// Type of primitives contained in the vertex source.
+        primitives: PrimitiveType,
+    },
+
+    /// Use a multidraw indirect buffer with indices, and read the number of commands to
+    /// execute from another buffer.
+    MultidrawElementCount {
+        /// The buffer of the commands.
+        commands: BufferAnySlice<'a>,
+        /// The buffer containing the number of commands to execute, as a single `u32`.
+        count: BufferAnySlice<'a>,
+        /// Maximum number of commands to execute, regardless of the value in `count`.
+        max_count: u32,
+        /// The buffer of the indices.
+        indices: BufferAnySlice<'a>,
+        /// Type of indices in the buffer.
+        data_type: IndexType,
+        /// Type of primitives contained in the vertex source.
+        primitives: PrimitiveType,
+    },
+
     /// Don't use indices. Assemble primitives by using the order in which the vertices are in
     /// the vertices source.
     NoIndices {
@@ -96,6 +132,8 @@ impl<'a> IndicesSource<'a> {
             &IndicesSource::IndexBuffer { primitives, .. } => primitives,
             &IndicesSource::MultidrawArray { primitives, .. } => primitives,
             &IndicesSource::MultidrawElement { primitives, .. } => primitives,
+            &IndicesSource::MultidrawArrayCount { primitives, .. } => primitives,
+            &IndicesSource::MultidrawElementCount { primitives, .. } => primitives,
             &IndicesSource::NoIndices { primitives } => primitives,
         }
     }
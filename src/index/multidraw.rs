@@ -112,6 +112,23 @@ impl DrawCommandsNoIndicesBuffer {
             primitives,
         }
     }
+
+    /// Builds an indices source from this buffer and a primitives type. The number of commands
+    /// to execute is read from `count` by the GPU, and is capped to `max_count`.
+    ///
+    /// Drawing with this indices source requires the `GL_ARB_indirect_parameters` extension.
+    #[inline]
+    pub fn with_primitive_type_and_count<'a, C>(&'a self, primitives: PrimitiveType, count: C,
+                                                max_count: u32) -> IndicesSource<'a>
+                                                where C: Into<BufferSlice<'a, u32>>
+    {
+        IndicesSource::MultidrawArrayCount {
+            buffer: self.buffer.as_slice_any(),
+            count: count.into().as_slice_any(),
+            max_count,
+            primitives,
+        }
+    }
 }
 
 impl Deref for DrawCommandsNoIndicesBuffer {
@@ -217,6 +234,25 @@ impl DrawCommandsIndicesBuffer {
             primitives: index_buffer.get_primitives_type(),
         }
     }
+
+    /// Builds an indices source from this buffer and an index buffer. The number of commands
+    /// to execute is read from `count` by the GPU, and is capped to `max_count`.
+    ///
+    /// Drawing with this indices source requires the `GL_ARB_indirect_parameters` extension.
+    #[inline]
+    pub fn with_index_buffer_and_count<'a, T, C>(&'a self, index_buffer: &'a IndexBuffer<T>,
+                                                 count: C, max_count: u32) -> IndicesSource<'a>
+                                                 where T: Index, C: Into<BufferSlice<'a, u32>>
+    {
+        IndicesSource::MultidrawElementCount {
+            commands: self.buffer.as_slice_any(),
+            count: count.into().as_slice_any(),
+            max_count,
+            indices: index_buffer.as_slice_any(),
+            data_type: index_buffer.get_indices_type(),
+            primitives: index_buffer.get_primitives_type(),
+        }
+    }
 }
 
 impl Deref for DrawCommandsIndicesBuffer {
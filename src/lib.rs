@@ -72,8 +72,9 @@ result to the user.
  - **Instancing**: Instancing is done either by passing a `vertex::EmptyInstanceAttributes` marker
    or one or several references to vertex buffers wrapped inside a `PerInstance` struct. See the
    `vertex` module for more infos.
- - **Memory barriers**: Calling `glMemoryBarrier` is automatically handled by glium, however you
-   still need to call `memoryBarrier()` in your GLSL code in some situations.
+ - **Memory barriers**: Calling `glMemoryBarrier` is automatically handled by glium for buffers,
+   however you still need to call `memoryBarrier()` in your GLSL code in some situations. The
+   textures written through image units require calling `Context::memory_barrier` yourself.
  - **Programs**: See the `program` module.
  - **Query objects**: The corresponding structs are in the `draw_parameters` module. They are
    passed as draw parameters.
@@ -102,18 +103,23 @@ extern crate lazy_static;
 
 #[cfg(feature = "glutin")]
 pub use crate::backend::glutin::glutin;
-pub use crate::context::Profile;
+pub use crate::context::{MemoryBarriers, MemoryBarriersNotSupportedError, Profile};
 pub use crate::draw_parameters::{Blend, BlendingFunction, LinearBlendingFactor, BackfaceCullingMode};
+pub use crate::draw_parameters::AdvancedBlendEquation;
 pub use crate::draw_parameters::{Depth, DepthTest, PolygonMode, DrawParameters, StencilTest, StencilOperation};
-pub use crate::draw_parameters::{Smooth};
+pub use crate::draw_parameters::{Smooth, LogicOp};
 pub use crate::index::IndexBuffer;
 pub use crate::vertex::{VertexBuffer, Vertex, VertexFormat};
 pub use crate::program::{Program, ProgramCreationError};
 pub use crate::program::ProgramCreationError::{CompilationError, LinkingError, ShaderTypeNotSupported};
-pub use crate::sync::{LinearSyncFence, SyncFence};
+pub use crate::sync::{FrameSync, LinearSyncFence, SyncFence, SyncNotSupportedError};
 pub use crate::texture::Texture2d;
 pub use crate::version::{Api, Version, get_supported_glsl_version};
 pub use crate::ops::ReadError;
+pub use crate::ops::{ColorSpace, CopyToCpuOptions, CpuImage};
+pub use crate::ops::InvalidateBuffers;
+pub use crate::ops::{BlitError, BlitMask};
+pub use crate::ops::PendingRead;
 
 use std::rc::Rc;
 use std::thread;
@@ -132,15 +138,21 @@ mod macros;
 
 pub mod backend;
 pub mod buffer;
+#[cfg(feature = "gl_call_log")]
+pub mod call_log;
 pub mod debug;
 pub mod draw_parameters;
 pub mod framebuffer;
+pub mod framepacing;
 pub mod index;
 pub mod pixel_buffer;
 pub mod program;
+pub mod skinning;
+pub mod sort;
 pub mod uniforms;
 pub mod vertex;
 pub mod texture;
+pub mod transfer;
 pub mod field;
 
 mod context;
@@ -248,6 +260,10 @@ trait BufferExt {
     /// `glMemoryBarrier(GL_COMMAND_BARRIER_BIT)` if necessary.
     fn prepare_and_bind_for_draw_indirect(&self, _: &mut CommandContext<'_>);
 
+    /// Makes sure that the buffer is bound to the `GL_PARAMETER_BUFFER` and calls
+    /// `glMemoryBarrier(GL_COMMAND_BARRIER_BIT)` if necessary.
+    fn prepare_and_bind_for_parameter(&self, _: &mut CommandContext<'_>);
+
     /// Makes sure that the buffer is bound to the `GL_DISPATCH_INDIRECT_BUFFER` and calls
     /// `glMemoryBarrier(GL_COMMAND_BARRIER_BIT)` if necessary.
     fn prepare_and_bind_for_dispatch_indirect(&self, _: &mut CommandContext<'_>);
@@ -287,6 +303,10 @@ trait ContextExt {
 
     /// Returns the capabilities of the backend.
     fn capabilities(&self) -> &context::Capabilities;
+
+    /// Makes current each other context whose objects are shared with this one and calls the
+    /// callback with it. Used to purge the caches of these contexts when an object is destroyed.
+    fn for_each_shared_context(&self, callback: &mut dyn FnMut(&mut context::CommandContext<'_>));
 }
 
 /// Internal trait for programs.
@@ -299,6 +319,13 @@ trait ProgramExt {
     fn set_uniform(&self, ctxt: &mut context::CommandContext<'_>, uniform_location: gl::types::GLint,
                    value: &RawUniformValue);
 
+    /// Returns the texture unit that was assigned to a sampler uniform by a previous draw, if any.
+    fn get_texture_unit(&self, uniform_location: gl::types::GLint) -> Option<u16>;
+
+    /// Assigns a texture unit to a sampler uniform of the program.
+    fn set_texture_unit(&self, ctxt: &mut context::CommandContext<'_>,
+                        uniform_location: gl::types::GLint, unit: u16);
+
     /// Changes the uniform block binding of the program.
     fn set_uniform_block_binding(&self, ctxt: &mut context::CommandContext<'_>,
                                  block_location: gl::types::GLuint, value: gl::types::GLuint);
@@ -355,6 +382,9 @@ trait TextureExt {
     /// Returns the bind point of the texture.
     fn get_bind_point(&self) -> gl::types::GLenum;
 
+    /// Returns the most detailed mipmap level that can be sampled.
+    fn get_base_level(&self) -> u32;
+
     /// Makes sure that the texture is bound to the current texture unit and returns the
     /// bind point to use to access the texture (eg. `GL_TEXTURE_2D`, `GL_TEXTURE_3D`, etc.).
     fn bind_to_current(&self, _: &mut CommandContext<'_>) -> gl::types::GLenum;
@@ -371,6 +401,10 @@ trait TextureMipmapExt {
                              where P: Send + Copy + Clone + 'a;
 
     fn download_compressed_data(&self) -> Option<(image_format::ClientFormatAny, Vec<u8>)>;
+
+    /// Reads all the layers and faces of the mipmap in the given format, without any conversion
+    /// if the format is compressed. Returns `None` if reading textures is not supported.
+    fn download_data(&self, format: image_format::ClientFormatAny) -> Option<Vec<u8>>;
 }
 
 /// Internal trait for transform feedback sessions.
@@ -394,6 +428,14 @@ trait UniformsExt {
     /// Will replace texture and buffer bind points.
     fn bind_uniforms<'a, P>(&'a self, _: &mut CommandContext<'_>, _: &P, _: &mut Vec<buffer::Inserter<'a>>)
                             -> Result<(), DrawError> where P: ProgramExt;
+
+    /// Binds the uniforms to each program of a pipeline, then binds the pipeline. A uniform
+    /// is only set on the programs that use it.
+    ///
+    /// Will replace texture and buffer bind points.
+    fn bind_uniforms_for_pipeline<'a>(&'a self, _: &mut CommandContext<'_>,
+                                      _: &program::ProgramPipeline<'_>,
+                                      _: &mut Vec<buffer::Inserter<'a>>) -> Result<(), DrawError>;
 }
 
 
@@ -439,6 +481,9 @@ pub enum RawUniformValue {
     UnsignedInt64Vec2([gl::types::GLuint64; 2]),
     UnsignedInt64Vec3([gl::types::GLuint64; 3]),
     UnsignedInt64Vec4([gl::types::GLuint64; 4]),
+
+    /// Handle of a bindless texture, set with `glUniformHandleui64ARB`.
+    TextureHandle(gl::types::GLuint64),
 }
 
 /// Area of a surface in pixels.
@@ -773,10 +818,28 @@ pub trait Surface {
     /// documentation for example how to use it.
     ///
     /// See above for what happens exactly on the GPU when you draw.
-    fn draw<'a, 'b, V, I, U>(&mut self, _: V, _: I, program: &Program, uniforms: &U,
+    ///
+    /// The program can be either a `&Program` or a `&ProgramPipeline`.
+    fn draw<'a, 'b, 'p, V, I, U, P>(&mut self, _: V, _: I, program: P, uniforms: &U,
         draw_parameters: &DrawParameters<'_>) -> Result<(), DrawError> where
         V: vertex::MultiVerticesSource<'b>, I: Into<index::IndicesSource<'a>>,
-        U: uniforms::Uniforms;
+        U: uniforms::Uniforms, P: Into<program::ProgramRef<'p>>;
+
+    /// Draws with a program that contains a mesh shader.
+    ///
+    /// Launches `count` work groups of the task shader, or of the mesh shader if the program
+    /// doesn't have a task shader. There is no vertex source: the shaders read their data from
+    /// the buffers and textures passed as uniforms, and the mesh shader outputs the primitives.
+    ///
+    /// Requires `GL_NV_mesh_shader` or `GL_EXT_mesh_shader`. See
+    /// `program::is_mesh_shader_supported`.
+    ///
+    /// The default implementation returns `DrawError::MeshShadersNotSupported`.
+    fn draw_mesh_tasks<U>(&mut self, _: u32, _: &Program, _: &U, _: &DrawParameters<'_>)
+                          -> Result<(), DrawError> where U: uniforms::Uniforms
+    {
+        Err(DrawError::MeshShadersNotSupported)
+    }
 
     /// Blits from the default framebuffer.
     fn blit_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
@@ -792,6 +855,38 @@ pub trait Surface {
                                          source_rect: &Rect, target_rect: &BlitTarget,
                                          filter: uniforms::MagnifySamplerFilter);
 
+    /// Blits some buffers from the default framebuffer. See `blit`.
+    ///
+    /// The default implementation returns `BlitError::NotSupported`.
+    fn blit_buffers_from_frame(&self, _: &Rect, _: &BlitTarget, _: BlitMask,
+                               _: uniforms::MagnifySamplerFilter) -> Result<(), BlitError>
+    {
+        Err(BlitError::NotSupported)
+    }
+
+    /// Blits some buffers from a simple framebuffer. See `blit`.
+    ///
+    /// The default implementation returns `BlitError::NotSupported`.
+    fn blit_buffers_from_simple_framebuffer(&self, _: &framebuffer::SimpleFrameBuffer<'_>,
+                                            _: &Rect, _: &BlitTarget, _: BlitMask,
+                                            _: uniforms::MagnifySamplerFilter)
+                                            -> Result<(), BlitError>
+    {
+        Err(BlitError::NotSupported)
+    }
+
+    /// Blits some buffers from a multi-output framebuffer. See `blit`.
+    ///
+    /// The default implementation returns `BlitError::NotSupported`.
+    fn blit_buffers_from_multioutput_framebuffer(&self,
+                                                 _: &framebuffer::MultiOutputFrameBuffer<'_>,
+                                                 _: &Rect, _: &BlitTarget, _: BlitMask,
+                                                 _: uniforms::MagnifySamplerFilter)
+                                                 -> Result<(), BlitError>
+    {
+        Err(BlitError::NotSupported)
+    }
+
     /// Copies a rectangle of pixels from this surface to another surface.
     ///
     /// The `source_rect` defines the area of the source (`self`) that will be copied, and the
@@ -807,6 +902,30 @@ pub trait Surface {
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
                      filter: uniforms::MagnifySamplerFilter) where S: Surface;
 
+    /// Copies a rectangle of the color, depth and/or stencil buffers of this surface to
+    /// another surface.
+    ///
+    /// Works like `blit_color`, except that `mask` chooses the buffers to copy and that the
+    /// operation is checked beforehand. A negative width or height in `target_rect` flips the
+    /// image horizontally or vertically.
+    ///
+    /// Some combinations are not allowed:
+    ///
+    /// - Depth and stencil buffers, and color buffers with an integral or unsigned format, can
+    ///   only be copied with the `Nearest` filter.
+    /// - A floating-point color buffer can't be copied to an integral or unsigned one, and
+    ///   vice versa.
+    /// - Copying from a multisampled surface resolves the samples. In that case the two
+    ///   rectangles must have the same dimensions, and the target must either not be
+    ///   multisampled or have the same number of samples.
+    ///
+    /// The default implementation returns `BlitError::NotSupported`.
+    fn blit<S>(&self, _: &Rect, _: &S, _: &BlitTarget, _: BlitMask,
+               _: uniforms::MagnifySamplerFilter) -> Result<(), BlitError> where S: Surface
+    {
+        Err(BlitError::NotSupported)
+    }
+
     /// Copies the entire surface to a target surface. See `blit_color`.
     #[inline]
     fn blit_whole_color_to<S>(&self, target: &S, target_rect: &BlitTarget,
@@ -826,6 +945,60 @@ pub trait Surface {
         let target_rect = BlitTarget { left: 0, bottom: 0, width: target_dim.0 as i32, height: target_dim.1 as i32 };
         self.blit_color(&src_rect, target, &target_rect, filter)
     }
+
+    /// Copies a rectangle of pixels of the color buffer to an RGBA8 image in client memory.
+    ///
+    /// The pixels are converted to the requested color space, and the rows of the image go
+    /// from top to bottom. See `copy_region_to_cpu_with_options` for more control.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the rect is out of the bounds of the surface.
+    #[inline]
+    fn copy_region_to_cpu(&self, rect: &Rect, color_space: ColorSpace)
+                          -> Result<CpuImage, ReadError>
+    {
+        self.copy_region_to_cpu_with_options(rect, &CopyToCpuOptions {
+            color_space,
+            .. Default::default()
+        })
+    }
+
+    /// Copies a rectangle of pixels of the color buffer to an RGBA8 image in client memory.
+    ///
+    /// Whether the surface stores sRGB values is automatically detected, and the pixels are
+    /// converted according to the options. If the surface has multiple color attachments,
+    /// the first one is read.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the rect is out of the bounds of the surface.
+    ///
+    /// The default implementation returns `ReadError::NotSupported`.
+    fn copy_region_to_cpu_with_options(&self, _: &Rect, _: &CopyToCpuOptions)
+                                       -> Result<CpuImage, ReadError>
+    {
+        Err(ReadError::NotSupported)
+    }
+
+    /// Starts copying a rectangle of pixels of the color buffer to video memory, and returns
+    /// without waiting for the GPU.
+    ///
+    /// Contrary to `copy_region_to_cpu`, this doesn't need a synchronization. The returned
+    /// object can be polled, or used as a `Future`, to obtain the pixels once the copy has
+    /// finished. The values are not converted, and the rows go from bottom to top. If the
+    /// surface has multiple color attachments, the first one is read.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the rect is out of the bounds of the surface.
+    ///
+    /// The default implementation returns `ReadError::NotSupported`.
+    fn read_async<T>(&self, _: &Rect) -> Result<PendingRead<T>, ReadError>
+                     where T: texture::Texture2dDataSink<(u8, u8, u8, u8)>
+    {
+        Err(ReadError::NotSupported)
+    }
 }
 
 /// Private trait for framebuffer-like objects that provide attachments.
@@ -884,6 +1057,64 @@ pub enum DrawError {
         err: uniforms::LayoutMismatchError,
     },
 
+    /// A buffer has been explicitly bound to a shader storage block, but the program doesn't
+    /// have any shader storage block with this name.
+    ShaderStorageBlockNotFound {
+        /// Name of the block you are trying to bind.
+        name: String,
+    },
+
+    /// The offset of the slice of buffer bound to a block is not a multiple of the alignment
+    /// required by the implementation.
+    BufferOffsetMisaligned {
+        /// Name of the block you are trying to bind.
+        name: String,
+        /// The required alignment in bytes.
+        alignment: usize,
+    },
+
+    /// A buffer bound to a block or an atomic counter is too small.
+    BufferTooSmall {
+        /// Name of the block or the counter you are trying to bind.
+        name: String,
+        /// The minimum size in bytes.
+        required: usize,
+        /// The size in bytes of the buffer.
+        obtained: usize,
+    },
+
+    /// The internal format of a texture bound to an image unit is not compatible with the
+    /// format declared by the layout qualifier of the image in the shader.
+    ImageFormatMismatch {
+        /// Name of the image uniform you are trying to bind.
+        name: String,
+        /// The format declared in the shader.
+        expected: uniforms::ImageUnitFormat,
+        /// The internal format of the texture.
+        obtained: texture::InternalFormat,
+    },
+
+    /// The program uses more image uniforms than the number of image units of the backend.
+    NotEnoughImageUnits {
+        /// Name of the image uniform that couldn't be bound.
+        name: String,
+    },
+
+    /// The color space validation is enabled, and the program declares that it outputs sRGB
+    /// values while the framebuffer has sRGB attachments. See
+    /// `Context::set_color_space_validation`.
+    ColorSpaceMismatch,
+
+    /// A texture that is attached to the framebuffer is sampled by the draw command, which
+    /// would produce undefined results.
+    ///
+    /// Set `DrawParameters::texture_barrier` if the draw command doesn't read the texels that
+    /// it writes.
+    TextureFeedbackLoop {
+        /// Name of the sampler uniform.
+        name: String,
+    },
+
     /// Tried to bind a subroutine uniform like a regular uniform value.
     SubroutineUniformToValue {
         /// Name of the uniform you are trying to bind.
@@ -924,6 +1155,18 @@ pub enum DrawError {
     /// When you use instancing, all vertices sources must have the same size.
     InstancesCountMismatch,
 
+    /// Instancing is emulated (see `Context::set_instancing_emulation`), but the content of a
+    /// per-instance buffer couldn't be read.
+    InstancingEmulationFailed,
+
+    /// Instancing is emulated (see `Context::set_instancing_emulation`), but the type of a
+    /// per-instance attribute can't be passed without a buffer. Only attributes whose type is a
+    /// float, a float vector or a float matrix in the program are supported.
+    UnsupportedEmulatedInstanceAttribute {
+        /// Name of the attribute.
+        name: String,
+    },
+
     /// If you don't use indices, then all vertices sources must have the same size.
     VerticesSourcesLengthMismatch,
 
@@ -951,8 +1194,59 @@ pub enum DrawError {
     /// Restarting indices (multiple objects per draw call) is not supported by the backend.
     FixedIndexRestartingNotSupported,
 
+    /// Restarting indices with a custom index is not supported by the backend.
+    CustomIndexRestartingNotSupported,
+
+    /// Tried to read the number of indirect draw commands from a buffer, but this is not
+    /// supported by the backend (requires `GL_ARB_indirect_parameters`).
+    IndirectCountNotSupported,
+
     /// Tried to enable a clip plane that does not exist.
     ClipPlaneIndexOutOfBounds,
+
+    /// A texture barrier was requested, but this is not supported by the backend.
+    TextureBarrierNotSupported,
+
+    /// Default tessellation levels were requested, but this is not supported by the backend.
+    PatchDefaultLevelsNotSupported,
+
+    /// Sample shading was requested, but this is not supported by the backend.
+    SampleShadingNotSupported,
+
+    /// A logical operation was requested, but this is not supported by the backend.
+    LogicOpNotSupported,
+
+    /// The depth bounds test was requested, but this is not supported by the backend.
+    DepthBoundsNotSupported,
+
+    /// The requested clip origin or clip depth mode is not supported by the backend.
+    ClipControlNotSupported,
+
+    /// The requested polygon offset is not supported by the backend.
+    PolygonOffsetNotSupported,
+
+    /// Multiple viewports were requested, but this is not supported by the backend, or more
+    /// viewports than `Capabilities::max_viewports` were requested.
+    ViewportArrayNotSupported,
+
+    /// The number of views of the program, as returned by `Program::get_num_views`, is not the
+    /// number of views of the framebuffer. Framebuffers that are not multiview framebuffers
+    /// have one view.
+    NumViewsMismatch,
+
+    /// Mesh shaders are not supported by the backend.
+    MeshShadersNotSupported,
+
+    /// `draw_mesh_tasks` was called with a program that doesn't contain a mesh shader.
+    MeshShaderRequired,
+
+    /// `draw` was called with a program that contains a mesh shader. Use `draw_mesh_tasks`
+    /// instead.
+    UnexpectedMeshShader,
+
+    /// The number of work groups passed to `draw_mesh_tasks` is larger than
+    /// `max_draw_mesh_tasks_count`.
+    TooManyMeshTasks,
 }
 
 impl Error for DrawError {
@@ -988,6 +1282,20 @@ impl fmt::Display for DrawError {
                 "Tried to bind a single uniform value to a uniform block",
             UniformBlockLayoutMismatch { .. } =>
                 "The layout of the content of the uniform buffer does not match the layout of the block",
+            ShaderStorageBlockNotFound { .. } =>
+                "The program doesn't have any shader storage block with this name",
+            BufferOffsetMisaligned { .. } =>
+                "The offset of the buffer slice is not a multiple of the required alignment",
+            BufferTooSmall { .. } =>
+                "The buffer is too small for the block or the counter it is bound to",
+            ImageFormatMismatch { .. } =>
+                "The format of the texture is not compatible with the format declared in the shader",
+            NotEnoughImageUnits { .. } =>
+                "The program uses more image uniforms than the number of available image units",
+            ColorSpaceMismatch =>
+                "The program outputs sRGB values but the framebuffer has sRGB attachments",
+            TextureFeedbackLoop { .. } =>
+                "A texture that is attached to the framebuffer is sampled by the draw command",
             SubroutineUniformToValue { .. } =>
                 "Tried to bind a subroutine uniform like a regular uniform value",
             SubroutineUniformMissing { .. } =>
@@ -1004,6 +1312,10 @@ impl fmt::Display for DrawError {
                 Trying to use a sampler, but they are not supported by the backend",
             InstancesCountMismatch =>
                 "When you use instancing, all vertices sources must have the same size",
+            InstancingEmulationFailed =>
+                "The content of a per-instance buffer couldn't be read to emulate instancing",
+            UnsupportedEmulatedInstanceAttribute { .. } =>
+                "The type of this per-instance attribute can't be used when instancing is emulated",
             VerticesSourcesLengthMismatch =>
                 "If you don't use indices, then all vertices sources must have the same size",
             TransformFeedbackNotSupported =>
@@ -1022,8 +1334,38 @@ impl fmt::Display for DrawError {
                 "One the blending parameters is not supported by the backend",
             FixedIndexRestartingNotSupported =>
                 "Restarting indices (multiple objects per draw call) is not supported by the backend",
+            CustomIndexRestartingNotSupported =>
+                "Restarting indices with a custom index is not supported by the backend",
+            IndirectCountNotSupported =>
+                "Reading the number of indirect draw commands from a buffer is not supported by the backend",
             ClipPlaneIndexOutOfBounds =>
-                "Tried to enable a clip plane that does not exist."
+                "Tried to enable a clip plane that does not exist.",
+            TextureBarrierNotSupported =>
+                "Texture barriers are not supported by the backend",
+            PatchDefaultLevelsNotSupported =>
+                "Default tessellation levels are not supported by the backend",
+            SampleShadingNotSupported =>
+                "Sample shading is not supported by the backend",
+            LogicOpNotSupported =>
+                "Logical operations are not supported by the backend",
+            DepthBoundsNotSupported =>
+                "The depth bounds test is not supported by the backend",
+            ClipControlNotSupported =>
+                "The requested clip origin or clip depth mode is not supported by the backend",
+            PolygonOffsetNotSupported =>
+                "The requested polygon offset is not supported by the backend",
+            ViewportArrayNotSupported =>
+                "The requested number of viewports is not supported by the backend",
+            NumViewsMismatch =>
+                "The number of views of the program doesn't match the framebuffer",
+            MeshShadersNotSupported =>
+                "Mesh shaders are not supported by the backend",
+            MeshShaderRequired =>
+                "Drawing mesh tasks requires a program that contains a mesh shader",
+            UnexpectedMeshShader =>
+                "Programs that contain a mesh shader must be drawn with `draw_mesh_tasks`",
+            TooManyMeshTasks =>
+                "The number of mesh tasks is larger than the maximum supported by the backend",
         };
         match self {
             UniformTypeMismatch { name, expected } =>
@@ -1056,6 +1398,60 @@ impl fmt::Display for DrawError {
                     name,
                     err,
                 ),
+            ShaderStorageBlockNotFound { name } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    desc,
+                    name,
+                ),
+            BufferOffsetMisaligned { name, alignment } =>
+                write!(
+                    fmt,
+                    "{}: {}, alignment: {}",
+                    desc,
+                    name,
+                    alignment,
+                ),
+            BufferTooSmall { name, required, obtained } =>
+                write!(
+                    fmt,
+                    "{}: {}, required: {} bytes, obtained: {} bytes",
+                    desc,
+                    name,
+                    required,
+                    obtained,
+                ),
+            ImageFormatMismatch { name, expected, obtained } =>
+                write!(
+                    fmt,
+                    "{}: {}, expected: {:?}, got: {:?}",
+                    desc,
+                    name,
+                    expected,
+                    obtained,
+                ),
+            NotEnoughImageUnits { name } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    desc,
+                    name,
+                ),
+            TextureFeedbackLoop { name } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    desc,
+                    name,
+                ),
+            UnsupportedEmulatedInstanceAttribute { name } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    desc,
+                    name,
+                ),
             _ =>
                 fmt.write_str(desc),
         }
@@ -1098,6 +1494,59 @@ impl fmt::Display for SwapBuffersError {
     }
 }
 
+/// How the swapping of the buffers is synchronized with the vertical blanking of the monitor.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SwapInterval {
+    /// The buffers are swapped immediately. This can cause tearing.
+    DontWait,
+    /// Waits for the given number of vertical blanks before swapping the buffers. `Wait(1)`
+    /// is the usual vsync.
+    Wait(u32),
+    /// Same as `Wait`, except that the buffers are swapped immediately if the frame is late,
+    /// instead of waiting for the next vertical blank. This is adaptive vsync, which requires
+    /// the `EXT_swap_control_tear` extension.
+    AdaptiveWait(u32),
+}
+
+/// Error that can happen when changing the swap interval.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SwapIntervalError {
+    /// The backend doesn't support changing the swap interval.
+    NotSupported,
+    /// The backend doesn't support adaptive vsync.
+    AdaptiveNotSupported,
+}
+
+impl Error for SwapIntervalError {}
+
+impl fmt::Display for SwapIntervalError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        use self::SwapIntervalError::*;
+        let desc = match *self {
+            NotSupported =>
+                "The backend doesn't support changing the swap interval",
+            AdaptiveNotSupported =>
+                "The backend doesn't support adaptive vsync",
+        };
+        fmt.write_str(desc)
+    }
+}
+
+/// The back buffers of the default framebuffer that a `Frame` draws to.
+///
+/// A stereo context, for example created with `ContextBuilder::with_stereoscopy` with the
+/// glutin backend, has one back buffer for each eye. See `Capabilities::stereo`. Otherwise
+/// there is only one back buffer, which is the left one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BackBuffer {
+    /// Both the left and the right back buffers. This is the default.
+    Both,
+    /// The back buffer for the left eye.
+    Left,
+    /// The back buffer for the right eye. Only available with a stereo context.
+    Right,
+}
+
 /// Implementation of `Surface`, targeting the default framebuffer.
 ///
 /// The back- and front-buffers are swapped when you call `finish`.
@@ -1106,6 +1555,8 @@ impl fmt::Display for SwapBuffersError {
 pub struct Frame {
     context: Rc<Context>,
     dimensions: (u32, u32),
+    back_buffer: BackBuffer,
+    invalidate_depth_stencil_on_finish: bool,
     destroyed: bool,        // TODO: use a linear type instead.
 }
 
@@ -1116,10 +1567,80 @@ impl Frame {
         Frame {
             context,
             dimensions,
+            back_buffer: BackBuffer::Both,
+            invalidate_depth_stencil_on_finish: false,
             destroyed: false,
         }
     }
 
+    /// Chooses the back buffers that the next draw, clear and blit commands write to, and
+    /// that reading from the frame reads from. Reading from both buffers reads the left one.
+    ///
+    /// This allows drawing the image of each eye on a stereo display. Returns an error if you
+    /// request the right buffer but the context is not a stereo context.
+    pub fn draw_to_buffer(&mut self, buffer: BackBuffer) -> Result<(), StereoNotSupportedError> {
+        if buffer == BackBuffer::Right && !self.context.capabilities().stereo {
+            return Err(StereoNotSupportedError);
+        }
+
+        self.back_buffer = buffer;
+        Ok(())
+    }
+
+    /// Returns the back buffers that the frame draws to.
+    #[inline]
+    pub fn get_back_buffer(&self) -> BackBuffer {
+        self.back_buffer
+    }
+
+    /// Makes the chosen back buffers the target of the draw commands.
+    fn bind_back_buffer(&self) {
+        // without stereo, `GL_BACK` is the only back buffer
+        let draw_buffer = match self.back_buffer {
+            BackBuffer::Left if self.context.capabilities().stereo => gl::BACK_LEFT,
+            BackBuffer::Right => gl::BACK_RIGHT,
+            _ => gl::BACK,
+        };
+
+        let mut ctxt = self.context.make_current();
+        fbo::FramebuffersContainer::set_default_framebuffer_draw_buffer(&mut ctxt, draw_buffer);
+    }
+
+    /// Returns the buffer that is read when reading from the frame.
+    #[inline]
+    fn read_buffer(&self) -> gl::types::GLenum {
+        match self.back_buffer {
+            BackBuffer::Right => gl::BACK_RIGHT,
+            _ => gl::BACK_LEFT,
+        }
+    }
+
+    /// Indicates that the content of some buffers of the frame is no longer needed. Their
+    /// content is undefined afterwards.
+    ///
+    /// On tiled GPUs, this avoids writing this content back to memory. This is only a hint,
+    /// and nothing happens if the backend doesn't support it.
+    #[inline]
+    pub fn invalidate(&mut self, buffers: InvalidateBuffers) {
+        ops::invalidate(&self.context, None, None, buffers);
+    }
+
+    /// Same as `invalidate`, but only for a part of the buffers.
+    #[inline]
+    pub fn invalidate_rect(&mut self, rect: &Rect, buffers: InvalidateBuffers) {
+        ops::invalidate(&self.context, None, Some(rect), buffers);
+    }
+
+    /// If true, the depth and stencil buffers are invalidated before the buffers are swapped
+    /// by `finish`. The default value is `false`.
+    ///
+    /// You should enable this if you don't read the depth and stencil buffers of the previous
+    /// frame, which is almost always the case.
+    #[inline]
+    pub fn set_invalidate_depth_stencil_on_finish(&mut self, invalidate: bool) {
+        self.invalidate_depth_stencil_on_finish = invalidate;
+    }
+
     /// Stop drawing, swap the buffers, and consume the Frame.
     ///
     /// See the documentation of `SwapBuffersError` about what is being returned.
@@ -1134,12 +1655,36 @@ impl Frame {
     /// cause `Err(SwapBuffersError::AlreadySwapped)` to be returned.
     #[inline]
     pub fn set_finish(&mut self) -> Result<(), SwapBuffersError> {
+        self.set_finish_impl(None)
+    }
+
+    /// Same as `finish`, but indicates that only the given rectangles of the frame have changed
+    /// since the previous frame.
+    ///
+    /// Applications that only redraw small parts of their window can use this to avoid
+    /// composing the whole window again. Use `Context::get_buffer_age` to know which previous
+    /// frame the back buffer contains. If the backend doesn't support damage rectangles, this
+    /// does the same as `finish`.
+    #[inline]
+    pub fn finish_with_damage(mut self, damage: &[Rect]) -> Result<(), SwapBuffersError> {
+        self.set_finish_impl(Some(damage))
+    }
+
+    fn set_finish_impl(&mut self, damage: Option<&[Rect]>) -> Result<(), SwapBuffersError> {
         if self.destroyed {
             return Err(SwapBuffersError::AlreadySwapped);
         }
 
         self.destroyed = true;
-        self.context.swap_buffers()
+
+        if self.invalidate_depth_stencil_on_finish {
+            self.invalidate(InvalidateBuffers { color: false, depth: true, stencil: true });
+        }
+
+        match damage {
+            Some(damage) => self.context.swap_buffers_with_damage(damage),
+            None => self.context.swap_buffers(),
+        }
     }
 }
 
@@ -1148,6 +1693,7 @@ impl Surface for Frame {
     fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
              depth: Option<f32>, stencil: Option<i32>)
     {
+        self.bind_back_buffer();
         ops::clear(&self.context, None, rect, color, color_srgb, depth, stencil);
     }
 
@@ -1163,11 +1709,11 @@ impl Surface for Frame {
         self.context.capabilities().stencil_bits
     }
 
-    fn draw<'a, 'b, V, I, U>(&mut self, vertex_buffer: V,
-                         index_buffer: I, program: &Program, uniforms: &U,
+    fn draw<'a, 'b, 'p, V, I, U, P>(&mut self, vertex_buffer: V,
+                         index_buffer: I, program: P, uniforms: &U,
                          draw_parameters: &DrawParameters<'_>) -> Result<(), DrawError>
                          where I: Into<index::IndicesSource<'a>>, U: uniforms::Uniforms,
-                         V: vertex::MultiVerticesSource<'b>
+                         V: vertex::MultiVerticesSource<'b>, P: Into<program::ProgramRef<'p>>
     {
         if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
                 draw_parameters.depth.write)
@@ -1188,10 +1734,26 @@ impl Surface for Frame {
             }
         }
 
-        ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program,
+        self.bind_back_buffer();
+        ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program.into(),
                   uniforms, draw_parameters, (self.dimensions.0 as u32, self.dimensions.1 as u32))
     }
 
+    fn draw_mesh_tasks<U>(&mut self, count: u32, program: &Program, uniforms: &U,
+                          draw_parameters: &DrawParameters<'_>) -> Result<(), DrawError>
+                          where U: uniforms::Uniforms
+    {
+        if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
+                draw_parameters.depth.write)
+        {
+            return Err(DrawError::NoDepthBuffer);
+        }
+
+        self.bind_back_buffer();
+        ops::draw_mesh_tasks(&self.context, None, count, program, uniforms,
+                             draw_parameters, self.get_dimensions())
+    }
+
     #[inline]
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
                      filter: uniforms::MagnifySamplerFilter) where S: Surface
@@ -1203,6 +1765,7 @@ impl Surface for Frame {
     fn blit_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
                        filter: uniforms::MagnifySamplerFilter)
     {
+        self.bind_back_buffer();
         ops::blit(&self.context, None, self.get_attachments(),
                   gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
     }
@@ -1212,6 +1775,7 @@ impl Surface for Frame {
                                     source_rect: &Rect, target_rect: &BlitTarget,
                                     filter: uniforms::MagnifySamplerFilter)
     {
+        self.bind_back_buffer();
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
     }
@@ -1221,9 +1785,67 @@ impl Surface for Frame {
                                          source_rect: &Rect, target_rect: &BlitTarget,
                                          filter: uniforms::MagnifySamplerFilter)
     {
+        self.bind_back_buffer();
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
     }
+
+    #[inline]
+    fn blit<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget, mask: BlitMask,
+               filter: uniforms::MagnifySamplerFilter) -> Result<(), BlitError> where S: Surface
+    {
+        target.blit_buffers_from_frame(source_rect, target_rect, mask, filter)
+    }
+
+    #[inline]
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               mask: BlitMask, filter: uniforms::MagnifySamplerFilter)
+                               -> Result<(), BlitError>
+    {
+        self.bind_back_buffer();
+        ops::blit_buffers(&self.context, None, self.get_attachments(), source_rect, target_rect,
+                          mask, filter)
+    }
+
+    #[inline]
+    fn blit_buffers_from_simple_framebuffer(&self, source: &framebuffer::SimpleFrameBuffer<'_>,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            mask: BlitMask, filter: uniforms::MagnifySamplerFilter)
+                                            -> Result<(), BlitError>
+    {
+        self.bind_back_buffer();
+        ops::blit_buffers(&self.context, source.get_attachments(), self.get_attachments(),
+                          source_rect, target_rect, mask, filter)
+    }
+
+    #[inline]
+    fn blit_buffers_from_multioutput_framebuffer(&self,
+                                                 source: &framebuffer::MultiOutputFrameBuffer<'_>,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 mask: BlitMask,
+                                                 filter: uniforms::MagnifySamplerFilter)
+                                                 -> Result<(), BlitError>
+    {
+        self.bind_back_buffer();
+        ops::blit_buffers(&self.context, source.get_attachments(), self.get_attachments(),
+                          source_rect, target_rect, mask, filter)
+    }
+
+    fn copy_region_to_cpu_with_options(&self, rect: &Rect, options: &CopyToCpuOptions)
+                                       -> Result<CpuImage, ReadError>
+    {
+        let mut ctxt = self.context.make_current();
+        ops::copy_to_cpu(&mut ctxt, ops::Source::DefaultFramebuffer(self.read_buffer()),
+                         self.context.capabilities().srgb, self.dimensions, rect, options)
+    }
+
+    #[inline]
+    fn read_async<T>(&self, rect: &Rect) -> Result<PendingRead<T>, ReadError>
+                     where T: texture::Texture2dDataSink<(u8, u8, u8, u8)>
+    {
+        ops::read_async(&self.context, ops::Source::DefaultFramebuffer(self.read_buffer()),
+                        self.dimensions, rect)
+    }
 }
 
 impl FboAttachments for Frame {
@@ -1255,6 +1877,20 @@ impl fmt::Display for IncompatibleOpenGl {
 
 impl Error for IncompatibleOpenGl {}
 
+/// Returned by `Frame::draw_to_buffer` if the right back buffer is requested but the context is
+/// not a stereo context.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StereoNotSupportedError;
+
+impl fmt::Display for StereoNotSupportedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("The context doesn't have a right back buffer because it is not a stereo context")
+    }
+}
+
+impl Error for StereoNotSupportedError {}
+
 #[allow(dead_code)]
 #[inline]
 fn get_gl_error(ctxt: &mut context::CommandContext<'_>) -> Option<&'static str> {
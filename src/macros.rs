@@ -73,6 +73,58 @@ macro_rules! uniform {
     };
 }
 
+/// Implements the `glium::uniforms::UniformMembers` trait for the given type, so that it can be
+/// passed to the `uniform!` macro as a GLSL struct.
+///
+/// The parameters must be the name of the struct and the names of its fields. Each field is
+/// passed as `name.field`. Fields that are arrays must be followed by `[..]`, in which case their
+/// elements are passed as `name.field[0]`, `name.field[1]`, etc. The type of each field must
+/// implement `UniformMembers`, which means that structs can be nested.
+///
+/// ## Example
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// #[derive(Copy, Clone)]
+/// struct Light {
+///     position: [f32; 3],
+///     color: [f32; 3],
+/// }
+///
+/// implement_uniform_struct!(Light, position, color);
+///
+/// #[derive(Copy, Clone)]
+/// struct Scene {
+///     ambient: [f32; 3],
+///     lights: [Light; 4],
+/// }
+///
+/// implement_uniform_struct!(Scene, ambient, lights[..]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! implement_uniform_struct {
+    ($struct_name:ident, $($field_name:ident $([$($range:tt)*])?),+) => (
+        impl $crate::uniforms::UniformMembers for $struct_name {
+            fn visit_members<'a, F>(&'a self, name: &str, output: &mut F)
+                where F: FnMut(&str, $crate::uniforms::UniformValue<'a>)
+            {
+                $(
+                    $crate::uniforms::UniformMembers::visit_members(
+                        &self.$field_name $([$($range)*])?,
+                        &format!("{}.{}", name, stringify!($field_name)), output);
+                )+
+            }
+        }
+    );
+
+    ($struct_name:ident, $($field_name:ident $([$($range:tt)*])?),+,) => (
+        implement_uniform_struct!($struct_name, $($field_name $([$($range)*])?),+);
+    );
+}
+
 /// Implements the `glium::vertex::Vertex` trait for the given type.
 ///
 /// The parameters must be the name of the struct and the names of its fields.
@@ -103,9 +155,31 @@ macro_rules! uniform {
 ///
 /// So, if you have a `vertex_position` atribute/input in your shader, a field named `vertex_position` must be present in the struct. Ohterwise the drawing functions will panic.
 ///
+/// ## Explicit locations
+///
+/// Alternatively, you can bind a field to an explicit attribute location with `location(...)`.
+/// The name of the field is then ignored, which is useful for shaders that use
+/// `layout(location = ...)` with different attribute names. Fields can also be marked with
+/// `normalize(...)`, in which case it must come before the location. Otherwise the field is
+/// normalized if its type requires it, like `glium::vertex::Color8`.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// #[derive(Copy, Clone)]
+/// struct Vertex {
+///     position: [f32; 3],
+///     color: [u8; 4],
+/// }
+///
+/// implement_vertex!(Vertex, position location(0), color normalize(true) location(1));
+/// # }
+/// ```
+///
 #[macro_export]
 macro_rules! implement_vertex {
-    ($struct_name:ident, $($field_name:ident),+) => (
+    ($struct_name:ident, $($field_name:ident $(normalize($should_normalize:expr))? $(location($location:expr))?),+) => (
         impl $crate::vertex::Vertex for $struct_name {
             #[inline]
             fn build_bindings() -> $crate::vertex::VertexFormat {
@@ -118,6 +192,7 @@ macro_rules! implement_vertex {
                         (
                             Cow::Borrowed(stringify!($field_name)),
                             $crate::__glium_offset_of!($struct_name, $field_name),
+                            $crate::__glium_attribute_option!(None $(, Some($location))?),
                             {
                                 // Obtain the type of the $field_name field of $struct_name and
                                 // call get_type on it.
@@ -129,53 +204,35 @@ macro_rules! implement_vertex {
                                 let field_option = None::<&$struct_name>.map(|v| &v.$field_name);
                                 attr_type_of_val(field_option)
                             },
-                            false
-                        )
-                    ),+
-                ])
-            }
-        }
-    );
-
-    ($struct_name:ident, $($field_name:ident normalize($should_normalize:expr)),+) => {
-        impl $crate::vertex::Vertex for $struct_name {
-            #[inline]
-            fn build_bindings() -> $crate::vertex::VertexFormat {
-                use std::borrow::Cow;
-
-                // TODO: use a &'static [] if possible
-
-                Cow::Owned(vec![
-                    $(
-                        (
-                            Cow::Borrowed(stringify!($field_name)),
-                            $crate::__glium_offset_of!($struct_name, $field_name),
-                            {
-                                // Obtain the type of the $field_name field of $struct_name and
-                                // call get_type on it.
-                                fn attr_type_of_val<T: $crate::vertex::Attribute>(_: Option<&T>)
-                                    -> $crate::vertex::AttributeType
+                            $crate::__glium_attribute_option!({
+                                fn normalized_of_val<T: $crate::vertex::Attribute>(_: Option<&T>)
+                                    -> bool
                                 {
-                                    <T as $crate::vertex::Attribute>::get_type()
+                                    <T as $crate::vertex::Attribute>::is_normalized()
                                 }
                                 let field_option = None::<&$struct_name>.map(|v| &v.$field_name);
-                                attr_type_of_val(field_option)
-                            },
-                            {
-                                $should_normalize
-                            }
+                                normalized_of_val(field_option)
+                            } $(, $should_normalize)?)
                         )
                     ),+
                 ])
             }
         }
-    };
+    );
 
-    ($struct_name:ident, $($field_name:ident),+,) => (
-        implement_vertex!($struct_name, $($field_name),+);
+    ($struct_name:ident, $($field_name:ident $(normalize($should_normalize:expr))? $(location($location:expr))?),+,) => (
+        implement_vertex!($struct_name, $($field_name $(normalize($should_normalize))? $(location($location))?),+);
     );
 }
 
+/// Expands to the value passed after the default, or to the default if there is none.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __glium_attribute_option {
+    ($default:expr) => ($default);
+    ($default:expr, $value:expr) => ($value);
+}
+
 /// Implements the `glium::buffer::Content` trait for the given type.
 ///
 /// Contrary to the other similar macros, this one doesn't require you pass the list of parameters.
@@ -319,7 +376,6 @@ macro_rules! implement_uniform_block {
                 fn matches(layout: &$crate::program::BlockLayout, base_offset: usize)
                            -> ::std::result::Result<(), $crate::uniforms::LayoutMismatchError>
                 {
-                    use std::mem;
                     use $crate::program::BlockLayout;
                     use $crate::uniforms::LayoutMismatchError;
 
@@ -333,7 +389,7 @@ macro_rules! implement_uniform_block {
                             }
                         }
 
-                        fn matches_from_ty<T: $crate::uniforms::UniformBlock + ?Sized>(_: &T,
+                        fn matches_from_ty<T: $crate::uniforms::UniformBlock + ?Sized>(_: Option<&T>,
                             layout: &$crate::program::BlockLayout, base_offset: usize)
                             -> ::std::result::Result<(), $crate::uniforms::LayoutMismatchError>
                         {
@@ -351,15 +407,10 @@ macro_rules! implement_uniform_block {
                                     name: stringify!($field_name).to_owned(),
                                 })
                             };
-                            let dummy: *const $struct_name = unsafe { mem::zeroed() };
-                            let input_offset = {
-                                let possibly_fat_pointer_to_field=unsafe{&(*dummy).$field_name};
-                                let pointer_to_possibly_fat_pointer_to_field:&u64=unsafe{mem::transmute( &possibly_fat_pointer_to_field )};
-                                let pointer_to_field=*pointer_to_possibly_fat_pointer_to_field;
-                                pointer_to_field as usize
-                            };
+                            let input_offset = $crate::__glium_offset_of!($struct_name, $field_name);
+                            let field_option = None::<&$struct_name>.map(|v| &v.$field_name);
 
-                            match matches_from_ty(unsafe{&(*dummy).$field_name}, reflected_ty, input_offset) {
+                            match matches_from_ty(field_option, reflected_ty, input_offset) {
                                 Ok(_) => (),
                                 Err(e) => return Err(LayoutMismatchError::MemberMismatch {
                                     member: stringify!($field_name).to_owned(),
@@ -419,6 +470,151 @@ macro_rules! implement_uniform_block {
     );
 }
 
+/// Defines a struct whose layout follows the `std140` rules, and implements the
+/// `glium::uniforms::UniformBlock` and `glium::uniforms::Std140` traits for it.
+///
+/// The struct gets `#[repr(C)]`. The offset and the size of each field are checked at compile
+/// time against the `std140` rules, and compilation fails if a field is not where a
+/// `layout(std140)` block would expect it. For example a `[f32; 3]` followed by another
+/// `[f32; 3]` is an error, because each `vec3` is aligned to 16 bytes. Putting a `f32` after
+/// the first `[f32; 3]` fixes it. At bind time, the layout is compared to the reflection of the
+/// program like with `implement_uniform_block!`.
+///
+/// All the types of the fields must implement `Std140`.
+///
+/// ## Example
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// implement_std140_block! {
+///     #[derive(Copy, Clone)]
+///     struct Light {
+///         position: [f32; 3],
+///         intensity: f32,
+///         color: [f32; 4],
+///         matrix: [[f32; 4]; 4],
+///     }
+/// }
+/// # }
+/// ```
+///
+#[macro_export]
+macro_rules! implement_std140_block {
+    ($($tt:tt)+) => (
+        $crate::__glium_implement_layout_block!(Std140, __std140_aggregate_alignment, "std140",
+                                                $($tt)+);
+    );
+}
+
+/// Defines a struct whose layout follows the `std430` rules, and implements the
+/// `glium::uniforms::UniformBlock` and `glium::uniforms::Std430` traits for it.
+///
+/// This is the equivalent of `implement_std140_block!` for `layout(std430)` shader storage
+/// blocks, which are commonly used with compute shaders. The offset and the size of each field
+/// are checked at compile time against the `std430` rules. When the buffer is bound to a shader
+/// storage block, for example with `bind_as_ssbo`, the offsets of the fields are compared to the
+/// offsets of the members of the block that the program reports, and the draw or the dispatch
+/// fails with `UniformBlockLayoutMismatch` if they don't match.
+///
+/// All the types of the fields must implement `Std430`.
+///
+/// ## Example
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// implement_std430_block! {
+///     #[derive(Copy, Clone)]
+///     struct Particle {
+///         position: [f32; 3],
+///         mass: f32,
+///         velocity: [f32; 2],
+///         weights: [f32; 6],
+///     }
+/// }
+/// # }
+/// ```
+///
+#[macro_export]
+macro_rules! implement_std430_block {
+    ($($tt:tt)+) => (
+        $crate::__glium_implement_layout_block!(Std430, __std430_aggregate_alignment, "std430",
+                                                $($tt)+);
+    );
+}
+
+/// Implementation of `implement_std140_block!` and `implement_std430_block!`.
+///
+/// `$layout` is the layout trait, and `$aggregate_alignment` the function of `glium::uniforms`
+/// that gives the alignment of a struct from the largest alignment of its fields.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __glium_implement_layout_block {
+    (
+        $layout:ident, $aggregate_alignment:ident, $rules:literal,
+        $(#[$attr:meta])*
+        $vis:vis struct $struct_name:ident {
+            $($(#[$field_attr:meta])* $field_vis:vis $field_name:ident : $field_ty:ty),+ $(,)?
+        }
+    ) => (
+        $(#[$attr])*
+        #[repr(C)]
+        $vis struct $struct_name {
+            $($(#[$field_attr])* $field_vis $field_name : $field_ty),+
+        }
+
+        impl $crate::uniforms::$layout for $struct_name {
+            const ALIGNMENT: usize = {
+                let alignment = 1;
+                $(
+                    let alignment = $crate::uniforms::__layout_max(alignment,
+                                            <$field_ty as $crate::uniforms::$layout>::ALIGNMENT);
+                )+
+                $crate::uniforms::$aggregate_alignment(alignment)
+            };
+
+            const SIZE: usize = {
+                let end = 0;
+                $(
+                    let end = $crate::uniforms::__layout_round_up(end,
+                                <$field_ty as $crate::uniforms::$layout>::ALIGNMENT) +
+                              <$field_ty as $crate::uniforms::$layout>::SIZE;
+                )+
+                $crate::uniforms::__layout_round_up(end, Self::ALIGNMENT)
+            };
+        }
+
+        // computing the offsets that `#[repr(C)]` gives to the fields and comparing them with
+        // the offsets required by the layout rules
+        const _: () = {
+            let layout_end = 0;
+            let repr_c_end = 0;
+            $(
+                let layout_offset = $crate::uniforms::__layout_round_up(layout_end,
+                                    <$field_ty as $crate::uniforms::$layout>::ALIGNMENT);
+                let repr_c_offset = $crate::uniforms::__layout_round_up(repr_c_end,
+                                    ::std::mem::align_of::<$field_ty>());
+                assert!(layout_offset == repr_c_offset,
+                        concat!("The field `", stringify!($field_name), "` of `",
+                                stringify!($struct_name), "` is not at its ", $rules, " offset"));
+                assert!(::std::mem::size_of::<$field_ty>() ==
+                        <$field_ty as $crate::uniforms::$layout>::SIZE,
+                        concat!("The type of the field `", stringify!($field_name), "` of `",
+                                stringify!($struct_name), "` doesn't have the same size in ",
+                                $rules));
+                let layout_end = layout_offset + <$field_ty as $crate::uniforms::$layout>::SIZE;
+                let repr_c_end = repr_c_offset + ::std::mem::size_of::<$field_ty>();
+            )+
+            let _ = (layout_end, repr_c_end);
+        };
+
+        $crate::implement_uniform_block!($struct_name, $($field_name),+);
+    );
+}
+
 /// Builds a program depending on the GLSL version supported by the backend.
 ///
 /// This is implemented with successive calls to `is_glsl_version_supported()`.
@@ -1,8 +1,15 @@
+use std::fmt;
+use std::error::Error;
+
 use crate::BlitTarget;
+use crate::CapabilitiesSource;
 use crate::Rect;
+use crate::ToGlEnum;
 
 use crate::context::Context;
 use crate::ContextExt;
+use crate::texture::TextureKind;
+use crate::uniforms::MagnifySamplerFilter;
 
 use crate::fbo::FramebuffersContainer;
 use crate::fbo::ValidatedAttachments;
@@ -11,6 +18,205 @@ use crate::gl;
 use crate::version::Version;
 use crate::version::Api;
 
+/// Buffers to copy with `Surface::blit`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct BlitMask {
+    /// If true, copies the color buffer.
+    pub color: bool,
+
+    /// If true, copies the depth buffer.
+    pub depth: bool,
+
+    /// If true, copies the stencil buffer.
+    pub stencil: bool,
+}
+
+/// Error that can happen while blitting.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BlitError {
+    /// Blitting isn't supported by the backend.
+    ///
+    /// Requires OpenGL 3.0, OpenGL ES 3.0 or `GL_EXT_framebuffer_blit`.
+    NotSupported,
+
+    /// The color buffer must be copied but the source or the target doesn't have one.
+    NoColorBuffer,
+
+    /// The depth buffer must be copied but the source or the target doesn't have one.
+    NoDepthBuffer,
+
+    /// The stencil buffer must be copied but the source or the target doesn't have one.
+    NoStencilBuffer,
+
+    /// Depth and stencil buffers can only be copied with the `Nearest` filter.
+    LinearFilterWithDepthStencil,
+
+    /// Color buffers with an integral or unsigned format can only be copied with the
+    /// `Nearest` filter.
+    LinearFilterWithIntegerFormat,
+
+    /// The source and target color buffers must both have a floating-point format, an
+    /// integral format or an unsigned format.
+    ColorFormatMismatch,
+
+    /// The source and the target are multisampled but don't have the same number of samples.
+    SamplesCountMismatch,
+
+    /// When the source is multisampled, the source and target rectangles must have the same
+    /// dimensions and can't be flipped. On OpenGL ES, they must also have the same position.
+    MultisampleRectMismatch,
+
+    /// OpenGL ES doesn't support blitting to a multisampled surface.
+    MultisampledTarget,
+}
+
+impl fmt::Display for BlitError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::BlitError::*;
+        let desc = match *self {
+            NotSupported =>
+                "Blitting isn't supported by the backend",
+            NoColorBuffer =>
+                "The source or the target doesn't have a color buffer",
+            NoDepthBuffer =>
+                "The source or the target doesn't have a depth buffer",
+            NoStencilBuffer =>
+                "The source or the target doesn't have a stencil buffer",
+            LinearFilterWithDepthStencil =>
+                "Depth and stencil buffers can only be copied with the `Nearest` filter",
+            LinearFilterWithIntegerFormat =>
+                "Color buffers with an integral or unsigned format can only be copied with the \
+                 `Nearest` filter",
+            ColorFormatMismatch =>
+                "The source and target color buffers don't have compatible formats",
+            SamplesCountMismatch =>
+                "The source and the target don't have the same number of samples",
+            MultisampleRectMismatch =>
+                "The source and target rectangles of a multisampled blit don't match",
+            MultisampledTarget =>
+                "Blitting to a multisampled surface isn't supported",
+        };
+        fmt.write_str(desc)
+    }
+}
+
+impl Error for BlitError {}
+
+/// Checks that the buffers of `mask` can be copied from the source to the target, then blits.
+///
+/// `None` designates the default framebuffer.
+pub fn blit_buffers(context: &Context, source: Option<&ValidatedAttachments<'_>>,
+                    target: Option<&ValidatedAttachments<'_>>, src_rect: &Rect,
+                    target_rect: &BlitTarget, mask: BlitMask, filter: MagnifySamplerFilter)
+                    -> Result<(), BlitError>
+{
+    let version = context.get_opengl_version();
+    if !(version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+         context.get_extensions().gl_ext_framebuffer_blit)
+    {
+        return Err(BlitError::NotSupported);
+    }
+
+    let capabilities = context.get_capabilities();
+    let linear = filter == MagnifySamplerFilter::Linear;
+
+    let mut gl_mask = 0;
+
+    if mask.color {
+        // the read buffer of a framebuffer object is always `GL_COLOR_ATTACHMENT0`
+        let source_kind = match source {
+            Some(source) => source.get_color_attachment_indices()
+                                  .zip(source.get_color_attachment_kinds().iter())
+                                  .find(|&(index, _)| index == 0)
+                                  .map(|(_, &kind)| kind),
+            None => Some(TextureKind::Float),
+        };
+
+        let source_kind = match source_kind {
+            Some(kind) => kind,
+            None => return Err(BlitError::NoColorBuffer),
+        };
+
+        let target_kinds = match target {
+            Some(target) => target.get_color_attachment_kinds(),
+            None => &[TextureKind::Float],
+        };
+
+        if target_kinds.is_empty() {
+            return Err(BlitError::NoColorBuffer);
+        }
+
+        if target_kinds.iter().any(|&kind| kind != source_kind) {
+            return Err(BlitError::ColorFormatMismatch);
+        }
+
+        if linear && source_kind != TextureKind::Float {
+            return Err(BlitError::LinearFilterWithIntegerFormat);
+        }
+
+        gl_mask |= gl::COLOR_BUFFER_BIT;
+    }
+
+    if mask.depth {
+        let source_bits = source.map_or(capabilities.depth_bits, |s| s.get_depth_buffer_bits());
+        let target_bits = target.map_or(capabilities.depth_bits, |t| t.get_depth_buffer_bits());
+        if source_bits.is_none() || target_bits.is_none() {
+            return Err(BlitError::NoDepthBuffer);
+        }
+
+        gl_mask |= gl::DEPTH_BUFFER_BIT;
+    }
+
+    if mask.stencil {
+        let source_bits = source.map_or(capabilities.stencil_bits, |s| s.get_stencil_buffer_bits());
+        let target_bits = target.map_or(capabilities.stencil_bits, |t| t.get_stencil_buffer_bits());
+        if source_bits.is_none() || target_bits.is_none() {
+            return Err(BlitError::NoStencilBuffer);
+        }
+
+        gl_mask |= gl::STENCIL_BUFFER_BIT;
+    }
+
+    if linear && (mask.depth || mask.stencil) {
+        return Err(BlitError::LinearFilterWithDepthStencil);
+    }
+
+    if gl_mask == 0 {
+        return Ok(());
+    }
+
+    let default_samples = capabilities.samples.map(|s| s as u32);
+    let source_samples = source.map_or(default_samples, |s| s.get_samples());
+    let target_samples = target.map_or(default_samples, |t| t.get_samples());
+
+    if target_samples.is_some() && version.0 == Api::GlEs {
+        return Err(BlitError::MultisampledTarget);
+    }
+
+    if let (Some(source_samples), Some(target_samples)) = (source_samples, target_samples) {
+        if source_samples != target_samples {
+            return Err(BlitError::SamplesCountMismatch);
+        }
+    }
+
+    if source_samples.is_some() {
+        if target_rect.width != src_rect.width as i32 ||
+           target_rect.height != src_rect.height as i32
+        {
+            return Err(BlitError::MultisampleRectMismatch);
+        }
+
+        if version.0 == Api::GlEs &&
+           (target_rect.left != src_rect.left || target_rect.bottom != src_rect.bottom)
+        {
+            return Err(BlitError::MultisampleRectMismatch);
+        }
+    }
+
+    blit(context, source, target, gl_mask, src_rect, target_rect, filter.to_glenum());
+    Ok(())
+}
+
 pub fn blit(context: &Context, source: Option<&ValidatedAttachments<'_>>,
             target: Option<&ValidatedAttachments<'_>>, mask: gl::types::GLbitfield,
             src_rect: &Rect, target_rect: &BlitTarget, filter: gl::types::GLenum)
@@ -10,6 +10,8 @@ use crate::draw_parameters::TimeElapsedQuery;
 use crate::Api;
 use crate::version::Version;
 use crate::gl;
+use crate::context::CommandContext;
+use crate::fbo::ClearBufferData;
 
 
 pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments<'_>>,
@@ -18,53 +20,7 @@ pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments<'_>>,
 {
     unsafe {
         let mut ctxt = context.make_current();
-
-        let fbo_id = fbo::FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, framebuffer);
-        fbo::bind_framebuffer(&mut ctxt, fbo_id, true, false);
-
-        if ctxt.state.enabled_rasterizer_discard {
-            ctxt.gl.Disable(gl::RASTERIZER_DISCARD);
-            ctxt.state.enabled_rasterizer_discard = false;
-        }
-
-        if ctxt.state.color_mask != (1, 1, 1, 1) {
-            ctxt.state.color_mask = (1, 1, 1, 1);
-            ctxt.gl.ColorMask(1, 1, 1, 1);
-        }
-
-        if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
-           ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control
-        {
-            if !color_srgb && !ctxt.state.enabled_framebuffer_srgb {
-                ctxt.gl.Enable(gl::FRAMEBUFFER_SRGB);
-                ctxt.state.enabled_framebuffer_srgb = true;
-
-            } else if color_srgb && ctxt.state.enabled_framebuffer_srgb {
-                ctxt.gl.Disable(gl::FRAMEBUFFER_SRGB);
-                ctxt.state.enabled_framebuffer_srgb = false;
-            }
-        }
-
-        TimeElapsedQuery::end_conditional_render(&mut ctxt);
-
-        if let Some(rect) = rect {
-            let rect = (rect.left as gl::types::GLint, rect.bottom as gl::types::GLint,
-                        rect.width as gl::types::GLsizei, rect.height as gl::types::GLsizei);
-
-            if ctxt.state.scissor != Some(rect) {
-                ctxt.gl.Scissor(rect.0, rect.1, rect.2, rect.3);
-                ctxt.state.scissor = Some(rect);
-            }
-
-            if !ctxt.state.enabled_scissor_test {
-                ctxt.gl.Enable(gl::SCISSOR_TEST);
-                ctxt.state.enabled_scissor_test = true;
-            }
-
-        } else if ctxt.state.enabled_scissor_test {
-            ctxt.gl.Disable(gl::SCISSOR_TEST);
-            ctxt.state.enabled_scissor_test = false;
-        }
+        prepare(&mut ctxt, framebuffer, rect, color_srgb);
 
         let mut flags = 0;
 
@@ -112,8 +68,101 @@ pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments<'_>>,
                 ctxt.gl.ClearStencil(stencil);
                 ctxt.state.clear_stencil = stencil;
             }
+
+            // the stencil write mask set by a previous draw would also apply to the clear
+            if ctxt.state.stencil_mask_front != 0xffffffff ||
+               ctxt.state.stencil_mask_back != 0xffffffff
+            {
+                ctxt.gl.StencilMask(0xffffffff);
+                ctxt.state.stencil_mask_front = 0xffffffff;
+                ctxt.state.stencil_mask_back = 0xffffffff;
+            }
         }
 
         ctxt.gl.Clear(flags);
     }
 }
+
+/// Clears the attachments of a framebuffer with a value that matches their kind.
+///
+/// Float, depth and stencil values are written with `glClear`, while integral and unsigned
+/// values are written to the first color attachment with `glClearBuffer`. Colors are not
+/// converted to sRGB.
+pub fn clear_buffer(context: &Context, framebuffer: &ValidatedAttachments<'_>,
+                    rect: Option<&Rect>, data: ClearBufferData)
+{
+    match data {
+        ClearBufferData::Float(c) =>
+            clear(context, Some(framebuffer), rect, Some((c[0], c[1], c[2], c[3])), true,
+                  None, None),
+        ClearBufferData::Depth(depth) =>
+            clear(context, Some(framebuffer), rect, None, false, Some(depth), None),
+        ClearBufferData::Stencil(stencil) =>
+            clear(context, Some(framebuffer), rect, None, false, None, Some(stencil)),
+        ClearBufferData::DepthStencil(depth, stencil) =>
+            clear(context, Some(framebuffer), rect, None, false, Some(depth), Some(stencil)),
+
+        ClearBufferData::Integral(data) => unsafe {
+            let mut ctxt = context.make_current();
+            prepare(&mut ctxt, Some(framebuffer), rect, true);
+            ctxt.gl.ClearBufferiv(gl::COLOR, 0, data.as_ptr());
+        },
+        ClearBufferData::Unsigned(data) => unsafe {
+            let mut ctxt = context.make_current();
+            prepare(&mut ctxt, Some(framebuffer), rect, true);
+            ctxt.gl.ClearBufferuiv(gl::COLOR, 0, data.as_ptr());
+        },
+    }
+}
+
+/// Binds the framebuffer and sets the states that affect clearing.
+unsafe fn prepare(ctxt: &mut CommandContext<'_>, framebuffer: Option<&ValidatedAttachments<'_>>,
+                  rect: Option<&Rect>, color_srgb: bool)
+{
+    let fbo_id = fbo::FramebuffersContainer::get_framebuffer_for_drawing(ctxt, framebuffer);
+    fbo::bind_framebuffer(ctxt, fbo_id, true, false);
+
+    if ctxt.state.enabled_rasterizer_discard {
+        ctxt.gl.Disable(gl::RASTERIZER_DISCARD);
+        ctxt.state.enabled_rasterizer_discard = false;
+    }
+
+    if ctxt.state.color_mask != (1, 1, 1, 1) {
+        ctxt.state.color_mask = (1, 1, 1, 1);
+        ctxt.gl.ColorMask(1, 1, 1, 1);
+    }
+
+    if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
+       ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control
+    {
+        if !color_srgb && !ctxt.state.enabled_framebuffer_srgb {
+            ctxt.gl.Enable(gl::FRAMEBUFFER_SRGB);
+            ctxt.state.enabled_framebuffer_srgb = true;
+
+        } else if color_srgb && ctxt.state.enabled_framebuffer_srgb {
+            ctxt.gl.Disable(gl::FRAMEBUFFER_SRGB);
+            ctxt.state.enabled_framebuffer_srgb = false;
+        }
+    }
+
+    TimeElapsedQuery::end_conditional_render(ctxt);
+
+    if let Some(rect) = rect {
+        let rect = (rect.left as gl::types::GLint, rect.bottom as gl::types::GLint,
+                    rect.width as gl::types::GLsizei, rect.height as gl::types::GLsizei);
+
+        if ctxt.state.scissor != Some(rect) {
+            ctxt.gl.Scissor(rect.0, rect.1, rect.2, rect.3);
+            ctxt.state.scissor = Some(rect);
+        }
+
+        if !ctxt.state.enabled_scissor_test {
+            ctxt.gl.Enable(gl::SCISSOR_TEST);
+            ctxt.state.enabled_scissor_test = true;
+        }
+
+    } else if ctxt.state.enabled_scissor_test {
+        ctxt.gl.Disable(gl::SCISSOR_TEST);
+        ctxt.state.enabled_scissor_test = false;
+    }
+}
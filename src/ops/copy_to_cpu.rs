@@ -0,0 +1,184 @@
+use crate::context::CommandContext;
+use crate::Rect;
+
+use super::read::{read, ReadError, Source};
+
+/// Color space of the pixels returned by `Surface::copy_region_to_cpu`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// The values are proportional to the intensity of the light.
+    Linear,
+
+    /// The values are encoded with the sRGB transfer function. This is what image files and
+    /// the clipboards of most platforms expect.
+    Srgb,
+}
+
+/// Describes how `Surface::copy_region_to_cpu_with_options` must convert the pixels.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CopyToCpuOptions {
+    /// Color space of the output. The color space of the surface is automatically detected.
+    ///
+    /// The default value is `Srgb`.
+    pub color_space: ColorSpace,
+
+    /// If true, the first row of the output is the top row of the region, like in image files.
+    /// Otherwise it is the bottom row, like in OpenGL.
+    ///
+    /// The default value is `true`.
+    pub top_to_bottom: bool,
+
+    /// If true, the color components are multiplied by the alpha component. The multiplication
+    /// is done on linear values, before converting to the output color space.
+    ///
+    /// The default value is `false`.
+    pub premultiply_alpha: bool,
+}
+
+impl Default for CopyToCpuOptions {
+    #[inline]
+    fn default() -> CopyToCpuOptions {
+        CopyToCpuOptions {
+            color_space: ColorSpace::Srgb,
+            top_to_bottom: true,
+            premultiply_alpha: false,
+        }
+    }
+}
+
+/// Pixels that have been copied from a surface with `Surface::copy_region_to_cpu`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CpuImage {
+    /// Tightly packed RGBA8 data, with 4 bytes per pixel and `width * 4` bytes per row.
+    pub data: Vec<u8>,
+
+    /// Number of pixels per row.
+    pub width: u32,
+
+    /// Number of rows.
+    pub height: u32,
+}
+
+/// Reads a region of a color buffer and converts its pixels.
+///
+/// `source_srgb` must be true if the values stored in the source are encoded in sRGB.
+///
+/// # Panic
+///
+/// Panics if `rect` is out of `dimensions`.
+pub fn copy_to_cpu(ctxt: &mut CommandContext<'_>, source: Source<'_>, source_srgb: bool,
+                   dimensions: (u32, u32), rect: &Rect, options: &CopyToCpuOptions)
+                   -> Result<CpuImage, ReadError>
+{
+    assert!(rect.left.checked_add(rect.width).map(|r| r <= dimensions.0).unwrap_or(false) &&
+            rect.bottom.checked_add(rect.height).map(|b| b <= dimensions.1).unwrap_or(false),
+            "The rect is out of the bounds of the surface");
+
+    let mut pixels: Vec<(u8, u8, u8, u8)> = Vec::with_capacity(0);
+    read(ctxt, source, rect, &mut pixels, false)?;
+
+    // OpenGL returns the rows from bottom to top
+    let row_len = rect.width as usize;
+    let mut data = Vec::with_capacity(pixels.len() * 4);
+    for row in 0 .. rect.height as usize {
+        let row = if options.top_to_bottom { rect.height as usize - row - 1 } else { row };
+        for &(r, g, b, a) in &pixels[row * row_len .. (row + 1) * row_len] {
+            data.push(r);
+            data.push(g);
+            data.push(b);
+            data.push(a);
+        }
+    }
+
+    convert(&mut data, source_srgb, options);
+
+    Ok(CpuImage {
+        data,
+        width: rect.width,
+        height: rect.height,
+    })
+}
+
+/// Converts RGBA8 pixels stored in the color space of the source to the color space of
+/// the output.
+fn convert(data: &mut [u8], source_srgb: bool, options: &CopyToCpuOptions) {
+    let dest_srgb = options.color_space == ColorSpace::Srgb;
+    if source_srgb == dest_srgb && !options.premultiply_alpha {
+        return;
+    }
+
+    let to_linear = (0 .. 256).map(|value| {
+        let value = value as f32 / 255.0;
+        if source_srgb { srgb_to_linear(value) } else { value }
+    }).collect::<Vec<_>>();
+
+    for pixel in data.chunks_mut(4) {
+        let alpha = if options.premultiply_alpha { pixel[3] as f32 / 255.0 } else { 1.0 };
+
+        for component in &mut pixel[.. 3] {
+            let linear = to_linear[*component as usize] * alpha;
+            let value = if dest_srgb { linear_to_srgb(linear) } else { linear };
+            *component = (value.clamp(0.0, 1.0) * 255.0 + 0.5) as u8;
+        }
+    }
+}
+
+#[inline]
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[inline]
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert, ColorSpace, CopyToCpuOptions};
+
+    #[test]
+    fn srgb_round_trip() {
+        let original = (0 .. 256).map(|v| v as u8).collect::<Vec<_>>();
+
+        let mut data = original.clone();
+        convert(&mut data, true, &CopyToCpuOptions::default());
+        assert_eq!(data, original);
+
+        let mut data = [188, 188, 188, 255];
+        convert(&mut data, true, &CopyToCpuOptions {
+            color_space: ColorSpace::Linear,
+            .. Default::default()
+        });
+        assert_eq!(data, [128, 128, 128, 255]);
+
+        convert(&mut data, false, &CopyToCpuOptions::default());
+        assert_eq!(data, [188, 188, 188, 255]);
+    }
+
+    #[test]
+    fn premultiply_in_linear_space() {
+        let mut data = [255, 128, 0, 128];
+        convert(&mut data, false, &CopyToCpuOptions {
+            color_space: ColorSpace::Linear,
+            premultiply_alpha: true,
+            .. Default::default()
+        });
+        assert_eq!(data, [128, 64, 0, 128]);
+
+        let mut data = [255, 255, 255, 128];
+        convert(&mut data, true, &CopyToCpuOptions {
+            premultiply_alpha: true,
+            .. Default::default()
+        });
+        assert_eq!(data, [188, 188, 188, 128]);
+    }
+}
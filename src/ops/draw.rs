@@ -1,12 +1,16 @@
+use std::cmp;
 use std::ptr;
 
+use smallvec::SmallVec;
+
 use crate::BufferExt;
 use crate::BufferSliceExt;
 use crate::ProgramExt;
 use crate::DrawError;
+use crate::CapabilitiesSource;
 use crate::UniformsExt;
 
-use crate::context::Context;
+use crate::context::{Context, FlushPolicy};
 use crate::ContextExt;
 use crate::TransformFeedbackSessionExt;
 
@@ -14,9 +18,11 @@ use crate::fbo::{self, ValidatedAttachments};
 
 use crate::uniforms::Uniforms;
 use crate::{Program, ToGlEnum};
-use crate::index::{self, IndicesSource};
-use crate::vertex::{MultiVerticesSource, VerticesSource, TransformFeedbackSession};
-use crate::vertex_array_object::VertexAttributesSystem;
+use crate::program::ProgramRef;
+use crate::index::{self, IndexType, IndicesSource, PrimitiveType};
+use crate::vertex::{self, MultiVerticesSource, VerticesSource, TransformFeedbackSession};
+use crate::vertex::VertexFormat;
+use crate::vertex_array_object::{self, VertexAttributesSystem};
 
 use crate::draw_parameters::DrawParameters;
 
@@ -27,14 +33,41 @@ use crate::version::Api;
 /// Draws everything.
 pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachments<'_>>,
                       vertex_buffers: V, indices: IndicesSource<'_>,
-                      program: &Program, uniforms: &U, draw_parameters: &DrawParameters<'_>,
+                      program: ProgramRef<'_>, uniforms: &U, draw_parameters: &DrawParameters<'_>,
                       dimensions: (u32, u32)) -> Result<(), DrawError>
                       where U: Uniforms, V: MultiVerticesSource<'a>
 {
+    // the program that contains the vertex shader, which determines the locations of the
+    // vertex attributes
+    let vertex_program = program.get_vertex_program();
+
+    if vertex_program.has_mesh_shader() {
+        return Err(DrawError::UnexpectedMeshShader);
+    }
+
+    // programs created from binaries have an unknown number of views
+    if let Some(num_views) = vertex_program.get_num_views() {
+        if num_views != framebuffer.and_then(|f| f.get_num_views()).unwrap_or(1) {
+            return Err(DrawError::NumViewsMismatch);
+        }
+    }
+
     // this contains the list of fences that will need to be fulfilled after the draw command
     // has started
     let mut fences = Vec::with_capacity(0);
 
+    // the count of indirect commands can only be sourced from a buffer with
+    // `GL_ARB_indirect_parameters`
+    match indices {
+        IndicesSource::MultidrawArrayCount { .. } |
+        IndicesSource::MultidrawElementCount { .. } => {
+            if !context.get_extensions().gl_arb_indirect_parameters {
+                return Err(DrawError::IndirectCountNotSupported);
+            }
+        },
+        _ => ()
+    };
+
     // handling tessellation
     let vertices_per_patch = match indices.get_primitives_type() {
         index::PrimitiveType::Patches { vertices_per_patch } => {
@@ -65,6 +98,28 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         },
     };
 
+    if context.is_color_space_validation_enabled() &&
+       program.get_fragment_program().has_srgb_output() &&
+       framebuffer.map(|f| f.has_srgb_color_attachments()).unwrap_or(false)
+    {
+        return Err(DrawError::ColorSpaceMismatch);
+    }
+
+    // if instancing is emulated, the per-instance attributes are read back and passed one
+    // instance at a time ; this must be done before the state changes start
+    let emulate_instancing = context.is_instancing_emulation_enabled() &&
+                             !vertex::is_instancing_supported(context);
+    let vertex_buffers: SmallVec<[VerticesSource<'_>; 4]> = vertex_buffers.iter().collect();
+    let mut emulated_instances: SmallVec<[(Vec<u8>, &VertexFormat, usize); 1]> = SmallVec::new();
+    if emulate_instancing {
+        for src in vertex_buffers.iter() {
+            if let VerticesSource::VertexBuffer(ref buffer, format, true) = *src {
+                let data = buffer.read_bytes().map_err(|_| DrawError::InstancingEmulationFailed)?;
+                emulated_instances.push((data, format, buffer.get_elements_size()));
+            }
+        }
+    }
+
     // starting the state changes
     let mut ctxt = context.make_current();
 
@@ -74,6 +129,8 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
             IndicesSource::IndexBuffer { buffer, .. } => Some(buffer),
             IndicesSource::MultidrawArray { .. } => None,
             IndicesSource::MultidrawElement { indices, .. } => Some(indices),
+            IndicesSource::MultidrawArrayCount { .. } => None,
+            IndicesSource::MultidrawElementCount { indices, .. } => Some(indices),
             IndicesSource::NoIndices { .. } => None,
         };
 
@@ -81,6 +138,8 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         let use_base_vertex = match indices {
             IndicesSource::MultidrawArray { .. } => false,
             IndicesSource::MultidrawElement { .. } => false,
+            IndicesSource::MultidrawArrayCount { .. } => false,
+            IndicesSource::MultidrawElementCount { .. } => false,
             IndicesSource::NoIndices { .. } => true,
             _ => ctxt.version >= &Version(Api::Gl, 3, 2) ||
                  ctxt.version >= &Version(Api::GlEs, 3, 2) ||
@@ -89,15 +148,18 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         };
 
         // object that is used to build the bindings
-        let mut binder = VertexAttributesSystem::start(&mut ctxt, program, index_buffer,
+        let mut binder = VertexAttributesSystem::start(&mut ctxt, vertex_program, index_buffer,
                                                        use_base_vertex);
         // number of vertices in the vertices sources, or `None` if there is a mismatch
         let mut vertices_count: Option<usize> = None;
         // number of instances to draw
         let mut instances_count: Option<usize> = None;
 
-        for src in vertex_buffers.iter() {
+        for src in vertex_buffers.into_iter() {
             match src {
+                VerticesSource::VertexBuffer(_, format, true) if emulate_instancing => {
+                    binder = binder.add_constant(format);
+                },
                 VerticesSource::VertexBuffer(buffer, format, per_instance) => {
                     // TODO: assert!(buffer.get_elements_size() == total_size(format));
 
@@ -162,9 +224,24 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         unsafe { fbo::bind_framebuffer(&mut ctxt, fbo_id, true, false) };
     };
 
+    // the textures of the framebuffer can't be sampled unless there is a texture barrier,
+    // which is checked when binding the uniforms
+    if let Some(framebuffer) = framebuffer {
+        if !draw_parameters.texture_barrier {
+            ctxt.framebuffer_textures = framebuffer.get_attached_textures();
+        }
+    }
+
     // binding the program and uniforms
-    program.use_program(&mut ctxt);
-    uniforms.bind_uniforms(&mut ctxt, program, &mut fences)?;
+    match program {
+        ProgramRef::Program(program) => {
+            program.use_program(&mut ctxt);
+            uniforms.bind_uniforms(&mut ctxt, program, &mut fences)?;
+        },
+        ProgramRef::Pipeline(pipeline) => {
+            uniforms.bind_uniforms_for_pipeline(&mut ctxt, pipeline, &mut fences)?;
+        },
+    }
 
     // sync-ing draw_parameters
     unsafe {
@@ -194,7 +271,15 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
 
                 unsafe {
                     if let Some(instances_count) = instances_count {
-                        if base_vertex != 0 {
+                        if emulate_instancing {
+                            for instance in 0 .. instances_count {
+                                set_emulated_instance(&mut ctxt, vertex_program, &emulated_instances,
+                                                      instance)?;
+                                draw_elements(&mut ctxt, primitives, buffer.get_elements_count(),
+                                              data_type, ptr, base_vertex);
+                            }
+
+                        } else if base_vertex != 0 {
                             if ctxt.version >= &Version(Api::Gl, 3, 2) ||
                                ctxt.version >= &Version(Api::GlEs, 3, 2) ||
                                ctxt.extensions.gl_arb_draw_elements_base_vertex
@@ -230,34 +315,9 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
                                                           instances_count as gl::types::GLsizei);
                         }
 
-                    } else if base_vertex != 0 {
-                        if ctxt.version >= &Version(Api::Gl, 3, 2) ||
-                           ctxt.version >= &Version(Api::GlEs, 3, 2) ||
-                           ctxt.extensions.gl_arb_draw_elements_base_vertex
-                        {
-                            ctxt.gl.DrawElementsBaseVertex(primitives.to_glenum(),
-                                                           buffer.get_elements_count() as
-                                                           gl::types::GLsizei,
-                                                           data_type.to_glenum(),
-                                                           ptr as *const _,
-                                                           base_vertex);
-
-                        } else if ctxt.extensions.gl_oes_draw_elements_base_vertex {
-                            ctxt.gl.DrawElementsBaseVertexOES(primitives.to_glenum(),
-                                                              buffer.get_elements_count() as
-                                                              gl::types::GLsizei,
-                                                              data_type.to_glenum(),
-                                                              ptr as *const _,
-                                                              base_vertex);
-                        } else {
-                            unreachable!();
-                        }
-
                     } else {
-                        ctxt.gl.DrawElements(primitives.to_glenum(),
-                                             buffer.get_elements_count() as gl::types::GLsizei,
-                                             data_type.to_glenum(),
-                                             ptr as *const _);
+                        draw_elements(&mut ctxt, primitives, buffer.get_elements_count(),
+                                      data_type, ptr, base_vertex);
                     }
                 }
             },
@@ -302,6 +362,63 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
                 }
             },
 
+            &IndicesSource::MultidrawArrayCount { ref buffer, ref count, max_count, primitives } => {
+                let ptr: *const u8 = ptr::null_mut();
+                let ptr = unsafe { ptr.add(buffer.get_offset_bytes()) };
+
+                let max_count = cmp::min(max_count as usize, buffer.get_elements_count());
+
+                debug_assert_eq!(base_vertex, 0);       // enforced earlier in this function
+
+                if let Some(fence) = buffer.add_fence() {
+                    fences.push(fence);
+                }
+
+                if let Some(fence) = count.add_fence() {
+                    fences.push(fence);
+                }
+
+                unsafe {
+                    buffer.prepare_and_bind_for_draw_indirect(&mut ctxt);
+                    count.prepare_and_bind_for_parameter(&mut ctxt);
+                    ctxt.gl.MultiDrawArraysIndirectCountARB(primitives.to_glenum(), ptr as *const _,
+                                                    count.get_offset_bytes() as gl::types::GLintptr,
+                                                    max_count as gl::types::GLsizei, 0);
+                }
+            },
+
+            &IndicesSource::MultidrawElementCount { ref commands, ref count, max_count,
+                                                    ref indices, data_type, primitives } =>
+            {
+                let cmd_ptr: *const u8 = ptr::null_mut();
+                let cmd_ptr = unsafe { cmd_ptr.add(commands.get_offset_bytes()) };
+
+                let max_count = cmp::min(max_count as usize, commands.get_elements_count());
+
+                if let Some(fence) = commands.add_fence() {
+                    fences.push(fence);
+                }
+
+                if let Some(fence) = count.add_fence() {
+                    fences.push(fence);
+                }
+
+                if let Some(fence) = indices.add_fence() {
+                    fences.push(fence);
+                }
+
+                unsafe {
+                    commands.prepare_and_bind_for_draw_indirect(&mut ctxt);
+                    count.prepare_and_bind_for_parameter(&mut ctxt);
+                    debug_assert_eq!(base_vertex, 0);       // enforced earlier in this function
+                    ctxt.gl.MultiDrawElementsIndirectCountARB(primitives.to_glenum(),
+                                                      data_type.to_glenum(),
+                                                      cmd_ptr as *const _,
+                                                      count.get_offset_bytes() as gl::types::GLintptr,
+                                                      max_count as gl::types::GLsizei, 0);
+                }
+            },
+
             &IndicesSource::NoIndices { primitives } => {
                 let vertices_count = match vertices_count {
                     Some(c) => c,
@@ -310,9 +427,18 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
 
                 unsafe {
                     if let Some(instances_count) = instances_count {
-                        ctxt.gl.DrawArraysInstanced(primitives.to_glenum(), base_vertex,
-                                                    vertices_count as gl::types::GLsizei,
-                                                    instances_count as gl::types::GLsizei);
+                        if emulate_instancing {
+                            for instance in 0 .. instances_count {
+                                set_emulated_instance(&mut ctxt, vertex_program, &emulated_instances,
+                                                      instance)?;
+                                ctxt.gl.DrawArrays(primitives.to_glenum(), base_vertex,
+                                                   vertices_count as gl::types::GLsizei);
+                            }
+                        } else {
+                            ctxt.gl.DrawArraysInstanced(primitives.to_glenum(), base_vertex,
+                                                        vertices_count as gl::types::GLsizei,
+                                                        instances_count as gl::types::GLsizei);
+                        }
                     } else {
                         ctxt.gl.DrawArrays(primitives.to_glenum(), base_vertex,
                                            vertices_count as gl::types::GLsizei);
@@ -329,6 +455,88 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         fence.insert(&mut ctxt);
     }
 
+    if context.get_flush_policy() == FlushPolicy::FlushAfterCommands {
+        unsafe { ctxt.gl.Flush(); }
+    }
+
+    Ok(())
+}
+
+/// Draws with a program that contains a mesh shader.
+pub fn draw_mesh_tasks<U>(context: &Context, framebuffer: Option<&ValidatedAttachments<'_>>,
+                          count: u32, program: &Program, uniforms: &U,
+                          draw_parameters: &DrawParameters<'_>, dimensions: (u32, u32))
+                          -> Result<(), DrawError> where U: Uniforms
+{
+    if !crate::program::is_mesh_shader_supported(context) {
+        return Err(DrawError::MeshShadersNotSupported);
+    }
+
+    if !program.has_mesh_shader() {
+        return Err(DrawError::MeshShaderRequired);
+    }
+
+    // the limit is unknown with `GL_EXT_mesh_shader`
+    if let Some(max_count) = context.capabilities().max_draw_mesh_tasks_count {
+        if count as u64 > max_count as u64 {
+            return Err(DrawError::TooManyMeshTasks);
+        }
+    }
+
+    // mesh shaders can't write to transform feedback buffers
+    if draw_parameters.transform_feedback.is_some() {
+        return Err(DrawError::TransformFeedbackNotSupported);
+    }
+
+    if let Some(viewport) = draw_parameters.viewport {
+        let max = context.capabilities().max_viewport_dims;
+        if viewport.width > max.0 as u32 || viewport.height > max.1 as u32 {
+            return Err(DrawError::ViewportTooLarge);
+        }
+    }
+
+    let mut fences = Vec::with_capacity(0);
+    let mut ctxt = context.make_current();
+
+    {
+        let fbo_id = fbo::FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, framebuffer);
+        unsafe { fbo::bind_framebuffer(&mut ctxt, fbo_id, true, false) };
+    };
+
+    if let Some(framebuffer) = framebuffer {
+        if !draw_parameters.texture_barrier {
+            ctxt.framebuffer_textures = framebuffer.get_attached_textures();
+        }
+    }
+
+    program.use_program(&mut ctxt);
+    uniforms.bind_uniforms(&mut ctxt, program, &mut fences)?;
+
+    // the primitives are determined by the mesh shader, the type passed here is only used to
+    // check the smoothing parameters
+    draw_parameters::sync(&mut ctxt, draw_parameters, dimensions, PrimitiveType::TrianglesList)?;
+    TransformFeedbackSession::unbind(&mut ctxt);
+
+    if count != 0 {
+        unsafe {
+            if ctxt.extensions.gl_nv_mesh_shader {
+                ctxt.gl.DrawMeshTasksNV(0, count);
+            } else {
+                ctxt.gl.DrawMeshTasksEXT(count, 1, 1);
+            }
+        }
+    }
+
+    ctxt.state.next_draw_call_id += 1;
+
+    for fence in fences.into_iter() {
+        fence.insert(&mut ctxt);
+    }
+
+    if context.get_flush_policy() == FlushPolicy::FlushAfterCommands {
+        unsafe { ctxt.gl.Flush(); }
+    }
+
     Ok(())
 }
 
@@ -341,3 +549,51 @@ unsafe fn sync_vertices_per_patch(ctxt: &mut context::CommandContext<'_>, vertic
         }
     }
 }
+
+/// Calls `glDrawElements` or one of its `BaseVertex` variants.
+unsafe fn draw_elements(ctxt: &mut context::CommandContext<'_>, primitives: PrimitiveType,
+                        count: usize, data_type: IndexType, ptr: *const u8,
+                        base_vertex: gl::types::GLint)
+{
+    if base_vertex != 0 {
+        if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+           ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+           ctxt.extensions.gl_arb_draw_elements_base_vertex
+        {
+            ctxt.gl.DrawElementsBaseVertex(primitives.to_glenum(),
+                                           count as gl::types::GLsizei,
+                                           data_type.to_glenum(),
+                                           ptr as *const _,
+                                           base_vertex);
+
+        } else if ctxt.extensions.gl_oes_draw_elements_base_vertex {
+            ctxt.gl.DrawElementsBaseVertexOES(primitives.to_glenum(),
+                                              count as gl::types::GLsizei,
+                                              data_type.to_glenum(),
+                                              ptr as *const _,
+                                              base_vertex);
+        } else {
+            unreachable!();
+        }
+
+    } else {
+        ctxt.gl.DrawElements(primitives.to_glenum(),
+                             count as gl::types::GLsizei,
+                             data_type.to_glenum(),
+                             ptr as *const _);
+    }
+}
+
+/// Sets the per-instance attributes of an instance when instancing is emulated.
+unsafe fn set_emulated_instance(ctxt: &mut context::CommandContext<'_>, program: &Program,
+                                sources: &[(Vec<u8>, &VertexFormat, usize)], instance: usize)
+                                -> Result<(), DrawError>
+{
+    for &(ref data, format, stride) in sources {
+        vertex_array_object::set_constant_attributes(ctxt, program, format,
+                                                     &data[instance * stride ..
+                                                           (instance + 1) * stride])?;
+    }
+
+    Ok(())
+}
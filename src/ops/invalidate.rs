@@ -0,0 +1,92 @@
+use smallvec::SmallVec;
+
+use crate::fbo::{self, ValidatedAttachments};
+
+use crate::context::Context;
+use crate::ContextExt;
+use crate::Rect;
+
+use crate::Api;
+use crate::version::Version;
+use crate::gl;
+
+/// Buffers of a surface whose content can be invalidated.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct InvalidateBuffers {
+    /// If true, invalidates the color buffers.
+    pub color: bool,
+
+    /// If true, invalidates the depth buffer.
+    pub depth: bool,
+
+    /// If true, invalidates the stencil buffer.
+    pub stencil: bool,
+}
+
+/// Indicates to the backend that the content of some buffers of a framebuffer is no longer
+/// needed.
+///
+/// This is only a hint, and nothing happens if the backend doesn't support it.
+pub fn invalidate(context: &Context, framebuffer: Option<&ValidatedAttachments<'_>>,
+                  rect: Option<&Rect>, buffers: InvalidateBuffers)
+{
+    unsafe {
+        let mut ctxt = context.make_current();
+
+        let invalidate_supported = ctxt.version >= &Version(Api::Gl, 4, 3) ||
+                                   ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+                                   ctxt.extensions.gl_arb_invalidate_subdata;
+
+        // `GL_EXT_discard_framebuffer` can only discard whole buffers
+        if !invalidate_supported && (rect.is_some() || !ctxt.extensions.gl_ext_discard_framebuffer) {
+            return;
+        }
+
+        let fbo_id = fbo::FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, framebuffer);
+
+        let mut attachments: SmallVec<[gl::types::GLenum; 8]> = SmallVec::new();
+        if let Some(framebuffer) = framebuffer {
+            if buffers.color {
+                attachments.extend(framebuffer.get_color_attachment_indices()
+                                              .map(|index| gl::COLOR_ATTACHMENT0 + index));
+            }
+            if buffers.depth && framebuffer.get_depth_buffer_bits().is_some() {
+                attachments.push(gl::DEPTH_ATTACHMENT);
+            }
+            if buffers.stencil && framebuffer.get_stencil_buffer_bits().is_some() {
+                attachments.push(gl::STENCIL_ATTACHMENT);
+            }
+
+        } else {
+            if buffers.color { attachments.push(gl::COLOR); }
+            if buffers.depth { attachments.push(gl::DEPTH); }
+            if buffers.stencil { attachments.push(gl::STENCIL); }
+        }
+
+        if attachments.is_empty() {
+            return;
+        }
+
+        fbo::bind_framebuffer(&mut ctxt, fbo_id, true, false);
+
+        if invalidate_supported {
+            let target = gl::DRAW_FRAMEBUFFER;
+
+            if let Some(rect) = rect {
+                ctxt.gl.InvalidateSubFramebuffer(target, attachments.len() as gl::types::GLsizei,
+                                                 attachments.as_ptr(),
+                                                 rect.left as gl::types::GLint,
+                                                 rect.bottom as gl::types::GLint,
+                                                 rect.width as gl::types::GLsizei,
+                                                 rect.height as gl::types::GLsizei);
+            } else {
+                ctxt.gl.InvalidateFramebuffer(target, attachments.len() as gl::types::GLsizei,
+                                              attachments.as_ptr());
+            }
+
+        } else {
+            ctxt.gl.DiscardFramebufferEXT(gl::FRAMEBUFFER, attachments.len() as gl::types::GLsizei,
+                                          attachments.as_ptr());
+        }
+    }
+}
@@ -1,9 +1,15 @@
-pub use self::blit::blit;
-pub use self::clear::clear;
-pub use self::draw::draw;
+pub use self::blit::{blit, blit_buffers, BlitError, BlitMask};
+pub use self::clear::{clear, clear_buffer};
+pub use self::copy_to_cpu::{copy_to_cpu, ColorSpace, CopyToCpuOptions, CpuImage};
+pub use self::draw::{draw, draw_mesh_tasks};
+pub use self::invalidate::{invalidate, InvalidateBuffers};
 pub use self::read::{read, ReadError, Source, Destination};
+pub use self::read_async::{read_async, PendingRead};
 
 mod blit;
 mod clear;
+mod copy_to_cpu;
 mod draw;
+mod invalidate;
 mod read;
+mod read_async;
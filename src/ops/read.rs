@@ -1,3 +1,4 @@
+use std::mem;
 use std::ptr;
 use std::fmt;
 use std::error::Error;
@@ -73,6 +74,14 @@ pub enum ReadError {
     /// Clamping the values is not supported by the implementation.
     ClampingNotSupported,
 
+    /// The surface doesn't have any color attachment to read from.
+    NoColorAttachment,
+
+    /// The surface doesn't implement reading its pixels to client memory.
+    ///
+    /// This is returned by the default implementations of the methods of `Surface`.
+    NotSupported,
+
     // TODO: context lost
 }
 
@@ -86,6 +95,10 @@ impl fmt::Display for ReadError {
                 "The implementation doesn't support reading a depth, depth-stencil or stencil attachment",
             ClampingNotSupported =>
                 "Clamping the values is not supported by the implementation",
+            NoColorAttachment =>
+                "The surface doesn't have any color attachment to read from",
+            NotSupported =>
+                "The surface doesn't implement reading its pixels to client memory",
         };
         fmt.write_str(desc)
     }
@@ -114,9 +127,10 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext<'_>, source: S, rect: &Re
     // checking that the output format is supported
     // OpenGL supported everything, while OpenGL ES only supports U8U8U8U8 plus an additional
     // implementation-defined format
-    if ctxt.version >= &Version(Api::GlEs, 2, 0) && output_pixel_format != ClientFormat::U8U8U8U8 {
-        // TODO: GLES is guaranteed to support GL_RGBA and an implementation-defined format
-        //       queried with GL_IMPLEMENTATION_COLOR_READ_FORMAT. We only handle GL_RGBA.
+    // U8U8U8 can be obtained by converting from U8U8U8U8, see `negotiate_read_format`
+    if ctxt.version >= &Version(Api::GlEs, 2, 0) && output_pixel_format != ClientFormat::U8U8U8U8 &&
+       output_pixel_format != ClientFormat::U8U8U8
+    {
         return Err(ReadError::OutputFormatNotSupported);
     }
 
@@ -207,9 +221,61 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext<'_>, source: S, rect: &Re
         },
     };
 
+    // the format and type actually passed to `glReadPixels`
+    // if they are different from `format` and `gltype`, the data is converted afterwards
+    let (read_format, read_gltype) = match (&read_src_type, &dest) {
+        (&ReadSourceType::Color, &Destination::Memory(_)) if !integer => {
+            negotiate_read_format(ctxt, format, gltype)
+        },
+        _ => (format, gltype),
+    };
+
+    // reading U8U8U8 in a pixel buffer with OpenGL ES is only possible if this is the
+    // implementation-defined format, as the data can't be converted
+    if ctxt.version >= &Version(Api::GlEs, 2, 0) && output_pixel_format == ClientFormat::U8U8U8 &&
+       (read_format, read_gltype) == (gl::RGB, gl::UNSIGNED_BYTE) &&
+       get_implementation_color_read_format(ctxt) != Some((gl::RGB, gl::UNSIGNED_BYTE))
+    {
+        return Err(ReadError::OutputFormatNotSupported);
+    }
+
     // reading
     unsafe {
         match dest {
+            Destination::Memory(dest) if (read_format, read_gltype) != (format, gltype) => {
+                let read_pixel_size = match read_format {
+                    gl::RGB => 3,
+                    gl::RGBA | gl::BGRA => 4,
+                    _ => unreachable!()
+                };
+
+                let mut buf: Vec<u8> = Vec::with_capacity(pixels_to_read as usize * read_pixel_size);
+
+                BufferAny::unbind_pixel_pack(ctxt);
+
+                // the rows of the data must be tightly packed
+                if ctxt.state.pixel_store_pack_alignment != 1 {
+                    ctxt.state.pixel_store_pack_alignment = 1;
+                    ctxt.gl.PixelStorei(gl::PACK_ALIGNMENT, 1);
+                }
+
+                ctxt.gl.ReadPixels(rect.left as gl::types::GLint, rect.bottom as gl::types::GLint,
+                                   rect.width as gl::types::GLsizei,
+                                   rect.height as gl::types::GLsizei, read_format, read_gltype,
+                                   buf.as_mut_ptr() as *mut _);
+                buf.set_len(pixels_to_read as usize * read_pixel_size);
+
+                let converted = convert_pixels(&buf, read_format, format);
+                assert_eq!(converted.len(), pixels_to_read as usize * mem::size_of::<T>());
+
+                let mut output = Vec::with_capacity(pixels_to_read as usize);
+                ptr::copy_nonoverlapping(converted.as_ptr(), output.as_mut_ptr() as *mut u8,
+                                         converted.len());
+                output.set_len(pixels_to_read as usize);
+
+                *dest = output;
+            },
+
             Destination::Memory(dest) => {
                 let mut buf = Vec::with_capacity(pixels_to_read as usize);
 
@@ -256,6 +322,82 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext<'_>, source: S, rect: &Re
     Ok(())
 }
 
+/// Returns the format and type that the implementation prefers for `glReadPixels`, for the
+/// framebuffer currently bound for reading.
+fn get_implementation_color_read_format(ctxt: &mut CommandContext<'_>)
+                                        -> Option<(gl::types::GLenum, gl::types::GLenum)>
+{
+    if !(ctxt.version >= &Version(Api::Gl, 4, 1) || ctxt.version >= &Version(Api::GlEs, 2, 0) ||
+         ctxt.extensions.gl_arb_es2_compatibility)
+    {
+        return None;
+    }
+
+    unsafe {
+        let mut format = 0;
+        let mut ty = 0;
+        ctxt.gl.GetIntegerv(gl::IMPLEMENTATION_COLOR_READ_FORMAT, &mut format);
+        ctxt.gl.GetIntegerv(gl::IMPLEMENTATION_COLOR_READ_TYPE, &mut ty);
+        Some((format as gl::types::GLenum, ty as gl::types::GLenum))
+    }
+}
+
+/// Chooses the format and type to pass to `glReadPixels` in order to obtain the requested
+/// format and type.
+///
+/// Some implementations, especially OpenGL ES ones, are much faster when reading in their
+/// preferred format. If the preferred format can be converted to the requested one, it is
+/// used instead.
+fn negotiate_read_format(ctxt: &mut CommandContext<'_>, format: gl::types::GLenum,
+                         ty: gl::types::GLenum) -> (gl::types::GLenum, gl::types::GLenum)
+{
+    // we only know how to convert to U8U8U8U8 and U8U8U8
+    if ty != gl::UNSIGNED_BYTE || (format != gl::RGBA && format != gl::RGB) {
+        return (format, ty);
+    }
+
+    match get_implementation_color_read_format(ctxt) {
+        Some((gl::RGBA, gl::UNSIGNED_BYTE)) => (gl::RGBA, gl::UNSIGNED_BYTE),
+        Some((gl::RGB, gl::UNSIGNED_BYTE)) => (gl::RGB, gl::UNSIGNED_BYTE),
+        Some((gl::BGRA, gl::UNSIGNED_BYTE)) => (gl::BGRA, gl::UNSIGNED_BYTE),
+        // OpenGL ES is only guaranteed to support RGBA
+        _ if ctxt.version >= &Version(Api::GlEs, 2, 0) => (gl::RGBA, gl::UNSIGNED_BYTE),
+        _ => (format, ty),
+    }
+}
+
+/// Converts pixels of type `GL_UNSIGNED_BYTE` from one format to another.
+///
+/// The source format must be `GL_RGB`, `GL_RGBA` or `GL_BGRA`, and the destination format
+/// must be `GL_RGB` or `GL_RGBA`.
+fn convert_pixels(data: &[u8], from: gl::types::GLenum, to: gl::types::GLenum) -> Vec<u8> {
+    let (src_size, order): (usize, [Option<usize>; 4]) = match from {
+        gl::RGB => (3, [Some(0), Some(1), Some(2), None]),
+        gl::RGBA => (4, [Some(0), Some(1), Some(2), Some(3)]),
+        gl::BGRA => (4, [Some(2), Some(1), Some(0), Some(3)]),
+        _ => unreachable!()
+    };
+
+    let dst_size = match to {
+        gl::RGB => 3,
+        gl::RGBA => 4,
+        _ => unreachable!()
+    };
+
+    let mut output = Vec::with_capacity(data.len() / src_size * dst_size);
+
+    for pixel in data.chunks(src_size) {
+        for component in order.iter().take(dst_size) {
+            output.push(match *component {
+                Some(index) => pixel[index],
+                None => 255,
+            });
+        }
+    }
+
+    output
+}
+
 fn client_format_to_gl_enum(format: &ClientFormat, integer: bool)
                             -> (gl::types::GLenum, gl::types::GLenum)
 {
@@ -313,3 +455,27 @@ fn client_format_to_gl_enum(format: &ClientFormat, integer: bool)
 
     (format, ty)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::gl;
+    use super::convert_pixels;
+
+    #[test]
+    fn convert_bgra_to_rgba() {
+        let data = [1, 2, 3, 4, 5, 6, 7, 8];
+        assert_eq!(convert_pixels(&data, gl::BGRA, gl::RGBA), vec![3, 2, 1, 4, 7, 6, 5, 8]);
+    }
+
+    #[test]
+    fn convert_rgba_to_rgb() {
+        let data = [1, 2, 3, 4, 5, 6, 7, 8];
+        assert_eq!(convert_pixels(&data, gl::RGBA, gl::RGB), vec![1, 2, 3, 5, 6, 7]);
+    }
+
+    #[test]
+    fn convert_rgb_to_rgba() {
+        let data = [1, 2, 3, 4, 5, 6];
+        assert_eq!(convert_pixels(&data, gl::RGB, gl::RGBA), vec![1, 2, 3, 255, 4, 5, 6, 255]);
+    }
+}
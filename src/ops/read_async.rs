@@ -0,0 +1,104 @@
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{self, Poll};
+
+use crate::buffer::ReadError as BufferReadError;
+use crate::context::Context;
+use crate::pixel_buffer::PixelBuffer;
+use crate::sync::SyncFence;
+use crate::texture::Texture2dDataSink;
+use crate::ContextExt;
+use crate::Rect;
+
+use super::read::{read, ReadError, Source};
+
+/// Pixels that are being copied from a surface or a texture by `read_async`.
+///
+/// The pixels are first copied to a pixel buffer by the GPU, which doesn't block. Once the copy
+/// has finished, they can be read without any synchronization. You can either poll `is_ready`
+/// once per frame, or use this object as a `Future`.
+///
+/// When used as a `Future`, the task is woken up every time the buffers of the context are
+/// swapped (with `Frame::finish` or `Context::swap_buffers`), as there is no way to be notified
+/// when the GPU has finished the copy. A future that is awaited while no frame is being drawn
+/// is therefore never woken up.
+///
+/// If the backend doesn't support sync fences, `is_ready` always returns true and reading may
+/// block.
+pub struct PendingRead<T> {
+    buffer: PixelBuffer<(u8, u8, u8, u8)>,
+    fence: Option<SyncFence>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> PendingRead<T> where T: Texture2dDataSink<(u8, u8, u8, u8)> {
+    /// Returns true if the GPU has finished copying the pixels, in which case reading them
+    /// doesn't block. Doesn't block.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        match self.fence {
+            Some(ref fence) => fence.is_signaled(),
+            None => true,
+        }
+    }
+
+    /// Reads the pixels, blocking until the GPU has finished copying them if necessary.
+    ///
+    /// The rows go from bottom to top.
+    #[inline]
+    pub fn read(self) -> Result<T, BufferReadError> {
+        self.buffer.read_as_texture_2d()
+    }
+
+    /// Returns the pixel buffer where the pixels are copied.
+    #[inline]
+    pub fn into_pixel_buffer(self) -> PixelBuffer<(u8, u8, u8, u8)> {
+        self.buffer
+    }
+}
+
+impl<T> Future for PendingRead<T> where T: Texture2dDataSink<(u8, u8, u8, u8)> {
+    type Output = Result<T, BufferReadError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        if !self.is_ready() {
+            // there is no way to be notified when a fence is signaled, so we check it again after
+            // the next frame
+            self.buffer.get_context().wake_after_swap(cx.waker());
+            return Poll::Pending;
+        }
+
+        self.fence = None;
+        Poll::Ready(self.buffer.read_as_texture_2d())
+    }
+}
+
+/// Starts copying a region of a color buffer to a pixel buffer.
+///
+/// # Panic
+///
+/// Panics if `rect` is out of `dimensions`.
+pub fn read_async<'a, S, T>(context: &Rc<Context>, source: S, dimensions: (u32, u32), rect: &Rect)
+                            -> Result<PendingRead<T>, ReadError>
+                            where S: Into<Source<'a>>, T: Texture2dDataSink<(u8, u8, u8, u8)>
+{
+    assert!(rect.left.checked_add(rect.width).map(|r| r <= dimensions.0).unwrap_or(false) &&
+            rect.bottom.checked_add(rect.height).map(|b| b <= dimensions.1).unwrap_or(false),
+            "The rect is out of the bounds of the surface");
+
+    let source = source.into();
+    let buffer = PixelBuffer::new_empty(context, rect.width as usize * rect.height as usize);
+
+    {
+        let mut ctxt = context.make_current();
+        read(&mut ctxt, source, rect, &buffer, false)?;
+    }
+
+    Ok(PendingRead {
+        buffer,
+        fence: SyncFence::new(context).ok(),
+        marker: PhantomData,
+    })
+}
@@ -0,0 +1,283 @@
+//! On-disk cache of program binaries.
+use std::fs;
+use std::hash::Hasher;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use fnv::FnvHasher;
+
+use crate::backend::Facade;
+use crate::CapabilitiesSource;
+
+use crate::program::{Binary, Program, ProgramCreationError, ProgramCreationInput};
+use crate::program::{ShaderModuleDependencies, TransformFeedbackMode, is_binary_supported};
+
+/// Magic number written at the start of each file of the cache.
+const MAGIC: &[u8; 4] = b"GLPC";
+
+/// Extension of the files of the cache.
+const EXTENSION: &str = "glpc";
+
+/// Stores the binaries of the programs in a directory, so that their source code only needs to
+/// be compiled the first time the application runs.
+///
+/// Each file of the cache is named after a hash of the source code of the shaders (after the
+/// `#import` directives have been resolved), of the other creation parameters and of the
+/// OpenGL version, vendor and renderer. Updating the driver or modifying a shader therefore
+/// results in a cache miss instead of a bad binary being loaded.
+///
+/// Drivers are allowed to reject binaries that they have produced themselves. When this
+/// happens, or when the file of the cache is missing or corrupt, the program is compiled
+/// from its source code and the file is rewritten.
+///
+/// Errors while reading or writing the cache are ignored, since the program can always be
+/// compiled from its source code.
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// # let vertex_source = ""; let fragment_source = "";
+/// let cache = glium::program::ProgramCache::new("shader-cache");
+/// let program = cache.get_or_create(&display, glium::program::SourceCode {
+///     vertex_shader: vertex_source,
+///     fragment_shader: fragment_source,
+///     geometry_shader: None,
+///     tessellation_control_shader: None,
+///     tessellation_evaluation_shader: None,
+/// }).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProgramCache {
+    directory: PathBuf,
+}
+
+impl ProgramCache {
+    /// Builds a cache that stores its files in the given directory.
+    ///
+    /// The directory is created the first time a binary is stored.
+    #[inline]
+    pub fn new<P>(directory: P) -> ProgramCache where P: Into<PathBuf> {
+        ProgramCache {
+            directory: directory.into(),
+        }
+    }
+
+    /// Returns the directory where the binaries are stored.
+    #[inline]
+    pub fn get_directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Builds a program, loading its binary from the cache if possible.
+    ///
+    /// If the input is source code and the backend supports program binaries, the cache is
+    /// looked up first. On a miss, the program is compiled and its binary is stored.
+    ///
+    /// If the input is already a binary, or if the backend doesn't support program binaries,
+    /// this is equivalent to `Program::new`.
+    pub fn get_or_create<'a, F: ?Sized, I>(&self, facade: &F, input: I)
+                                           -> Result<Program, ProgramCreationError>
+                                           where I: Into<ProgramCreationInput<'a>>, F: Facade
+    {
+        let input = input.into();
+
+        let context = facade.get_context();
+        if !is_binary_supported(&**context) {
+            return Program::new(facade, input);
+        }
+
+        let (key, dependencies, outputs_srgb, uses_point_size) = match input {
+            ProgramCreationInput::SourceCode { outputs_srgb, uses_point_size, .. } => {
+                let (key, dependencies) = cache_key(facade, &input)?;
+                (key, dependencies, outputs_srgb, uses_point_size)
+            },
+            ProgramCreationInput::MeshSourceCode { outputs_srgb, .. } => {
+                let (key, dependencies) = cache_key(facade, &input)?;
+                (key, dependencies, outputs_srgb, false)
+            },
+            ProgramCreationInput::Binary { .. } => return Program::new(facade, input),
+        };
+
+        let path = self.directory.join(format!("{:016x}.{}", key, EXTENSION));
+
+        if let Some(data) = read_binary(&path) {
+            let binary = ProgramCreationInput::Binary {
+                data,
+                outputs_srgb,
+                uses_point_size,
+            };
+
+            // the driver can reject the binary, in which case we fall back to the source code
+            if let Ok(mut program) = Program::new(facade, binary) {
+                program.set_module_dependencies(dependencies);
+                return Ok(program);
+            }
+        }
+
+        let program = Program::new(facade, input)?;
+
+        if let Ok(binary) = program.get_binary() {
+            let _ = self.write_binary(&path, &binary);
+        }
+
+        Ok(program)
+    }
+
+    /// Removes all the binaries stored in the cache.
+    pub fn clear(&self) -> io::Result<()> {
+        let entries = match fs::read_dir(&self.directory) {
+            Ok(entries) => entries,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err),
+        };
+
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().map(|e| e == EXTENSION).unwrap_or(false) {
+                fs::remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_binary(&self, path: &Path, binary: &Binary) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+
+        let mut data = Vec::with_capacity(8 + binary.content.len());
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&binary.format.to_le_bytes());
+        data.extend_from_slice(&binary.content);
+
+        // writing to a temporary file first, so that a crash never leaves a truncated binary
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, &data)?;
+        fs::rename(&temporary, path)
+    }
+}
+
+/// Reads a binary written by `ProgramCache::write_binary`.
+fn read_binary(path: &Path) -> Option<Binary> {
+    let data = fs::read(path).ok()?;
+    if data.len() <= 8 || &data[0 .. 4] != MAGIC {
+        return None;
+    }
+
+    let mut format = [0; 4];
+    format.copy_from_slice(&data[4 .. 8]);
+
+    Some(Binary {
+        format: u32::from_le_bytes(format),
+        content: data[8 ..].to_vec(),
+    })
+}
+
+/// Computes the hash that identifies a program in the cache, and returns the shader modules
+/// that the program uses.
+fn cache_key<F: ?Sized>(facade: &F, input: &ProgramCreationInput<'_>)
+                        -> Result<(u64, ShaderModuleDependencies), ProgramCreationError>
+                        where F: Facade
+{
+    let context = facade.get_context();
+    let registry = context.get_shader_module_registry();
+
+    // the hasher of the standard library is not guaranteed to give the same results between two
+    // versions of Rust, which would invalidate the cache
+    let mut hasher = FnvHasher::default();
+    let mut dependencies = ShaderModuleDependencies::default();
+
+    let capabilities = context.get_capabilities();
+    write_str(&mut hasher, env!("CARGO_PKG_VERSION"));
+    write_str(&mut hasher, &format!("{:?}", context.get_version()));
+    write_str(&mut hasher, &capabilities.version);
+    write_str(&mut hasher, &capabilities.vendor);
+    write_str(&mut hasher, &capabilities.renderer);
+
+    if let ProgramCreationInput::SourceCode { vertex_shader, tessellation_control_shader,
+                                              tessellation_evaluation_shader, geometry_shader,
+                                              fragment_shader, ref transform_feedback_varyings,
+                                              outputs_srgb, uses_point_size } = *input
+    {
+        let shaders = [Some(vertex_shader), tessellation_control_shader,
+                       tessellation_evaluation_shader, geometry_shader, Some(fragment_shader)];
+
+        for shader in shaders.iter() {
+            match *shader {
+                Some(source) => {
+                    let (source, deps) = registry.resolve(source)
+                                                 .map_err(ProgramCreationError::ShaderModuleError)?;
+                    dependencies.extend(deps);
+                    hasher.write(&[1]);
+                    write_str(&mut hasher, &source);
+                },
+                None => hasher.write(&[0]),
+            }
+        }
+
+        match *transform_feedback_varyings {
+            Some((ref varyings, mode)) => {
+                hasher.write(&[match mode {
+                    TransformFeedbackMode::Interleaved => 1,
+                    TransformFeedbackMode::Separate => 2,
+                }]);
+                for varying in varyings {
+                    write_str(&mut hasher, varying);
+                }
+            },
+            None => hasher.write(&[0]),
+        }
+
+        hasher.write(&[outputs_srgb as u8, uses_point_size as u8]);
+
+    } else if let ProgramCreationInput::MeshSourceCode { task_shader, mesh_shader,
+                                                         fragment_shader, outputs_srgb } = *input
+    {
+        write_str(&mut hasher, "mesh");
+
+        let shaders = [task_shader, Some(mesh_shader), Some(fragment_shader)];
+
+        for shader in shaders.iter() {
+            match *shader {
+                Some(source) => {
+                    let (source, deps) = registry.resolve(source)
+                                                 .map_err(ProgramCreationError::ShaderModuleError)?;
+                    dependencies.extend(deps);
+                    hasher.write(&[1]);
+                    write_str(&mut hasher, &source);
+                },
+                None => hasher.write(&[0]),
+            }
+        }
+
+        hasher.write(&[outputs_srgb as u8]);
+    }
+
+    Ok((hasher.finish(), dependencies))
+}
+
+/// Writes a string followed by its length, so that consecutive strings can't be confused.
+fn write_str(hasher: &mut FnvHasher, s: &str) {
+    hasher.write(s.as_bytes());
+    hasher.write(&(s.len() as u64).to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::Hasher;
+
+    use fnv::FnvHasher;
+
+    use super::write_str;
+
+    #[test]
+    fn strings_are_delimited() {
+        let mut a = FnvHasher::default();
+        write_str(&mut a, "ab");
+        write_str(&mut a, "c");
+
+        let mut b = FnvHasher::default();
+        write_str(&mut b, "a");
+        write_str(&mut b, "bc");
+
+        assert!(a.finish() != b.finish());
+    }
+}
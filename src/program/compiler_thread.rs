@@ -0,0 +1,204 @@
+//! Compiling programs on a thread whose context shares its objects with the render thread.
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::backend::Facade;
+use crate::context::ShareGroupId;
+use crate::transfer::TransferTicket;
+
+use crate::program::{Program, ProgramCreationError, ProgramCreationInput, ShaderType};
+use crate::program::TransformFeedbackMode;
+
+/// The result of a compilation, sent back by the thread.
+pub(crate) type CompilationResult = Result<TransferTicket<Program>, ProgramCreationError>;
+
+/// A thread that builds programs with a context that shares its objects with other contexts.
+///
+/// Pass it to `Context::set_compiler_thread`. From then on, if the backend doesn't support
+/// `GL_KHR_parallel_shader_compile` or `GL_ARB_parallel_shader_compile`, `Program::new_async`
+/// sends the programs to this thread instead of compiling them on the thread of the context.
+/// The programs are moved back with a `TransferTicket` (see the `transfer` module).
+///
+/// ```no_run
+/// # use glium::glutin;
+/// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// # let event_loop = glutin::event_loop::EventLoop::new();
+/// let resource_context = display.create_resource_context(&event_loop).unwrap();
+/// let thread = glium::program::CompilerThread::new(move || {
+///     resource_context.make_current().unwrap()
+/// });
+///
+/// display.set_compiler_thread(Some(thread));
+/// ```
+pub struct CompilerThread {
+    sender: Sender<Job>,
+    share_group: ShareGroupId,
+}
+
+/// A program to build, and where to send the result.
+struct Job {
+    source: OwnedSource,
+    result: Sender<CompilationResult>,
+}
+
+impl CompilerThread {
+    /// Spawns a thread, and calls `init` on this thread in order to obtain the context that
+    /// builds the programs.
+    ///
+    /// Blocks until `init` has returned.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if `init` panics.
+    pub fn new<C, F>(init: C) -> CompilerThread
+                     where C: FnOnce() -> F + Send + 'static, F: Facade
+    {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let (share_group_sender, share_group_receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            let facade = init();
+            let _ = share_group_sender.send(facade.get_context().get_share_group_id());
+
+            for job in receiver {
+                let result = Program::new(&facade, job.source.as_input())
+                                     .map(|program| TransferTicket::new(&facade, program));
+
+                // the pending program has been dropped, destroying the program here instead of
+                // leaking it
+                if let Err(mpsc::SendError(Ok(ticket))) = job.result.send(result) {
+                    drop(ticket.wait(&facade));
+                }
+            }
+        });
+
+        let share_group = share_group_receiver.recv()
+                                              .expect("The initialization of the compiler \
+                                                       thread has panicked");
+
+        CompilerThread { sender, share_group }
+    }
+
+    /// Returns the share group of the context of the thread.
+    #[inline]
+    pub(crate) fn get_share_group_id(&self) -> ShareGroupId {
+        self.share_group
+    }
+
+    /// Sends a program to build to the thread.
+    pub(crate) fn compile(&self, source: OwnedSource) -> Receiver<CompilationResult> {
+        let (result, receiver) = mpsc::channel();
+
+        // if the thread has stopped, the receiver reports it as a disconnection
+        let _ = self.sender.send(Job { source, result });
+        receiver
+    }
+}
+
+impl fmt::Debug for CompilerThread {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(fmt, "CompilerThread({:?})", self.share_group)
+    }
+}
+
+/// Source code of a program, after its `#import` directives have been resolved, that can be
+/// sent to another thread.
+pub(crate) enum OwnedSource {
+    SourceCode {
+        vertex_shader: String,
+        tessellation_control_shader: Option<String>,
+        tessellation_evaluation_shader: Option<String>,
+        geometry_shader: Option<String>,
+        fragment_shader: String,
+        transform_feedback_varyings: Option<(Vec<String>, TransformFeedbackMode)>,
+        outputs_srgb: bool,
+        uses_point_size: bool,
+    },
+    MeshSourceCode {
+        task_shader: Option<String>,
+        mesh_shader: String,
+        fragment_shader: String,
+        outputs_srgb: bool,
+    },
+}
+
+impl OwnedSource {
+    /// Builds the source from the shaders returned by `prepare_source`.
+    pub(crate) fn new(shaders: Vec<(String, ShaderType)>,
+                      transform_feedback_varyings: Option<(Vec<String>, TransformFeedbackMode)>,
+                      outputs_srgb: bool, uses_point_size: bool) -> OwnedSource
+    {
+        let mut vertex_shader = String::new();
+        let mut tessellation_control_shader = None;
+        let mut tessellation_evaluation_shader = None;
+        let mut geometry_shader = None;
+        let mut fragment_shader = String::new();
+        let mut task_shader = None;
+        let mut mesh_shader = None;
+
+        for (source, ty) in shaders {
+            match ty {
+                ShaderType::Vertex => vertex_shader = source,
+                ShaderType::TesselationControl => tessellation_control_shader = Some(source),
+                ShaderType::TesselationEvaluation => tessellation_evaluation_shader = Some(source),
+                ShaderType::Geometry => geometry_shader = Some(source),
+                ShaderType::Fragment => fragment_shader = source,
+                ShaderType::Task => task_shader = Some(source),
+                ShaderType::Mesh => mesh_shader = Some(source),
+                ShaderType::Compute => unreachable!(),
+            }
+        }
+
+        match mesh_shader {
+            Some(mesh_shader) => OwnedSource::MeshSourceCode {
+                task_shader,
+                mesh_shader,
+                fragment_shader,
+                outputs_srgb,
+            },
+            None => OwnedSource::SourceCode {
+                vertex_shader,
+                tessellation_control_shader,
+                tessellation_evaluation_shader,
+                geometry_shader,
+                fragment_shader,
+                transform_feedback_varyings,
+                outputs_srgb,
+                uses_point_size,
+            },
+        }
+    }
+
+    fn as_input(&self) -> ProgramCreationInput<'_> {
+        match *self {
+            OwnedSource::SourceCode { ref vertex_shader, ref tessellation_control_shader,
+                                      ref tessellation_evaluation_shader, ref geometry_shader,
+                                      ref fragment_shader, ref transform_feedback_varyings,
+                                      outputs_srgb, uses_point_size } =>
+            {
+                ProgramCreationInput::SourceCode {
+                    vertex_shader,
+                    tessellation_control_shader: tessellation_control_shader.as_deref(),
+                    tessellation_evaluation_shader: tessellation_evaluation_shader.as_deref(),
+                    geometry_shader: geometry_shader.as_deref(),
+                    fragment_shader,
+                    transform_feedback_varyings: transform_feedback_varyings.clone(),
+                    outputs_srgb,
+                    uses_point_size,
+                }
+            },
+            OwnedSource::MeshSourceCode { ref task_shader, ref mesh_shader, ref fragment_shader,
+                                          outputs_srgb } =>
+            {
+                ProgramCreationInput::MeshSourceCode {
+                    task_shader: task_shader.as_deref(),
+                    mesh_shader,
+                    fragment_shader,
+                    outputs_srgb,
+                }
+            },
+        }
+    }
+}
@@ -12,11 +12,13 @@ use fnv::FnvHasher;
 
 use crate::CapabilitiesSource;
 use crate::GlObject;
+use crate::ContextExt;
 use crate::ProgramExt;
 use crate::Handle;
 use crate::RawUniformValue;
 
 use crate::program::{COMPILER_GLOBAL_LOCK, ProgramCreationError, Binary, GetBinaryError};
+use crate::program::ShaderModuleDependencies;
 
 use crate::program::reflection::{Uniform, UniformBlock};
 use crate::program::reflection::{ShaderStage, SubroutineData};
@@ -30,6 +32,8 @@ use crate::uniforms::Uniforms;
 /// A combination of compute shaders linked together.
 pub struct ComputeShader {
     raw: RawProgram,
+    module_dependencies: ShaderModuleDependencies,
+    work_group_size: (u32, u32, u32),
 }
 
 impl ComputeShader {
@@ -44,12 +48,19 @@ impl ComputeShader {
     pub fn from_source<F: ?Sized>(facade: &F, src: &str) -> Result<ComputeShader, ProgramCreationError>
                           where F: Facade
     {
+        let (src, module_dependencies) = facade.get_context().get_shader_module_registry()
+                                               .resolve(src)
+                                               .map_err(ProgramCreationError::ShaderModuleError)?;
+
         let _lock = COMPILER_GLOBAL_LOCK.lock();
 
-        let shader = build_shader(facade, gl::COMPUTE_SHADER, src)?;
+        let shader = build_shader(facade, gl::COMPUTE_SHADER, &src)?;
+        let raw = RawProgram::from_shaders(facade, &[shader], false, false, false, None)?;
 
         Ok(ComputeShader {
-            raw: RawProgram::from_shaders(facade, &[shader], false, false, false, None)?
+            work_group_size: reflect_work_group_size(&raw),
+            raw,
+            module_dependencies,
         })
     }
 
@@ -59,17 +70,57 @@ impl ComputeShader {
                           where F: Facade
     {
         let _lock = COMPILER_GLOBAL_LOCK.lock();
+        let raw = RawProgram::from_binary(facade, data)?;
 
         Ok(ComputeShader {
-            raw: RawProgram::from_binary(facade, data)?
+            work_group_size: reflect_work_group_size(&raw),
+            raw,
+            module_dependencies: ShaderModuleDependencies::default(),
         })
     }
 
+    /// Returns the list of shader modules that have been imported by the source code of this
+    /// compute shader, with their version at the time it was created.
+    #[inline]
+    pub fn get_shader_module_dependencies(&self) -> &ShaderModuleDependencies {
+        &self.module_dependencies
+    }
+
+    /// Returns true if one of the shader modules imported by this compute shader has been
+    /// modified or unregistered since it was created.
+    #[inline]
+    pub fn is_outdated(&self) -> bool {
+        !self.raw.get_context().get_shader_module_registry()
+                               .is_up_to_date(&self.module_dependencies)
+    }
+
+    /// Returns the size of the work groups, as declared with `local_size_x`, `local_size_y`
+    /// and `local_size_z` in the source code.
+    ///
+    /// This is useful to compute the number of work groups to execute for a given number
+    /// of elements.
+    ///
+    /// ```no_run
+    /// # let program: glium::program::ComputeShader = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// # let uniforms = glium::uniforms::EmptyUniforms; let elements = 1000;
+    /// let (size_x, _, _) = program.work_group_size();
+    /// program.execute(uniforms, (elements + size_x - 1) / size_x, 1, 1);
+    /// ```
+    #[inline]
+    pub fn work_group_size(&self) -> (u32, u32, u32) {
+        self.work_group_size
+    }
+
     /// Executes the compute shader.
     ///
     /// `x * y * z` work groups will be started. The current work group can be retrieved with
     /// `gl_WorkGroupID`. Inside each work group, additional local work groups can be started
     /// depending on the attributes of the compute shader itself.
+    ///
+    /// The buffers written by the shader through shader storage blocks, atomic counters and
+    /// buffer textures get the required memory barriers automatically before they are used
+    /// again. The textures written through an `ImageUnit` don't, and you must call
+    /// `Context::memory_barrier` before using them.
     #[inline]
     pub fn execute<U>(&self, uniforms: U, x: u32, y: u32, z: u32) where U: Uniforms {
         unsafe { self.raw.dispatch_compute(uniforms, x, y, z) }.unwrap();       // FIXME: return error
@@ -77,12 +128,46 @@ impl ComputeShader {
 
     /// Executes the compute shader.
     ///
-    /// This is similar to `execute`, except that the parameters are stored in a buffer.
+    /// This is similar to `execute`, except that the number of work groups is read by the GPU
+    /// from a buffer. A previous compute shader can therefore choose the size of the dispatch,
+    /// for example depending on the number of objects that passed a culling test, without
+    /// the buffer being read back by the CPU.
+    ///
+    /// ```no_run
+    /// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// # let program: glium::program::ComputeShader = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// use glium::buffer::{Buffer, BufferMode, BufferType};
+    /// use glium::program::ComputeCommand;
+    ///
+    /// let command = ComputeCommand { num_groups_x: 16, num_groups_y: 1, num_groups_z: 1 };
+    /// let buffer = Buffer::new(&display, &command, BufferType::DispatchIndirectBuffer,
+    ///                          BufferMode::Default).unwrap();
+    ///
+    /// program.execute_indirect(glium::uniforms::EmptyUniforms, &buffer);
+    /// ```
     #[inline]
-    pub fn execute_indirect<U>(&self, uniforms: U, buffer: BufferSlice<'_, ComputeCommand>)
-                               where U: Uniforms
+    pub fn execute_indirect<'a, U, B>(&self, uniforms: U, buffer: B)
+                                      where U: Uniforms, B: Into<BufferSlice<'a, ComputeCommand>>
     {
-        unsafe { self.raw.dispatch_compute_indirect(uniforms, buffer) }.unwrap();       // FIXME: return error
+        unsafe { self.raw.dispatch_compute_indirect(uniforms, buffer.into()) }.unwrap();       // FIXME: return error
+    }
+
+    /// Executes the compute shader with the parameters stored at index `index` of a buffer
+    /// of commands.
+    ///
+    /// See `execute_indirect`.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if `index` is out of range.
+    pub fn execute_indirect_at<'a, U, B>(&self, uniforms: U, buffer: B, index: usize)
+                                         where U: Uniforms,
+                                               B: Into<BufferSlice<'a, [ComputeCommand]>>
+    {
+        let buffer = buffer.into();
+        let command = buffer.slice(index .. index + 1)
+                            .expect("The index of the compute command is out of range");
+        unsafe { self.raw.dispatch_compute_indirect(uniforms, command) }.unwrap();       // FIXME: return error
     }
 
     /// Returns the program's compiled binary.
@@ -115,6 +200,24 @@ impl ComputeShader {
         self.raw.uniforms()
     }
 
+    /// Returns the texture unit that each sampler uniform was assigned to, sorted by unit.
+    ///
+    /// Units are assigned during draw calls and are kept from one draw call to the next one
+    /// whenever possible. Sampler uniforms that haven't been used yet are not in the list.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::program::ComputeShader = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// for (name, unit) in program.get_texture_units() {
+    ///     println!("{} is bound to GL_TEXTURE{}", name, unit);
+    /// }
+    /// ```
+    #[inline]
+    pub fn get_texture_units(&self) -> Vec<(&str, u16)> {
+        self.raw.get_texture_units()
+    }
+
     /// Returns a list of uniform blocks.
     ///
     /// ## Example
@@ -177,6 +280,18 @@ impl ProgramExt for ComputeShader {
         self.raw.set_uniform(ctxt, uniform_location, value)
     }
 
+    #[inline]
+    fn get_texture_unit(&self, uniform_location: gl::types::GLint) -> Option<u16> {
+        self.raw.get_texture_unit(uniform_location)
+    }
+
+    #[inline]
+    fn set_texture_unit(&self, ctxt: &mut CommandContext<'_>, uniform_location: gl::types::GLint,
+                        unit: u16)
+    {
+        self.raw.set_texture_unit(ctxt, uniform_location, unit)
+    }
+
     #[inline]
     fn set_uniform_block_binding(&self, ctxt: &mut CommandContext<'_>, block_location: gl::types::GLuint,
                                  value: gl::types::GLuint)
@@ -228,6 +343,20 @@ impl ProgramExt for ComputeShader {
     }
 }
 
+/// Queries the work group size declared by a linked compute shader.
+fn reflect_work_group_size(raw: &RawProgram) -> (u32, u32, u32) {
+    let ctxt = raw.get_context().make_current();
+
+    let id = match raw.get_id() {
+        Handle::Id(id) => id,
+        Handle::Handle(_) => unreachable!(),
+    };
+
+    let mut size: [gl::types::GLint; 3] = [0; 3];
+    unsafe { ctxt.gl.GetProgramiv(id, gl::COMPUTE_WORK_GROUP_SIZE, size.as_mut_ptr()); }
+    (size[0] as u32, size[1] as u32, size[2] as u32)
+}
+
 /// Represents a compute shader command waiting to be dispatched.
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
@@ -0,0 +1,455 @@
+//! Reloading programs when their source code is modified.
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::SystemTime;
+
+use crate::backend::Facade;
+use crate::context::Context;
+
+use crate::program::{PendingProgram, Program, ProgramCreationError, SourceCode};
+
+/// Where the source code of a shader comes from.
+pub enum SourceProvider {
+    /// The source code is read from a file, which is read again whenever its modification
+    /// time changes.
+    Path(PathBuf),
+
+    /// The source code is returned by a callback, which is called by each `HotReload::update`.
+    ///
+    /// The callback must return the source code when it is first called, then `Some` whenever
+    /// the source code has been modified and `None` otherwise.
+    Callback(Box<dyn FnMut() -> Option<String>>),
+}
+
+impl SourceProvider {
+    /// Builds a provider that reads a file.
+    #[inline]
+    pub fn path<P>(path: P) -> SourceProvider where P: Into<PathBuf> {
+        SourceProvider::Path(path.into())
+    }
+
+    /// Builds a provider that calls a callback.
+    #[inline]
+    pub fn callback<C>(callback: C) -> SourceProvider
+                       where C: FnMut() -> Option<String> + 'static
+    {
+        SourceProvider::Callback(Box::new(callback))
+    }
+}
+
+impl fmt::Debug for SourceProvider {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match *self {
+            SourceProvider::Path(ref path) => write!(fmt, "SourceProvider::Path({:?})", path),
+            SourceProvider::Callback(_) => write!(fmt, "SourceProvider::Callback"),
+        }
+    }
+}
+
+/// The source providers of the shaders of a program managed by `HotReload`.
+#[derive(Debug)]
+pub struct HotReloadSources {
+    /// Source code of the vertex shader.
+    pub vertex_shader: SourceProvider,
+
+    /// Source code of the optional tessellation control shader.
+    pub tessellation_control_shader: Option<SourceProvider>,
+
+    /// Source code of the optional tessellation evaluation shader.
+    pub tessellation_evaluation_shader: Option<SourceProvider>,
+
+    /// Source code of the optional geometry shader.
+    pub geometry_shader: Option<SourceProvider>,
+
+    /// Source code of the fragment shader.
+    pub fragment_shader: SourceProvider,
+}
+
+/// Error that can happen while loading or reloading a program.
+#[derive(Debug)]
+pub enum HotReloadError {
+    /// Failed to read the source code of a shader from a file.
+    Io {
+        /// The file that couldn't be read.
+        path: PathBuf,
+        /// The error returned by the operating system.
+        error: io::Error,
+    },
+
+    /// A callback returned `None` the first time it was called.
+    MissingSource,
+
+    /// Failed to compile or link the program.
+    ProgramCreationError(ProgramCreationError),
+
+    /// The program has been built, but `glValidateProgram` has rejected it. Contains the
+    /// information log of the program.
+    ValidationFailed(String),
+}
+
+impl fmt::Display for HotReloadError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::HotReloadError::*;
+        match *self {
+            Io { ref path, ref error } =>
+                write!(fmt, "Failed to read `{}`: {}", path.display(), error),
+            MissingSource =>
+                write!(fmt, "A source provider didn't return the initial source code"),
+            ProgramCreationError(ref err) =>
+                write!(fmt, "Failed to build the program: {}", err),
+            ValidationFailed(ref log) =>
+                write!(fmt, "The validation of the program has failed: {}", log),
+        }
+    }
+}
+
+impl Error for HotReloadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use self::HotReloadError::*;
+        match *self {
+            Io { ref error, .. } => Some(error),
+            MissingSource => None,
+            ProgramCreationError(ref err) => Some(err),
+            ValidationFailed(_) => None,
+        }
+    }
+}
+
+impl From<ProgramCreationError> for HotReloadError {
+    #[inline]
+    fn from(err: ProgramCreationError) -> HotReloadError {
+        HotReloadError::ProgramCreationError(err)
+    }
+}
+
+/// Identifies a program managed by a `HotReload`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HotReloadId(usize);
+
+/// Something that happened during `HotReload::update`.
+#[derive(Debug)]
+pub enum ReloadEvent {
+    /// The program has been rebuilt, and the new version is now returned by `HotReload::get`.
+    Reloaded(HotReloadId),
+
+    /// Failed to rebuild the program. The previous version is still used.
+    Failed(HotReloadId, HotReloadError),
+}
+
+/// Rebuilds programs whenever the source code of their shaders is modified.
+///
+/// Call `update` once per frame. It checks the source providers of each program, and starts
+/// rebuilding the programs whose source code has changed or that import a shader module that
+/// has been modified (see `Program::is_outdated`). A program stays in use until its new
+/// version has been successfully compiled, linked and validated with `Program::validate`, so
+/// that a typo in a shader doesn't break the application.
+///
+/// The programs are rebuilt with `Program::new_async`. If the backend supports
+/// `GL_KHR_parallel_shader_compile` or `GL_ARB_parallel_shader_compile`, they are compiled by
+/// the driver in the background. Otherwise they are compiled by the thread set with
+/// `Context::set_compiler_thread`. In both cases `update` never waits for them. If neither is
+/// available, the compilation happens during `update`.
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// use glium::program::{HotReload, HotReloadSources, ReloadEvent, SourceProvider};
+///
+/// let mut programs = HotReload::new(&display);
+/// let id = programs.add(HotReloadSources {
+///     vertex_shader: SourceProvider::path("shaders/model.vert"),
+///     fragment_shader: SourceProvider::path("shaders/model.frag"),
+///     geometry_shader: None,
+///     tessellation_control_shader: None,
+///     tessellation_evaluation_shader: None,
+/// }).unwrap();
+///
+/// // each frame
+/// for event in programs.update() {
+///     if let ReloadEvent::Failed(_, err) = event {
+///         println!("{}", err);
+///     }
+/// }
+///
+/// let program = programs.get(id);
+/// ```
+pub struct HotReload {
+    context: Rc<Context>,
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    /// The vertex, tessellation control, tessellation evaluation, geometry and fragment
+    /// shaders, in this order.
+    stages: [Option<Stage>; 5],
+    program: Program,
+    pending: Option<PendingProgram>,
+    /// False if the last rebuild of an outdated program failed, in which case we wait for the
+    /// source code to change instead of trying again every frame.
+    check_outdated: bool,
+}
+
+impl HotReload {
+    /// Builds an empty `HotReload`.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F) -> HotReload where F: Facade {
+        HotReload {
+            context: facade.get_context().clone(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Loads the source code of a program, builds it and validates it.
+    ///
+    /// The program is built immediately, since there is no previous version to use in
+    /// the meantime.
+    pub fn add(&mut self, sources: HotReloadSources) -> Result<HotReloadId, HotReloadError> {
+        let HotReloadSources { vertex_shader, tessellation_control_shader,
+                               tessellation_evaluation_shader, geometry_shader,
+                               fragment_shader } = sources;
+
+        let load = |provider: Option<SourceProvider>| provider.map(Stage::load).transpose();
+        let stages = [
+            load(Some(vertex_shader))?,
+            load(tessellation_control_shader)?,
+            load(tessellation_evaluation_shader)?,
+            load(geometry_shader)?,
+            load(Some(fragment_shader))?,
+        ];
+
+        let program = finish(build(&self.context, &stages))?;
+
+        self.entries.push(Entry {
+            stages,
+            program,
+            pending: None,
+            check_outdated: true,
+        });
+
+        Ok(HotReloadId(self.entries.len() - 1))
+    }
+
+    /// Returns the current version of a program.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if the id was returned by another `HotReload`.
+    #[inline]
+    pub fn get(&self, id: HotReloadId) -> &Program {
+        &self.entries[id.0].program
+    }
+
+    /// Returns true if a new version of the program is being built.
+    #[inline]
+    pub fn is_pending(&self, id: HotReloadId) -> bool {
+        self.entries[id.0].pending.is_some()
+    }
+
+    /// Starts rebuilding a program from its current source code, even if it hasn't changed.
+    pub fn reload(&mut self, id: HotReloadId) {
+        let entry = &mut self.entries[id.0];
+        entry.pending = Some(build(&self.context, &entry.stages));
+    }
+
+    /// Checks the source providers and the programs being built.
+    ///
+    /// Returns the programs that have been replaced and the errors that happened. If the source
+    /// code of a program changes while it is being built, the build is restarted.
+    ///
+    /// Files that can't be accessed are ignored until they exist again, since some text editors
+    /// delete the file before writing its new content.
+    pub fn update(&mut self) -> Vec<ReloadEvent> {
+        let mut events = Vec::new();
+
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            let id = HotReloadId(index);
+
+            let mut modified = false;
+            for stage in entry.stages.iter_mut().flatten() {
+                match stage.poll() {
+                    Ok(m) => modified |= m,
+                    Err(err) => events.push(ReloadEvent::Failed(id, err)),
+                }
+            }
+
+            if modified {
+                entry.check_outdated = true;
+            }
+
+            if modified || (entry.pending.is_none() && entry.check_outdated &&
+                            entry.program.is_outdated())
+            {
+                // dropping the previous pending program cancels it
+                entry.pending = Some(build(&self.context, &entry.stages));
+            }
+
+            if entry.pending.as_ref().is_some_and(|p| p.is_ready()) {
+                match finish(entry.pending.take().unwrap()) {
+                    Ok(program) => {
+                        entry.program = program;
+                        events.push(ReloadEvent::Reloaded(id));
+                    },
+                    Err(err) => {
+                        entry.check_outdated = false;
+                        events.push(ReloadEvent::Failed(id, err));
+                    },
+                }
+            }
+        }
+
+        events
+    }
+}
+
+impl fmt::Debug for HotReload {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(fmt, "HotReload({} programs)", self.entries.len())
+    }
+}
+
+/// Starts building a program from the current source code of the stages.
+fn build(context: &Rc<Context>, stages: &[Option<Stage>; 5]) -> PendingProgram {
+    let source = |index: usize| stages[index].as_ref().map(|s| &s.source[..]);
+
+    Program::new_async(context, SourceCode {
+        vertex_shader: source(0).unwrap(),
+        tessellation_control_shader: source(1),
+        tessellation_evaluation_shader: source(2),
+        geometry_shader: source(3),
+        fragment_shader: source(4).unwrap(),
+    })
+}
+
+/// Waits for a program to be built and validates it.
+fn finish(pending: PendingProgram) -> Result<Program, HotReloadError> {
+    let program = pending.finish()?;
+    program.validate().map_err(HotReloadError::ValidationFailed)?;
+    Ok(program)
+}
+
+/// A shader and its current source code.
+struct Stage {
+    provider: SourceProvider,
+    /// Modification time of the file when it was last read.
+    modified: Option<SystemTime>,
+    source: String,
+}
+
+impl Stage {
+    /// Loads the initial source code.
+    fn load(mut provider: SourceProvider) -> Result<Stage, HotReloadError> {
+        let (modified, source) = match provider {
+            SourceProvider::Path(ref path) => {
+                let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+                let source = fs::read_to_string(path).map_err(|error| HotReloadError::Io {
+                    path: path.clone(),
+                    error,
+                })?;
+                (modified, source)
+            },
+            SourceProvider::Callback(ref mut callback) => {
+                (None, callback().ok_or(HotReloadError::MissingSource)?)
+            },
+        };
+
+        Ok(Stage { provider, modified, source })
+    }
+
+    /// Updates the source code. Returns true if it has changed.
+    fn poll(&mut self) -> Result<bool, HotReloadError> {
+        let source = match self.provider {
+            SourceProvider::Path(ref path) => {
+                let modified = match fs::metadata(path).and_then(|m| m.modified()) {
+                    Ok(modified) => modified,
+                    Err(_) => return Ok(false),
+                };
+
+                if self.modified == Some(modified) {
+                    return Ok(false);
+                }
+
+                let source = fs::read_to_string(path).map_err(|error| HotReloadError::Io {
+                    path: path.clone(),
+                    error,
+                })?;
+                self.modified = Some(modified);
+                source
+            },
+            SourceProvider::Callback(ref mut callback) => match callback() {
+                Some(source) => source,
+                None => return Ok(false),
+            },
+        };
+
+        if source == self.source {
+            return Ok(false);
+        }
+
+        self.source = source;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::fs;
+    use std::rc::Rc;
+
+    use super::{HotReloadError, SourceProvider, Stage};
+
+    #[test]
+    fn callback_polling() {
+        let next = Rc::new(RefCell::new(Some("a".to_owned())));
+        let provider = {
+            let next = next.clone();
+            SourceProvider::callback(move || next.borrow_mut().take())
+        };
+
+        let mut stage = Stage::load(provider).unwrap();
+        assert_eq!(stage.source, "a");
+        assert!(!stage.poll().unwrap());
+
+        *next.borrow_mut() = Some("a".to_owned());
+        assert!(!stage.poll().unwrap());
+
+        *next.borrow_mut() = Some("b".to_owned());
+        assert!(stage.poll().unwrap());
+        assert_eq!(stage.source, "b");
+
+        match Stage::load(SourceProvider::callback(|| None)) {
+            Err(HotReloadError::MissingSource) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn file_polling() {
+        let path = std::env::temp_dir().join(format!("glium-hot-reload-{}.glsl",
+                                                      std::process::id()));
+        fs::write(&path, "a").unwrap();
+
+        let mut stage = Stage::load(SourceProvider::path(&path)).unwrap();
+        assert_eq!(stage.source, "a");
+        assert!(!stage.poll().unwrap());
+
+        // forcing the modification time to be different, whatever the precision of the
+        // file system
+        fs::write(&path, "b").unwrap();
+        stage.modified = None;
+        assert!(stage.poll().unwrap());
+        assert_eq!(stage.source, "b");
+
+        fs::remove_file(&path).unwrap();
+        assert!(!stage.poll().unwrap());
+
+        match Stage::load(SourceProvider::path(&path)) {
+            Err(HotReloadError::Io { .. }) => (),
+            _ => panic!(),
+        }
+    }
+}
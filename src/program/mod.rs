@@ -9,13 +9,28 @@ use crate::gl;
 use crate::version::Api;
 use crate::version::Version;
 
+pub use self::cache::ProgramCache;
+pub use self::compiler_thread::CompilerThread;
 pub use self::compute::{ComputeShader, ComputeCommand};
-pub use self::program::Program;
-pub use self::reflection::{Uniform, UniformBlock, BlockLayout, OutputPrimitives};
+pub use self::pipeline::{SeparableProgram, ProgramPipeline, PipelineStages, ProgramRef};
+pub use self::pipeline::ProgramPipelineCreationError;
+pub use self::program::{Program, PendingProgram, DetachedProgram};
+pub use self::reflection::{Uniform, UniformBlock, BlockLayout, BlockVariable, OutputPrimitives};
 pub use self::reflection::{Attribute, TransformFeedbackVarying, TransformFeedbackBuffer, TransformFeedbackMode};
 pub use self::reflection::{ShaderStage, SubroutineData, SubroutineUniform};
-
+pub use self::reflection::{FragmentOutput, ProgramReflection};
+pub use self::modules::{ShaderModuleRegistry, ShaderModuleDependencies, ShaderModuleError};
+pub use self::preprocessor::{ShaderPreprocessor, PreprocessedSource, IncludeError};
+pub use self::hot_reload::{HotReload, HotReloadSources, HotReloadId, HotReloadError};
+pub use self::hot_reload::{SourceProvider, ReloadEvent};
+
+mod cache;
+mod compiler_thread;
 mod compute;
+mod hot_reload;
+mod modules;
+mod pipeline;
+mod preprocessor;
 mod program;
 mod raw;
 mod reflection;
@@ -35,6 +50,14 @@ pub fn is_tessellation_shader_supported<C: ?Sized>(ctxt: &C) -> bool where C: Ca
     shader::check_shader_type_compatibility(ctxt, gl::TESS_CONTROL_SHADER)
 }
 
+/// Returns true if the backend supports mesh and task shaders.
+///
+/// See `ProgramCreationInput::MeshSourceCode` and `Surface::draw_mesh_tasks`.
+#[inline]
+pub fn is_mesh_shader_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
+    shader::check_shader_type_compatibility(ctxt, gl::MESH_SHADER_NV)
+}
+
 /// Returns true if the backend supports creating and retrieving binary format.
 #[inline]
 pub fn is_binary_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
@@ -42,6 +65,27 @@ pub fn is_binary_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSou
         || ctxt.get_extensions().gl_arb_get_programy_binary
 }
 
+/// Returns true if the backend can compile programs in the background.
+///
+/// See `Program::new_async`.
+#[inline]
+pub fn is_parallel_compile_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
+    ctxt.get_extensions().gl_khr_parallel_shader_compile ||
+        ctxt.get_extensions().gl_arb_parallel_shader_compile
+}
+
+/// Returns true if the backend supports separable programs and program pipelines.
+///
+/// See `SeparableProgram` and `ProgramPipeline`.
+#[inline]
+pub fn is_separate_shader_objects_supported<C: ?Sized>(ctxt: &C) -> bool
+    where C: CapabilitiesSource
+{
+    ctxt.get_version() >= &Version(Api::Gl, 4, 1) ||
+        ctxt.get_version() >= &Version(Api::GlEs, 3, 1) ||
+        ctxt.get_extensions().gl_arb_separate_shader_objects
+}
+
 /// Returns true if the backend supports shader subroutines.
 #[inline]
 pub fn is_subroutine_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
@@ -55,6 +99,164 @@ pub fn is_subroutine_supported<C: ?Sized>(ctxt: &C) -> bool where C: Capabilitie
     ctxt.get_version() >= &Version(Api::Gl, 4, 0) || ctxt.get_extensions().gl_arb_shader_subroutine
 }
 
+/// Returns true if the backend supports the `gl_DrawID`, `gl_BaseVertex` and `gl_BaseInstance`
+/// shader inputs.
+///
+/// Unless you're using GLSL 4.60, these inputs are named `gl_DrawIDARB`, `gl_BaseVertexARB`
+/// and `gl_BaseInstanceARB` and the shader must enable the `GL_ARB_shader_draw_parameters`
+/// extension. See `enable_shader_draw_parameters`.
+#[inline]
+pub fn is_shader_draw_parameters_supported<C: ?Sized>(ctxt: &C) -> bool
+    where C: CapabilitiesSource
+{
+    ctxt.get_version() >= &Version(Api::Gl, 4, 6) ||
+        ctxt.get_extensions().gl_arb_shader_draw_parameters
+}
+
+/// Inserts an `#extension <name> : require` directive in the source code of a shader.
+///
+/// The directive is inserted right after the `#version` directive, or at the beginning of the
+/// source code if there is none.
+pub fn add_extension_directive(source: &str, extension: &str) -> String {
+    let directive = format!("#extension {} : require\n", extension);
+
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        offset += line.len();
+
+        if line.trim_start().starts_with("#version") {
+            let mut result = String::with_capacity(source.len() + directive.len());
+            result.push_str(&source[.. offset]);
+            if !line.ends_with('\n') {
+                result.push('\n');
+            }
+            result.push_str(&directive);
+            result.push_str(&source[offset ..]);
+            return result;
+        }
+    }
+
+    directive + source
+}
+
+/// Enables the `GL_ARB_shader_draw_parameters` extension in the source code of a shader.
+///
+/// This gives access to `gl_DrawIDARB`, which contains the index of the current draw command
+/// when drawing with multiple draw commands at once (for example with a
+/// `DrawCommandsIndicesBuffer`). It can be used to look up per-draw data in a buffer.
+///
+/// Returns `None` if the backend doesn't support it.
+///
+/// ## Example
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// let vertex_shader = glium::program::enable_shader_draw_parameters(&display, "
+///     #version 430
+///
+///     struct PerDraw {
+///         mat4 matrix;
+///     };
+///
+///     buffer PerDrawData {
+///         PerDraw per_draw[];
+///     };
+///
+///     in vec3 position;
+///
+///     void main() {
+///         gl_Position = per_draw[gl_DrawIDARB].matrix * vec4(position, 1.0);
+///     }
+/// ").expect("gl_DrawID is not supported");
+/// ```
+pub fn enable_shader_draw_parameters<C: ?Sized>(ctxt: &C, source: &str) -> Option<String>
+    where C: CapabilitiesSource
+{
+    if !ctxt.get_extensions().gl_arb_shader_draw_parameters {
+        // OpenGL 4.6 contexts always support GLSL 4.60, where `gl_DrawID` is a regular input,
+        // but we can't use the `ARB` names without the extension
+        return None;
+    }
+
+    Some(add_extension_directive(source, "GL_ARB_shader_draw_parameters"))
+}
+
+/// Returns the number of views declared by a `layout(num_views = N) in;` qualifier in the source
+/// code of a vertex shader, or 1 if there is none.
+///
+/// The comments are ignored, and so are the `num_views` that are not part of the input layout
+/// qualifiers of the shader.
+fn parse_num_views(source: &str) -> u32 {
+    let source = strip_comments(source);
+
+    for (offset, _) in source.match_indices("layout") {
+        // `layout` must not be the end of another identifier
+        if source[.. offset].ends_with(|c: char| c.is_ascii_alphanumeric() || c == '_') {
+            continue;
+        }
+
+        let rest = source[offset + "layout".len() ..].trim_start();
+        let rest = match rest.strip_prefix('(') {
+            Some(rest) => rest,
+            None => continue,
+        };
+        let (qualifiers, rest) = match rest.find(')') {
+            Some(end) => (&rest[.. end], &rest[end + 1 ..]),
+            None => continue,
+        };
+
+        // only `layout(...) in;` declares the number of views
+        match rest.trim_start().strip_prefix("in") {
+            Some(rest) if rest.trim_start().starts_with(';') => (),
+            _ => continue,
+        }
+
+        for qualifier in qualifiers.split(',') {
+            let mut qualifier = qualifier.splitn(2, '=');
+            if qualifier.next().map(str::trim) != Some("num_views") {
+                continue;
+            }
+
+            if let Some(Ok(num_views)) = qualifier.next().map(|value| value.trim().parse()) {
+                return num_views;
+            }
+        }
+    }
+
+    1
+}
+
+/// Replaces the comments of GLSL source code with spaces.
+fn strip_comments(source: &str) -> String {
+    let mut result = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('/', Some('/')) => {
+                while chars.peek().map_or(false, |&c| c != '\n') {
+                    chars.next();
+                }
+                result.push(' ');
+            },
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                result.push(' ');
+            },
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
 // Some shader compilers have race-condition issues, so we lock this mutex
 // in the GL thread every time we compile a shader or link a program.
 // TODO: replace by a StaticMutex
@@ -77,6 +279,10 @@ pub enum ShaderType {
     TesselationEvaluation,
     /// Compute shader, maps to gl::COMPUTE_SHADER
     Compute,
+    /// Task shader, maps to gl::TASK_SHADER_NV (same value as `GL_TASK_SHADER_EXT`)
+    Task,
+    /// Mesh shader, maps to gl::MESH_SHADER_NV (same value as `GL_MESH_SHADER_EXT`)
+    Mesh,
 }
 
 impl ShaderType {
@@ -89,6 +295,8 @@ impl ShaderType {
             ShaderType::TesselationControl => gl::TESS_CONTROL_SHADER,
             ShaderType::TesselationEvaluation => gl::TESS_EVALUATION_SHADER,
             ShaderType::Compute => gl::COMPUTE_SHADER,
+            ShaderType::Task => gl::TASK_SHADER_NV,
+            ShaderType::Mesh => gl::MESH_SHADER_NV,
         }
     }
     /// Creates an instance of ShaderType corresponding to the given gl::types::GLenum.
@@ -101,6 +309,8 @@ impl ShaderType {
             gl::TESS_CONTROL_SHADER => ShaderType::TesselationControl,
             gl::TESS_EVALUATION_SHADER => ShaderType::TesselationEvaluation,
             gl::COMPUTE_SHADER  => ShaderType::Compute,
+            gl::TASK_SHADER_NV => ShaderType::Task,
+            gl::MESH_SHADER_NV => ShaderType::Mesh,
             _ => {
                 panic!("Unsupported shader type")
             }
@@ -135,6 +345,13 @@ pub enum ProgramCreationError {
 
     /// The glium-specific binary header was not found or is corrupt.
     BinaryHeaderError,
+
+    /// Error while replacing the `#import` directives with the content of the shader modules.
+    ShaderModuleError(ShaderModuleError),
+
+    /// You have requested a separable program, but separate shader objects are not supported
+    /// by the backend.
+    SeparableProgramNotSupported,
 }
 
 impl fmt::Display for ProgramCreationError {
@@ -148,7 +365,9 @@ impl fmt::Display for ProgramCreationError {
                     ShaderType::Fragment => "Compilation error in fragment shader",
                     ShaderType::TesselationControl => "Compilation error in tesselation control shader",
                     ShaderType::TesselationEvaluation => "Compilation error in tesselation evaluation shader",
-                    ShaderType::Compute => "Compilation error in compute shader"
+                    ShaderType::Compute => "Compilation error in compute shader",
+                    ShaderType::Task => "Compilation error in task shader",
+                    ShaderType::Mesh => "Compilation error in mesh shader",
                 }
             },
             LinkingError(_) =>
@@ -163,12 +382,18 @@ impl fmt::Display for ProgramCreationError {
                 "Point size is not supported by the backend.",
             BinaryHeaderError =>
                 "The glium-specific binary header was not found or is corrupt.",
+            ShaderModuleError(_) =>
+                "Error while importing the shader modules",
+            SeparableProgramNotSupported =>
+                "Separable programs are not supported by the backend",
         };
         match *self {
             CompilationError(ref s, _) =>
                 write!(fmt, "{}: {}", desc, s),
             LinkingError(ref s) =>
                 write!(fmt, "{}: {}", desc, s),
+            ShaderModuleError(ref err) =>
+                write!(fmt, "{}: {}", desc, err),
             _ =>
                 write!(fmt, "{}", desc),
         }
@@ -273,6 +498,28 @@ pub enum ProgramCreationInput<'a> {
         uses_point_size: bool,
     },
 
+    /// Use the GLSL source code of a mesh shader pipeline.
+    ///
+    /// The mesh shader and the optional task shader replace the vertex, tessellation and
+    /// geometry stages. Programs built this way don't have any vertex attribute, and can
+    /// only be drawn with `Surface::draw_mesh_tasks`.
+    ///
+    /// Requires `GL_NV_mesh_shader` or `GL_EXT_mesh_shader`. The shaders must enable the
+    /// corresponding GLSL extension. See `is_mesh_shader_supported`.
+    MeshSourceCode {
+        /// Source code of the optional task shader.
+        task_shader: Option<&'a str>,
+
+        /// Source code of the mesh shader.
+        mesh_shader: &'a str,
+
+        /// Source code of the fragment shader.
+        fragment_shader: &'a str,
+
+        /// See `SourceCode::outputs_srgb`.
+        outputs_srgb: bool,
+    },
+
     /// Use a precompiled binary.
     Binary {
         /// The data.
@@ -342,3 +589,47 @@ impl<'a> From<Binary> for ProgramCreationInput<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{add_extension_directive, parse_num_views};
+
+    #[test]
+    fn extension_directive_after_version() {
+        let source = "\n    #version 330\n    void main() {}";
+        assert_eq!(add_extension_directive(source, "GL_ARB_foo"),
+                   "\n    #version 330\n#extension GL_ARB_foo : require\n    void main() {}");
+    }
+
+    #[test]
+    fn extension_directive_without_version() {
+        assert_eq!(add_extension_directive("void main() {}", "GL_ARB_foo"),
+                   "#extension GL_ARB_foo : require\nvoid main() {}");
+    }
+
+    #[test]
+    fn extension_directive_version_last_line() {
+        assert_eq!(add_extension_directive("#version 330", "GL_ARB_foo"),
+                   "#version 330\n#extension GL_ARB_foo : require\n");
+    }
+
+    #[test]
+    fn num_views() {
+        let source = "#version 300 es\n#extension GL_OVR_multiview2 : require\n\
+                      layout(num_views = 2) in;\nvoid main() {}";
+        assert_eq!(parse_num_views(source), 2);
+        assert_eq!(parse_num_views("layout(num_views=4)in;"), 4);
+        assert_eq!(parse_num_views("#version 330\nvoid main() {}"), 1);
+    }
+
+    #[test]
+    fn num_views_ignores_comments_and_other_declarations() {
+        assert_eq!(parse_num_views("// layout(num_views = 2) in;\nvoid main() {}"), 1);
+        assert_eq!(parse_num_views("/* layout(num_views = 2) in; */ void main() {}"), 1);
+        assert_eq!(parse_num_views("uniform int num_views = 2;\nvoid main() {}"), 1);
+        assert_eq!(parse_num_views("layout(num_views = 2) out;"), 1);
+        assert_eq!(parse_num_views("layout(location = 0) in vec2 num_views;"), 1);
+        assert_eq!(parse_num_views("// num_views = 2\nlayout(num_views = 3) in;"), 3);
+        assert_eq!(parse_num_views("/* * / */layout /* views */ (num_views = 4)\nin ;"), 4);
+    }
+}
@@ -0,0 +1,341 @@
+//! Registry of GLSL chunks that are shared between programs.
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// Error that can happen when resolving the `#import` directives of a shader.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShaderModuleError {
+    /// A shader imports a module that hasn't been registered.
+    UnknownModule(String),
+
+    /// A module imports itself, directly or through another module.
+    CyclicImport(String),
+}
+
+impl fmt::Display for ShaderModuleError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match *self {
+            ShaderModuleError::UnknownModule(ref name) =>
+                write!(fmt, "The shader module `{}` has not been registered", name),
+            ShaderModuleError::CyclicImport(ref name) =>
+                write!(fmt, "The shader module `{}` imports itself", name),
+        }
+    }
+}
+
+impl Error for ShaderModuleError {}
+
+/// List of the modules, and their versions, that have been inserted in the source code of
+/// a program.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShaderModuleDependencies {
+    modules: Vec<(String, u64)>,
+}
+
+impl ShaderModuleDependencies {
+    /// Returns true if no module has been used.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    /// Returns true if the module with the given name has been used.
+    #[inline]
+    pub fn depends_on(&self, name: &str) -> bool {
+        self.modules.iter().any(|&(ref n, _)| n == name)
+    }
+
+    /// Returns the name and the version of each module that has been used.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.modules.iter().map(|&(ref name, version)| (&name[..], version))
+    }
+
+    pub(crate) fn extend(&mut self, other: ShaderModuleDependencies) {
+        for module in other.modules {
+            if !self.modules.contains(&module) {
+                self.modules.push(module);
+            }
+        }
+    }
+}
+
+struct ShaderModule {
+    source: String,
+    version: u64,
+    imports: Vec<String>,
+}
+
+/// Named and versioned GLSL chunks that can be imported by the shaders of any program created
+/// with the context.
+///
+/// Chunks of GLSL code that are used by many programs, like lighting or tonemapping functions, can
+/// be registered once in the `ShaderModuleRegistry` of the context under a name. The source code
+/// of a shader can then reference them with an `#import` directive, which is replaced with the
+/// content of the module when the program is created.
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// # let vertex_source = "";
+/// let registry = display.get_shader_module_registry();
+/// registry.register("tonemapping", "
+///     vec3 tonemap(vec3 color) {
+///         return color / (color + vec3(1.0));
+///     }
+/// ");
+///
+/// let program = glium::Program::from_source(&display, vertex_source, "
+///     #version 140
+///
+///     #import tonemapping
+///
+///     in vec3 v_color;
+///     out vec4 f_color;
+///
+///     void main() {
+///         f_color = vec4(tonemap(v_color), 1.0);
+///     }
+/// ", None).unwrap();
+///
+/// // registering a module again replaces its content and makes the programs that use it outdated
+/// registry.register("tonemapping", "
+///     vec3 tonemap(vec3 color) {
+///         return clamp(color, 0.0, 1.0);
+///     }
+/// ");
+/// assert!(program.is_outdated());
+/// ```
+///
+/// Modules can import other modules. Each module is inserted only once per shader, even if it is
+/// imported multiple times. When a module changes, all the programs that depend on it, directly or
+/// through another module, are considered outdated.
+pub struct ShaderModuleRegistry {
+    modules: RefCell<HashMap<String, ShaderModule>>,
+
+    // Versions are never reused, even after a module has been unregistered.
+    next_version: Cell<u64>,
+}
+
+impl ShaderModuleRegistry {
+    /// Builds an empty registry.
+    #[inline]
+    pub fn new() -> ShaderModuleRegistry {
+        ShaderModuleRegistry {
+            modules: RefCell::new(HashMap::new()),
+            next_version: Cell::new(1),
+        }
+    }
+
+    /// Registers a module, or replaces the content of an existing one.
+    ///
+    /// Returns the new version of the module. Programs that have been created with a previous
+    /// version of this module, or of a module importing it, become outdated.
+    pub fn register(&self, name: &str, source: &str) -> u64 {
+        let version = self.next_version.get();
+        self.next_version.set(version + 1);
+
+        let imports = parse_imports(source).map(|(_, _, name)| name.to_owned()).collect();
+
+        self.modules.borrow_mut().insert(name.to_owned(), ShaderModule {
+            source: source.to_owned(),
+            version,
+            imports,
+        });
+
+        version
+    }
+
+    /// Removes a module from the registry. Returns false if it didn't exist.
+    #[inline]
+    pub fn unregister(&self, name: &str) -> bool {
+        self.modules.borrow_mut().remove(name).is_some()
+    }
+
+    /// Returns true if a module with this name has been registered.
+    #[inline]
+    pub fn contains(&self, name: &str) -> bool {
+        self.modules.borrow().contains_key(name)
+    }
+
+    /// Returns the current version of a module.
+    #[inline]
+    pub fn get_version(&self, name: &str) -> Option<u64> {
+        self.modules.borrow().get(name).map(|m| m.version)
+    }
+
+    /// Returns the source code of a module.
+    #[inline]
+    pub fn get_source(&self, name: &str) -> Option<String> {
+        self.modules.borrow().get(name).map(|m| m.source.clone())
+    }
+
+    /// Returns the names of all the modules that import the given module, directly or through
+    /// another module.
+    pub fn get_dependent_modules(&self, name: &str) -> Vec<String> {
+        let modules = self.modules.borrow();
+        let mut result: Vec<String> = Vec::new();
+        let mut to_visit = vec![name.to_owned()];
+
+        while let Some(current) = to_visit.pop() {
+            for (module_name, module) in modules.iter() {
+                if module.imports.iter().any(|i| *i == current) && module_name != name &&
+                   !result.contains(module_name)
+                {
+                    result.push(module_name.clone());
+                    to_visit.push(module_name.clone());
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Returns true if all the modules of the list still exist and have the same version.
+    pub fn is_up_to_date(&self, dependencies: &ShaderModuleDependencies) -> bool {
+        let modules = self.modules.borrow();
+        dependencies.modules.iter().all(|&(ref name, version)| {
+            modules.get(name).map(|m| m.version == version).unwrap_or(false)
+        })
+    }
+
+    /// Replaces all the `#import` directives of a source code with the content of the
+    /// corresponding modules.
+    ///
+    /// Returns the new source code and the list of modules that have been used. The source
+    /// code is returned unchanged if it doesn't contain any `#import` directive.
+    pub fn resolve(&self, source: &str)
+                   -> Result<(String, ShaderModuleDependencies), ShaderModuleError>
+    {
+        let mut dependencies = ShaderModuleDependencies::default();
+        let mut stack = Vec::new();
+        let result = self.resolve_impl(source, &mut dependencies, &mut stack)?;
+        Ok((result, dependencies))
+    }
+
+    fn resolve_impl(&self, source: &str, dependencies: &mut ShaderModuleDependencies,
+                    stack: &mut Vec<String>) -> Result<String, ShaderModuleError>
+    {
+        let mut result = String::with_capacity(source.len());
+        let mut last = 0;
+
+        for (start, end, name) in parse_imports(source) {
+            result.push_str(&source[last .. start]);
+            last = end;
+
+            if stack.iter().any(|n| n == name) {
+                return Err(ShaderModuleError::CyclicImport(name.to_owned()));
+            }
+
+            if dependencies.depends_on(name) {
+                // already inserted
+                continue;
+            }
+
+            let (module_source, version) = {
+                let modules = self.modules.borrow();
+                match modules.get(name) {
+                    Some(m) => (m.source.clone(), m.version),
+                    None => return Err(ShaderModuleError::UnknownModule(name.to_owned())),
+                }
+            };
+
+            stack.push(name.to_owned());
+            let mut inner = ShaderModuleDependencies::default();
+            inner.modules.push((name.to_owned(), version));
+            inner.extend(dependencies.clone());
+            let resolved = self.resolve_impl(&module_source, &mut inner, stack)?;
+            stack.pop();
+
+            dependencies.extend(inner);
+            result.push_str(&resolved);
+            if !resolved.ends_with('\n') {
+                result.push('\n');
+            }
+        }
+
+        result.push_str(&source[last ..]);
+        Ok(result)
+    }
+}
+
+impl Default for ShaderModuleRegistry {
+    #[inline]
+    fn default() -> ShaderModuleRegistry {
+        ShaderModuleRegistry::new()
+    }
+}
+
+impl fmt::Debug for ShaderModuleRegistry {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let modules = self.modules.borrow();
+        fmt.debug_map().entries(modules.iter().map(|(name, m)| (name, m.version))).finish()
+    }
+}
+
+/// Returns the byte range of each line containing an `#import` directive, and the name that
+/// is imported. The name can optionally be surrounded by double quotes.
+fn parse_imports(source: &str) -> impl Iterator<Item = (usize, usize, &str)> {
+    let mut offset = 0;
+
+    source.split_inclusive('\n').filter_map(move |line| {
+        let start = offset;
+        offset += line.len();
+
+        let directive = line.trim();
+        if !directive.starts_with("#import") {
+            return None;
+        }
+
+        let name = directive["#import".len() ..].trim();
+        let name = name.trim_start_matches('"').trim_end_matches('"').trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        Some((start, offset, name))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ShaderModuleRegistry, ShaderModuleError};
+
+    #[test]
+    fn resolve_nested() {
+        let registry = ShaderModuleRegistry::new();
+        registry.register("a", "float a() { return 1.0; }");
+        registry.register("b", "#import a\nfloat b() { return a(); }\n");
+
+        let (source, deps) = registry.resolve("#version 110\n#import \"b\"\n#import a\nvoid main() {}\n").unwrap();
+        assert_eq!(source, "#version 110\nfloat a() { return 1.0; }\nfloat b() { return a(); }\nvoid main() {}\n");
+        assert!(deps.depends_on("a"));
+        assert!(deps.depends_on("b"));
+        assert!(registry.is_up_to_date(&deps));
+
+        registry.register("a", "float a() { return 2.0; }");
+        assert!(!registry.is_up_to_date(&deps));
+        assert_eq!(registry.get_dependent_modules("a"), vec!["b".to_owned()]);
+    }
+
+    #[test]
+    fn resolve_errors() {
+        let registry = ShaderModuleRegistry::new();
+        assert_eq!(registry.resolve("#import missing\n").unwrap_err(),
+                   ShaderModuleError::UnknownModule("missing".to_owned()));
+
+        registry.register("a", "#import b\n");
+        registry.register("b", "#import a\n");
+        assert_eq!(registry.resolve("#import a\n").unwrap_err(),
+                   ShaderModuleError::CyclicImport("a".to_owned()));
+    }
+
+    #[test]
+    fn no_imports() {
+        let registry = ShaderModuleRegistry::new();
+        let (source, deps) = registry.resolve("#version 110\nvoid main() {}").unwrap();
+        assert_eq!(source, "#version 110\nvoid main() {}");
+        assert!(deps.is_empty());
+    }
+}
@@ -0,0 +1,337 @@
+/*!
+
+Separable programs and program pipelines.
+
+Linking a program for each combination of vertex and fragment shaders that you use quickly
+becomes expensive. With separate shader objects, each stage is linked on its own in a
+`SeparableProgram`, and a `ProgramPipeline` combines stages that have been linked separately.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+# let vertex_source = ""; let fragment_source = "";
+use glium::program::{SeparableProgram, ProgramPipeline, PipelineStages, ShaderType};
+
+let vertex = SeparableProgram::new(&display, ShaderType::Vertex, vertex_source).unwrap();
+let fragment = SeparableProgram::new(&display, ShaderType::Fragment, fragment_source).unwrap();
+
+let pipeline = ProgramPipeline::new(&display, PipelineStages {
+    vertex: &vertex,
+    tessellation_control: None,
+    tessellation_evaluation: None,
+    geometry: None,
+    fragment: &fragment,
+}).unwrap();
+
+// `&pipeline` can now be passed to `Surface::draw` instead of a `&Program`
+```
+
+The outputs of a stage are matched with the inputs of the next stage when drawing, and not
+when the pipeline is created. It is recommended to use explicit locations for them
+(`layout(location = 0) out vec3 v_normal;`), otherwise they are matched by name and
+the result is undefined if they don't match.
+
+The uniforms passed to `draw` are set on each stage that uses them, including subroutine
+uniforms.
+
+*/
+use std::fmt;
+use std::error::Error;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use smallvec::SmallVec;
+
+use crate::gl;
+use crate::GlObject;
+use crate::Handle;
+
+use crate::backend::Facade;
+use crate::context::{CommandContext, Context};
+use crate::ContextExt;
+
+use crate::program::{Program, ProgramCreationError, ShaderType};
+use crate::program::is_separate_shader_objects_supported;
+
+/// A program that contains a single stage, and that can be combined with other stages in a
+/// `ProgramPipeline`.
+///
+/// A `SeparableProgram` dereferences to a `Program`, which gives access to its uniforms,
+/// attributes and other reflection data.
+pub struct SeparableProgram {
+    program: Program,
+    ty: ShaderType,
+}
+
+impl SeparableProgram {
+    /// Compiles and links a stage from its source code.
+    ///
+    /// The `#import` directives are resolved with the shader module registry of the context,
+    /// like with `Program::new`.
+    ///
+    /// Returns `ShaderTypeNotSupported` if `ty` is `ShaderType::Compute`, or if the backend
+    /// doesn't support this stage.
+    pub fn new<F: ?Sized>(facade: &F, ty: ShaderType, source: &str)
+                          -> Result<SeparableProgram, ProgramCreationError> where F: Facade
+    {
+        if ty == ShaderType::Compute {
+            return Err(ProgramCreationError::ShaderTypeNotSupported);
+        }
+
+        Ok(SeparableProgram {
+            program: Program::new_separable(facade, ty, source)?,
+            ty,
+        })
+    }
+
+    /// Returns the stage that this program contains.
+    #[inline]
+    pub fn get_shader_type(&self) -> ShaderType {
+        self.ty
+    }
+}
+
+impl Deref for SeparableProgram {
+    type Target = Program;
+
+    #[inline]
+    fn deref(&self) -> &Program {
+        &self.program
+    }
+}
+
+impl fmt::Debug for SeparableProgram {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(fmt, "SeparableProgram({:?}, {:?})", self.ty, self.program)
+    }
+}
+
+/// The stages of a `ProgramPipeline`.
+#[derive(Copy, Clone, Debug)]
+pub struct PipelineStages<'a> {
+    /// The vertex stage. Its type must be `ShaderType::Vertex`.
+    pub vertex: &'a SeparableProgram,
+
+    /// The tessellation control stage, if any.
+    pub tessellation_control: Option<&'a SeparableProgram>,
+
+    /// The tessellation evaluation stage, if any.
+    pub tessellation_evaluation: Option<&'a SeparableProgram>,
+
+    /// The geometry stage, if any.
+    pub geometry: Option<&'a SeparableProgram>,
+
+    /// The fragment stage. Its type must be `ShaderType::Fragment`.
+    pub fragment: &'a SeparableProgram,
+}
+
+/// Error that can happen while creating a `ProgramPipeline`.
+#[derive(Copy, Clone, Debug)]
+pub enum ProgramPipelineCreationError {
+    /// Program pipelines are not supported by the backend.
+    NotSupported,
+
+    /// One of the programs was put in a stage that it doesn't contain.
+    WrongShaderType {
+        /// The stage that the program was used for.
+        expected: ShaderType,
+        /// The stage that the program contains.
+        obtained: ShaderType,
+    },
+}
+
+impl fmt::Display for ProgramPipelineCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::ProgramPipelineCreationError::*;
+        match *self {
+            NotSupported =>
+                write!(fmt, "Program pipelines are not supported by the backend"),
+            WrongShaderType { expected, obtained } =>
+                write!(fmt, "A program containing a {:?} shader was used as the {:?} stage \
+                             of a pipeline", obtained, expected),
+        }
+    }
+}
+
+impl Error for ProgramPipelineCreationError {}
+
+/// A combination of separable programs, one for each stage.
+///
+/// Creating a pipeline is cheap compared to linking a program, since the stages have already
+/// been linked.
+pub struct ProgramPipeline<'a> {
+    context: Rc<Context>,
+    id: gl::types::GLuint,
+    stages: PipelineStages<'a>,
+}
+
+impl<'a> ProgramPipeline<'a> {
+    /// Builds a new pipeline from its stages.
+    pub fn new<F: ?Sized>(facade: &F, stages: PipelineStages<'a>)
+                          -> Result<ProgramPipeline<'a>, ProgramPipelineCreationError>
+                          where F: Facade
+    {
+        let context = facade.get_context();
+
+        if !is_separate_shader_objects_supported(&**context) {
+            return Err(ProgramPipelineCreationError::NotSupported);
+        }
+
+        let list = [
+            (Some(stages.vertex), ShaderType::Vertex, gl::VERTEX_SHADER_BIT),
+            (stages.tessellation_control, ShaderType::TesselationControl,
+             gl::TESS_CONTROL_SHADER_BIT),
+            (stages.tessellation_evaluation, ShaderType::TesselationEvaluation,
+             gl::TESS_EVALUATION_SHADER_BIT),
+            (stages.geometry, ShaderType::Geometry, gl::GEOMETRY_SHADER_BIT),
+            (Some(stages.fragment), ShaderType::Fragment, gl::FRAGMENT_SHADER_BIT),
+        ];
+
+        for &(program, expected, _) in list.iter() {
+            if let Some(program) = program {
+                if program.get_shader_type() != expected {
+                    return Err(ProgramPipelineCreationError::WrongShaderType {
+                        expected,
+                        obtained: program.get_shader_type(),
+                    });
+                }
+            }
+        }
+
+        let ctxt = context.make_current();
+
+        let id = unsafe {
+            let mut id = 0;
+            ctxt.gl.GenProgramPipelines(1, &mut id);
+
+            for &(program, _, bit) in list.iter() {
+                if let Some(program) = program {
+                    let program_id = match program.get_id() {
+                        Handle::Id(id) => id,
+                        Handle::Handle(_) => unreachable!(),
+                    };
+
+                    ctxt.gl.UseProgramStages(id, bit, program_id);
+                }
+            }
+
+            id
+        };
+
+        Ok(ProgramPipeline {
+            context: context.clone(),
+            id,
+            stages,
+        })
+    }
+
+    /// Returns the stages of this pipeline.
+    #[inline]
+    pub fn get_stages(&self) -> &PipelineStages<'a> {
+        &self.stages
+    }
+
+    /// Returns the programs of the stages, in the order of the pipeline.
+    pub(crate) fn get_programs(&self) -> SmallVec<[&Program; 5]> {
+        let mut programs = SmallVec::new();
+        programs.push(&**self.stages.vertex);
+        programs.extend(self.stages.tessellation_control.map(|p| &**p));
+        programs.extend(self.stages.tessellation_evaluation.map(|p| &**p));
+        programs.extend(self.stages.geometry.map(|p| &**p));
+        programs.push(&**self.stages.fragment);
+        programs
+    }
+
+    /// Unbinds the current program and binds this pipeline.
+    pub(crate) fn bind(&self, ctxt: &mut CommandContext<'_>) {
+        unsafe {
+            // the pipeline is ignored as long as a program is current
+            if ctxt.state.program != Handle::Id(0) {
+                ctxt.gl.UseProgram(0);
+                ctxt.state.program = Handle::Id(0);
+            }
+
+            if ctxt.state.program_pipeline != self.id {
+                ctxt.gl.BindProgramPipeline(self.id);
+                ctxt.state.program_pipeline = self.id;
+            }
+        }
+    }
+}
+
+impl<'a> fmt::Debug for ProgramPipeline<'a> {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(fmt, "ProgramPipeline #{}", self.id)
+    }
+}
+
+impl<'a> GlObject for ProgramPipeline<'a> {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+impl<'a> Drop for ProgramPipeline<'a> {
+    fn drop(&mut self) {
+        let mut ctxt = self.context.make_current();
+
+        unsafe {
+            if ctxt.state.program_pipeline == self.id {
+                ctxt.state.program_pipeline = 0;
+            }
+
+            ctxt.gl.DeleteProgramPipelines(1, &self.id);
+        }
+    }
+}
+
+/// The program to use for a draw command: either a `Program` or a `ProgramPipeline`.
+///
+/// You don't need to build this object yourself. `Surface::draw` accepts both `&Program`
+/// and `&ProgramPipeline`.
+#[derive(Copy, Clone, Debug)]
+pub enum ProgramRef<'a> {
+    /// A program that contains all the stages.
+    Program(&'a Program),
+
+    /// A combination of separable programs.
+    Pipeline(&'a ProgramPipeline<'a>),
+}
+
+impl<'a> ProgramRef<'a> {
+    /// Returns the program that contains the vertex shader.
+    #[inline]
+    pub(crate) fn get_vertex_program(&self) -> &'a Program {
+        match *self {
+            ProgramRef::Program(program) => program,
+            ProgramRef::Pipeline(pipeline) => pipeline.stages.vertex,
+        }
+    }
+
+    /// Returns the program that contains the fragment shader.
+    #[inline]
+    pub(crate) fn get_fragment_program(&self) -> &'a Program {
+        match *self {
+            ProgramRef::Program(program) => program,
+            ProgramRef::Pipeline(pipeline) => pipeline.stages.fragment,
+        }
+    }
+}
+
+impl<'a> From<&'a Program> for ProgramRef<'a> {
+    #[inline]
+    fn from(program: &'a Program) -> ProgramRef<'a> {
+        ProgramRef::Program(program)
+    }
+}
+
+impl<'a, 'b: 'a> From<&'a ProgramPipeline<'b>> for ProgramRef<'a> {
+    #[inline]
+    fn from(pipeline: &'a ProgramPipeline<'b>) -> ProgramRef<'a> {
+        ProgramRef::Pipeline(pipeline)
+    }
+}
@@ -0,0 +1,459 @@
+//! Resolution of the `#include` directives of GLSL source code.
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+
+use crate::gl;
+use crate::backend::Facade;
+use crate::CapabilitiesSource;
+use crate::ContextExt;
+
+/// Error that can happen when resolving the `#include` directives of a shader.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IncludeError {
+    /// The loader didn't find an included file.
+    FileNotFound {
+        /// Path of the file, relative to the root of the virtual filesystem.
+        path: String,
+        /// Path of the file that contains the `#include` directive.
+        included_from: String,
+        /// Line of the `#include` directive, starting from 1.
+        line: usize,
+    },
+
+    /// A file includes itself, directly or through another file.
+    RecursiveInclude(String),
+
+    /// An `#include` directive doesn't have the form `#include "path"` or `#include <path>`.
+    MalformedDirective {
+        /// Path of the file that contains the directive.
+        file: String,
+        /// Line of the directive, starting from 1.
+        line: usize,
+    },
+}
+
+impl fmt::Display for IncludeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match *self {
+            IncludeError::FileNotFound { ref path, ref included_from, line } =>
+                write!(fmt, "The file `{}` included from `{}`, line {}, has not been found",
+                       path, included_from, line),
+            IncludeError::RecursiveInclude(ref path) =>
+                write!(fmt, "The file `{}` includes itself", path),
+            IncludeError::MalformedDirective { ref file, line } =>
+                write!(fmt, "Malformed `#include` directive in `{}`, line {}", file, line),
+        }
+    }
+}
+
+impl Error for IncludeError {}
+
+/// Resolves the `#include` directives of GLSL source code.
+///
+/// The included files are obtained from a loader, which receives the path of a file relative
+/// to the root of a virtual filesystem and returns its content, or `None` if the file doesn't
+/// exist. The loader can read files from the disk, from a `HashMap`, or from data embedded in
+/// the executable.
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// # let vertex_source = "";
+/// use glium::program::ShaderPreprocessor;
+///
+/// let mut preprocessor = ShaderPreprocessor::new(|path: &str| {
+///     std::fs::read_to_string(std::path::Path::new("shaders").join(path)).ok()
+/// });
+///
+/// let fragment = preprocessor.preprocess("sky.frag", "
+///     #version 140
+///
+///     #include \"lib/atmosphere.glsl\"
+///
+///     in vec3 v_direction;
+///     out vec4 f_color;
+///
+///     void main() {
+///         f_color = vec4(atmosphere(v_direction), 1.0);
+///     }
+/// ").unwrap();
+///
+/// match glium::Program::from_source(&display, vertex_source, fragment.get_source(), None) {
+///     Ok(program) => (),
+///     Err(glium::CompilationError(log, _)) => panic!("{}", fragment.map_log(&log)),
+///     Err(err) => panic!("{}", err),
+/// }
+/// ```
+///
+/// Paths between double quotes are relative to the directory of the file that contains the
+/// directive, while paths between angle brackets are relative to the root of the virtual
+/// filesystem. A file that contains `#pragma once` is only included once per shader.
+///
+/// The content of the included files is inserted in the source code, surrounded with `#line`
+/// directives. Each file is given its own source string number, which means that the error
+/// messages of the compiler point to the lines of the original files. Use
+/// `PreprocessedSource::map_log` to replace the numbers with the paths of the files.
+///
+/// This is done by glium and doesn't require any extension. The `#import` directives of the
+/// `ShaderModuleRegistry` are resolved afterwards, when the program is created.
+pub struct ShaderPreprocessor<L> {
+    loader: L,
+}
+
+impl<L> ShaderPreprocessor<L> where L: FnMut(&str) -> Option<String> {
+    /// Builds a new preprocessor that loads the included files with the given function.
+    #[inline]
+    pub fn new(loader: L) -> ShaderPreprocessor<L> {
+        ShaderPreprocessor {
+            loader,
+        }
+    }
+
+    /// Resolves the `#include` directives of a source code.
+    ///
+    /// `name` is the path of the source code in the virtual filesystem. It is used to resolve
+    /// relative paths and in the error messages.
+    pub fn preprocess(&mut self, name: &str, source: &str)
+                      -> Result<PreprocessedSource, IncludeError>
+    {
+        let name = normalize_path(name);
+
+        let mut state = State {
+            output: String::with_capacity(source.len()),
+            files: vec![(name.clone(), source.to_owned())],
+            included_once: HashSet::new(),
+            stack: vec![name],
+            line_is_next_line: uses_next_line_numbering(source),
+        };
+
+        self.process_file(0, &mut state)?;
+
+        Ok(PreprocessedSource {
+            source: state.output,
+            files: state.files,
+        })
+    }
+
+    fn process_file(&mut self, index: usize, state: &mut State) -> Result<(), IncludeError> {
+        let (path, source) = state.files[index].clone();
+
+        for (line_index, line) in source.split_inclusive('\n').enumerate() {
+            let line_number = line_index + 1;
+            let directive = line.trim();
+
+            if is_pragma_once(directive) {
+                state.included_once.insert(path.clone());
+                // replaced with an empty line in order to keep the line numbers
+                state.output.push('\n');
+                continue;
+            }
+
+            if !directive.starts_with("#include") {
+                state.output.push_str(line);
+                continue;
+            }
+
+            let included = match parse_include(&directive["#include".len() ..]) {
+                Some((included, true)) => normalize_path(&format!("{}/{}", parent(&path), included)),
+                Some((included, false)) => normalize_path(included),
+                None => return Err(IncludeError::MalformedDirective {
+                    file: path,
+                    line: line_number,
+                }),
+            };
+
+            if state.stack.contains(&included) {
+                return Err(IncludeError::RecursiveInclude(included));
+            }
+
+            if state.included_once.contains(&included) {
+                state.output.push('\n');
+                continue;
+            }
+
+            let included_index = match state.files.iter().position(|f| f.0 == included) {
+                Some(index) => index,
+                None => {
+                    let content = match (self.loader)(&included) {
+                        Some(content) => content,
+                        None => return Err(IncludeError::FileNotFound {
+                            path: included,
+                            included_from: path,
+                            line: line_number,
+                        }),
+                    };
+
+                    state.files.push((included.clone(), content));
+                    state.files.len() - 1
+                },
+            };
+
+            state.push_line_directive(1, included_index);
+            state.stack.push(included);
+            self.process_file(included_index, state)?;
+            state.stack.pop();
+
+            if !state.output.ends_with('\n') {
+                state.output.push('\n');
+            }
+            state.push_line_directive(line_number + 1, index);
+        }
+
+        Ok(())
+    }
+}
+
+impl<L> fmt::Debug for ShaderPreprocessor<L> {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(fmt, "ShaderPreprocessor")
+    }
+}
+
+/// State of the preprocessing of a source code.
+struct State {
+    output: String,
+    // path and content of each file, indexed by source string number
+    files: Vec<(String, String)>,
+    included_once: HashSet<String>,
+    stack: Vec<String>,
+    // before GLSL 3.30 and GLSL ES 3.00, `#line n` sets the number of the line that contains
+    // the directive instead of the next one
+    line_is_next_line: bool,
+}
+
+impl State {
+    /// Adds a `#line` directive so that the next line has the given number.
+    fn push_line_directive(&mut self, line: usize, source_string: usize) {
+        let line = if self.line_is_next_line { line } else { line - 1 };
+        self.output.push_str(&format!("#line {} {}\n", line, source_string));
+    }
+}
+
+/// The result of `ShaderPreprocessor::preprocess`.
+#[derive(Clone, Debug)]
+pub struct PreprocessedSource {
+    source: String,
+    files: Vec<(String, String)>,
+}
+
+impl PreprocessedSource {
+    /// Returns the source code, which can be passed to the compiler.
+    #[inline]
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+
+    /// Turns this object into the source code.
+    #[inline]
+    pub fn into_source(self) -> String {
+        self.source
+    }
+
+    /// Returns the path of the file that has the given source string number in the error
+    /// messages of the compiler. The number `0` corresponds to the preprocessed source code
+    /// itself.
+    #[inline]
+    pub fn get_file_path(&self, source_string: usize) -> Option<&str> {
+        self.files.get(source_string).map(|f| &f.0[..])
+    }
+
+    /// Returns the paths of the files that have been included.
+    #[inline]
+    pub fn get_included_files(&self) -> impl Iterator<Item = &str> {
+        self.files[1 ..].iter().map(|f| &f.0[..])
+    }
+
+    /// Replaces the source string numbers at the start of the lines of a compilation log with
+    /// the paths of the files.
+    ///
+    /// The format of the logs depends on the implementation. This function recognizes the
+    /// `0:12`, `0(12)` and `ERROR: 0:12` forms, and leaves the other lines untouched.
+    pub fn map_log(&self, log: &str) -> String {
+        let mut result = String::with_capacity(log.len());
+
+        for line in log.split_inclusive('\n') {
+            let prefix_len = ["ERROR: ", "WARNING: "].iter()
+                                 .find(|p| line.starts_with(*p))
+                                 .map_or(0, |p| p.len());
+            let rest = &line[prefix_len ..];
+
+            let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            let followed_by_line = rest[digits ..].starts_with(':') ||
+                                   rest[digits ..].starts_with('(');
+            let followed_by_line = followed_by_line &&
+                rest[digits + 1 ..].starts_with(|c: char| c.is_ascii_digit());
+
+            match rest[.. digits].parse().ok().and_then(|n| self.get_file_path(n)) {
+                Some(path) if followed_by_line => {
+                    result.push_str(&line[.. prefix_len]);
+                    result.push_str(path);
+                    result.push_str(&rest[digits ..]);
+                },
+                _ => result.push_str(line),
+            }
+        }
+
+        result
+    }
+
+    /// Defines each included file as a named string of `GL_ARB_shading_language_include`,
+    /// under its path prefixed with `/`.
+    ///
+    /// This allows shaders that are compiled with the extension, for example by another
+    /// library sharing the context, to include the same files. Returns false if the extension
+    /// is not supported.
+    pub fn define_named_strings<F: ?Sized>(&self, facade: &F) -> bool where F: Facade {
+        let context = facade.get_context();
+        if !context.get_extensions().gl_arb_shading_language_include {
+            return false;
+        }
+
+        let ctxt = context.make_current();
+        for (path, content) in self.files[1 ..].iter() {
+            let name = format!("/{}", path);
+            unsafe {
+                ctxt.gl.NamedStringARB(gl::SHADER_INCLUDE_ARB,
+                                       name.len() as gl::types::GLint,
+                                       name.as_ptr() as *const gl::types::GLchar,
+                                       content.len() as gl::types::GLint,
+                                       content.as_ptr() as *const gl::types::GLchar);
+            }
+        }
+
+        true
+    }
+}
+
+/// Returns true if `#line n` sets the number of the next line, depending on the `#version`
+/// directive of the source code.
+fn uses_next_line_numbering(source: &str) -> bool {
+    let version = source.lines().map(|l| l.trim()).find(|l| !l.is_empty())
+                        .and_then(|l| l.strip_prefix("#version"));
+
+    let mut words = match version {
+        Some(version) => version.split_whitespace(),
+        None => return false,
+    };
+
+    let number: u32 = match words.next().and_then(|n| n.parse().ok()) {
+        Some(number) => number,
+        None => return false,
+    };
+
+    if words.next() == Some("es") {
+        number >= 300
+    } else {
+        number >= 330
+    }
+}
+
+fn is_pragma_once(directive: &str) -> bool {
+    directive.strip_prefix("#pragma").is_some_and(|rest| rest.trim() == "once")
+}
+
+/// Parses what follows `#include`. Returns the path and true if it is between double quotes.
+fn parse_include(rest: &str) -> Option<(&str, bool)> {
+    let rest = rest.trim();
+
+    let quoted = rest.strip_prefix('"').and_then(|r| r.strip_suffix('"')).map(|p| (p, true));
+    let bracketed = rest.strip_prefix('<').and_then(|r| r.strip_suffix('>')).map(|p| (p, false));
+
+    quoted.or(bracketed).filter(|&(path, _)| !path.is_empty())
+}
+
+/// Returns the directory that contains a file.
+fn parent(path: &str) -> &str {
+    path.rfind('/').map_or("", |pos| &path[.. pos])
+}
+
+/// Removes the `.` and `..` components of a path, and the leading and duplicate slashes.
+fn normalize_path(path: &str) -> String {
+    let mut components: Vec<&str> = Vec::new();
+
+    for component in path.split('/') {
+        match component {
+            "" | "." => (),
+            ".." => { components.pop(); },
+            c => components.push(c),
+        }
+    }
+
+    components.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use super::{ShaderPreprocessor, IncludeError};
+
+    fn files() -> HashMap<&'static str, &'static str> {
+        let mut files = HashMap::new();
+        files.insert("lib/a.glsl", "#pragma once\nfloat a() { return 1.0; }\n");
+        files.insert("lib/b.glsl", "#include \"a.glsl\"\nfloat b() { return a(); }");
+        files.insert("lib/loop.glsl", "#include <lib/loop.glsl>\n");
+        files
+    }
+
+    #[test]
+    fn include_nested() {
+        let files = files();
+        let mut preprocessor = ShaderPreprocessor::new(|p: &str| files.get(p).map(|s| s.to_string()));
+
+        let result = preprocessor.preprocess("main.frag", "#version 330\n#include <lib/b.glsl>\n\
+                                                           #include \"lib/a.glsl\"\nvoid main() {}\n")
+                                 .unwrap();
+
+        assert_eq!(result.get_source(), "#version 330\n#line 1 1\n#line 1 2\n\n\
+                                         float a() { return 1.0; }\n#line 2 1\n\
+                                         float b() { return a(); }\n#line 3 0\n\n\
+                                         void main() {}\n");
+        assert_eq!(result.get_file_path(0), Some("main.frag"));
+        assert_eq!(result.get_included_files().collect::<Vec<_>>(),
+                   vec!["lib/b.glsl", "lib/a.glsl"]);
+    }
+
+    #[test]
+    fn line_numbering_before_330() {
+        let files = files();
+        let mut preprocessor = ShaderPreprocessor::new(|p: &str| files.get(p).map(|s| s.to_string()));
+
+        let result = preprocessor.preprocess("main.frag", "#version 110\n#include \"lib/a.glsl\"\n")
+                                 .unwrap();
+        assert_eq!(result.get_source(), "#version 110\n#line 0 1\n\n\
+                                         float a() { return 1.0; }\n#line 2 0\n");
+    }
+
+    #[test]
+    fn include_errors() {
+        let files = files();
+        let mut preprocessor = ShaderPreprocessor::new(|p: &str| files.get(p).map(|s| s.to_string()));
+
+        assert_eq!(preprocessor.preprocess("shaders/main.frag", "\n#include \"missing.glsl\"\n")
+                               .unwrap_err(),
+                   IncludeError::FileNotFound {
+                       path: "shaders/missing.glsl".to_owned(),
+                       included_from: "shaders/main.frag".to_owned(),
+                       line: 2,
+                   });
+
+        assert_eq!(preprocessor.preprocess("main.frag", "#include <lib/loop.glsl>\n").unwrap_err(),
+                   IncludeError::RecursiveInclude("lib/loop.glsl".to_owned()));
+
+        assert_eq!(preprocessor.preprocess("main.frag", "#include lib/a.glsl\n").unwrap_err(),
+                   IncludeError::MalformedDirective { file: "main.frag".to_owned(), line: 1 });
+    }
+
+    #[test]
+    fn map_log() {
+        let files = files();
+        let mut preprocessor = ShaderPreprocessor::new(|p: &str| files.get(p).map(|s| s.to_string()));
+
+        let result = preprocessor.preprocess("main.frag", "#version 330\n#include <lib/a.glsl>\n")
+                                 .unwrap();
+        assert_eq!(result.map_log("1:2(10): error: syntax error\n0(2) : warning\n\
+                                   ERROR: 1:2: 'a' : redefinition\n5:1: unknown\n"),
+                   "lib/a.glsl:2(10): error: syntax error\nmain.frag(2) : warning\n\
+                    ERROR: lib/a.glsl:2: 'a' : redefinition\n5:1: unknown\n");
+    }
+}
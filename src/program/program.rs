@@ -5,9 +5,14 @@ use crate::version::Version;
 use crate::version::Api;
 
 use crate::backend::Facade;
+use crate::context::Context;
 use crate::CapabilitiesSource;
+use crate::ContextExt;
 
+use std::cell::RefCell;
 use std::fmt;
+use std::rc::Rc;
+use std::sync::mpsc::{Receiver, TryRecvError};
 use std::collections::hash_map::{self, HashMap};
 use std::hash::BuildHasherDefault;
 
@@ -20,13 +25,20 @@ use crate::RawUniformValue;
 
 use crate::program::{COMPILER_GLOBAL_LOCK, ProgramCreationInput, ProgramCreationError, ShaderType, Binary};
 use crate::program::GetBinaryError;
+use crate::program::ShaderModuleDependencies;
 
 use crate::program::reflection::{Uniform, UniformBlock, OutputPrimitives};
 use crate::program::reflection::{Attribute, TransformFeedbackBuffer};
 use crate::program::reflection::{SubroutineData, ShaderStage, SubroutineUniform};
-use crate::program::shader::build_shader;
+use crate::program::reflection::{FragmentOutput, ProgramReflection};
+use crate::program::{raw, is_parallel_compile_supported, is_separate_shader_objects_supported};
+use crate::program::parse_num_views;
+use crate::program::shader::{Shader, build_shader, start_shader_compilation, check_shader_compilation};
 
-use crate::program::raw::RawProgram;
+use crate::program::raw::{RawProgram, DetachedRawProgram};
+use crate::program::compiler_thread::{CompilationResult, OwnedSource};
+
+use crate::transfer::Transferable;
 
 use crate::vertex::VertexFormat;
 
@@ -35,6 +47,8 @@ pub struct Program {
     raw: RawProgram,
     outputs_srgb: bool,
     uses_point_size: bool,
+    num_views: Option<u32>,
+    module_dependencies: ShaderModuleDependencies,
 }
 
 impl Program {
@@ -44,62 +58,36 @@ impl Program {
     {
         let input = input.into();
 
-        let (raw, outputs_srgb, uses_point_size) = match input {
-            ProgramCreationInput::SourceCode { vertex_shader, tessellation_control_shader,
-                                               tessellation_evaluation_shader, geometry_shader,
-                                               fragment_shader, transform_feedback_varyings,
-                                               outputs_srgb, uses_point_size } =>
-            {
-                let mut has_geometry_shader = false;
-                let mut has_tessellation_control_shader = false;
-                let mut has_tessellation_evaluation_shader = false;
-
-                let mut shaders = vec![
-                    (vertex_shader, ShaderType::Vertex),
-                    (fragment_shader, ShaderType::Fragment)
-                ];
-
-                if let Some(gs) = geometry_shader {
-                    shaders.push((gs, ShaderType::Geometry));
-                    has_geometry_shader = true;
-                }
-
-                if let Some(ts) = tessellation_control_shader {
-                    shaders.push((ts, ShaderType::TesselationControl));
-                    has_tessellation_control_shader = true;
-                }
-
-                if let Some(ts) = tessellation_evaluation_shader {
-                    shaders.push((ts, ShaderType::TesselationEvaluation));
-                    has_tessellation_evaluation_shader = true;
-                }
-
-                // TODO: move somewhere else
-                if transform_feedback_varyings.is_some() &&
-                    !(facade.get_context().get_version() >= &Version(Api::Gl, 3, 0)) &&
-                    !facade.get_context().get_extensions().gl_ext_transform_feedback
-                {
-                    return Err(ProgramCreationError::TransformFeedbackNotSupported);
-                }
-
-                if uses_point_size && !(facade.get_context().get_version() >= &Version(Api::Gl, 3, 0)) {
-                    return Err(ProgramCreationError::PointSizeNotSupported);
-                }
+        let (raw, outputs_srgb, uses_point_size, num_views, module_dependencies) = match input {
+            ProgramCreationInput::SourceCode { .. } |
+            ProgramCreationInput::MeshSourceCode { .. } => {
+                let source = prepare_source(facade, &input)?;
+                let (transform_feedback_varyings, outputs_srgb, uses_point_size) = match input {
+                    ProgramCreationInput::SourceCode { transform_feedback_varyings, outputs_srgb,
+                                                       uses_point_size, .. } =>
+                    {
+                        (transform_feedback_varyings, outputs_srgb, uses_point_size)
+                    },
+                    ProgramCreationInput::MeshSourceCode { outputs_srgb, .. } =>
+                        (None, outputs_srgb, false),
+                    _ => unreachable!()
+                };
 
                 let _lock = COMPILER_GLOBAL_LOCK.lock();
 
                 let shaders_store = {
                     let mut shaders_store = Vec::new();
-                    for (src, ty) in shaders.into_iter() {
-                        shaders_store.push(build_shader(facade, ty.to_opengl_type(), src)?);
+                    for (src, ty) in source.shaders.into_iter() {
+                        shaders_store.push(build_shader(facade, ty.to_opengl_type(), &src)?);
                     }
                     shaders_store
                 };
 
-                (RawProgram::from_shaders(facade, &shaders_store, has_geometry_shader,
-                                               has_tessellation_control_shader, has_tessellation_evaluation_shader,
-                                               transform_feedback_varyings)?,
-                 outputs_srgb, uses_point_size)
+                (RawProgram::from_shaders(facade, &shaders_store, source.has_geometry_shader,
+                                          source.has_tessellation_control_shader,
+                                          source.has_tessellation_evaluation_shader,
+                                          transform_feedback_varyings)?,
+                 outputs_srgb, uses_point_size, Some(source.num_views), source.module_dependencies)
             },
 
             ProgramCreationInput::Binary { data, outputs_srgb, uses_point_size } => {
@@ -107,16 +95,140 @@ impl Program {
                     return Err(ProgramCreationError::PointSizeNotSupported);
                 }
 
-                (RawProgram::from_binary(facade, data)?, outputs_srgb, uses_point_size)
+                (RawProgram::from_binary(facade, data)?, outputs_srgb, uses_point_size, None,
+                 ShaderModuleDependencies::default())
             },
         };
         Ok(Program {
             raw,
             outputs_srgb,
             uses_point_size,
+            num_views,
+            module_dependencies,
         })
     }
 
+    /// Starts building a new program without waiting for the driver to compile and link it.
+    ///
+    /// If the backend supports `GL_KHR_parallel_shader_compile` or
+    /// `GL_ARB_parallel_shader_compile`, the shaders are compiled by the driver in the
+    /// background and the returned `PendingProgram` can be polled with `is_ready`. This allows
+    /// you to keep drawing a loading screen while dozens of programs are being compiled.
+    ///
+    /// Otherwise, if a `CompilerThread` has been passed to `Context::set_compiler_thread`, the
+    /// program is built by this thread. If there is no such thread, or if the input is a binary,
+    /// the program is built immediately and the returned `PendingProgram` is always ready.
+    /// Errors are only reported by `PendingProgram::finish`.
+    ///
+    /// ```no_run
+    /// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// # let vertex_source = ""; let fragment_source = "";
+    /// let pending = glium::Program::new_async(&display, glium::program::SourceCode {
+    ///     vertex_shader: vertex_source,
+    ///     fragment_shader: fragment_source,
+    ///     geometry_shader: None,
+    ///     tessellation_control_shader: None,
+    ///     tessellation_evaluation_shader: None,
+    /// });
+    ///
+    /// // each frame
+    /// if pending.is_ready() {
+    ///     let program = pending.finish().unwrap();
+    /// }
+    /// ```
+    pub fn new_async<'a, F: ?Sized, I>(facade: &F, input: I) -> PendingProgram
+                                       where I: Into<ProgramCreationInput<'a>>, F: Facade
+    {
+        let input = input.into();
+        let context = facade.get_context().clone();
+
+        let finished = |result| PendingProgram {
+            context: facade.get_context().clone(),
+            state: Some(PendingState::Finished(Box::new(result))),
+        };
+
+        let parallel_compile = is_parallel_compile_supported(&*context);
+        if !parallel_compile && context.get_compiler_thread().is_none() {
+            return finished(Program::new(facade, input));
+        }
+
+        if let ProgramCreationInput::Binary { .. } = input {
+            return finished(Program::new(facade, input));
+        }
+
+        let source = match prepare_source(facade, &input) {
+            Ok(source) => source,
+            Err(err) => return finished(Err(err)),
+        };
+
+        let (transform_feedback_varyings, outputs_srgb, uses_point_size) = match input {
+            ProgramCreationInput::SourceCode { transform_feedback_varyings, outputs_srgb,
+                                               uses_point_size, .. } =>
+            {
+                (transform_feedback_varyings, outputs_srgb, uses_point_size)
+            },
+            ProgramCreationInput::MeshSourceCode { outputs_srgb, .. } =>
+                (None, outputs_srgb, false),
+            _ => unreachable!()
+        };
+
+        if !parallel_compile {
+            let owned_source = OwnedSource::new(source.shaders, transform_feedback_varyings,
+                                                outputs_srgb, uses_point_size);
+            let receiver = context.get_compiler_thread().as_ref().unwrap().compile(owned_source);
+
+            return PendingProgram {
+                context,
+                state: Some(PendingState::Compiling {
+                    receiver,
+                    received: RefCell::new(None),
+                    module_dependencies: source.module_dependencies,
+                }),
+            };
+        }
+
+        let _lock = COMPILER_GLOBAL_LOCK.lock();
+
+        {
+            // letting the driver use as many threads as it wants
+            let ctxt = context.make_current();
+            unsafe {
+                if ctxt.extensions.gl_khr_parallel_shader_compile {
+                    ctxt.gl.MaxShaderCompilerThreadsKHR(0xffffffff);
+                } else {
+                    ctxt.gl.MaxShaderCompilerThreadsARB(0xffffffff);
+                }
+            }
+        }
+
+        let mut shaders = Vec::with_capacity(source.shaders.len());
+        for (src, ty) in source.shaders.into_iter() {
+            match start_shader_compilation(facade, ty.to_opengl_type(), &src) {
+                Ok(shader) => shaders.push(shader),
+                Err(err) => return finished(Err(err)),
+            }
+        }
+
+        let shaders_ids = shaders.iter().map(|s| s.get_id()).collect::<Vec<_>>();
+        let id = RawProgram::start_linking(facade, &shaders_ids, transform_feedback_varyings,
+                                           false);
+
+        PendingProgram {
+            context,
+            state: Some(PendingState::Linking {
+                id,
+                shaders,
+                has_geometry_shader: source.has_geometry_shader,
+                has_tessellation_control_shader: source.has_tessellation_control_shader,
+                has_tessellation_evaluation_shader: source.has_tessellation_evaluation_shader,
+                outputs_srgb,
+                uses_point_size,
+                num_views: source.num_views,
+                module_dependencies: source.module_dependencies,
+            }),
+        }
+    }
+
     /// Builds a new program from GLSL source code.
     ///
     /// A program is a group of shaders linked together.
@@ -153,6 +265,66 @@ impl Program {
         })
     }
 
+    /// Returns the list of shader modules that have been imported by the source code of this
+    /// program, with their version at the time the program was created.
+    ///
+    /// This list is always empty for programs created from a binary, except when the binary
+    /// has been loaded by a `ProgramCache`.
+    #[inline]
+    pub fn get_shader_module_dependencies(&self) -> &ShaderModuleDependencies {
+        &self.module_dependencies
+    }
+
+    /// Used by the `ProgramCache` to restore the dependencies of a program loaded from a binary.
+    #[inline]
+    pub(crate) fn set_module_dependencies(&mut self, dependencies: ShaderModuleDependencies) {
+        self.module_dependencies = dependencies;
+    }
+
+    /// Builds a program that contains a single stage and that can be used in a program pipeline.
+    ///
+    /// Used by `SeparableProgram`.
+    pub(crate) fn new_separable<F: ?Sized>(facade: &F, ty: ShaderType, source: &str)
+                                           -> Result<Program, ProgramCreationError>
+                                           where F: Facade
+    {
+        if !is_separate_shader_objects_supported(&**facade.get_context()) {
+            return Err(ProgramCreationError::SeparableProgramNotSupported);
+        }
+
+        let (source, module_dependencies) = facade.get_context().get_shader_module_registry()
+                                                  .resolve(source)
+                                                  .map_err(ProgramCreationError::ShaderModuleError)?;
+
+        let _lock = COMPILER_GLOBAL_LOCK.lock();
+
+        let shader = build_shader(facade, ty.to_opengl_type(), &source)?;
+        let shaders_ids = [shader.get_id()];
+        let id = RawProgram::start_linking(facade, &shaders_ids, None, true);
+        let raw = RawProgram::finish_linking(facade, id, &shaders_ids,
+                                             ty == ShaderType::Geometry,
+                                             ty == ShaderType::TesselationControl,
+                                             ty == ShaderType::TesselationEvaluation)?;
+
+        Ok(Program {
+            raw,
+            outputs_srgb: false,
+            uses_point_size: false,
+            num_views: if ty == ShaderType::Vertex { Some(parse_num_views(&source)) } else { None },
+            module_dependencies,
+        })
+    }
+
+    /// Returns true if one of the shader modules imported by this program has been modified or
+    /// unregistered since the program was created, in which case the program should be rebuilt.
+    ///
+    /// See `ShaderModuleRegistry`.
+    #[inline]
+    pub fn is_outdated(&self) -> bool {
+        !self.raw.get_context().get_shader_module_registry()
+                               .is_up_to_date(&self.module_dependencies)
+    }
+
     /// Returns the program's compiled binary.
     ///
     /// You can store the result in a file, then reload it later. This avoids having to compile
@@ -199,6 +371,44 @@ impl Program {
         self.raw.uniforms()
     }
 
+    /// Returns the texture unit that each sampler uniform was assigned to, sorted by unit.
+    ///
+    /// Units are assigned during draw calls and are kept from one draw call to the next one
+    /// whenever possible. Sampler uniforms that haven't been used yet are not in the list,
+    /// unless `validate` has assigned them a unit.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// for (name, unit) in program.get_texture_units() {
+    ///     println!("{} is bound to GL_TEXTURE{}", name, unit);
+    /// }
+    /// ```
+    #[inline]
+    pub fn get_texture_units(&self) -> Vec<(&str, u16)> {
+        self.raw.get_texture_units()
+    }
+
+    /// Checks with `glValidateProgram` that the program can be executed with the current state
+    /// of the context. On failure, returns the information log of the program.
+    ///
+    /// The sampler uniforms that haven't been used yet are assigned distinct texture units
+    /// first, since they would otherwise all point to the same unit.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// if let Err(log) = program.validate() {
+    ///     println!("The program can't be executed: {}", log);
+    /// }
+    /// ```
+    #[inline]
+    pub fn validate(&self) -> Result<(), String> {
+        self.raw.validate()
+    }
+
     /// Returns a list of uniform blocks.
     ///
     /// ## Example
@@ -264,6 +474,13 @@ impl Program {
         self.raw.has_geometry_shader()
     }
 
+    /// Returns true if the program contains a mesh shader, in which case it must be drawn with
+    /// `Surface::draw_mesh_tasks`.
+    #[inline]
+    pub fn has_mesh_shader(&self) -> bool {
+        self.raw.has_mesh_shader()
+    }
+
     /// Returns informations about an attribute, if it exists.
     #[inline]
     pub fn get_attribute(&self, name: &str) -> Option<&Attribute> {
@@ -285,6 +502,26 @@ impl Program {
         self.raw.attributes()
     }
 
+    /// Returns the map of the active attributes of the program, indexed by name.
+    ///
+    /// Each entry contains the location, type and array size of the attribute. This map is
+    /// built when the program is created, so calling this function doesn't query the driver.
+    /// This is useful if you build your own vertex attribute bindings.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// if let Some(attribute) = program.attribute_locations().get("position") {
+    ///     println!("Location: {} - Type: {:?}", attribute.location, attribute.ty);
+    /// }
+    /// ```
+    #[inline]
+    pub fn attribute_locations(&self)
+                               -> &HashMap<String, Attribute, BuildHasherDefault<FnvHasher>> {
+        self.raw.attribute_locations()
+    }
+
     /// Returns true if the program has been configured to output sRGB instead of RGB.
     #[inline]
     pub fn has_srgb_output(&self) -> bool {
@@ -307,6 +544,36 @@ impl Program {
         self.raw.get_shader_storage_blocks()
     }
 
+    /// Returns the outputs of the fragment shader, or `None` if the backend doesn't support
+    /// `GL_ARB_program_interface_query`.
+    #[inline]
+    pub fn get_fragment_outputs(&self)
+            -> Option<&HashMap<String, FragmentOutput, BuildHasherDefault<FnvHasher>>> {
+        self.raw.get_fragment_outputs()
+    }
+
+    /// Returns a structured description of all the interfaces of the program: attributes,
+    /// uniforms, samplers with their texture units, uniform and shader storage blocks with the
+    /// offsets of their members, and outputs.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// let reflection = program.reflect();
+    ///
+    /// for &(name, block) in &reflection.uniform_blocks {
+    ///     for variable in &block.variables {
+    ///         println!("{}.{}: {:?} at offset {}", name, variable.name, variable.ty,
+    ///                  variable.offset);
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn reflect(&self) -> ProgramReflection<'_> {
+        self.raw.reflect()
+    }
+
     /// Returns the list of shader storage blocks.
     ///
     /// ## Example
@@ -349,6 +616,20 @@ impl Program {
     pub fn uses_point_size(&self) -> bool {
       self.uses_point_size
     }
+
+    /// Returns the number of views declared by the vertex shader with the
+    /// `layout(num_views = N) in;` qualifier of `GL_OVR_multiview`, or 1 if it doesn't declare
+    /// any.
+    ///
+    /// Returns `None` if the program has been created from a binary, in which case the number
+    /// of views is unknown.
+    ///
+    /// A program can only be used to draw into a framebuffer with the same number of views.
+    /// See `SimpleFrameBuffer::multiview`.
+    #[inline]
+    pub fn get_num_views(&self) -> Option<u32> {
+        self.num_views
+    }
 }
 
 impl fmt::Debug for Program {
@@ -397,6 +678,18 @@ impl ProgramExt for Program {
         self.raw.set_uniform(ctxt, uniform_location, value)
     }
 
+    #[inline]
+    fn get_texture_unit(&self, uniform_location: gl::types::GLint) -> Option<u16> {
+        self.raw.get_texture_unit(uniform_location)
+    }
+
+    #[inline]
+    fn set_texture_unit(&self, ctxt: &mut CommandContext<'_>, uniform_location: gl::types::GLint,
+                        unit: u16)
+    {
+        self.raw.set_texture_unit(ctxt, uniform_location, unit)
+    }
+
     #[inline]
     fn set_uniform_block_binding(&self, ctxt: &mut CommandContext<'_>, block_location: gl::types::GLuint,
                                  value: gl::types::GLuint)
@@ -447,3 +740,327 @@ impl ProgramExt for Program {
         self.raw.get_subroutine_data()
     }
 }
+
+/// A program whose shaders are being compiled and linked in the background.
+///
+/// Returned by `Program::new_async`. Call `is_ready` regularly, for example once per frame, and
+/// call `finish` once it returns `true` in order to obtain the program. Calling `finish` before
+/// the program is ready is allowed, but blocks until the driver or the compiler thread has
+/// finished.
+pub struct PendingProgram {
+    context: Rc<Context>,
+    state: Option<PendingState>,
+}
+
+enum PendingState {
+    /// The program has been created synchronously, or an error happened before anything was sent
+    /// to the driver.
+    Finished(Box<Result<Program, ProgramCreationError>>),
+
+    /// The driver is compiling and linking the program.
+    Linking {
+        id: Handle,
+        shaders: Vec<Shader>,
+        has_geometry_shader: bool,
+        has_tessellation_control_shader: bool,
+        has_tessellation_evaluation_shader: bool,
+        outputs_srgb: bool,
+        uses_point_size: bool,
+        num_views: u32,
+        module_dependencies: ShaderModuleDependencies,
+    },
+
+    /// The program has been sent to the compiler thread of the context.
+    Compiling {
+        receiver: Receiver<CompilationResult>,
+        /// The result, once it has been received by `is_ready`.
+        received: RefCell<Option<CompilationResult>>,
+        module_dependencies: ShaderModuleDependencies,
+    },
+}
+
+impl PendingProgram {
+    /// Returns true if calling `finish` won't block.
+    ///
+    /// Always returns true if the backend doesn't support parallel shader compilation and the
+    /// context doesn't have a compiler thread.
+    pub fn is_ready(&self) -> bool {
+        match self.state {
+            Some(PendingState::Linking { id, .. }) => {
+                let mut ctxt = self.context.make_current();
+                raw::is_linking_finished(&mut ctxt, id)
+            },
+            Some(PendingState::Compiling { ref receiver, ref received, .. }) => {
+                let mut received = received.borrow_mut();
+                if received.is_none() {
+                    match receiver.try_recv() {
+                        Ok(result) => *received = Some(result),
+                        Err(TryRecvError::Empty) => return false,
+                        // `finish` reports that the thread has stopped
+                        Err(TryRecvError::Disconnected) => return true,
+                    }
+                }
+
+                match *received {
+                    Some(Ok(ref ticket)) => ticket.is_ready(&self.context),
+                    _ => true,
+                }
+            },
+            _ => true,
+        }
+    }
+
+    /// Waits for the compilation and linking to be finished and returns the program.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if the compiler thread that builds the program has panicked.
+    pub fn finish(mut self) -> Result<Program, ProgramCreationError> {
+        match self.state.take().unwrap() {
+            PendingState::Finished(result) => *result,
+
+            PendingState::Linking { id, shaders, has_geometry_shader,
+                                    has_tessellation_control_shader,
+                                    has_tessellation_evaluation_shader, outputs_srgb,
+                                    uses_point_size, num_views, module_dependencies } =>
+            {
+                for shader in shaders.iter() {
+                    if let Err(err) = check_shader_compilation(shader) {
+                        let mut ctxt = self.context.make_current();
+                        unsafe { raw::delete_program(&mut ctxt, id) };
+                        return Err(err);
+                    }
+                }
+
+                let shaders_ids = shaders.iter().map(|s| s.get_id()).collect::<Vec<_>>();
+                let raw = RawProgram::finish_linking(&self.context, id, &shaders_ids,
+                                                     has_geometry_shader,
+                                                     has_tessellation_control_shader,
+                                                     has_tessellation_evaluation_shader)?;
+
+                Ok(Program {
+                    raw,
+                    outputs_srgb,
+                    uses_point_size,
+                    num_views: Some(num_views),
+                    module_dependencies,
+                })
+            },
+
+            PendingState::Compiling { receiver, received, module_dependencies } => {
+                let result = match received.into_inner() {
+                    Some(result) => result,
+                    None => receiver.recv().expect("The compiler thread has panicked"),
+                };
+
+                let mut program = result?.wait(&self.context);
+
+                // the imports have been resolved with the shader modules of this context
+                program.module_dependencies = module_dependencies;
+                Ok(program)
+            },
+        }
+    }
+}
+
+impl fmt::Debug for PendingProgram {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self.state {
+            Some(PendingState::Linking { id, .. }) => write!(formatter, "PendingProgram({:?})", id),
+            Some(PendingState::Compiling { .. }) => write!(formatter, "PendingProgram(compiling)"),
+            _ => write!(formatter, "PendingProgram(finished)"),
+        }
+    }
+}
+
+impl Drop for PendingProgram {
+    fn drop(&mut self) {
+        match self.state.take() {
+            Some(PendingState::Linking { id, .. }) => {
+                let mut ctxt = self.context.make_current();
+                unsafe { raw::delete_program(&mut ctxt, id) };
+            },
+
+            // if the result hasn't been received yet, the compiler thread destroys the program
+            // once it notices that the receiver has been dropped
+            Some(PendingState::Compiling { received, .. }) => {
+                if let Some(Ok(ticket)) = received.into_inner() {
+                    drop(ticket.wait(&self.context));
+                }
+            },
+
+            _ => (),
+        }
+    }
+}
+
+/// A program detached from its context, which can be sent to another thread.
+///
+/// See the `transfer` module.
+pub struct DetachedProgram {
+    raw: DetachedRawProgram,
+    outputs_srgb: bool,
+    uses_point_size: bool,
+    num_views: Option<u32>,
+    module_dependencies: ShaderModuleDependencies,
+}
+
+impl fmt::Debug for DetachedProgram {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(formatter, "DetachedProgram")
+    }
+}
+
+impl Transferable for Program {
+    type Detached = DetachedProgram;
+
+    #[inline]
+    fn detach(self) -> DetachedProgram {
+        DetachedProgram {
+            raw: self.raw.detach(),
+            outputs_srgb: self.outputs_srgb,
+            uses_point_size: self.uses_point_size,
+            num_views: self.num_views,
+            module_dependencies: self.module_dependencies,
+        }
+    }
+
+    #[inline]
+    unsafe fn attach<F: ?Sized>(facade: &F, detached: DetachedProgram) -> Program
+                                where F: Facade
+    {
+        Program {
+            raw: RawProgram::attach(facade, detached.raw),
+            outputs_srgb: detached.outputs_srgb,
+            uses_point_size: detached.uses_point_size,
+            num_views: detached.num_views,
+            module_dependencies: detached.module_dependencies,
+        }
+    }
+}
+
+/// Source code of a program, after checking that its shaders are supported and resolving its
+/// `#import` directives.
+struct PreparedSource {
+    shaders: Vec<(String, ShaderType)>,
+    has_geometry_shader: bool,
+    has_tessellation_control_shader: bool,
+    has_tessellation_evaluation_shader: bool,
+    num_views: u32,
+    module_dependencies: ShaderModuleDependencies,
+}
+
+fn prepare_source<F: ?Sized>(facade: &F, input: &ProgramCreationInput<'_>)
+                             -> Result<PreparedSource, ProgramCreationError> where F: Facade
+{
+    if let ProgramCreationInput::MeshSourceCode { task_shader, mesh_shader,
+                                                  fragment_shader, .. } = *input
+    {
+        let mut shaders = vec![
+            (mesh_shader, ShaderType::Mesh),
+            (fragment_shader, ShaderType::Fragment)
+        ];
+
+        if let Some(ts) = task_shader {
+            shaders.push((ts, ShaderType::Task));
+        }
+
+        let (shaders, module_dependencies) = resolve_imports(facade, shaders)?;
+
+        return Ok(PreparedSource {
+            shaders,
+            has_geometry_shader: false,
+            has_tessellation_control_shader: false,
+            has_tessellation_evaluation_shader: false,
+            num_views: 1,
+            module_dependencies,
+        });
+    }
+
+    let (vertex_shader, tessellation_control_shader, tessellation_evaluation_shader,
+         geometry_shader, fragment_shader, has_transform_feedback, uses_point_size) = match *input
+    {
+        ProgramCreationInput::SourceCode { vertex_shader, tessellation_control_shader,
+                                           tessellation_evaluation_shader, geometry_shader,
+                                           fragment_shader, ref transform_feedback_varyings,
+                                           uses_point_size, .. } =>
+        {
+            (vertex_shader, tessellation_control_shader, tessellation_evaluation_shader,
+             geometry_shader, fragment_shader, transform_feedback_varyings.is_some(),
+             uses_point_size)
+        },
+        ProgramCreationInput::MeshSourceCode { .. } | ProgramCreationInput::Binary { .. } =>
+            unreachable!(),
+    };
+
+    let mut has_geometry_shader = false;
+    let mut has_tessellation_control_shader = false;
+    let mut has_tessellation_evaluation_shader = false;
+
+    let mut shaders = vec![
+        (vertex_shader, ShaderType::Vertex),
+        (fragment_shader, ShaderType::Fragment)
+    ];
+
+    if let Some(gs) = geometry_shader {
+        shaders.push((gs, ShaderType::Geometry));
+        has_geometry_shader = true;
+    }
+
+    if let Some(ts) = tessellation_control_shader {
+        shaders.push((ts, ShaderType::TesselationControl));
+        has_tessellation_control_shader = true;
+    }
+
+    if let Some(ts) = tessellation_evaluation_shader {
+        shaders.push((ts, ShaderType::TesselationEvaluation));
+        has_tessellation_evaluation_shader = true;
+    }
+
+    // TODO: move somewhere else
+    if has_transform_feedback &&
+        !(facade.get_context().get_version() >= &Version(Api::Gl, 3, 0)) &&
+        !facade.get_context().get_extensions().gl_ext_transform_feedback
+    {
+        return Err(ProgramCreationError::TransformFeedbackNotSupported);
+    }
+
+    if uses_point_size && !(facade.get_context().get_version() >= &Version(Api::Gl, 3, 0)) {
+        return Err(ProgramCreationError::PointSizeNotSupported);
+    }
+
+    let (shaders, module_dependencies) = resolve_imports(facade, shaders)?;
+
+    let num_views = shaders.iter().find(|&&(_, ty)| ty == ShaderType::Vertex)
+                               .map(|(src, _)| parse_num_views(src)).unwrap_or(1);
+
+    Ok(PreparedSource {
+        shaders,
+        has_geometry_shader,
+        has_tessellation_control_shader,
+        has_tessellation_evaluation_shader,
+        num_views,
+        module_dependencies,
+    })
+}
+
+/// Replaces the `#import` directives with the content of the modules.
+fn resolve_imports<F: ?Sized>(facade: &F, shaders: Vec<(&str, ShaderType)>)
+                              -> Result<(Vec<(String, ShaderType)>, ShaderModuleDependencies),
+                                        ProgramCreationError>
+                              where F: Facade
+{
+    let mut module_dependencies = ShaderModuleDependencies::default();
+    let registry = facade.get_context().get_shader_module_registry();
+    let mut resolved = Vec::with_capacity(shaders.len());
+    for (src, ty) in shaders.into_iter() {
+        let (src, deps) = registry.resolve(src)
+                                  .map_err(ProgramCreationError::ShaderModuleError)?;
+        module_dependencies.extend(deps);
+        resolved.push((src, ty));
+    }
+
+    Ok((resolved, module_dependencies))
+}
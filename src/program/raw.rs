@@ -5,11 +5,11 @@ use crate::version::Version;
 use crate::version::Api;
 
 use crate::backend::Facade;
-use crate::context::Context;
+use crate::context::{Context, FlushPolicy};
 use crate::ContextExt;
 use crate::UniformsExt;
 
-use std::{ffi, fmt};
+use std::{ffi, fmt, mem};
 use std::collections::hash_map::{self, HashMap};
 use std::rc::Rc;
 use std::cell::RefCell;
@@ -27,21 +27,22 @@ use crate::RawUniformValue;
 use crate::QueryExt;
 use crate::draw_parameters::TimeElapsedQuery;
 
-use crate::buffer::BufferSlice;
+use crate::buffer::{BufferSlice, Content};
 use crate::BufferExt;
 use crate::BufferSliceExt;
 
 use crate::program::{ProgramCreationError, Binary, GetBinaryError};
 use crate::program::uniforms_storage::UniformsStorage;
 
-use crate::program::compute::ComputeCommand;
 use crate::program::reflection::{Uniform, UniformBlock, OutputPrimitives};
 use crate::program::reflection::{Attribute, TransformFeedbackMode, TransformFeedbackBuffer};
 use crate::program::reflection::{SubroutineData, ShaderStage};
 use crate::program::reflection::{reflect_uniforms, reflect_attributes, reflect_uniform_blocks};
 use crate::program::reflection::{reflect_transform_feedback, reflect_geometry_output_type};
 use crate::program::reflection::{reflect_tess_eval_output_type, reflect_shader_storage_blocks};
-use crate::program::reflection::{reflect_subroutine_data};
+use crate::program::reflection::{reflect_subroutine_data, reflect_image_formats};
+use crate::program::reflection::{reflect_shader_stages, get_shader_stages};
+use crate::program::reflection::{reflect_fragment_outputs, FragmentOutput, ProgramReflection};
 use crate::program::shader::Shader;
 use crate::program::binary_header::{attach_glium_header, process_glium_header};
 
@@ -64,11 +65,36 @@ pub struct RawProgram {
     ssbos: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
     atomic_counters: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
     output_primitives: Option<OutputPrimitives>,
+    frag_outputs: Option<HashMap<String, FragmentOutput, BuildHasherDefault<FnvHasher>>>,
     has_geometry_shader: bool,
     has_tessellation_control_shader: bool,
     has_tessellation_evaluation_shader: bool,
+    has_mesh_shader: bool,
+    // false if the program has been detached, in which case it must not be destroyed
+    owned: bool,
 }
 
+/// A program detached from its context with `RawProgram::detach`, along with its reflection.
+pub struct DetachedRawProgram {
+    id: Handle,
+    uniforms: HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>,
+    uniform_blocks: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
+    subroutine_data: SubroutineData,
+    attributes: HashMap<String, Attribute, BuildHasherDefault<FnvHasher>>,
+    tf_buffers: Vec<TransformFeedbackBuffer>,
+    ssbos: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
+    atomic_counters: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
+    output_primitives: Option<OutputPrimitives>,
+    frag_outputs: Option<HashMap<String, FragmentOutput, BuildHasherDefault<FnvHasher>>>,
+    has_geometry_shader: bool,
+    has_tessellation_control_shader: bool,
+    has_tessellation_evaluation_shader: bool,
+    has_mesh_shader: bool,
+}
+
+// the handle is only an identifier, which is valid in all the contexts of the share group
+unsafe impl Send for DetachedRawProgram {}
+
 impl RawProgram {
     /// Builds a new program from a list of shaders.
     // TODO: the "has_*" parameters are bad
@@ -79,11 +105,28 @@ impl RawProgram {
                                   -> Result<RawProgram, ProgramCreationError>
                                   where F: Facade, I: IntoIterator<Item = &'a Shader>
     {
-        let mut ctxt = facade.get_context().make_current();
-
         let shaders_ids = shaders.into_iter().map(|s| s.get_id()).collect::<Vec<_>>();
+        let id = RawProgram::start_linking(facade, &shaders_ids, transform_feedback, false);
 
-        let id = unsafe {
+        RawProgram::finish_linking(facade, id, &shaders_ids, has_geometry_shader,
+                                   has_tessellation_control_shader,
+                                   has_tessellation_evaluation_shader)
+    }
+
+    /// Creates a program, attaches the shaders and starts linking it, without waiting for
+    /// the result.
+    ///
+    /// Use `finish_linking` to obtain the result.
+    ///
+    /// If `separable` is true, the program is marked as usable in a program pipeline. This
+    /// requires separate shader objects to be supported.
+    pub fn start_linking<F: ?Sized>(facade: &F, shaders_ids: &[Handle],
+                                    transform_feedback: Option<(Vec<String>, TransformFeedbackMode)>,
+                                    separable: bool) -> Handle where F: Facade
+    {
+        let mut ctxt = facade.get_context().make_current();
+
+        unsafe {
             let id = create_program(&mut ctxt);
 
             // attaching shaders
@@ -139,6 +182,16 @@ impl RawProgram {
                 }
             }
 
+            if separable {
+                let id = match id {
+                    Handle::Id(id) => id,
+                    Handle::Handle(_) => unreachable!()     // has been checked in the frontend
+                };
+
+                ctxt.gl.ProgramParameteri(id, gl::PROGRAM_SEPARABLE,
+                                          gl::TRUE as gl::types::GLint);
+            }
+
             // linking
             {
                 ctxt.report_debug_output_errors.set(false);
@@ -158,22 +211,43 @@ impl RawProgram {
                 ctxt.report_debug_output_errors.set(true);
             }
 
-            // checking for errors
-            check_program_link_errors(&mut ctxt, id)?;
-
             id
-        };
+        }
+    }
+
+    /// Waits for the linking of a program started with `start_linking` to be finished, then
+    /// checks for errors and queries the reflection data of the program.
+    ///
+    /// The program object is destroyed if linking failed.
+    pub fn finish_linking<F: ?Sized>(facade: &F, id: Handle, shaders_ids: &[Handle],
+                                     has_geometry_shader: bool,
+                                     has_tessellation_control_shader: bool,
+                                     has_tessellation_evaluation_shader: bool)
+                                     -> Result<RawProgram, ProgramCreationError> where F: Facade
+    {
+        let mut ctxt = facade.get_context().make_current();
+
+        // checking for errors
+        if let Err(err) = unsafe { check_program_link_errors(&mut ctxt, id) } {
+            unsafe { delete_program(&mut ctxt, id) };
+            return Err(err);
+        }
+
+        let (mut uniforms, atomic_counters) = unsafe { reflect_uniforms(&mut ctxt, id) };
+        let image_formats = unsafe { reflect_image_formats(&mut ctxt, shaders_ids) };
+        for (name, uniform) in uniforms.iter_mut() {
+            let name = name.split('[').next().unwrap();
+            uniform.image_format = image_formats.get(name).cloned();
+        }
 
-        let (uniforms, atomic_counters) = unsafe { reflect_uniforms(&mut ctxt, id) };
         let attributes = unsafe { reflect_attributes(&mut ctxt, id) };
         let blocks = unsafe { reflect_uniform_blocks(&mut ctxt, id) };
         let tf_buffers = unsafe { reflect_transform_feedback(&mut ctxt, id) };
         let ssbos = unsafe { reflect_shader_storage_blocks(&mut ctxt, id) };
         let subroutine_data = unsafe {
-            reflect_subroutine_data(&mut ctxt, id, has_geometry_shader,
-                                    has_tessellation_control_shader,
-                                    has_tessellation_evaluation_shader)
-            };
+            let stages = reflect_shader_stages(&mut ctxt, shaders_ids);
+            reflect_subroutine_data(&mut ctxt, id, &stages)
+        };
 
         let output_primitives = if has_geometry_shader {
             Some(unsafe { reflect_geometry_output_type(&mut ctxt, id) })
@@ -183,6 +257,9 @@ impl RawProgram {
             None
         };
 
+        let frag_outputs = unsafe { reflect_fragment_outputs(&mut ctxt, id) };
+        let has_mesh_shader = unsafe { has_mesh_shader(&mut ctxt, shaders_ids) };
+
         Ok(RawProgram {
             context: facade.get_context().clone(),
             id,
@@ -196,9 +273,12 @@ impl RawProgram {
             ssbos,
             atomic_counters,
             output_primitives,
+            frag_outputs,
             has_geometry_shader,
             has_tessellation_control_shader,
             has_tessellation_evaluation_shader,
+            has_mesh_shader,
+            owned: true,
         })
     }
 
@@ -206,7 +286,8 @@ impl RawProgram {
     pub fn from_binary<F: ?Sized>(facade: &F, binary: Binary)
                           -> Result<RawProgram, ProgramCreationError> where F: Facade
     {
-        let (has_geometry_shader, has_tessellation_control_shader, has_tessellation_evaluation_shader) = {
+        let (has_geometry_shader, has_tessellation_control_shader, has_tessellation_evaluation_shader,
+             has_mesh_shader) = {
             match process_glium_header(&binary.content) {
                 Some(flags) => flags,
                 None => return Err(ProgramCreationError::BinaryHeaderError)
@@ -241,9 +322,10 @@ impl RawProgram {
                 reflect_uniform_blocks(&mut ctxt, id),
                 reflect_transform_feedback(&mut ctxt, id),
                 reflect_shader_storage_blocks(&mut ctxt, id),
-                reflect_subroutine_data(&mut ctxt, id, has_geometry_shader,
-                                        has_tessellation_control_shader,
-                                        has_tessellation_evaluation_shader),
+                reflect_subroutine_data(&mut ctxt, id,
+                                        &get_shader_stages(has_geometry_shader,
+                                                           has_tessellation_control_shader,
+                                                           has_tessellation_evaluation_shader)),
             )
         };
 
@@ -255,6 +337,8 @@ impl RawProgram {
             None
         };
 
+        let frag_outputs = unsafe { reflect_fragment_outputs(&mut ctxt, id) };
+
         Ok(RawProgram {
             context: facade.get_context().clone(),
             id,
@@ -268,12 +352,21 @@ impl RawProgram {
             ssbos,
             atomic_counters,
             output_primitives,
+            frag_outputs,
             has_geometry_shader,
             has_tessellation_control_shader,
             has_tessellation_evaluation_shader,
+            has_mesh_shader,
+            owned: true,
         })
     }
 
+    /// Returns the context this program belongs to.
+    #[inline]
+    pub fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+
     /// Returns the program's compiled binary.
     ///
     /// You can store the result in a file, then reload it later. This avoids having to compile
@@ -382,6 +475,106 @@ impl RawProgram {
         self.uniforms.iter()
     }
 
+    /// Returns the texture unit that each sampler uniform was assigned to, sorted by unit.
+    ///
+    /// Units are assigned during draw calls and are kept from one draw call to the next one
+    /// whenever possible. Sampler uniforms that haven't been used yet are not in the list.
+    pub fn get_texture_units(&self) -> Vec<(&str, u16)> {
+        let mut units = self.uniforms.iter()
+            .filter_map(|(name, uniform)| {
+                self.uniform_values.get_texture_unit(uniform.location)
+                    .map(|unit| (&name[..], unit))
+            })
+            .collect::<Vec<_>>();
+        units.sort_by_key(|&(name, unit)| (unit, name));
+        units
+    }
+
+    /// Checks with `glValidateProgram` that the program can be executed with the current state
+    /// of the context. Returns the information log of the program on failure.
+    ///
+    /// The sampler uniforms that haven't been used yet are first assigned distinct texture
+    /// units, otherwise they would all point to unit 0, which is invalid if their types differ.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut ctxt = self.context.make_current();
+
+        let mut used_units = self.uniforms.values()
+            .filter_map(|uniform| self.uniform_values.get_texture_unit(uniform.location))
+            .collect::<Vec<_>>();
+        let mut next_unit = 0;
+        for uniform in self.uniforms.values().filter(|uniform| uniform.ty.is_sampler()) {
+            if self.uniform_values.get_texture_unit(uniform.location).is_some() {
+                continue;
+            }
+
+            while used_units.contains(&next_unit) {
+                next_unit += 1;
+            }
+
+            self.use_program(&mut ctxt);
+            self.uniform_values.set_texture_unit(&mut ctxt, self.id, uniform.location, next_unit);
+            used_units.push(next_unit);
+        }
+
+        let mut status: gl::types::GLint = 0;
+        unsafe {
+            match self.id {
+                Handle::Id(id) => {
+                    ctxt.gl.ValidateProgram(id);
+                    ctxt.gl.GetProgramiv(id, gl::VALIDATE_STATUS, &mut status);
+                },
+                Handle::Handle(id) => {
+                    ctxt.gl.ValidateProgramARB(id);
+                    ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_VALIDATE_STATUS_ARB,
+                                                    &mut status);
+                },
+            }
+
+            if status == 0 {
+                return Err(get_program_info_log(&mut ctxt, self.id));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the outputs of the fragment shader, or `None` if the backend doesn't support
+    /// program interface queries.
+    #[inline]
+    pub fn get_fragment_outputs(&self)
+            -> Option<&HashMap<String, FragmentOutput, BuildHasherDefault<FnvHasher>>> {
+        self.frag_outputs.as_ref()
+    }
+
+    /// Returns a structured description of all the interfaces of the program.
+    pub fn reflect(&self) -> ProgramReflection<'_> {
+        fn sorted<'a, T>(map: &'a HashMap<String, T, BuildHasherDefault<FnvHasher>>)
+                         -> Vec<(&'a str, &'a T)>
+        {
+            let mut list = map.iter().map(|(name, value)| (&name[..], value)).collect::<Vec<_>>();
+            list.sort_by_key(|&(name, _)| name);
+            list
+        }
+
+        // the members of uniform blocks are active uniforms too, but they don't have a location
+        let mut uniforms = sorted(&self.uniforms);
+        uniforms.retain(|&(_, uniform)| uniform.location >= 0);
+
+        let samplers = uniforms.iter()
+            .filter(|&&(_, uniform)| uniform.ty.is_sampler())
+            .map(|&(name, uniform)| (name, self.uniform_values.get_texture_unit(uniform.location)))
+            .collect();
+
+        ProgramReflection {
+            attributes: sorted(&self.attributes),
+            uniforms,
+            samplers,
+            uniform_blocks: sorted(&self.uniform_blocks),
+            shader_storage_blocks: sorted(&self.ssbos),
+            outputs: self.frag_outputs.as_ref().map(sorted),
+        }
+    }
+
     /// Returns a list of uniform blocks.
     ///
     /// ## Example
@@ -426,7 +619,7 @@ impl RawProgram {
         }
 
         for elem in buf.elements.iter() {
-            if format.iter().find(|e| e.1 == elem.offset && e.2 == elem.ty)
+            if format.iter().find(|e| e.1 == elem.offset && e.3 == elem.ty)
                             .is_none()
             {
                 return false;
@@ -475,12 +668,25 @@ impl RawProgram {
         self.has_geometry_shader
     }
 
+    /// Returns true if the program contains a mesh shader.
+    #[inline]
+    pub fn has_mesh_shader(&self) -> bool {
+        self.has_mesh_shader
+    }
+
     /// Returns informations about an attribute, if it exists.
     #[inline]
     pub fn get_attribute(&self, name: &str) -> Option<&Attribute> {
         self.attributes.get(name)
     }
 
+    /// Returns the map of the active attributes of the program, indexed by name.
+    #[inline]
+    pub fn attribute_locations(&self)
+                               -> &HashMap<String, Attribute, BuildHasherDefault<FnvHasher>> {
+        &self.attributes
+    }
+
     /// Returns an iterator to the list of attributes.
     ///
     /// ## Example
@@ -547,9 +753,9 @@ impl RawProgram {
         let mut ctxt = self.context.make_current();
 
         // TODO: return an error instead
-        assert!(x < ctxt.capabilities.max_compute_work_group_count.0 as u32);
-        assert!(y < ctxt.capabilities.max_compute_work_group_count.1 as u32);
-        assert!(z < ctxt.capabilities.max_compute_work_group_count.2 as u32);
+        assert!(x <= ctxt.capabilities.max_compute_work_group_count.0 as u32);
+        assert!(y <= ctxt.capabilities.max_compute_work_group_count.1 as u32);
+        assert!(z <= ctxt.capabilities.max_compute_work_group_count.2 as u32);
 
         assert!(ctxt.version >= &Version(Api::Gl, 4, 3) ||
                 ctxt.version >= &Version(Api::GlEs, 3, 1) ||
@@ -567,6 +773,10 @@ impl RawProgram {
             fence.insert(&mut ctxt);
         }
 
+        if self.context.get_flush_policy() == FlushPolicy::FlushAfterCommands {
+            ctxt.gl.Flush();
+        }
+
         Ok(())
     }
 
@@ -576,10 +786,10 @@ impl RawProgram {
     ///
     /// The program *must* contain a compute shader.
     /// TODO: check inside the program if it has a compute shader instead of being unsafe
-    pub unsafe fn dispatch_compute_indirect<U>(&self, uniforms: U,
-                                               buffer: BufferSlice<'_, ComputeCommand>)
-                                               -> Result<(), DrawError>      // TODO: other error?
-                                               where U: Uniforms
+    pub unsafe fn dispatch_compute_indirect<U, T: ?Sized>(&self, uniforms: U,
+                                                          buffer: BufferSlice<'_, T>)
+                                                          -> Result<(), DrawError>      // TODO: other error?
+                                                          where U: Uniforms, T: Content
     {
         let mut ctxt = self.context.make_current();
 
@@ -595,13 +805,15 @@ impl RawProgram {
         // an error is generated if the offset is not a multiple of 4
         assert!(offset % 4 == 0);
 
+        let mut fences = Vec::with_capacity(0);
+
+        // the fence must be inserted after the command, so that it isn't signaled before the
+        // GPU has read the parameters
         if let Some(fence) = buffer.add_fence() {
-            fence.insert(&mut ctxt);
+            fences.push(fence);
         }
 
         self.use_program(&mut ctxt);
-
-        let mut fences = Vec::with_capacity(0);
         uniforms.bind_uniforms(&mut ctxt, self, &mut fences)?;
 
         ctxt.gl.DispatchComputeIndirect(offset as gl::types::GLintptr);
@@ -610,10 +822,73 @@ impl RawProgram {
             fence.insert(&mut ctxt);
         }
 
+        if self.context.get_flush_policy() == FlushPolicy::FlushAfterCommands {
+            ctxt.gl.Flush();
+        }
+
         Ok(())
     }
 }
 
+impl RawProgram {
+    /// Detaches the program from its context without destroying it.
+    pub fn detach(mut self) -> DetachedRawProgram {
+        let detached = DetachedRawProgram {
+            id: self.id,
+            uniforms: mem::take(&mut self.uniforms),
+            uniform_blocks: mem::take(&mut self.uniform_blocks),
+            subroutine_data: SubroutineData {
+                location_counts: mem::take(&mut self.subroutine_data.location_counts),
+                subroutine_uniforms: mem::take(&mut self.subroutine_data.subroutine_uniforms),
+            },
+            attributes: mem::take(&mut self.attributes),
+            tf_buffers: mem::take(&mut self.tf_buffers),
+            ssbos: mem::take(&mut self.ssbos),
+            atomic_counters: mem::take(&mut self.atomic_counters),
+            output_primitives: self.output_primitives,
+            frag_outputs: self.frag_outputs.take(),
+            has_geometry_shader: self.has_geometry_shader,
+            has_tessellation_control_shader: self.has_tessellation_control_shader,
+            has_tessellation_evaluation_shader: self.has_tessellation_evaluation_shader,
+            has_mesh_shader: self.has_mesh_shader,
+        };
+
+        // the destructor purges the caches of the context but doesn't destroy the program
+        self.owned = false;
+        detached
+    }
+
+    /// Attaches a detached program to the context of `facade`.
+    ///
+    /// # Unsafety
+    ///
+    /// The context must share its objects with the context the program has been detached from.
+    pub unsafe fn attach<F: ?Sized>(facade: &F, detached: DetachedRawProgram) -> RawProgram
+                                    where F: Facade
+    {
+        RawProgram {
+            context: facade.get_context().clone(),
+            id: detached.id,
+            uniform_values: UniformsStorage::new(),
+            uniforms: detached.uniforms,
+            uniform_blocks: detached.uniform_blocks,
+            subroutine_data: detached.subroutine_data,
+            attributes: detached.attributes,
+            frag_data_locations: RefCell::new(HashMap::with_hasher(Default::default())),
+            tf_buffers: detached.tf_buffers,
+            ssbos: detached.ssbos,
+            atomic_counters: detached.atomic_counters,
+            output_primitives: detached.output_primitives,
+            frag_outputs: detached.frag_outputs,
+            has_geometry_shader: detached.has_geometry_shader,
+            has_tessellation_control_shader: detached.has_tessellation_control_shader,
+            has_tessellation_evaluation_shader: detached.has_tessellation_evaluation_shader,
+            has_mesh_shader: detached.has_mesh_shader,
+            owned: true,
+        }
+    }
+}
+
 impl fmt::Debug for RawProgram {
     #[inline]
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
@@ -652,6 +927,18 @@ impl ProgramExt for RawProgram {
         self.uniform_values.set_uniform_value(ctxt, self.id, uniform_location, value);
     }
 
+    #[inline]
+    fn get_texture_unit(&self, uniform_location: gl::types::GLint) -> Option<u16> {
+        self.uniform_values.get_texture_unit(uniform_location)
+    }
+
+    #[inline]
+    fn set_texture_unit(&self, ctxt: &mut CommandContext<'_>, uniform_location: gl::types::GLint,
+                        unit: u16)
+    {
+        self.uniform_values.set_texture_unit(ctxt, self.id, uniform_location, unit);
+    }
+
     #[inline]
     fn set_uniform_block_binding(&self, ctxt: &mut CommandContext<'_>, block_location: gl::types::GLuint,
                                  value: gl::types::GLuint)
@@ -705,40 +992,101 @@ impl ProgramExt for RawProgram {
 
 impl Drop for RawProgram {
     fn drop(&mut self) {
+        // the program is only destroyed once it is no longer used by any context
+        let id = self.id;
+        self.context.for_each_shared_context(&mut |ctxt| {
+            VertexAttributesSystem::purge_program(ctxt, id);
+            unsafe { unuse_program(ctxt, id) };
+        });
+
         let mut ctxt = self.context.make_current();
 
         // removing VAOs which contain this program
         VertexAttributesSystem::purge_program(&mut ctxt, self.id);
 
         // sending the destroy command
-        unsafe {
-            match self.id {
-                Handle::Id(id) => {
-                    assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
-                            ctxt.version >= &Version(Api::GlEs, 2, 0));
+        if self.owned {
+            unsafe {
+                delete_program(&mut ctxt, self.id);
+            }
+        } else {
+            unsafe { unuse_program(&mut ctxt, self.id) };
+        }
+    }
+}
 
-                    if ctxt.state.program == Handle::Id(id) {
-                        ctxt.gl.UseProgram(0);
-                        ctxt.state.program = Handle::Id(0);
-                    }
+/// Destroys a program, unbinding it first if it is the current program.
+pub unsafe fn delete_program(ctxt: &mut CommandContext<'_>, id: Handle) {
+    unuse_program(ctxt, id);
 
-                    ctxt.gl.DeleteProgram(id);
-                },
-                Handle::Handle(id) => {
-                    assert!(ctxt.extensions.gl_arb_shader_objects);
+    match id {
+        Handle::Id(id) => {
+            assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                    ctxt.version >= &Version(Api::GlEs, 2, 0));
+            ctxt.gl.DeleteProgram(id);
+        },
+        Handle::Handle(id) => {
+            assert!(ctxt.extensions.gl_arb_shader_objects);
+            ctxt.gl.DeleteObjectARB(id);
+        }
+    }
+}
 
-                    if ctxt.state.program == Handle::Handle(id) {
-                        ctxt.gl.UseProgramObjectARB(0 as gl::types::GLhandleARB);
-                        ctxt.state.program = Handle::Handle(0 as gl::types::GLhandleARB);
-                    }
+/// Unbinds a program if it is the current program.
+unsafe fn unuse_program(ctxt: &mut CommandContext<'_>, id: Handle) {
+    if ctxt.state.program != id {
+        return;
+    }
 
-                    ctxt.gl.DeleteObjectARB(id);
-                }
-            }
+    match id {
+        Handle::Id(_) => {
+            ctxt.gl.UseProgram(0);
+            ctxt.state.program = Handle::Id(0);
+        },
+        Handle::Handle(_) => {
+            ctxt.gl.UseProgramObjectARB(0 as gl::types::GLhandleARB);
+            ctxt.state.program = Handle::Handle(0 as gl::types::GLhandleARB);
         }
     }
 }
 
+/// Returns true if the compilation and linking of a program started with
+/// `RawProgram::start_linking` is finished, in which case querying its status won't block.
+///
+/// Always returns true if `GL_KHR_parallel_shader_compile` is not supported.
+pub fn is_linking_finished(ctxt: &mut CommandContext<'_>, id: Handle) -> bool {
+    if !ctxt.extensions.gl_khr_parallel_shader_compile &&
+       !ctxt.extensions.gl_arb_parallel_shader_compile
+    {
+        return true;
+    }
+
+    let id = match id {
+        Handle::Id(id) => id,
+        Handle::Handle(_) => return true,
+    };
+
+    let mut status = 0;
+    unsafe { ctxt.gl.GetProgramiv(id, gl::COMPLETION_STATUS_KHR, &mut status) };
+    status != 0
+}
+
+/// Returns true if one of the shaders is a mesh shader.
+unsafe fn has_mesh_shader(ctxt: &mut CommandContext<'_>, shaders_ids: &[Handle]) -> bool {
+    if !ctxt.extensions.gl_nv_mesh_shader && !ctxt.extensions.gl_ext_mesh_shader {
+        return false;
+    }
+
+    shaders_ids.iter().any(|&shader| match shader {
+        Handle::Id(id) => {
+            let mut shader_type = 0;
+            ctxt.gl.GetShaderiv(id, gl::SHADER_TYPE, &mut shader_type);
+            shader_type as gl::types::GLenum == gl::MESH_SHADER_NV
+        },
+        Handle::Handle(_) => false,
+    })
+}
+
 /// Builds an empty program from within the GL context.
 unsafe fn create_program(ctxt: &mut CommandContext<'_>) -> Handle {
     let id = if ctxt.version >= &Version(Api::Gl, 2, 0) ||
@@ -792,42 +1140,46 @@ unsafe fn check_program_link_errors(ctxt: &mut CommandContext<'_>, id: Handle)
             }
         };
 
-        let mut error_log_size: gl::types::GLint = 0;
+        return Err(LinkingError(get_program_info_log(ctxt, id)));
+    }
 
-        match id {
-            Handle::Id(id) => {
-                assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
-                    ctxt.version >= &Version(Api::GlEs, 2, 0));
-                ctxt.gl.GetProgramiv(id, gl::INFO_LOG_LENGTH, &mut error_log_size);
-            },
-            Handle::Handle(id) => {
-                assert!(ctxt.extensions.gl_arb_shader_objects);
-                ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_INFO_LOG_LENGTH_ARB,
-                                                &mut error_log_size);
-            }
-        }
+    Ok(())
+}
 
-        let mut error_log: Vec<u8> = Vec::with_capacity(error_log_size as usize);
+/// Returns the information log of a program, which contains the errors of the last link or
+/// validation.
+unsafe fn get_program_info_log(ctxt: &mut CommandContext<'_>, id: Handle) -> String {
+    let mut error_log_size: gl::types::GLint = 0;
 
-        match id {
-            Handle::Id(id) => {
-                assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
-                    ctxt.version >= &Version(Api::GlEs, 2, 0));
-                ctxt.gl.GetProgramInfoLog(id, error_log_size, &mut error_log_size,
-                                          error_log.as_mut_ptr() as *mut gl::types::GLchar);
-            },
-            Handle::Handle(id) => {
-                assert!(ctxt.extensions.gl_arb_shader_objects);
-                ctxt.gl.GetInfoLogARB(id, error_log_size, &mut error_log_size,
-                                      error_log.as_mut_ptr() as *mut gl::types::GLchar);
-            }
+    match id {
+        Handle::Id(id) => {
+            assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                ctxt.version >= &Version(Api::GlEs, 2, 0));
+            ctxt.gl.GetProgramiv(id, gl::INFO_LOG_LENGTH, &mut error_log_size);
+        },
+        Handle::Handle(id) => {
+            assert!(ctxt.extensions.gl_arb_shader_objects);
+            ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_INFO_LOG_LENGTH_ARB,
+                                            &mut error_log_size);
         }
+    }
 
-        error_log.set_len(error_log_size as usize);
+    let mut error_log: Vec<u8> = Vec::with_capacity(error_log_size as usize);
 
-        let msg = String::from_utf8(error_log).unwrap();
-        return Err(LinkingError(msg));
+    match id {
+        Handle::Id(id) => {
+            assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                ctxt.version >= &Version(Api::GlEs, 2, 0));
+            ctxt.gl.GetProgramInfoLog(id, error_log_size, &mut error_log_size,
+                                      error_log.as_mut_ptr() as *mut gl::types::GLchar);
+        },
+        Handle::Handle(id) => {
+            assert!(ctxt.extensions.gl_arb_shader_objects);
+            ctxt.gl.GetInfoLogARB(id, error_log_size, &mut error_log_size,
+                                  error_log.as_mut_ptr() as *mut gl::types::GLchar);
+        }
     }
 
-    Ok(())
+    error_log.set_len(error_log_size as usize);
+    String::from_utf8(error_log).unwrap()
 }
@@ -12,7 +12,7 @@ use crate::context::CommandContext;
 use crate::version::Version;
 use crate::version::Api;
 
-use crate::uniforms::UniformType;
+use crate::uniforms::{ImageUnitFormat, UniformType};
 use crate::vertex::AttributeType;
 use crate::program;
 
@@ -31,6 +31,11 @@ pub struct Uniform {
 
     /// If it is an array, the number of elements.
     pub size: Option<usize>,
+
+    /// If the uniform is an image, the format declared with its layout qualifier.
+    ///
+    /// This is `None` for uniforms that are not images or if the format couldn't be determined.
+    pub image_format: Option<ImageUnitFormat>,
 }
 
 /// Information about a uniform block (except its name).
@@ -51,6 +56,51 @@ pub struct UniformBlock {
 
     /// Layout of the block.
     pub layout: BlockLayout,
+
+    /// List of the variables of the block, as reported by the implementation.
+    ///
+    /// Contrary to `layout`, each element of an array of structs is a separate variable, and
+    /// the strides of arrays and matrices are known. This list is empty for atomic counters.
+    pub variables: Vec<BlockVariable>,
+}
+
+/// Information about a variable inside a uniform block or a shader storage block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockVariable {
+    /// Full name of the variable, for example `lights[2].color`.
+    pub name: String,
+
+    /// Type of the variable.
+    pub ty: UniformType,
+
+    /// Offset of the variable in bytes from the start of the block.
+    pub offset: usize,
+
+    /// Number of elements if the variable is an array, or 1 otherwise. Is 0 for the last
+    /// member of a shader storage block if its size isn't known at compile-time.
+    pub array_size: usize,
+
+    /// Number of bytes between two elements of the array, or 0 if the variable is not an array.
+    pub array_stride: usize,
+
+    /// Number of bytes between two columns of a column-major matrix or two rows of a row-major
+    /// matrix, or 0 if the variable is not a matrix.
+    pub matrix_stride: usize,
+
+    /// True if the variable is a matrix stored in row-major order, for example because the
+    /// block has the `row_major` layout qualifier.
+    pub is_row_major: bool,
+
+    /// Number of elements of the top-level array that contains the variable, or 1 if it is
+    /// not in an array. Is 0 if the size of this array isn't known at compile-time.
+    ///
+    /// Only available for shader storage blocks.
+    pub top_level_array_size: Option<usize>,
+
+    /// Number of bytes between two elements of the top-level array that contains the variable.
+    ///
+    /// Only available for shader storage blocks.
+    pub top_level_array_stride: Option<usize>,
 }
 
 /// Layout of a shader storage buffer or a uniform buffer.
@@ -121,6 +171,48 @@ pub struct Attribute {
     pub size: usize,
 }
 
+/// Information about an output of the fragment shader of a program (except its name).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FragmentOutput {
+    /// The location of the output, in other words the index of the color attachment it
+    /// writes to.
+    pub location: i32,
+
+    /// Type of the output.
+    pub ty: AttributeType,
+
+    /// Number of elements of the output.
+    pub size: usize,
+}
+
+/// A structured description of all the interfaces of a program.
+///
+/// Each list is sorted by name. This is returned by `Program::reflect`.
+#[derive(Debug, Clone)]
+pub struct ProgramReflection<'a> {
+    /// The vertex attributes of the program.
+    pub attributes: Vec<(&'a str, &'a Attribute)>,
+
+    /// The uniforms that are not inside a block, including samplers and images.
+    pub uniforms: Vec<(&'a str, &'a Uniform)>,
+
+    /// The sampler uniforms and the texture unit that each of them is bound to.
+    ///
+    /// The unit is `None` for samplers that haven't been used in a draw call yet, as units are
+    /// assigned during draw calls.
+    pub samplers: Vec<(&'a str, Option<u16>)>,
+
+    /// The uniform blocks, with the offsets of their members.
+    pub uniform_blocks: Vec<(&'a str, &'a UniformBlock)>,
+
+    /// The shader storage blocks, with the offsets of their members.
+    pub shader_storage_blocks: Vec<(&'a str, &'a UniformBlock)>,
+
+    /// The outputs of the fragment shader, or `None` if the backend doesn't support
+    /// `GL_ARB_program_interface_query`.
+    pub outputs: Option<Vec<(&'a str, &'a FragmentOutput)>>,
+}
+
 /// Describes the layout of a buffer that can receive transform feedback output.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TransformFeedbackBuffer {
@@ -265,11 +357,14 @@ pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext<'_>, program: Handle)
             assert!(query_atomic_counters);
             let mut atomic_counter_id: gl::types::GLint = 0;
             let mut atomic_counter_buffer_bind_point: gl::types::GLint = 0;
+            let mut atomic_counter_offset: gl::types::GLint = 0;
             match program {
                 Handle::Id(program) => {
                     ctxt.gl.GetActiveUniformsiv(program, 1, &(uniform_id as gl::types::GLuint),
                                                 gl::UNIFORM_ATOMIC_COUNTER_BUFFER_INDEX,
                                                 &mut atomic_counter_id);
+                    ctxt.gl.GetActiveUniformsiv(program, 1, &(uniform_id as gl::types::GLuint),
+                                                gl::UNIFORM_OFFSET, &mut atomic_counter_offset);
                     ctxt.gl.GetActiveAtomicCounterBufferiv(program,
                                                            atomic_counter_id as gl::types::GLuint,
                                                            gl::ATOMIC_COUNTER_BUFFER_BINDING,
@@ -280,17 +375,19 @@ pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext<'_>, program: Handle)
             atomic_counters.insert(uniform_name, UniformBlock {
                 id: atomic_counter_id,
                 initial_binding: atomic_counter_buffer_bind_point,
-                size: 4,
+                size: 4 * data_size as usize,
                 layout: BlockLayout::BasicType {
                     ty: UniformType::UnsignedInt,
-                    offset_in_buffer: 0,
+                    offset_in_buffer: atomic_counter_offset as usize,
                 },
+                variables: Vec::new(),
             });
         } else {
             uniforms.insert(uniform_name, Uniform {
                 location: location as i32,
                 ty: glenum_to_uniform_type(data_type),
                 size: if data_size == 1 { None } else { Some(data_size as usize) },
+                image_format: None,
             });
         }
     }
@@ -323,9 +420,170 @@ pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext<'_>, program: Handle)
     (uniforms_flattened, atomic_counters)
 }
 
+/// Returns the formats declared with layout qualifiers by the image uniforms of the given shaders.
+///
+/// OpenGL doesn't provide any way to query these formats, so the source code of the shaders
+/// is retrieved and parsed.
+pub unsafe fn reflect_image_formats(ctxt: &mut CommandContext<'_>, shaders: &[Handle])
+                                    -> HashMap<String, ImageUnitFormat, BuildHasherDefault<FnvHasher>>
+{
+    let mut formats = HashMap::with_hasher(Default::default());
+
+    for &shader in shaders {
+        let mut source_len: gl::types::GLint = 0;
+
+        match shader {
+            Handle::Id(id) => {
+                assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                        ctxt.version >= &Version(Api::GlEs, 2, 0));
+                ctxt.gl.GetShaderiv(id, gl::SHADER_SOURCE_LENGTH, &mut source_len);
+            },
+            Handle::Handle(id) => {
+                assert!(ctxt.extensions.gl_arb_shader_objects);
+                ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_SHADER_SOURCE_LENGTH_ARB,
+                                                &mut source_len);
+            }
+        }
+
+        if source_len <= 0 {
+            continue;
+        }
+
+        let mut source: Vec<u8> = vec![0; source_len as usize];
+        let mut written: gl::types::GLsizei = 0;
+
+        match shader {
+            Handle::Id(id) => {
+                ctxt.gl.GetShaderSource(id, source_len, &mut written,
+                                        source.as_mut_ptr() as *mut gl::types::GLchar);
+            },
+            Handle::Handle(id) => {
+                ctxt.gl.GetShaderSourceARB(id, source_len, &mut written,
+                                           source.as_mut_ptr() as *mut gl::types::GLchar);
+            }
+        }
+
+        source.truncate(written as usize);
+        let source = String::from_utf8_lossy(&source);
+        formats.extend(parse_image_formats(&source));
+    }
+
+    formats
+}
+
+/// Parses GLSL source code and returns the list of image uniforms whose declaration contains
+/// a format layout qualifier, with their format.
+fn parse_image_formats(source: &str) -> Vec<(String, ImageUnitFormat)> {
+    // removing comments
+    let mut code = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('/', Some(&'/')) => {
+                while let Some(c) = chars.next() {
+                    if c == '\n' { code.push('\n'); break; }
+                }
+            },
+            ('/', Some(&'*')) => {
+                chars.next();
+                let mut prev = ' ';
+                while let Some(c) = chars.next() {
+                    if prev == '*' && c == '/' { break; }
+                    prev = c;
+                }
+                code.push(' ');
+            },
+            (c, _) => code.push(c),
+        }
+    }
+
+    // splitting into identifiers and single-character symbols
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    for c in code.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            current.push(c);
+            continue;
+        }
+
+        if !current.is_empty() {
+            tokens.push(current.clone());
+            current.clear();
+        }
+
+        if !c.is_whitespace() {
+            tokens.push(c.to_string());
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    let mut result = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        if tokens[i] != "layout" || tokens.get(i + 1).map(|t| &t[..]) != Some("(") {
+            i += 1;
+            continue;
+        }
+
+        // finding the format in the list of layout qualifiers
+        i += 2;
+        let mut format = None;
+        while i < tokens.len() && tokens[i] != ")" {
+            if let Some(f) = ImageUnitFormat::from_glsl_qualifier(&tokens[i]) {
+                format = Some(f);
+            }
+            i += 1;
+        }
+
+        let format = match format {
+            Some(f) => f,
+            None => continue
+        };
+
+        // the rest of the declaration must contain an image type followed by names
+        let mut is_image = false;
+        let mut expect_name = false;
+        let mut array_depth = 0;
+        while i < tokens.len() && tokens[i] != ";" {
+            let token = &tokens[i];
+            i += 1;
+
+            match &token[..] {
+                "[" => array_depth += 1,
+                "]" => array_depth -= 1,
+                "," if array_depth == 0 => expect_name = is_image,
+                "=" | "(" | "{" => break,
+                _ if array_depth != 0 => (),
+                t if t.starts_with("image") || t.starts_with("iimage") || t.starts_with("uimage") => {
+                    is_image = true;
+                    expect_name = true;
+                },
+                t if expect_name => {
+                    result.push((t.to_owned(), format));
+                    expect_name = false;
+                },
+                _ => ()
+            }
+        }
+    }
+
+    result
+}
+
 pub unsafe fn reflect_attributes(ctxt: &mut CommandContext<'_>, program: Handle)
                                  -> HashMap<String, Attribute, BuildHasherDefault<FnvHasher>>
 {
+    // program interface queries let us get everything with a single call per attribute
+    if let Handle::Id(program) = program {
+        if ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+           ctxt.extensions.gl_arb_program_interface_query
+        {
+            return reflect_program_inputs(ctxt, program);
+        }
+    }
+
     // number of active attributes
     let active_attributes = {
         let mut active_attributes: gl::types::GLint = 0;
@@ -406,6 +664,120 @@ pub unsafe fn reflect_attributes(ctxt: &mut CommandContext<'_>, program: Handle)
     attributes
 }
 
+/// Same as `reflect_attributes`, but uses `glGetProgramResource*`.
+unsafe fn reflect_program_inputs(ctxt: &mut CommandContext<'_>, program: gl::types::GLuint)
+                                 -> HashMap<String, Attribute, BuildHasherDefault<FnvHasher>>
+{
+    // number of active inputs
+    let active_inputs = {
+        let mut active_inputs: gl::types::GLint = 0;
+        ctxt.gl.GetProgramInterfaceiv(program, gl::PROGRAM_INPUT, gl::ACTIVE_RESOURCES,
+                                      &mut active_inputs);
+        active_inputs as gl::types::GLuint
+    };
+
+    // the result of this function
+    let mut attributes = HashMap::with_hasher(Default::default());
+    attributes.reserve(active_inputs as usize);
+
+    for input_id in 0 .. active_inputs {
+        let (name_len, data_type, data_size, location) = {
+            let mut output: [gl::types::GLint; 4] = [0; 4];
+            ctxt.gl.GetProgramResourceiv(program, gl::PROGRAM_INPUT, input_id, 4,
+                                         [gl::NAME_LENGTH, gl::TYPE, gl::ARRAY_SIZE,
+                                          gl::LOCATION].as_ptr(), 4,
+                                         ptr::null_mut(), output.as_mut_ptr() as *mut _);
+            (output[0] as usize, output[1] as gl::types::GLenum, output[2] as usize, output[3])
+        };
+
+        let name = {
+            let mut name_tmp: Vec<u8> = Vec::with_capacity(1 + name_len);
+            let mut name_tmp_len = name_len as gl::types::GLsizei;
+
+            ctxt.gl.GetProgramResourceName(program, gl::PROGRAM_INPUT, input_id,
+                                           name_tmp_len, &mut name_tmp_len,
+                                           name_tmp.as_mut_ptr() as *mut _);
+            name_tmp.set_len(name_tmp_len as usize);
+            String::from_utf8(name_tmp).unwrap()
+        };
+
+        if name.starts_with("gl_") {   // ignoring everything built-in
+            continue;
+        }
+
+        attributes.insert(name, Attribute {
+            location,
+            ty: glenum_to_attribute_type(data_type),
+            size: data_size,
+        });
+    }
+
+    attributes
+}
+
+/// Returns the list of outputs of a program, or `None` if program interface queries are not
+/// supported.
+pub unsafe fn reflect_fragment_outputs(ctxt: &mut CommandContext<'_>, program: Handle)
+                                      -> Option<HashMap<String, FragmentOutput, BuildHasherDefault<FnvHasher>>>
+{
+    if !(ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+         ctxt.extensions.gl_arb_program_interface_query)
+    {
+        return None;
+    }
+
+    let program = match program {
+        Handle::Id(program) => program,
+        Handle::Handle(_) => return None
+    };
+
+    // number of active outputs
+    let active_outputs = {
+        let mut active_outputs: gl::types::GLint = 0;
+        ctxt.gl.GetProgramInterfaceiv(program, gl::PROGRAM_OUTPUT, gl::ACTIVE_RESOURCES,
+                                      &mut active_outputs);
+        active_outputs as gl::types::GLuint
+    };
+
+    // the result of this function
+    let mut outputs = HashMap::with_hasher(Default::default());
+    outputs.reserve(active_outputs as usize);
+
+    for output_id in 0 .. active_outputs {
+        let (name_len, data_type, data_size, location) = {
+            let mut output: [gl::types::GLint; 4] = [0; 4];
+            ctxt.gl.GetProgramResourceiv(program, gl::PROGRAM_OUTPUT, output_id, 4,
+                                         [gl::NAME_LENGTH, gl::TYPE, gl::ARRAY_SIZE,
+                                          gl::LOCATION].as_ptr(), 4,
+                                         ptr::null_mut(), output.as_mut_ptr() as *mut _);
+            (output[0] as usize, output[1] as gl::types::GLenum, output[2] as usize, output[3])
+        };
+
+        let name = {
+            let mut name_tmp: Vec<u8> = Vec::with_capacity(1 + name_len);
+            let mut name_tmp_len = name_len as gl::types::GLsizei;
+
+            ctxt.gl.GetProgramResourceName(program, gl::PROGRAM_OUTPUT, output_id,
+                                           name_tmp_len, &mut name_tmp_len,
+                                           name_tmp.as_mut_ptr() as *mut _);
+            name_tmp.set_len(name_tmp_len as usize);
+            String::from_utf8(name_tmp).unwrap()
+        };
+
+        if name.starts_with("gl_") {   // ignoring everything built-in
+            continue;
+        }
+
+        outputs.insert(name, FragmentOutput {
+            location,
+            ty: glenum_to_attribute_type(data_type),
+            size: data_size,
+        });
+    }
+
+    Some(outputs)
+}
+
 pub unsafe fn reflect_uniform_blocks(ctxt: &mut CommandContext<'_>, program: Handle)
                                      -> HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>
 {
@@ -490,6 +862,24 @@ pub unsafe fn reflect_uniform_blocks(ctxt: &mut CommandContext<'_>, program: Han
         ctxt.gl.GetActiveUniformsiv(program, num_members, members_indices.as_ptr(),
                                     gl::UNIFORM_SIZE, member_size.as_mut_ptr());
 
+        // getting the array strides of the members
+        let mut member_array_stride = ::std::iter::repeat(0).take(num_members as usize)
+                                                            .collect::<Vec<gl::types::GLint>>();
+        ctxt.gl.GetActiveUniformsiv(program, num_members, members_indices.as_ptr(),
+                                    gl::UNIFORM_ARRAY_STRIDE, member_array_stride.as_mut_ptr());
+
+        // getting the matrix strides of the members
+        let mut member_matrix_stride = ::std::iter::repeat(0).take(num_members as usize)
+                                                             .collect::<Vec<gl::types::GLint>>();
+        ctxt.gl.GetActiveUniformsiv(program, num_members, members_indices.as_ptr(),
+                                    gl::UNIFORM_MATRIX_STRIDE, member_matrix_stride.as_mut_ptr());
+
+        // getting whether the matrices are row-major
+        let mut member_row_major = ::std::iter::repeat(0).take(num_members as usize)
+                                                         .collect::<Vec<gl::types::GLint>>();
+        ctxt.gl.GetActiveUniformsiv(program, num_members, members_indices.as_ptr(),
+                                    gl::UNIFORM_IS_ROW_MAJOR, member_row_major.as_mut_ptr());
+
         // getting the length of the names of the members
         let mut member_name_len = ::std::iter::repeat(0).take(num_members as usize)
                                                          .collect::<Vec<gl::types::GLint>>();
@@ -510,10 +900,22 @@ pub unsafe fn reflect_uniform_blocks(ctxt: &mut CommandContext<'_>, program: Han
         }).collect::<Vec<_>>();
 
         // now computing the list of members
-        let members = member_names.into_iter().enumerate().map(|(index, name)| {
-            (name, member_offsets[index] as usize,
-             glenum_to_uniform_type(member_types[index] as gl::types::GLenum),
-             member_size[index] as usize, None)
+        let variables = member_names.into_iter().enumerate().map(|(index, name)| {
+            BlockVariable {
+                name,
+                ty: glenum_to_uniform_type(member_types[index] as gl::types::GLenum),
+                offset: member_offsets[index] as usize,
+                array_size: member_size[index] as usize,
+                array_stride: member_array_stride[index] as usize,
+                matrix_stride: member_matrix_stride[index] as usize,
+                is_row_major: member_row_major[index] != 0,
+                top_level_array_size: None,
+                top_level_array_stride: None,
+            }
+        }).collect::<Vec<_>>();
+
+        let members = variables.iter().map(|v| {
+            (v.name.clone(), v.offset, v.ty, v.array_size, None)
         });
 
         // finally inserting into the blocks list
@@ -522,6 +924,7 @@ pub unsafe fn reflect_uniform_blocks(ctxt: &mut CommandContext<'_>, program: Han
             initial_binding: binding as i32,
             size: block_size as usize,
             layout: introspection_output_to_layout(members),
+            variables,
         });
     }
 
@@ -782,20 +1185,23 @@ pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext<'_>, progr
             variables
         };
 
-        // iterator over variables
-        let members = active_variables.into_iter().map(|variable| {
-            let (ty, array_size, offset, _array_stride, name_len, top_level_array_size) = {
-                let mut output: [gl::types::GLint; 6] = [0; 6];
+        // list of variables
+        let variables = active_variables.into_iter().map(|variable| {
+            let output = {
+                let mut output: [gl::types::GLint; 9] = [0; 9];
                 ctxt.gl.GetProgramResourceiv(program, gl::BUFFER_VARIABLE,
-                                             variable as gl::types::GLuint, 6,
+                                             variable as gl::types::GLuint, 9,
                                              [gl::TYPE, gl::ARRAY_SIZE, gl::OFFSET,
                                               gl::ARRAY_STRIDE, gl::NAME_LENGTH,
-                                              gl::TOP_LEVEL_ARRAY_SIZE].as_ptr(), 6,
+                                              gl::TOP_LEVEL_ARRAY_SIZE, gl::MATRIX_STRIDE,
+                                              gl::TOP_LEVEL_ARRAY_STRIDE,
+                                              gl::IS_ROW_MAJOR].as_ptr(), 9,
                                              ptr::null_mut(), output.as_mut_ptr() as *mut _);
-                (glenum_to_uniform_type(output[0] as gl::types::GLenum), output[1] as usize,
-                 output[2] as usize, output[3] as usize, output[4] as usize, output[5] as usize)
+                output
             };
 
+            let name_len = output[4] as usize;
+
             let name = {
                 let mut name_tmp: Vec<u8> = Vec::with_capacity(1 + name_len);
                 let mut name_tmp_len = name_len as gl::types::GLsizei;
@@ -808,7 +1214,21 @@ pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext<'_>, progr
                 String::from_utf8(name_tmp).unwrap()
             };
 
-            (name, offset, ty, array_size, Some(top_level_array_size))
+            BlockVariable {
+                name,
+                ty: glenum_to_uniform_type(output[0] as gl::types::GLenum),
+                offset: output[2] as usize,
+                array_size: output[1] as usize,
+                array_stride: output[3] as usize,
+                matrix_stride: output[6] as usize,
+                is_row_major: output[8] != 0,
+                top_level_array_size: Some(output[5] as usize),
+                top_level_array_stride: Some(output[7] as usize),
+            }
+        }).collect::<Vec<_>>();
+
+        let members = variables.iter().map(|v| {
+            (v.name.clone(), v.offset, v.ty, v.array_size, v.top_level_array_size)
         });
 
         // finally inserting into the blocks list
@@ -817,6 +1237,7 @@ pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext<'_>, progr
             initial_binding: binding as i32,
             size: total_size,
             layout: introspection_output_to_layout(members),
+            variables,
         });
     }
 
@@ -1171,10 +1592,12 @@ impl ShaderStage {
     }
 }
 
-fn get_shader_stages(has_geometry_shader: bool,
-                     has_tessellation_control_shader: bool,
-                     has_tessellation_evaluation_shader: bool)
-                     -> Vec<ShaderStage> {
+/// Returns the stages of a program whose shaders are unknown, like a program loaded from a
+/// binary, from the flags that describe it.
+pub fn get_shader_stages(has_geometry_shader: bool,
+                         has_tessellation_control_shader: bool,
+                         has_tessellation_evaluation_shader: bool)
+                         -> Vec<ShaderStage> {
     let mut stages = vec![ShaderStage::Vertex, ShaderStage::Fragment];
     if has_tessellation_evaluation_shader {
         stages.push(ShaderStage::TessellationEvaluation);
@@ -1188,11 +1611,47 @@ fn get_shader_stages(has_geometry_shader: bool,
     stages
 }
 
+/// Returns the stages of the given shaders that can have subroutine uniforms.
+///
+/// Separable programs and compute shaders don't have a vertex or a fragment shader, and
+/// querying the subroutines of a stage that a program doesn't have is an error.
+pub unsafe fn reflect_shader_stages(ctxt: &mut CommandContext<'_>, shaders: &[Handle])
+                                    -> Vec<ShaderStage>
+{
+    let mut stages = Vec::new();
+
+    for &shader in shaders {
+        let id = match shader {
+            Handle::Id(id) => id,
+            // subroutines not supported.
+            Handle::Handle(_) => continue,
+        };
+
+        let mut shader_type: gl::types::GLint = 0;
+        ctxt.gl.GetShaderiv(id, gl::SHADER_TYPE, &mut shader_type);
+
+        let stage = match shader_type as gl::types::GLenum {
+            gl::VERTEX_SHADER => ShaderStage::Vertex,
+            gl::FRAGMENT_SHADER => ShaderStage::Fragment,
+            gl::TESS_CONTROL_SHADER => ShaderStage::TessellationControl,
+            gl::TESS_EVALUATION_SHADER => ShaderStage::TessellationEvaluation,
+            gl::GEOMETRY_SHADER => ShaderStage::Geometry,
+            _ => continue,
+        };
+
+        if !stages.contains(&stage) {
+            stages.push(stage);
+        }
+    }
+
+    stages
+}
+
 /// Returns the data associated with a programs subroutines.
+///
+/// Only the subroutines of the given stages are queried.
 pub unsafe fn reflect_subroutine_data(ctxt: &mut CommandContext<'_>, program: Handle,
-                                      has_geometry_shader: bool,
-                                      has_tessellation_control_shader: bool,
-                                      has_tessellation_evaluation_shader: bool)
+                                      shader_stages: &[ShaderStage])
                                       -> SubroutineData
 {
     if !program::is_subroutine_supported(ctxt) {
@@ -1211,9 +1670,6 @@ pub unsafe fn reflect_subroutine_data(ctxt: &mut CommandContext<'_>, program: Ha
         Handle::Id(id) => id
     };
 
-    let shader_stages = get_shader_stages(has_geometry_shader,
-                                          has_tessellation_control_shader,
-                                          has_tessellation_evaluation_shader);
     let mut subroutine_uniforms = HashMap::with_hasher(Default::default());
     let mut location_counts = HashMap::with_hasher(Default::default());
     for stage in shader_stages.iter() {
@@ -1226,9 +1682,18 @@ pub unsafe fn reflect_subroutine_data(ctxt: &mut CommandContext<'_>, program: Ha
         ctxt.gl.GetProgramStageiv(program, stage.to_gl_enum(),
                                   gl::ACTIVE_SUBROUTINE_UNIFORMS,
                                   &mut subroutine_count);
+        // The names are not limited in length, so we query the size of the buffers to use.
+        let mut uniform_name_max_len: gl::types::GLint = 0;
+        ctxt.gl.GetProgramStageiv(program, stage.to_gl_enum(),
+                                  gl::ACTIVE_SUBROUTINE_UNIFORM_MAX_LENGTH,
+                                  &mut uniform_name_max_len);
+        let mut subroutine_name_max_len: gl::types::GLint = 0;
+        ctxt.gl.GetProgramStageiv(program, stage.to_gl_enum(),
+                                  gl::ACTIVE_SUBROUTINE_MAX_LENGTH,
+                                  &mut subroutine_name_max_len);
         for i in 0..subroutine_count {
             // Get the name of the uniform
-            let mut uniform_name_tmp: Vec<u8> = vec![0; 64];
+            let mut uniform_name_tmp: Vec<u8> = vec![0; uniform_name_max_len as usize + 1];
             let mut name_len: gl::types::GLsizei = 0;
             ctxt.gl.GetActiveSubroutineUniformName(program, stage.to_gl_enum(),
                                                    i as gl::types::GLuint,
@@ -1265,7 +1730,7 @@ pub unsafe fn reflect_subroutine_data(ctxt: &mut CommandContext<'_>, program: Ha
             let mut compatible_subroutines: Vec<Subroutine> = Vec::new();
             for j in 0..compatible_count {
                 // Get the names of compatible subroutines.
-                let mut subroutine_name_tmp: Vec<u8> = vec![0; 64];
+                let mut subroutine_name_tmp: Vec<u8> = vec![0; subroutine_name_max_len as usize + 1];
                 let mut name_len: gl::types::GLsizei = 0;
                 ctxt.gl.GetActiveSubroutineName(program, stage.to_gl_enum(), compatible_sr_indices[j as usize],
                                                 subroutine_name_tmp.len() as gl::types::GLint,
@@ -1296,3 +1761,32 @@ pub unsafe fn reflect_subroutine_data(ctxt: &mut CommandContext<'_>, program: Ha
         subroutine_uniforms
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::parse_image_formats;
+    use crate::uniforms::ImageUnitFormat;
+
+    #[test]
+    fn image_format_qualifiers() {
+        let source = "
+            #version 430
+            layout(local_size_x = 1) in;
+            layout(rgba8, binding = 0) uniform writeonly image2D colors;
+            layout ( r32ui ) coherent uniform uimage2D counters, others;
+            // layout(r8) uniform image2D commented;
+            /* layout(r16) uniform image2D commented_block; */
+            layout(binding = 2, r32f) uniform image2DArray layers[4];
+            layout(std140) uniform Block { vec4 value; };
+            uniform image2D no_format;
+        ";
+
+        let formats = parse_image_formats(source);
+        assert_eq!(formats, vec![
+            ("colors".to_owned(), ImageUnitFormat::RGBA8),
+            ("counters".to_owned(), ImageUnitFormat::R32UI),
+            ("others".to_owned(), ImageUnitFormat::R32UI),
+            ("layers".to_owned(), ImageUnitFormat::R32F),
+        ]);
+    }
+}
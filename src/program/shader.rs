@@ -19,6 +19,7 @@ use crate::program::{ProgramCreationError, ShaderType};
 pub struct Shader {
     context: Rc<Context>,
     id: Handle,
+    shader_type: gl::types::GLenum,
 }
 
 impl GlObject for Shader {
@@ -53,6 +54,19 @@ impl Drop for Shader {
 /// Builds an individual shader.
 pub fn build_shader<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum, source_code: &str)
                        -> Result<Shader, ProgramCreationError> where F: Facade
+{
+    let shader = start_shader_compilation(facade, shader_type, source_code)?;
+    check_shader_compilation(&shader)?;
+    Ok(shader)
+}
+
+/// Sends the source code of a shader to the driver and starts compiling it, without waiting
+/// for the result.
+///
+/// Use `check_shader_compilation` to obtain the result.
+pub fn start_shader_compilation<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum,
+                                           source_code: &str)
+                                           -> Result<Shader, ProgramCreationError> where F: Facade
 {
     unsafe {
         let mut ctxt = facade.get_context().make_current();
@@ -112,6 +126,23 @@ pub fn build_shader<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum, sourc
             ctxt.report_debug_output_errors.set(true);
         }
 
+        Ok(Shader {
+            context: facade.get_context().clone(),
+            id,
+            shader_type,
+        })
+    }
+}
+
+/// Waits for the compilation of a shader to be finished and returns the compilation error,
+/// if any.
+pub fn check_shader_compilation(shader: &Shader) -> Result<(), ProgramCreationError> {
+    let id = shader.id;
+    let shader_type = shader.shader_type;
+
+    unsafe {
+        let ctxt = shader.context.make_current();
+
         // checking compilation success by reading a flag on the shader
         let compilation_success = {
             let mut compilation_success: gl::types::GLint = 0;
@@ -131,10 +162,7 @@ pub fn build_shader<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum, sourc
         };
 
         if compilation_success == 1 {
-            Ok(Shader {
-                context: facade.get_context().clone(),
-                id
-            })
+            Ok(())
 
         } else {
             // compilation error
@@ -215,6 +243,13 @@ pub fn check_shader_type_compatibility<C: ?Sized>(ctxt: &C, shader_type: gl::typ
                 return false;
             }
         },
+        gl::TASK_SHADER_NV | gl::MESH_SHADER_NV => {
+            if !ctxt.get_extensions().gl_nv_mesh_shader &&
+               !ctxt.get_extensions().gl_ext_mesh_shader
+            {
+                return false;
+            }
+        },
         _ => unreachable!()
     };
 
@@ -18,8 +18,7 @@ pub struct UniformsStorage {
                             BuildHasherDefault<FnvHasher>>>,
     uniform_blocks: RefCell<SmallVec<[Option<gl::types::GLuint>; 4]>>,
     shader_storage_blocks: RefCell<SmallVec<[Option<gl::types::GLuint>; 4]>>,
-    subroutine_uniforms: RefCell<HashMap<ShaderStage, Vec<gl::types::GLuint>,
-                                         BuildHasherDefault<FnvHasher>>>,
+    texture_units: RefCell<HashMap<gl::types::GLint, u16, BuildHasherDefault<FnvHasher>>>,
 }
 
 impl UniformsStorage {
@@ -30,10 +29,27 @@ impl UniformsStorage {
             values: RefCell::new(HashMap::with_hasher(Default::default())),
             uniform_blocks: RefCell::new(SmallVec::new()),
             shader_storage_blocks: RefCell::new(SmallVec::new()),
-            subroutine_uniforms: RefCell::new(HashMap::with_hasher(Default::default())),
+            texture_units: RefCell::new(HashMap::with_hasher(Default::default())),
         }
     }
 
+    /// Returns the texture unit that was last assigned to the sampler uniform at `location`.
+    #[inline]
+    pub fn get_texture_unit(&self, location: gl::types::GLint) -> Option<u16> {
+        self.texture_units.borrow().get(&location).cloned()
+    }
+
+    /// Assigns a texture unit to the sampler uniform at `location` and updates the value of
+    /// the uniform if necessary.
+    #[inline]
+    pub fn set_texture_unit(&self, ctxt: &mut CommandContext<'_>, program: Handle,
+                            location: gl::types::GLint, unit: u16)
+    {
+        self.texture_units.borrow_mut().insert(location, unit);
+        self.set_uniform_value(ctxt, program, location,
+                               &RawUniformValue::SignedInt(unit as gl::types::GLint));
+    }
+
     /// Compares `value` with the value stored in this object. If the values differ, updates
     /// the storage and calls `glUniform`.
     pub fn set_uniform_value(&self, ctxt: &mut CommandContext<'_>, program: Handle,
@@ -114,6 +130,7 @@ impl UniformsStorage {
             (&RawUniformValue::UnsignedInt64Vec2(a), &mut Some(RawUniformValue::UnsignedInt64Vec2(b))) if a == b => (),
             (&RawUniformValue::UnsignedInt64Vec3(a), &mut Some(RawUniformValue::UnsignedInt64Vec3(b))) if a == b => (),
             (&RawUniformValue::UnsignedInt64Vec4(a), &mut Some(RawUniformValue::UnsignedInt64Vec4(b))) if a == b => (),
+            (&RawUniformValue::TextureHandle(a), &mut Some(RawUniformValue::TextureHandle(b))) if a == b => (),
 
             (&RawUniformValue::SignedInt(v), target) => {
                 *target = Some(RawUniformValue::SignedInt(v));
@@ -309,6 +326,12 @@ impl UniformsStorage {
                 *target = Some(RawUniformValue::UnsignedInt64Vec4(v));
                 uniform_i64!(ctxt, Uniform4ui64vARB, location, 1, v.as_ptr() as *const gl::types::GLuint64);
             },
+
+            (&RawUniformValue::TextureHandle(v), target) => {
+                *target = Some(RawUniformValue::TextureHandle(v));
+                // the handle can only exist if bindless textures are supported
+                unsafe { ctxt.gl.UniformHandleui64ARB(location, v); }
+            },
         }
     }
 
@@ -374,22 +397,19 @@ impl UniformsStorage {
         }
     }
 
-    /// Compares `indices` to the value stored in this object. If the values differ,
-    /// updates the programs subroutine uniform bindings.
+    /// Updates the programs subroutine uniform bindings.
+    ///
+    /// Contrary to the other uniforms, the values are not cached. The subroutine uniforms of
+    /// all the stages are reset whenever `glUseProgram` or `glBindProgramPipeline` is called,
+    /// which means that we need to set them again before each draw command.
     pub fn set_subroutine_uniforms_for_stage(&self, ctxt: &mut CommandContext<'_>,
                                          program: Handle,
                                          stage: ShaderStage,
                                          indices: &[gl::types::GLuint])
     {
-        let mut subroutine_uniforms = self.subroutine_uniforms.borrow_mut();
-        if let Some(stored_indices) = subroutine_uniforms.get(&stage) {
-            if &stored_indices[..] == indices {
-                return
-            }
-        }
-        // TODO: don't assume that, instead use DSA if the program is not current
-        assert!(ctxt.state.program == program);
-        subroutine_uniforms.insert(stage, indices.to_vec());
+        // the program must be current, either directly or as a stage of the current pipeline
+        assert!(ctxt.state.program == program ||
+                (ctxt.state.program == Handle::Id(0) && ctxt.state.program_pipeline != 0));
         unsafe {
             ctxt.gl.UniformSubroutinesuiv(stage.to_gl_enum(), indices.len() as gl::types::GLsizei, indices.as_ptr() as *const _);
         }
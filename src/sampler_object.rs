@@ -57,6 +57,12 @@ impl SamplerObject {
 
                 ctxt.gl.SamplerParameterf(sampler, gl::TEXTURE_MAX_ANISOTROPY_EXT, value);
             }
+
+            if ctxt.extensions.gl_arb_seamless_cubemap_per_texture {
+                let value = if behavior.seamless_cubemap { gl::TRUE } else { gl::FALSE };
+                ctxt.gl.SamplerParameteri(sampler, gl::TEXTURE_CUBE_MAP_SEAMLESS,
+                                          value as gl::types::GLint);
+            }
         }
 
         SamplerObject {
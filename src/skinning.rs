@@ -0,0 +1,560 @@
+/*!
+Helpers for sending the bone palette of skinned meshes to the GPU.
+
+A `BonePalette` holds, for each bone of a skeleton, either a transformation matrix or a dual
+quaternion. It is meant to be updated every frame with `upload_matrices` or
+`upload_dual_quaternions`, which discard the previous content of the buffer so that the CPU never
+has to wait for the draw calls of the previous frame.
+
+Depending on the capabilities of the backend and on the number of bones, the palette is stored in
+a uniform buffer, a shader storage buffer or a buffer texture. The shaders must declare it
+accordingly, which is why the palette provides the GLSL code to use with `get_glsl_source`:
+
+```no_run
+# #[macro_use]
+# extern crate glium;
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+# let vertex_buffer = glium::vertex::EmptyVertexAttributes { len: 3 };
+# let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+# let fragment_source = "";
+# let mut frame = display.draw();
+use glium::Surface;
+use glium::skinning::{BonePalette, SkinningMode};
+
+let mut palette = BonePalette::new(&display, SkinningMode::Matrices, 64).unwrap();
+
+let vertex_source = format!("
+    #version 140
+
+    {}
+
+    in vec3 position;
+    in ivec4 bone_ids;
+    in vec4 bone_weights;
+
+    void main() {{
+        mat4 skin = get_bone_matrix(bone_ids.x) * bone_weights.x +
+                    get_bone_matrix(bone_ids.y) * bone_weights.y +
+                    get_bone_matrix(bone_ids.z) * bone_weights.z +
+                    get_bone_matrix(bone_ids.w) * bone_weights.w;
+        gl_Position = skin * vec4(position, 1.0);
+    }}
+", palette.get_glsl_source());
+
+let program = glium::Program::from_source(&display, &vertex_source, fragment_source,
+                                          None).unwrap();
+
+// every frame
+let identity = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0f32]];
+palette.upload_matrices(&[identity; 64]);
+
+frame.draw(vertex_buffer, &indices, &program, &uniform!{ bones: &palette },
+           &Default::default()).unwrap();
+# }
+```
+
+The GLSL code requires GLSL 1.40 for uniform buffers and buffer textures, and GLSL 4.30 for shader
+storage buffers.
+
+# Dual quaternions
+
+With `SkinningMode::DualQuaternions`, each bone is a unit dual quaternion `[real, dual]`, which
+avoids the volume loss of linear blend skinning. Rigid transformation matrices can be converted
+with `matrix_to_dual_quaternion`. The GLSL code then provides `get_bone_dual_quaternion`, which
+returns a `mat2x4`, and `transform_by_dual_quaternion`, which applies a normalized dual quaternion
+to a position.
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::slice;
+
+use crate::CapabilitiesSource;
+use crate::backend::Facade;
+use crate::buffer::{Buffer, BufferCreationError, BufferMode, BufferType};
+use crate::program;
+use crate::texture::buffer_texture::{BufferTexture, BufferTextureType, TextureCreationError};
+use crate::texture::buffer_texture::CreationError as BufferTextureCreationError;
+use crate::uniforms::{AsUniformValue, LayoutMismatchError, UniformBlock, UniformValue};
+use crate::version::{Api, Version};
+
+/// Error that can happen while building a `BonePalette`.
+#[derive(Copy, Clone, Debug)]
+pub enum CreationError {
+    /// None of the storages is supported by the backend for this number of bones.
+    NotSupported,
+
+    /// Failed to create the buffer.
+    BufferCreationError(BufferCreationError),
+
+    /// Failed to create the buffer texture.
+    TextureCreationError(TextureCreationError),
+}
+
+impl fmt::Display for CreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::CreationError::*;
+        let desc = match *self {
+            NotSupported =>
+                "None of the storages is supported by the backend for this number of bones",
+            BufferCreationError(_) =>
+                "Failed to create the buffer",
+            TextureCreationError(_) =>
+                "Failed to create the buffer texture",
+        };
+        fmt.write_str(desc)
+    }
+}
+
+impl Error for CreationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use self::CreationError::*;
+        match *self {
+            NotSupported => None,
+            BufferCreationError(ref err) => Some(err),
+            TextureCreationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<BufferCreationError> for CreationError {
+    #[inline]
+    fn from(err: BufferCreationError) -> CreationError {
+        CreationError::BufferCreationError(err)
+    }
+}
+
+impl From<TextureCreationError> for CreationError {
+    #[inline]
+    fn from(err: TextureCreationError) -> CreationError {
+        CreationError::TextureCreationError(err)
+    }
+}
+
+impl From<BufferTextureCreationError> for CreationError {
+    #[inline]
+    fn from(err: BufferTextureCreationError) -> CreationError {
+        match err {
+            BufferTextureCreationError::BufferCreationError(err) => err.into(),
+            BufferTextureCreationError::TextureCreationError(err) => err.into(),
+        }
+    }
+}
+
+/// How the bones are represented.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SkinningMode {
+    /// Each bone is a column-major 4x4 transformation matrix.
+    Matrices,
+
+    /// Each bone is a unit dual quaternion, made of a real part and a dual part.
+    DualQuaternions,
+}
+
+impl SkinningMode {
+    /// Returns the number of `vec4`s that are used to store one bone.
+    #[inline]
+    pub fn get_vec4s_per_bone(&self) -> usize {
+        match *self {
+            SkinningMode::Matrices => 4,
+            SkinningMode::DualQuaternions => 2,
+        }
+    }
+}
+
+/// Where the bones are stored on the GPU.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SkinningStorage {
+    /// A uniform buffer, accessed through a uniform block. This is usually the fastest storage,
+    /// but the size of uniform blocks is limited.
+    UniformBuffer,
+
+    /// A shader storage buffer, accessed through a buffer block.
+    ShaderStorageBuffer,
+
+    /// A buffer texture, accessed through a `samplerBuffer`.
+    BufferTexture,
+}
+
+impl SkinningStorage {
+    /// Returns true if this storage can be used for the given number of bones.
+    pub fn is_supported<C: ?Sized>(&self, caps: &C, mode: SkinningMode, max_bones: usize) -> bool
+                                   where C: CapabilitiesSource
+    {
+        let vec4s = max_bones * mode.get_vec4s_per_bone();
+
+        match *self {
+            SkinningStorage::UniformBuffer => {
+                caps.get_capabilities().max_uniform_block_size
+                    .map_or(false, |max| vec4s * 16 <= max as usize)
+            },
+            SkinningStorage::ShaderStorageBuffer => {
+                caps.get_version() >= &Version(Api::Gl, 4, 3) ||
+                caps.get_version() >= &Version(Api::GlEs, 3, 1) ||
+                caps.get_extensions().gl_arb_shader_storage_buffer_object
+            },
+            SkinningStorage::BufferTexture => {
+                caps.get_capabilities().max_texture_buffer_size
+                    .map_or(false, |max| vec4s <= max as usize)
+            },
+        }
+    }
+
+    /// Returns the preferred storage for the given number of bones, or `None` if none is
+    /// supported.
+    ///
+    /// Uniform buffers are preferred, then shader storage buffers, then buffer textures.
+    pub fn choose<C: ?Sized>(caps: &C, mode: SkinningMode, max_bones: usize)
+                             -> Option<SkinningStorage> where C: CapabilitiesSource
+    {
+        [SkinningStorage::UniformBuffer, SkinningStorage::ShaderStorageBuffer,
+         SkinningStorage::BufferTexture]
+            .iter()
+            .cloned()
+            .find(|s| s.is_supported(caps, mode, max_bones))
+    }
+}
+
+enum PaletteBuffer {
+    Buffer(Buffer<[[f32; 4]]>),
+    Texture(BufferTexture<[f32; 4]>),
+}
+
+/// The bones of a skeleton, stored on the GPU.
+///
+/// See the module-level documentation for more infos.
+pub struct BonePalette {
+    buffer: PaletteBuffer,
+    mode: SkinningMode,
+    storage: SkinningStorage,
+    max_bones: usize,
+    len: usize,
+}
+
+impl BonePalette {
+    /// Builds a palette that can hold `max_bones` bones, with the storage returned by
+    /// `SkinningStorage::choose`.
+    pub fn new<F: ?Sized>(facade: &F, mode: SkinningMode, max_bones: usize)
+                          -> Result<BonePalette, CreationError> where F: Facade
+    {
+        let storage = SkinningStorage::choose(&*facade.get_context(), mode, max_bones)
+                                      .ok_or(CreationError::NotSupported)?;
+        BonePalette::with_storage(facade, mode, storage, max_bones)
+    }
+
+    /// Builds a palette that can hold `max_bones` bones, with the given storage.
+    pub fn with_storage<F: ?Sized>(facade: &F, mode: SkinningMode, storage: SkinningStorage,
+                                   max_bones: usize) -> Result<BonePalette, CreationError>
+                                   where F: Facade
+    {
+        if !storage.is_supported(&*facade.get_context(), mode, max_bones) {
+            return Err(CreationError::NotSupported);
+        }
+
+        let len = max_bones * mode.get_vec4s_per_bone();
+
+        let buffer = match storage {
+            SkinningStorage::UniformBuffer => {
+                PaletteBuffer::Buffer(Buffer::empty_array(facade, BufferType::UniformBuffer, len,
+                                                          BufferMode::Dynamic)?)
+            },
+            SkinningStorage::ShaderStorageBuffer => {
+                PaletteBuffer::Buffer(Buffer::empty_array(facade, BufferType::ShaderStorageBuffer,
+                                                          len, BufferMode::Dynamic)?)
+            },
+            SkinningStorage::BufferTexture => {
+                PaletteBuffer::Texture(BufferTexture::empty_dynamic(facade, len,
+                                                                    BufferTextureType::Float)?)
+            },
+        };
+
+        Ok(BonePalette {
+            buffer,
+            mode,
+            storage,
+            max_bones,
+            len: 0,
+        })
+    }
+
+    /// Returns how the bones are represented.
+    #[inline]
+    pub fn get_mode(&self) -> SkinningMode {
+        self.mode
+    }
+
+    /// Returns where the bones are stored.
+    #[inline]
+    pub fn get_storage(&self) -> SkinningStorage {
+        self.storage
+    }
+
+    /// Returns the maximum number of bones.
+    #[inline]
+    pub fn get_max_bones(&self) -> usize {
+        self.max_bones
+    }
+
+    /// Returns the number of bones that have been uploaded the last time.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no bone has been uploaded.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the buffer that contains the bones, as a list of `vec4`s.
+    #[inline]
+    pub fn get_buffer(&self) -> &Buffer<[[f32; 4]]> {
+        match self.buffer {
+            PaletteBuffer::Buffer(ref buffer) => buffer,
+            PaletteBuffer::Texture(ref texture) => texture,
+        }
+    }
+
+    /// Replaces the bones with new transformation matrices.
+    ///
+    /// The previous content of the palette is discarded, which means that this function doesn't
+    /// wait for the draw calls that are still using it.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the mode of the palette is not `SkinningMode::Matrices` or if there are more
+    /// matrices than the maximum number of bones.
+    pub fn upload_matrices(&mut self, matrices: &[[[f32; 4]; 4]]) {
+        assert_eq!(self.mode, SkinningMode::Matrices);
+
+        // a `[[f32; 4]; 4]` has the same layout as four `[f32; 4]`
+        let vec4s = unsafe {
+            slice::from_raw_parts(matrices.as_ptr() as *const [f32; 4], matrices.len() * 4)
+        };
+
+        self.upload(vec4s, matrices.len());
+    }
+
+    /// Replaces the bones with new dual quaternions.
+    ///
+    /// The previous content of the palette is discarded, which means that this function doesn't
+    /// wait for the draw calls that are still using it.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the mode of the palette is not `SkinningMode::DualQuaternions` or if there are
+    /// more dual quaternions than the maximum number of bones.
+    pub fn upload_dual_quaternions(&mut self, dual_quaternions: &[[[f32; 4]; 2]]) {
+        assert_eq!(self.mode, SkinningMode::DualQuaternions);
+
+        // a `[[f32; 4]; 2]` has the same layout as two `[f32; 4]`
+        let vec4s = unsafe {
+            slice::from_raw_parts(dual_quaternions.as_ptr() as *const [f32; 4],
+                                  dual_quaternions.len() * 2)
+        };
+
+        self.upload(vec4s, dual_quaternions.len());
+    }
+
+    fn upload(&mut self, vec4s: &[[f32; 4]], bones: usize) {
+        assert!(bones <= self.max_bones, "Too many bones");
+
+        let buffer = self.get_buffer();
+        if vec4s.len() == buffer.len() {
+            buffer.write_discard(vec4s);
+        } else if !vec4s.is_empty() {
+            buffer.slice(0 .. vec4s.len()).unwrap().write_discard(vec4s);
+        }
+
+        self.len = bones;
+    }
+
+    /// Returns the GLSL code that declares the palette under the name `bones`, and the functions
+    /// to access it.
+    ///
+    /// With `SkinningMode::Matrices`, this declares `mat4 get_bone_matrix(int index)`. With
+    /// `SkinningMode::DualQuaternions`, this declares `mat2x4 get_bone_dual_quaternion(int index)`
+    /// and `vec3 transform_by_dual_quaternion(mat2x4 dq, vec3 position)`.
+    pub fn get_glsl_source(&self) -> String {
+        let vec4s = self.mode.get_vec4s_per_bone();
+
+        let (declaration, fetch) = match self.storage {
+            SkinningStorage::UniformBuffer => {
+                let ty = match self.mode {
+                    SkinningMode::Matrices => format!("mat4 bones_data[{}]", self.max_bones),
+                    SkinningMode::DualQuaternions => format!("vec4 bones_data[{}]",
+                                                             self.max_bones * vec4s),
+                };
+                (format!("layout(std140) uniform bones {{\n    {};\n}};\n", ty),
+                 "bones_data[{}]")
+            },
+            SkinningStorage::ShaderStorageBuffer => {
+                let ty = match self.mode {
+                    SkinningMode::Matrices => "mat4 bones_data[]",
+                    SkinningMode::DualQuaternions => "vec4 bones_data[]",
+                };
+                (format!("layout(std430) readonly buffer bones {{\n    {};\n}};\n", ty),
+                 "bones_data[{}]")
+            },
+            SkinningStorage::BufferTexture => {
+                ("uniform samplerBuffer bones;\n".to_owned(), "texelFetch(bones, {})")
+            },
+        };
+
+        // builds the expression that fetches the `n`th vec4 of the bone at `index`
+        let fetch = |n: usize| fetch.replace("{}", &format!("index * {} + {}", vec4s, n));
+
+        let mut source = declaration;
+
+        match (self.mode, self.storage) {
+            (SkinningMode::Matrices, SkinningStorage::BufferTexture) => {
+                source.push_str(&format!("mat4 get_bone_matrix(int index) {{\n    \
+                                              return mat4({}, {}, {}, {});\n}}\n",
+                                         fetch(0), fetch(1), fetch(2), fetch(3)));
+            },
+            (SkinningMode::Matrices, _) => {
+                source.push_str("mat4 get_bone_matrix(int index) {\n    \
+                                     return bones_data[index];\n}\n");
+            },
+            (SkinningMode::DualQuaternions, _) => {
+                source.push_str(&format!("mat2x4 get_bone_dual_quaternion(int index) {{\n    \
+                                              return mat2x4({}, {});\n}}\n",
+                                         fetch(0), fetch(1)));
+                source.push_str("vec3 transform_by_dual_quaternion(mat2x4 dq, vec3 position) {\n    \
+                                     vec4 r = dq[0];\n    \
+                                     vec4 d = dq[1];\n    \
+                                     vec3 rotated = position + 2.0 * cross(r.xyz, cross(r.xyz, position) + r.w * position);\n    \
+                                     return rotated + 2.0 * (r.w * d.xyz - d.w * r.xyz + cross(r.xyz, d.xyz));\n}\n");
+            },
+        }
+
+        source
+    }
+}
+
+impl AsUniformValue for BonePalette {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        fn matrices_layout(block: &program::UniformBlock) -> Result<(), LayoutMismatchError> {
+            <[[[f32; 4]; 4]] as UniformBlock>::matches(&block.layout, 0)
+        }
+
+        fn vec4s_layout(block: &program::UniformBlock) -> Result<(), LayoutMismatchError> {
+            <[[f32; 4]] as UniformBlock>::matches(&block.layout, 0)
+        }
+
+        match self.buffer {
+            PaletteBuffer::Texture(ref texture) => texture.as_uniform_value(),
+            PaletteBuffer::Buffer(ref buffer) => {
+                let layout = match self.mode {
+                    SkinningMode::Matrices => matrices_layout,
+                    SkinningMode::DualQuaternions => vec4s_layout,
+                };
+
+                UniformValue::Block(buffer.as_slice_any(), layout)
+            },
+        }
+    }
+}
+
+impl<'a> AsUniformValue for &'a BonePalette {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        (*self).as_uniform_value()
+    }
+}
+
+/// Converts a rigid transformation matrix, that only contains a rotation and a translation, to
+/// a unit dual quaternion `[real, dual]`.
+///
+/// The matrix is column-major, like the matrices that are passed to `upload_matrices`.
+pub fn matrix_to_dual_quaternion(matrix: &[[f32; 4]; 4]) -> [[f32; 4]; 2] {
+    // element at row `r` and column `c`
+    let m = |r: usize, c: usize| matrix[c][r];
+
+    let trace = m(0, 0) + m(1, 1) + m(2, 2);
+
+    let (x, y, z, w) = if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        ((m(2, 1) - m(1, 2)) / s, (m(0, 2) - m(2, 0)) / s, (m(1, 0) - m(0, 1)) / s, 0.25 * s)
+    } else if m(0, 0) > m(1, 1) && m(0, 0) > m(2, 2) {
+        let s = (1.0 + m(0, 0) - m(1, 1) - m(2, 2)).sqrt() * 2.0;
+        (0.25 * s, (m(0, 1) + m(1, 0)) / s, (m(0, 2) + m(2, 0)) / s, (m(2, 1) - m(1, 2)) / s)
+    } else if m(1, 1) > m(2, 2) {
+        let s = (1.0 + m(1, 1) - m(0, 0) - m(2, 2)).sqrt() * 2.0;
+        ((m(0, 1) + m(1, 0)) / s, 0.25 * s, (m(1, 2) + m(2, 1)) / s, (m(0, 2) - m(2, 0)) / s)
+    } else {
+        let s = (1.0 + m(2, 2) - m(0, 0) - m(1, 1)).sqrt() * 2.0;
+        ((m(0, 2) + m(2, 0)) / s, (m(1, 2) + m(2, 1)) / s, 0.25 * s, (m(1, 0) - m(0, 1)) / s)
+    };
+
+    let (tx, ty, tz) = (matrix[3][0], matrix[3][1], matrix[3][2]);
+
+    // dual = 0.5 * translation * real
+    let dual = [
+        0.5 * (w * tx + ty * z - tz * y),
+        0.5 * (w * ty + tz * x - tx * z),
+        0.5 * (w * tz + tx * y - ty * x),
+        -0.5 * (tx * x + ty * y + tz * z),
+    ];
+
+    [[x, y, z, w], dual]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::matrix_to_dual_quaternion;
+
+    fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+        [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+    }
+
+    // same as `transform_by_dual_quaternion` in the GLSL code
+    fn transform(dq: [[f32; 4]; 2], p: [f32; 3]) -> [f32; 3] {
+        let (r, d) = (dq[0], dq[1]);
+        let rv = [r[0], r[1], r[2]];
+        let dv = [d[0], d[1], d[2]];
+
+        let inner = cross(rv, p);
+        let inner = [inner[0] + r[3] * p[0], inner[1] + r[3] * p[1], inner[2] + r[3] * p[2]];
+        let rot = cross(rv, inner);
+        let tr = cross(rv, dv);
+
+        let mut result = [0.0; 3];
+        for i in 0 .. 3 {
+            result[i] = p[i] + 2.0 * rot[i] + 2.0 * (r[3] * dv[i] - d[3] * rv[i] + tr[i]);
+        }
+        result
+    }
+
+    #[test]
+    fn dual_quaternion_matches_matrix() {
+        let (sin, cos) = 0.7f32.sin_cos();
+
+        let matrices = [
+            // rotation around Z and translation
+            [[cos, sin, 0.0, 0.0], [-sin, cos, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [1.0, 2.0, 3.0, 1.0]],
+            // rotation of 180 degrees around X
+            [[1.0, 0.0, 0.0, 0.0], [0.0, -1.0, 0.0, 0.0], [0.0, 0.0, -1.0, 0.0], [0.0, -4.0, 0.5, 1.0]],
+        ];
+
+        for m in matrices.iter() {
+            let dq = matrix_to_dual_quaternion(m);
+            let p = [0.3, -1.2, 2.5];
+
+            let expected = [
+                m[0][0] * p[0] + m[1][0] * p[1] + m[2][0] * p[2] + m[3][0],
+                m[0][1] * p[0] + m[1][1] * p[1] + m[2][1] * p[2] + m[3][1],
+                m[0][2] * p[0] + m[1][2] * p[1] + m[2][2] * p[2] + m[3][2],
+            ];
+
+            let obtained = transform(dq, p);
+            for i in 0 .. 3 {
+                assert!((expected[i] - obtained[i]).abs() < 1e-4, "{:?} {:?}", expected, obtained);
+            }
+        }
+    }
+}
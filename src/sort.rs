@@ -0,0 +1,325 @@
+/*!
+Sorting buffers on the GPU.
+
+A `BufferSorter` sorts a buffer of `u32` keys in ascending order with compute shaders, and can
+reorder a buffer of values along with the keys. This is what you need in order to draw
+transparent particles from back to front, or to group lights by cluster, without reading the
+data back to the CPU.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+use glium::buffer::{Buffer, BufferMode, BufferType};
+use glium::sort::BufferSorter;
+
+let sorter = BufferSorter::new(&display).unwrap();
+
+let keys = Buffer::new(&display, &[5u32, 3, 8, 1][..], BufferType::ShaderStorageBuffer,
+                       BufferMode::Default).unwrap();
+let particles = Buffer::new(&display, &[0u32, 1, 2, 3][..], BufferType::ShaderStorageBuffer,
+                            BufferMode::Default).unwrap();
+
+sorter.sort(&keys, &particles);
+assert_eq!(particles.read().unwrap(), vec![3, 1, 0, 2]);
+```
+
+The keys are compared as unsigned integers. In order to sort by a positive `f32`, such as a
+distance to the camera, use the bits of the float with `f32::to_bits`: their order is the same
+as the order of the floats. In order to sort in descending order, use `!key` instead of `key`.
+
+The sort is a bitonic sort, which is not stable: values whose keys are equal can be reordered.
+It requires compute shaders and shader storage buffers, which means OpenGL 4.3 or
+OpenGL ES 3.1. See `BufferSorter::is_supported`.
+
+*/
+use std::error::Error;
+use std::fmt;
+
+use crate::CapabilitiesSource;
+use crate::backend::Facade;
+use crate::buffer::BufferSlice;
+use crate::program::{ComputeShader, ProgramCreationError};
+use crate::uniforms::ShaderStorageSlice;
+use crate::version::{Api, Version};
+
+/// Number of invocations of each work group of the compute shaders.
+const WORK_GROUP_SIZE: usize = 256;
+
+/// Error that can happen while building a `BufferSorter`.
+#[derive(Clone, Debug)]
+pub enum CreationError {
+    /// Compute shaders or shader storage buffers are not supported by the backend.
+    NotSupported,
+
+    /// Failed to compile the compute shaders.
+    ProgramCreationError(ProgramCreationError),
+}
+
+impl fmt::Display for CreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::CreationError::*;
+        match *self {
+            NotSupported =>
+                write!(fmt, "Compute shaders or shader storage buffers are not supported by \
+                             the backend"),
+            ProgramCreationError(ref err) =>
+                write!(fmt, "Failed to compile the compute shaders: {}", err),
+        }
+    }
+}
+
+impl Error for CreationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use self::CreationError::*;
+        match *self {
+            NotSupported => None,
+            ProgramCreationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<ProgramCreationError> for CreationError {
+    #[inline]
+    fn from(err: ProgramCreationError) -> CreationError {
+        CreationError::ProgramCreationError(err)
+    }
+}
+
+/// Sorts buffers of `u32` keys on the GPU.
+///
+/// Building a sorter compiles its compute shaders, so you should build it once and reuse it.
+///
+/// The number of elements that can be sorted is limited to about 33 million by the minimum
+/// number of work groups that backends must support.
+pub struct BufferSorter {
+    keys: ComputeShader,
+    keys_values: ComputeShader,
+}
+
+impl BufferSorter {
+    /// Returns true if the backend supports sorting buffers.
+    pub fn is_supported<C: ?Sized>(caps: &C) -> bool where C: CapabilitiesSource {
+        ComputeShader::is_supported(caps) &&
+            (caps.get_version() >= &Version(Api::Gl, 4, 3) ||
+             caps.get_version() >= &Version(Api::GlEs, 3, 1) ||
+             caps.get_extensions().gl_arb_shader_storage_buffer_object)
+    }
+
+    /// Builds a new sorter.
+    pub fn new<F: ?Sized>(facade: &F) -> Result<BufferSorter, CreationError> where F: Facade {
+        if !BufferSorter::is_supported(&**facade.get_context()) {
+            return Err(CreationError::NotSupported);
+        }
+
+        let es = facade.get_context().get_version().0 == Api::GlEs;
+
+        Ok(BufferSorter {
+            keys: ComputeShader::from_source(facade, &shader_source(es, false))?,
+            keys_values: ComputeShader::from_source(facade, &shader_source(es, true))?,
+        })
+    }
+
+    /// Sorts the keys in ascending order.
+    pub fn sort_keys<'a, K>(&self, keys: K) where K: Into<BufferSlice<'a, [u32]>> {
+        let keys = keys.into();
+        self.run(&self.keys, keys, None);
+    }
+
+    /// Sorts the keys in ascending order, and applies the same permutation to the values.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if the two buffers don't have the same length.
+    pub fn sort<'a, 'b, K, V>(&self, keys: K, values: V)
+                              where K: Into<BufferSlice<'a, [u32]>>,
+                                    V: Into<BufferSlice<'b, [u32]>>
+    {
+        let keys = keys.into();
+        let values = values.into();
+        assert_eq!(keys.len(), values.len(), "The keys and the values must have the same length");
+        self.run(&self.keys_values, keys, Some(values));
+    }
+
+    fn run(&self, program: &ComputeShader, keys: BufferSlice<'_, [u32]>,
+           values: Option<BufferSlice<'_, [u32]>>)
+    {
+        let count = keys.len();
+        if count < 2 {
+            return;
+        }
+
+        // the sort operates on a power of two number of elements, the missing ones being
+        // considered greater than all the others
+        let size = count.next_power_of_two();
+
+        let execute = |block_size: usize, distance: usize, local_steps: bool| {
+            // each invocation compares two elements
+            let groups = if local_steps {
+                size.div_ceil(2 * WORK_GROUP_SIZE)
+            } else {
+                (size / 2).div_ceil(WORK_GROUP_SIZE)
+            };
+
+            // `BufferSlice<[u32]>` isn't `Copy`, so we reslice the whole buffer for each dispatch
+            let uniforms = keys.slice(..).unwrap().bind_as_ssbo("Keys")
+                               .add("count", count as u32)
+                               .add("block_size", block_size as u32)
+                               .add("distance", distance as u32)
+                               .add("local_steps", local_steps);
+
+            match values {
+                Some(ref values) => {
+                    let values = values.slice(..).unwrap();
+                    let uniforms = uniforms.add("Values", ShaderStorageSlice::new(values));
+                    program.execute(uniforms, groups as u32, 1, 1);
+                },
+                None => program.execute(uniforms, groups as u32, 1, 1),
+            }
+        };
+
+        let mut block_size = 2;
+        while block_size <= size {
+            let mut distance = block_size / 2;
+            while distance > 0 {
+                // the last steps of each stage only compare elements that are close to each
+                // other, and are done in one dispatch with shared memory
+                if distance <= WORK_GROUP_SIZE {
+                    execute(block_size, distance, true);
+                    break;
+                }
+
+                execute(block_size, distance, false);
+                distance /= 2;
+            }
+
+            block_size *= 2;
+        }
+    }
+}
+
+/// Returns the source code of the compute shader.
+fn shader_source(es: bool, with_values: bool) -> String {
+    format!("
+        #version {version}
+        {define}
+        layout(local_size_x = {group_size}) in;
+
+        layout(std430) buffer Keys {{
+            uint keys[];
+        }};
+
+        #ifdef WITH_VALUES
+        layout(std430) buffer Values {{
+            uint values[];
+        }};
+        #endif
+
+        uniform uint count;
+        uniform uint block_size;
+        uniform uint distance;
+        uniform bool local_steps;
+
+        shared uint local_keys[{local_size}];
+        // true for the elements past the end of the buffer, which are greater than all the
+        // keys, including the keys equal to 0xffffffff
+        shared bool local_padding[{local_size}];
+        #ifdef WITH_VALUES
+        shared uint local_values[{local_size}];
+        #endif
+
+        // Returns the indices of the two elements compared by the invocation `i` during a step.
+        // During the first step of each stage, the second half of each block is compared in
+        // reverse order, which means that all the comparisons are done in ascending order.
+        uvec2 get_pair(uint i, uint d) {{
+            uint low = i & (d - 1u);
+            uint left = ((i - low) << 1u) + low;
+
+            if (d == block_size / 2u) {{
+                uint start = left & ~(block_size - 1u);
+                return uvec2(left, start + block_size - 1u - (left - start));
+            }} else {{
+                return uvec2(left, left + d);
+            }}
+        }}
+
+        void global_step() {{
+            uvec2 pair = get_pair(gl_GlobalInvocationID.x, distance);
+            if (pair.y >= count) {{
+                return;
+            }}
+
+            uint a = keys[pair.x];
+            uint b = keys[pair.y];
+            if (a > b) {{
+                keys[pair.x] = b;
+                keys[pair.y] = a;
+                #ifdef WITH_VALUES
+                uint value = values[pair.x];
+                values[pair.x] = values[pair.y];
+                values[pair.y] = value;
+                #endif
+            }}
+        }}
+
+        void local_steps_() {{
+            uint base = gl_WorkGroupID.x * {local_size}u;
+            uint id = gl_LocalInvocationID.x;
+
+            for (uint i = id; i < {local_size}u; i += {group_size}u) {{
+                uint index = base + i;
+                local_keys[i] = index < count ? keys[index] : 0xffffffffu;
+                local_padding[i] = index >= count;
+                #ifdef WITH_VALUES
+                local_values[i] = index < count ? values[index] : 0u;
+                #endif
+            }}
+
+            memoryBarrierShared();
+            barrier();
+
+            for (uint d = distance; d > 0u; d >>= 1u) {{
+                uvec2 pair = get_pair(id, d);
+
+                uint a = local_keys[pair.x];
+                uint b = local_keys[pair.y];
+                bool padding_a = local_padding[pair.x];
+                bool padding_b = local_padding[pair.y];
+                if (a > b || (a == b && padding_a && !padding_b)) {{
+                    local_keys[pair.x] = b;
+                    local_keys[pair.y] = a;
+                    local_padding[pair.x] = padding_b;
+                    local_padding[pair.y] = padding_a;
+                    #ifdef WITH_VALUES
+                    uint value = local_values[pair.x];
+                    local_values[pair.x] = local_values[pair.y];
+                    local_values[pair.y] = value;
+                    #endif
+                }}
+
+                memoryBarrierShared();
+                barrier();
+            }}
+
+            for (uint i = id; i < {local_size}u; i += {group_size}u) {{
+                uint index = base + i;
+                if (index < count) {{
+                    keys[index] = local_keys[i];
+                    #ifdef WITH_VALUES
+                    values[index] = local_values[i];
+                    #endif
+                }}
+            }}
+        }}
+
+        void main() {{
+            if (local_steps) {{
+                local_steps_();
+            }} else {{
+                global_step();
+            }}
+        }}
+    ",
+    version = if es { "310 es" } else { "430" },
+    define = if with_values { "#define WITH_VALUES" } else { "" },
+    group_size = WORK_GROUP_SIZE,
+    local_size = 2 * WORK_GROUP_SIZE)
+}
@@ -6,7 +6,9 @@ use crate::gl;
 use crate::backend::Facade;
 use crate::context::Context;
 use crate::ContextExt;
+use std::collections::VecDeque;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use std::thread;
 
@@ -54,6 +56,15 @@ impl SyncFence {
             _ => panic!("Could not wait for the fence")
         };
     }
+
+    /// Returns true if the operation has finished on the server. Doesn't block.
+    pub fn is_signaled(&self) -> bool {
+        let sync = self.id.unwrap();
+
+        let mut ctxt = self.context.make_current();
+        matches!(unsafe { client_poll(&mut ctxt, sync) },
+                 gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED)
+    }
 }
 
 impl Drop for SyncFence {
@@ -69,6 +80,106 @@ impl Drop for SyncFence {
     }
 }
 
+/// Limits the number of frames that the GPU can lag behind the CPU.
+///
+/// Drivers are usually allowed to buffer several frames of commands. While this improves the
+/// throughput, it also increases the latency between the moment a frame is built and the moment
+/// it is shown on the screen.
+///
+/// A `FrameSync` inserts a fence at the end of each frame and, if too many frames are still
+/// being processed by the GPU, waits until the oldest one is finished. The maximum number of
+/// frames in flight includes the frame that is about to be built, which means that a value of
+/// `1` waits for each frame to be finished before starting the next one.
+///
+/// ## Example
+///
+/// ```no_run
+/// # use glium::Surface;
+/// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// let mut frame_sync = glium::FrameSync::new(2);
+///
+/// loop {
+///     let mut frame = display.draw();
+///     frame.clear_color(0.0, 0.0, 0.0, 0.0);
+///     frame.finish().unwrap();
+///
+///     let waited = frame_sync.end_frame(&display).unwrap();
+///     println!("Waited {:?} for the GPU", waited);
+/// }
+/// ```
+pub struct FrameSync {
+    max_frames_in_flight: usize,
+    fences: VecDeque<SyncFence>,
+    last_wait: Duration,
+}
+
+impl FrameSync {
+    /// Builds a new `FrameSync` that allows `max_frames_in_flight` frames in flight.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if `max_frames_in_flight` is 0.
+    #[inline]
+    pub fn new(max_frames_in_flight: usize) -> FrameSync {
+        assert!(max_frames_in_flight >= 1);
+
+        FrameSync {
+            max_frames_in_flight,
+            fences: VecDeque::with_capacity(max_frames_in_flight),
+            last_wait: Duration::new(0, 0),
+        }
+    }
+
+    /// Returns the maximum number of frames in flight.
+    #[inline]
+    pub fn get_max_frames_in_flight(&self) -> usize {
+        self.max_frames_in_flight
+    }
+
+    /// Changes the maximum number of frames in flight. Takes effect at the next call to
+    /// `end_frame`.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if `max_frames_in_flight` is 0.
+    #[inline]
+    pub fn set_max_frames_in_flight(&mut self, max_frames_in_flight: usize) {
+        assert!(max_frames_in_flight >= 1);
+        self.max_frames_in_flight = max_frames_in_flight;
+    }
+
+    /// Returns the number of frames that have been ended but that may still be processed by
+    /// the GPU.
+    #[inline]
+    pub fn get_frames_in_flight(&self) -> usize {
+        self.fences.len()
+    }
+
+    /// Returns how long the last call to `end_frame` waited for the GPU.
+    #[inline]
+    pub fn get_last_wait_duration(&self) -> Duration {
+        self.last_wait
+    }
+
+    /// Marks the end of a frame. Call this after `Frame::finish`.
+    ///
+    /// Blocks until the number of frames being processed by the GPU is below the limit, and
+    /// returns how long it waited.
+    pub fn end_frame<F: ?Sized>(&mut self, facade: &F) -> Result<Duration, SyncNotSupportedError>
+                                where F: Facade
+    {
+        self.fences.push_back(SyncFence::new(facade)?);
+
+        let before = Instant::now();
+        while self.fences.len() >= self.max_frames_in_flight {
+            self.fences.pop_front().unwrap().wait();
+        }
+
+        self.last_wait = before.elapsed();
+        Ok(self.last_wait)
+    }
+}
+
 /// Prototype for a `SyncFence`.
 ///
 /// The fence must be consumed with either `into_sync_fence`, otherwise
@@ -130,6 +241,15 @@ pub unsafe fn wait_linear_sync_fence_and_drop(mut fence: LinearSyncFence,
     delete_fence(ctxt, fence);
 }
 
+/// Returns true if the fence is signaled, from within the commands context. Doesn't block.
+#[inline]
+pub unsafe fn is_linear_sync_fence_signaled(ctxt: &mut CommandContext<'_>,
+                                            fence: &LinearSyncFence) -> bool
+{
+    matches!(client_poll(ctxt, fence.id.unwrap()),
+             gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED)
+}
+
 /// Destroys a fence, from within the commands context.
 #[inline]
 pub unsafe fn destroy_linear_sync_fence(ctxt: &mut CommandContext<'_>, mut fence: LinearSyncFence) {
@@ -180,6 +300,26 @@ unsafe fn client_wait(ctxt: &mut CommandContext<'_>, fence: gl::types::GLsync) -
     }
 }
 
+/// Calls `glClientWaitSync` without waiting and returns the result.
+///
+/// The commands queue is flushed, so that the fence is eventually signaled.
+///
+/// # Unsafety
+///
+/// The fence object must exist.
+///
+unsafe fn client_poll(ctxt: &mut CommandContext<'_>, fence: gl::types::GLsync) -> gl::types::GLenum {
+    if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+       ctxt.version >= &Version(Api::GlEs, 3, 0) || ctxt.extensions.gl_arb_sync
+    {
+        ctxt.gl.ClientWaitSync(fence, gl::SYNC_FLUSH_COMMANDS_BIT, 0)
+    } else if ctxt.extensions.gl_apple_sync {
+        ctxt.gl.ClientWaitSyncAPPLE(fence, gl::SYNC_FLUSH_COMMANDS_BIT_APPLE, 0)
+    } else {
+        unreachable!();
+    }
+}
+
 /// Deletes a fence.
 ///
 /// # Unsafety
@@ -11,18 +11,25 @@ use crate::TextureExt;
 use crate::TextureMipmapExt;
 use crate::version::Api;
 use crate::Rect;
+use crate::BlitTarget;
+use crate::transfer::Transferable;
 
-use crate::image_format::{self, TextureFormatRequest, ClientFormatAny};
+use crate::image_format::{self, TextureFormatRequest, ClientFormat, ClientFormatAny};
 use crate::texture::Texture2dDataSink;
 use crate::texture::TextureKind;
 use crate::texture::{MipmapsOption, TextureFormat, TextureCreationError, CubeLayer};
+use crate::texture::{CopyImageError, Swizzle, SwizzleNotSupportedError};
+use crate::texture::{DepthStencilTextureMode, StencilTexturingNotSupportedError};
+use crate::texture::BaseLevelNotSupportedError;
 use crate::texture::{get_format, InternalFormat, GetFormatError};
 use crate::texture::pixel::PixelValue;
 use crate::texture::pixel_buffer::PixelBuffer;
 
 use crate::fbo::ClearBufferData;
 
-use crate::buffer::BufferSlice;
+use crate::uniforms::{ImageUnit, ImageUnitError, ImageUnitFormat};
+
+use crate::buffer::{BufferSlice, BufferAnySlice};
 use crate::buffer::BufferAny;
 use crate::BufferExt;
 use crate::BufferSliceExt;
@@ -38,8 +45,11 @@ use std::ops::Range;
 use std::ffi::c_void;
 
 use crate::ops;
+use crate::ops::PendingRead;
 use crate::fbo;
 
+use smallvec::SmallVec;
+
 /// Type of a texture.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[allow(missing_docs)]      // TODO: document and remove
@@ -65,6 +75,15 @@ pub struct TextureAny {
     /// been checked yet. The inner Result is Err if the format has been checked but is unknown.
     actual_format: Cell<Option<Result<InternalFormat, GetFormatError>>>,
 
+    /// The latest swizzle set on the texture.
+    swizzle: Cell<[Swizzle; 4]>,
+
+    /// The latest depth-stencil mode set on the texture.
+    depth_stencil_mode: Cell<DepthStencilTextureMode>,
+
+    /// The latest base level set on the texture.
+    base_level: Cell<u32>,
+
     /// Type and dimensions of the texture.
     ty: Dimensions,
 
@@ -255,6 +274,14 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
             ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MAX_LEVEL, 0);
         }
 
+        // when seamless filtering is controlled per texture, it isn't enabled globally and
+        // must be enabled for the textures that are sampled without a sampler object
+        if (bind_point == gl::TEXTURE_CUBE_MAP || bind_point == gl::TEXTURE_CUBE_MAP_ARRAY) &&
+           ctxt.extensions.gl_arb_seamless_cubemap_per_texture
+        {
+            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_CUBE_MAP_SEAMLESS, gl::TRUE as i32);
+        }
+
         if bind_point == gl::TEXTURE_3D || bind_point == gl::TEXTURE_2D_ARRAY ||
            bind_point == gl::TEXTURE_CUBE_MAP_ARRAY
         {
@@ -448,6 +475,9 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
         id,
         requested_format: format,
         actual_format: Cell::new(None),
+        swizzle: Cell::new(Swizzle::IDENTITY),
+        depth_stencil_mode: Cell::new(DepthStencilTextureMode::Depth),
+        base_level: Cell::new(0),
         ty,
         levels: texture_levels as u32,
         generate_mipmaps: should_generate_mipmaps,
@@ -478,6 +508,9 @@ pub unsafe fn from_id<F: Facade + ?Sized>(facade: &F,
         id,
         requested_format: format,
         actual_format: Cell::new(None),
+        swizzle: Cell::new(Swizzle::IDENTITY),
+        depth_stencil_mode: Cell::new(DepthStencilTextureMode::Depth),
+        base_level: Cell::new(0),
         ty,
         levels: mipmap_levels,
         generate_mipmaps: should_generate_mipmaps,
@@ -485,6 +518,34 @@ pub unsafe fn from_id<F: Facade + ?Sized>(facade: &F,
     }
 }
 
+/// Checks that `glCopyImageSubData` can copy between the formats of two textures.
+fn check_copy_compatibility(source: &TextureAny, target: &TextureAny)
+                            -> Result<(), CopyImageError>
+{
+    // compressed, depth and stencil formats can only be copied to the same format
+    let exact = |texture: &TextureAny| {
+        texture.is_compressed() ||
+            matches!(texture.kind(),
+                     TextureKind::Depth | TextureKind::Stencil | TextureKind::DepthStencil)
+    };
+
+    if exact(source) || exact(target) {
+        return match (source.requested_format, target.requested_format) {
+            (TextureFormatRequest::Specific(a), TextureFormatRequest::Specific(b)) if a == b =>
+                Ok(()),
+            _ => Err(CopyImageError::FormatNotCompatible),
+        };
+    }
+
+    // other formats are compatible if their texels have the same size, and we let the
+    // backend check the formats that we can't determine
+    match (source.get_internal_format(), target.get_internal_format()) {
+        (Ok(a), Ok(b)) if a.get_total_bits() != b.get_total_bits() =>
+            Err(CopyImageError::FormatNotCompatible),
+        _ => Ok(()),
+    }
+}
+
 impl TextureAny {
     /// Returns the width of the texture.
     #[inline]
@@ -534,6 +595,27 @@ impl TextureAny {
         self.requested_format
     }
 
+    /// Returns true if the texture has an sRGB format.
+    ///
+    /// When a shader samples an sRGB texture, the values are automatically converted from the
+    /// sRGB color space to linear color space. When it samples any other texture, the values are
+    /// returned as they are stored.
+    pub fn is_srgb(&self) -> bool {
+        match self.requested_format {
+            TextureFormatRequest::Specific(TextureFormat::Srgb(_)) => true,
+            TextureFormatRequest::Specific(TextureFormat::CompressedSrgbFormat(_)) => true,
+
+            // glium falls back to a regular format if sRGB isn't supported
+            TextureFormatRequest::AnySrgb | TextureFormatRequest::AnyCompressedSrgb => {
+                self.context.get_version() >= &Version(Api::Gl, 2, 1) ||
+                self.context.get_version() >= &Version(Api::GlEs, 3, 0) ||
+                self.context.get_extensions().gl_ext_texture_srgb
+            },
+
+            _ => false,
+        }
+    }
+
     /// Returns the kind of texture.
     #[inline]
     pub fn kind(&self) -> TextureKind {
@@ -680,6 +762,16 @@ impl TextureAny {
         })
     }
 
+    /// Builds an object that binds the main level of this texture to an image unit, so that
+    /// shaders can read and write it with `imageLoad` and `imageStore`.
+    ///
+    /// Returns an error if the internal format of the texture is not compatible with `format`.
+    /// See `ImageUnit` for how to select another level or a single layer.
+    #[inline]
+    pub fn image_unit(&self, format: ImageUnitFormat) -> Result<ImageUnit<'_>, ImageUnitError> {
+        ImageUnit::new(self, format)
+    }
+
     /// Binds this texture and generates mipmaps.
     #[inline]
     pub unsafe fn generate_mipmaps(&self) {
@@ -687,6 +779,252 @@ impl TextureAny {
         self.bind_to_current(&mut ctxt);
         generate_mipmaps(&ctxt, self.get_bind_point());
     }
+
+    /// Changes the source of each component of the values that shaders read from this
+    /// texture, in the order red, green, blue, alpha.
+    ///
+    /// For example `[Swizzle::Red, Swizzle::Red, Swizzle::Red, Swizzle::One]` presents a
+    /// single-channel texture as an opaque grey texture, like the legacy `LUMINANCE` format.
+    /// This affects sampling and `texelFetch`, but not image units nor framebuffers.
+    ///
+    /// Requires OpenGL 3.3, OpenGL ES 3.0, `GL_ARB_texture_swizzle` or
+    /// `GL_EXT_texture_swizzle`.
+    pub fn set_swizzle(&self, swizzle: [Swizzle; 4]) -> Result<(), SwizzleNotSupportedError> {
+        let mut ctxt = self.context.make_current();
+
+        let supported = ctxt.version >= &Version(Api::Gl, 3, 3) ||
+                        ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+                        ctxt.extensions.gl_arb_texture_swizzle ||
+                        ctxt.extensions.gl_ext_texture_swizzle;
+        if !supported {
+            return Err(SwizzleNotSupportedError);
+        }
+
+        if self.swizzle.get() == swizzle {
+            return Ok(());
+        }
+
+        let bind_point = self.bind_to_current(&mut ctxt);
+        let names = [gl::TEXTURE_SWIZZLE_R, gl::TEXTURE_SWIZZLE_G, gl::TEXTURE_SWIZZLE_B,
+                     gl::TEXTURE_SWIZZLE_A];
+
+        for (&name, &value) in names.iter().zip(swizzle.iter()) {
+            unsafe {
+                ctxt.gl.TexParameteri(bind_point, name, value.to_glenum() as gl::types::GLint);
+            }
+        }
+
+        self.swizzle.set(swizzle);
+        Ok(())
+    }
+
+    /// Returns the swizzle of the texture, as set with `set_swizzle`.
+    #[inline]
+    pub fn get_swizzle(&self) -> [Swizzle; 4] {
+        self.swizzle.get()
+    }
+
+    /// Chooses whether shaders read the depth or the stencil component of this texture, if it
+    /// has a depth-stencil format.
+    ///
+    /// In `Stencil` mode the texture must be bound to a `usampler*` uniform and returns the
+    /// stencil index in the red component. Only the `Nearest` filters can be used to sample
+    /// a texture in this mode.
+    ///
+    /// Requires OpenGL 4.3, OpenGL ES 3.1 or `GL_ARB_stencil_texturing`.
+    pub fn set_depth_stencil_mode(&self, mode: DepthStencilTextureMode)
+                                  -> Result<(), StencilTexturingNotSupportedError>
+    {
+        if !crate::texture::is_stencil_texturing_supported(&*self.context) {
+            return Err(StencilTexturingNotSupportedError);
+        }
+
+        if self.depth_stencil_mode.get() == mode {
+            return Ok(());
+        }
+
+        let mut ctxt = self.context.make_current();
+        let bind_point = self.bind_to_current(&mut ctxt);
+        unsafe {
+            ctxt.gl.TexParameteri(bind_point, gl::DEPTH_STENCIL_TEXTURE_MODE,
+                                  mode.to_glenum() as gl::types::GLint);
+        }
+
+        self.depth_stencil_mode.set(mode);
+        Ok(())
+    }
+
+    /// Returns the depth-stencil mode of the texture, as set with `set_depth_stencil_mode`.
+    #[inline]
+    pub fn get_depth_stencil_mode(&self) -> DepthStencilTextureMode {
+        self.depth_stencil_mode.get()
+    }
+
+    /// Sets the most detailed mipmap level that can be sampled. The levels below it are ignored
+    /// when sampling, and the base level is used instead when the texture is magnified.
+    ///
+    /// This is useful to render with a texture whose most detailed levels have not been
+    /// uploaded yet. Contrary to the minimum level of detail of samplers, the base level is a
+    /// property of the texture and applies whatever the sampler used. It also changes the
+    /// level 0 of `textureSize` and `texelFetch` in shaders.
+    ///
+    /// Requires OpenGL 1.2 or OpenGL ES 3.0.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `level` is not a mipmap level of the texture.
+    pub fn set_base_level(&self, level: u32) -> Result<(), BaseLevelNotSupportedError> {
+        assert!(level < self.levels, "The mipmap level doesn't exist");
+
+        let mut ctxt = self.context.make_current();
+
+        if !(ctxt.version >= &Version(Api::Gl, 1, 2) || ctxt.version >= &Version(Api::GlEs, 3, 0)) {
+            return Err(BaseLevelNotSupportedError);
+        }
+
+        if self.base_level.get() == level {
+            return Ok(());
+        }
+
+        let bind_point = self.bind_to_current(&mut ctxt);
+        unsafe {
+            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_BASE_LEVEL, level as gl::types::GLint);
+        }
+
+        self.base_level.set(level);
+        Ok(())
+    }
+
+    /// Returns the base level of the texture, as set with `set_base_level`.
+    #[inline]
+    pub fn get_base_level(&self) -> u32 {
+        self.base_level.get()
+    }
+
+    /// Clears a whole mipmap level of the texture to a specific value.
+    ///
+    /// See `clear_rect`.
+    pub fn clear<D>(&self, level: u32, data: D) where D: Into<ClearBufferData> {
+        let mipmap = self.mipmap(level).expect("The mipmap level doesn't exist");
+        let rect = Rect {
+            left: 0,
+            bottom: 0,
+            width: mipmap.get_width(),
+            height: mipmap.get_height().unwrap_or(1),
+        };
+
+        self.clear_rect(level, rect, 0 .. self.get_num_image_layers(level), data);
+    }
+
+    /// Clears a region of a mipmap level of the texture to a specific value.
+    ///
+    /// `layers` is the range of layers of array textures, of depth slices of 3D textures or of
+    /// faces of cubemaps (six per cubemap for cubemap arrays), and must be `0 .. 1` for other
+    /// textures. Colors are written as they are, without any conversion to sRGB.
+    ///
+    /// This uses `glClearTexSubImage` if it is supported (OpenGL 4.4 or
+    /// `GL_ARB_clear_texture`), and otherwise clears each layer with a framebuffer.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the region is out of range, if the texture is compressed, or if `data` does
+    /// not match the kind of texture. For example passing a `[i32; 4]` when using a regular
+    /// (float) texture.
+    pub fn clear_rect<D>(&self, level: u32, rect: Rect, layers: Range<u32>, data: D)
+        where D: Into<ClearBufferData>
+    {
+        let data = data.into();
+
+        let mipmap = self.mipmap(level).expect("The mipmap level doesn't exist");
+        assert!(rect.left + rect.width <= mipmap.get_width() &&
+                rect.bottom + rect.height <= mipmap.get_height().unwrap_or(1),
+                "The rectangle is out of range");
+        assert!(layers.start <= layers.end && layers.end <= self.get_num_image_layers(level),
+                "The layers are out of range");
+
+        assert!(!self.is_compressed(), "Compressed textures can't be cleared");
+
+        match (self.kind(), data) {
+            (TextureKind::Float, ClearBufferData::Float(_)) => (),
+            (TextureKind::Integral, ClearBufferData::Integral(_)) => (),
+            (TextureKind::Unsigned, ClearBufferData::Unsigned(_)) => (),
+            (TextureKind::Depth, ClearBufferData::Depth(_)) => (),
+            (TextureKind::Stencil, ClearBufferData::Stencil(_)) => (),
+            (TextureKind::DepthStencil, ClearBufferData::DepthStencil(_, _)) => (),
+            _ => panic!("The data passed to `clear_rect` does not match the kind of texture"),
+        };
+
+        if rect.width == 0 || rect.height == 0 || layers.start == layers.end {
+            return;
+        }
+
+        if self.context.get_version() >= &Version(Api::Gl, 4, 4) ||
+           self.context.get_extensions().gl_arb_clear_texture
+        {
+            // 1D arrays store their layers in the second coordinate
+            let (y, height, z, depth) = match self.ty {
+                Dimensions::Texture1dArray { .. } => (layers.start, layers.end - layers.start, 0, 1),
+                _ => (rect.bottom, rect.height, layers.start, layers.end - layers.start),
+            };
+
+            // the depth and stencil values of a depth-stencil texture are packed together
+            let depth_stencil;
+
+            let (format, ty, ptr) = match data {
+                ClearBufferData::Float(ref c) =>
+                    (gl::RGBA, gl::FLOAT, c.as_ptr() as *const c_void),
+                ClearBufferData::Integral(ref c) =>
+                    (gl::RGBA_INTEGER, gl::INT, c.as_ptr() as *const c_void),
+                ClearBufferData::Unsigned(ref c) =>
+                    (gl::RGBA_INTEGER, gl::UNSIGNED_INT, c.as_ptr() as *const c_void),
+                ClearBufferData::Depth(ref d) =>
+                    (gl::DEPTH_COMPONENT, gl::FLOAT, d as *const f32 as *const c_void),
+                ClearBufferData::Stencil(ref s) =>
+                    (gl::STENCIL_INDEX, gl::INT, s as *const i32 as *const c_void),
+                ClearBufferData::DepthStencil(d, s) => {
+                    depth_stencil = [d.to_bits(), s as u32 & 0xff];
+                    (gl::DEPTH_STENCIL, gl::FLOAT_32_UNSIGNED_INT_24_8_REV,
+                     depth_stencil.as_ptr() as *const c_void)
+                },
+            };
+
+            let ctxt = self.context.make_current();
+            unsafe {
+                ctxt.gl.ClearTexSubImage(self.id, level as gl::types::GLint,
+                                         rect.left as gl::types::GLint, y as gl::types::GLint,
+                                         z as gl::types::GLint,
+                                         rect.width as gl::types::GLsizei,
+                                         height as gl::types::GLsizei,
+                                         depth as gl::types::GLsizei, format, ty, ptr);
+            }
+
+            return;
+        }
+
+        for layer in layers {
+            let framebuffer = mipmap.get_layer_framebuffer(layer);
+            ops::clear_buffer(&self.context, &framebuffer, Some(&rect), data);
+        }
+    }
+
+    /// Returns true if the texture has a compressed format.
+    fn is_compressed(&self) -> bool {
+        matches!(self.requested_format,
+                 TextureFormatRequest::Specific(TextureFormat::CompressedFormat(_)) |
+                 TextureFormatRequest::Specific(TextureFormat::CompressedSrgbFormat(_)) |
+                 TextureFormatRequest::AnyCompressed | TextureFormatRequest::AnyCompressedSrgb)
+    }
+
+    /// Returns the number of layers of a mipmap level, as accepted by `clear_rect` and
+    /// `copy_sub_image`.
+    fn get_num_image_layers(&self, level: u32) -> u32 {
+        match self.ty {
+            Dimensions::Texture3d { .. } => self.mipmap(level).unwrap().get_depth().unwrap(),
+            Dimensions::Cubemap { .. } => 6,
+            Dimensions::CubemapArray { array_size, .. } => array_size * 6,
+            _ => self.get_array_size().unwrap_or(1),
+        }
+    }
 }
 
 impl TextureExt for TextureAny {
@@ -705,6 +1043,11 @@ impl TextureExt for TextureAny {
         get_bind_point(self.ty)
     }
 
+    #[inline]
+    fn get_base_level(&self) -> u32 {
+        self.base_level.get()
+    }
+
     fn bind_to_current(&self, ctxt: &mut CommandContext<'_>) -> gl::types::GLenum {
         let bind_point = self.get_bind_point();
 
@@ -738,17 +1081,19 @@ impl fmt::Debug for TextureAny {
 
 impl Drop for TextureAny {
     fn drop(&mut self) {
+        let id = self.id;
+        self.context.for_each_shared_context(&mut |ctxt| {
+            fbo::FramebuffersContainer::purge_texture(ctxt, id);
+            forget_texture_bindings(ctxt, id);
+        });
+
         let mut ctxt = self.context.make_current();
 
         // removing FBOs which contain this texture
         fbo::FramebuffersContainer::purge_texture(&mut ctxt, self.id);
 
         // resetting the bindings
-        for tex_unit in ctxt.state.texture_units.iter_mut() {
-            if tex_unit.texture == self.id {
-                tex_unit.texture = 0;
-            }
-        }
+        forget_texture_bindings(&mut ctxt, self.id);
 
         if self.owned {
             unsafe { ctxt.gl.DeleteTextures(1, [ self.id ].as_ptr()); }
@@ -756,6 +1101,71 @@ impl Drop for TextureAny {
     }
 }
 
+/// A texture that has been detached from its context, and that can be sent to another thread.
+///
+/// See `transfer::Transferable`.
+#[derive(Debug)]
+pub struct DetachedTexture {
+    id: gl::types::GLuint,
+    requested_format: TextureFormatRequest,
+    swizzle: [Swizzle; 4],
+    depth_stencil_mode: DepthStencilTextureMode,
+    base_level: u32,
+    ty: Dimensions,
+    levels: u32,
+    generate_mipmaps: bool,
+    owned: bool,
+}
+
+impl Transferable for TextureAny {
+    type Detached = DetachedTexture;
+
+    fn detach(mut self) -> DetachedTexture {
+        let detached = DetachedTexture {
+            id: self.id,
+            requested_format: self.requested_format,
+            swizzle: self.swizzle.get(),
+            depth_stencil_mode: self.depth_stencil_mode.get(),
+            base_level: self.base_level.get(),
+            ty: self.ty,
+            levels: self.levels,
+            generate_mipmaps: self.generate_mipmaps,
+            owned: self.owned,
+        };
+
+        // the destructor purges the caches of the context but doesn't destroy the texture
+        self.owned = false;
+        detached
+    }
+
+    unsafe fn attach<F: ?Sized>(facade: &F, detached: DetachedTexture) -> TextureAny
+                                where F: Facade
+    {
+        TextureAny {
+            context: facade.get_context().clone(),
+            id: detached.id,
+            requested_format: detached.requested_format,
+            actual_format: Cell::new(None),
+            swizzle: Cell::new(detached.swizzle),
+            depth_stencil_mode: Cell::new(detached.depth_stencil_mode),
+            base_level: Cell::new(detached.base_level),
+            ty: detached.ty,
+            levels: detached.levels,
+            generate_mipmaps: detached.generate_mipmaps,
+            owned: detached.owned,
+        }
+    }
+}
+
+/// Removes a texture from the cached texture units of the context.
+fn forget_texture_bindings(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
+    for tex_unit in ctxt.state.texture_units.iter_mut() {
+        if tex_unit.texture == id {
+            tex_unit.texture = 0;
+        }
+    }
+}
+
 /// Represents a specific layer of an array texture and 3D textures.
 #[derive(Copy, Clone)]
 pub struct TextureAnyLayer<'a> {
@@ -852,10 +1262,13 @@ impl<'a> TextureAnyMipmap<'a> {
     ///
     /// In the case of 1D texture arrays, use array size as width.
     /// In the case of 2D texture arrays, use array size as depth.
+    /// In the case of cubemaps and cubemap arrays, use the number of faces as depth.
     #[inline]
     fn get_mipmap_dimensions(&self) -> (u32, u32, u32) {
         let tex_depth = match self.texture.ty {
             Dimensions::Texture2dArray { array_size, .. } => array_size,
+            Dimensions::Cubemap { .. } => 6,
+            Dimensions::CubemapArray { array_size, .. } => 6 * array_size,
             _ => self.depth.unwrap_or(1),
         };
         let tex_height = match self.texture.ty {
@@ -940,7 +1353,7 @@ impl<'a> TextureAnyMipmap<'a> {
                                            y: Range<u32>, z: Range<u32>)
                                            where P: PixelValue
     {
-        self.raw_upload_from_pixel_buffer_impl(source, x, y, z, false);
+        self.raw_upload_from_pixel_buffer_impl(source.as_slice_any(), P::get_format(), x, y, z, false);
     }
 
     /// Uploads data to the texture from a buffer. The R, G and B components are flipped.
@@ -954,12 +1367,236 @@ impl<'a> TextureAnyMipmap<'a> {
                                                     x: Range<u32>, y: Range<u32>, z: Range<u32>)
                                                     where P: PixelValue
     {
-        self.raw_upload_from_pixel_buffer_impl(source, x, y, z, true);
+        self.raw_upload_from_pixel_buffer_impl(source.as_slice_any(), P::get_format(), x, y, z, true);
+    }
+
+    /// Copies this whole mipmap level to a mipmap level of another texture, starting at its
+    /// bottom-left corner.
+    ///
+    /// See `copy_sub_image`.
+    pub fn copy_to(&self, target: &TextureAnyMipmap<'_>) -> Result<(), CopyImageError> {
+        let rect = Rect {
+            left: 0,
+            bottom: 0,
+            width: self.width,
+            height: self.height.unwrap_or(1),
+        };
+
+        let layers = 0 .. self.texture.get_num_image_layers(self.level);
+        self.copy_sub_image(rect, layers, target, 0, 0, 0)
+    }
+
+    /// Copies a region of this mipmap level to a mipmap level of another texture.
+    ///
+    /// `source_layers` and `target_layer` are layers of array textures, depth slices of 3D
+    /// textures or faces of cubemaps (six per cubemap for cubemap arrays), which means that
+    /// you can for example copy a face of a cubemap to a layer of an array. They must be
+    /// `0 .. 1` and `0` for other textures.
+    ///
+    /// This uses `glCopyImageSubData` if it is supported (OpenGL 4.3, OpenGL ES 3.2 or
+    /// `GL_ARB_copy_image`). In that case the formats of the two textures only need to have
+    /// the same number of bits per texel, and the data is copied without any conversion.
+    /// Otherwise each layer is blitted, which requires both textures to be renderable and
+    /// to have the same kind of format.
+    pub fn copy_sub_image(&self, source_rect: Rect, source_layers: Range<u32>,
+                          target: &TextureAnyMipmap<'_>, target_left: u32, target_bottom: u32,
+                          target_layer: u32) -> Result<(), CopyImageError>
+    {
+        let source = self.texture;
+
+        if source_rect.left + source_rect.width > self.width ||
+           source_rect.bottom + source_rect.height > self.height.unwrap_or(1) ||
+           source_layers.start > source_layers.end ||
+           source_layers.end > source.get_num_image_layers(self.level)
+        {
+            return Err(CopyImageError::SourceOutOfRange);
+        }
+
+        let num_layers = source_layers.end - source_layers.start;
+        if target_left + source_rect.width > target.width ||
+           target_bottom + source_rect.height > target.height.unwrap_or(1) ||
+           target_layer + num_layers > target.texture.get_num_image_layers(target.level)
+        {
+            return Err(CopyImageError::TargetOutOfRange);
+        }
+
+        if source.get_samples() != target.get_samples() {
+            return Err(CopyImageError::SamplesMismatch);
+        }
+
+        let copy_image_supported = source.context.get_version() >= &Version(Api::Gl, 4, 3) ||
+                                   source.context.get_version() >= &Version(Api::GlEs, 3, 2) ||
+                                   source.context.get_extensions().gl_arb_copy_image;
+
+        if copy_image_supported {
+            check_copy_compatibility(source, target.texture)?;
+
+            if source_rect.width == 0 || source_rect.height == 0 || num_layers == 0 {
+                return Ok(());
+            }
+
+            // 1D arrays store their layers in the second coordinate
+            let coords = |texture: &TextureAny, left: u32, bottom: u32, layer: u32| {
+                match texture.ty {
+                    Dimensions::Texture1dArray { .. } => (left, layer, 0),
+                    _ => (left, bottom, layer),
+                }
+            };
+            let (src_x, src_y, src_z) = coords(source, source_rect.left, source_rect.bottom,
+                                               source_layers.start);
+            let (dst_x, dst_y, dst_z) = coords(target.texture, target_left, target_bottom,
+                                               target_layer);
+            let (height, depth) = match source.ty {
+                Dimensions::Texture1dArray { .. } => (num_layers, 1),
+                _ => (source_rect.height, num_layers),
+            };
+
+            let ctxt = source.context.make_current();
+            unsafe {
+                ctxt.gl.CopyImageSubData(source.id, source.get_bind_point(),
+                                         self.level as gl::types::GLint,
+                                         src_x as gl::types::GLint, src_y as gl::types::GLint,
+                                         src_z as gl::types::GLint,
+                                         target.texture.id, target.texture.get_bind_point(),
+                                         target.level as gl::types::GLint,
+                                         dst_x as gl::types::GLint, dst_y as gl::types::GLint,
+                                         dst_z as gl::types::GLint,
+                                         source_rect.width as gl::types::GLsizei,
+                                         height as gl::types::GLsizei,
+                                         depth as gl::types::GLsizei);
+            }
+
+            return Ok(());
+        }
+
+        if source.is_compressed() || target.texture.is_compressed() ||
+           source.kind() != target.texture.kind()
+        {
+            return Err(CopyImageError::FormatNotCompatible);
+        }
+
+        let mask = match source.kind() {
+            TextureKind::Float | TextureKind::Integral | TextureKind::Unsigned =>
+                gl::COLOR_BUFFER_BIT,
+            TextureKind::Depth => gl::DEPTH_BUFFER_BIT,
+            TextureKind::Stencil => gl::STENCIL_BUFFER_BIT,
+            TextureKind::DepthStencil => gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT,
+        };
+
+        let target_rect = BlitTarget {
+            left: target_left,
+            bottom: target_bottom,
+            width: source_rect.width as i32,
+            height: source_rect.height as i32,
+        };
+
+        for (num, layer) in source_layers.enumerate() {
+            let source_fb = self.get_layer_framebuffer(layer);
+            let target_fb = target.get_layer_framebuffer(target_layer + num as u32);
+            ops::blit(&source.context, Some(&source_fb), Some(&target_fb), mask, &source_rect,
+                      &target_rect, gl::NEAREST);
+        }
+
+        Ok(())
     }
 
-    fn raw_upload_from_pixel_buffer_impl<P>(&self, source: BufferSlice<'_, [P]>, x: Range<u32>,
-                                            y: Range<u32>, z: Range<u32>, inverted: bool)
-                                            where P: PixelValue
+    /// Resolves this multisample mipmap into a mipmap of a non-multisample texture with the same
+    /// dimensions and number of layers. Each layer is resolved separately.
+    ///
+    /// The samples of color formats are averaged. For depth and stencil formats, the value of a
+    /// single sample is used, and the two textures must have the same format.
+    pub fn resolve_to(&self, target: &TextureAnyMipmap<'_>) -> Result<(), CopyImageError> {
+        let source = self.texture;
+
+        if source.get_samples().is_none() || target.texture.get_samples().is_some() {
+            return Err(CopyImageError::SamplesMismatch);
+        }
+
+        let num_layers = source.get_num_image_layers(self.level);
+        if self.width != target.width || self.height != target.height ||
+           num_layers != target.texture.get_num_image_layers(target.level)
+        {
+            return Err(CopyImageError::TargetOutOfRange);
+        }
+
+        if target.texture.is_compressed() || source.kind() != target.texture.kind() {
+            return Err(CopyImageError::FormatNotCompatible);
+        }
+
+        let mask = match source.kind() {
+            TextureKind::Float | TextureKind::Integral | TextureKind::Unsigned =>
+                gl::COLOR_BUFFER_BIT,
+            TextureKind::Depth => gl::DEPTH_BUFFER_BIT,
+            TextureKind::Stencil => gl::STENCIL_BUFFER_BIT,
+            TextureKind::DepthStencil => gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT,
+        };
+
+        let rect = Rect {
+            left: 0,
+            bottom: 0,
+            width: self.width,
+            height: self.height.unwrap_or(1),
+        };
+
+        let target_rect = BlitTarget {
+            left: 0,
+            bottom: 0,
+            width: rect.width as i32,
+            height: rect.height as i32,
+        };
+
+        // a multisample framebuffer can only be blitted with `GL_NEAREST` and without scaling
+        for layer in 0 .. num_layers {
+            let source_fb = self.get_layer_framebuffer(layer);
+            let target_fb = target.get_layer_framebuffer(layer);
+            ops::blit(&source.context, Some(&source_fb), Some(&target_fb), mask, &rect,
+                      &target_rect, gl::NEAREST);
+        }
+
+        Ok(())
+    }
+
+    /// Builds a framebuffer whose only attachment is a layer of this mipmap, with the same
+    /// numbering of layers as `TextureAny::clear_rect`.
+    fn get_layer_framebuffer(&self, layer: u32) -> fbo::ValidatedAttachments<'a> {
+        let cube_layers = [CubeLayer::PositiveX, CubeLayer::NegativeX, CubeLayer::PositiveY,
+                           CubeLayer::NegativeY, CubeLayer::PositiveZ, CubeLayer::NegativeZ];
+
+        let (layer, cube_layer) = match self.texture.ty {
+            Dimensions::Cubemap { .. } | Dimensions::CubemapArray { .. } =>
+                (layer / 6, Some(cube_layers[layer as usize % 6])),
+            _ => (layer, None),
+        };
+
+        let image = self.layer(layer).unwrap().into_image(cube_layer).unwrap();
+        let attachment = fbo::RegularAttachment::Texture(image);
+
+        let mut colors = SmallVec::new();
+        let depth_stencil = match self.texture.kind() {
+            TextureKind::Depth => fbo::DepthStencilAttachments::DepthAttachment(attachment),
+            TextureKind::Stencil => fbo::DepthStencilAttachments::StencilAttachment(attachment),
+            TextureKind::DepthStencil =>
+                fbo::DepthStencilAttachments::DepthStencilAttachment(attachment),
+            _ => {
+                colors.push((0, attachment));
+                fbo::DepthStencilAttachments::None
+            },
+        };
+
+        fbo::FramebufferAttachments::Regular(
+            fbo::FramebufferSpecificAttachments { colors, depth_stencil }
+        ).validate(&*self.texture.context).unwrap()
+    }
+
+    /// Uploads data to the texture from a buffer whose content is in the given format.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the offsets and dimensions are outside the boundaries of the texture. Panics
+    /// if the buffer is not big enough to hold the data.
+    pub(crate) fn raw_upload_from_pixel_buffer_impl(&self, source: BufferAnySlice<'_>,
+                                                    format: ClientFormat, x: Range<u32>,
+                                                    y: Range<u32>, z: Range<u32>, inverted: bool)
     {
         let tex_dim = self.get_mipmap_dimensions();
         assert!(x.start < tex_dim.0);
@@ -973,17 +1610,22 @@ impl<'a> TextureAnyMipmap<'a> {
         let height = y.end - y.start;
         let depth = z.end - z.start;
 
-        if source.len() < (width * height * depth) as usize {
+        if source.get_size() < (width * height * depth) as usize * format.get_size() {
             panic!("Buffer is too small");
         }
 
         let (client_format, client_type) =
             image_format::client_format_to_glenum(&self.texture.context,
-                                                  ClientFormatAny::ClientFormat(P::get_format()),
+                                                  ClientFormatAny::ClientFormat(format),
                                                   self.texture.requested_format, inverted).unwrap();
 
         let mut ctxt = self.texture.context.make_current();
 
+        if ctxt.state.pixel_store_unpack_alignment != 1 {
+            ctxt.state.pixel_store_unpack_alignment = 1;
+            unsafe { ctxt.gl.PixelStorei(gl::UNPACK_ALIGNMENT, 1); }
+        }
+
         // binds the pixel buffer
         source.prepare_and_bind_for_pixel_unpack(&mut ctxt);
 
@@ -1139,18 +1781,26 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
         let id = self.texture.id;
         let level = self.level;
 
-        let (is_client_compressed, data_bufsize) = (format.is_compressed(),
-                                                    format.get_buffer_size(width, height, depth, None));
+        // compressed formats are made of two-dimensional blocks, so each slice is compressed
+        // separately
+        let is_client_compressed = format.is_compressed();
+        let data_bufsize = if is_client_compressed {
+            format.get_buffer_size(width, height, None, depth)
+        } else {
+            format.get_buffer_size(width, height, depth, None)
+        };
         let regen_mipmaps = regen_mipmaps && self.texture.levels >= 2 &&
                             self.texture.generate_mipmaps && !is_client_compressed;
 
+        let (mip_width, mip_height, mip_depth) = self.get_mipmap_dimensions();
+
         assert!(!regen_mipmaps || level == 0);  // when regen_mipmaps is true, level must be 0!
-        assert!(x_offset <= self.width);
-        assert!(y_offset <= self.height.unwrap_or(1));
-        assert!(z_offset <= self.depth.unwrap_or(1));
-        assert!(x_offset + width <= self.width);
-        assert!(y_offset + height.unwrap_or(1) <= self.height.unwrap_or(1));
-        assert!(z_offset + depth.unwrap_or(1) <= self.depth.unwrap_or(1));
+        assert!(x_offset <= mip_width);
+        assert!(y_offset <= mip_height);
+        assert!(z_offset <= mip_depth);
+        assert!(x_offset + width <= mip_width);
+        assert!(y_offset + height.unwrap_or(1) <= mip_height);
+        assert!(z_offset + depth.unwrap_or(1) <= mip_depth);
 
         if data.len() * mem::size_of::<P>() != data_bufsize
         {
@@ -1173,8 +1823,31 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
             BufferAny::unbind_pixel_unpack(&mut ctxt);
             let bind_point = self.texture.bind_to_current(&mut ctxt);
 
-            if bind_point == gl::TEXTURE_3D || bind_point == gl::TEXTURE_2D_ARRAY {
-                unimplemented!();
+            if bind_point == gl::TEXTURE_3D || bind_point == gl::TEXTURE_2D_ARRAY ||
+               bind_point == gl::TEXTURE_CUBE_MAP_ARRAY
+            {
+                if is_client_compressed {
+                    ctxt.gl.CompressedTexSubImage3D(bind_point, level as gl::types::GLint,
+                                                    x_offset as gl::types::GLint,
+                                                    y_offset as gl::types::GLint,
+                                                    z_offset as gl::types::GLint,
+                                                    width as gl::types::GLsizei,
+                                                    height.unwrap_or(1) as gl::types::GLsizei,
+                                                    depth.unwrap_or(1) as gl::types::GLsizei,
+                                                    client_format,
+                                                    data_bufsize as gl::types::GLsizei,
+                                                    data.as_ptr() as *const _);
+                } else {
+                    ctxt.gl.TexSubImage3D(bind_point, level as gl::types::GLint,
+                                          x_offset as gl::types::GLint,
+                                          y_offset as gl::types::GLint,
+                                          z_offset as gl::types::GLint,
+                                          width as gl::types::GLsizei,
+                                          height.unwrap_or(1) as gl::types::GLsizei,
+                                          depth.unwrap_or(1) as gl::types::GLsizei,
+                                          client_format, client_type,
+                                          data.as_ptr() as *const _);
+                }
 
             } else if bind_point == gl::TEXTURE_2D || bind_point == gl::TEXTURE_1D_ARRAY {
                 assert!(z_offset == 0);
@@ -1198,6 +1871,53 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
                                           data.as_ptr() as *const _);
                 }
 
+            } else if bind_point == gl::TEXTURE_CUBE_MAP {
+                // each face is a separate target, the faces are uploaded one by one
+                let depth = depth.unwrap_or(1);
+                let face_bufsize = data_bufsize / depth as usize;
+                let data = data.as_ptr() as *const u8;
+
+                for face in 0 .. depth {
+                    let target = gl::TEXTURE_CUBE_MAP_POSITIVE_X + z_offset + face;
+                    let ptr = data.add(face as usize * face_bufsize) as *const _;
+
+                    if is_client_compressed {
+                        ctxt.gl.CompressedTexSubImage2D(target, level as gl::types::GLint,
+                                                        x_offset as gl::types::GLint,
+                                                        y_offset as gl::types::GLint,
+                                                        width as gl::types::GLsizei,
+                                                        height.unwrap_or(1) as gl::types::GLsizei,
+                                                        client_format,
+                                                        face_bufsize as gl::types::GLsizei, ptr);
+                    } else {
+                        ctxt.gl.TexSubImage2D(target, level as gl::types::GLint,
+                                              x_offset as gl::types::GLint,
+                                              y_offset as gl::types::GLint,
+                                              width as gl::types::GLsizei,
+                                              height.unwrap_or(1) as gl::types::GLsizei,
+                                              client_format, client_type, ptr);
+                    }
+                }
+
+            } else if bind_point == gl::TEXTURE_1D {
+                assert!(z_offset == 0);
+                assert!(y_offset == 0);
+
+                if is_client_compressed {
+                    ctxt.gl.CompressedTexSubImage1D(bind_point, level as gl::types::GLint,
+                                                    x_offset as gl::types::GLint,
+                                                    width as gl::types::GLsizei,
+                                                    client_format,
+                                                    data_bufsize as gl::types::GLsizei,
+                                                    data.as_ptr() as *const _);
+                } else {
+                    ctxt.gl.TexSubImage1D(bind_point, level as gl::types::GLint,
+                                          x_offset as gl::types::GLint,
+                                          width as gl::types::GLsizei,
+                                          client_format, client_type,
+                                          data.as_ptr() as *const _);
+                }
+
             } else {
                 assert!(z_offset == 0);
                 assert!(y_offset == 0);
@@ -1224,6 +1944,11 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
 
         let mut ctxt = texture.context.make_current();
 
+        // `glGetCompressedTexImage` doesn't exist in OpenGL ES
+        if !(ctxt.version >= &Version(Api::Gl, 1, 3)) {
+            return None;
+        }
+
         unsafe {
             let bind_point = texture.bind_to_current(&mut ctxt);
 
@@ -1269,6 +1994,62 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
             }
         }
     }
+
+    fn download_data(&self, format: ClientFormatAny) -> Option<Vec<u8>> {
+        let texture = self.texture;
+        let level = self.level as i32;
+
+        let (client_format, client_type) =
+            image_format::client_format_to_glenum(&texture.context, format,
+                                                  texture.requested_format, false).ok()?;
+
+        let mut ctxt = texture.context.make_current();
+
+        // `glGetTexImage` doesn't exist in OpenGL ES
+        if !(ctxt.version >= &Version(Api::Gl, 1, 3)) {
+            return None;
+        }
+
+        unsafe {
+            let bind_point = texture.bind_to_current(&mut ctxt);
+
+            // each face of a cubemap is a separate target
+            let targets = if bind_point == gl::TEXTURE_CUBE_MAP {
+                gl::TEXTURE_CUBE_MAP_POSITIVE_X .. gl::TEXTURE_CUBE_MAP_POSITIVE_X + 6
+            } else {
+                bind_point .. bind_point + 1
+            };
+
+            let (width, height, depth) = self.get_mipmap_dimensions();
+            let target_bufsize = if format.is_compressed() {
+                let mut size = 0;
+                ctxt.gl.GetTexLevelParameteriv(targets.start, level,
+                                               gl::TEXTURE_COMPRESSED_IMAGE_SIZE, &mut size);
+                size as usize
+            } else {
+                format.get_buffer_size(width, Some(height), Some(depth), None) / targets.len()
+            };
+
+            let mut buf = vec![0u8; target_bufsize * targets.len()];
+
+            BufferAny::unbind_pixel_pack(&mut ctxt);
+            if ctxt.state.pixel_store_pack_alignment != 1 {
+                ctxt.state.pixel_store_pack_alignment = 1;
+                ctxt.gl.PixelStorei(gl::PACK_ALIGNMENT, 1);
+            }
+
+            for (num, target) in targets.enumerate() {
+                let ptr = buf[num * target_bufsize ..].as_mut_ptr() as *mut _;
+                if format.is_compressed() {
+                    ctxt.gl.GetCompressedTexImage(target, level, ptr);
+                } else {
+                    ctxt.gl.GetTexImage(target, level, client_format, client_type, ptr);
+                }
+            }
+
+            Some(buf)
+        }
+    }
 }
 
 /// Represents a specific layer of a specific mipmap. This is the same as `TextureAnyImage`, except
@@ -1455,6 +2236,21 @@ impl<'a> TextureAnyImage<'a> {
             .unwrap();
     }
 
+    /// Starts reading the content of the image to a pixel buffer, without waiting for the GPU.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the rect is out of range.
+    /// - Panics if it fails to read the texture.
+    ///
+    pub fn raw_read_async<T>(&self, rect: &Rect) -> PendingRead<T>
+        where T: Texture2dDataSink<(u8, u8, u8, u8)>
+    {
+        let attachment = fbo::RegularAttachment::Texture(*self);
+        ops::read_async(&self.texture.context, &attachment,
+                        (self.width, self.height.unwrap_or(1)), rect).unwrap()
+    }
+
     /// Clears the content of the texture to a specific value.
     ///
     /// # Panic
@@ -0,0 +1,228 @@
+/*!
+
+Sub-allocation of the layers of a `Texture2dArray`.
+
+Without bindless textures, the number of textures that can be used by a single draw call is
+limited by the number of available texture units. A common workaround is to store many
+same-sized images in the layers of one big array texture, and to pass the index of the layer
+to the shader alongside the texture itself.
+
+A `TextureArrayAllocator` wraps a `Texture2dArray` and hands out its layers one by one as
+`TextureArraySlot`s. When a slot is destroyed, its layer is returned to the allocator and can
+be handed out again.
+
+```no_run
+# #[macro_use] extern crate glium;
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+# let image: glium::texture::RawImage2d<u8> = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+use glium::texture::array_allocator::TextureArrayAllocator;
+
+let allocator = TextureArrayAllocator::empty(&display, 256, 256, 64).unwrap();
+
+let slot = allocator.allocate().unwrap();
+slot.write(glium::Rect { left: 0, bottom: 0, width: 256, height: 256 }, image);
+
+let uniforms = uniform! {
+    textures: slot.get_texture(),
+    layer: slot.get_layer_coordinate(),
+};
+# }
+```
+
+Inside your shader, use a `sampler2DArray` and pass the layer as the third texture coordinate:
+
+```glsl
+uniform sampler2DArray textures;
+uniform float layer;
+
+// ...
+vec4 color = texture(textures, vec3(tex_coords, layer));
+```
+
+*/
+use std::cell::RefCell;
+use std::fmt;
+
+use crate::backend::Facade;
+use crate::image_format::ClientFormatAny;
+use crate::uniforms::Sampler;
+use crate::Rect;
+use crate::TextureMipmapExt;
+
+use crate::texture::{Texture2dArray, Texture2dDataSource, RawImage2d};
+use crate::texture::texture2d_array::Texture2dArrayLayer;
+use crate::texture::TextureCreationError;
+
+/// Wraps around a `Texture2dArray` and hands out its layers individually.
+pub struct TextureArrayAllocator {
+    texture: Texture2dArray,
+
+    // List of the layers that are not used by any slot. The next layer to hand out is at the end.
+    free_layers: RefCell<Vec<u32>>,
+}
+
+impl TextureArrayAllocator {
+    /// Builds a new allocator that manages all the layers of an existing texture.
+    ///
+    /// All the layers are considered free, regardless of their current content.
+    #[inline]
+    pub fn new(texture: Texture2dArray) -> TextureArrayAllocator {
+        let free_layers = (0 .. texture.array_size()).rev().collect();
+
+        TextureArrayAllocator {
+            texture,
+            free_layers: RefCell::new(free_layers),
+        }
+    }
+
+    /// Creates an empty `Texture2dArray` and builds an allocator that manages its layers.
+    #[inline]
+    pub fn empty<F: ?Sized>(facade: &F, width: u32, height: u32, array_size: u32)
+                            -> Result<TextureArrayAllocator, TextureCreationError>
+                            where F: Facade
+    {
+        let texture = Texture2dArray::empty(facade, width, height, array_size)?;
+        Ok(TextureArrayAllocator::new(texture))
+    }
+
+    /// Returns the texture whose layers are handed out.
+    #[inline]
+    pub fn get_texture(&self) -> &Texture2dArray {
+        &self.texture
+    }
+
+    /// Returns the total number of layers managed by this allocator.
+    #[inline]
+    pub fn get_capacity(&self) -> u32 {
+        self.texture.array_size()
+    }
+
+    /// Returns the number of layers that are not currently used by a slot.
+    #[inline]
+    pub fn get_free_layers(&self) -> u32 {
+        self.free_layers.borrow().len() as u32
+    }
+
+    /// Hands out a layer of the texture.
+    ///
+    /// Returns `None` if all the layers are already in use. The layer is given back to the
+    /// allocator when the slot is destroyed.
+    #[inline]
+    pub fn allocate(&self) -> Option<TextureArraySlot<'_>> {
+        let layer = self.free_layers.borrow_mut().pop()?;
+
+        Some(TextureArraySlot {
+            allocator: self,
+            layer,
+        })
+    }
+
+    /// Destroys the allocator and returns the underlying texture.
+    #[inline]
+    pub fn into_texture(self) -> Texture2dArray {
+        self.texture
+    }
+}
+
+impl fmt::Debug for TextureArrayAllocator {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(fmt, "TextureArrayAllocator {{ free layers: {}/{} }}", self.get_free_layers(),
+               self.get_capacity())
+    }
+}
+
+/// A single layer of a `Texture2dArray` handed out by a `TextureArrayAllocator`.
+///
+/// The layer is given back to the allocator when the slot is destroyed.
+pub struct TextureArraySlot<'a> {
+    allocator: &'a TextureArrayAllocator,
+    layer: u32,
+}
+
+impl<'a> TextureArraySlot<'a> {
+    /// Returns the whole texture that this slot belongs to.
+    ///
+    /// This is the texture that you must bind in order to sample from this slot.
+    #[inline]
+    pub fn get_texture(&self) -> &'a Texture2dArray {
+        &self.allocator.texture
+    }
+
+    /// Builds a `Sampler` marker object for the texture that this slot belongs to.
+    #[inline]
+    pub fn sampled(&self) -> Sampler<'a, Texture2dArray> {
+        self.get_texture().sampled()
+    }
+
+    /// Returns the index of the layer within the texture.
+    #[inline]
+    pub fn get_layer(&self) -> u32 {
+        self.layer
+    }
+
+    /// Returns the index of the layer as a floating-point value, which is what the third
+    /// coordinate passed to `texture()` expects when sampling a `sampler2DArray`.
+    #[inline]
+    pub fn get_layer_coordinate(&self) -> f32 {
+        self.layer as f32
+    }
+
+    /// Returns the layer of the texture corresponding to this slot.
+    #[inline]
+    pub fn as_layer(&self) -> Texture2dArrayLayer<'a> {
+        self.get_texture().layer(self.layer).unwrap()
+    }
+
+    /// Returns the width of the slot.
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.get_texture().width()
+    }
+
+    /// Returns the height of the slot.
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.get_texture().height()
+    }
+
+    /// Returns the width and height of the slot.
+    #[inline]
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width(), self.height())
+    }
+
+    /// Uploads some data in the main mipmap level of the layer.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if the the dimensions of `data` don't match the `Rect`.
+    #[inline]
+    pub fn write<'d, T>(&self, rect: Rect, data: T) where T: Texture2dDataSource<'d> {
+        let RawImage2d { data, width, height, format: client_format } = data.into_raw();
+
+        assert_eq!(width, rect.width);
+        assert_eq!(height, rect.height);
+
+        let client_format = ClientFormatAny::ClientFormat(client_format);
+
+        self.get_texture().main_level().upload_texture(rect.left, rect.bottom, self.layer,
+                                                       (client_format, data), width,
+                                                       Some(height), Some(1), true).unwrap()
+    }
+}
+
+impl<'a> fmt::Debug for TextureArraySlot<'a> {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(fmt, "TextureArraySlot {{ layer: {} }}", self.layer)
+    }
+}
+
+impl<'a> Drop for TextureArraySlot<'a> {
+    #[inline]
+    fn drop(&mut self) {
+        self.allocator.free_layers.borrow_mut().push(self.layer);
+    }
+}
@@ -0,0 +1,259 @@
+/*!
+Uploading data to textures without stalling the rendering.
+
+When you call `write` on a texture, the data is transferred during the call, and the driver
+often has to wait for the GPU to stop using the texture first. An `AsyncUploader` instead copies
+the data to a pixel unpack buffer and asks the GPU to transfer it from there. The call returns
+immediately, and the texture can be used right away: the GPU performs the transfer before
+executing the commands that come after it.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+# let texture: glium::texture::Texture2d = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+use glium::texture::RawImage2d;
+use glium::texture::async_upload::AsyncUploader;
+
+let mut uploader = AsyncUploader::new(&display, 4).unwrap();
+
+let rect = glium::Rect { left: 0, bottom: 0, width: 256, height: 256 };
+let data = RawImage2d::from_raw_rgba(vec![0u8; 256 * 256 * 4], (256, 256));
+let ticket = texture.write_async(&mut uploader, rect, data).unwrap();
+
+// ... later
+if ticket.is_complete() {
+    println!("the data has arrived in the texture");
+}
+```
+
+The uploader keeps a pool of buffers. A buffer is reused once the GPU has finished the transfer
+that reads from it, and new buffers are created as long as the maximum number of buffers passed
+to `AsyncUploader::new` isn't reached. Only if all the buffers are still in use does the
+uploader wait for the oldest transfer to finish.
+
+Asynchronous uploads require sync fences (OpenGL 3.2, OpenGL ES 3.0, `GL_ARB_sync` or
+`GL_APPLE_sync`).
+
+*/
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::rc::Rc;
+use std::slice;
+
+use crate::backend::Facade;
+use crate::buffer::{Buffer, BufferCreationError, BufferMode, BufferType};
+use crate::context::Context;
+use crate::sync::{SyncFence, SyncNotSupportedError};
+use crate::texture::{RawImage2d, Texture2dDataSource, TextureAnyMipmap};
+use crate::Rect;
+
+/// Error that can happen when uploading data asynchronously.
+#[derive(Debug, Clone, Copy)]
+pub enum AsyncUploadError {
+    /// The backend doesn't support sync fences.
+    SyncNotSupported,
+
+    /// Failed to create a pixel unpack buffer.
+    BufferCreationError(BufferCreationError),
+}
+
+impl fmt::Display for AsyncUploadError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::AsyncUploadError::*;
+        let desc = match *self {
+            SyncNotSupported =>
+                "The backend doesn't support sync fences",
+            BufferCreationError(_) =>
+                "Failed to create a pixel unpack buffer",
+        };
+        fmt.write_str(desc)
+    }
+}
+
+impl Error for AsyncUploadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use self::AsyncUploadError::*;
+        match *self {
+            SyncNotSupported => None,
+            BufferCreationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<SyncNotSupportedError> for AsyncUploadError {
+    #[inline]
+    fn from(_: SyncNotSupportedError) -> AsyncUploadError {
+        AsyncUploadError::SyncNotSupported
+    }
+}
+
+impl From<BufferCreationError> for AsyncUploadError {
+    #[inline]
+    fn from(err: BufferCreationError) -> AsyncUploadError {
+        AsyncUploadError::BufferCreationError(err)
+    }
+}
+
+/// Stages texture data through a pool of pixel unpack buffers.
+///
+/// See the documentation of the `async_upload` module.
+pub struct AsyncUploader {
+    context: Rc<Context>,
+
+    /// Maximum number of buffers in the pool.
+    max_buffers: usize,
+
+    /// The buffers of the pool, from the least recently used to the most recently used.
+    buffers: VecDeque<StagingBuffer>,
+}
+
+/// A buffer of the pool.
+struct StagingBuffer {
+    buffer: Buffer<[u8]>,
+
+    /// Signaled when the GPU has finished reading the buffer. `None` if the buffer has never
+    /// been used.
+    fence: Option<SyncFence>,
+}
+
+impl StagingBuffer {
+    #[inline]
+    fn is_free(&self) -> bool {
+        match self.fence {
+            Some(ref fence) => fence.is_signaled(),
+            None => true,
+        }
+    }
+}
+
+impl AsyncUploader {
+    /// Builds a new uploader that uses at most `max_buffers` buffers at the same time.
+    ///
+    /// Returns an error if the backend doesn't support sync fences.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if `max_buffers` is 0.
+    pub fn new<F: ?Sized>(facade: &F, max_buffers: usize) -> Result<AsyncUploader, AsyncUploadError>
+                          where F: Facade
+    {
+        assert!(max_buffers >= 1);
+
+        // checks that fences are supported before accepting any upload
+        SyncFence::new(facade)?;
+
+        Ok(AsyncUploader {
+            context: facade.get_context().clone(),
+            max_buffers,
+            buffers: VecDeque::with_capacity(max_buffers),
+        })
+    }
+
+    /// Returns the maximum number of buffers of the pool.
+    #[inline]
+    pub fn get_max_buffers(&self) -> usize {
+        self.max_buffers
+    }
+
+    /// Returns the number of uploads whose buffer is still being read by the GPU.
+    pub fn get_uploads_in_flight(&self) -> usize {
+        self.buffers.iter().filter(|buffer| !buffer.is_free()).count()
+    }
+
+    /// Uploads some data to a part of a mipmap of a two-dimensional texture.
+    ///
+    /// Prefer using `write_async` on the texture.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if the the dimensions of `data` don't match the `Rect`, or if the `Rect` is outside
+    /// of the mipmap.
+    pub fn write<'a, T>(&mut self, mipmap: TextureAnyMipmap<'_>, rect: Rect, data: T)
+                        -> Result<UploadTicket, AsyncUploadError>
+                        where T: Texture2dDataSource<'a>
+    {
+        let RawImage2d { data, width, height, format } = data.into_raw();
+
+        assert_eq!(width, rect.width);
+        assert_eq!(height, rect.height);
+
+        let bytes = unsafe {
+            slice::from_raw_parts(data.as_ptr() as *const u8,
+                                  data.len() * mem::size_of::<T::Data>())
+        };
+
+        let staging = self.take_buffer(bytes.len())?;
+        let slice = staging.buffer.slice(0 .. bytes.len()).unwrap();
+        slice.write(bytes);
+
+        mipmap.raw_upload_from_pixel_buffer_impl(slice.as_slice_any(), format,
+                                                 rect.left .. rect.left + width,
+                                                 rect.bottom .. rect.bottom + height, 0 .. 1, false);
+
+        let ticket = UploadTicket { fence: SyncFence::new(&self.context)? };
+        self.buffers.push_back(StagingBuffer {
+            fence: Some(SyncFence::new(&self.context)?),
+            .. staging
+        });
+
+        Ok(ticket)
+    }
+
+    /// Removes from the pool a buffer that can hold `len` bytes and that isn't used by the GPU,
+    /// or creates a new one.
+    fn take_buffer(&mut self, len: usize) -> Result<StagingBuffer, AsyncUploadError> {
+        if let Some(pos) = self.buffers.iter()
+                                       .position(|b| b.buffer.len() >= len && b.is_free())
+        {
+            return Ok(self.buffers.remove(pos).unwrap());
+        }
+
+        if self.buffers.len() >= self.max_buffers {
+            // replacing a free buffer that is too small, or waiting for the oldest upload if
+            // all the buffers are in use
+            match self.buffers.iter().position(|b| b.is_free()) {
+                Some(pos) => {
+                    self.buffers.remove(pos);
+                },
+                None => {
+                    let mut oldest = self.buffers.pop_front().unwrap();
+                    if let Some(fence) = oldest.fence.take() {
+                        fence.wait();
+                    }
+                    if oldest.buffer.len() >= len {
+                        return Ok(oldest);
+                    }
+                },
+            }
+        }
+
+        let buffer = Buffer::empty_array(&self.context, BufferType::PixelUnpackBuffer,
+                                         len.next_power_of_two(), BufferMode::Persistent)?;
+
+        Ok(StagingBuffer {
+            buffer,
+            fence: None,
+        })
+    }
+}
+
+/// Returned when uploading data asynchronously. Tells whether the transfer has finished.
+pub struct UploadTicket {
+    fence: SyncFence,
+}
+
+impl UploadTicket {
+    /// Returns true if the GPU has finished transferring the data to the texture. Doesn't
+    /// block.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.fence.is_signaled()
+    }
+
+    /// Blocks until the GPU has finished transferring the data to the texture.
+    #[inline]
+    pub fn wait(self) {
+        self.fence.wait()
+    }
+}
@@ -51,6 +51,37 @@ let uniform_buffer = glium::uniforms::UniformBuffer::new(&display, UniformBuffer
 # }
 ```
 
+A `TextureHandle` can also be passed directly as the value of a `sampler*` uniform, which doesn't
+use any texture unit, or stored in a buffer in order to access an array of textures through a
+shader storage block.
+
+```no_run
+#[macro_use]
+extern crate glium;
+
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+# let textures: Vec<glium::texture::bindless::ResidentTexture> = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+use glium::buffer::{Buffer, BufferMode, BufferType};
+use glium::texture::TextureHandle;
+
+let handles = textures.iter().map(|t| TextureHandle::new(t, &Default::default()))
+                      .collect::<Vec<_>>();
+
+// can be used as `buffer Textures { sampler2D textures[]; };` in the shader
+let buffer = Buffer::new(&display, &handles[..], BufferType::ShaderStorageBuffer,
+                         BufferMode::Default).unwrap();
+
+let uniforms = uniform! {
+    main_texture: handles[0],
+    Textures: &buffer,
+};
+# }
+```
+
+Since the handles borrow the `ResidentTexture`s, the textures can't be destroyed while they are
+used in a buffer or a uniform.
+
 Inside your shader, you can refer to the texture with a traditional `sampler*` variable. Glium
 currently doesn't check whether the type of your texture matches the expected type (but it may
 do in the future). Binding the wrong type of texture may lead to undefined values when sampling
@@ -173,13 +204,18 @@ impl<'a> TextureHandle<'a> {
         // FIXME: take sampler into account
         self.value = texture.handle;
     }
+
+    /// Returns the 64-bits value of the handle.
+    #[inline]
+    pub fn get_raw(&self) -> gl::types::GLuint64 {
+        self.value
+    }
 }
 
 impl<'a> AsUniformValue for TextureHandle<'a> {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
-        // TODO: u64
-        unimplemented!();
+        UniformValue::BindlessTexture(*self)
     }
 }
 
@@ -51,10 +51,25 @@ sample from a buffer texture of type `Integral` you need to use a `isamplerBuffe
 to sample from a buffer texture of type `Unsigned` you need to use a `usamplerBuffer`. Using the
 wrong type will result in an error.
 
+# Views
+
+A `BufferTextureView` is a buffer texture that doesn't own its buffer. It can be built from a slice
+of any buffer with `BufferTextureView::new`, or from a range of the elements of a `BufferTexture`
+with `BufferTexture::view`. This is useful for example to store the skinning matrices of all the
+objects of a scene in a single buffer, and to give each draw call a texture that only covers its
+own matrices.
+
+Using only a part of a buffer requires OpenGL 4.3, OpenGL ES 3.2 or the
+`GL_ARB_texture_buffer_range` extension.
+
+# Image load/store
+
+Buffer textures can also be bound to an image unit with `image_unit`, and then be read or written
+with an `imageBuffer`, `iimageBuffer` or `uimageBuffer` in your GLSL code.
+
 */
-use std::{ mem, fmt };
-use std::marker::PhantomData;
-use std::ops::{Deref, DerefMut};
+use std::fmt;
+use std::ops::{Deref, DerefMut, Range};
 use std::rc::Rc;
 use std::error::Error;
 
@@ -73,12 +88,17 @@ use crate::BufferExt;
 use crate::buffer::BufferMode;
 use crate::buffer::BufferType;
 use crate::buffer::Buffer;
+use crate::buffer::BufferSlice;
+use crate::buffer::BufferAnySlice;
 use crate::buffer::BufferCreationError;
 use crate::buffer::Content as BufferContent;
 
 use crate::uniforms::AsUniformValue;
+use crate::uniforms::ImageUnitAccess;
 use crate::uniforms::UniformValue;
 
+use crate::texture::{InternalFormat, InternalFormatType};
+
 /// Error that can happen while building the texture part of a buffer texture.
 #[derive(Copy, Clone, Debug)]
 pub enum TextureCreationError {
@@ -90,6 +110,12 @@ pub enum TextureCreationError {
 
     /// The size of the buffer that you are trying to bind exceeds `GL_MAX_TEXTURE_BUFFER_SIZE`.
     TooLarge,
+
+    /// Creating a buffer texture from a part of a buffer is not supported.
+    RangeNotSupported,
+
+    /// The offset of the buffer slice is not a multiple of `GL_TEXTURE_BUFFER_OFFSET_ALIGNMENT`.
+    MisalignedOffset,
 }
 
 impl fmt::Display for TextureCreationError {
@@ -102,6 +128,10 @@ impl fmt::Display for TextureCreationError {
                 "The requested format is not supported in combination with the given texture buffer type",
             TooLarge =>
                 "The size of the buffer that you are trying to bind exceeds `GL_MAX_TEXTURE_BUFFER_SIZE`",
+            RangeNotSupported =>
+                "Creating a buffer texture from a part of a buffer is not supported",
+            MisalignedOffset =>
+                "The offset of the buffer slice is not a multiple of `GL_TEXTURE_BUFFER_OFFSET_ALIGNMENT`",
         };
         fmt.write_str(desc)
     }
@@ -181,6 +211,8 @@ pub struct BufferTexture<T> where [T]: BufferContent {
     buffer: Buffer<[T]>,
     texture: gl::types::GLuint,
     ty: BufferTextureType,
+    internal_format: gl::types::GLenum,
+    format: InternalFormat,
 }
 
 impl<T> BufferTexture<T> where [T]: BufferContent, T: TextureBufferContent + Copy {
@@ -282,176 +314,45 @@ impl<T> BufferTexture<T> where [T]: BufferContent, T: TextureBufferContent + Cop
         let context = context.get_context();
         let mut ctxt = context.make_current();
 
-        // checking capabilities
-        if buffer.get_size() / mem::size_of::<T>() > ctxt.capabilities
-                                                         .max_texture_buffer_size.unwrap() as usize
-        {
-            return Err((TextureCreationError::TooLarge, buffer));
-        }
-
         // before starting, we determine the internal format and check that buffer textures are
         // supported
-        let internal_format = if ctxt.version >= &Version(Api::Gl, 3, 0) ||
-                                 ctxt.extensions.gl_oes_texture_buffer ||
-                                 ctxt.extensions.gl_ext_texture_buffer
-        {
-            match (T::get_type(), ty) {
-                (TextureBufferContentType::U8, BufferTextureType::Float) => gl::R8,
-                (TextureBufferContentType::U8, BufferTextureType::Unsigned) => gl::R8UI,
-                (TextureBufferContentType::I8, BufferTextureType::Integral) => gl::R8I,
-                (TextureBufferContentType::U16, BufferTextureType::Float) => gl::R16,
-                (TextureBufferContentType::U16, BufferTextureType::Unsigned) => gl::R16UI,
-                (TextureBufferContentType::I16, BufferTextureType::Integral) => gl::R16I,
-                (TextureBufferContentType::U32, BufferTextureType::Unsigned) => gl::R32UI,
-                (TextureBufferContentType::I32, BufferTextureType::Integral) => gl::R32I,
-                (TextureBufferContentType::U8U8, BufferTextureType::Float) => gl::RG8,
-                (TextureBufferContentType::U8U8, BufferTextureType::Unsigned) => gl::RG8UI,
-                (TextureBufferContentType::I8I8, BufferTextureType::Integral) => gl::RG8I,
-                (TextureBufferContentType::U16U16, BufferTextureType::Float) => gl::RG16,
-                (TextureBufferContentType::U16U16, BufferTextureType::Unsigned) => gl::RG16UI,
-                (TextureBufferContentType::I16I16, BufferTextureType::Integral) => gl::RG16I,
-                (TextureBufferContentType::U32U32, BufferTextureType::Unsigned) => gl::RG32UI,
-                (TextureBufferContentType::I32I32, BufferTextureType::Integral) => gl::RG32I,
-                (TextureBufferContentType::U8U8U8U8, BufferTextureType::Float) => gl::RGBA8,
-                (TextureBufferContentType::U8U8U8U8, BufferTextureType::Unsigned) => gl::RGBA8UI,
-                (TextureBufferContentType::I8I8I8I8, BufferTextureType::Integral) => gl::RGBA8I,
-                (TextureBufferContentType::U16U16U16U16, BufferTextureType::Float) => gl::RGBA16,
-                (TextureBufferContentType::U16U16U16U16, BufferTextureType::Unsigned) =>
-                                                                                      gl::RGBA16UI,
-                (TextureBufferContentType::I16I16I16I16, BufferTextureType::Integral) =>
-                                                                                       gl::RGBA16I,
-                (TextureBufferContentType::U32U32U32U32, BufferTextureType::Unsigned) =>
-                                                                                      gl::RGBA32UI,
-                (TextureBufferContentType::I32I32I32I32, BufferTextureType::Integral) =>
-                                                                                       gl::RGBA32I,
-                (TextureBufferContentType::F16, BufferTextureType::Float) => gl::R16F,
-                (TextureBufferContentType::F32, BufferTextureType::Float) => gl::R32F,
-                (TextureBufferContentType::F16F16, BufferTextureType::Float) => gl::RG16F,
-                (TextureBufferContentType::F32F32, BufferTextureType::Float) => gl::RG32F,
-                (TextureBufferContentType::F16F16F16F16, BufferTextureType::Float) => gl::RGBA16F,
-                (TextureBufferContentType::F32F32F32F32, BufferTextureType::Float) => gl::RGBA32F,
-
-                (TextureBufferContentType::U32U32U32, BufferTextureType::Unsigned)
-                                            if ctxt.version >= &Version(Api::Gl, 4, 0) ||
-                                               ctxt.extensions.gl_arb_texture_buffer_object_rgb32
-                                                                                    => gl::RGB32UI,
-                (TextureBufferContentType::I32I32I32, BufferTextureType::Integral)
-                                            if ctxt.version >= &Version(Api::Gl, 4, 0) ||
-                                               ctxt.extensions.gl_arb_texture_buffer_object_rgb32
-                                                                                    => gl::RGB32I,
-                (TextureBufferContentType::F32F32F32, BufferTextureType::Float)
-                                            if ctxt.version >= &Version(Api::Gl, 4, 0) ||
-                                               ctxt.extensions.gl_arb_texture_buffer_object_rgb32
-                                                                                    => gl::RGB32F,
-
-                _ => return Err((TextureCreationError::FormatNotSupported, buffer))
-            }
-
-        } else if ctxt.extensions.gl_arb_texture_buffer_object ||
-                  ctxt.extensions.gl_ext_texture_buffer_object
-        {
-            match (T::get_type(), ty) {
-                (TextureBufferContentType::U8U8U8U8, BufferTextureType::Float) => gl::RGBA8,
-                (TextureBufferContentType::U16U16U16U16, BufferTextureType::Float) => gl::RGBA16,
-                (TextureBufferContentType::F16F16F16F16, BufferTextureType::Float) => gl::RGBA16F,
-                (TextureBufferContentType::F32F32F32F32, BufferTextureType::Float) => gl::RGBA32F,
-                (TextureBufferContentType::I8I8I8I8, BufferTextureType::Integral) => gl::RGBA8I,
-                (TextureBufferContentType::I16I16I16I16, BufferTextureType::Integral) =>
-                                                                                      gl::RGBA16I,
-                (TextureBufferContentType::I32I32I32I32, BufferTextureType::Integral) =>
-                                                                                      gl::RGBA32I,
-                (TextureBufferContentType::U8U8U8U8, BufferTextureType::Unsigned) => gl::RGBA8UI,
-                (TextureBufferContentType::U16U16U16U16, BufferTextureType::Unsigned) =>
-                                                                                      gl::RGBA16UI,
-                (TextureBufferContentType::U32U32U32U32, BufferTextureType::Unsigned) =>
-                                                                                      gl::RGBA32UI,
-
-                (TextureBufferContentType::U32U32U32, BufferTextureType::Unsigned)
-                                            if ctxt.extensions.gl_arb_texture_buffer_object_rgb32
-                                                                                    => gl::RGB32UI,
-                (TextureBufferContentType::I32I32I32, BufferTextureType::Integral)
-                                            if ctxt.extensions.gl_arb_texture_buffer_object_rgb32
-                                                                                    => gl::RGB32I,
-                (TextureBufferContentType::F32F32F32, BufferTextureType::Float)
-                                            if ctxt.extensions.gl_arb_texture_buffer_object_rgb32
-                                                                                    => gl::RGB32F,
-
-                // TODO: intensity?
-
-                _ => return Err((TextureCreationError::FormatNotSupported, buffer))
-            }
-
-        } else {
-            return Err((TextureCreationError::NotSupported, buffer));
+        let internal_format = match get_internal_format(&ctxt, T::get_type(), ty) {
+            Ok(f) => f,
+            Err(e) => return Err((e, buffer)),
         };
 
+        // checking capabilities
+        if let Err(e) = check_texture_buffer_size(&ctxt, buffer.len()) {
+            return Err((e, buffer));
+        }
+
         // now the texture creation
         debug_assert_eq!(buffer.get_offset_bytes(), 0);
-        let id = if ctxt.version >= &Version(Api::Gl, 4, 5) ||
-                    ctxt.extensions.gl_arb_direct_state_access
-        {
-            unsafe {
-                let mut id = 0;
-                ctxt.gl.CreateTextures(gl::TEXTURE_BUFFER, 1, &mut id);
-                ctxt.gl.TextureBuffer(id, internal_format, buffer.get_id());
-                id
-            }
-
-        } else {
-            // reserving the ID
-            let id = unsafe {
-                let mut id = 0;
-                ctxt.gl.GenTextures(1, &mut id);
-                id
-            };
-
-            // binding the texture
-            unsafe {
-                ctxt.gl.BindTexture(gl::TEXTURE_BUFFER, id);
-                let act = ctxt.state.active_texture as usize;
-                ctxt.state.texture_units[act].texture = id;
-            }
-
-            // binding the buffer
-            if ctxt.version >= &Version(Api::Gl, 3, 0) ||
-               ctxt.version >= &Version(Api::GlEs, 3, 2)
-            {
-                unsafe {
-                    ctxt.gl.TexBuffer(gl::TEXTURE_BUFFER, internal_format, buffer.get_id());
-                }
-            } else if ctxt.extensions.gl_arb_texture_buffer_object {
-                unsafe {
-                    ctxt.gl.TexBufferARB(gl::TEXTURE_BUFFER, internal_format,
-                                         buffer.get_id());
-                }
-            } else if ctxt.extensions.gl_ext_texture_buffer_object ||
-                      ctxt.extensions.gl_ext_texture_buffer
-            {
-                unsafe {
-                    ctxt.gl.TexBufferEXT(gl::TEXTURE_BUFFER, internal_format,
-                                         buffer.get_id());
-                }
-            } else if ctxt.extensions.gl_oes_texture_buffer {
-                unsafe {
-                    ctxt.gl.TexBufferOES(gl::TEXTURE_BUFFER, internal_format,
-                                         buffer.get_id());
-                }
-
-            } else {
-                // handled during the choice for the internal format
-                // note that this panic will leak the texture
-                unreachable!();
-            }
-
-            id
-        };
+        let id = unsafe { create_texture(&mut ctxt, internal_format, buffer.get_id(), None) };
 
         Ok(BufferTexture {
             buffer,
             ty,
+            internal_format,
+            format: T::get_type().to_internal_format(ty),
             texture: id,
         })
     }
+
+    /// Builds a buffer texture that only contains some of the elements of this one.
+    ///
+    /// See `BufferTextureView::new` for the requirements.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the range is out of bounds.
+    #[inline]
+    pub fn view(&self, range: Range<usize>)
+                -> Result<BufferTextureView<'_>, TextureCreationError>
+    {
+        let slice = self.buffer.slice(range).expect("Range out of bounds");
+        BufferTextureView::new(slice, self.ty)
+    }
 }
 
 impl<T> Deref for BufferTexture<T> where [T]: BufferContent {
@@ -471,17 +372,10 @@ impl<T> DerefMut for BufferTexture<T> where [T]: BufferContent {
 }
 
 impl<T> Drop for BufferTexture<T> where [T]: BufferContent {
+    #[inline]
     fn drop(&mut self) {
         let mut ctxt = self.buffer.get_context().make_current();
-
-        // resetting the bindings
-        for tex_unit in ctxt.state.texture_units.iter_mut() {
-            if tex_unit.texture == self.texture {
-                tex_unit.texture = 0;
-            }
-        }
-
-        unsafe { ctxt.gl.DeleteTextures(1, [ self.texture ].as_ptr()); }
+        unsafe { destroy_texture(&mut ctxt, self.texture); }
     }
 }
 
@@ -492,7 +386,19 @@ impl<T> BufferTexture<T> where [T]: BufferContent {
         BufferTextureRef {
             texture: self.texture,
             ty: self.ty,
-            marker: PhantomData,
+            internal_format: self.internal_format,
+            format: self.format,
+            buffer: self.buffer.as_slice_any(),
+        }
+    }
+
+    /// Builds an object that binds this texture to an image unit. It can then be accessed with an
+    /// `imageBuffer`, an `iimageBuffer` or an `uimageBuffer` in your GLSL code.
+    #[inline]
+    pub fn image_unit(&self, access: ImageUnitAccess) -> BufferTextureImageUnit<'_> {
+        BufferTextureImageUnit {
+            texture: self.as_buffer_texture_ref(),
+            access,
         }
     }
 }
@@ -513,12 +419,158 @@ impl<'a, T: 'a> AsUniformValue for &'a BufferTexture<T> where [T]: BufferContent
     }
 }
 
+/// A buffer texture that gets its data from a slice of a buffer that it doesn't own.
+///
+/// Contrary to `BufferTexture`, the buffer can have any type and can be used for other purposes
+/// at the same time. For example you can write skinning matrices to a region of a uniform or
+/// vertex buffer and read them in your shader through a `samplerBuffer`.
+pub struct BufferTextureView<'a> {
+    buffer: BufferAnySlice<'a>,
+    texture: gl::types::GLuint,
+    ty: BufferTextureType,
+    internal_format: gl::types::GLenum,
+    format: InternalFormat,
+}
+
+impl<'a> BufferTextureView<'a> {
+    /// Builds a buffer texture that gets its data from a slice of a buffer.
+    ///
+    /// If the slice doesn't cover the whole buffer, this requires OpenGL 4.3, OpenGL ES 3.2 or
+    /// the `GL_ARB_texture_buffer_range` extension, and the offset of the slice must be a
+    /// multiple of `GL_TEXTURE_BUFFER_OFFSET_ALIGNMENT` bytes.
+    pub fn new<T>(slice: BufferSlice<'a, [T]>, ty: BufferTextureType)
+                  -> Result<BufferTextureView<'a>, TextureCreationError>
+                  where [T]: BufferContent, T: TextureBufferContent + Copy
+    {
+        let (id, internal_format) = {
+            let mut ctxt = slice.get_context().make_current();
+
+            let internal_format = get_internal_format(&ctxt, T::get_type(), ty)?;
+            check_texture_buffer_size(&ctxt, slice.len())?;
+
+            let range = if slice.is_whole_buffer() {
+                None
+            } else {
+                let alignment = match ctxt.capabilities.texture_buffer_offset_alignment {
+                    Some(a) => a,
+                    None => return Err(TextureCreationError::RangeNotSupported),
+                };
+
+                if slice.get_offset_bytes() % alignment != 0 {
+                    return Err(TextureCreationError::MisalignedOffset);
+                }
+
+                Some((slice.get_offset_bytes(), slice.get_size()))
+            };
+
+            let id = unsafe {
+                create_texture(&mut ctxt, internal_format, slice.as_slice_any().get_id(), range)
+            };
+
+            (id, internal_format)
+        };
+
+        Ok(BufferTextureView {
+            buffer: slice.as_slice_any(),
+            texture: id,
+            ty,
+            internal_format,
+            format: T::get_type().to_internal_format(ty),
+        })
+    }
+
+    /// Returns the number of texels of the texture.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buffer.get_elements_count()
+    }
+
+    /// Returns true if the texture doesn't contain any texel.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the type of the texture.
+    #[inline]
+    pub fn get_texture_type(&self) -> BufferTextureType {
+        self.ty
+    }
+
+    /// Builds a `BufferTextureRef`.
+    #[inline]
+    pub fn as_buffer_texture_ref(&self) -> BufferTextureRef<'_> {
+        BufferTextureRef {
+            texture: self.texture,
+            ty: self.ty,
+            internal_format: self.internal_format,
+            format: self.format,
+            buffer: self.buffer,
+        }
+    }
+
+    /// Builds an object that binds this texture to an image unit. It can then be accessed with an
+    /// `imageBuffer`, an `iimageBuffer` or an `uimageBuffer` in your GLSL code.
+    #[inline]
+    pub fn image_unit(&self, access: ImageUnitAccess) -> BufferTextureImageUnit<'_> {
+        BufferTextureImageUnit {
+            texture: self.as_buffer_texture_ref(),
+            access,
+        }
+    }
+}
+
+impl<'a> Drop for BufferTextureView<'a> {
+    #[inline]
+    fn drop(&mut self) {
+        let mut ctxt = self.buffer.get_context().make_current();
+        unsafe { destroy_texture(&mut ctxt, self.texture); }
+    }
+}
+
+impl<'a> AsUniformValue for BufferTextureView<'a> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        // FIXME: handle `glMemoryBarrier` for the buffer
+        UniformValue::BufferTexture(self.as_buffer_texture_ref())
+    }
+}
+
+impl<'a, 'b> AsUniformValue for &'b BufferTextureView<'a> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        // FIXME: handle `glMemoryBarrier` for the buffer
+        UniformValue::BufferTexture(self.as_buffer_texture_ref())
+    }
+}
+
+/// A buffer texture bound to an image unit, in order to be read or written by a shader.
+///
+/// If the shader declares the format of the image with a layout qualifier, the internal format
+/// of the texture must be compatible with it or drawing will return an error. When the access
+/// allows writing, the required memory barrier is inserted before the buffer is used again.
+#[derive(Copy, Clone)]
+pub struct BufferTextureImageUnit<'a> {
+    texture: BufferTextureRef<'a>,
+    access: ImageUnitAccess,
+}
+
+impl<'a> AsUniformValue for BufferTextureImageUnit<'a> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        // FIXME: handle `glMemoryBarrier` for the buffer
+        UniformValue::BufferTextureImage(self.texture, self.access)
+    }
+}
+
 /// Holds a reference to a `BufferTexture`.
 #[derive(Copy, Clone)]
 pub struct BufferTextureRef<'a> {
     texture: gl::types::GLuint,
     ty: BufferTextureType,
-    marker: PhantomData<&'a ()>,
+    internal_format: gl::types::GLenum,
+    format: InternalFormat,
+    buffer: BufferAnySlice<'a>,
 }
 
 impl<'a> BufferTextureRef<'a> {
@@ -527,6 +579,24 @@ impl<'a> BufferTextureRef<'a> {
     pub fn get_texture_type(&self) -> BufferTextureType {
         self.ty
     }
+
+    /// Returns the internal format of the texture.
+    #[inline]
+    pub fn get_internal_format(&self) -> InternalFormat {
+        self.format
+    }
+
+    /// Returns the OpenGL enum of the internal format of the texture.
+    #[inline]
+    pub(crate) fn get_internal_format_glenum(&self) -> gl::types::GLenum {
+        self.internal_format
+    }
+
+    /// Returns the slice of the buffer that the texture gets its data from.
+    #[inline]
+    pub(crate) fn get_buffer(&self) -> BufferAnySlice<'a> {
+        self.buffer
+    }
 }
 
 impl<'a> TextureExt for BufferTextureRef<'a> {
@@ -545,6 +615,11 @@ impl<'a> TextureExt for BufferTextureRef<'a> {
         gl::TEXTURE_BUFFER
     }
 
+    #[inline]
+    fn get_base_level(&self) -> u32 {
+        0
+    }
+
     #[inline]
     fn bind_to_current(&self, ctxt: &mut CommandContext<'_>) -> gl::types::GLenum {
         unsafe { ctxt.gl.BindTexture(gl::TEXTURE_BUFFER, self.texture); }
@@ -552,11 +627,210 @@ impl<'a> TextureExt for BufferTextureRef<'a> {
     }
 }
 
+/// Determines the internal format of a buffer texture and checks that buffer textures are
+/// supported.
+fn get_internal_format(ctxt: &CommandContext<'_>, content: TextureBufferContentType,
+                       ty: BufferTextureType)
+                       -> Result<gl::types::GLenum, TextureCreationError>
+{
+    if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+       ctxt.extensions.gl_oes_texture_buffer || ctxt.extensions.gl_ext_texture_buffer
+    {
+        Ok(match (content, ty) {
+            (TextureBufferContentType::U8, BufferTextureType::Float) => gl::R8,
+            (TextureBufferContentType::U8, BufferTextureType::Unsigned) => gl::R8UI,
+            (TextureBufferContentType::I8, BufferTextureType::Integral) => gl::R8I,
+            (TextureBufferContentType::U16, BufferTextureType::Float) => gl::R16,
+            (TextureBufferContentType::U16, BufferTextureType::Unsigned) => gl::R16UI,
+            (TextureBufferContentType::I16, BufferTextureType::Integral) => gl::R16I,
+            (TextureBufferContentType::U32, BufferTextureType::Unsigned) => gl::R32UI,
+            (TextureBufferContentType::I32, BufferTextureType::Integral) => gl::R32I,
+            (TextureBufferContentType::U8U8, BufferTextureType::Float) => gl::RG8,
+            (TextureBufferContentType::U8U8, BufferTextureType::Unsigned) => gl::RG8UI,
+            (TextureBufferContentType::I8I8, BufferTextureType::Integral) => gl::RG8I,
+            (TextureBufferContentType::U16U16, BufferTextureType::Float) => gl::RG16,
+            (TextureBufferContentType::U16U16, BufferTextureType::Unsigned) => gl::RG16UI,
+            (TextureBufferContentType::I16I16, BufferTextureType::Integral) => gl::RG16I,
+            (TextureBufferContentType::U32U32, BufferTextureType::Unsigned) => gl::RG32UI,
+            (TextureBufferContentType::I32I32, BufferTextureType::Integral) => gl::RG32I,
+            (TextureBufferContentType::U8U8U8U8, BufferTextureType::Float) => gl::RGBA8,
+            (TextureBufferContentType::U8U8U8U8, BufferTextureType::Unsigned) => gl::RGBA8UI,
+            (TextureBufferContentType::I8I8I8I8, BufferTextureType::Integral) => gl::RGBA8I,
+            (TextureBufferContentType::U16U16U16U16, BufferTextureType::Float) => gl::RGBA16,
+            (TextureBufferContentType::U16U16U16U16, BufferTextureType::Unsigned) =>
+                                                                                  gl::RGBA16UI,
+            (TextureBufferContentType::I16I16I16I16, BufferTextureType::Integral) =>
+                                                                                   gl::RGBA16I,
+            (TextureBufferContentType::U32U32U32U32, BufferTextureType::Unsigned) =>
+                                                                                  gl::RGBA32UI,
+            (TextureBufferContentType::I32I32I32I32, BufferTextureType::Integral) =>
+                                                                                   gl::RGBA32I,
+            (TextureBufferContentType::F16, BufferTextureType::Float) => gl::R16F,
+            (TextureBufferContentType::F32, BufferTextureType::Float) => gl::R32F,
+            (TextureBufferContentType::F16F16, BufferTextureType::Float) => gl::RG16F,
+            (TextureBufferContentType::F32F32, BufferTextureType::Float) => gl::RG32F,
+            (TextureBufferContentType::F16F16F16F16, BufferTextureType::Float) => gl::RGBA16F,
+            (TextureBufferContentType::F32F32F32F32, BufferTextureType::Float) => gl::RGBA32F,
+
+            (TextureBufferContentType::U32U32U32, BufferTextureType::Unsigned)
+                                        if ctxt.version >= &Version(Api::Gl, 4, 0) ||
+                                           ctxt.extensions.gl_arb_texture_buffer_object_rgb32
+                                                                                => gl::RGB32UI,
+            (TextureBufferContentType::I32I32I32, BufferTextureType::Integral)
+                                        if ctxt.version >= &Version(Api::Gl, 4, 0) ||
+                                           ctxt.extensions.gl_arb_texture_buffer_object_rgb32
+                                                                                => gl::RGB32I,
+            (TextureBufferContentType::F32F32F32, BufferTextureType::Float)
+                                        if ctxt.version >= &Version(Api::Gl, 4, 0) ||
+                                           ctxt.extensions.gl_arb_texture_buffer_object_rgb32
+                                                                                => gl::RGB32F,
+
+            _ => return Err(TextureCreationError::FormatNotSupported)
+        })
+
+    } else if ctxt.extensions.gl_arb_texture_buffer_object ||
+              ctxt.extensions.gl_ext_texture_buffer_object
+    {
+        Ok(match (content, ty) {
+            (TextureBufferContentType::U8U8U8U8, BufferTextureType::Float) => gl::RGBA8,
+            (TextureBufferContentType::U16U16U16U16, BufferTextureType::Float) => gl::RGBA16,
+            (TextureBufferContentType::F16F16F16F16, BufferTextureType::Float) => gl::RGBA16F,
+            (TextureBufferContentType::F32F32F32F32, BufferTextureType::Float) => gl::RGBA32F,
+            (TextureBufferContentType::I8I8I8I8, BufferTextureType::Integral) => gl::RGBA8I,
+            (TextureBufferContentType::I16I16I16I16, BufferTextureType::Integral) =>
+                                                                                  gl::RGBA16I,
+            (TextureBufferContentType::I32I32I32I32, BufferTextureType::Integral) =>
+                                                                                  gl::RGBA32I,
+            (TextureBufferContentType::U8U8U8U8, BufferTextureType::Unsigned) => gl::RGBA8UI,
+            (TextureBufferContentType::U16U16U16U16, BufferTextureType::Unsigned) =>
+                                                                                  gl::RGBA16UI,
+            (TextureBufferContentType::U32U32U32U32, BufferTextureType::Unsigned) =>
+                                                                                  gl::RGBA32UI,
+
+            (TextureBufferContentType::U32U32U32, BufferTextureType::Unsigned)
+                                        if ctxt.extensions.gl_arb_texture_buffer_object_rgb32
+                                                                                => gl::RGB32UI,
+            (TextureBufferContentType::I32I32I32, BufferTextureType::Integral)
+                                        if ctxt.extensions.gl_arb_texture_buffer_object_rgb32
+                                                                                => gl::RGB32I,
+            (TextureBufferContentType::F32F32F32, BufferTextureType::Float)
+                                        if ctxt.extensions.gl_arb_texture_buffer_object_rgb32
+                                                                                => gl::RGB32F,
+
+            // TODO: intensity?
+
+            _ => return Err(TextureCreationError::FormatNotSupported)
+        })
+
+    } else {
+        Err(TextureCreationError::NotSupported)
+    }
+}
+
+/// Checks that a buffer texture with the given number of texels can be created.
+fn check_texture_buffer_size(ctxt: &CommandContext<'_>, len: usize)
+                             -> Result<(), TextureCreationError>
+{
+    match ctxt.capabilities.max_texture_buffer_size {
+        None => Err(TextureCreationError::NotSupported),
+        Some(max) if len > max as usize => Err(TextureCreationError::TooLarge),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Creates a buffer texture that gets its data from a buffer. If `range` contains an offset and
+/// a size in bytes, only this range of the buffer is used.
+///
+/// The internal format and the range must have been checked beforehand.
+unsafe fn create_texture(ctxt: &mut CommandContext<'_>, internal_format: gl::types::GLenum,
+                         buffer: gl::types::GLuint, range: Option<(usize, usize)>)
+                         -> gl::types::GLuint
+{
+    if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access {
+        let mut id = 0;
+        ctxt.gl.CreateTextures(gl::TEXTURE_BUFFER, 1, &mut id);
+
+        match range {
+            Some((offset, size)) => {
+                ctxt.gl.TextureBufferRange(id, internal_format, buffer,
+                                           offset as gl::types::GLintptr,
+                                           size as gl::types::GLsizeiptr);
+            },
+            None => {
+                ctxt.gl.TextureBuffer(id, internal_format, buffer);
+            },
+        }
+
+        return id;
+    }
+
+    // reserving the ID
+    let mut id = 0;
+    ctxt.gl.GenTextures(1, &mut id);
+
+    // binding the texture
+    ctxt.gl.BindTexture(gl::TEXTURE_BUFFER, id);
+    let act = ctxt.state.active_texture as usize;
+    ctxt.state.texture_units[act].texture = id;
+
+    // binding the buffer
+    if let Some((offset, size)) = range {
+        let offset = offset as gl::types::GLintptr;
+        let size = size as gl::types::GLsizeiptr;
+
+        if ctxt.version >= &Version(Api::Gl, 4, 3) ||
+           ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+           ctxt.extensions.gl_arb_texture_buffer_range
+        {
+            ctxt.gl.TexBufferRange(gl::TEXTURE_BUFFER, internal_format, buffer, offset, size);
+        } else if ctxt.extensions.gl_oes_texture_buffer {
+            ctxt.gl.TexBufferRangeOES(gl::TEXTURE_BUFFER, internal_format, buffer, offset, size);
+        } else {
+            // handled when checking the range
+            // note that this panic will leak the texture
+            unreachable!();
+        }
+
+    } else if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+              ctxt.version >= &Version(Api::GlEs, 3, 2)
+    {
+        ctxt.gl.TexBuffer(gl::TEXTURE_BUFFER, internal_format, buffer);
+    } else if ctxt.extensions.gl_arb_texture_buffer_object {
+        ctxt.gl.TexBufferARB(gl::TEXTURE_BUFFER, internal_format, buffer);
+    } else if ctxt.extensions.gl_ext_texture_buffer_object ||
+              ctxt.extensions.gl_ext_texture_buffer
+    {
+        ctxt.gl.TexBufferEXT(gl::TEXTURE_BUFFER, internal_format, buffer);
+    } else if ctxt.extensions.gl_oes_texture_buffer {
+        ctxt.gl.TexBufferOES(gl::TEXTURE_BUFFER, internal_format, buffer);
+
+    } else {
+        // handled during the choice for the internal format
+        // note that this panic will leak the texture
+        unreachable!();
+    }
+
+    id
+}
+
+/// Resets the texture units that use a buffer texture, then destroys it.
+unsafe fn destroy_texture(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
+    // resetting the bindings
+    for tex_unit in ctxt.state.texture_units.iter_mut() {
+        if tex_unit.texture == id {
+            tex_unit.texture = 0;
+        }
+    }
+
+    ctxt.gl.DeleteTextures(1, [ id ].as_ptr());
+}
+
 ///
 ///
 /// Note that some three-component types are missing. This is not a mistake. OpenGL doesn't
 /// support them.
 #[allow(missing_docs)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum TextureBufferContentType {
     U8,
     I8,
@@ -587,6 +861,53 @@ pub enum TextureBufferContentType {
     F32F32F32F32,
 }
 
+impl TextureBufferContentType {
+    /// Returns the internal format of a buffer texture with this content and the given type.
+    pub fn to_internal_format(&self, ty: BufferTextureType) -> InternalFormat {
+        use self::TextureBufferContentType::*;
+
+        let (components, bits, float) = match *self {
+            U8 | I8 => (1, 8, false),
+            U16 | I16 => (1, 16, false),
+            U32 | I32 => (1, 32, false),
+            U8U8 | I8I8 => (2, 8, false),
+            U16U16 | I16I16 => (2, 16, false),
+            U32U32 | I32I32 => (2, 32, false),
+            U32U32U32 | I32I32I32 => (3, 32, false),
+            U8U8U8U8 | I8I8I8I8 => (4, 8, false),
+            U16U16U16U16 | I16I16I16I16 => (4, 16, false),
+            U32U32U32U32 | I32I32I32I32 => (4, 32, false),
+            F16 => (1, 16, true),
+            F32 => (1, 32, true),
+            F16F16 => (2, 16, true),
+            F32F32 => (2, 32, true),
+            F32F32F32 => (3, 32, true),
+            F16F16F16F16 => (4, 16, true),
+            F32F32F32F32 => (4, 32, true),
+        };
+
+        let signed = matches!(*self, I8 | I16 | I32 | I8I8 | I16I16 | I32I32 | I32I32I32 |
+                                     I8I8I8I8 | I16I16I16I16 | I32I32I32I32);
+
+        let ty = match (ty, float, signed) {
+            (_, true, _) => InternalFormatType::Float,
+            (BufferTextureType::Float, false, true) => InternalFormatType::SignedNormalized,
+            (BufferTextureType::Float, false, false) => InternalFormatType::UnsignedNormalized,
+            (BufferTextureType::Integral, false, _) => InternalFormatType::Int,
+            (BufferTextureType::Unsigned, false, _) => InternalFormatType::UnsignedInt,
+        };
+
+        match components {
+            1 => InternalFormat::OneComponent { ty1: ty, bits1: bits },
+            2 => InternalFormat::TwoComponents { ty1: ty, bits1: bits, ty2: ty, bits2: bits },
+            3 => InternalFormat::ThreeComponents { ty1: ty, bits1: bits, ty2: ty, bits2: bits,
+                                                   ty3: ty, bits3: bits },
+            _ => InternalFormat::FourComponents { ty1: ty, bits1: bits, ty2: ty, bits2: bits,
+                                                  ty3: ty, bits3: bits, ty4: ty, bits4: bits },
+        }
+    }
+}
+
 /// Trait for data types that can be interpreted by a buffer texture.
 pub unsafe trait TextureBufferContent: BufferContent {
     /// Returns the enumeration corresponding to elements of this data type.
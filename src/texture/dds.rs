@@ -0,0 +1,454 @@
+/*!
+Loading textures from DDS files.
+
+DDS files are the most common container for the block-compressed formats used by games. The
+`DdsTexture` trait, implemented on the compressed texture types of glium, creates a texture
+with all the mipmap levels, array layers and cube faces contained in a file.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+use glium::texture::CompressedSrgbTexture2d;
+use glium::texture::dds::DdsTexture;
+
+let data = std::fs::read("texture.dds").unwrap();
+let texture = CompressedSrgbTexture2d::from_dds(&display, &data).unwrap();
+```
+
+The DXT1, DXT3 and DXT5 (also known as BC1, BC2 and BC3) formats, as well as BC4, BC5, BC6H and
+BC7 with the `DX10` extended header, are supported.
+
+Files without the extended header can't indicate whether their content is in the sRGB color
+space, and can be loaded both as regular and as sRGB textures. Files with the extended header
+can only be loaded as the kind of texture that corresponds to their format.
+
+The type of the texture must match the content of the file: a file with six faces can only be
+loaded as a cubemap, and a file with several array layers only as an array texture. Volume
+textures are not supported.
+
+The rows of the images are uploaded in the order in which they are stored in the file, which
+means that the first row of the file ends up at the texture coordinate `0.0`.
+
+Compressed textures can only be uploaded if the dimensions of each mipmap level are multiples
+of 4 or are smaller than 4. Files that don't respect this produce a `DimensionsNotSupported`
+error.
+
+This module is only available with the `dds` feature.
+
+*/
+use std::borrow::Cow;
+use std::cmp;
+use std::error::Error;
+use std::fmt;
+
+use crate::TextureMipmapExt;
+
+use crate::backend::Facade;
+use crate::image_format::ClientFormatAny;
+use crate::texture::{CompressedFormat, CompressedMipmapsOption, CompressedSrgbFormat};
+use crate::texture::{Dimensions, TextureAny, TextureCreationError};
+
+use crate::texture::{CompressedTexture2d, CompressedTexture2dArray};
+use crate::texture::{CompressedCubemap, CompressedCubemapArray};
+use crate::texture::{CompressedSrgbTexture2d, CompressedSrgbTexture2dArray};
+use crate::texture::{CompressedSrgbCubemap, CompressedSrgbCubemapArray};
+
+/// The four bytes that start every DDS file.
+const MAGIC: [u8; 4] = *b"DDS ";
+
+/// Size of the magic number and of the header that follows it.
+const HEADER_SIZE: usize = 128;
+
+/// Size of the extended header that follows the header if the four-character code is `DX10`.
+const HEADER_DX10_SIZE: usize = 20;
+
+/// Flag of the header indicating that the number of mipmaps is valid.
+const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+
+/// Flag of the pixel format indicating that the four-character code is valid.
+const DDPF_FOURCC: u32 = 0x4;
+
+/// Flag of the second capabilities indicating that the file contains a cubemap.
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+
+/// Flag of the second capabilities indicating that the file contains a volume texture.
+const DDSCAPS2_VOLUME: u32 = 0x200000;
+
+/// Flag of the extended header indicating that the file contains a cubemap.
+const DDS_RESOURCE_MISC_TEXTURECUBE: u32 = 0x4;
+
+/// Error that can happen when loading a DDS file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DdsError {
+    /// The data is not a valid DDS file.
+    InvalidFile,
+
+    /// The file uses a format that glium doesn't support.
+    UnsupportedFormat,
+
+    /// The type of the texture doesn't match the content of the file.
+    WrongTextureType,
+
+    /// Failed to create the texture.
+    TextureCreationError(TextureCreationError),
+}
+
+impl fmt::Display for DdsError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::DdsError::*;
+        let desc = match *self {
+            InvalidFile =>
+                "The data is not a valid DDS file",
+            UnsupportedFormat =>
+                "The file uses a format that glium doesn't support",
+            WrongTextureType =>
+                "The type of the texture doesn't match the content of the file",
+            TextureCreationError(_) =>
+                "Failed to create the texture",
+        };
+        fmt.write_str(desc)
+    }
+}
+
+impl Error for DdsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use self::DdsError::*;
+        match *self {
+            TextureCreationError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<TextureCreationError> for DdsError {
+    #[inline]
+    fn from(err: TextureCreationError) -> DdsError {
+        DdsError::TextureCreationError(err)
+    }
+}
+
+/// A texture type that can be loaded from DDS files.
+pub trait DdsTexture: Sized {
+    /// Creates a texture from the content of a DDS file, with all the mipmap levels, array
+    /// layers and faces that it contains.
+    fn from_dds<F: ?Sized>(facade: &F, data: &[u8]) -> Result<Self, DdsError> where F: Facade;
+}
+
+macro_rules! impl_dds_texture {
+    ($ty:ident, $format:ident, $client:ident, $dimensions:ident { $($arg:ident),* }) => (
+        impl DdsTexture for $ty {
+            fn from_dds<F: ?Sized>(facade: &F, data: &[u8]) -> Result<$ty, DdsError>
+                                   where F: Facade
+            {
+                let file = File::parse(data)?;
+                let format = file.$format.ok_or(DdsError::WrongTextureType)?;
+                let mipmaps = CompressedMipmapsOption::EmptyMipmapsMax(file.mipmap_levels - 1);
+
+                let texture = match file.dimensions {
+                    Dimensions::$dimensions { $($arg),* } => {
+                        $ty::empty_with_format(facade, format, mipmaps, $($arg),*)?
+                    },
+                    _ => return Err(DdsError::WrongTextureType),
+                };
+
+                upload(&texture, &file, ClientFormatAny::$client(format))?;
+                Ok(texture)
+            }
+        }
+    );
+}
+
+impl_dds_texture!(CompressedTexture2d, format, CompressedFormat, Texture2d { width, height });
+impl_dds_texture!(CompressedTexture2dArray, format, CompressedFormat,
+                  Texture2dArray { width, height, array_size });
+impl_dds_texture!(CompressedCubemap, format, CompressedFormat, Cubemap { dimension });
+impl_dds_texture!(CompressedCubemapArray, format, CompressedFormat,
+                  CubemapArray { dimension, array_size });
+impl_dds_texture!(CompressedSrgbTexture2d, srgb_format, CompressedSrgbFormat,
+                  Texture2d { width, height });
+impl_dds_texture!(CompressedSrgbTexture2dArray, srgb_format, CompressedSrgbFormat,
+                  Texture2dArray { width, height, array_size });
+impl_dds_texture!(CompressedSrgbCubemap, srgb_format, CompressedSrgbFormat,
+                  Cubemap { dimension });
+impl_dds_texture!(CompressedSrgbCubemapArray, srgb_format, CompressedSrgbFormat,
+                  CubemapArray { dimension, array_size });
+
+/// A parsed DDS file.
+struct File<'a> {
+    /// The format of the file, if it can be loaded as a regular texture.
+    format: Option<CompressedFormat>,
+    /// The format of the file, if it can be loaded as an sRGB texture.
+    srgb_format: Option<CompressedSrgbFormat>,
+    /// Number of bytes of a block of 4x4 texels.
+    block_size: usize,
+    dimensions: Dimensions,
+    mipmap_levels: u32,
+    /// Number of array layers multiplied by the number of faces.
+    images: u32,
+    /// The images of the file, starting with all the mipmaps of the first array layer or face.
+    data: &'a [u8],
+}
+
+impl<'a> File<'a> {
+    fn parse(data: &'a [u8]) -> Result<File<'a>, DdsError> {
+        if data.len() < HEADER_SIZE || data[.. 4] != MAGIC || read_u32(data, 4) != 124 {
+            return Err(DdsError::InvalidFile);
+        }
+
+        let flags = read_u32(data, 8);
+        let height = read_u32(data, 12);
+        let width = read_u32(data, 16);
+        let mipmap_count = read_u32(data, 28);
+        let pixel_format_flags = read_u32(data, 80);
+        let four_cc = &data[84 .. 88];
+        let caps2 = read_u32(data, 112);
+
+        if width == 0 || height == 0 {
+            return Err(DdsError::InvalidFile);
+        }
+
+        if caps2 & DDSCAPS2_VOLUME != 0 {
+            return Err(DdsError::WrongTextureType);
+        }
+
+        if pixel_format_flags & DDPF_FOURCC == 0 {
+            return Err(DdsError::UnsupportedFormat);
+        }
+
+        let (format, srgb_format, cubemap, array_size, data_offset) = if four_cc == b"DX10" {
+            if data.len() < HEADER_SIZE + HEADER_DX10_SIZE {
+                return Err(DdsError::InvalidFile);
+            }
+
+            let dxgi_format = read_u32(data, 128);
+            let misc_flags = read_u32(data, 136);
+            let array_size = cmp::max(read_u32(data, 140), 1);
+
+            let (format, srgb_format) = match dxgi_format {
+                71 => (Some(CompressedFormat::S3tcDxt1Alpha), None),
+                72 => (None, Some(CompressedSrgbFormat::S3tcDxt1Alpha)),
+                74 => (Some(CompressedFormat::S3tcDxt3Alpha), None),
+                75 => (None, Some(CompressedSrgbFormat::S3tcDxt3Alpha)),
+                77 => (Some(CompressedFormat::S3tcDxt5Alpha), None),
+                78 => (None, Some(CompressedSrgbFormat::S3tcDxt5Alpha)),
+                80 => (Some(CompressedFormat::RgtcFormatU), None),
+                81 => (Some(CompressedFormat::RgtcFormatI), None),
+                83 => (Some(CompressedFormat::RgtcFormatUU), None),
+                84 => (Some(CompressedFormat::RgtcFormatII), None),
+                95 => (Some(CompressedFormat::BptcUnsignedFloat3), None),
+                96 => (Some(CompressedFormat::BptcSignedFloat3), None),
+                98 => (Some(CompressedFormat::BptcUnorm4), None),
+                99 => (None, Some(CompressedSrgbFormat::Bptc)),
+                _ => return Err(DdsError::UnsupportedFormat),
+            };
+
+            let cubemap = misc_flags & DDS_RESOURCE_MISC_TEXTURECUBE != 0;
+            (format, srgb_format, cubemap, array_size, HEADER_SIZE + HEADER_DX10_SIZE)
+
+        } else {
+            // DXT1 files can contain texels with an alpha of 0, the alpha variant of the format
+            // handles both cases
+            let (format, srgb_format) = match four_cc {
+                b"DXT1" => (Some(CompressedFormat::S3tcDxt1Alpha),
+                            Some(CompressedSrgbFormat::S3tcDxt1Alpha)),
+                b"DXT2" | b"DXT3" => (Some(CompressedFormat::S3tcDxt3Alpha),
+                                      Some(CompressedSrgbFormat::S3tcDxt3Alpha)),
+                b"DXT4" | b"DXT5" => (Some(CompressedFormat::S3tcDxt5Alpha),
+                                      Some(CompressedSrgbFormat::S3tcDxt5Alpha)),
+                b"ATI1" | b"BC4U" => (Some(CompressedFormat::RgtcFormatU), None),
+                b"BC4S" => (Some(CompressedFormat::RgtcFormatI), None),
+                b"ATI2" | b"BC5U" => (Some(CompressedFormat::RgtcFormatUU), None),
+                b"BC5S" => (Some(CompressedFormat::RgtcFormatII), None),
+                _ => return Err(DdsError::UnsupportedFormat),
+            };
+
+            (format, srgb_format, caps2 & DDSCAPS2_CUBEMAP != 0, 1, HEADER_SIZE)
+        };
+
+        let dimensions = match (cubemap, array_size) {
+            (true, _) if width != height => return Err(DdsError::InvalidFile),
+            (true, 1) => Dimensions::Cubemap { dimension: width },
+            (true, array_size) => Dimensions::CubemapArray { dimension: width, array_size },
+            (false, 1) => Dimensions::Texture2d { width, height },
+            (false, array_size) => Dimensions::Texture2dArray { width, height, array_size },
+        };
+
+        let max_levels = 32 - cmp::max(width, height).leading_zeros();
+        let mipmap_levels = if flags & DDSD_MIPMAPCOUNT != 0 {
+            cmp::max(mipmap_count, 1)
+        } else {
+            1
+        };
+        if mipmap_levels > max_levels {
+            return Err(DdsError::InvalidFile);
+        }
+
+        let block_size = match (format, srgb_format) {
+            (Some(format), _) => ClientFormatAny::CompressedFormat(format),
+            (None, Some(format)) => ClientFormatAny::CompressedSrgbFormat(format),
+            (None, None) => unreachable!(),
+        }.get_buffer_size(4, Some(4), None, None);
+
+        let file = File {
+            format,
+            srgb_format,
+            block_size,
+            dimensions,
+            mipmap_levels,
+            images: array_size * if cubemap { 6 } else { 1 },
+            data: &data[data_offset ..],
+        };
+
+        if file.data.len() < file.images as usize * file.get_layer_size() {
+            return Err(DdsError::InvalidFile);
+        }
+
+        // the blocks of the levels must be entirely used, except for the smallest levels
+        for level in 0 .. file.mipmap_levels {
+            let (width, height) = file.get_level_dimensions(level);
+            if (width >= 4 && width % 4 != 0) || (height >= 4 && height % 4 != 0) {
+                return Err(TextureCreationError::DimensionsNotSupported.into());
+            }
+        }
+
+        Ok(file)
+    }
+
+    /// Returns the width and height of a mipmap level.
+    fn get_level_dimensions(&self, level: u32) -> (u32, u32) {
+        let (width, height) = match self.dimensions {
+            Dimensions::Texture2d { width, height } |
+            Dimensions::Texture2dArray { width, height, .. } => (width, height),
+            Dimensions::Cubemap { dimension } |
+            Dimensions::CubemapArray { dimension, .. } => (dimension, dimension),
+            _ => unreachable!(),
+        };
+
+        (cmp::max(1, width >> level), cmp::max(1, height >> level))
+    }
+
+    /// Returns the number of bytes of a mipmap level of one array layer or face.
+    fn get_level_size(&self, level: u32) -> usize {
+        let (width, height) = self.get_level_dimensions(level);
+        let blocks_x = width.div_ceil(4) as usize;
+        let blocks_y = height.div_ceil(4) as usize;
+        blocks_x * blocks_y * self.block_size
+    }
+
+    /// Returns the number of bytes of all the mipmap levels of one array layer or face.
+    fn get_layer_size(&self) -> usize {
+        (0 .. self.mipmap_levels).map(|level| self.get_level_size(level)).sum()
+    }
+}
+
+/// Uploads all the images of a file to a texture that has been created with the dimensions and
+/// the format of the file.
+fn upload(texture: &TextureAny, file: &File<'_>, format: ClientFormatAny) -> Result<(), DdsError> {
+    let is_layered = !matches!(file.dimensions, Dimensions::Texture2d { .. });
+
+    // the images of the file are ordered by array layer or face, then by mipmap level
+    let mut offset = 0;
+    for image in 0 .. file.images {
+        for level in 0 .. file.mipmap_levels {
+            let size = file.get_level_size(level);
+            let data = &file.data[offset .. offset + size];
+            offset += size;
+
+            // the texture can have less levels than the file if mipmaps aren't supported
+            let mipmap = match texture.mipmap(level) {
+                Some(mipmap) => mipmap,
+                None => continue,
+            };
+
+            let (width, height) = file.get_level_dimensions(level);
+            let (z_offset, depth) = if is_layered { (image, Some(1)) } else { (0, None) };
+            mipmap.upload_texture(0, 0, z_offset, (format, Cow::Borrowed(data)), width,
+                                  Some(height), depth, false)
+                  .map_err(|_| TextureCreationError::FormatNotSupported)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[inline]
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&data[offset .. offset + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DdsError, File};
+    use crate::texture::{CompressedFormat, CompressedSrgbFormat, Dimensions};
+    use crate::texture::TextureCreationError;
+
+    /// Builds a file whose images are filled with zeroes.
+    fn build_file(four_cc: &[u8; 4], dx10: Option<(u32, u32, u32)>, width: u32, height: u32,
+                  levels: u32, caps2: u32, data_size: usize) -> Vec<u8>
+    {
+        let mut data = vec![0; 128];
+        data[.. 4].copy_from_slice(b"DDS ");
+        data[4 .. 8].copy_from_slice(&124u32.to_le_bytes());
+        data[8 .. 12].copy_from_slice(&0x20000u32.to_le_bytes());
+        data[12 .. 16].copy_from_slice(&height.to_le_bytes());
+        data[16 .. 20].copy_from_slice(&width.to_le_bytes());
+        data[28 .. 32].copy_from_slice(&levels.to_le_bytes());
+        data[80 .. 84].copy_from_slice(&4u32.to_le_bytes());
+        data[84 .. 88].copy_from_slice(four_cc);
+        data[112 .. 116].copy_from_slice(&caps2.to_le_bytes());
+
+        if let Some((dxgi_format, misc_flags, array_size)) = dx10 {
+            data.extend_from_slice(&dxgi_format.to_le_bytes());
+            data.extend_from_slice(&3u32.to_le_bytes());
+            data.extend_from_slice(&misc_flags.to_le_bytes());
+            data.extend_from_slice(&array_size.to_le_bytes());
+            data.extend_from_slice(&0u32.to_le_bytes());
+        }
+
+        data.resize(data.len() + data_size, 0);
+        data
+    }
+
+    #[test]
+    fn dxt5_with_mipmaps() {
+        // 16x16, 8x8, 4x4, 2x2 and 1x1 levels
+        let data = build_file(b"DXT5", None, 16, 16, 5, 0, (16 + 4 + 1 + 1 + 1) * 16);
+        let file = File::parse(&data).unwrap();
+        assert_eq!(file.format, Some(CompressedFormat::S3tcDxt5Alpha));
+        assert_eq!(file.srgb_format, Some(CompressedSrgbFormat::S3tcDxt5Alpha));
+        assert_eq!(file.dimensions, Dimensions::Texture2d { width: 16, height: 16 });
+        assert_eq!(file.mipmap_levels, 5);
+        assert_eq!(file.get_layer_size(), 23 * 16);
+    }
+
+    #[test]
+    fn dxt1_cubemap() {
+        let data = build_file(b"DXT1", None, 8, 8, 1, 0xFE00, 6 * 4 * 8);
+        let file = File::parse(&data).unwrap();
+        assert_eq!(file.dimensions, Dimensions::Cubemap { dimension: 8 });
+        assert_eq!(file.images, 6);
+    }
+
+    #[test]
+    fn dx10_bc7_srgb_array() {
+        let data = build_file(b"DX10", Some((99, 0, 3)), 4, 4, 1, 0, 3 * 16);
+        let file = File::parse(&data).unwrap();
+        assert_eq!(file.format, None);
+        assert_eq!(file.srgb_format, Some(CompressedSrgbFormat::Bptc));
+        assert_eq!(file.dimensions,
+                   Dimensions::Texture2dArray { width: 4, height: 4, array_size: 3 });
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(File::parse(b"not a dds file"), Err(DdsError::InvalidFile)));
+        assert!(matches!(File::parse(&build_file(b"DXT1", None, 8, 8, 1, 0, 16)),
+                         Err(DdsError::InvalidFile)));
+        assert!(matches!(File::parse(&build_file(b"ETC1", None, 8, 8, 1, 0, 32)),
+                         Err(DdsError::UnsupportedFormat)));
+        assert!(matches!(File::parse(&build_file(b"DXT1", None, 12, 12, 2, 0, 9 * 8 + 4 * 8)),
+                         Err(DdsError::TextureCreationError(
+                             TextureCreationError::DimensionsNotSupported))));
+    }
+}
@@ -0,0 +1,1086 @@
+/*!
+Loading and saving textures in the KTX2 container format.
+
+KTX2 files can contain all the mipmap levels, array layers and cube faces of a texture, either
+uncompressed or in a compressed format. The `Ktx2Texture` trait, implemented on the texture
+types of glium, creates a texture directly from the content of a file and serializes a texture
+back to a file.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+use glium::texture::CompressedSrgbTexture2d;
+use glium::texture::ktx2::Ktx2Texture;
+
+let data = std::fs::read("texture.ktx2").unwrap();
+let texture = CompressedSrgbTexture2d::from_ktx2(&display, &data).unwrap();
+
+let data = texture.to_ktx2().unwrap();
+std::fs::write("copy.ktx2", data).unwrap();
+```
+
+The type of the texture must match the content of the file. For example a file with six faces
+can only be loaded as a cubemap, and a file with an sRGB format only as an sRGB texture. Use
+`read_info` to find out which type to use before loading a file.
+
+Files whose level data is compressed with zlib are supported. Files that use the BasisLZ or
+Zstandard supercompression schemes, as well as files using a format that glium doesn't have,
+are rejected. If the file doesn't contain mipmaps and the texture is uncompressed, the mipmaps
+are generated after loading.
+
+The rows of the images are uploaded in the order in which they are stored in the file, which
+means that the first row of the file ends up at the texture coordinate `0.0`.
+
+Saving a texture requires reading back its content, which isn't possible with OpenGL ES.
+
+This module is only available with the `ktx2` feature.
+
+*/
+use std::borrow::Cow;
+use std::cmp;
+use std::error::Error;
+use std::fmt;
+
+use crate::gl;
+use crate::ContextExt;
+use crate::TextureExt;
+use crate::TextureMipmapExt;
+
+use crate::backend::Facade;
+use crate::image_format::{self, ClientFormat, ClientFormatAny, RequestType};
+use crate::image_format::TextureFormatRequest;
+use crate::texture::{CompressedFormat, CompressedMipmapsOption, CompressedSrgbFormat};
+use crate::texture::{Dimensions, MipmapsOption, SrgbFormat, TextureAny, TextureAnyMipmap};
+use crate::texture::{TextureCreationError, TextureFormat, UncompressedFloatFormat};
+use crate::texture::{UncompressedIntFormat, UncompressedUintFormat};
+
+use crate::texture::{Texture1d, Texture2d, Texture3d, Texture1dArray, Texture2dArray};
+use crate::texture::{Cubemap, CubemapArray};
+use crate::texture::{SrgbTexture1d, SrgbTexture2d, SrgbTexture3d, SrgbTexture1dArray};
+use crate::texture::{SrgbTexture2dArray, SrgbCubemap, SrgbCubemapArray};
+use crate::texture::{CompressedTexture2d, CompressedTexture3d, CompressedTexture2dArray};
+use crate::texture::{CompressedCubemap, CompressedCubemapArray};
+use crate::texture::{CompressedSrgbTexture2d, CompressedSrgbTexture3d};
+use crate::texture::{CompressedSrgbTexture2dArray, CompressedSrgbCubemap};
+use crate::texture::CompressedSrgbCubemapArray;
+use crate::texture::{IntegralTexture1d, IntegralTexture2d, IntegralTexture3d};
+use crate::texture::{IntegralTexture1dArray, IntegralTexture2dArray, IntegralCubemap};
+use crate::texture::IntegralCubemapArray;
+use crate::texture::{UnsignedTexture1d, UnsignedTexture2d, UnsignedTexture3d};
+use crate::texture::{UnsignedTexture1dArray, UnsignedTexture2dArray, UnsignedCubemap};
+use crate::texture::UnsignedCubemapArray;
+
+/// The twelve bytes that start every KTX2 file.
+const IDENTIFIER: [u8; 12] = [0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// Size of the header and of the index that follows it.
+const HEADER_SIZE: usize = 80;
+
+/// Size of one entry of the level index.
+const LEVEL_INDEX_ENTRY_SIZE: usize = 24;
+
+/// Supercompression scheme of the files whose level data is compressed with zlib.
+const SUPERCOMPRESSION_ZLIB: u32 = 3;
+
+/// Correspondence between the `VkFormat` values stored in KTX2 files, the formats of textures
+/// and the formats of the data in the file.
+const FORMATS: &[(u32, TextureFormat, ClientFormatAny)] = &[
+    (2, TextureFormat::UncompressedFloat(UncompressedFloatFormat::U4U4U4U4),
+     ClientFormatAny::ClientFormat(ClientFormat::U4U4U4U4)),
+    (6, TextureFormat::UncompressedFloat(UncompressedFloatFormat::U5U5U5U1),
+     ClientFormatAny::ClientFormat(ClientFormat::U5U5U5U1)),
+    (9, TextureFormat::UncompressedFloat(UncompressedFloatFormat::U8),
+     ClientFormatAny::ClientFormat(ClientFormat::U8)),
+    (10, TextureFormat::UncompressedFloat(UncompressedFloatFormat::I8),
+     ClientFormatAny::ClientFormat(ClientFormat::I8)),
+    (13, TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U8),
+     ClientFormatAny::ClientFormat(ClientFormat::U8)),
+    (14, TextureFormat::UncompressedIntegral(UncompressedIntFormat::I8),
+     ClientFormatAny::ClientFormat(ClientFormat::I8)),
+    (16, TextureFormat::UncompressedFloat(UncompressedFloatFormat::U8U8),
+     ClientFormatAny::ClientFormat(ClientFormat::U8U8)),
+    (17, TextureFormat::UncompressedFloat(UncompressedFloatFormat::I8I8),
+     ClientFormatAny::ClientFormat(ClientFormat::I8I8)),
+    (20, TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U8U8),
+     ClientFormatAny::ClientFormat(ClientFormat::U8U8)),
+    (21, TextureFormat::UncompressedIntegral(UncompressedIntFormat::I8I8),
+     ClientFormatAny::ClientFormat(ClientFormat::I8I8)),
+    (23, TextureFormat::UncompressedFloat(UncompressedFloatFormat::U8U8U8),
+     ClientFormatAny::ClientFormat(ClientFormat::U8U8U8)),
+    (24, TextureFormat::UncompressedFloat(UncompressedFloatFormat::I8I8I8),
+     ClientFormatAny::ClientFormat(ClientFormat::I8I8I8)),
+    (27, TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U8U8U8),
+     ClientFormatAny::ClientFormat(ClientFormat::U8U8U8)),
+    (28, TextureFormat::UncompressedIntegral(UncompressedIntFormat::I8I8I8),
+     ClientFormatAny::ClientFormat(ClientFormat::I8I8I8)),
+    (29, TextureFormat::Srgb(SrgbFormat::U8U8U8),
+     ClientFormatAny::ClientFormat(ClientFormat::U8U8U8)),
+    (37, TextureFormat::UncompressedFloat(UncompressedFloatFormat::U8U8U8U8),
+     ClientFormatAny::ClientFormat(ClientFormat::U8U8U8U8)),
+    (38, TextureFormat::UncompressedFloat(UncompressedFloatFormat::I8I8I8I8),
+     ClientFormatAny::ClientFormat(ClientFormat::I8I8I8I8)),
+    (41, TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U8U8U8U8),
+     ClientFormatAny::ClientFormat(ClientFormat::U8U8U8U8)),
+    (42, TextureFormat::UncompressedIntegral(UncompressedIntFormat::I8I8I8I8),
+     ClientFormatAny::ClientFormat(ClientFormat::I8I8I8I8)),
+    (43, TextureFormat::Srgb(SrgbFormat::U8U8U8U8),
+     ClientFormatAny::ClientFormat(ClientFormat::U8U8U8U8)),
+    (70, TextureFormat::UncompressedFloat(UncompressedFloatFormat::U16),
+     ClientFormatAny::ClientFormat(ClientFormat::U16)),
+    (71, TextureFormat::UncompressedFloat(UncompressedFloatFormat::I16),
+     ClientFormatAny::ClientFormat(ClientFormat::I16)),
+    (74, TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U16),
+     ClientFormatAny::ClientFormat(ClientFormat::U16)),
+    (75, TextureFormat::UncompressedIntegral(UncompressedIntFormat::I16),
+     ClientFormatAny::ClientFormat(ClientFormat::I16)),
+    (76, TextureFormat::UncompressedFloat(UncompressedFloatFormat::F16),
+     ClientFormatAny::ClientFormat(ClientFormat::F16)),
+    (77, TextureFormat::UncompressedFloat(UncompressedFloatFormat::U16U16),
+     ClientFormatAny::ClientFormat(ClientFormat::U16U16)),
+    (78, TextureFormat::UncompressedFloat(UncompressedFloatFormat::I16I16),
+     ClientFormatAny::ClientFormat(ClientFormat::I16I16)),
+    (81, TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U16U16),
+     ClientFormatAny::ClientFormat(ClientFormat::U16U16)),
+    (82, TextureFormat::UncompressedIntegral(UncompressedIntFormat::I16I16),
+     ClientFormatAny::ClientFormat(ClientFormat::I16I16)),
+    (83, TextureFormat::UncompressedFloat(UncompressedFloatFormat::F16F16),
+     ClientFormatAny::ClientFormat(ClientFormat::F16F16)),
+    (84, TextureFormat::UncompressedFloat(UncompressedFloatFormat::U16U16U16),
+     ClientFormatAny::ClientFormat(ClientFormat::U16U16U16)),
+    (85, TextureFormat::UncompressedFloat(UncompressedFloatFormat::I16I16I16),
+     ClientFormatAny::ClientFormat(ClientFormat::I16I16I16)),
+    (88, TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U16U16U16),
+     ClientFormatAny::ClientFormat(ClientFormat::U16U16U16)),
+    (89, TextureFormat::UncompressedIntegral(UncompressedIntFormat::I16I16I16),
+     ClientFormatAny::ClientFormat(ClientFormat::I16I16I16)),
+    (90, TextureFormat::UncompressedFloat(UncompressedFloatFormat::F16F16F16),
+     ClientFormatAny::ClientFormat(ClientFormat::F16F16F16)),
+    (91, TextureFormat::UncompressedFloat(UncompressedFloatFormat::U16U16U16U16),
+     ClientFormatAny::ClientFormat(ClientFormat::U16U16U16U16)),
+    (92, TextureFormat::UncompressedFloat(UncompressedFloatFormat::I16I16I16I16),
+     ClientFormatAny::ClientFormat(ClientFormat::I16I16I16I16)),
+    (95, TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U16U16U16U16),
+     ClientFormatAny::ClientFormat(ClientFormat::U16U16U16U16)),
+    (96, TextureFormat::UncompressedIntegral(UncompressedIntFormat::I16I16I16I16),
+     ClientFormatAny::ClientFormat(ClientFormat::I16I16I16I16)),
+    (97, TextureFormat::UncompressedFloat(UncompressedFloatFormat::F16F16F16F16),
+     ClientFormatAny::ClientFormat(ClientFormat::F16F16F16F16)),
+    (98, TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U32),
+     ClientFormatAny::ClientFormat(ClientFormat::U32)),
+    (99, TextureFormat::UncompressedIntegral(UncompressedIntFormat::I32),
+     ClientFormatAny::ClientFormat(ClientFormat::I32)),
+    (100, TextureFormat::UncompressedFloat(UncompressedFloatFormat::F32),
+     ClientFormatAny::ClientFormat(ClientFormat::F32)),
+    (101, TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U32U32),
+     ClientFormatAny::ClientFormat(ClientFormat::U32U32)),
+    (102, TextureFormat::UncompressedIntegral(UncompressedIntFormat::I32I32),
+     ClientFormatAny::ClientFormat(ClientFormat::I32I32)),
+    (103, TextureFormat::UncompressedFloat(UncompressedFloatFormat::F32F32),
+     ClientFormatAny::ClientFormat(ClientFormat::F32F32)),
+    (104, TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U32U32U32),
+     ClientFormatAny::ClientFormat(ClientFormat::U32U32U32)),
+    (105, TextureFormat::UncompressedIntegral(UncompressedIntFormat::I32I32I32),
+     ClientFormatAny::ClientFormat(ClientFormat::I32I32I32)),
+    (106, TextureFormat::UncompressedFloat(UncompressedFloatFormat::F32F32F32),
+     ClientFormatAny::ClientFormat(ClientFormat::F32F32F32)),
+    (107, TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U32U32U32U32),
+     ClientFormatAny::ClientFormat(ClientFormat::U32U32U32U32)),
+    (108, TextureFormat::UncompressedIntegral(UncompressedIntFormat::I32I32I32I32),
+     ClientFormatAny::ClientFormat(ClientFormat::I32I32I32I32)),
+    (109, TextureFormat::UncompressedFloat(UncompressedFloatFormat::F32F32F32F32),
+     ClientFormatAny::ClientFormat(ClientFormat::F32F32F32F32)),
+    (131, TextureFormat::CompressedFormat(CompressedFormat::S3tcDxt1NoAlpha),
+     ClientFormatAny::CompressedFormat(CompressedFormat::S3tcDxt1NoAlpha)),
+    (132, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt1NoAlpha),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt1NoAlpha)),
+    (133, TextureFormat::CompressedFormat(CompressedFormat::S3tcDxt1Alpha),
+     ClientFormatAny::CompressedFormat(CompressedFormat::S3tcDxt1Alpha)),
+    (134, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt1Alpha),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt1Alpha)),
+    (135, TextureFormat::CompressedFormat(CompressedFormat::S3tcDxt3Alpha),
+     ClientFormatAny::CompressedFormat(CompressedFormat::S3tcDxt3Alpha)),
+    (136, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt3Alpha),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt3Alpha)),
+    (137, TextureFormat::CompressedFormat(CompressedFormat::S3tcDxt5Alpha),
+     ClientFormatAny::CompressedFormat(CompressedFormat::S3tcDxt5Alpha)),
+    (138, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt5Alpha),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt5Alpha)),
+    (139, TextureFormat::CompressedFormat(CompressedFormat::RgtcFormatU),
+     ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatU)),
+    (140, TextureFormat::CompressedFormat(CompressedFormat::RgtcFormatI),
+     ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatI)),
+    (141, TextureFormat::CompressedFormat(CompressedFormat::RgtcFormatUU),
+     ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatUU)),
+    (142, TextureFormat::CompressedFormat(CompressedFormat::RgtcFormatII),
+     ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatII)),
+    (143, TextureFormat::CompressedFormat(CompressedFormat::BptcUnsignedFloat3),
+     ClientFormatAny::CompressedFormat(CompressedFormat::BptcUnsignedFloat3)),
+    (144, TextureFormat::CompressedFormat(CompressedFormat::BptcSignedFloat3),
+     ClientFormatAny::CompressedFormat(CompressedFormat::BptcSignedFloat3)),
+    (145, TextureFormat::CompressedFormat(CompressedFormat::BptcUnorm4),
+     ClientFormatAny::CompressedFormat(CompressedFormat::BptcUnorm4)),
+    (146, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Bptc),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Bptc)),
+    (147, TextureFormat::CompressedFormat(CompressedFormat::Etc2Rgb8),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Etc2Rgb8)),
+    (148, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Etc2Rgb8),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Etc2Rgb8)),
+    (149, TextureFormat::CompressedFormat(CompressedFormat::Etc2Rgb8A1),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Etc2Rgb8A1)),
+    (150, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Etc2Rgb8A1),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Etc2Rgb8A1)),
+    (151, TextureFormat::CompressedFormat(CompressedFormat::Etc2Rgba8),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Etc2Rgba8)),
+    (152, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Etc2Rgba8),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Etc2Rgba8)),
+    (153, TextureFormat::CompressedFormat(CompressedFormat::EacR11U),
+     ClientFormatAny::CompressedFormat(CompressedFormat::EacR11U)),
+    (154, TextureFormat::CompressedFormat(CompressedFormat::EacR11I),
+     ClientFormatAny::CompressedFormat(CompressedFormat::EacR11I)),
+    (155, TextureFormat::CompressedFormat(CompressedFormat::EacRg11U),
+     ClientFormatAny::CompressedFormat(CompressedFormat::EacRg11U)),
+    (156, TextureFormat::CompressedFormat(CompressedFormat::EacRg11I),
+     ClientFormatAny::CompressedFormat(CompressedFormat::EacRg11I)),
+    (157, TextureFormat::CompressedFormat(CompressedFormat::Astc4x4),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Astc4x4)),
+    (158, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Astc4x4),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc4x4)),
+    (159, TextureFormat::CompressedFormat(CompressedFormat::Astc5x4),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Astc5x4)),
+    (160, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Astc5x4),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc5x4)),
+    (161, TextureFormat::CompressedFormat(CompressedFormat::Astc5x5),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Astc5x5)),
+    (162, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Astc5x5),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc5x5)),
+    (163, TextureFormat::CompressedFormat(CompressedFormat::Astc6x5),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Astc6x5)),
+    (164, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Astc6x5),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc6x5)),
+    (165, TextureFormat::CompressedFormat(CompressedFormat::Astc6x6),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Astc6x6)),
+    (166, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Astc6x6),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc6x6)),
+    (167, TextureFormat::CompressedFormat(CompressedFormat::Astc8x5),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Astc8x5)),
+    (168, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Astc8x5),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc8x5)),
+    (169, TextureFormat::CompressedFormat(CompressedFormat::Astc8x6),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Astc8x6)),
+    (170, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Astc8x6),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc8x6)),
+    (171, TextureFormat::CompressedFormat(CompressedFormat::Astc8x8),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Astc8x8)),
+    (172, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Astc8x8),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc8x8)),
+    (173, TextureFormat::CompressedFormat(CompressedFormat::Astc10x5),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Astc10x5)),
+    (174, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Astc10x5),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc10x5)),
+    (175, TextureFormat::CompressedFormat(CompressedFormat::Astc10x6),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Astc10x6)),
+    (176, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Astc10x6),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc10x6)),
+    (177, TextureFormat::CompressedFormat(CompressedFormat::Astc10x8),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Astc10x8)),
+    (178, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Astc10x8),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc10x8)),
+    (179, TextureFormat::CompressedFormat(CompressedFormat::Astc10x10),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Astc10x10)),
+    (180, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Astc10x10),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc10x10)),
+    (181, TextureFormat::CompressedFormat(CompressedFormat::Astc12x10),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Astc12x10)),
+    (182, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Astc12x10),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc12x10)),
+    (183, TextureFormat::CompressedFormat(CompressedFormat::Astc12x12),
+     ClientFormatAny::CompressedFormat(CompressedFormat::Astc12x12)),
+    (184, TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::Astc12x12),
+     ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Astc12x12)),
+];
+
+/// Error that can happen when loading or saving a KTX2 file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ktx2Error {
+    /// The data is not a valid KTX2 file.
+    InvalidFile,
+
+    /// The file uses a format that glium doesn't support. Contains the `VkFormat` of the file.
+    UnsupportedFormat(u32),
+
+    /// The level data of the file is compressed with a scheme that glium doesn't support.
+    /// Contains the identifier of the scheme.
+    ///
+    /// Only zlib (`3`) is supported. BasisLZ (`1`) and Zstandard (`2`) are rejected.
+    UnsupportedSupercompression(u32),
+
+    /// The type of the texture doesn't match the content of the file, or can't be stored in a
+    /// KTX2 file.
+    WrongTextureType,
+
+    /// The format of the texture can't be stored in a KTX2 file.
+    FormatNotSupported,
+
+    /// Reading the content of textures is not supported by the backend.
+    ReadNotSupported,
+
+    /// Failed to create the texture.
+    TextureCreationError(TextureCreationError),
+}
+
+impl fmt::Display for Ktx2Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::Ktx2Error::*;
+        let desc = match *self {
+            InvalidFile =>
+                "The data is not a valid KTX2 file",
+            UnsupportedFormat(_) =>
+                "The file uses a format that glium doesn't support",
+            UnsupportedSupercompression(_) =>
+                "The level data of the file is compressed with a scheme that glium doesn't support",
+            WrongTextureType =>
+                "The type of the texture doesn't match the content of the file",
+            FormatNotSupported =>
+                "The format of the texture can't be stored in a KTX2 file",
+            ReadNotSupported =>
+                "Reading the content of textures is not supported by the backend",
+            TextureCreationError(_) =>
+                "Failed to create the texture",
+        };
+        fmt.write_str(desc)
+    }
+}
+
+impl Error for Ktx2Error {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use self::Ktx2Error::*;
+        match *self {
+            TextureCreationError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<TextureCreationError> for Ktx2Error {
+    #[inline]
+    fn from(err: TextureCreationError) -> Ktx2Error {
+        Ktx2Error::TextureCreationError(err)
+    }
+}
+
+/// Description of the texture contained in a KTX2 file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Ktx2Info {
+    /// Format of the texture.
+    pub format: TextureFormat,
+
+    /// Type and dimensions of the texture.
+    pub dimensions: Dimensions,
+
+    /// Number of mipmap levels stored in the file, including the main level.
+    pub mipmap_levels: u32,
+
+    /// True if the file only contains the main level and the mipmaps are to be generated.
+    pub generate_mipmaps: bool,
+}
+
+/// Reads the description of the texture contained in a KTX2 file, without loading it.
+pub fn read_info(data: &[u8]) -> Result<Ktx2Info, Ktx2Error> {
+    File::parse(data).map(|file| file.info)
+}
+
+/// A texture type that can be loaded from and saved to KTX2 files.
+pub trait Ktx2Texture: Sized {
+    /// Creates a texture from the content of a KTX2 file, with all the mipmap levels, array
+    /// layers and faces that it contains.
+    fn from_ktx2<F: ?Sized>(facade: &F, data: &[u8]) -> Result<Self, Ktx2Error> where F: Facade;
+
+    /// Serializes all the mipmap levels, array layers and faces of the texture to a KTX2 file.
+    fn to_ktx2(&self) -> Result<Vec<u8>, Ktx2Error>;
+}
+
+macro_rules! impl_ktx2_texture {
+    ($ty:ident, $kind:ident, $mipmaps:ident, $dimensions:ident { $($arg:ident),* }) => (
+        impl Ktx2Texture for $ty {
+            fn from_ktx2<F: ?Sized>(facade: &F, data: &[u8]) -> Result<$ty, Ktx2Error>
+                                    where F: Facade
+            {
+                let file = File::parse(data)?;
+
+                let format = match file.info.format {
+                    TextureFormat::$kind(format) => format,
+                    _ => return Err(Ktx2Error::WrongTextureType),
+                };
+
+                let mipmaps = $mipmaps(&file.info);
+
+                let texture = match file.info.dimensions {
+                    Dimensions::$dimensions { $($arg),* } => {
+                        $ty::empty_with_format(facade, format, mipmaps, $($arg),*)?
+                    },
+                    _ => return Err(Ktx2Error::WrongTextureType),
+                };
+
+                upload(&texture, &file)?;
+                Ok(texture)
+            }
+
+            #[inline]
+            fn to_ktx2(&self) -> Result<Vec<u8>, Ktx2Error> {
+                save(self)
+            }
+        }
+    );
+}
+
+impl_ktx2_texture!(Texture1d, UncompressedFloat, mipmaps_option, Texture1d { width });
+impl_ktx2_texture!(Texture2d, UncompressedFloat, mipmaps_option, Texture2d { width, height });
+impl_ktx2_texture!(Texture3d, UncompressedFloat, mipmaps_option,
+                   Texture3d { width, height, depth });
+impl_ktx2_texture!(Texture1dArray, UncompressedFloat, mipmaps_option,
+                   Texture1dArray { width, array_size });
+impl_ktx2_texture!(Texture2dArray, UncompressedFloat, mipmaps_option,
+                   Texture2dArray { width, height, array_size });
+impl_ktx2_texture!(Cubemap, UncompressedFloat, mipmaps_option, Cubemap { dimension });
+impl_ktx2_texture!(CubemapArray, UncompressedFloat, mipmaps_option,
+                   CubemapArray { dimension, array_size });
+
+impl_ktx2_texture!(SrgbTexture1d, Srgb, mipmaps_option, Texture1d { width });
+impl_ktx2_texture!(SrgbTexture2d, Srgb, mipmaps_option, Texture2d { width, height });
+impl_ktx2_texture!(SrgbTexture3d, Srgb, mipmaps_option, Texture3d { width, height, depth });
+impl_ktx2_texture!(SrgbTexture1dArray, Srgb, mipmaps_option,
+                   Texture1dArray { width, array_size });
+impl_ktx2_texture!(SrgbTexture2dArray, Srgb, mipmaps_option,
+                   Texture2dArray { width, height, array_size });
+impl_ktx2_texture!(SrgbCubemap, Srgb, mipmaps_option, Cubemap { dimension });
+impl_ktx2_texture!(SrgbCubemapArray, Srgb, mipmaps_option,
+                   CubemapArray { dimension, array_size });
+
+impl_ktx2_texture!(CompressedTexture2d, CompressedFormat, compressed_mipmaps_option,
+                   Texture2d { width, height });
+impl_ktx2_texture!(CompressedTexture3d, CompressedFormat, compressed_mipmaps_option,
+                   Texture3d { width, height, depth });
+impl_ktx2_texture!(CompressedTexture2dArray, CompressedFormat, compressed_mipmaps_option,
+                   Texture2dArray { width, height, array_size });
+impl_ktx2_texture!(CompressedCubemap, CompressedFormat, compressed_mipmaps_option,
+                   Cubemap { dimension });
+impl_ktx2_texture!(CompressedCubemapArray, CompressedFormat, compressed_mipmaps_option,
+                   CubemapArray { dimension, array_size });
+
+impl_ktx2_texture!(CompressedSrgbTexture2d, CompressedSrgbFormat, compressed_mipmaps_option,
+                   Texture2d { width, height });
+impl_ktx2_texture!(CompressedSrgbTexture3d, CompressedSrgbFormat, compressed_mipmaps_option,
+                   Texture3d { width, height, depth });
+impl_ktx2_texture!(CompressedSrgbTexture2dArray, CompressedSrgbFormat, compressed_mipmaps_option,
+                   Texture2dArray { width, height, array_size });
+impl_ktx2_texture!(CompressedSrgbCubemap, CompressedSrgbFormat, compressed_mipmaps_option,
+                   Cubemap { dimension });
+impl_ktx2_texture!(CompressedSrgbCubemapArray, CompressedSrgbFormat, compressed_mipmaps_option,
+                   CubemapArray { dimension, array_size });
+
+impl_ktx2_texture!(IntegralTexture1d, UncompressedIntegral, mipmaps_option,
+                   Texture1d { width });
+impl_ktx2_texture!(IntegralTexture2d, UncompressedIntegral, mipmaps_option,
+                   Texture2d { width, height });
+impl_ktx2_texture!(IntegralTexture3d, UncompressedIntegral, mipmaps_option,
+                   Texture3d { width, height, depth });
+impl_ktx2_texture!(IntegralTexture1dArray, UncompressedIntegral, mipmaps_option,
+                   Texture1dArray { width, array_size });
+impl_ktx2_texture!(IntegralTexture2dArray, UncompressedIntegral, mipmaps_option,
+                   Texture2dArray { width, height, array_size });
+impl_ktx2_texture!(IntegralCubemap, UncompressedIntegral, mipmaps_option,
+                   Cubemap { dimension });
+impl_ktx2_texture!(IntegralCubemapArray, UncompressedIntegral, mipmaps_option,
+                   CubemapArray { dimension, array_size });
+
+impl_ktx2_texture!(UnsignedTexture1d, UncompressedUnsigned, mipmaps_option,
+                   Texture1d { width });
+impl_ktx2_texture!(UnsignedTexture2d, UncompressedUnsigned, mipmaps_option,
+                   Texture2d { width, height });
+impl_ktx2_texture!(UnsignedTexture3d, UncompressedUnsigned, mipmaps_option,
+                   Texture3d { width, height, depth });
+impl_ktx2_texture!(UnsignedTexture1dArray, UncompressedUnsigned, mipmaps_option,
+                   Texture1dArray { width, array_size });
+impl_ktx2_texture!(UnsignedTexture2dArray, UncompressedUnsigned, mipmaps_option,
+                   Texture2dArray { width, height, array_size });
+impl_ktx2_texture!(UnsignedCubemap, UncompressedUnsigned, mipmaps_option,
+                   Cubemap { dimension });
+impl_ktx2_texture!(UnsignedCubemapArray, UncompressedUnsigned, mipmaps_option,
+                   CubemapArray { dimension, array_size });
+
+/// Returns the mipmaps to allocate for an uncompressed texture loaded from a file.
+fn mipmaps_option(info: &Ktx2Info) -> MipmapsOption {
+    if info.generate_mipmaps {
+        MipmapsOption::AutoGeneratedMipmaps
+    } else {
+        MipmapsOption::EmptyMipmapsMax(info.mipmap_levels - 1)
+    }
+}
+
+/// Returns the mipmaps to allocate for a compressed texture loaded from a file.
+fn compressed_mipmaps_option(info: &Ktx2Info) -> CompressedMipmapsOption {
+    // compressed textures can't generate their mipmaps, so all the levels of the file are
+    // allocated and loaded
+    CompressedMipmapsOption::EmptyMipmapsMax(info.mipmap_levels - 1)
+}
+
+/// Location of a mipmap level in a file.
+struct Level {
+    offset: usize,
+    length: usize,
+    uncompressed_length: usize,
+}
+
+/// A parsed KTX2 file.
+struct File<'a> {
+    data: &'a [u8],
+    info: Ktx2Info,
+    client_format: ClientFormatAny,
+    supercompression: u32,
+    levels: Vec<Level>,
+}
+
+impl<'a> File<'a> {
+    fn parse(data: &'a [u8]) -> Result<File<'a>, Ktx2Error> {
+        if data.len() < HEADER_SIZE || data[.. 12] != IDENTIFIER {
+            return Err(Ktx2Error::InvalidFile);
+        }
+
+        let vk_format = read_u32(data, 12);
+        let width = read_u32(data, 20);
+        let height = read_u32(data, 24);
+        let depth = read_u32(data, 28);
+        let layers = read_u32(data, 32);
+        let faces = read_u32(data, 36);
+        let level_count = read_u32(data, 40);
+        let supercompression = read_u32(data, 44);
+
+        match supercompression {
+            0 | SUPERCOMPRESSION_ZLIB => (),
+            scheme => return Err(Ktx2Error::UnsupportedSupercompression(scheme)),
+        }
+
+        let (format, client_format) = FORMATS.iter()
+                                             .find(|&&(vk, _, _)| vk == vk_format)
+                                             .map(|&(_, format, client)| (format, client))
+                                             .ok_or(Ktx2Error::UnsupportedFormat(vk_format))?;
+
+        let dimensions = match (faces, height, depth, layers) {
+            (6, _, 0, 0) if width == height => Dimensions::Cubemap { dimension: width },
+            (6, _, 0, array_size) if width == height => {
+                Dimensions::CubemapArray { dimension: width, array_size }
+            },
+            (1, 0, 0, 0) => Dimensions::Texture1d { width },
+            (1, 0, 0, array_size) => Dimensions::Texture1dArray { width, array_size },
+            (1, _, 0, 0) => Dimensions::Texture2d { width, height },
+            (1, _, 0, array_size) => Dimensions::Texture2dArray { width, height, array_size },
+            (1, _, _, 0) if height != 0 => Dimensions::Texture3d { width, height, depth },
+            _ => return Err(Ktx2Error::InvalidFile),
+        };
+
+        if width == 0 {
+            return Err(Ktx2Error::InvalidFile);
+        }
+
+        // a level count of 0 means that only the main level is stored
+        let max_levels = 32 - cmp::max(width, cmp::max(height, depth)).leading_zeros();
+        let mipmap_levels = cmp::max(level_count, 1);
+        if mipmap_levels > max_levels {
+            return Err(Ktx2Error::InvalidFile);
+        }
+
+        let generate_mipmaps = level_count == 0 && !client_format.is_compressed();
+
+        if data.len() < HEADER_SIZE + mipmap_levels as usize * LEVEL_INDEX_ENTRY_SIZE {
+            return Err(Ktx2Error::InvalidFile);
+        }
+
+        let levels = (0 .. mipmap_levels as usize).map(|level| {
+            let entry = HEADER_SIZE + level * LEVEL_INDEX_ENTRY_SIZE;
+            let offset = read_u64(data, entry) as usize;
+            let length = read_u64(data, entry + 8) as usize;
+            let uncompressed_length = read_u64(data, entry + 16) as usize;
+
+            match offset.checked_add(length) {
+                Some(end) if end <= data.len() => Ok(Level { offset, length, uncompressed_length }),
+                _ => Err(Ktx2Error::InvalidFile),
+            }
+        }).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(File {
+            data,
+            info: Ktx2Info {
+                format,
+                dimensions,
+                mipmap_levels,
+                generate_mipmaps,
+            },
+            client_format,
+            supercompression,
+            levels,
+        })
+    }
+
+    /// Returns the data of a level, decompressing it if necessary.
+    fn get_level_data(&self, level: u32) -> Result<Cow<'a, [u8]>, Ktx2Error> {
+        let level = &self.levels[level as usize];
+        let data = &self.data[level.offset .. level.offset + level.length];
+
+        if self.supercompression == SUPERCOMPRESSION_ZLIB {
+            // the limit prevents a small file from allocating an arbitrary amount of memory
+            let data = miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(
+                data, level.uncompressed_length
+            ).map_err(|_| Ktx2Error::InvalidFile)?;
+            if data.len() != level.uncompressed_length {
+                return Err(Ktx2Error::InvalidFile);
+            }
+            Ok(Cow::Owned(data))
+
+        } else {
+            Ok(Cow::Borrowed(data))
+        }
+    }
+}
+
+/// Uploads all the levels of a file to a texture that has been created with the dimensions and
+/// the format of the file.
+fn upload(texture: &TextureAny, file: &File<'_>) -> Result<(), Ktx2Error> {
+    for level in 0 .. file.info.mipmap_levels {
+        // the texture can have less levels than the file if mipmaps aren't supported
+        let mipmap = match texture.mipmap(level) {
+            Some(mipmap) => mipmap,
+            None => break,
+        };
+
+        let data = file.get_level_data(level)?;
+
+        let (width, height, depth) = get_upload_dimensions(&mipmap);
+        if data.len() != get_level_size(file.client_format, width, height, depth) {
+            return Err(Ktx2Error::InvalidFile);
+        }
+
+        // the file always contains the layers, faces and slices of a level one after another,
+        // which is also the order in which `upload_texture` expects them
+        mipmap.upload_texture(0, 0, 0, (file.client_format, data), width, height, depth,
+                              file.info.generate_mipmaps)
+              .map_err(|_| TextureCreationError::FormatNotSupported)?;
+    }
+
+    Ok(())
+}
+
+/// Serializes all the levels of a texture.
+fn save(texture: &TextureAny) -> Result<Vec<u8>, Ktx2Error> {
+    let dimensions = texture.get_texture_type();
+    let (width, height, depth, layers, faces) = match dimensions {
+        Dimensions::Texture1d { width } => (width, 0, 0, 0, 1),
+        Dimensions::Texture1dArray { width, array_size } => (width, 0, 0, array_size, 1),
+        Dimensions::Texture2d { width, height } => (width, height, 0, 0, 1),
+        Dimensions::Texture2dArray { width, height, array_size } => {
+            (width, height, 0, array_size, 1)
+        },
+        Dimensions::Texture3d { width, height, depth } => (width, height, depth, 0, 1),
+        Dimensions::Cubemap { dimension } => (dimension, dimension, 0, 0, 6),
+        Dimensions::CubemapArray { dimension, array_size } => {
+            (dimension, dimension, 0, array_size, 6)
+        },
+        Dimensions::Texture2dMultisample { .. } |
+        Dimensions::Texture2dMultisampleArray { .. } => return Err(Ktx2Error::WrongTextureType),
+    };
+
+    let format = match texture.get_requested_format() {
+        TextureFormatRequest::Specific(format) => format,
+        _ => get_actual_format(texture).ok_or(Ktx2Error::FormatNotSupported)?,
+    };
+
+    let (vk_format, client_format) = FORMATS.iter()
+                                            .find(|&&(_, f, _)| f == format)
+                                            .map(|&(vk, _, client)| (vk, client))
+                                            .ok_or(Ktx2Error::FormatNotSupported)?;
+
+    let levels = (0 .. texture.get_mipmap_levels()).map(|level| {
+        texture.mipmap(level).unwrap().download_data(client_format).ok_or(Ktx2Error::ReadNotSupported)
+    }).collect::<Result<Vec<_>, _>>()?;
+
+    let dfd = build_data_format_descriptor(format, client_format);
+
+    let (_, _, block_size) = get_block_info(client_format);
+    let type_size = match client_format {
+        ClientFormatAny::ClientFormat(ClientFormat::U4U4U4U4) |
+        ClientFormatAny::ClientFormat(ClientFormat::U5U5U5U1) => 2,
+        ClientFormatAny::ClientFormat(format) => {
+            format.get_size() as u32 / format.get_num_components() as u32
+        },
+        _ => 1,
+    };
+
+    // the data of each level must be aligned to both the size of a texel block and 4 bytes
+    let alignment = block_size * 4 / gcd(block_size, 4);
+
+    let dfd_offset = HEADER_SIZE + levels.len() * LEVEL_INDEX_ENTRY_SIZE;
+    let mut output = vec![0; dfd_offset];
+    output.extend_from_slice(&dfd);
+
+    output[.. 12].copy_from_slice(&IDENTIFIER);
+    write_u32(&mut output, 12, vk_format);
+    write_u32(&mut output, 16, type_size);
+    write_u32(&mut output, 20, width);
+    write_u32(&mut output, 24, height);
+    write_u32(&mut output, 28, depth);
+    write_u32(&mut output, 32, layers);
+    write_u32(&mut output, 36, faces);
+    write_u32(&mut output, 40, levels.len() as u32);
+    write_u32(&mut output, 44, 0);
+    write_u32(&mut output, 48, dfd_offset as u32);
+    write_u32(&mut output, 52, dfd.len() as u32);
+
+    // the levels are stored from the smallest to the largest
+    for (level, data) in levels.iter().enumerate().rev() {
+        let padding = (alignment - output.len() % alignment) % alignment;
+        output.resize(output.len() + padding, 0);
+
+        let entry = HEADER_SIZE + level * LEVEL_INDEX_ENTRY_SIZE;
+        let offset = output.len() as u64;
+        write_u64(&mut output, entry, offset);
+        write_u64(&mut output, entry + 8, data.len() as u64);
+        write_u64(&mut output, entry + 16, data.len() as u64);
+
+        output.extend_from_slice(data);
+    }
+
+    Ok(output)
+}
+
+/// Returns the width, height and depth to pass to `upload_texture` to upload a whole mipmap.
+fn get_upload_dimensions(mipmap: &TextureAnyMipmap<'_>) -> (u32, Option<u32>, Option<u32>) {
+    let width = mipmap.get_width();
+    match mipmap.get_texture().get_texture_type() {
+        Dimensions::Texture1d { .. } => (width, None, None),
+        Dimensions::Texture1dArray { array_size, .. } => (width, Some(array_size), None),
+        Dimensions::Texture2dArray { array_size, .. } => {
+            (width, mipmap.get_height(), Some(array_size))
+        },
+        Dimensions::Cubemap { .. } => (width, mipmap.get_height(), Some(6)),
+        Dimensions::CubemapArray { array_size, .. } => {
+            (width, mipmap.get_height(), Some(6 * array_size))
+        },
+        _ => (width, mipmap.get_height(), mipmap.get_depth()),
+    }
+}
+
+/// Returns the number of bytes of a whole mipmap.
+fn get_level_size(format: ClientFormatAny, width: u32, height: Option<u32>,
+                  depth: Option<u32>) -> usize
+{
+    // compressed formats are made of two-dimensional blocks, so each slice is compressed
+    // separately
+    if format.is_compressed() {
+        format.get_buffer_size(width, height, None, depth)
+    } else {
+        format.get_buffer_size(width, height, depth, None)
+    }
+}
+
+/// Returns the width, the height and the number of bytes of a block of texels.
+fn get_block_info(format: ClientFormatAny) -> (u32, u32, usize) {
+    let (width, height) = match format {
+        ClientFormatAny::ClientFormat(_) => (1, 1),
+        ClientFormatAny::CompressedFormat(format) => format.get_astc_block_size().unwrap_or((4, 4)),
+        ClientFormatAny::CompressedSrgbFormat(format) => {
+            format.get_astc_block_size().unwrap_or((4, 4))
+        },
+    };
+
+    (width, height, format.get_buffer_size(width, Some(height), None, None))
+}
+
+/// Finds the format of a texture that hasn't been created with a specific format.
+fn get_actual_format(texture: &TextureAny) -> Option<TextureFormat> {
+    let context = texture.get_context();
+
+    let internal_format = {
+        let mut ctxt = context.make_current();
+        let bind_point = texture.bind_to_current(&mut ctxt);
+
+        // the levels of cubemaps can only be queried through their faces
+        let target = if bind_point == gl::TEXTURE_CUBE_MAP {
+            gl::TEXTURE_CUBE_MAP_POSITIVE_X
+        } else {
+            bind_point
+        };
+
+        let mut value = 0;
+        unsafe {
+            ctxt.gl.GetTexLevelParameteriv(target, 0, gl::TEXTURE_INTERNAL_FORMAT, &mut value);
+        }
+        value as gl::types::GLenum
+    };
+
+    FORMATS.iter().map(|&(_, format, _)| format).find(|&format| {
+        let request = TextureFormatRequest::Specific(format);
+        image_format::format_request_to_glenum(context, request, RequestType::TexStorage).ok()
+                                                                        == Some(internal_format)
+    })
+}
+
+/// How the values of the samples of a format are interpreted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum SampleType {
+    Unorm,
+    Snorm,
+    Ufloat,
+    Sfloat,
+    Uint,
+    Sint,
+}
+
+/// Builds the data format descriptor of a format, made of a basic descriptor block.
+fn build_data_format_descriptor(format: TextureFormat, client_format: ClientFormatAny) -> Vec<u8> {
+    // the channels of the `KHR_DF_MODEL_RGBSDA` color model
+    const RED: u8 = 0;
+    const GREEN: u8 = 1;
+    const BLUE: u8 = 2;
+    const ALPHA: u8 = 15;
+
+    let is_srgb = matches!(format, TextureFormat::Srgb(_) | TextureFormat::CompressedSrgbFormat(_));
+
+    // color model, type of the samples, and the channel, bit offset and bit length of each
+    // sample
+    let (color_model, sample_type, samples): (u8, SampleType, Vec<(u8, u32, u32)>) = match client_format {
+        ClientFormatAny::ClientFormat(client) => {
+            let sample_type = match (format, client) {
+                (TextureFormat::UncompressedIntegral(_), _) => SampleType::Sint,
+                (TextureFormat::UncompressedUnsigned(_), _) => SampleType::Uint,
+                (_, ClientFormat::F16) | (_, ClientFormat::F16F16) |
+                (_, ClientFormat::F16F16F16) | (_, ClientFormat::F16F16F16F16) |
+                (_, ClientFormat::F32) | (_, ClientFormat::F32F32) |
+                (_, ClientFormat::F32F32F32) | (_, ClientFormat::F32F32F32F32) => SampleType::Sfloat,
+                (_, ClientFormat::I8) | (_, ClientFormat::I8I8) |
+                (_, ClientFormat::I8I8I8) | (_, ClientFormat::I8I8I8I8) |
+                (_, ClientFormat::I16) | (_, ClientFormat::I16I16) |
+                (_, ClientFormat::I16I16I16) | (_, ClientFormat::I16I16I16I16) => SampleType::Snorm,
+                _ => SampleType::Unorm,
+            };
+
+            let samples = match client {
+                ClientFormat::U4U4U4U4 => {
+                    vec![(RED, 12, 4), (GREEN, 8, 4), (BLUE, 4, 4), (ALPHA, 0, 4)]
+                },
+                ClientFormat::U5U5U5U1 => {
+                    vec![(RED, 11, 5), (GREEN, 6, 5), (BLUE, 1, 5), (ALPHA, 0, 1)]
+                },
+                _ => {
+                    let components = client.get_num_components() as usize;
+                    let bits = client.get_size() as u32 * 8 / components as u32;
+                    [RED, GREEN, BLUE, ALPHA].iter().take(components).enumerate()
+                                             .map(|(num, &channel)| (channel, num as u32 * bits, bits))
+                                             .collect()
+                },
+            };
+
+            (1, sample_type, samples)
+        },
+
+        ClientFormatAny::CompressedFormat(CompressedFormat::S3tcDxt1NoAlpha) |
+        ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt1NoAlpha) => {
+            (128, SampleType::Unorm, vec![(0, 0, 64)])
+        },
+        ClientFormatAny::CompressedFormat(CompressedFormat::S3tcDxt1Alpha) |
+        ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt1Alpha) => {
+            // the channel of BC1 indicating that the alpha is present
+            (128, SampleType::Unorm, vec![(1, 0, 64)])
+        },
+        ClientFormatAny::CompressedFormat(CompressedFormat::S3tcDxt3Alpha) |
+        ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt3Alpha) => {
+            (129, SampleType::Unorm, vec![(ALPHA, 0, 64), (0, 64, 64)])
+        },
+        ClientFormatAny::CompressedFormat(CompressedFormat::S3tcDxt5Alpha) |
+        ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt5Alpha) => {
+            (130, SampleType::Unorm, vec![(ALPHA, 0, 64), (0, 64, 64)])
+        },
+        ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatU) => {
+            (131, SampleType::Unorm, vec![(0, 0, 64)])
+        },
+        ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatI) => {
+            (131, SampleType::Snorm, vec![(0, 0, 64)])
+        },
+        ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatUU) => {
+            (132, SampleType::Unorm, vec![(0, 0, 64), (1, 64, 64)])
+        },
+        ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatII) => {
+            (132, SampleType::Snorm, vec![(0, 0, 64), (1, 64, 64)])
+        },
+        ClientFormatAny::CompressedFormat(CompressedFormat::BptcUnsignedFloat3) => {
+            (133, SampleType::Ufloat, vec![(0, 0, 128)])
+        },
+        ClientFormatAny::CompressedFormat(CompressedFormat::BptcSignedFloat3) => {
+            (133, SampleType::Sfloat, vec![(0, 0, 128)])
+        },
+        ClientFormatAny::CompressedFormat(CompressedFormat::BptcUnorm4) |
+        ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Bptc) => {
+            (134, SampleType::Unorm, vec![(0, 0, 128)])
+        },
+
+        // the channels of the ETC2 color model are red, green, color and alpha
+        ClientFormatAny::CompressedFormat(CompressedFormat::Etc2Rgb8) |
+        ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Etc2Rgb8) => {
+            (161, SampleType::Unorm, vec![(2, 0, 64)])
+        },
+        ClientFormatAny::CompressedFormat(CompressedFormat::Etc2Rgb8A1) |
+        ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Etc2Rgb8A1) => {
+            (161, SampleType::Unorm, vec![(2, 0, 64), (ALPHA, 0, 64)])
+        },
+        ClientFormatAny::CompressedFormat(CompressedFormat::Etc2Rgba8) |
+        ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::Etc2Rgba8) => {
+            (161, SampleType::Unorm, vec![(ALPHA, 0, 64), (2, 64, 64)])
+        },
+        ClientFormatAny::CompressedFormat(CompressedFormat::EacR11U) => {
+            (161, SampleType::Unorm, vec![(0, 0, 64)])
+        },
+        ClientFormatAny::CompressedFormat(CompressedFormat::EacR11I) => {
+            (161, SampleType::Snorm, vec![(0, 0, 64)])
+        },
+        ClientFormatAny::CompressedFormat(CompressedFormat::EacRg11U) => {
+            (161, SampleType::Unorm, vec![(0, 0, 64), (1, 64, 64)])
+        },
+        ClientFormatAny::CompressedFormat(CompressedFormat::EacRg11I) => {
+            (161, SampleType::Snorm, vec![(0, 0, 64), (1, 64, 64)])
+        },
+
+        // ASTC formats
+        ClientFormatAny::CompressedFormat(_) | ClientFormatAny::CompressedSrgbFormat(_) => {
+            (162, SampleType::Unorm, vec![(0, 0, 128)])
+        },
+    };
+
+    let (block_width, block_height, block_size) = get_block_info(client_format);
+
+    let block_size_in_dfd = 24 + 16 * samples.len();
+    let mut dfd = Vec::with_capacity(4 + block_size_in_dfd);
+    let mut push = |value: u32| dfd.extend_from_slice(&value.to_le_bytes());
+
+    push(4 + block_size_in_dfd as u32);
+    push(0);        // vendor and descriptor type
+    push(2 | (block_size_in_dfd as u32) << 16);       // version 1.3 of the specification
+    // BT.709 primaries, with either the linear or the sRGB transfer function
+    let transfer_function = if is_srgb { 2 } else { 1 };
+    push(color_model as u32 | 1 << 8 | transfer_function << 16);
+    push((block_width - 1) | (block_height - 1) << 8);
+    push(block_size as u32);
+    push(0);
+
+    for (channel, offset, length) in samples {
+        let mut qualifiers = match sample_type {
+            SampleType::Snorm | SampleType::Sint => 0x4,
+            SampleType::Sfloat => 0x4 | 0x8,
+            SampleType::Ufloat => 0x8,
+            SampleType::Unorm | SampleType::Uint => 0,
+        };
+        if is_srgb && channel == ALPHA {
+            qualifiers |= 0x1;
+        }
+
+        let (lower, upper) = match sample_type {
+            SampleType::Unorm if length >= 32 => (0, u32::MAX),
+            SampleType::Unorm => (0, (1 << length) - 1),
+            SampleType::Snorm if length >= 32 => (i32::MIN as u32, i32::MAX as u32),
+            SampleType::Snorm => {
+                let max = (1 << (length - 1)) - 1;
+                (-(max as i32) as u32, max)
+            },
+            SampleType::Ufloat => (0, 1.0f32.to_bits()),
+            SampleType::Sfloat => ((-1.0f32).to_bits(), 1.0f32.to_bits()),
+            SampleType::Uint => (0, 1),
+            SampleType::Sint => (-1i32 as u32, 1),
+        };
+
+        push(offset | (length - 1) << 16 | (channel as u32 | qualifiers << 4) << 24);
+        push(0);        // sample position
+        push(lower);
+        push(upper);
+    }
+
+    dfd
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+#[inline]
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&data[offset .. offset + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+#[inline]
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&data[offset .. offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+#[inline]
+fn write_u32(data: &mut [u8], offset: usize, value: u32) {
+    data[offset .. offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+#[inline]
+fn write_u64(data: &mut [u8], offset: usize, value: u64) {
+    data[offset .. offset + 8].copy_from_slice(&value.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_info, write_u32, write_u64, Ktx2Error, IDENTIFIER};
+    use crate::texture::{Dimensions, TextureFormat, UncompressedFloatFormat};
+
+    /// Builds the header and level index of a file, with room for the level data.
+    fn build_file(vk_format: u32, dimensions: [u32; 3], layers: u32, faces: u32,
+                  levels: u32, supercompression: u32) -> Vec<u8>
+    {
+        let index_size = 80 + 24 * std::cmp::max(levels, 1) as usize;
+        let mut data = vec![0; index_size + 64];
+        data[.. 12].copy_from_slice(&IDENTIFIER);
+        write_u32(&mut data, 12, vk_format);
+        write_u32(&mut data, 20, dimensions[0]);
+        write_u32(&mut data, 24, dimensions[1]);
+        write_u32(&mut data, 28, dimensions[2]);
+        write_u32(&mut data, 32, layers);
+        write_u32(&mut data, 36, faces);
+        write_u32(&mut data, 40, levels);
+        write_u32(&mut data, 44, supercompression);
+        for level in 0 .. std::cmp::max(levels, 1) as usize {
+            write_u64(&mut data, 80 + level * 24, index_size as u64);
+        }
+        data
+    }
+
+    #[test]
+    fn info_cubemap_with_mipmaps() {
+        let info = read_info(&build_file(37, [16, 16, 0], 0, 6, 5, 0)).unwrap();
+        assert_eq!(info.format, TextureFormat::UncompressedFloat(UncompressedFloatFormat::U8U8U8U8));
+        assert_eq!(info.dimensions, Dimensions::Cubemap { dimension: 16 });
+        assert_eq!(info.mipmap_levels, 5);
+        assert!(!info.generate_mipmaps);
+    }
+
+    #[test]
+    fn info_array_without_mipmaps() {
+        let info = read_info(&build_file(9, [8, 4, 0], 3, 1, 0, 0)).unwrap();
+        assert_eq!(info.dimensions, Dimensions::Texture2dArray { width: 8, height: 4, array_size: 3 });
+        assert_eq!(info.mipmap_levels, 1);
+        assert!(info.generate_mipmaps);
+    }
+
+    #[test]
+    fn info_errors() {
+        assert_eq!(read_info(b"not a ktx2 file"), Err(Ktx2Error::InvalidFile));
+        assert_eq!(read_info(&build_file(37, [16, 16, 0], 0, 1, 6, 0)), Err(Ktx2Error::InvalidFile));
+        assert_eq!(read_info(&build_file(37, [16, 8, 0], 0, 6, 1, 0)), Err(Ktx2Error::InvalidFile));
+        assert_eq!(read_info(&build_file(1000, [16, 16, 0], 0, 1, 1, 0)),
+                   Err(Ktx2Error::UnsupportedFormat(1000)));
+        assert_eq!(read_info(&build_file(37, [16, 16, 0], 0, 1, 1, 2)),
+                   Err(Ktx2Error::UnsupportedSupercompression(2)));
+    }
+}
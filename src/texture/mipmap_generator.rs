@@ -0,0 +1,348 @@
+/*!
+Generation of mipmaps with a choice of filters.
+
+`TextureAny::generate_mipmaps` and `MipmapsOption::AutoGeneratedMipmaps` let the driver compute
+the mipmaps, usually with a box filter applied to the values as they are stored. A
+`MipmapGenerator` instead computes each level by drawing a full-screen triangle into it, which
+allows:
+
+ - Using a Kaiser-windowed sinc filter, which keeps the small levels sharper than a box filter.
+ - Filtering colors in linear space. This is always the case for sRGB textures, and can be
+   requested for regular textures that contain sRGB-encoded values.
+ - Renormalizing the normals of a normal map, whose averages are otherwise too short.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+# let texture: glium::texture::Texture2d = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+use glium::texture::mipmap_generator::{MipmapFilter, MipmapGenerator, MipmapGenerationOptions};
+
+let generator = MipmapGenerator::new(&display).unwrap();
+
+generator.generate(&texture, &MipmapGenerationOptions {
+    filter: MipmapFilter::Kaiser { alpha: 4.0 },
+    normal_map: true,
+    .. Default::default()
+}).unwrap();
+```
+
+The mipmaps must have been allocated when the texture was created, for example with
+`MipmapsOption::EmptyMipmaps`. Each level is computed from the previous one.
+
+A `MipmapGenerator` requires GLSL 1.40 or GLSL ES 3.00, sampler objects, and textures whose
+format is renderable.
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+
+use crate::backend::Facade;
+use crate::framebuffer::{ColorAttachment, SimpleFrameBuffer, ValidationError};
+use crate::image_format::{TextureFormat, TextureFormatRequest};
+use crate::index::{NoIndices, PrimitiveType};
+use crate::program::{Program, ProgramCreationError};
+use crate::texture::{Dimensions, TextureAny};
+use crate::uniforms::{AsUniformValue, MagnifySamplerFilter, MinifySamplerFilter, Sampler};
+use crate::vertex::EmptyVertexAttributes;
+use crate::version::Api;
+use crate::CapabilitiesSource;
+use crate::DrawError;
+use crate::Surface;
+use crate::TextureExt;
+
+/// Filter used to compute a mipmap level from the previous one.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MipmapFilter {
+    /// Averages each block of 2x2 texels. This is what most drivers do.
+    Box,
+
+    /// Kaiser-windowed sinc over 6x6 texels. A higher `alpha` attenuates the ringing and makes
+    /// the result blurrier. `4.0` is a good default.
+    Kaiser {
+        /// Shape parameter of the Kaiser window.
+        alpha: f32,
+    },
+}
+
+/// Describes how a `MipmapGenerator` computes the mipmaps.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MipmapGenerationOptions {
+    /// The filter to use.
+    ///
+    /// The default value is `Box`.
+    pub filter: MipmapFilter,
+
+    /// If true, the RGB values of the texture are considered to be encoded in sRGB and are
+    /// filtered in linear space. This is the right choice for the colors of a regular texture
+    /// loaded from an image file.
+    ///
+    /// This is ignored for sRGB textures, which are always filtered in linear space.
+    ///
+    /// The default value is `false`.
+    pub gamma_correct: bool,
+
+    /// If true, the RGB values are considered to be unit vectors encoded as `n * 0.5 + 0.5`,
+    /// and the filtered vectors are normalized again.
+    ///
+    /// The default value is `false`.
+    pub normal_map: bool,
+}
+
+impl Default for MipmapGenerationOptions {
+    #[inline]
+    fn default() -> MipmapGenerationOptions {
+        MipmapGenerationOptions {
+            filter: MipmapFilter::Box,
+            gamma_correct: false,
+            normal_map: false,
+        }
+    }
+}
+
+/// Error that can happen while generating mipmaps.
+#[derive(Debug)]
+pub enum MipmapGenerationError {
+    /// The texture is not a two-dimensional texture.
+    WrongTextureType,
+
+    /// The base level of the texture can't be changed.
+    BaseLevelNotSupported,
+
+    /// A level of the texture can't be used as a framebuffer attachment.
+    FramebufferValidationError(ValidationError),
+
+    /// Failed to draw into a level of the texture.
+    DrawError(DrawError),
+}
+
+impl fmt::Display for MipmapGenerationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::MipmapGenerationError::*;
+        let desc = match *self {
+            WrongTextureType =>
+                "The texture is not a two-dimensional texture",
+            BaseLevelNotSupported =>
+                "The base level of the texture can't be changed",
+            FramebufferValidationError(_) =>
+                "A level of the texture can't be used as a framebuffer attachment",
+            DrawError(_) =>
+                "Failed to draw into a level of the texture",
+        };
+        fmt.write_str(desc)
+    }
+}
+
+impl Error for MipmapGenerationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use self::MipmapGenerationError::*;
+        match *self {
+            FramebufferValidationError(ref err) => Some(err),
+            DrawError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ValidationError> for MipmapGenerationError {
+    #[inline]
+    fn from(err: ValidationError) -> MipmapGenerationError {
+        MipmapGenerationError::FramebufferValidationError(err)
+    }
+}
+
+impl From<DrawError> for MipmapGenerationError {
+    #[inline]
+    fn from(err: DrawError) -> MipmapGenerationError {
+        MipmapGenerationError::DrawError(err)
+    }
+}
+
+/// Computes the mipmaps of textures with a shader.
+///
+/// See the documentation of the `mipmap_generator` module.
+pub struct MipmapGenerator {
+    program: Program,
+}
+
+impl MipmapGenerator {
+    /// Builds a new generator. This compiles its program.
+    pub fn new<F: ?Sized>(facade: &F) -> Result<MipmapGenerator, ProgramCreationError>
+                          where F: Facade
+    {
+        let header = match facade.get_context().get_version().0 {
+            Api::Gl => "#version 140\n",
+            Api::GlEs => "#version 300 es\nprecision highp float;\nprecision highp int;\n",
+        };
+
+        let program = Program::from_source(facade, &format!("{}{}", header, VERTEX_SHADER),
+                                           &format!("{}{}", header, FRAGMENT_SHADER), None)?;
+
+        Ok(MipmapGenerator {
+            program,
+        })
+    }
+
+    /// Computes the levels of the texture from the main level, as described by `options`.
+    ///
+    /// The content of the levels other than the main level is overwritten. The base level of
+    /// the texture is restored afterwards.
+    pub fn generate<'t, T>(&self, texture: &'t T, options: &MipmapGenerationOptions)
+                           -> Result<(), MipmapGenerationError>
+                           where T: Deref<Target = TextureAny>, Sampler<'t, T>: AsUniformValue
+    {
+        let any: &TextureAny = texture;
+
+        match any.get_texture_type() {
+            Dimensions::Texture2d { .. } => (),
+            _ => return Err(MipmapGenerationError::WrongTextureType),
+        }
+
+        let is_srgb = matches!(any.get_requested_format(),
+                               TextureFormatRequest::AnySrgb |
+                               TextureFormatRequest::Specific(TextureFormat::Srgb(_)) |
+                               TextureFormatRequest::Specific(TextureFormat::CompressedSrgbFormat(_)));
+
+        let (filter, kaiser_alpha) = match options.filter {
+            MipmapFilter::Box => (0, 0.0),
+            MipmapFilter::Kaiser { alpha } => (1, alpha),
+        };
+
+        let previous_base_level = any.get_base_level();
+        let result = (1 .. any.get_mipmap_levels()).try_for_each(|level| {
+            // the previous level is the only one that the sampler can read, which avoids a
+            // feedback loop with the level that is attached to the framebuffer
+            any.set_base_level(level - 1)
+               .map_err(|_| MipmapGenerationError::BaseLevelNotSupported)?;
+
+            let image = any.mipmap(level).unwrap().first_layer().into_image(None).unwrap();
+            let mut framebuffer = SimpleFrameBuffer::new(any.get_context(),
+                                                         ColorAttachment::Texture(image))?;
+
+            let source = Sampler::new(texture)
+                .minify_filter(MinifySamplerFilter::Nearest)
+                .magnify_filter(MagnifySamplerFilter::Nearest);
+
+            let uniforms = uniform! {
+                source: source,
+                filter_type: filter,
+                kaiser_alpha: kaiser_alpha,
+                decode_srgb: options.gamma_correct && !is_srgb,
+                normal_map: options.normal_map,
+            };
+
+            framebuffer.draw(EmptyVertexAttributes { len: 3 },
+                             NoIndices(PrimitiveType::TrianglesList), &self.program, &uniforms,
+                             &Default::default())?;
+            Ok(())
+        });
+
+        let _ = any.set_base_level(previous_base_level);
+        result
+    }
+}
+
+/// Draws a triangle that covers the whole target.
+const VERTEX_SHADER: &str = "
+    void main() {
+        vec2 position = vec2(float((gl_VertexID & 1) << 2) - 1.0,
+                             float((gl_VertexID & 2) << 1) - 1.0);
+        gl_Position = vec4(position, 0.0, 1.0);
+    }
+";
+
+/// Computes a texel of the level from the base level of `source`.
+const FRAGMENT_SHADER: &str = "
+    uniform sampler2D source;
+    uniform int filter_type;
+    uniform float kaiser_alpha;
+    uniform bool decode_srgb;
+    uniform bool normal_map;
+
+    out vec4 color;
+
+    const float PI = 3.14159265;
+
+    vec3 srgb_to_linear(vec3 c) {
+        return mix(c / 12.92, pow((c + 0.055) / 1.055, vec3(2.4)), step(vec3(0.04045), c));
+    }
+
+    vec3 linear_to_srgb(vec3 c) {
+        return mix(c * 12.92, 1.055 * pow(c, vec3(1.0 / 2.4)) - 0.055,
+                   step(vec3(0.0031308), c));
+    }
+
+    vec4 fetch(ivec2 coords) {
+        ivec2 size = textureSize(source, 0);
+        vec4 value = texelFetch(source, clamp(coords, ivec2(0), size - 1), 0);
+
+        if (decode_srgb) {
+            value.rgb = srgb_to_linear(value.rgb);
+        }
+
+        if (normal_map) {
+            value.rgb = value.rgb * 2.0 - 1.0;
+        }
+
+        return value;
+    }
+
+    float bessel_i0(float x) {
+        float sum = 1.0;
+        float term = 1.0;
+        for (int k = 1; k < 16; ++k) {
+            float t = x / (2.0 * float(k));
+            term *= t * t;
+            sum += term;
+        }
+        return sum;
+    }
+
+    // `d` is the distance to the center of the texel of the level, in texels of the source
+    float kaiser_weight(float d) {
+        float t = d / 3.0;
+        if (abs(t) >= 1.0) {
+            return 0.0;
+        }
+
+        float x = d * 0.5 * PI;
+        float sinc = abs(x) < 0.0001 ? 1.0 : sin(x) / x;
+        return sinc * bessel_i0(kaiser_alpha * sqrt(1.0 - t * t)) / bessel_i0(kaiser_alpha);
+    }
+
+    void main() {
+        ivec2 base = ivec2(gl_FragCoord.xy) * 2;
+
+        vec4 sum = vec4(0.0);
+        float total = 0.0;
+
+        if (filter_type == 0) {
+            for (int y = 0; y < 2; ++y) {
+                for (int x = 0; x < 2; ++x) {
+                    sum += fetch(base + ivec2(x, y));
+                    total += 1.0;
+                }
+            }
+        } else {
+            for (int y = -2; y < 4; ++y) {
+                for (int x = -2; x < 4; ++x) {
+                    float weight = kaiser_weight(float(x) - 0.5) * kaiser_weight(float(y) - 0.5);
+                    sum += fetch(base + ivec2(x, y)) * weight;
+                    total += weight;
+                }
+            }
+        }
+
+        vec4 value = sum / total;
+
+        if (normal_map) {
+            float len = length(value.rgb);
+            value.rgb = (len > 0.0 ? value.rgb / len : vec3(0.0, 0.0, 1.0)) * 0.5 + 0.5;
+        }
+
+        if (decode_srgb) {
+            value.rgb = linear_to_srgb(max(value.rgb, vec3(0.0)));
+        }
+
+        color = value;
+    }
+";
@@ -113,10 +113,15 @@ You can use thousands of textures if you want.
 #![allow(unreachable_code)]     // TODO: remove
 
 use std::borrow::Cow;
+use std::cmp;
 use std::fmt;
+use std::mem;
 use std::error::Error;
 
+use crate::gl;
 use crate::image_format::FormatNotSupportedError;
+use crate::version::{Api, Version};
+use crate::CapabilitiesSource;
 
 pub use crate::image_format::{ClientFormat, TextureFormat};
 pub use crate::image_format::{UncompressedFloatFormat, UncompressedIntFormat, UncompressedUintFormat};
@@ -124,6 +129,8 @@ pub use crate::image_format::{CompressedFormat, DepthFormat, DepthStencilFormat,
 pub use crate::image_format::{CompressedSrgbFormat, SrgbFormat};
 pub use self::any::{TextureAny, TextureAnyMipmap, TextureAnyLayer, TextureAnyLayerMipmap};
 pub use self::any::{TextureAnyImage, Dimensions};
+pub use self::any::DetachedTexture;
+pub use self::array_allocator::{TextureArrayAllocator, TextureArraySlot};
 pub use self::bindless::{ResidentTexture, TextureHandle, BindlessTexturesNotSupportedError};
 pub use self::get_format::{InternalFormat, InternalFormatType, GetFormatError};
 pub use self::pixel::PixelValue;
@@ -133,9 +140,19 @@ pub use self::ty_support::{is_texture_2d_array_supported, is_texture_2d_multisam
 pub use self::ty_support::{is_texture_2d_multisample_array_supported, is_cubemaps_supported};
 pub use self::ty_support::is_cubemap_arrays_supported;
 
+pub mod array_allocator;
+pub mod async_upload;
 pub mod bindless;
 pub mod buffer_texture;
+#[cfg(feature = "dds")]
+pub mod dds;
+#[cfg(feature = "ktx2")]
+pub mod ktx2;
+pub mod mipmap_generator;
 pub mod pixel_buffer;
+pub mod sparse;
+pub mod streaming;
+pub mod view;
 
 mod any;
 mod get_format;
@@ -186,6 +203,114 @@ pub enum TextureKind {
     DepthStencil,
 }
 
+/// Source of a component of the values that shaders read from a texture.
+///
+/// See `TextureAny::set_swizzle`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Swizzle {
+    /// The red component of the texture.
+    Red,
+    /// The green component of the texture.
+    Green,
+    /// The blue component of the texture.
+    Blue,
+    /// The alpha component of the texture.
+    Alpha,
+    /// Always `0`.
+    Zero,
+    /// Always `1`.
+    One,
+}
+
+impl Swizzle {
+    /// The swizzle of textures that haven't been configured, which returns each component
+    /// as it is.
+    pub const IDENTITY: [Swizzle; 4] = [Swizzle::Red, Swizzle::Green, Swizzle::Blue,
+                                        Swizzle::Alpha];
+
+    #[inline]
+    pub(crate) fn to_glenum(self) -> gl::types::GLenum {
+        match self {
+            Swizzle::Red => gl::RED,
+            Swizzle::Green => gl::GREEN,
+            Swizzle::Blue => gl::BLUE,
+            Swizzle::Alpha => gl::ALPHA,
+            Swizzle::Zero => gl::ZERO,
+            Swizzle::One => gl::ONE,
+        }
+    }
+}
+
+/// Texture swizzles are not supported by the backend.
+#[derive(Debug, Copy, Clone)]
+pub struct SwizzleNotSupportedError;
+
+impl fmt::Display for SwizzleNotSupportedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "Texture swizzles are not supported by the backend")
+    }
+}
+
+impl Error for SwizzleNotSupportedError {}
+
+/// Component of a depth-stencil texture that is read by shaders.
+///
+/// See `TextureAny::set_depth_stencil_mode`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DepthStencilTextureMode {
+    /// Shaders read the depth component, with a `sampler*` or a `sampler*Shadow` uniform.
+    Depth,
+    /// Shaders read the stencil index, with a `usampler*` uniform.
+    Stencil,
+}
+
+impl DepthStencilTextureMode {
+    #[inline]
+    pub(crate) fn to_glenum(self) -> gl::types::GLenum {
+        match self {
+            DepthStencilTextureMode::Depth => gl::DEPTH_COMPONENT,
+            DepthStencilTextureMode::Stencil => gl::STENCIL_INDEX,
+        }
+    }
+}
+
+/// Reading the stencil component of depth-stencil textures is not supported by the backend.
+#[derive(Debug, Copy, Clone)]
+pub struct StencilTexturingNotSupportedError;
+
+impl fmt::Display for StencilTexturingNotSupportedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "Stencil texturing is not supported by the backend")
+    }
+}
+
+impl Error for StencilTexturingNotSupportedError {}
+
+/// Returns true if the stencil component of depth-stencil textures can be read by shaders.
+#[inline]
+pub fn is_stencil_texturing_supported<C: ?Sized>(context: &C) -> bool
+                                         where C: CapabilitiesSource
+{
+    context.get_version() >= &Version(Api::Gl, 4, 3) ||
+    context.get_version() >= &Version(Api::GlEs, 3, 1) ||
+    context.get_extensions().gl_arb_stencil_texturing
+}
+
+/// Changing the base level of textures is not supported by the backend.
+#[derive(Debug, Copy, Clone)]
+pub struct BaseLevelNotSupportedError;
+
+impl fmt::Display for BaseLevelNotSupportedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "Changing the base level of textures is not supported by the backend")
+    }
+}
+
+impl Error for BaseLevelNotSupportedError {}
+
 /// Describes what to do about mipmaps during texture creation.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum MipmapsOption {
@@ -678,6 +803,212 @@ impl_2d_sink_for_raw_image!((f32, f32));
 impl_2d_sink_for_raw_image!((f32, f32, f32));
 impl_2d_sink_for_raw_image!((f32, f32, f32, f32));
 
+/// An image and all its mipmap levels, ready to be uploaded to a texture.
+///
+/// The levels are `RawImage1d`, `RawImage2d` or `RawImage3d`, and the first element is the
+/// main level. The dimensions of each following level must be the dimensions of the previous
+/// level divided by two (rounded down, with a minimum of 1), and all levels must share the same
+/// client format. This is checked when building the object.
+///
+/// Pass this object to the `with_mipmapped_image` constructor of a texture type whose
+/// dimensions match the levels, or a list of them (one for each layer) to the constructor of an
+/// array texture. The texture will have exactly as many levels as the image.
+pub struct MipmappedImage<I> {
+    levels: Vec<I>,
+}
+
+/// An image that can be a level of a `MipmappedImage`.
+pub trait MipmapLevel {
+    /// Returns the width, the height and the depth of the image.
+    fn dimensions(&self) -> (u32, Option<u32>, Option<u32>);
+
+    /// Returns the format of the pixels.
+    fn client_format(&self) -> ClientFormat;
+
+    /// Returns the number of bytes of the data.
+    fn data_size(&self) -> usize;
+}
+
+impl<'a, T: Clone> MipmapLevel for RawImage1d<'a, T> {
+    #[inline]
+    fn dimensions(&self) -> (u32, Option<u32>, Option<u32>) {
+        (self.width, None, None)
+    }
+
+    #[inline]
+    fn client_format(&self) -> ClientFormat {
+        self.format
+    }
+
+    #[inline]
+    fn data_size(&self) -> usize {
+        self.data.len() * mem::size_of::<T>()
+    }
+}
+
+impl<'a, T: Clone> MipmapLevel for RawImage2d<'a, T> {
+    #[inline]
+    fn dimensions(&self) -> (u32, Option<u32>, Option<u32>) {
+        (self.width, Some(self.height), None)
+    }
+
+    #[inline]
+    fn client_format(&self) -> ClientFormat {
+        self.format
+    }
+
+    #[inline]
+    fn data_size(&self) -> usize {
+        self.data.len() * mem::size_of::<T>()
+    }
+}
+
+impl<'a, T: Clone> MipmapLevel for RawImage3d<'a, T> {
+    #[inline]
+    fn dimensions(&self) -> (u32, Option<u32>, Option<u32>) {
+        (self.width, Some(self.height), Some(self.depth))
+    }
+
+    #[inline]
+    fn client_format(&self) -> ClientFormat {
+        self.format
+    }
+
+    #[inline]
+    fn data_size(&self) -> usize {
+        self.data.len() * mem::size_of::<T>()
+    }
+}
+
+impl<I: MipmapLevel> MipmappedImage<I> {
+    /// Builds a mipmapped image from its list of levels, starting with the main level.
+    pub fn new(levels: Vec<I>) -> Result<MipmappedImage<I>, MipmappedImageError> {
+        let ((width, height, depth), format) = match levels.first() {
+            Some(main) => (main.dimensions(), main.client_format()),
+            None => return Err(MipmappedImageError::NoLevel),
+        };
+
+        let maximum = MipmapsOption::EmptyMipmaps.num_levels(width, height, depth);
+        if levels.len() as u32 > maximum {
+            return Err(MipmappedImageError::TooManyLevels { maximum, obtained: levels.len() as u32 });
+        }
+
+        for (level, image) in levels.iter().enumerate() {
+            let expected = (cmp::max(1, width >> level), height.map(|h| cmp::max(1, h >> level)),
+                            depth.map(|d| cmp::max(1, d >> level)));
+            let obtained = image.dimensions();
+            if obtained != expected {
+                return Err(MipmappedImageError::WrongDimensions {
+                    level: level as u32,
+                    expected,
+                    obtained,
+                });
+            }
+
+            if image.client_format() != format {
+                return Err(MipmappedImageError::FormatMismatch { level: level as u32 });
+            }
+
+            let required = obtained.0 as usize * obtained.1.unwrap_or(1) as usize *
+                           obtained.2.unwrap_or(1) as usize * format.get_size();
+            let obtained = image.data_size();
+            if required != obtained {
+                return Err(MipmappedImageError::WrongDataSize { level: level as u32, required, obtained });
+            }
+        }
+
+        Ok(MipmappedImage { levels })
+    }
+
+    /// Returns the width, the height and the depth of the main level.
+    #[inline]
+    pub fn dimensions(&self) -> (u32, Option<u32>, Option<u32>) {
+        self.levels[0].dimensions()
+    }
+
+    /// Returns the number of levels, including the main level.
+    #[inline]
+    pub fn get_levels_count(&self) -> u32 {
+        self.levels.len() as u32
+    }
+
+    /// Returns the list of levels, starting with the main level.
+    #[inline]
+    pub fn get_levels(&self) -> &[I] {
+        &self.levels
+    }
+
+    /// Destroys the object and returns the list of levels, starting with the main level.
+    #[inline]
+    pub fn into_levels(self) -> Vec<I> {
+        self.levels
+    }
+}
+
+/// Error that can happen when building a `MipmappedImage`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MipmappedImageError {
+    /// The list of levels is empty.
+    NoLevel,
+
+    /// There are more levels than allowed by the dimensions of the main level.
+    TooManyLevels {
+        /// Maximum number of levels, including the main level.
+        maximum: u32,
+        /// Number of levels that were passed.
+        obtained: u32,
+    },
+
+    /// The dimensions of a level don't follow the mipmap chain.
+    WrongDimensions {
+        /// Index of the faulty level.
+        level: u32,
+        /// Dimensions that were expected for this level.
+        expected: (u32, Option<u32>, Option<u32>),
+        /// Dimensions of the level.
+        obtained: (u32, Option<u32>, Option<u32>),
+    },
+
+    /// The client format of a level is different from the one of the main level.
+    FormatMismatch {
+        /// Index of the faulty level.
+        level: u32,
+    },
+
+    /// The length of the data of a level doesn't match its dimensions and format.
+    WrongDataSize {
+        /// Index of the faulty level.
+        level: u32,
+        /// Number of bytes required by the dimensions and format.
+        required: usize,
+        /// Number of bytes of the data.
+        obtained: usize,
+    },
+}
+
+impl fmt::Display for MipmappedImageError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::MipmappedImageError::*;
+        match *self {
+            NoLevel =>
+                write!(fmt, "The mipmapped image doesn't have any level"),
+            TooManyLevels { maximum, obtained } =>
+                write!(fmt, "The mipmapped image has {} levels, but the maximum for its \
+                             dimensions is {}", obtained, maximum),
+            WrongDimensions { level, expected, obtained } =>
+                write!(fmt, "Level {} has dimensions {:?}, expected {:?}", level, obtained,
+                       expected),
+            FormatMismatch { level } =>
+                write!(fmt, "Level {} doesn't have the same format as the main level", level),
+            WrongDataSize { level, required, obtained } =>
+                write!(fmt, "Level {} has {} bytes of data, expected {}", level, obtained,
+                       required),
+        }
+    }
+}
+
+impl Error for MipmappedImageError {}
+
 /// Trait that describes data for a two-dimensional texture.
 pub trait Texture3dDataSource<'a> {
     /// The type of each pixel.
@@ -821,3 +1152,38 @@ impl From<FormatNotSupportedError> for TextureCreationError {
         TextureCreationError::FormatNotSupported
     }
 }
+
+/// Error that can happen when copying between two textures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyImageError {
+    /// The region to copy is outside of the source texture.
+    SourceOutOfRange,
+
+    /// The region to copy doesn't fit in the target texture.
+    TargetOutOfRange,
+
+    /// The two textures don't have the same number of samples.
+    SamplesMismatch,
+
+    /// The formats of the two textures are not compatible.
+    FormatNotCompatible,
+}
+
+impl fmt::Display for CopyImageError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::CopyImageError::*;
+        let desc = match *self {
+            SourceOutOfRange =>
+                "The region to copy is outside of the source texture",
+            TargetOutOfRange =>
+                "The region to copy doesn't fit in the target texture",
+            SamplesMismatch =>
+                "The two textures don't have the same number of samples",
+            FormatNotCompatible =>
+                "The formats of the two textures are not compatible",
+        };
+        fmt.write_str(desc)
+    }
+}
+
+impl Error for CopyImageError {}
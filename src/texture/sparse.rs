@@ -0,0 +1,411 @@
+/*!
+Sparse textures, whose memory is allocated page by page.
+
+A sparse texture reserves address space for all its texels when it is created, but doesn't
+allocate any memory for them. You then choose which regions of the texture must be backed by
+memory with `commit`, and release them with `decommit`. This allows you to create textures
+much larger than the available video memory, for example for virtual texturing.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+use glium::texture::{MipmapsOption, Texture2d, UncompressedFloatFormat};
+use glium::texture::sparse::SparseTexture;
+
+let format = UncompressedFloatFormat::U8U8U8U8;
+let page_size = SparseTexture::<Texture2d>::get_page_sizes(&display, format)[0];
+
+let texture = SparseTexture::<Texture2d>::empty(&display, format, MipmapsOption::NoMipmap,
+                                                page_size, 16384, 16384).unwrap();
+
+// allocates the memory of the top-left page of the main level
+texture.commit(0, (0, 0, 0), (page_size.width, page_size.height, 1)).unwrap();
+```
+
+A `SparseTexture` dereferences to the regular texture, which can be sampled, written and
+attached to framebuffers like any other texture. Reading from a region that isn't committed
+returns undefined values, and writing to it has no effect.
+
+The textures are allocated with a page size chosen among the ones returned by
+`get_page_sizes`. The regions passed to `commit` and `decommit` must be aligned to the pages,
+except that they can end at the edge of the mipmap level. The smallest levels, starting from
+`get_num_sparse_levels`, form a tail that is committed as a whole.
+
+Sparse textures require `GL_ARB_sparse_texture`.
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+
+use crate::gl;
+use crate::GlObject;
+use crate::CapabilitiesSource;
+use crate::ContextExt;
+use crate::TextureExt;
+
+use crate::backend::Facade;
+use crate::context::Context;
+use crate::image_format::{self, TextureFormatRequest};
+use crate::texture::{Dimensions, MipmapsOption, TextureAny, UncompressedFloatFormat};
+use crate::texture::{Texture2d, Texture2dArray, Texture3d};
+
+/// Size of a page of a sparse texture, in texels.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PageSize {
+    /// Width of a page.
+    pub width: u32,
+    /// Height of a page.
+    pub height: u32,
+    /// Depth of a page. Always `1` for 2D textures.
+    pub depth: u32,
+}
+
+/// Returns true if sparse textures are supported by the backend.
+#[inline]
+pub fn is_sparse_texture_supported<C: ?Sized>(caps: &C) -> bool where C: CapabilitiesSource {
+    caps.get_extensions().gl_arb_sparse_texture
+}
+
+/// Error that can happen when creating a sparse texture.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SparseTextureCreationError {
+    /// Sparse textures are not supported by the backend.
+    NotSupported,
+
+    /// The requested format is not supported by the backend.
+    FormatNotSupported,
+
+    /// The page size is not one of the sizes returned by `get_page_sizes` for this format.
+    PageSizeNotSupported,
+
+    /// The dimensions of the texture are not multiples of the page size.
+    DimensionsNotAligned,
+}
+
+impl fmt::Display for SparseTextureCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::SparseTextureCreationError::*;
+        let desc = match *self {
+            NotSupported =>
+                "Sparse textures are not supported by the backend",
+            FormatNotSupported =>
+                "The requested format is not supported by the backend",
+            PageSizeNotSupported =>
+                "The page size is not supported for this format",
+            DimensionsNotAligned =>
+                "The dimensions of the texture are not multiples of the page size",
+        };
+        fmt.write_str(desc)
+    }
+}
+
+impl Error for SparseTextureCreationError {}
+
+/// Error that can happen when committing or decommitting a region of a sparse texture.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PageCommitmentError {
+    /// The texture doesn't have a mipmap at this level.
+    NoMipmapAtLevel(u32),
+
+    /// The region is outside of the mipmap level.
+    OutOfRange,
+
+    /// The region is not aligned to the pages of the texture.
+    NotAligned,
+}
+
+impl fmt::Display for PageCommitmentError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::PageCommitmentError::*;
+        match *self {
+            NoMipmapAtLevel(level) =>
+                write!(fmt, "The texture doesn't have a mipmap at level {}", level),
+            OutOfRange =>
+                write!(fmt, "The region is outside of the mipmap level"),
+            NotAligned =>
+                write!(fmt, "The region is not aligned to the pages of the texture"),
+        }
+    }
+}
+
+impl Error for PageCommitmentError {}
+
+/// A texture whose memory is committed page by page. Dereferences to the texture.
+///
+/// See the module-level documentation.
+pub struct SparseTexture<T> {
+    texture: T,
+    page_size: PageSize,
+    sparse_levels: u32,
+}
+
+impl<T> SparseTexture<T> where T: Deref<Target = TextureAny> {
+    /// Returns the size of the pages of the texture.
+    #[inline]
+    pub fn get_page_size(&self) -> PageSize {
+        self.page_size
+    }
+
+    /// Returns the number of mipmap levels that can be committed region by region. The levels
+    /// after these ones form the tail of the mipmaps, which is committed as a whole.
+    #[inline]
+    pub fn get_num_sparse_levels(&self) -> u32 {
+        self.sparse_levels
+    }
+
+    /// Allocates the memory of a region of a mipmap level. `offset` and `size` are the
+    /// coordinates of the region in texels, the third component being the layer of array
+    /// textures.
+    ///
+    /// The content of the newly committed pages is undefined.
+    #[inline]
+    pub fn commit(&self, level: u32, offset: (u32, u32, u32), size: (u32, u32, u32))
+                  -> Result<(), PageCommitmentError>
+    {
+        self.page_commitment(level, offset, size, true)
+    }
+
+    /// Releases the memory of a region of a mipmap level.
+    ///
+    /// See `commit`.
+    #[inline]
+    pub fn decommit(&self, level: u32, offset: (u32, u32, u32), size: (u32, u32, u32))
+                    -> Result<(), PageCommitmentError>
+    {
+        self.page_commitment(level, offset, size, false)
+    }
+
+    /// Unwraps the texture. The memory that is still committed stays allocated.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.texture
+    }
+
+    fn page_commitment(&self, level: u32, offset: (u32, u32, u32), size: (u32, u32, u32),
+                       commit: bool) -> Result<(), PageCommitmentError>
+    {
+        let mipmap = self.texture.mipmap(level)
+                                 .ok_or(PageCommitmentError::NoMipmapAtLevel(level))?;
+        let level_size = (
+            mipmap.get_width(),
+            mipmap.get_height().unwrap_or(1),
+            mipmap.get_depth().or_else(|| self.texture.get_array_size()).unwrap_or(1),
+        );
+
+        let dimensions = [
+            (offset.0, size.0, level_size.0, self.page_size.width),
+            (offset.1, size.1, level_size.1, self.page_size.height),
+            (offset.2, size.2, level_size.2, self.page_size.depth),
+        ];
+
+        for &(offset, size, level_size, page_size) in dimensions.iter() {
+            match offset.checked_add(size) {
+                Some(end) if end <= level_size => (),
+                _ => return Err(PageCommitmentError::OutOfRange),
+            }
+
+            // the levels of the tail are committed as a whole
+            if level < self.sparse_levels &&
+               (offset % page_size != 0 ||
+                (size % page_size != 0 && offset + size != level_size))
+            {
+                return Err(PageCommitmentError::NotAligned);
+            }
+        }
+
+        let mut ctxt = self.texture.get_context().make_current();
+
+        unsafe {
+            let bind_point = self.texture.bind_to_current(&mut ctxt);
+            ctxt.gl.TexPageCommitmentARB(bind_point, level as gl::types::GLint,
+                                         offset.0 as gl::types::GLint,
+                                         offset.1 as gl::types::GLint,
+                                         offset.2 as gl::types::GLint,
+                                         size.0 as gl::types::GLsizei,
+                                         size.1 as gl::types::GLsizei,
+                                         size.2 as gl::types::GLsizei,
+                                         if commit { gl::TRUE } else { gl::FALSE });
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Deref for SparseTexture<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.texture
+    }
+}
+
+impl<T> fmt::Debug for SparseTexture<T> where T: fmt::Debug {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(fmt, "SparseTexture({:?}, {:?})", self.texture, self.page_size)
+    }
+}
+
+macro_rules! impl_sparse_texture {
+    ($ty:ident, $bind_point:expr, $two_d:expr, ($($dim:ident),+), $size:expr, $dimensions:expr) => (
+        impl SparseTexture<$ty> {
+            /// Returns the page sizes that the backend supports for this format, or an empty
+            /// list if sparse textures are not supported.
+            pub fn get_page_sizes<F: ?Sized>(facade: &F, format: UncompressedFloatFormat)
+                                             -> Vec<PageSize> where F: Facade
+            {
+                match get_internal_format(facade.get_context(), format) {
+                    Ok(internal_format) => get_page_sizes(facade.get_context(), $bind_point,
+                                                          internal_format),
+                    Err(_) => Vec::new(),
+                }
+            }
+
+            /// Creates a sparse texture. No memory is committed.
+            ///
+            /// The dimensions must be multiples of the page size, which must be one of the
+            /// sizes returned by `get_page_sizes`.
+            pub fn empty<F: ?Sized>(facade: &F, format: UncompressedFloatFormat,
+                                    mipmaps: MipmapsOption, page_size: PageSize,
+                                    $($dim: u32),+)
+                                    -> Result<SparseTexture<$ty>, SparseTextureCreationError>
+                                    where F: Facade
+            {
+                let ty = $dimensions;
+                let (width, height, depth): (u32, u32, u32) = $size;
+                let internal_format = get_internal_format(facade.get_context(), format)?;
+
+                let page_size_index = get_page_sizes(facade.get_context(), $bind_point,
+                                                     internal_format)
+                                          .iter().position(|&p| p == page_size)
+                                          .ok_or(SparseTextureCreationError::PageSizeNotSupported)?;
+
+                if width % page_size.width != 0 || height % page_size.height != 0 ||
+                   (!$two_d && depth % page_size.depth != 0)
+                {
+                    return Err(SparseTextureCreationError::DimensionsNotAligned);
+                }
+
+                let levels = mipmaps.num_levels(width, Some(height),
+                                                if $two_d { None } else { Some(depth) });
+
+                let (id, sparse_levels) = unsafe {
+                    create_sparse_texture(facade.get_context(), $bind_point, internal_format,
+                                          page_size_index, levels, (width, height, depth))
+                };
+
+                let texture = unsafe { $ty::from_id(facade, format, id, true, mipmaps, ty) };
+
+                Ok(SparseTexture {
+                    texture,
+                    page_size,
+                    sparse_levels,
+                })
+            }
+        }
+    );
+}
+
+impl_sparse_texture!(Texture2d, gl::TEXTURE_2D, true, (width, height), (width, height, 1),
+                     Dimensions::Texture2d { width, height });
+impl_sparse_texture!(Texture2dArray, gl::TEXTURE_2D_ARRAY, true, (width, height, array_size),
+                     (width, height, array_size),
+                     Dimensions::Texture2dArray { width, height, array_size });
+impl_sparse_texture!(Texture3d, gl::TEXTURE_3D, false, (width, height, depth),
+                     (width, height, depth), Dimensions::Texture3d { width, height, depth });
+
+/// Returns the internal format to use to create a sparse texture.
+fn get_internal_format(context: &Context, format: UncompressedFloatFormat)
+                       -> Result<gl::types::GLenum, SparseTextureCreationError>
+{
+    if !is_sparse_texture_supported(context) {
+        return Err(SparseTextureCreationError::NotSupported);
+    }
+
+    let format = TextureFormatRequest::Specific(format.to_texture_format());
+    image_format::format_request_to_glenum(context, format, image_format::RequestType::TexStorage)
+        .map_err(|_| SparseTextureCreationError::FormatNotSupported)
+}
+
+/// Queries the list of page sizes supported for a target and an internal format.
+fn get_page_sizes(context: &Context, bind_point: gl::types::GLenum,
+                  internal_format: gl::types::GLenum) -> Vec<PageSize>
+{
+    let ctxt = context.make_current();
+
+    unsafe {
+        let mut num = 0;
+        ctxt.gl.GetInternalformativ(bind_point, internal_format, gl::NUM_VIRTUAL_PAGE_SIZES_ARB,
+                                    1, &mut num);
+        if num <= 0 {
+            return Vec::new();
+        }
+
+        let mut widths = vec![0; num as usize];
+        let mut heights = vec![0; num as usize];
+        let mut depths = vec![0; num as usize];
+        ctxt.gl.GetInternalformativ(bind_point, internal_format, gl::VIRTUAL_PAGE_SIZE_X_ARB,
+                                    num, widths.as_mut_ptr());
+        ctxt.gl.GetInternalformativ(bind_point, internal_format, gl::VIRTUAL_PAGE_SIZE_Y_ARB,
+                                    num, heights.as_mut_ptr());
+        ctxt.gl.GetInternalformativ(bind_point, internal_format, gl::VIRTUAL_PAGE_SIZE_Z_ARB,
+                                    num, depths.as_mut_ptr());
+
+        widths.into_iter().zip(heights).zip(depths)
+              .map(|((width, height), depth)| PageSize {
+                  width: width as u32,
+                  height: height as u32,
+                  depth: depth as u32,
+              })
+              .collect()
+    }
+}
+
+/// Creates the texture object and its sparse storage. Returns the id of the texture and its
+/// number of sparse levels.
+unsafe fn create_sparse_texture(context: &Context, bind_point: gl::types::GLenum,
+                                internal_format: gl::types::GLenum, page_size_index: usize,
+                                levels: u32, (width, height, depth): (u32, u32, u32))
+                                -> (gl::types::GLuint, u32)
+{
+    let mut ctxt = context.make_current();
+
+    let mut id = 0;
+    ctxt.gl.GenTextures(1, &mut id);
+
+    ctxt.gl.BindTexture(bind_point, id);
+    let act = ctxt.state.active_texture as usize;
+    ctxt.state.texture_units[act].texture = id;
+
+    let min_filter = if levels > 1 { gl::LINEAR_MIPMAP_LINEAR } else { gl::LINEAR };
+    ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MIN_FILTER, min_filter as gl::types::GLint);
+    ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MAG_FILTER, gl::LINEAR as gl::types::GLint);
+
+    ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_SPARSE_ARB, gl::TRUE as gl::types::GLint);
+    ctxt.gl.TexParameteri(bind_point, gl::VIRTUAL_PAGE_SIZE_INDEX_ARB,
+                          page_size_index as gl::types::GLint);
+
+    if bind_point == gl::TEXTURE_2D {
+        ctxt.gl.TexStorage2D(bind_point, levels as gl::types::GLsizei, internal_format,
+                             width as gl::types::GLsizei, height as gl::types::GLsizei);
+    } else {
+        ctxt.gl.TexStorage3D(bind_point, levels as gl::types::GLsizei, internal_format,
+                             width as gl::types::GLsizei, height as gl::types::GLsizei,
+                             depth as gl::types::GLsizei);
+    }
+
+    let mut sparse_levels = 0;
+    ctxt.gl.GetTexParameteriv(bind_point, gl::NUM_SPARSE_LEVELS_ARB, &mut sparse_levels);
+
+    (id, sparse_levels as u32)
+}
+
+impl<T> GlObject for SparseTexture<T> where T: GlObject {
+    type Id = T::Id;
+
+    #[inline]
+    fn get_id(&self) -> T::Id {
+        self.texture.get_id()
+    }
+}
@@ -0,0 +1,369 @@
+/*!
+Streaming of mipmap levels with a memory budget.
+
+A `TextureStreamer` owns a set of two-dimensional textures whose content is loaded on demand.
+Each texture is registered with a loader that produces the content of a given mipmap level.
+When a texture is requested, the least detailed level is uploaded first, and the more detailed
+levels are uploaded over the next calls to `update`. In the meantime the texture is clamped to
+its most detailed uploaded level with `TextureAny::set_base_level`, so that it can be drawn
+immediately.
+
+The textures are counted against a budget of video memory. When the budget is exceeded, or if
+the backend reports that the free video memory is below a threshold, the textures that have been
+used the least recently are destroyed. They are streamed again the next time they are
+requested.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+# fn load_from_disk(_: u32) -> glium::texture::RawImage2d<'static, u8> { unimplemented!() }
+use glium::texture::streaming::TextureStreamer;
+
+let mut streamer = TextureStreamer::new(&display, 256 * 1024 * 1024);
+streamer.insert("rock", 1024, 1024, |level| load_from_disk(level));
+
+// every frame
+streamer.request(&"rock", 0);
+streamer.update(4).unwrap();
+
+if let Some(texture) = streamer.get(&"rock") {
+    // draw with the texture
+}
+```
+
+## Memory
+
+The memory of a texture is allocated for all its mipmap levels when the texture is created.
+Textures are therefore evicted whole, and the memory usage reported by `TextureStreamer`
+includes the levels that have not been uploaded yet.
+
+The free video memory is obtained with `GL_NVX_gpu_memory_info` or `GL_ATI_meminfo`. It is
+not available with other backends.
+
+*/
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::Rc;
+
+use crate::backend::Facade;
+use crate::context::Context;
+use crate::texture::{MipmapsOption, RawImage2d, Texture2d, TextureCreationError};
+use crate::texture::UncompressedFloatFormat;
+use crate::Rect;
+
+/// Produces the content of the mipmap levels of a streamed texture.
+///
+/// This trait is implemented on closures that take a level and return its content.
+pub trait LevelLoader {
+    /// Returns the content of a mipmap level. Level 0 is the most detailed level.
+    ///
+    /// The dimensions of the image must be the dimensions of the level.
+    fn load_level(&mut self, level: u32) -> RawImage2d<'static, u8>;
+}
+
+impl<F> LevelLoader for F where F: FnMut(u32) -> RawImage2d<'static, u8> {
+    #[inline]
+    fn load_level(&mut self, level: u32) -> RawImage2d<'static, u8> {
+        self(level)
+    }
+}
+
+/// Memory usage of a `TextureStreamer`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StreamingMemoryInfo {
+    /// The budget of the streamer, in bytes.
+    pub budget: usize,
+
+    /// Memory used by the textures of the streamer, in bytes.
+    pub used: usize,
+
+    /// Number of textures that currently exist in video memory.
+    pub resident_textures: usize,
+
+    /// Estimate of the free video memory reported by the backend, in bytes.
+    pub free_video_memory: Option<usize>,
+}
+
+/// Manages a set of textures whose mipmap levels are loaded on demand.
+///
+/// See the documentation of the `streaming` module.
+pub struct TextureStreamer<K> {
+    context: Rc<Context>,
+    budget: usize,
+    min_free_video_memory: Option<usize>,
+    entries: HashMap<K, Entry>,
+
+    /// Memory used by the textures that exist, in bytes.
+    used: usize,
+
+    /// Incremented at each call to `update`.
+    frame: u64,
+}
+
+struct Entry {
+    width: u32,
+    height: u32,
+    loader: Box<dyn LevelLoader>,
+
+    /// `None` if the texture has never been requested or has been evicted.
+    texture: Option<Texture2d>,
+
+    /// Most detailed level that has been uploaded. Only relevant if `texture` is `Some`.
+    resident_level: u32,
+
+    /// Most detailed level that has been requested since the texture was created.
+    requested_level: Option<u32>,
+
+    /// Value of the frame counter when the texture was last requested.
+    last_use: u64,
+}
+
+impl Entry {
+    #[inline]
+    fn num_levels(&self) -> u32 {
+        32 - self.width.max(self.height).leading_zeros()
+    }
+
+    /// Returns the dimensions of a mipmap level.
+    #[inline]
+    fn level_dimensions(&self, level: u32) -> (u32, u32) {
+        ((self.width >> level).max(1), (self.height >> level).max(1))
+    }
+
+    /// Returns the amount of memory used by the texture with all its levels.
+    fn memory_size(&self) -> usize {
+        (0 .. self.num_levels()).map(|level| {
+            let (width, height) = self.level_dimensions(level);
+            width as usize * height as usize * 4
+        }).sum()
+    }
+
+    /// Uploads a level of the texture and makes it the base level.
+    fn upload_level(&mut self, level: u32) {
+        let (width, height) = self.level_dimensions(level);
+        let image = self.loader.load_level(level);
+        assert_eq!((image.width, image.height), (width, height),
+                   "The loader returned an image with the wrong dimensions");
+
+        let texture = self.texture.as_ref().unwrap();
+        texture.mipmap(level).unwrap()
+               .write(Rect { left: 0, bottom: 0, width, height }, image);
+
+        // the base level can't be changed on OpenGL ES 2.0, in which case the texture is sampled
+        // with levels that may still be empty
+        let _ = texture.set_base_level(level);
+        self.resident_level = level;
+    }
+}
+
+impl<K> TextureStreamer<K> where K: Hash + Eq + Clone {
+    /// Builds a new streamer that keeps the memory used by its textures under `budget` bytes.
+    pub fn new<F: ?Sized>(facade: &F, budget: usize) -> TextureStreamer<K> where F: Facade {
+        TextureStreamer {
+            context: facade.get_context().clone(),
+            budget,
+            min_free_video_memory: None,
+            entries: HashMap::new(),
+            used: 0,
+            frame: 0,
+        }
+    }
+
+    /// Returns the budget of the streamer, in bytes.
+    #[inline]
+    pub fn get_budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Changes the budget of the streamer. Textures are evicted during the next call to `update`
+    /// if necessary.
+    #[inline]
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+    }
+
+    /// If `Some`, textures are also evicted when the backend reports that less than this amount
+    /// of video memory is free. The default value is `None`.
+    ///
+    /// This has no effect if the backend doesn't report the free video memory.
+    #[inline]
+    pub fn set_min_free_video_memory(&mut self, bytes: Option<usize>) {
+        self.min_free_video_memory = bytes;
+    }
+
+    /// Registers a texture. Nothing is loaded until the texture is requested.
+    ///
+    /// If a texture with the same key already exists, it is replaced.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `width` or `height` is 0.
+    pub fn insert<L>(&mut self, key: K, width: u32, height: u32, loader: L)
+                     where L: LevelLoader + 'static
+    {
+        assert!(width >= 1 && height >= 1);
+
+        self.remove(&key);
+        self.entries.insert(key, Entry {
+            width,
+            height,
+            loader: Box::new(loader),
+            texture: None,
+            resident_level: 0,
+            requested_level: None,
+            last_use: 0,
+        });
+    }
+
+    /// Removes a texture and destroys it.
+    pub fn remove(&mut self, key: &K) {
+        if let Some(entry) = self.entries.remove(key) {
+            if entry.texture.is_some() {
+                self.used -= entry.memory_size();
+            }
+        }
+    }
+
+    /// Indicates that the texture is going to be used, and that its levels up to `level` should
+    /// be loaded. Level 0 is the most detailed level.
+    ///
+    /// The loading is done by `update`. Does nothing if the texture doesn't exist.
+    pub fn request(&mut self, key: &K, level: u32) {
+        let frame = self.frame;
+        if let Some(entry) = self.entries.get_mut(key) {
+            let level = level.min(entry.num_levels() - 1);
+            entry.requested_level = Some(entry.requested_level.map_or(level, |l| l.min(level)));
+            entry.last_use = frame;
+        }
+    }
+
+    /// Returns the texture if at least one of its levels has been uploaded.
+    ///
+    /// The levels that have not been uploaded yet are excluded with `set_base_level`.
+    #[inline]
+    pub fn get(&self, key: &K) -> Option<&Texture2d> {
+        self.entries.get(key).and_then(|entry| entry.texture.as_ref())
+    }
+
+    /// Returns the most detailed level of the texture that has been uploaded, or `None` if the
+    /// texture doesn't exist in video memory.
+    #[inline]
+    pub fn get_resident_level(&self, key: &K) -> Option<u32> {
+        self.entries.get(key).and_then(|entry| entry.texture.as_ref().map(|_| entry.resident_level))
+    }
+
+    /// Returns the memory usage of the streamer.
+    pub fn get_memory_info(&self) -> StreamingMemoryInfo {
+        StreamingMemoryInfo {
+            budget: self.budget,
+            used: self.used,
+            resident_textures: self.entries.values().filter(|e| e.texture.is_some()).count(),
+            free_video_memory: self.context.get_free_video_memory(),
+        }
+    }
+
+    /// Creates and uploads the textures that have been requested, and evicts textures if the
+    /// memory is insufficient.
+    ///
+    /// At most `max_uploads` levels are uploaded, starting with the textures that have been
+    /// requested the most recently. Each texture receives at most one new level per call, so
+    /// that the detail increases progressively. You should call this once per frame.
+    pub fn update(&mut self, max_uploads: u32) -> Result<(), TextureCreationError> {
+        let frame = self.frame;
+        self.frame += 1;
+
+        // the keys of the textures that need an upload, the most recently requested first
+        let mut pending = self.entries.iter()
+            .filter(|&(_, entry)| match (entry.requested_level, &entry.texture) {
+                (Some(_), None) => true,
+                (Some(level), Some(_)) => level < entry.resident_level,
+                (None, _) => false,
+            })
+            .map(|(key, entry)| (key.clone(), entry.last_use))
+            .collect::<Vec<_>>();
+        pending.sort_by_key(|&(_, last_use)| Reverse(last_use));
+
+        let mut uploads = 0;
+        for (key, _) in pending {
+            if uploads >= max_uploads {
+                break;
+            }
+
+            if self.entries[&key].texture.is_none() {
+                let size = self.entries[&key].memory_size();
+                if !self.make_room(size, frame) {
+                    continue;
+                }
+
+                let entry = self.entries.get_mut(&key).unwrap();
+                entry.texture = Some(Texture2d::empty_with_format(&self.context,
+                                                                  UncompressedFloatFormat::U8U8U8U8,
+                                                                  MipmapsOption::EmptyMipmaps,
+                                                                  entry.width, entry.height)?);
+                self.used += size;
+
+                let level = entry.num_levels() - 1;
+                entry.upload_level(level);
+
+            } else {
+                let entry = self.entries.get_mut(&key).unwrap();
+                let level = entry.resident_level - 1;
+                entry.upload_level(level);
+            }
+
+            uploads += 1;
+        }
+
+        self.make_room(0, frame);
+        Ok(())
+    }
+
+    /// Evicts the least recently used textures until `size` more bytes fit in the budget. If
+    /// the free video memory is below the threshold, one more texture is evicted. The textures
+    /// used during `frame` are never evicted.
+    ///
+    /// Returns false if there is not enough room for `size` bytes.
+    fn make_room(&mut self, size: usize, frame: u64) -> bool {
+        while self.used.saturating_add(size) > self.budget {
+            if !self.evict_one(frame) {
+                return false;
+            }
+        }
+
+        // the free memory reported by the backend is not updated immediately, so we only evict
+        // one texture per call
+        let under_pressure = match (self.min_free_video_memory,
+                                    self.context.get_free_video_memory())
+        {
+            (Some(min), Some(free)) => free < min,
+            _ => false,
+        };
+
+        if under_pressure {
+            self.evict_one(frame);
+            return size == 0;
+        }
+
+        true
+    }
+
+    /// Destroys the least recently used texture that hasn't been used during `frame`. Returns
+    /// false if there is no such texture.
+    fn evict_one(&mut self, frame: u64) -> bool {
+        let victim = self.entries.iter()
+            .filter(|&(_, entry)| entry.texture.is_some() && entry.last_use < frame)
+            .min_by_key(|&(_, entry)| entry.last_use)
+            .map(|(key, _)| key.clone());
+
+        let victim = match victim {
+            Some(victim) => victim,
+            None => return false,
+        };
+
+        let entry = self.entries.get_mut(&victim).unwrap();
+        entry.texture = None;
+        entry.requested_level = None;
+        self.used -= entry.memory_size();
+        true
+    }
+}
@@ -0,0 +1,314 @@
+/*!
+Texture views, which reinterpret the storage of another texture.
+
+A view is a texture that shares the memory of an existing texture, with a different format,
+a subset of its mipmap levels or a subset of its layers. Writing to the original texture
+modifies the view and vice versa. No data is copied when a view is created.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+use glium::texture::{MipmapsOption, SrgbFormat, SrgbTexture2d, Texture2dArray};
+use glium::texture::UncompressedFloatFormat;
+
+// sampling `linear` returns the values as they are stored, without sRGB conversion
+let texture = SrgbTexture2d::empty_with_format(&display, SrgbFormat::U8U8U8U8,
+                                               MipmapsOption::NoMipmap, 256, 256).unwrap();
+let linear = texture.linear_view(0 .. 1).unwrap();
+
+// the third layer of an array, that can be attached to a framebuffer like a `Texture2d`
+let array = Texture2dArray::empty_with_format(&display, UncompressedFloatFormat::U8U8U8U8,
+                                              MipmapsOption::NoMipmap, 256, 256, 4).unwrap();
+let layer = array.layer_view(UncompressedFloatFormat::U8U8U8U8, 0 .. 1, 2).unwrap();
+```
+
+The format of a view must have the same number of bits per texel as the format of the
+original texture, and some formats such as `U5U5U5U1` can't be viewed at all. The mipmap
+level `0` of a view is the first level of the range that it was created with.
+
+The storage is freed once the original texture and all its views have been destroyed, which
+means that a view can outlive its texture.
+
+Views require OpenGL 4.3 or `GL_ARB_texture_view`, and textures whose storage is immutable.
+This is the case of all the textures created by glium when `glTexStorage` is available, which
+is always the case if views are supported.
+
+A view of a depth-stencil texture whose shaders read the stencil component can be created with
+`DepthStencilTexture2d::stencil_view`. This makes it possible to sample both the depth and the
+stencil of the same texture in a single draw, for example in a deferred renderer.
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+use crate::gl;
+use crate::CapabilitiesSource;
+use crate::ContextExt;
+use crate::GlObject;
+use crate::TextureExt;
+
+use crate::image_format::{self, TextureFormat, TextureFormatRequest};
+use crate::texture::{Dimensions, MipmapsOption, SrgbFormat, TextureAny, UncompressedFloatFormat};
+use crate::texture::{DepthStencilFormat, DepthStencilTextureMode, DepthStencilTexture2d};
+use crate::texture::{SrgbTexture2d, Texture2d, Texture2dArray};
+use crate::version::{Api, Version};
+
+/// Returns true if texture views are supported by the backend.
+#[inline]
+pub fn is_texture_view_supported<C: ?Sized>(caps: &C) -> bool where C: CapabilitiesSource {
+    caps.get_version() >= &Version(Api::Gl, 4, 3) || caps.get_extensions().gl_arb_texture_view
+}
+
+/// Error that can happen when creating a texture view.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TextureViewError {
+    /// Texture views are not supported by the backend.
+    NotSupported,
+
+    /// The storage of the texture is not immutable.
+    NotImmutable,
+
+    /// The requested format is not supported by the backend.
+    FormatNotSupported,
+
+    /// The requested format is not compatible with the format of the texture.
+    IncompatibleFormat,
+
+    /// The range of mipmap levels is empty or outside of the texture.
+    LevelsOutOfRange,
+
+    /// The range of layers is empty or outside of the texture.
+    LayersOutOfRange,
+}
+
+impl fmt::Display for TextureViewError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::TextureViewError::*;
+        let desc = match *self {
+            NotSupported =>
+                "Texture views are not supported by the backend",
+            NotImmutable =>
+                "The storage of the texture is not immutable",
+            FormatNotSupported =>
+                "The requested format is not supported by the backend",
+            IncompatibleFormat =>
+                "The requested format is not compatible with the format of the texture",
+            LevelsOutOfRange =>
+                "The range of mipmap levels is empty or outside of the texture",
+            LayersOutOfRange =>
+                "The range of layers is empty or outside of the texture",
+        };
+        fmt.write_str(desc)
+    }
+}
+
+impl Error for TextureViewError {}
+
+impl Texture2d {
+    /// Builds a view of a range of mipmap levels of this texture, with another format.
+    pub fn view(&self, format: UncompressedFloatFormat, levels: Range<u32>)
+                -> Result<Texture2d, TextureViewError>
+    {
+        let id = create_view(self, gl::TEXTURE_2D, format.to_texture_format(), &levels, 0 .. 1)?;
+        let (width, height) = level_dimensions(self, levels.start);
+        let ty = Dimensions::Texture2d { width, height };
+        Ok(unsafe { Texture2d::from_id(self.get_context(), format, id, true,
+                                         mipmaps_option(&levels), ty) })
+    }
+
+    /// Builds a view of a range of mipmap levels of this texture, whose values are converted
+    /// from the sRGB color space when the view is sampled.
+    pub fn srgb_view(&self, format: SrgbFormat, levels: Range<u32>)
+                     -> Result<SrgbTexture2d, TextureViewError>
+    {
+        let id = create_view(self, gl::TEXTURE_2D, format.to_texture_format(), &levels, 0 .. 1)?;
+        let (width, height) = level_dimensions(self, levels.start);
+        let ty = Dimensions::Texture2d { width, height };
+        Ok(unsafe { SrgbTexture2d::from_id(self.get_context(), format, id, true,
+                                             mipmaps_option(&levels), ty) })
+    }
+}
+
+impl SrgbTexture2d {
+    /// Builds a view of a range of mipmap levels of this texture, whose values are not
+    /// converted from the sRGB color space when the view is sampled.
+    pub fn linear_view(&self, levels: Range<u32>) -> Result<Texture2d, TextureViewError> {
+        let format = match self.get_requested_format() {
+            TextureFormatRequest::Specific(TextureFormat::Srgb(SrgbFormat::U8U8U8)) =>
+                UncompressedFloatFormat::U8U8U8,
+            _ => UncompressedFloatFormat::U8U8U8U8,
+        };
+
+        let id = create_view(self, gl::TEXTURE_2D, format.to_texture_format(), &levels, 0 .. 1)?;
+        let (width, height) = level_dimensions(self, levels.start);
+        let ty = Dimensions::Texture2d { width, height };
+        Ok(unsafe { Texture2d::from_id(self.get_context(), format, id, true,
+                                         mipmaps_option(&levels), ty) })
+    }
+}
+
+impl Texture2dArray {
+    /// Builds a view of a range of mipmap levels and of a range of layers of this texture,
+    /// with another format.
+    pub fn view(&self, format: UncompressedFloatFormat, levels: Range<u32>, layers: Range<u32>)
+                -> Result<Texture2dArray, TextureViewError>
+    {
+        let id = create_view(self, gl::TEXTURE_2D_ARRAY, format.to_texture_format(), &levels,
+                             layers.clone())?;
+        let (width, height) = level_dimensions(self, levels.start);
+        let ty = Dimensions::Texture2dArray { width, height, array_size: layers.end - layers.start };
+        Ok(unsafe { Texture2dArray::from_id(self.get_context(), format, id, true,
+                                              mipmaps_option(&levels), ty) })
+    }
+
+    /// Builds a view of a range of mipmap levels of one layer of this texture, with another
+    /// format. The view is a regular 2D texture.
+    pub fn layer_view(&self, format: UncompressedFloatFormat, levels: Range<u32>, layer: u32)
+                      -> Result<Texture2d, TextureViewError>
+    {
+        let id = create_view(self, gl::TEXTURE_2D, format.to_texture_format(), &levels,
+                             layer .. layer + 1)?;
+        let (width, height) = level_dimensions(self, levels.start);
+        let ty = Dimensions::Texture2d { width, height };
+        Ok(unsafe { Texture2d::from_id(self.get_context(), format, id, true,
+                                         mipmaps_option(&levels), ty) })
+    }
+}
+
+impl DepthStencilTexture2d {
+    /// Builds a view of a range of mipmap levels of this texture, whose shaders read the
+    /// stencil component with a `usampler2D` uniform. The texture itself keeps returning the
+    /// depth component.
+    ///
+    /// Also requires OpenGL 4.3, OpenGL ES 3.1 or `GL_ARB_stencil_texturing`.
+    pub fn stencil_view(&self, levels: Range<u32>)
+                        -> Result<DepthStencilTexture2d, TextureViewError>
+    {
+        if !crate::texture::is_stencil_texturing_supported(&**self.get_context()) {
+            return Err(TextureViewError::NotSupported);
+        }
+
+        // textures with immutable storage that were created without a specific format
+        // have the `DEPTH24_STENCIL8` format
+        let format = match self.get_requested_format() {
+            TextureFormatRequest::Specific(TextureFormat::DepthStencilFormat(format)) => format,
+            _ => DepthStencilFormat::I24I8,
+        };
+
+        let id = create_view(self, gl::TEXTURE_2D, format.to_texture_format(), &levels, 0 .. 1)?;
+        let (width, height) = level_dimensions(self, levels.start);
+        let ty = Dimensions::Texture2d { width, height };
+        let view = unsafe {
+            DepthStencilTexture2d::from_id(self.get_context(), format, id, true,
+                                           mipmaps_option(&levels), ty)
+        };
+
+        view.set_depth_stencil_mode(DepthStencilTextureMode::Stencil)
+            .map_err(|_| TextureViewError::NotSupported)?;
+        Ok(view)
+    }
+}
+
+/// Returns the width and height of a mipmap level of a texture.
+fn level_dimensions(texture: &TextureAny, level: u32) -> (u32, u32) {
+    let mipmap = texture.mipmap(level).unwrap();
+    (mipmap.get_width(), mipmap.get_height().unwrap_or(1))
+}
+
+/// Returns the option that gives the number of levels of a view.
+#[inline]
+fn mipmaps_option(levels: &Range<u32>) -> MipmapsOption {
+    MipmapsOption::EmptyMipmapsMax(levels.end - levels.start - 1)
+}
+
+/// Checks that a view can be created and creates it. Returns the id of the new texture.
+fn create_view(texture: &TextureAny, target: gl::types::GLenum, format: TextureFormat,
+               levels: &Range<u32>, layers: Range<u32>)
+               -> Result<gl::types::GLuint, TextureViewError>
+{
+    let context = texture.get_context();
+
+    if !is_texture_view_supported(&**context) {
+        return Err(TextureViewError::NotSupported);
+    }
+
+    if levels.start >= levels.end || levels.end > texture.get_mipmap_levels() {
+        return Err(TextureViewError::LevelsOutOfRange);
+    }
+
+    if layers.start >= layers.end || layers.end > texture.get_array_size().unwrap_or(1) {
+        return Err(TextureViewError::LayersOutOfRange);
+    }
+
+    let internal_format = image_format::format_request_to_glenum(context,
+                                                    TextureFormatRequest::Specific(format),
+                                                    image_format::RequestType::TexStorage)
+                                        .map_err(|_| TextureViewError::FormatNotSupported)?;
+
+    let compatible = match format {
+        // depth-stencil textures can only be viewed with their own format
+        TextureFormat::DepthStencilFormat(_) => match texture.get_requested_format() {
+            TextureFormatRequest::Specific(texture_format) => texture_format == format,
+            TextureFormatRequest::AnyDepthStencil => true,
+            _ => false,
+        },
+        _ => match (get_texture_view_class(texture), get_view_class(format)) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        },
+    };
+
+    if !compatible {
+        return Err(TextureViewError::IncompatibleFormat);
+    }
+
+    let mut ctxt = context.make_current();
+
+    unsafe {
+        let bind_point = texture.bind_to_current(&mut ctxt);
+        let mut immutable = 0;
+        ctxt.gl.GetTexParameteriv(bind_point, gl::TEXTURE_IMMUTABLE_FORMAT, &mut immutable);
+        if immutable == 0 {
+            return Err(TextureViewError::NotImmutable);
+        }
+
+        let mut id = 0;
+        ctxt.gl.GenTextures(1, &mut id);
+        ctxt.gl.TextureView(id, target, texture.get_id(), internal_format, levels.start,
+                            levels.end - levels.start, layers.start, layers.end - layers.start);
+        Ok(id)
+    }
+}
+
+/// Returns the view class of the format of a texture.
+fn get_texture_view_class(texture: &TextureAny) -> Option<u32> {
+    match texture.get_requested_format() {
+        TextureFormatRequest::Specific(format) => get_view_class(format),
+        _ => texture.get_internal_format().ok().map(|format| format.get_total_bits() as u32),
+    }
+}
+
+/// Returns the view class of a format, which is its number of bits per texel. Formats can only
+/// be viewed with formats of the same class. Returns `None` if the format can't be viewed.
+fn get_view_class(format: TextureFormat) -> Option<u32> {
+    use crate::texture::UncompressedFloatFormat::*;
+
+    match format {
+        TextureFormat::UncompressedFloat(format) => match format {
+            U8 | I8 => Some(8),
+            U16 | I16 | U8U8 | I8I8 | F16 => Some(16),
+            U8U8U8 | I8I8I8 => Some(24),
+            U16U16 | I16I16 | U8U8U8U8 | I8I8I8I8 | U10U10U10U2 | F16F16 | F32 |
+            F11F11F10 | F9F9F9 => Some(32),
+            U16U16U16 | I16I16I16 | F16F16F16 => Some(48),
+            U16U16U16U16 | I16I16I16I16 | F16F16F16F16 | F32F32 => Some(64),
+            F32F32F32 => Some(96),
+            F32F32F32F32 => Some(128),
+            U3U3U2 | U4U4U4 | U5U5U5 | U10U10U10 | U12U12U12 | U2U2U2U2 | U4U4U4U4 |
+            U5U5U5U1 | U12U12U12U12 => None,
+        },
+        TextureFormat::Srgb(SrgbFormat::U8U8U8) => Some(24),
+        TextureFormat::Srgb(SrgbFormat::U8U8U8U8) => Some(32),
+        _ => None,
+    }
+}
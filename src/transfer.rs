@@ -0,0 +1,191 @@
+/*!
+Moving objects between contexts that live on different threads.
+
+glium objects belong to the context that created them and can't be sent to another thread. When
+the OpenGL contexts of two threads share their objects, for example a `Display` and the resource
+context returned by `Display::create_resource_context`, an object can be detached from the
+context of one thread and attached to the context of the other thread with a `TransferTicket`.
+
+This lets applications upload textures and buffers in the background, without going through the
+render thread.
+
+```no_run
+# use glium::glutin;
+# let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+# let event_loop = glutin::event_loop::EventLoop::new();
+# let image = glium::texture::RawImage2d::from_raw_rgba(vec![0u8; 4], (1, 1));
+use glium::transfer::TransferTicket;
+
+let resource_context = display.create_resource_context(&event_loop).unwrap();
+let (sender, receiver) = std::sync::mpsc::channel();
+
+std::thread::spawn(move || {
+    let resources = resource_context.make_current().unwrap();
+    let texture = glium::Texture2d::new(&resources, image).unwrap();
+    sender.send(TransferTicket::new(&resources, texture)).unwrap();
+});
+
+// later, in the render loop
+let ticket = receiver.recv().unwrap();
+if ticket.is_ready(&display) {
+    let texture = ticket.wait(&display);
+}
+```
+
+*/
+use std::mem;
+
+use crate::backend::Facade;
+use crate::context::ShareGroupId;
+use crate::sync::{self, LinearSyncFence};
+use crate::ContextExt;
+
+pub use crate::buffer::DetachedBuffer;
+pub use crate::texture::DetachedTexture;
+pub use crate::program::DetachedProgram;
+
+/// An object that can be detached from its context and attached to another context whose
+/// objects are shared with it.
+///
+/// Implemented on buffers, vertex buffers, index buffers, textures and programs.
+pub trait Transferable: Sized {
+    /// The detached object, which can be sent to other threads.
+    type Detached: Send;
+
+    /// Detaches the object from its context without destroying it.
+    ///
+    /// If the detached object is never attached again, the OpenGL object is leaked.
+    fn detach(self) -> Self::Detached;
+
+    /// Attaches a detached object to the context of `facade`.
+    ///
+    /// # Unsafety
+    ///
+    /// The context must share its objects with the context the object has been detached from,
+    /// and the commands that modified the object in this other context must be finished.
+    unsafe fn attach<F: ?Sized>(facade: &F, detached: Self::Detached) -> Self where F: Facade;
+}
+
+impl<T> Transferable for Vec<T> where T: Transferable {
+    type Detached = Vec<T::Detached>;
+
+    #[inline]
+    fn detach(self) -> Vec<T::Detached> {
+        self.into_iter().map(Transferable::detach).collect()
+    }
+
+    #[inline]
+    unsafe fn attach<F: ?Sized>(facade: &F, detached: Vec<T::Detached>) -> Vec<T>
+                                where F: Facade
+    {
+        detached.into_iter().map(|d| T::attach(facade, d)).collect()
+    }
+}
+
+impl<A, B> Transferable for (A, B) where A: Transferable, B: Transferable {
+    type Detached = (A::Detached, B::Detached);
+
+    #[inline]
+    fn detach(self) -> (A::Detached, B::Detached) {
+        (self.0.detach(), self.1.detach())
+    }
+
+    #[inline]
+    unsafe fn attach<F: ?Sized>(facade: &F, detached: (A::Detached, B::Detached)) -> (A, B)
+                                where F: Facade
+    {
+        (A::attach(facade, detached.0), B::attach(facade, detached.1))
+    }
+}
+
+/// Objects detached from a context, along with a fence that is signaled once the commands that
+/// built them are finished.
+///
+/// The ticket can be sent to another thread, and the objects can be used there once the ticket
+/// is ready. If the ticket is dropped without calling `wait`, the objects are leaked.
+#[must_use]
+pub struct TransferTicket<T> where T: Transferable {
+    share_group: ShareGroupId,
+    fence: Option<LinearSyncFence>,
+    objects: Option<T::Detached>,
+}
+
+impl<T> TransferTicket<T> where T: Transferable {
+    /// Detaches objects from the context of `facade`, inserts a fence after the commands that
+    /// built them and flushes the context.
+    ///
+    /// If fences aren't supported, waits for the commands to be finished instead.
+    pub fn new<F: ?Sized>(facade: &F, objects: T) -> TransferTicket<T> where F: Facade {
+        let objects = objects.detach();
+
+        let mut ctxt = facade.get_context().make_current();
+        let fence = match unsafe { sync::new_linear_sync_fence(&mut ctxt) } {
+            Ok(fence) => {
+                // the other contexts can only wait for a fence that has been flushed
+                unsafe { ctxt.gl.Flush(); }
+                Some(fence)
+            },
+            Err(_) => {
+                unsafe { ctxt.gl.Finish(); }
+                None
+            },
+        };
+
+        TransferTicket {
+            share_group: facade.get_context().get_share_group_id(),
+            fence,
+            objects: Some(objects),
+        }
+    }
+
+    /// Returns true if the objects can be used without waiting. Doesn't block.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if the context of `facade` doesn't share its objects with the context the objects
+    /// have been detached from.
+    pub fn is_ready<F: ?Sized>(&self, facade: &F) -> bool where F: Facade {
+        self.check_share_group(facade);
+
+        match self.fence {
+            Some(ref fence) => {
+                let mut ctxt = facade.get_context().make_current();
+                unsafe { sync::is_linear_sync_fence_signaled(&mut ctxt, fence) }
+            },
+            None => true,
+        }
+    }
+
+    /// Blocks until the commands that built the objects are finished, then attaches the objects
+    /// to the context of `facade`.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if the context of `facade` doesn't share its objects with the context the objects
+    /// have been detached from.
+    pub fn wait<F: ?Sized>(mut self, facade: &F) -> T where F: Facade {
+        self.check_share_group(facade);
+
+        if let Some(fence) = self.fence.take() {
+            let mut ctxt = facade.get_context().make_current();
+            unsafe { sync::wait_linear_sync_fence_and_drop(fence, &mut ctxt) };
+        }
+
+        unsafe { T::attach(facade, self.objects.take().unwrap()) }
+    }
+
+    fn check_share_group<F: ?Sized>(&self, facade: &F) where F: Facade {
+        assert_eq!(facade.get_context().get_share_group_id(), self.share_group,
+                   "The context doesn't share its objects with the context of the ticket");
+    }
+}
+
+impl<T> Drop for TransferTicket<T> where T: Transferable {
+    #[inline]
+    fn drop(&mut self) {
+        // the fence can't be destroyed without a context
+        if let Some(fence) = self.fence.take() {
+            mem::forget(fence);
+        }
+    }
+}
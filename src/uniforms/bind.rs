@@ -21,8 +21,14 @@ use crate::TextureExt;
 use crate::uniforms::Uniforms;
 use crate::uniforms::UniformValue;
 use crate::uniforms::SamplerBehavior;
+use crate::uniforms::MinifySamplerFilter;
+use crate::uniforms::ImageUnitAccess;
+use crate::uniforms::ImageUnitFormat;
+
+use crate::texture::InternalFormat;
 
 use crate::context::CommandContext;
+use crate::program::ProgramPipeline;
 use crate::buffer::Inserter;
 
 use crate::utils::bitsfield::Bitsfield;
@@ -33,120 +39,224 @@ use crate::version::Version;
 use crate::version::Api;
 
 impl<U> UniformsExt for U where U: Uniforms {
-    fn bind_uniforms<'a, P>(&'a self, mut ctxt: &mut CommandContext<'_>, program: &P,
+    fn bind_uniforms<'a, P>(&'a self, ctxt: &mut CommandContext<'_>, program: &P,
                             fences: &mut Vec<Inserter<'a>>)
                             -> Result<(), DrawError>
                             where P: ProgramExt
     {
-        let mut texture_bind_points = Bitsfield::new();
-        let mut uniform_buffer_bind_points = Bitsfield::new();
-        let mut shared_storage_buffer_bind_points = Bitsfield::new();
+        let mut bind_points = BindPoints::new();
+        bind_program_uniforms(self, ctxt, program, fences, &mut bind_points, true)
+    }
+
+    fn bind_uniforms_for_pipeline<'a>(&'a self, ctxt: &mut CommandContext<'_>,
+                                      pipeline: &ProgramPipeline<'_>,
+                                      fences: &mut Vec<Inserter<'a>>)
+                                      -> Result<(), DrawError>
+    {
+        let programs = pipeline.get_programs();
 
-        // Subroutine uniforms must be bound all at once, so we collect them first and process them at the end.
-        // The vec contains the uniform we want to set and the value we want to set it to.
-        let mut subroutine_bindings: HashMap<program::ShaderStage, Vec<(&program::SubroutineUniform, &str)>, _>
-            = HashMap::with_hasher(Default::default());
+        // the bind points are shared between the stages, since they are all used by the
+        // same draw command
+        let mut bind_points = BindPoints::new();
 
-        let mut visiting_result = Ok(());
+        for program in programs.iter() {
+            // `glUniform` modifies the current program
+            program.use_program(ctxt);
+            bind_program_uniforms(self, ctxt, *program, fences, &mut bind_points, false)?;
+        }
+
+        // a shader storage block must be used by at least one of the stages
+        let mut result = Ok(());
         self.visit_values(|name, value| {
-            if visiting_result.is_err() { return; }
+            if let UniformValue::ShaderStorageBlock(..) = value {
+                if result.is_ok() &&
+                   !programs.iter().any(|p| p.get_shader_storage_blocks().contains_key(name))
+                {
+                    result = Err(DrawError::ShaderStorageBlockNotFound {
+                        name: name.to_owned(),
+                    });
+                }
+            }
+        });
+        result?;
+
+        // binding the pipeline resets the subroutine uniforms, so they are set afterwards on
+        // the program of each stage
+        pipeline.bind(ctxt);
+        for program in programs.iter() {
+            bind_subroutine_uniforms(self, ctxt, *program)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The bind points that have been assigned so far during a draw command.
+struct BindPoints {
+    textures: Bitsfield,
+    image_units: Bitsfield,
+    uniform_buffers: Bitsfield,
+    shared_storage_buffers: Bitsfield,
+}
 
-            if let Some(uniform) = program.get_uniform(name) {
-                // TODO: remove the size member
-                debug_assert!(uniform.size.is_none());
+impl BindPoints {
+    #[inline]
+    fn new() -> BindPoints {
+        BindPoints {
+            textures: Bitsfield::new(),
+            image_units: Bitsfield::new(),
+            uniform_buffers: Bitsfield::new(),
+            shared_storage_buffers: Bitsfield::new(),
+        }
+    }
+}
 
-                if !value.is_usable_with(&uniform.ty) {
-                    visiting_result = Err(DrawError::UniformTypeMismatch {
+/// Binds the uniforms to a program.
+///
+/// If `whole_program` is false, the program is one of the stages of a pipeline. Shader
+/// storage blocks that it doesn't use are then ignored instead of producing an error, and
+/// subroutine uniforms are not set, since they must be set after the pipeline is bound.
+fn bind_program_uniforms<'a, U, P>(uniforms: &'a U, mut ctxt: &mut CommandContext<'_>,
+                                   program: &P, fences: &mut Vec<Inserter<'a>>,
+                                   bind_points: &mut BindPoints, whole_program: bool)
+                                   -> Result<(), DrawError>
+                                   where U: Uniforms, P: ProgramExt
+{
+    let texture_bind_points = &mut bind_points.textures;
+    let image_unit_bind_points = &mut bind_points.image_units;
+    let uniform_buffer_bind_points = &mut bind_points.uniform_buffers;
+    let shared_storage_buffer_bind_points = &mut bind_points.shared_storage_buffers;
+
+    let mut visiting_result = Ok(());
+    uniforms.visit_values(|name, value| {
+        if visiting_result.is_err() { return; }
+
+        if let UniformValue::ShaderStorageBlock(..) = value {
+            let block = match program.get_shader_storage_blocks().get(name) {
+                Some(block) => block,
+                None if !whole_program => return,
+                None => {
+                    visiting_result = Err(DrawError::ShaderStorageBlockNotFound {
                         name: name.to_owned(),
-                        expected: uniform.ty,
                     });
                     return;
                 }
+            };
 
-                match bind_uniform(&mut ctxt, &value, program, uniform.location,
-                                   &mut texture_bind_points, name)
-                {
-                    Ok(_) => (),
-                    Err(e) => {
-                        visiting_result = Err(e);
-                        
-                    }
-                };
-
-            } else if let Some(block) = program.get_uniform_blocks().get(name) {
-                let fence = match bind_uniform_block(&mut ctxt, &value, block,
-                                                     program, &mut uniform_buffer_bind_points, name)
-                {
-                    Ok(f) => f,
-                    Err(e) => {
-                        visiting_result = Err(e);
-                        return;
-                    }
-                };
-
-                if let Some(fence) = fence {
-                    fences.push(fence);
-                }
+            match bind_shared_storage_block(&mut ctxt, &value, block, program,
+                                            shared_storage_buffer_bind_points, name)
+            {
+                Ok(Some(fence)) => fences.push(fence),
+                Ok(None) => (),
+                Err(e) => visiting_result = Err(e),
+            }
 
-            } else if let Some(block) = program.get_shader_storage_blocks().get(name) {
-                let fence = match bind_shared_storage_block(&mut ctxt, &value, block, program,
-                                                            &mut shared_storage_buffer_bind_points,
-                                                            name)
-                {
-                    Ok(f) => f,
-                    Err(e) => {
-                        visiting_result = Err(e);
-                        return;
-                    }
-                };
-
-                if let Some(fence) = fence {
-                    fences.push(fence);
-                }
-            } else if let Some(block) = program.get_atomic_counters().get(name) {
-                let fence = match bind_atomic_counter(&mut ctxt, &value, block, program,
-                                                      name)
-                {
-                    Ok(f) => f,
-                    Err(e) => {
-                        visiting_result = Err(e);
-                        return;
-                    }
-                };
-
-                if let Some(fence) = fence {
-                    fences.push(fence);
+            return;
+        }
+
+        if let Some(uniform) = program.get_uniform(name) {
+            // TODO: remove the size member
+            debug_assert!(uniform.size.is_none());
+
+            if !value.is_usable_with(&uniform.ty) {
+                visiting_result = Err(DrawError::UniformTypeMismatch {
+                    name: name.to_owned(),
+                    expected: uniform.ty,
+                });
+                return;
+            }
+
+            match bind_uniform(&mut ctxt, &value, program, uniform,
+                               texture_bind_points, image_unit_bind_points, name)
+            {
+                Ok(_) => (),
+                Err(e) => {
+                    visiting_result = Err(e);
+                    
                 }
-            } else if let UniformValue::Subroutine(stage, sr_name) = value {
-                if let Some(subroutine_uniform) = program.get_subroutine_data().subroutine_uniforms.get(&(name.into(), stage)) {
-                    subroutine_bindings.entry(stage).or_insert(Vec::new());
-                    let vec = subroutine_bindings.get_mut(&stage).unwrap();
-                    vec.push((subroutine_uniform, sr_name));
+            };
+
+        } else if let Some(block) = program.get_uniform_blocks().get(name) {
+            let fence = match bind_uniform_block(&mut ctxt, &value, block,
+                                                 program, uniform_buffer_bind_points, name)
+            {
+                Ok(f) => f,
+                Err(e) => {
+                    visiting_result = Err(e);
+                    return;
                 }
+            };
+
+            if let Some(fence) = fence {
+                fences.push(fence);
             }
-        });
 
-        // Process all subroutine uniforms in one batch.
-        if !subroutine_bindings.is_empty() {
-            match bind_subroutine_uniforms(&mut ctxt, program, &subroutine_bindings) {
-                Ok(_) => (),
+        } else if let Some(block) = program.get_shader_storage_blocks().get(name) {
+            let fence = match bind_shared_storage_block(&mut ctxt, &value, block, program,
+                                                        shared_storage_buffer_bind_points,
+                                                        name)
+            {
+                Ok(f) => f,
                 Err(e) => {
                     visiting_result = Err(e);
+                    return;
+                }
+            };
+
+            if let Some(fence) = fence {
+                fences.push(fence);
+            }
+        } else if let Some(block) = program.get_atomic_counters().get(name) {
+            let fence = match bind_atomic_counter(&mut ctxt, &value, block, program,
+                                                  name)
+            {
+                Ok(f) => f,
+                Err(e) => {
+                    visiting_result = Err(e);
+                    return;
                 }
+            };
+
+            if let Some(fence) = fence {
+                fences.push(fence);
             }
         }
+    });
+    visiting_result?;
 
-        visiting_result
+    if whole_program {
+        bind_subroutine_uniforms(uniforms, ctxt, program)?;
     }
+
+    Ok(())
 }
 
-fn bind_subroutine_uniforms<P>(ctxt: &mut context::CommandContext<'_>, program: &P,
-                            subroutine_bindings: &HashMap<program::ShaderStage, Vec<(&program::SubroutineUniform, &str)>, BuildHasherDefault<FnvHasher>>)
-                            -> Result<(), DrawError>
-                            where P: ProgramExt
+/// Sets the subroutine uniforms of a program, which must be current.
+fn bind_subroutine_uniforms<U, P>(uniforms: &U, ctxt: &mut context::CommandContext<'_>,
+                                  program: &P) -> Result<(), DrawError>
+                                  where U: Uniforms, P: ProgramExt
 {
     let subroutine_data = program.get_subroutine_data();
-    for (stage, bindings) in subroutine_bindings {
+    if subroutine_data.subroutine_uniforms.is_empty() {
+        return Ok(());
+    }
+
+    // Subroutine uniforms must be bound all at once, so we collect them first and process them at the end.
+    // The vec contains the uniform we want to set and the value we want to set it to.
+    let mut subroutine_bindings: HashMap<program::ShaderStage, Vec<(&program::SubroutineUniform, &str)>,
+                                         BuildHasherDefault<FnvHasher>>
+        = HashMap::with_hasher(Default::default());
+
+    uniforms.visit_values(|name, value| {
+        if let UniformValue::Subroutine(stage, sr_name) = value {
+            if let Some(subroutine_uniform) = subroutine_data.subroutine_uniforms.get(&(name.into(), stage)) {
+                subroutine_bindings.entry(stage).or_default().push((subroutine_uniform, sr_name));
+            }
+        }
+    });
+
+    // Process all subroutine uniforms in one batch.
+    for (stage, bindings) in &subroutine_bindings {
         // Validate that all subroutine uniforms of this stage are set, otherwise OpenGL will throw an error.
         let set_cnt = bindings.len();
         let expected_cnt = subroutine_data.subroutine_uniforms.iter()
@@ -202,7 +312,17 @@ fn bind_uniform_block<'a, P>(ctxt: &mut context::CommandContext<'_>, value: &Uni
             let bind_point = buffer_bind_points.get_unused().expect("Not enough buffer units");
             buffer_bind_points.set_used(bind_point);
 
-            assert!(buffer.get_offset_bytes() == 0);     // TODO: not implemented
+            if buffer.get_offset_bytes() != 0 {
+                let alignment = ctxt.capabilities.uniform_buffer_offset_alignment
+                                                 .unwrap_or(1).max(1);
+                if buffer.get_offset_bytes() % alignment != 0 {
+                    return Err(DrawError::BufferOffsetMisaligned {
+                        name: name.to_owned(),
+                        alignment,
+                    });
+                }
+            }
+
             let fence = buffer.add_fence();
             let block_id = block.id as gl::types::GLuint;
 
@@ -224,7 +344,8 @@ fn bind_shared_storage_block<'a, P>(ctxt: &mut context::CommandContext<'_>, valu
                                     where P: ProgramExt
 {
     match value {
-        &UniformValue::Block(buffer, ref layout) => {
+        &UniformValue::Block(buffer, ref layout) |
+        &UniformValue::ShaderStorageBlock(buffer, ref layout) => {
             match layout(block) {
                 Ok(_) => (),
                 Err(e) => {
@@ -235,10 +356,20 @@ fn bind_shared_storage_block<'a, P>(ctxt: &mut context::CommandContext<'_>, valu
                 }
             }
 
+            if buffer.get_offset_bytes() != 0 {
+                let alignment = ctxt.capabilities.shader_storage_buffer_offset_alignment
+                                                 .unwrap_or(1).max(1);
+                if buffer.get_offset_bytes() % alignment != 0 {
+                    return Err(DrawError::BufferOffsetMisaligned {
+                        name: name.to_owned(),
+                        alignment,
+                    });
+                }
+            }
+
             let bind_point = buffer_bind_points.get_unused().expect("Not enough buffer units");
             buffer_bind_points.set_used(bind_point);
 
-            assert!(buffer.get_offset_bytes() == 0);     // TODO: not implemented
             let fence = buffer.add_fence();
             let block_id = block.id as gl::types::GLuint;
 
@@ -271,7 +402,23 @@ fn bind_atomic_counter<'a, P>(ctxt: &mut context::CommandContext<'_>, value: &Un
                 }
             }
 
-            assert!(buffer.get_offset_bytes() == 0);     // TODO: not implemented
+            if let program::BlockLayout::BasicType { offset_in_buffer, .. } = block.layout {
+                if buffer.get_size() < offset_in_buffer + block.size {
+                    return Err(DrawError::BufferTooSmall {
+                        name: name.to_owned(),
+                        required: offset_in_buffer + block.size,
+                        obtained: buffer.get_size(),
+                    });
+                }
+            }
+
+            if buffer.get_offset_bytes() % 4 != 0 {
+                return Err(DrawError::BufferOffsetMisaligned {
+                    name: name.to_owned(),
+                    alignment: 4,
+                });
+            }
+
             let fence = buffer.add_fence();
 
             buffer.prepare_and_bind_for_atomic_counter(ctxt, block.initial_binding as gl::types::GLuint);
@@ -285,14 +432,16 @@ fn bind_atomic_counter<'a, P>(ctxt: &mut context::CommandContext<'_>, value: &Un
 }
 
 fn bind_uniform<P>(ctxt: &mut context::CommandContext<'_>,
-                   value: &UniformValue<'_>, program: &P, location: gl::types::GLint,
-                   texture_bind_points: &mut Bitsfield, name: &str)
+                   value: &UniformValue<'_>, program: &P, uniform: &program::Uniform,
+                   texture_bind_points: &mut Bitsfield, image_unit_bind_points: &mut Bitsfield,
+                   name: &str)
                    -> Result<(), DrawError> where P: ProgramExt
 {
+    let location = uniform.location;
     assert!(location >= 0);
 
     match *value {
-        UniformValue::Block(_, _) => {
+        UniformValue::Block(_, _) | UniformValue::ShaderStorageBlock(_, _) => {
             Err(DrawError::UniformBufferToValue {
                 name: name.to_owned(),
             })
@@ -443,194 +592,311 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext<'_>,
             Ok(())
         },
         UniformValue::Texture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::CompressedTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::SrgbTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::CompressedSrgbTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::IntegralTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::UnsignedTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::DepthTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
+        },
+        UniformValue::DepthStencilTexture1d(texture, sampler) => {
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::Texture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::CompressedTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::SrgbTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::CompressedSrgbTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::IntegralTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::UnsignedTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::DepthTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
+        },
+        UniformValue::DepthStencilTexture2d(texture, sampler) => {
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::Texture2dMultisample(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::SrgbTexture2dMultisample(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::IntegralTexture2dMultisample(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::UnsignedTexture2dMultisample(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::DepthTexture2dMultisample(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
+        },
+        UniformValue::DepthStencilTexture2dMultisample(texture, sampler) => {
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::Texture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::CompressedTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::SrgbTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::CompressedSrgbTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::IntegralTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::UnsignedTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::DepthTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
+        },
+        UniformValue::DepthStencilTexture3d(texture, sampler) => {
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::Texture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::CompressedTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::SrgbTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::CompressedSrgbTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::IntegralTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::UnsignedTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::DepthTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
+        },
+        UniformValue::DepthStencilTexture1dArray(texture, sampler) => {
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::Texture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::CompressedTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::SrgbTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::CompressedSrgbTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::IntegralTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::UnsignedTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::DepthTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
+        },
+        UniformValue::DepthStencilTexture2dArray(texture, sampler) => {
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::Texture2dMultisampleArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::SrgbTexture2dMultisampleArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::IntegralTexture2dMultisampleArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::UnsignedTexture2dMultisampleArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::DepthTexture2dMultisampleArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
+        },
+        UniformValue::DepthStencilTexture2dMultisampleArray(texture, sampler) => {
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::Cubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::CompressedCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::SrgbCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::CompressedSrgbCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::IntegralCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::UnsignedCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::DepthCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
+        },
+        UniformValue::DepthStencilCubemap(texture, sampler) => {
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::CubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::CompressedCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::SrgbCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::CompressedSrgbCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::IntegralCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::UnsignedCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::DepthCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
+        },
+        UniformValue::DepthStencilCubemapArray(texture, sampler) => {
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points, name)
         },
         UniformValue::BufferTexture(texture) => {
-            bind_texture_uniform(ctxt, &texture, None, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &texture, None, location, program, texture_bind_points, name)
+        },
+        UniformValue::BufferTextureImage(texture, access) => {
+            texture.get_buffer().prepare_for_image_unit(ctxt, access != ImageUnitAccess::Read);
+            bind_image_uniform(ctxt, &texture, texture.get_internal_format(),
+                               texture.get_internal_format_glenum(), access, 0, None, uniform,
+                               program, image_unit_bind_points, name)
         },
+        UniformValue::BindlessTexture(handle) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::TextureHandle(handle.get_raw()));
+            Ok(())
+        },
+        UniformValue::TextureImage(image) => {
+            bind_image_uniform(ctxt, image.get_texture(), image.get_texture_format(),
+                               image.get_format().to_glenum(), image.get_access(),
+                               image.get_level(), image.get_layer(), uniform, program,
+                               image_unit_bind_points, name)
+        },
+    }
+}
+
+/// Checks that a texture whose internal format is `format` can be accessed through the image
+/// uniform, and returns the format declared by the layout qualifier of the image in the shader,
+/// which has priority over the format of the texture.
+fn check_image_format(uniform: &program::Uniform, format: InternalFormat, name: &str)
+                      -> Result<Option<ImageUnitFormat>, DrawError>
+{
+    match uniform.image_format {
+        Some(expected) if !expected.is_compatible_with(&format) => {
+            Err(DrawError::ImageFormatMismatch {
+                name: name.to_owned(),
+                expected,
+                obtained: format,
+            })
+        },
+        declared => Ok(declared),
     }
 }
 
+/// Binds a level of a texture to an image unit. `layer` is the layer to bind, or `None` to
+/// bind all the layers of the level.
+fn bind_image_uniform<P, T: ?Sized>(ctxt: &mut context::CommandContext<'_>, texture: &T,
+                                    format: InternalFormat, default_format: gl::types::GLenum,
+                                    access: ImageUnitAccess, level: u32, layer: Option<u32>,
+                                    uniform: &program::Uniform, program: &P,
+                                    image_unit_bind_points: &mut Bitsfield, name: &str)
+                            -> Result<(), DrawError> where P: ProgramExt, T: TextureExt
+{
+    let format = match check_image_format(uniform, format, name)? {
+        Some(format) => format.to_glenum(),
+        None => default_format,
+    };
+
+    let image_unit = match image_unit_bind_points.get_unused() {
+        Some(unit) if (unit as gl::types::GLint) < ctxt.capabilities.max_image_units.unwrap_or(0) => unit,
+        _ => return Err(DrawError::NotEnoughImageUnits { name: name.to_owned() }),
+    };
+    image_unit_bind_points.set_used(image_unit);
+
+    // updating the program to use the right unit
+    program.set_uniform(ctxt, uniform.location,
+                        &RawUniformValue::SignedInt(image_unit as gl::types::GLint));
+
+    let (layered, layer) = match layer {
+        Some(layer) => (gl::FALSE, layer),
+        None => (gl::TRUE, 0),
+    };
+
+    unsafe {
+        ctxt.gl.BindImageTexture(image_unit as gl::types::GLuint, texture.get_texture_id(),
+                                 level as gl::types::GLint, layered,
+                                 layer as gl::types::GLint, access.to_glenum(), format);
+    }
+
+    Ok(())
+}
+
 fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext<'_>,
                               texture: &T, sampler: Option<SamplerBehavior>,
                               location: gl::types::GLint, program: &P,
-                              texture_bind_points: &mut Bitsfield)
+                              texture_bind_points: &mut Bitsfield, name: &str)
                               -> Result<(), DrawError> where P: ProgramExt, T: TextureExt
 {
+    // a texture that is attached to the framebuffer can only be sampled if the attached level
+    // is not accessible through the sampler, which is the case for the levels other than the
+    // base level when mipmapping is disabled, and for the levels below the base level
+    let uses_mipmaps = sampler.is_none_or(|s| {
+        !matches!(s.minify_filter, MinifySamplerFilter::Nearest | MinifySamplerFilter::Linear)
+    });
+    let base_level = texture.get_base_level();
+    if ctxt.framebuffer_textures.iter()
+           .any(|&(id, level)| {
+               id == texture.get_texture_id() &&
+               (level == base_level || (uses_mipmaps && level > base_level))
+           })
+    {
+        return Err(DrawError::TextureFeedbackLoop {
+            name: name.to_owned(),
+        });
+    }
+
     let sampler = if let Some(sampler) = sampler {
         Some(crate::sampler_object::get_sampler(ctxt, &sampler)?)
     } else {
@@ -639,15 +905,31 @@ fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext<'_>,
 
     let sampler = sampler.unwrap_or(0);
 
-    // finding an appropriate texture unit
+    // the unit that this uniform was assigned to during the previous draw, if it is still free
+    let previous_unit = program.get_texture_unit(location)
+                               .filter(|&unit| !texture_bind_points.is_used(unit));
+
+    // finding an appropriate texture unit ; we first try to keep the previous assignment if the
+    // texture is still there, then look for a unit that already contains the texture, then
+    // reuse the previous unit so that the assignments don't move from one draw to the next
     let texture_unit =
-        ctxt.state.texture_units
-            .iter().enumerate()
-            .find(|&(unit, content)| {
-                content.texture == texture.get_texture_id() && (content.sampler == sampler ||
-                                                        !texture_bind_points.is_used(unit as u16))
+        previous_unit
+            .filter(|&unit| {
+                ctxt.state.texture_units.get(unit as usize)
+                    .map(|content| content.texture == texture.get_texture_id() &&
+                                   content.sampler == sampler)
+                    .unwrap_or(false)
+            })
+            .or_else(|| {
+                ctxt.state.texture_units
+                    .iter().enumerate()
+                    .find(|&(unit, content)| {
+                        content.texture == texture.get_texture_id() &&
+                        (content.sampler == sampler || !texture_bind_points.is_used(unit as u16))
+                    })
+                    .map(|(unit, _)| unit as u16)
             })
-            .map(|(unit, _)| unit as u16)
+            .or(previous_unit)
             .or_else(|| {
                 if ctxt.state.texture_units.len() <
                     ctxt.capabilities.max_combined_texture_image_units as usize
@@ -665,8 +947,7 @@ fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext<'_>,
     texture_bind_points.set_used(texture_unit);
 
     // updating the program to use the right unit
-    program.set_uniform(ctxt, location,
-                        &RawUniformValue::SignedInt(texture_unit as gl::types::GLint));
+    program.set_texture_unit(ctxt, location, texture_unit);
 
     // updating the state of the texture unit
     if ctxt.state.texture_units.len() <= texture_unit as usize {
@@ -0,0 +1,419 @@
+use std::borrow::Cow;
+use std::convert::TryInto;
+use std::error::Error;
+use std::fmt;
+
+use crate::buffer::{BufferAnySlice, BufferSlice, Content};
+use crate::program::{self, BlockVariable};
+use crate::uniforms::{AsUniformValue, LayoutMismatchError, Uniforms, UniformType, UniformValue};
+
+/// A list of uniforms whose values are read from a blob of bytes.
+///
+/// The blob has the layout of a uniform block, which is described by the list of the variables
+/// of the block. This list is usually obtained from the reflection of a program, with
+/// `program.get_uniform_blocks()[name].variables`, and can be stored alongside the blob. Each
+/// variable is then passed as a uniform with the same name, which makes it possible for example
+/// to store the parameters of a material as an opaque blob that matches both a
+/// `uniform Material { ... }` block and the equivalent loose uniforms.
+///
+/// The elements of arrays are passed as `name[0]`, `name[1]`, etc.
+///
+/// To bind the whole blob to a uniform block instead, upload it in a buffer and use a
+/// `RawUniformBlock`.
+///
+/// # Example
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// # let program: glium::Program = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// # let material_blob: Vec<u8> = Vec::new();
+/// let variables = &program.get_uniform_blocks()["Material"].variables;
+/// let uniforms = glium::uniforms::UniformBlob::new(&material_blob[..], variables).unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct UniformBlob<'a> {
+    data: Cow<'a, [u8]>,
+    // name, type, offset, matrix stride and whether the matrix is row-major, of each value
+    variables: Vec<(String, UniformType, usize, usize, bool)>,
+}
+
+/// Error that can happen when building a `UniformBlob`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UniformBlobError {
+    /// The type of a variable can't be read from a blob. Only scalars, vectors and square
+    /// matrices are supported.
+    UnsupportedType {
+        /// Name of the variable.
+        name: String,
+        /// Type of the variable.
+        ty: UniformType,
+    },
+
+    /// The size of the last array of a shader storage block isn't known.
+    UnsizedArray {
+        /// Name of the variable.
+        name: String,
+    },
+
+    /// The blob is too small to contain a variable.
+    BlobTooSmall {
+        /// Name of the variable.
+        name: String,
+        /// The minimum size in bytes.
+        required: usize,
+        /// The size in bytes of the blob.
+        obtained: usize,
+    },
+}
+
+impl fmt::Display for UniformBlobError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        use self::UniformBlobError::*;
+        let desc = match *self {
+            UnsupportedType { .. } =>
+                "The type of this variable can't be read from a blob",
+            UnsizedArray { .. } =>
+                "The size of this array isn't known",
+            BlobTooSmall { .. } =>
+                "The blob is too small to contain this variable",
+        };
+        match *self {
+            UnsupportedType { ref name, ty } =>
+                write!(fmt, "{}: {}, type: {:?}", desc, name, ty),
+            UnsizedArray { ref name } =>
+                write!(fmt, "{}: {}", desc, name),
+            BlobTooSmall { ref name, required, obtained } =>
+                write!(fmt, "{}: {}, required: {} bytes, obtained: {} bytes", desc, name,
+                       required, obtained),
+        }
+    }
+}
+
+impl Error for UniformBlobError {}
+
+impl<'a> UniformBlob<'a> {
+    /// Builds a list of uniforms from a blob and the variables of the block whose layout the
+    /// blob follows.
+    pub fn new<D>(data: D, variables: &[BlockVariable]) -> Result<UniformBlob<'a>, UniformBlobError>
+                  where D: Into<Cow<'a, [u8]>>
+    {
+        let data = data.into();
+        let mut list = Vec::with_capacity(variables.len());
+
+        for variable in variables {
+            let size = match value_size(variable.ty, variable.matrix_stride,
+                                        variable.is_row_major)
+            {
+                Some(s) => s,
+                None => return Err(UniformBlobError::UnsupportedType {
+                    name: variable.name.clone(),
+                    ty: variable.ty,
+                }),
+            };
+
+            // the name of an array is reported as `name[0]`
+            let (base_name, len) = if variable.array_stride != 0 {
+                if variable.array_size == 0 {
+                    return Err(UniformBlobError::UnsizedArray { name: variable.name.clone() });
+                }
+
+                let base_name = variable.name.strip_suffix("[0]").unwrap_or(&variable.name);
+                (Some(base_name), variable.array_size)
+            } else {
+                (None, 1)
+            };
+
+            let required = variable.offset + (len - 1) * variable.array_stride + size;
+            if required > data.len() {
+                return Err(UniformBlobError::BlobTooSmall {
+                    name: variable.name.clone(),
+                    required,
+                    obtained: data.len(),
+                });
+            }
+
+            for index in 0 .. len {
+                let name = match base_name {
+                    Some(base_name) => format!("{}[{}]", base_name, index),
+                    None => variable.name.clone(),
+                };
+
+                list.push((name, variable.ty, variable.offset + index * variable.array_stride,
+                           variable.matrix_stride, variable.is_row_major));
+            }
+        }
+
+        Ok(UniformBlob {
+            data,
+            variables: list,
+        })
+    }
+
+    /// Returns the blob.
+    #[inline]
+    pub fn get_data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl<'b> Uniforms for UniformBlob<'b> {
+    #[inline]
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, mut output: F) {
+        for &(ref name, ty, offset, matrix_stride, row_major) in &self.variables {
+            output(name, read_value(&self.data, ty, offset, matrix_stride, row_major));
+        }
+    }
+}
+
+/// A slice of a buffer that is bound to a uniform block without checking its layout.
+///
+/// This is meant for buffers whose content already follows the layout of the block, for
+/// example blobs that have been built offline from the reflection of the program. Contrary
+/// to the other ways of binding a buffer to a block, the content of the buffer is not compared
+/// to the layout of the block when drawing.
+#[derive(Copy, Clone)]
+pub struct RawUniformBlock<'a> {
+    slice: BufferAnySlice<'a>,
+}
+
+impl<'a> RawUniformBlock<'a> {
+    /// Wraps around a slice of a buffer.
+    #[inline]
+    pub fn new<T: ?Sized, S>(slice: S) -> RawUniformBlock<'a>
+                             where S: Into<BufferSlice<'a, T>>, T: Content + 'a
+    {
+        RawUniformBlock {
+            slice: slice.into().as_slice_any(),
+        }
+    }
+}
+
+impl<'a> AsUniformValue for RawUniformBlock<'a> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        #[inline]
+        fn f(_: &program::UniformBlock) -> Result<(), LayoutMismatchError> {
+            Ok(())
+        }
+
+        UniformValue::Block(self.slice, f)
+    }
+}
+
+/// Returns the number of bytes that a value of this type occupies in a blob, or `None` if it
+/// can't be read from a blob.
+fn value_size(ty: UniformType, matrix_stride: usize, row_major: bool) -> Option<usize> {
+    // number of columns, number of rows and size of a component
+    let (columns, rows, component) = match ty {
+        UniformType::Float | UniformType::Int | UniformType::UnsignedInt |
+        UniformType::Bool => (1, 1, 4),
+        UniformType::FloatVec2 | UniformType::IntVec2 | UniformType::UnsignedIntVec2 |
+        UniformType::BoolVec2 => (1, 2, 4),
+        UniformType::FloatVec3 | UniformType::IntVec3 | UniformType::UnsignedIntVec3 |
+        UniformType::BoolVec3 => (1, 3, 4),
+        UniformType::FloatVec4 | UniformType::IntVec4 | UniformType::UnsignedIntVec4 |
+        UniformType::BoolVec4 => (1, 4, 4),
+        UniformType::Double => (1, 1, 8),
+        UniformType::DoubleVec2 => (1, 2, 8),
+        UniformType::DoubleVec3 => (1, 3, 8),
+        UniformType::DoubleVec4 => (1, 4, 8),
+        UniformType::FloatMat2 => (2, 2, 4),
+        UniformType::FloatMat3 => (3, 3, 4),
+        UniformType::FloatMat4 => (4, 4, 4),
+        UniformType::DoubleMat2 => (2, 2, 8),
+        UniformType::DoubleMat3 => (3, 3, 8),
+        UniformType::DoubleMat4 => (4, 4, 8),
+        _ => return None,
+    };
+
+    // the stride separates the columns of a column-major matrix and the rows of a row-major one
+    let (vectors, vector_len) = if row_major { (rows, columns) } else { (columns, rows) };
+    let matrix_stride = if matrix_stride == 0 { vector_len * component } else { matrix_stride };
+    Some((vectors - 1) * matrix_stride + vector_len * component)
+}
+
+/// Reads a value whose type is supported by `value_size`.
+fn read_value(data: &[u8], ty: UniformType, offset: usize, matrix_stride: usize,
+              row_major: bool) -> UniformValue<'static>
+{
+    let bytes4 = |i: usize| -> [u8; 4] { data[offset + i * 4 .. offset + i * 4 + 4].try_into().unwrap() };
+    let bytes8 = |i: usize| -> [u8; 8] { data[offset + i * 8 .. offset + i * 8 + 8].try_into().unwrap() };
+    let float = |i| f32::from_ne_bytes(bytes4(i));
+    let int = |i| i32::from_ne_bytes(bytes4(i));
+    let uint = |i| u32::from_ne_bytes(bytes4(i));
+    let boolean = |i| uint(i) != 0;
+    let double = |i| f64::from_ne_bytes(bytes8(i));
+
+    // returns the position of the element at `row` of the column `column` of a square matrix
+    let mat_start = |column: usize, row: usize, size: usize, component: usize| {
+        let stride = if matrix_stride == 0 { size * component } else { matrix_stride };
+        if row_major {
+            offset + row * stride + column * component
+        } else {
+            offset + column * stride + row * component
+        }
+    };
+    let float_mat = |column: usize, row: usize, size: usize| {
+        let start = mat_start(column, row, size, 4);
+        f32::from_ne_bytes(data[start .. start + 4].try_into().unwrap())
+    };
+    let double_mat = |column: usize, row: usize, size: usize| {
+        let start = mat_start(column, row, size, 8);
+        f64::from_ne_bytes(data[start .. start + 8].try_into().unwrap())
+    };
+
+    match ty {
+        UniformType::Float => UniformValue::Float(float(0)),
+        UniformType::FloatVec2 => UniformValue::Vec2([float(0), float(1)]),
+        UniformType::FloatVec3 => UniformValue::Vec3([float(0), float(1), float(2)]),
+        UniformType::FloatVec4 => UniformValue::Vec4([float(0), float(1), float(2), float(3)]),
+        UniformType::Int => UniformValue::SignedInt(int(0)),
+        UniformType::IntVec2 => UniformValue::IntVec2([int(0), int(1)]),
+        UniformType::IntVec3 => UniformValue::IntVec3([int(0), int(1), int(2)]),
+        UniformType::IntVec4 => UniformValue::IntVec4([int(0), int(1), int(2), int(3)]),
+        UniformType::UnsignedInt => UniformValue::UnsignedInt(uint(0)),
+        UniformType::UnsignedIntVec2 => UniformValue::UnsignedIntVec2([uint(0), uint(1)]),
+        UniformType::UnsignedIntVec3 => UniformValue::UnsignedIntVec3([uint(0), uint(1), uint(2)]),
+        UniformType::UnsignedIntVec4 => UniformValue::UnsignedIntVec4([uint(0), uint(1), uint(2),
+                                                                      uint(3)]),
+        UniformType::Bool => UniformValue::Bool(boolean(0)),
+        UniformType::BoolVec2 => UniformValue::BoolVec2([boolean(0), boolean(1)]),
+        UniformType::BoolVec3 => UniformValue::BoolVec3([boolean(0), boolean(1), boolean(2)]),
+        UniformType::BoolVec4 => UniformValue::BoolVec4([boolean(0), boolean(1), boolean(2),
+                                                        boolean(3)]),
+        UniformType::Double => UniformValue::Double(double(0)),
+        UniformType::DoubleVec2 => UniformValue::DoubleVec2([double(0), double(1)]),
+        UniformType::DoubleVec3 => UniformValue::DoubleVec3([double(0), double(1), double(2)]),
+        UniformType::DoubleVec4 => UniformValue::DoubleVec4([double(0), double(1), double(2),
+                                                            double(3)]),
+        UniformType::FloatMat2 => {
+            let m = |c, r| float_mat(c, r, 2);
+            UniformValue::Mat2([[m(0, 0), m(0, 1)], [m(1, 0), m(1, 1)]])
+        },
+        UniformType::FloatMat3 => {
+            let m = |c, r| float_mat(c, r, 3);
+            UniformValue::Mat3([[m(0, 0), m(0, 1), m(0, 2)], [m(1, 0), m(1, 1), m(1, 2)],
+                                [m(2, 0), m(2, 1), m(2, 2)]])
+        },
+        UniformType::FloatMat4 => {
+            let m = |c, r| float_mat(c, r, 4);
+            UniformValue::Mat4([[m(0, 0), m(0, 1), m(0, 2), m(0, 3)],
+                                [m(1, 0), m(1, 1), m(1, 2), m(1, 3)],
+                                [m(2, 0), m(2, 1), m(2, 2), m(2, 3)],
+                                [m(3, 0), m(3, 1), m(3, 2), m(3, 3)]])
+        },
+        UniformType::DoubleMat2 => {
+            let m = |c, r| double_mat(c, r, 2);
+            UniformValue::DoubleMat2([[m(0, 0), m(0, 1)], [m(1, 0), m(1, 1)]])
+        },
+        UniformType::DoubleMat3 => {
+            let m = |c, r| double_mat(c, r, 3);
+            UniformValue::DoubleMat3([[m(0, 0), m(0, 1), m(0, 2)], [m(1, 0), m(1, 1), m(1, 2)],
+                                      [m(2, 0), m(2, 1), m(2, 2)]])
+        },
+        UniformType::DoubleMat4 => {
+            let m = |c, r| double_mat(c, r, 4);
+            UniformValue::DoubleMat4([[m(0, 0), m(0, 1), m(0, 2), m(0, 3)],
+                                      [m(1, 0), m(1, 1), m(1, 2), m(1, 3)],
+                                      [m(2, 0), m(2, 1), m(2, 2), m(2, 3)],
+                                      [m(3, 0), m(3, 1), m(3, 2), m(3, 3)]])
+        },
+        _ => unreachable!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UniformBlob, UniformBlobError};
+    use crate::program::BlockVariable;
+    use crate::uniforms::{Uniforms, UniformType, UniformValue};
+
+    fn variable(name: &str, ty: UniformType, offset: usize, array_size: usize,
+                array_stride: usize, matrix_stride: usize, is_row_major: bool) -> BlockVariable
+    {
+        BlockVariable {
+            name: name.to_owned(),
+            ty,
+            offset,
+            array_size,
+            array_stride,
+            matrix_stride,
+            is_row_major,
+            top_level_array_size: None,
+            top_level_array_stride: None,
+        }
+    }
+
+    #[test]
+    fn read_std140_blob() {
+        // layout(std140) uniform Material { vec3 color; float weights[2]; mat2 transform; };
+        let mut data = vec![0u8; 80];
+        for (i, v) in [1.0f32, 2.0, 3.0].iter().enumerate() {
+            data[i * 4 .. i * 4 + 4].copy_from_slice(&v.to_ne_bytes());
+        }
+        data[16 .. 20].copy_from_slice(&0.25f32.to_ne_bytes());
+        data[32 .. 36].copy_from_slice(&0.75f32.to_ne_bytes());
+        data[48 .. 52].copy_from_slice(&5.0f32.to_ne_bytes());
+        data[68 .. 72].copy_from_slice(&6.0f32.to_ne_bytes());
+
+        let variables = vec![
+            variable("color", UniformType::FloatVec3, 0, 1, 0, 0, false),
+            variable("weights[0]", UniformType::Float, 16, 2, 16, 0, false),
+            variable("transform", UniformType::FloatMat2, 48, 1, 0, 16, false),
+        ];
+
+        let blob = UniformBlob::new(&data[..], &variables).unwrap();
+
+        let mut values = Vec::new();
+        blob.visit_values(|name, value| {
+            let value = match value {
+                UniformValue::Vec3(v) => v.to_vec(),
+                UniformValue::Float(v) => vec![v],
+                UniformValue::Mat2(m) => vec![m[0][0], m[0][1], m[1][0], m[1][1]],
+                _ => unreachable!(),
+            };
+            values.push((name.to_owned(), value));
+        });
+
+        assert_eq!(values, vec![
+            ("color".to_owned(), vec![1.0, 2.0, 3.0]),
+            ("weights[0]".to_owned(), vec![0.25]),
+            ("weights[1]".to_owned(), vec![0.75]),
+            ("transform".to_owned(), vec![5.0, 0.0, 0.0, 6.0]),
+        ]);
+    }
+
+    #[test]
+    fn read_row_major_matrix() {
+        // layout(std140, row_major) uniform Block { mat2 transform; };
+        let mut data = vec![0u8; 32];
+        for (i, v) in [1.0f32, 2.0].iter().enumerate() {
+            data[i * 4 .. i * 4 + 4].copy_from_slice(&v.to_ne_bytes());
+        }
+        for (i, v) in [3.0f32, 4.0].iter().enumerate() {
+            data[16 + i * 4 .. 20 + i * 4].copy_from_slice(&v.to_ne_bytes());
+        }
+
+        let variables = vec![variable("transform", UniformType::FloatMat2, 0, 1, 0, 16, true)];
+        let blob = UniformBlob::new(&data[..], &variables).unwrap();
+
+        let mut matrix = None;
+        blob.visit_values(|_, value| match value {
+            UniformValue::Mat2(m) => matrix = Some(m),
+            _ => unreachable!(),
+        });
+
+        // the first row is [1.0, 2.0], which means that the first column is [1.0, 3.0]
+        assert_eq!(matrix, Some([[1.0, 3.0], [2.0, 4.0]]));
+    }
+
+    #[test]
+    fn blob_too_small() {
+        let variables = vec![variable("color", UniformType::FloatVec4, 16, 1, 0, 0, false)];
+
+        match UniformBlob::new(&[0u8; 24][..], &variables) {
+            Err(UniformBlobError::BlobTooSmall { required: 32, obtained: 24, .. }) => (),
+            e => panic!("{:?}", e),
+        }
+    }
+}
@@ -1,10 +1,12 @@
 use crate::buffer::{Content, Buffer, BufferAny, BufferType, BufferMode, BufferCreationError};
 use crate::buffer::{BufferSlice, BufferMutSlice};
 use crate::uniforms::{AsUniformValue, UniformBlock, UniformValue, LayoutMismatchError};
+use crate::uniforms::{EmptyUniforms, UniformsStorage};
 use crate::program;
 
 use crate::gl;
 use crate::GlObject;
+use crate::utils::range::RangeArgument;
 
 use std::ops::{Deref, DerefMut};
 
@@ -201,6 +203,100 @@ impl<T: ?Sized> UniformBuffer<T> where T: Content {
     }
 }
 
+impl<T> UniformBuffer<[T]> where [T]: Content, T: Copy {
+    /// Uploads an array of elements in the uniforms buffer.
+    #[inline]
+    pub fn new_array<F: ?Sized>(facade: &F, data: &[T])
+                                -> Result<UniformBuffer<[T]>, BufferCreationError>
+                                where F: Facade
+    {
+        UniformBuffer::new_array_impl(facade, data, BufferMode::Default)
+    }
+
+    /// Uploads an array of elements in the uniforms buffer.
+    #[inline]
+    pub fn dynamic_array<F: ?Sized>(facade: &F, data: &[T])
+                                    -> Result<UniformBuffer<[T]>, BufferCreationError>
+                                    where F: Facade
+    {
+        UniformBuffer::new_array_impl(facade, data, BufferMode::Dynamic)
+    }
+
+    /// Uploads an array of elements in the uniforms buffer.
+    #[inline]
+    pub fn persistent_array<F: ?Sized>(facade: &F, data: &[T])
+                                       -> Result<UniformBuffer<[T]>, BufferCreationError>
+                                       where F: Facade
+    {
+        UniformBuffer::new_array_impl(facade, data, BufferMode::Persistent)
+    }
+
+    /// Uploads an array of elements in the uniforms buffer.
+    #[inline]
+    pub fn immutable_array<F: ?Sized>(facade: &F, data: &[T])
+                                      -> Result<UniformBuffer<[T]>, BufferCreationError>
+                                      where F: Facade
+    {
+        UniformBuffer::new_array_impl(facade, data, BufferMode::Immutable)
+    }
+
+    #[inline]
+    fn new_array_impl<F: ?Sized>(facade: &F, data: &[T], mode: BufferMode)
+                                 -> Result<UniformBuffer<[T]>, BufferCreationError>
+                                 where F: Facade
+    {
+        let buffer = Buffer::new(facade, data, BufferType::UniformBuffer, mode)?;
+
+        Ok(UniformBuffer {
+            buffer,
+        })
+    }
+
+    /// Builds a slice of this buffer that can be passed to `uniform!`. Returns `None` if out of
+    /// range.
+    ///
+    /// This allows packing the data of multiple objects in a single buffer. Only the range of
+    /// the slice is bound to the block, with `glBindBufferRange`. The block can either contain a
+    /// single `T`, in which case the first element of the slice is used, or an array of `T`.
+    ///
+    /// The offset of the slice in bytes must be a multiple of
+    /// `uniform_buffer_offset_alignment` (see `Capabilities`), otherwise drawing returns a
+    /// `DrawError::BufferOffsetMisaligned`. Padding `T` to a multiple of this alignment makes
+    /// every element usable.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #[macro_use]
+    /// # extern crate glium;
+    /// # fn main() {
+    /// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// #[derive(Copy, Clone)]
+    /// struct Object {
+    ///     color: [f32; 4],
+    ///     _padding: [f32; 60],
+    /// }
+    ///
+    /// implement_uniform_block!(Object, color);
+    ///
+    /// let objects = vec![Object { color: [1.0, 0.0, 0.0, 1.0], _padding: [0.0; 60] }; 16];
+    /// let buffer = glium::uniforms::UniformBuffer::new_array(&display, &objects).unwrap();
+    ///
+    /// for index in 0 .. 16 {
+    ///     let uniforms = uniform! {
+    ///         Object: buffer.slice(index .. index + 1).unwrap(),
+    ///     };
+    ///
+    ///     // ... draw ...
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn slice<R: RangeArgument<usize>>(&self, range: R) -> Option<UniformBufferSlice<'_, T>> {
+        self.buffer.slice(range).map(|slice| UniformBufferSlice { slice })
+    }
+}
+
 impl<T: ?Sized> Deref for UniformBuffer<T> where T: Content {
     type Target = Buffer<T>;
 
@@ -245,3 +341,99 @@ impl<'a, T: ?Sized> AsUniformValue for &'a UniformBuffer<T> where T: UniformBloc
         UniformValue::Block(self.buffer.as_slice_any(), f::<T>)
     }
 }
+
+/// A slice of a `UniformBuffer<[T]>` that can be bound to a uniform block.
+///
+/// See `UniformBuffer::slice`.
+pub struct UniformBufferSlice<'a, T> where [T]: Content {
+    slice: BufferSlice<'a, [T]>,
+}
+
+impl<'a, T> Deref for UniformBufferSlice<'a, T> where [T]: Content {
+    type Target = BufferSlice<'a, [T]>;
+
+    #[inline]
+    fn deref(&self) -> &BufferSlice<'a, [T]> {
+        &self.slice
+    }
+}
+
+impl<'a, T> From<UniformBufferSlice<'a, T>> for BufferSlice<'a, [T]> where [T]: Content {
+    #[inline]
+    fn from(s: UniformBufferSlice<'a, T>) -> BufferSlice<'a, [T]> {
+        s.slice
+    }
+}
+
+impl<'a, T> AsUniformValue for UniformBufferSlice<'a, T> where T: UniformBlock + Copy {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        #[inline]
+        fn f<T>(block: &program::UniformBlock)
+                -> Result<(), LayoutMismatchError> where T: UniformBlock + Copy
+        {
+            // the block contains either a single element or an array of elements
+            T::matches(&block.layout, 0).or_else(|_| <[T]>::matches(&block.layout, 0))
+        }
+
+        UniformValue::Block(self.slice.as_slice_any(), f::<T>)
+    }
+}
+
+/// A slice of a buffer that can only be bound to a shader storage block.
+///
+/// Passing a buffer to `uniform!` binds it to the uniform block or the shader storage block
+/// with the given name, and silently ignores it if the program doesn't have such a block.
+/// Wrapping the buffer in a `ShaderStorageSlice` makes the draw command return an error
+/// instead. The layout of the content of the buffer is checked against the layout of the
+/// block, and the slice can start anywhere in the buffer as long as its offset is a multiple
+/// of `shader_storage_buffer_offset_alignment`.
+#[derive(Copy, Clone)]
+pub struct ShaderStorageSlice<'a, T: ?Sized> where T: Content {
+    slice: BufferSlice<'a, T>,
+}
+
+impl<'a, T: ?Sized> ShaderStorageSlice<'a, T> where T: UniformBlock + Content {
+    /// Wraps around a slice of a buffer.
+    #[inline]
+    pub fn new<S>(slice: S) -> ShaderStorageSlice<'a, T> where S: Into<BufferSlice<'a, T>> {
+        ShaderStorageSlice {
+            slice: slice.into(),
+        }
+    }
+}
+
+impl<'a, T: ?Sized> AsUniformValue for ShaderStorageSlice<'a, T> where T: UniformBlock + Content {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        #[inline]
+        fn f<T: ?Sized>(block: &program::UniformBlock)
+                        -> Result<(), LayoutMismatchError> where T: UniformBlock + Content
+        {
+            T::matches(&block.layout, 0)
+        }
+
+        UniformValue::ShaderStorageBlock(self.slice.as_slice_any(), f::<T>)
+    }
+}
+
+impl<'a, T: ?Sized> BufferSlice<'a, T> where T: UniformBlock + Content {
+    /// Builds a list of uniforms that binds this slice to the shader storage block named `name`.
+    ///
+    /// Other uniforms can be added to the list with `add`. See `ShaderStorageSlice` for the
+    /// checks that are performed when drawing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let buffer: glium::buffer::Buffer<[u32]> = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+    /// let uniforms = buffer.slice(16 ..).unwrap().bind_as_ssbo("MyBlock")
+    ///                      .add("scale", 2.0f32);
+    /// ```
+    #[inline]
+    pub fn bind_as_ssbo<'n>(self, name: &'n str)
+                            -> UniformsStorage<'n, ShaderStorageSlice<'a, T>, EmptyUniforms>
+    {
+        UniformsStorage::new(name, ShaderStorageSlice::new(self))
+    }
+}
@@ -0,0 +1,501 @@
+use std::cmp;
+use std::error::Error;
+use std::fmt;
+
+use crate::gl;
+use crate::CapabilitiesSource;
+use crate::TextureExt;
+use crate::texture::{Dimensions, InternalFormat, TextureAny, TextureKind};
+use crate::uniforms::{AsUniformValue, UniformType, UniformValue};
+
+/// Format of an image, as declared with a layout qualifier in a shader.
+///
+/// For example `layout(rgba8) uniform image2D img;` corresponds to `ImageUnitFormat::RGBA8`.
+///
+/// In order to access a texture through an image unit, the texture's internal format must be
+/// compatible with the format declared by the shader. Two formats are compatible if their
+/// texels have the same size. Using incompatible formats is undefined behavior.
+#[allow(missing_docs)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ImageUnitFormat {
+    RGBA32F,
+    RGBA16F,
+    RG32F,
+    RG16F,
+    R11FG11FB10F,
+    R32F,
+    R16F,
+
+    RGBA32UI,
+    RGBA16UI,
+    RGB10A2UI,
+    RGBA8UI,
+    RG32UI,
+    RG16UI,
+    RG8UI,
+    R32UI,
+    R16UI,
+    R8UI,
+
+    RGBA32I,
+    RGBA16I,
+    RGBA8I,
+    RG32I,
+    RG16I,
+    RG8I,
+    R32I,
+    R16I,
+    R8I,
+
+    RGBA16,
+    RGB10A2,
+    RGBA8,
+    RG16,
+    RG8,
+    R16,
+    R8,
+
+    RGBA16snorm,
+    RGBA8snorm,
+    RG16snorm,
+    RG8snorm,
+    R16snorm,
+    R8snorm,
+}
+
+impl ImageUnitFormat {
+    /// Parses the name of a GLSL format layout qualifier, for example `rgba8` or `r32ui`.
+    pub fn from_glsl_qualifier(qualifier: &str) -> Option<ImageUnitFormat> {
+        Some(match qualifier {
+            "rgba32f" => ImageUnitFormat::RGBA32F,
+            "rgba16f" => ImageUnitFormat::RGBA16F,
+            "rg32f" => ImageUnitFormat::RG32F,
+            "rg16f" => ImageUnitFormat::RG16F,
+            "r11f_g11f_b10f" => ImageUnitFormat::R11FG11FB10F,
+            "r32f" => ImageUnitFormat::R32F,
+            "r16f" => ImageUnitFormat::R16F,
+            "rgba32ui" => ImageUnitFormat::RGBA32UI,
+            "rgba16ui" => ImageUnitFormat::RGBA16UI,
+            "rgb10_a2ui" => ImageUnitFormat::RGB10A2UI,
+            "rgba8ui" => ImageUnitFormat::RGBA8UI,
+            "rg32ui" => ImageUnitFormat::RG32UI,
+            "rg16ui" => ImageUnitFormat::RG16UI,
+            "rg8ui" => ImageUnitFormat::RG8UI,
+            "r32ui" => ImageUnitFormat::R32UI,
+            "r16ui" => ImageUnitFormat::R16UI,
+            "r8ui" => ImageUnitFormat::R8UI,
+            "rgba32i" => ImageUnitFormat::RGBA32I,
+            "rgba16i" => ImageUnitFormat::RGBA16I,
+            "rgba8i" => ImageUnitFormat::RGBA8I,
+            "rg32i" => ImageUnitFormat::RG32I,
+            "rg16i" => ImageUnitFormat::RG16I,
+            "rg8i" => ImageUnitFormat::RG8I,
+            "r32i" => ImageUnitFormat::R32I,
+            "r16i" => ImageUnitFormat::R16I,
+            "r8i" => ImageUnitFormat::R8I,
+            "rgba16" => ImageUnitFormat::RGBA16,
+            "rgb10_a2" => ImageUnitFormat::RGB10A2,
+            "rgba8" => ImageUnitFormat::RGBA8,
+            "rg16" => ImageUnitFormat::RG16,
+            "rg8" => ImageUnitFormat::RG8,
+            "r16" => ImageUnitFormat::R16,
+            "r8" => ImageUnitFormat::R8,
+            "rgba16_snorm" => ImageUnitFormat::RGBA16snorm,
+            "rgba8_snorm" => ImageUnitFormat::RGBA8snorm,
+            "rg16_snorm" => ImageUnitFormat::RG16snorm,
+            "rg8_snorm" => ImageUnitFormat::RG8snorm,
+            "r16_snorm" => ImageUnitFormat::R16snorm,
+            "r8_snorm" => ImageUnitFormat::R8snorm,
+            _ => return None
+        })
+    }
+
+    /// Returns the size in bits of a texel of this format.
+    pub fn get_size_in_bits(&self) -> usize {
+        match *self {
+            ImageUnitFormat::RGBA32F | ImageUnitFormat::RGBA32UI |
+            ImageUnitFormat::RGBA32I => 128,
+
+            ImageUnitFormat::RGBA16F | ImageUnitFormat::RG32F | ImageUnitFormat::RGBA16UI |
+            ImageUnitFormat::RG32UI | ImageUnitFormat::RGBA16I | ImageUnitFormat::RG32I |
+            ImageUnitFormat::RGBA16 | ImageUnitFormat::RGBA16snorm => 64,
+
+            ImageUnitFormat::RG16F | ImageUnitFormat::R11FG11FB10F | ImageUnitFormat::R32F |
+            ImageUnitFormat::RGB10A2UI | ImageUnitFormat::RGBA8UI | ImageUnitFormat::RG16UI |
+            ImageUnitFormat::R32UI | ImageUnitFormat::RGBA8I | ImageUnitFormat::RG16I |
+            ImageUnitFormat::R32I | ImageUnitFormat::RGB10A2 | ImageUnitFormat::RGBA8 |
+            ImageUnitFormat::RG16 | ImageUnitFormat::RGBA8snorm |
+            ImageUnitFormat::RG16snorm => 32,
+
+            ImageUnitFormat::R16F | ImageUnitFormat::RG8UI | ImageUnitFormat::R16UI |
+            ImageUnitFormat::RG8I | ImageUnitFormat::R16I | ImageUnitFormat::RG8 |
+            ImageUnitFormat::R16 | ImageUnitFormat::RG8snorm | ImageUnitFormat::R16snorm => 16,
+
+            ImageUnitFormat::R8UI | ImageUnitFormat::R8I | ImageUnitFormat::R8 |
+            ImageUnitFormat::R8snorm => 8,
+        }
+    }
+
+    /// Returns true if a texture with the given internal format can be accessed through an
+    /// image unit with this format.
+    #[inline]
+    pub fn is_compatible_with(&self, format: &InternalFormat) -> bool {
+        format.get_total_bits() == self.get_size_in_bits()
+    }
+
+    /// Returns the kind of image that the shader must declare to access this format: `image*`
+    /// for `Float`, `iimage*` for `Integral` and `uimage*` for `Unsigned`.
+    pub fn get_kind(&self) -> TextureKind {
+        match *self {
+            ImageUnitFormat::RGBA32UI | ImageUnitFormat::RGBA16UI | ImageUnitFormat::RGB10A2UI |
+            ImageUnitFormat::RGBA8UI | ImageUnitFormat::RG32UI | ImageUnitFormat::RG16UI |
+            ImageUnitFormat::RG8UI | ImageUnitFormat::R32UI | ImageUnitFormat::R16UI |
+            ImageUnitFormat::R8UI => TextureKind::Unsigned,
+
+            ImageUnitFormat::RGBA32I | ImageUnitFormat::RGBA16I | ImageUnitFormat::RGBA8I |
+            ImageUnitFormat::RG32I | ImageUnitFormat::RG16I | ImageUnitFormat::RG8I |
+            ImageUnitFormat::R32I | ImageUnitFormat::R16I |
+            ImageUnitFormat::R8I => TextureKind::Integral,
+
+            _ => TextureKind::Float,
+        }
+    }
+
+    /// Returns the corresponding OpenGL enum.
+    pub fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            ImageUnitFormat::RGBA32F => gl::RGBA32F,
+            ImageUnitFormat::RGBA16F => gl::RGBA16F,
+            ImageUnitFormat::RG32F => gl::RG32F,
+            ImageUnitFormat::RG16F => gl::RG16F,
+            ImageUnitFormat::R11FG11FB10F => gl::R11F_G11F_B10F,
+            ImageUnitFormat::R32F => gl::R32F,
+            ImageUnitFormat::R16F => gl::R16F,
+            ImageUnitFormat::RGBA32UI => gl::RGBA32UI,
+            ImageUnitFormat::RGBA16UI => gl::RGBA16UI,
+            ImageUnitFormat::RGB10A2UI => gl::RGB10_A2UI,
+            ImageUnitFormat::RGBA8UI => gl::RGBA8UI,
+            ImageUnitFormat::RG32UI => gl::RG32UI,
+            ImageUnitFormat::RG16UI => gl::RG16UI,
+            ImageUnitFormat::RG8UI => gl::RG8UI,
+            ImageUnitFormat::R32UI => gl::R32UI,
+            ImageUnitFormat::R16UI => gl::R16UI,
+            ImageUnitFormat::R8UI => gl::R8UI,
+            ImageUnitFormat::RGBA32I => gl::RGBA32I,
+            ImageUnitFormat::RGBA16I => gl::RGBA16I,
+            ImageUnitFormat::RGBA8I => gl::RGBA8I,
+            ImageUnitFormat::RG32I => gl::RG32I,
+            ImageUnitFormat::RG16I => gl::RG16I,
+            ImageUnitFormat::RG8I => gl::RG8I,
+            ImageUnitFormat::R32I => gl::R32I,
+            ImageUnitFormat::R16I => gl::R16I,
+            ImageUnitFormat::R8I => gl::R8I,
+            ImageUnitFormat::RGBA16 => gl::RGBA16,
+            ImageUnitFormat::RGB10A2 => gl::RGB10_A2,
+            ImageUnitFormat::RGBA8 => gl::RGBA8,
+            ImageUnitFormat::RG16 => gl::RG16,
+            ImageUnitFormat::RG8 => gl::RG8,
+            ImageUnitFormat::R16 => gl::R16,
+            ImageUnitFormat::R8 => gl::R8,
+            ImageUnitFormat::RGBA16snorm => gl::RGBA16_SNORM,
+            ImageUnitFormat::RGBA8snorm => gl::RGBA8_SNORM,
+            ImageUnitFormat::RG16snorm => gl::RG16_SNORM,
+            ImageUnitFormat::RG8snorm => gl::RG8_SNORM,
+            ImageUnitFormat::R16snorm => gl::R16_SNORM,
+            ImageUnitFormat::R8snorm => gl::R8_SNORM,
+        }
+    }
+}
+
+/// How a shader accesses an image unit.
+///
+/// This should match the memory qualifiers of the image in the shader. For example
+/// `layout(r32f) writeonly uniform imageBuffer img;` corresponds to `ImageUnitAccess::Write`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ImageUnitAccess {
+    /// The shader only reads from the image.
+    Read,
+
+    /// The shader only writes to the image.
+    Write,
+
+    /// The shader both reads from and writes to the image.
+    ReadWrite,
+}
+
+impl ImageUnitAccess {
+    /// Returns the corresponding OpenGL enum.
+    #[inline]
+    pub fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            ImageUnitAccess::Read => gl::READ_ONLY,
+            ImageUnitAccess::Write => gl::WRITE_ONLY,
+            ImageUnitAccess::ReadWrite => gl::READ_WRITE,
+        }
+    }
+}
+
+/// Error that can happen when building an `ImageUnit`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImageUnitError {
+    /// Image load/store is not supported by the backend.
+    NotSupported,
+
+    /// The internal format of the texture couldn't be determined.
+    UnknownFormat,
+
+    /// The internal format of the texture is not compatible with the requested format.
+    FormatNotCompatible {
+        /// The requested format.
+        expected: ImageUnitFormat,
+        /// The internal format of the texture.
+        obtained: InternalFormat,
+    },
+
+    /// The texture doesn't have a mipmap at this level.
+    NoMipmapAtLevel(u32),
+
+    /// Tried to select a layer of a texture that doesn't have layers.
+    LayeringNotSupported(Dimensions),
+
+    /// The layer is out of range for this texture and mipmap level.
+    LayerOutOfBounds(u32),
+}
+
+impl fmt::Display for ImageUnitError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::ImageUnitError::*;
+        match *self {
+            NotSupported =>
+                write!(fmt, "Image load/store is not supported by the backend"),
+            UnknownFormat =>
+                write!(fmt, "The internal format of the texture couldn't be determined"),
+            FormatNotCompatible { expected, obtained } =>
+                write!(fmt, "The internal format of the texture ({:?}) is not compatible with \
+                             the image format {:?}", obtained, expected),
+            NoMipmapAtLevel(level) =>
+                write!(fmt, "The texture doesn't have a mipmap at level {}", level),
+            LayeringNotSupported(dimensions) =>
+                write!(fmt, "A texture of type {:?} doesn't have layers", dimensions),
+            LayerOutOfBounds(layer) =>
+                write!(fmt, "The layer {} is out of range", layer),
+        }
+    }
+}
+
+impl Error for ImageUnitError {}
+
+/// A level of a texture bound to an image unit, in order to be read or written by a shader.
+///
+/// Build it with `ImageUnit::new` or with the `image_unit` method of the textures. By default
+/// all the layers of the main level are bound with `ImageUnitAccess::ReadWrite`.
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// # let texture: glium::texture::Texture2dArray = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// use glium::uniforms::{ImageUnitAccess, ImageUnitFormat};
+///
+/// // can be used as a `layout(rgba8) writeonly uniform image2D` in the shader
+/// let image = texture.image_unit(ImageUnitFormat::RGBA8).unwrap()
+///                    .set_level(1).unwrap()
+///                    .set_layer(Some(3)).unwrap()
+///                    .set_access(ImageUnitAccess::Write);
+/// ```
+///
+/// If the shader declares the format of the image with a layout qualifier, it takes priority
+/// over the format of the `ImageUnit`, and drawing returns an error if it's not compatible
+/// with the texture.
+///
+/// Glium doesn't insert memory barriers for the textures written through image units. Call
+/// `Context::memory_barrier` with the barriers that correspond to the next uses of the texture,
+/// for example `MemoryBarriers::TEXTURE_FETCH` before sampling it or
+/// `MemoryBarriers::TEXTURE_UPDATE` before reading it.
+#[derive(Debug, Copy, Clone)]
+pub struct ImageUnit<'a> {
+    texture: &'a TextureAny,
+    texture_format: InternalFormat,
+    format: ImageUnitFormat,
+    level: u32,
+    layer: Option<u32>,
+    access: ImageUnitAccess,
+}
+
+impl<'a> ImageUnit<'a> {
+    /// Builds an image unit that binds all the layers of the main level of a texture.
+    ///
+    /// Returns an error if the internal format of the texture is not compatible with `format`.
+    pub fn new(texture: &'a TextureAny, format: ImageUnitFormat)
+               -> Result<ImageUnit<'a>, ImageUnitError>
+    {
+        if texture.get_context().get_capabilities().max_image_units.is_none() {
+            return Err(ImageUnitError::NotSupported);
+        }
+
+        let texture_format = texture.get_internal_format()
+                                    .map_err(|_| ImageUnitError::UnknownFormat)?;
+        if !format.is_compatible_with(&texture_format) {
+            return Err(ImageUnitError::FormatNotCompatible {
+                expected: format,
+                obtained: texture_format,
+            });
+        }
+
+        Ok(ImageUnit {
+            texture,
+            texture_format,
+            format,
+            level: 0,
+            layer: None,
+            access: ImageUnitAccess::ReadWrite,
+        })
+    }
+
+    /// Selects the mipmap level to bind.
+    pub fn set_level(mut self, level: u32) -> Result<ImageUnit<'a>, ImageUnitError> {
+        if level >= self.texture.get_mipmap_levels() {
+            return Err(ImageUnitError::NoMipmapAtLevel(level));
+        }
+
+        self.level = level;
+        if let Some(layer) = self.layer {
+            self.check_layer(layer)?;
+        }
+        Ok(self)
+    }
+
+    /// Selects a single layer to bind, or `None` to bind all the layers.
+    ///
+    /// The layers of a 3D texture are its depth slices, and the layers of a cubemap are its
+    /// faces. The shader must then declare an image with one dimension less, for example an
+    /// `image2D` for a layer of a `Texture2dArray`.
+    pub fn set_layer(mut self, layer: Option<u32>) -> Result<ImageUnit<'a>, ImageUnitError> {
+        if let Some(layer) = layer {
+            self.check_layer(layer)?;
+        }
+
+        self.layer = layer;
+        Ok(self)
+    }
+
+    /// Sets how the shader accesses the image.
+    #[inline]
+    pub fn set_access(mut self, access: ImageUnitAccess) -> ImageUnit<'a> {
+        self.access = access;
+        self
+    }
+
+    /// Returns the texture.
+    #[inline]
+    pub fn get_texture(&self) -> &'a TextureAny {
+        self.texture
+    }
+
+    /// Returns the format of the image.
+    #[inline]
+    pub fn get_format(&self) -> ImageUnitFormat {
+        self.format
+    }
+
+    /// Returns the internal format of the texture.
+    #[inline]
+    pub(crate) fn get_texture_format(&self) -> InternalFormat {
+        self.texture_format
+    }
+
+    /// Returns the bound mipmap level.
+    #[inline]
+    pub fn get_level(&self) -> u32 {
+        self.level
+    }
+
+    /// Returns the bound layer, or `None` if all the layers are bound.
+    #[inline]
+    pub fn get_layer(&self) -> Option<u32> {
+        self.layer
+    }
+
+    /// Returns how the shader accesses the image.
+    #[inline]
+    pub fn get_access(&self) -> ImageUnitAccess {
+        self.access
+    }
+
+    /// Returns true if the image can be bound to a uniform of the given type.
+    pub(crate) fn is_usable_with(&self, ty: &UniformType) -> bool {
+        use crate::uniforms::UniformType::*;
+
+        let (dimensions, kind) = match *ty {
+            Image1d => ("1d", TextureKind::Float),
+            IImage1d => ("1d", TextureKind::Integral),
+            UImage1d => ("1d", TextureKind::Unsigned),
+            Image2d => ("2d", TextureKind::Float),
+            IImage2d => ("2d", TextureKind::Integral),
+            UImage2d => ("2d", TextureKind::Unsigned),
+            Image3d => ("3d", TextureKind::Float),
+            IImage3d => ("3d", TextureKind::Integral),
+            UImage3d => ("3d", TextureKind::Unsigned),
+            ImageCube => ("cube", TextureKind::Float),
+            IImageCube => ("cube", TextureKind::Integral),
+            UImageCube => ("cube", TextureKind::Unsigned),
+            Image1dArray => ("1d array", TextureKind::Float),
+            IImage1dArray => ("1d array", TextureKind::Integral),
+            UImage1dArray => ("1d array", TextureKind::Unsigned),
+            Image2dArray => ("2d array", TextureKind::Float),
+            IImage2dArray => ("2d array", TextureKind::Integral),
+            UImage2dArray => ("2d array", TextureKind::Unsigned),
+            Image2dMultisample => ("2d ms", TextureKind::Float),
+            IImage2dMultisample => ("2d ms", TextureKind::Integral),
+            UImage2dMultisample => ("2d ms", TextureKind::Unsigned),
+            Image2dMultisampleArray => ("2d ms array", TextureKind::Float),
+            IImage2dMultisampleArray => ("2d ms array", TextureKind::Integral),
+            UImage2dMultisampleArray => ("2d ms array", TextureKind::Unsigned),
+            _ => return false,
+        };
+
+        // binding a single layer gives access to an image with one dimension less
+        let bound = match (self.texture.dimensions(), self.layer.is_some()) {
+            (Dimensions::Texture1d { .. }, _) => "1d",
+            (Dimensions::Texture1dArray { .. }, false) => "1d array",
+            (Dimensions::Texture1dArray { .. }, true) => "1d",
+            (Dimensions::Texture2d { .. }, _) => "2d",
+            (Dimensions::Texture2dArray { .. }, false) => "2d array",
+            (Dimensions::Texture2dArray { .. }, true) => "2d",
+            (Dimensions::Texture2dMultisample { .. }, _) => "2d ms",
+            (Dimensions::Texture2dMultisampleArray { .. }, false) => "2d ms array",
+            (Dimensions::Texture2dMultisampleArray { .. }, true) => "2d ms",
+            (Dimensions::Texture3d { .. }, false) => "3d",
+            (Dimensions::Texture3d { .. }, true) => "2d",
+            (Dimensions::Cubemap { .. }, false) => "cube",
+            (Dimensions::Cubemap { .. }, true) => "2d",
+            (Dimensions::CubemapArray { .. }, false) => return false,
+            (Dimensions::CubemapArray { .. }, true) => "2d",
+        };
+
+        bound == dimensions && self.format.get_kind() == kind
+    }
+
+    fn check_layer(&self, layer: u32) -> Result<(), ImageUnitError> {
+        let layers = match self.texture.dimensions() {
+            Dimensions::Texture1dArray { array_size, .. } => array_size,
+            Dimensions::Texture2dArray { array_size, .. } => array_size,
+            Dimensions::Texture2dMultisampleArray { array_size, .. } => array_size,
+            Dimensions::Texture3d { depth, .. } => cmp::max(1, depth >> self.level),
+            Dimensions::Cubemap { .. } => 6,
+            Dimensions::CubemapArray { array_size, .. } => 6 * array_size,
+            dimensions => return Err(ImageUnitError::LayeringNotSupported(dimensions)),
+        };
+
+        if layer >= layers {
+            return Err(ImageUnitError::LayerOutOfBounds(layer));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> AsUniformValue for ImageUnit<'a> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::TextureImage(*self)
+    }
+}
@@ -0,0 +1,98 @@
+//! Layout walk shared by the `std140` and `std430` rules.
+//!
+//! The two rules only differ in the alignment of aggregates (arrays, matrices and structs): it
+//! is rounded up to 16 bytes by `std140` and left as is by `std430`. Everything else is
+//! generated by the `impl_block_layout!` macro below, which takes the rule as a parameter.
+
+/// Rounds up `value` to a multiple of `alignment`.
+#[doc(hidden)]
+#[inline]
+pub const fn round_up(value: usize, alignment: usize) -> usize {
+    value.div_ceil(alignment) * alignment
+}
+
+/// Returns the larger of two values.
+#[doc(hidden)]
+#[inline]
+pub const fn max(a: usize, b: usize) -> usize {
+    if a > b { a } else { b }
+}
+
+/// Alignment of an aggregate under the `std140` rules.
+#[doc(hidden)]
+#[inline]
+pub const fn std140_aggregate_alignment(alignment: usize) -> usize {
+    round_up(alignment, 16)
+}
+
+/// Alignment of an aggregate under the `std430` rules.
+#[doc(hidden)]
+#[inline]
+pub const fn std430_aggregate_alignment(alignment: usize) -> usize {
+    alignment
+}
+
+/// Implements a layout trait (`Std140` or `Std430`) for the scalars, vectors, matrices and
+/// arrays, `$aggregate_alignment` being the function that gives the alignment of an aggregate
+/// from the alignment of its elements.
+macro_rules! impl_block_layout {
+    ($layout:ident, $aggregate_alignment:path) => (
+        impl_block_layout!(@scalar $layout, i32, 4);
+        impl_block_layout!(@scalar $layout, u32, 4);
+        impl_block_layout!(@scalar $layout, f32, 4);
+        impl_block_layout!(@scalar $layout, f64, 8);
+
+        impl_block_layout!(@matrix $layout, $aggregate_alignment, f32, 2);
+        impl_block_layout!(@matrix $layout, $aggregate_alignment, f32, 3);
+        impl_block_layout!(@matrix $layout, $aggregate_alignment, f32, 4);
+        impl_block_layout!(@matrix $layout, $aggregate_alignment, f64, 2);
+        impl_block_layout!(@matrix $layout, $aggregate_alignment, f64, 3);
+        impl_block_layout!(@matrix $layout, $aggregate_alignment, f64, 4);
+
+        impl_block_layout!(@arrays $layout, $aggregate_alignment, 5, 6, 7, 8, 9, 10, 11, 12, 13,
+                           14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30,
+                           31, 32, 64, 128, 256, 512, 1024, 2048, 4096);
+    );
+
+    (@scalar $layout:ident, $ty:ty, $size:expr) => (
+        impl $layout for $ty {
+            const ALIGNMENT: usize = $size;
+            const SIZE: usize = $size;
+        }
+
+        impl $layout for [$ty; 2] {
+            const ALIGNMENT: usize = 2 * $size;
+            const SIZE: usize = 2 * $size;
+        }
+
+        impl $layout for [$ty; 3] {
+            const ALIGNMENT: usize = 4 * $size;
+            const SIZE: usize = 3 * $size;
+        }
+
+        impl $layout for [$ty; 4] {
+            const ALIGNMENT: usize = 4 * $size;
+            const SIZE: usize = 4 * $size;
+        }
+    );
+
+    // a matrix is stored like an array of column vectors
+    (@matrix $layout:ident, $aggregate_alignment:path, $ty:ty, $columns:expr) => (
+        impl $layout for [[$ty; $columns]; $columns] {
+            const ALIGNMENT: usize = $aggregate_alignment(<[$ty; $columns] as $layout>::ALIGNMENT);
+            const SIZE: usize = $columns *
+                $crate::uniforms::layout::round_up(<[$ty; $columns] as $layout>::SIZE,
+                                                   Self::ALIGNMENT);
+        }
+    );
+
+    (@arrays $layout:ident, $aggregate_alignment:path, $($len:expr),+) => (
+        $(
+            impl<T> $layout for [T; $len] where T: $layout {
+                const ALIGNMENT: usize = $aggregate_alignment(T::ALIGNMENT);
+                const SIZE: usize = $len *
+                    $crate::uniforms::layout::round_up(T::SIZE, Self::ALIGNMENT);
+            }
+        )+
+    );
+}
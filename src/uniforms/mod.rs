@@ -25,6 +25,39 @@ let uniforms = uniform! {
 
 In both situations, each field must implement the `UniformValue` trait.
 
+## Arrays and structs
+
+Slices are passed element by element, which makes it possible to set the uniforms of a GLSL
+array. Structs can be passed member by member as well thanks to the `implement_uniform_struct!`
+macro. Fields that are arrays of values or of structs must be followed by `[..]` in the
+macro.
+
+```no_run
+#[macro_use]
+extern crate glium;
+
+# fn main() {
+#[derive(Copy, Clone)]
+struct Light {
+    position: [f32; 3],
+    color: [f32; 3],
+}
+
+implement_uniform_struct!(Light, position, color);
+
+let lights = [
+    Light { position: [0.0, 1.0, 0.0], color: [1.0, 1.0, 1.0] },
+    Light { position: [2.0, 1.0, 0.0], color: [1.0, 0.0, 0.0] },
+];
+
+// matches `uniform Light lights[2];` and defines `lights[0].position`, `lights[0].color`,
+// `lights[1].position` and `lights[1].color`
+let uniforms = uniform! {
+    lights: &lights[..],
+};
+# }
+```
+
 ## Samplers
 
 In order to customize the way a texture is being sampled, you must use a `Sampler`.
@@ -140,11 +173,22 @@ let program = glium::Program::from_source(&display,
 # }
 ```
 */
-pub use self::buffer::UniformBuffer;
+pub use self::blob::{UniformBlob, UniformBlobError, RawUniformBlock};
+pub use self::buffer::{UniformBuffer, UniformBufferSlice, ShaderStorageSlice};
+pub use crate::buffer::AtomicCounterBuffer;
+pub use self::image_unit::{ImageUnit, ImageUnitFormat, ImageUnitAccess, ImageUnitError};
 pub use self::sampler::{SamplerWrapFunction, MagnifySamplerFilter, MinifySamplerFilter, DepthTextureComparison};
-pub use self::sampler::{Sampler, SamplerBehavior};
-pub use self::uniforms::{EmptyUniforms, UniformsStorage};
+pub use self::sampler::{Sampler, OwnedSampler, SamplerBehavior};
+pub use self::uniforms::{EmptyUniforms, UniformsStorage, DynamicUniforms, OwnedUniforms};
 pub use self::value::{UniformValue, UniformType};
+pub use self::std140::Std140;
+pub use self::std430::Std430;
+
+#[doc(hidden)]
+pub use self::layout::{max as __layout_max, round_up as __layout_round_up};
+#[doc(hidden)]
+pub use self::layout::{std140_aggregate_alignment as __std140_aggregate_alignment,
+                       std430_aggregate_alignment as __std430_aggregate_alignment};
 
 use std::error::Error;
 use std::fmt;
@@ -155,8 +199,14 @@ use crate::program;
 use crate::program::BlockLayout;
 
 mod bind;
+mod blob;
 mod buffer;
+mod image_unit;
+#[macro_use]
+mod layout;
 mod sampler;
+mod std140;
+mod std430;
 mod uniforms;
 mod value;
 
@@ -306,6 +356,42 @@ impl<'a, T: ?Sized> AsUniformValue for &'a Buffer<T> where T: UniformBlock + Buf
     }
 }
 
+/// Value that can be passed as one or more uniforms whose names start with the same name.
+///
+/// This trait is implemented on all the types that implement `AsUniformValue`, in which case the
+/// value is passed as a single uniform, on slices, whose elements are passed as `name[0]`,
+/// `name[1]`, etc., and on the structs that use the `implement_uniform_struct!` macro, whose
+/// fields are passed as `name.field`.
+///
+/// All the values of the `uniform!` macro must implement this trait.
+pub trait UniformMembers {
+    /// Calls `output` with the name and value of each uniform, `name` being the name of the
+    /// value itself.
+    fn visit_members<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, name: &str, output: &mut F);
+}
+
+impl<T> UniformMembers for T where T: AsUniformValue {
+    #[inline]
+    fn visit_members<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, name: &str, output: &mut F) {
+        output(name, self.as_uniform_value());
+    }
+}
+
+impl<T> UniformMembers for [T] where T: UniformMembers {
+    fn visit_members<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, name: &str, output: &mut F) {
+        for (index, element) in self.iter().enumerate() {
+            element.visit_members(&format!("{}[{}]", name, index), output);
+        }
+    }
+}
+
+impl<'b, T> UniformMembers for &'b [T] where T: UniformMembers {
+    #[inline]
+    fn visit_members<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, name: &str, output: &mut F) {
+        (**self).visit_members(name, output);
+    }
+}
+
 /// Objects that are suitable for being inside a uniform block or a SSBO.
 pub trait UniformBlock {        // TODO: `: Copy`, but unsized structs don't impl `Copy`
     /// Checks whether the uniforms' layout matches the given block if `Self` starts at
@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use crate::ToGlEnum;
 use crate::gl;
 
@@ -179,6 +181,12 @@ impl<'t, T: 't> Sampler<'t, T> {
         self.1.max_anisotropy = level;
         self
     }
+
+    /// Enables or disables seamless filtering of cubemaps.
+    pub fn seamless_cubemap(mut self, seamless: bool) -> Sampler<'t, T> {
+        self.1.seamless_cubemap = seamless;
+        self
+    }
 }
 
 impl<'t, T: 't> Copy for Sampler<'t, T> {}
@@ -189,6 +197,35 @@ impl<'t, T: 't> Clone for Sampler<'t, T> {
     }
 }
 
+/// A sampler that shares the ownership of its texture.
+///
+/// Contrary to `Sampler`, this object doesn't borrow the texture and can be stored for example
+/// in an `OwnedUniforms`.
+#[derive(Debug)]
+pub struct OwnedSampler<T>(pub Rc<T>, pub SamplerBehavior);
+
+impl<T> OwnedSampler<T> {
+    /// Builds a new `OwnedSampler` with default parameters.
+    #[inline]
+    pub fn new(texture: Rc<T>) -> OwnedSampler<T> {
+        OwnedSampler(texture, Default::default())
+    }
+
+    /// Changes the behavior of the sampler.
+    #[inline]
+    pub fn behavior(mut self, behavior: SamplerBehavior) -> OwnedSampler<T> {
+        self.1 = behavior;
+        self
+    }
+}
+
+impl<T> Clone for OwnedSampler<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        OwnedSampler(self.0.clone(), self.1)
+    }
+}
+
 /// Behavior of a sampler.
 // TODO: GL_TEXTURE_BORDER_COLOR, GL_TEXTURE_MIN_LOD, GL_TEXTURE_MAX_LOD, GL_TEXTURE_LOD_BIAS
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
@@ -215,6 +252,17 @@ pub struct SamplerBehavior {
     /// If you set the value to a value higher than what the hardware supports, it will
     /// be clamped.
     pub max_anisotropy: u16,
+
+    /// If true, cubemaps are filtered across their faces, which removes the visible seams
+    /// between the faces. Default value is true.
+    ///
+    /// ## Compatibility
+    ///
+    /// Cubemaps are always filtered seamlessly on OpenGL ES 3.0 and above. On OpenGL, seamless
+    /// filtering requires OpenGL 3.2 or `GL_ARB_seamless_cube_map`, and this parameter is
+    /// ignored unless `GL_ARB_seamless_cubemap_per_texture` is supported. Without this
+    /// extension, seamless filtering is enabled for all the cubemaps.
+    pub seamless_cubemap: bool,
 }
 
 impl Default for SamplerBehavior {
@@ -230,6 +278,7 @@ impl Default for SamplerBehavior {
             magnify_filter: MagnifySamplerFilter::Linear,
             depth_texture_comparison: None,
             max_anisotropy: 1,
+            seamless_cubemap: true,
         }
     }
 }
@@ -0,0 +1,37 @@
+/// Types whose size and alignment under the `std140` layout rules are known.
+///
+/// This trait is used by the `implement_std140_block!` macro in order to check at compile time
+/// that the fields of a struct are where a `layout(std140)` block expects them.
+///
+/// Arrays are only implemented for the same lengths as `UniformBlock`. Keep in mind that the
+/// elements of an array always have a stride that is a multiple of 16 bytes in `std140`, which
+/// means that `[f32; 8]` for example doesn't match `float[8]` while `[[f32; 4]; 8]` matches
+/// `vec4[8]`.
+pub trait Std140 {
+    /// Base alignment of the type, in bytes.
+    const ALIGNMENT: usize;
+
+    /// Number of bytes occupied by the type, without any trailing padding for members that
+    /// follow it.
+    const SIZE: usize;
+}
+
+impl_block_layout!(Std140, super::layout::std140_aggregate_alignment);
+
+#[cfg(test)]
+mod tests {
+    use super::Std140;
+
+    #[test]
+    fn std140_sizes() {
+        assert_eq!(<[f32; 3] as Std140>::ALIGNMENT, 16);
+        assert_eq!(<[f32; 3] as Std140>::SIZE, 12);
+        assert_eq!(<[f64; 2] as Std140>::ALIGNMENT, 16);
+        assert_eq!(<[[f32; 3]; 3] as Std140>::SIZE, 48);
+        assert_eq!(<[[f32; 4]; 4] as Std140>::SIZE, 64);
+        assert_eq!(<[[f64; 3]; 3] as Std140>::ALIGNMENT, 32);
+        assert_eq!(<[[f64; 3]; 3] as Std140>::SIZE, 96);
+        assert_eq!(<[f32; 8] as Std140>::SIZE, 128);
+        assert_eq!(<[[f32; 3]; 8] as Std140>::SIZE, 128);
+    }
+}
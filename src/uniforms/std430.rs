@@ -0,0 +1,38 @@
+/// Types whose size and alignment under the `std430` layout rules are known.
+///
+/// This trait is used by the `implement_std430_block!` macro in order to check at compile time
+/// that the fields of a struct are where a `layout(std430)` shader storage block expects them.
+///
+/// The `std430` rules are the same as the `std140` rules (see `Std140`), except that the
+/// alignment of arrays and structs is no longer rounded up to 16 bytes. For example `[f32; 8]`
+/// matches `float[8]` and `[[f32; 2]; 2]` matches `mat2`. A `vec3` is still aligned to 16 bytes.
+///
+/// Arrays are only implemented for the same lengths as `UniformBlock`.
+pub trait Std430 {
+    /// Base alignment of the type, in bytes.
+    const ALIGNMENT: usize;
+
+    /// Number of bytes occupied by the type, without any trailing padding for members that
+    /// follow it.
+    const SIZE: usize;
+}
+
+impl_block_layout!(Std430, super::layout::std430_aggregate_alignment);
+
+#[cfg(test)]
+mod tests {
+    use super::Std430;
+
+    #[test]
+    fn std430_sizes() {
+        assert_eq!(<[f32; 3] as Std430>::ALIGNMENT, 16);
+        assert_eq!(<[f32; 3] as Std430>::SIZE, 12);
+        assert_eq!(<[[f32; 2]; 2] as Std430>::ALIGNMENT, 8);
+        assert_eq!(<[[f32; 2]; 2] as Std430>::SIZE, 16);
+        assert_eq!(<[[f32; 3]; 3] as Std430>::SIZE, 48);
+        assert_eq!(<[[f64; 3]; 3] as Std430>::SIZE, 96);
+        assert_eq!(<[f32; 8] as Std430>::ALIGNMENT, 4);
+        assert_eq!(<[f32; 8] as Std430>::SIZE, 32);
+        assert_eq!(<[[f32; 3]; 8] as Std430>::SIZE, 128);
+    }
+}
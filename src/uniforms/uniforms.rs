@@ -1,4 +1,8 @@
-use crate::uniforms::{Uniforms, UniformValue, AsUniformValue};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::uniforms::{Uniforms, UniformValue, AsUniformValue, UniformMembers};
 
 /// Object that can be used when you don't have any uniforms.
 #[derive(Debug, Copy, Clone)]
@@ -11,13 +15,13 @@ impl Uniforms for EmptyUniforms {
 }
 
 /// Stores uniforms.
-pub struct UniformsStorage<'n, T, R> where T: AsUniformValue, R: Uniforms {
+pub struct UniformsStorage<'n, T, R> where T: UniformMembers, R: Uniforms {
     name: &'n str,
     value: T,
     rest: R,
 }
 
-impl<'n, T> UniformsStorage<'n, T, EmptyUniforms> where T: AsUniformValue {
+impl<'n, T> UniformsStorage<'n, T, EmptyUniforms> where T: UniformMembers {
     /// Builds a new storage with a value.
     #[inline]
     pub fn new(name: &'n str, value: T)
@@ -31,12 +35,12 @@ impl<'n, T> UniformsStorage<'n, T, EmptyUniforms> where T: AsUniformValue {
     }
 }
 
-impl<'n, T, R> UniformsStorage<'n, T, R> where T: AsUniformValue, R: Uniforms {
+impl<'n, T, R> UniformsStorage<'n, T, R> where T: UniformMembers, R: Uniforms {
     /// Adds a value to the storage.
     #[inline]
     pub fn add<U>(self, name: &'n str, value: U)
                   -> UniformsStorage<'n, U, UniformsStorage<'n, T, R>>
-                  where U: AsUniformValue
+                  where U: UniformMembers
     {
         UniformsStorage {
             name,
@@ -46,10 +50,145 @@ impl<'n, T, R> UniformsStorage<'n, T, R> where T: AsUniformValue, R: Uniforms {
     }
 }
 
-impl<'n, T, R> Uniforms for UniformsStorage<'n, T, R> where T: AsUniformValue, R: Uniforms {
+impl<'n, T, R> Uniforms for UniformsStorage<'n, T, R> where T: UniformMembers, R: Uniforms {
     #[inline]
     fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, mut output: F) {
-        output(self.name, self.value.as_uniform_value());
+        self.value.visit_members(self.name, &mut output);
         self.rest.visit_values(output);
     }
 }
+
+/// Stores uniforms whose names are only known at runtime.
+///
+/// The values are borrowed. See `OwnedUniforms` for a container that owns its values.
+#[derive(Clone, Default)]
+pub struct DynamicUniforms<'a, 's> {
+    map: HashMap<Cow<'s, str>, UniformValue<'a>>,
+}
+
+impl<'a, 's> DynamicUniforms<'a, 's> {
+    /// Builds an empty storage.
+    #[inline]
+    pub fn new() -> DynamicUniforms<'a, 's> {
+        DynamicUniforms {
+            map: HashMap::new(),
+        }
+    }
+
+    /// Adds a value to the storage. Replaces any previous value with the same name.
+    #[inline]
+    pub fn add(&mut self, name: &'s str, value: &'a dyn AsUniformValue) {
+        self.map.insert(Cow::Borrowed(name), value.as_uniform_value());
+    }
+
+    /// Adds a slice or a struct to the storage. Its members are added as `name[0]`,
+    /// `name.field`, etc.
+    pub fn add_members<T: ?Sized>(&mut self, name: &str, value: &'a T) where T: UniformMembers {
+        let map = &mut self.map;
+        value.visit_members(name, &mut |name, value| {
+            map.insert(Cow::Owned(name.to_owned()), value);
+        });
+    }
+}
+
+impl<'a, 's> Uniforms for DynamicUniforms<'a, 's> {
+    #[inline]
+    fn visit_values<'b, F: FnMut(&str, UniformValue<'b>)>(&'b self, mut output: F) {
+        for (name, value) in self.map.iter() {
+            output(name, *value);
+        }
+    }
+}
+
+/// Stores uniforms whose names are only known at runtime, and that owns its values.
+///
+/// Textures can be stored in an `Rc`, or in an `OwnedSampler` to customize the way they are
+/// sampled. Cloning an `OwnedUniforms` is cheap, as the values are reference-counted.
+///
+/// The members of GLSL structs and arrays are stored under their full names, for example
+/// `material.albedo` or `lights[2].color`. `add_array` and `add_nested` build these names.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::rc::Rc;
+/// # let albedo: glium::texture::Texture2d = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// let mut material = glium::uniforms::OwnedUniforms::new();
+/// material.add("albedo", Rc::new(albedo));
+/// material.add("roughness", 0.5f32);
+///
+/// let mut uniforms = glium::uniforms::OwnedUniforms::new();
+/// uniforms.add_nested("material", &material);
+/// uniforms.add_array("weights", vec![0.25f32, 0.75]);
+///
+/// // overrides the roughness for this object
+/// let mut overrides = glium::uniforms::OwnedUniforms::new();
+/// overrides.add("material.roughness", 0.9f32);
+/// uniforms.merge(&overrides);
+/// ```
+#[derive(Clone, Default)]
+pub struct OwnedUniforms {
+    map: HashMap<String, Rc<dyn AsUniformValue>>,
+}
+
+impl OwnedUniforms {
+    /// Builds an empty storage.
+    #[inline]
+    pub fn new() -> OwnedUniforms {
+        OwnedUniforms {
+            map: HashMap::new(),
+        }
+    }
+
+    /// Adds a value to the storage. Replaces any previous value with the same name.
+    #[inline]
+    pub fn add<S, T>(&mut self, name: S, value: T) where S: Into<String>, T: AsUniformValue + 'static {
+        self.map.insert(name.into(), Rc::new(value));
+    }
+
+    /// Adds the elements of an array as `name[0]`, `name[1]`, etc.
+    pub fn add_array<I, T>(&mut self, name: &str, values: I)
+                           where I: IntoIterator<Item = T>, T: AsUniformValue + 'static
+    {
+        for (index, value) in values.into_iter().enumerate() {
+            self.add(format!("{}[{}]", name, index), value);
+        }
+    }
+
+    /// Adds all the values of `other` as the members of a struct, in other words as
+    /// `name.member`.
+    pub fn add_nested(&mut self, name: &str, other: &OwnedUniforms) {
+        for (member, value) in other.map.iter() {
+            self.map.insert(format!("{}.{}", name, member), value.clone());
+        }
+    }
+
+    /// Adds all the values of `other`. The values of `other` replace the values of `self` that
+    /// have the same name.
+    pub fn merge(&mut self, other: &OwnedUniforms) {
+        for (name, value) in other.map.iter() {
+            self.map.insert(name.clone(), value.clone());
+        }
+    }
+
+    /// Removes a value from the storage. Returns `false` if there was no value with this name.
+    #[inline]
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.map.remove(name).is_some()
+    }
+
+    /// Returns true if the storage contains a value with this name.
+    #[inline]
+    pub fn contains(&self, name: &str) -> bool {
+        self.map.contains_key(name)
+    }
+}
+
+impl Uniforms for OwnedUniforms {
+    #[inline]
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, mut output: F) {
+        for (name, value) in self.map.iter() {
+            output(name, value.as_uniform_value());
+        }
+    }
+}
@@ -4,6 +4,8 @@ use crate::program::ShaderStage;
 use crate::texture;
 
 use crate::uniforms::AsUniformValue;
+use crate::uniforms::ImageUnit;
+use crate::uniforms::ImageUnitAccess;
 use crate::uniforms::LayoutMismatchError;
 use crate::uniforms::UniformBlock;
 use crate::uniforms::SamplerBehavior;
@@ -133,6 +135,32 @@ pub enum UniformType {
     AtomicCounterUint,
 }
 
+impl UniformType {
+    /// Returns true if the type is a sampler, in other words if the uniform must be bound to a
+    /// texture unit.
+    pub fn is_sampler(&self) -> bool {
+        matches!(*self,
+            UniformType::Sampler1d | UniformType::ISampler1d | UniformType::USampler1d |
+            UniformType::Sampler2d | UniformType::ISampler2d | UniformType::USampler2d |
+            UniformType::Sampler3d | UniformType::ISampler3d | UniformType::USampler3d |
+            UniformType::Sampler1dArray | UniformType::ISampler1dArray |
+            UniformType::USampler1dArray | UniformType::Sampler2dArray |
+            UniformType::ISampler2dArray | UniformType::USampler2dArray |
+            UniformType::SamplerCube | UniformType::ISamplerCube | UniformType::USamplerCube |
+            UniformType::Sampler2dRect | UniformType::ISampler2dRect |
+            UniformType::USampler2dRect | UniformType::Sampler2dRectShadow |
+            UniformType::SamplerCubeArray | UniformType::ISamplerCubeArray |
+            UniformType::USamplerCubeArray | UniformType::SamplerBuffer |
+            UniformType::ISamplerBuffer | UniformType::USamplerBuffer |
+            UniformType::Sampler2dMultisample | UniformType::ISampler2dMultisample |
+            UniformType::USampler2dMultisample | UniformType::Sampler2dMultisampleArray |
+            UniformType::ISampler2dMultisampleArray | UniformType::USampler2dMultisampleArray |
+            UniformType::Sampler1dShadow | UniformType::Sampler2dShadow |
+            UniformType::SamplerCubeShadow | UniformType::Sampler1dArrayShadow |
+            UniformType::Sampler2dArrayShadow | UniformType::SamplerCubeArrayShadow)
+    }
+}
+
 /// Represents a value to bind to a uniform.
 #[allow(missing_docs)]
 #[derive(Copy)]
@@ -142,6 +170,10 @@ pub enum UniformValue<'a> {
     /// The last parameter is a sender which must be used to send a `SyncFence` that expires when
     /// the buffer has finished being used.
     Block(BufferAnySlice<'a>, fn(&program::UniformBlock) -> Result<(), LayoutMismatchError>),
+    /// Same as `Block`, but can only be bound to a shader storage block. Drawing returns an
+    /// error if the program doesn't have a shader storage block with this name.
+    ShaderStorageBlock(BufferAnySlice<'a>,
+                       fn(&program::UniformBlock) -> Result<(), LayoutMismatchError>),
     Subroutine(ShaderStage, &'a str),
     SignedInt(i32),
     UnsignedInt(u32),
@@ -187,6 +219,7 @@ pub enum UniformValue<'a> {
     IntegralTexture1d(&'a texture::IntegralTexture1d, Option<SamplerBehavior>),
     UnsignedTexture1d(&'a texture::UnsignedTexture1d, Option<SamplerBehavior>),
     DepthTexture1d(&'a texture::DepthTexture1d, Option<SamplerBehavior>),
+    DepthStencilTexture1d(&'a texture::DepthStencilTexture1d, Option<SamplerBehavior>),
     Texture2d(&'a texture::Texture2d, Option<SamplerBehavior>),
     CompressedTexture2d(&'a texture::CompressedTexture2d, Option<SamplerBehavior>),
     SrgbTexture2d(&'a texture::SrgbTexture2d, Option<SamplerBehavior>),
@@ -194,11 +227,13 @@ pub enum UniformValue<'a> {
     IntegralTexture2d(&'a texture::IntegralTexture2d, Option<SamplerBehavior>),
     UnsignedTexture2d(&'a texture::UnsignedTexture2d, Option<SamplerBehavior>),
     DepthTexture2d(&'a texture::DepthTexture2d, Option<SamplerBehavior>),
+    DepthStencilTexture2d(&'a texture::DepthStencilTexture2d, Option<SamplerBehavior>),
     Texture2dMultisample(&'a texture::Texture2dMultisample, Option<SamplerBehavior>),
     SrgbTexture2dMultisample(&'a texture::SrgbTexture2dMultisample, Option<SamplerBehavior>),
     IntegralTexture2dMultisample(&'a texture::IntegralTexture2dMultisample, Option<SamplerBehavior>),
     UnsignedTexture2dMultisample(&'a texture::UnsignedTexture2dMultisample, Option<SamplerBehavior>),
     DepthTexture2dMultisample(&'a texture::DepthTexture2dMultisample, Option<SamplerBehavior>),
+    DepthStencilTexture2dMultisample(&'a texture::DepthStencilTexture2dMultisample, Option<SamplerBehavior>),
     Texture3d(&'a texture::Texture3d, Option<SamplerBehavior>),
     CompressedTexture3d(&'a texture::CompressedTexture3d, Option<SamplerBehavior>),
     SrgbTexture3d(&'a texture::SrgbTexture3d, Option<SamplerBehavior>),
@@ -206,6 +241,7 @@ pub enum UniformValue<'a> {
     IntegralTexture3d(&'a texture::IntegralTexture3d, Option<SamplerBehavior>),
     UnsignedTexture3d(&'a texture::UnsignedTexture3d, Option<SamplerBehavior>),
     DepthTexture3d(&'a texture::DepthTexture3d, Option<SamplerBehavior>),
+    DepthStencilTexture3d(&'a texture::DepthStencilTexture3d, Option<SamplerBehavior>),
     Texture1dArray(&'a texture::Texture1dArray, Option<SamplerBehavior>),
     CompressedTexture1dArray(&'a texture::CompressedTexture1dArray, Option<SamplerBehavior>),
     SrgbTexture1dArray(&'a texture::SrgbTexture1dArray, Option<SamplerBehavior>),
@@ -213,6 +249,7 @@ pub enum UniformValue<'a> {
     IntegralTexture1dArray(&'a texture::IntegralTexture1dArray, Option<SamplerBehavior>),
     UnsignedTexture1dArray(&'a texture::UnsignedTexture1dArray, Option<SamplerBehavior>),
     DepthTexture1dArray(&'a texture::DepthTexture1dArray, Option<SamplerBehavior>),
+    DepthStencilTexture1dArray(&'a texture::DepthStencilTexture1dArray, Option<SamplerBehavior>),
     Texture2dArray(&'a texture::Texture2dArray, Option<SamplerBehavior>),
     CompressedTexture2dArray(&'a texture::CompressedTexture2dArray, Option<SamplerBehavior>),
     SrgbTexture2dArray(&'a texture::SrgbTexture2dArray, Option<SamplerBehavior>),
@@ -220,11 +257,13 @@ pub enum UniformValue<'a> {
     IntegralTexture2dArray(&'a texture::IntegralTexture2dArray, Option<SamplerBehavior>),
     UnsignedTexture2dArray(&'a texture::UnsignedTexture2dArray, Option<SamplerBehavior>),
     DepthTexture2dArray(&'a texture::DepthTexture2dArray, Option<SamplerBehavior>),
+    DepthStencilTexture2dArray(&'a texture::DepthStencilTexture2dArray, Option<SamplerBehavior>),
     Texture2dMultisampleArray(&'a texture::Texture2dMultisampleArray, Option<SamplerBehavior>),
     SrgbTexture2dMultisampleArray(&'a texture::SrgbTexture2dMultisampleArray, Option<SamplerBehavior>),
     IntegralTexture2dMultisampleArray(&'a texture::IntegralTexture2dMultisampleArray, Option<SamplerBehavior>),
     UnsignedTexture2dMultisampleArray(&'a texture::UnsignedTexture2dMultisampleArray, Option<SamplerBehavior>),
     DepthTexture2dMultisampleArray(&'a texture::DepthTexture2dMultisampleArray, Option<SamplerBehavior>),
+    DepthStencilTexture2dMultisampleArray(&'a texture::DepthStencilTexture2dMultisampleArray, Option<SamplerBehavior>),
     Cubemap(&'a texture::Cubemap, Option<SamplerBehavior>),
     CompressedCubemap(&'a texture::CompressedCubemap, Option<SamplerBehavior>),
     SrgbCubemap(&'a texture::SrgbCubemap, Option<SamplerBehavior>),
@@ -232,6 +271,7 @@ pub enum UniformValue<'a> {
     IntegralCubemap(&'a texture::IntegralCubemap, Option<SamplerBehavior>),
     UnsignedCubemap(&'a texture::UnsignedCubemap, Option<SamplerBehavior>),
     DepthCubemap(&'a texture::DepthCubemap, Option<SamplerBehavior>),
+    DepthStencilCubemap(&'a texture::DepthStencilCubemap, Option<SamplerBehavior>),
     CubemapArray(&'a texture::CubemapArray, Option<SamplerBehavior>),
     CompressedCubemapArray(&'a texture::CompressedCubemapArray, Option<SamplerBehavior>),
     SrgbCubemapArray(&'a texture::SrgbCubemapArray, Option<SamplerBehavior>),
@@ -239,7 +279,11 @@ pub enum UniformValue<'a> {
     IntegralCubemapArray(&'a texture::IntegralCubemapArray, Option<SamplerBehavior>),
     UnsignedCubemapArray(&'a texture::UnsignedCubemapArray, Option<SamplerBehavior>),
     DepthCubemapArray(&'a texture::DepthCubemapArray, Option<SamplerBehavior>),
+    DepthStencilCubemapArray(&'a texture::DepthStencilCubemapArray, Option<SamplerBehavior>),
     BufferTexture(texture::buffer_texture::BufferTextureRef<'a>),
+    BufferTextureImage(texture::buffer_texture::BufferTextureRef<'a>, ImageUnitAccess),
+    TextureImage(ImageUnit<'a>),
+    BindlessTexture(texture::TextureHandle<'a>),
 }
 
 impl<'a> Clone for UniformValue<'a> {
@@ -287,6 +331,15 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::UnsignedTexture1d(_, _), UniformType::USampler1d) => true,
             (&UniformValue::DepthTexture1d(_, _), UniformType::Sampler1d) => true,
             (&UniformValue::DepthTexture1d(_, _), UniformType::Sampler1dShadow) => true,
+            (&UniformValue::DepthStencilTexture1d(tex, _), UniformType::Sampler1d) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Depth
+            },
+            (&UniformValue::DepthStencilTexture1d(tex, _), UniformType::Sampler1dShadow) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Depth
+            },
+            (&UniformValue::DepthStencilTexture1d(tex, _), UniformType::USampler1d) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Stencil
+            },
             (&UniformValue::Texture2d(_, _), UniformType::Sampler2d) => true,
             (&UniformValue::CompressedTexture2d(_, _), UniformType::Sampler2d) => true,
             (&UniformValue::SrgbTexture2d(_, _), UniformType::Sampler2d) => true,
@@ -295,6 +348,15 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::UnsignedTexture2d(_, _), UniformType::USampler2d) => true,
             (&UniformValue::DepthTexture2d(_, _), UniformType::Sampler2d) => true,
             (&UniformValue::DepthTexture2d(_, _), UniformType::Sampler2dShadow) => true,
+            (&UniformValue::DepthStencilTexture2d(tex, _), UniformType::Sampler2d) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Depth
+            },
+            (&UniformValue::DepthStencilTexture2d(tex, _), UniformType::Sampler2dShadow) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Depth
+            },
+            (&UniformValue::DepthStencilTexture2d(tex, _), UniformType::USampler2d) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Stencil
+            },
             (&UniformValue::Texture3d(_, _), UniformType::Sampler3d) => true,
             (&UniformValue::CompressedTexture3d(_, _), UniformType::Sampler3d) => true,
             (&UniformValue::SrgbTexture3d(_, _), UniformType::Sampler3d) => true,
@@ -302,6 +364,12 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::IntegralTexture3d(_, _), UniformType::ISampler3d) => true,
             (&UniformValue::UnsignedTexture3d(_, _), UniformType::USampler3d) => true,
             (&UniformValue::DepthTexture3d(_, _), UniformType::Sampler3d) => true,
+            (&UniformValue::DepthStencilTexture3d(tex, _), UniformType::Sampler3d) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Depth
+            },
+            (&UniformValue::DepthStencilTexture3d(tex, _), UniformType::USampler3d) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Stencil
+            },
             (&UniformValue::Texture1dArray(_, _), UniformType::Sampler1dArray) => true,
             (&UniformValue::CompressedTexture1dArray(_, _), UniformType::Sampler1dArray) => true,
             (&UniformValue::SrgbTexture1dArray(_, _), UniformType::Sampler1dArray) => true,
@@ -310,6 +378,15 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::UnsignedTexture1dArray(_, _), UniformType::USampler1dArray) => true,
             (&UniformValue::DepthTexture1dArray(_, _), UniformType::Sampler1dArray) => true,
             (&UniformValue::DepthTexture1dArray(_, _), UniformType::Sampler1dArrayShadow) => true,
+            (&UniformValue::DepthStencilTexture1dArray(tex, _), UniformType::Sampler1dArray) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Depth
+            },
+            (&UniformValue::DepthStencilTexture1dArray(tex, _), UniformType::Sampler1dArrayShadow) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Depth
+            },
+            (&UniformValue::DepthStencilTexture1dArray(tex, _), UniformType::USampler1dArray) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Stencil
+            },
             (&UniformValue::Texture2dArray(_, _), UniformType::Sampler2dArray) => true,
             (&UniformValue::CompressedTexture2dArray(_, _), UniformType::Sampler2dArray) => true,
             (&UniformValue::SrgbTexture2dArray(_, _), UniformType::Sampler2dArray) => true,
@@ -318,6 +395,15 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::UnsignedTexture2dArray(_, _), UniformType::USampler2dArray) => true,
             (&UniformValue::DepthTexture2dArray(_, _), UniformType::Sampler2dArray) => true,
             (&UniformValue::DepthTexture2dArray(_, _), UniformType::Sampler2dArrayShadow) => true,
+            (&UniformValue::DepthStencilTexture2dArray(tex, _), UniformType::Sampler2dArray) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Depth
+            },
+            (&UniformValue::DepthStencilTexture2dArray(tex, _), UniformType::Sampler2dArrayShadow) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Depth
+            },
+            (&UniformValue::DepthStencilTexture2dArray(tex, _), UniformType::USampler2dArray) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Stencil
+            },
             (&UniformValue::Cubemap(_, _), UniformType::SamplerCube) => true,
             (&UniformValue::CompressedCubemap(_, _), UniformType::SamplerCube) => true,
             (&UniformValue::SrgbCubemap(_, _), UniformType::SamplerCube) => true,
@@ -326,6 +412,15 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::UnsignedCubemap(_, _), UniformType::USamplerCube) => true,
             (&UniformValue::DepthCubemap(_, _), UniformType::SamplerCube) => true,
             (&UniformValue::DepthCubemap(_, _), UniformType::SamplerCubeShadow) => true,
+            (&UniformValue::DepthStencilCubemap(tex, _), UniformType::SamplerCube) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Depth
+            },
+            (&UniformValue::DepthStencilCubemap(tex, _), UniformType::SamplerCubeShadow) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Depth
+            },
+            (&UniformValue::DepthStencilCubemap(tex, _), UniformType::USamplerCube) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Stencil
+            },
             (&UniformValue::CubemapArray(_, _), UniformType::SamplerCubeArray) => true,
             (&UniformValue::CompressedCubemapArray(_, _), UniformType::SamplerCubeArray) => true,
             (&UniformValue::SrgbCubemapArray(_, _), UniformType::SamplerCubeArray) => true,
@@ -334,6 +429,15 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::UnsignedCubemapArray(_, _), UniformType::USamplerCubeArray) => true,
             (&UniformValue::DepthCubemapArray(_, _), UniformType::SamplerCubeArray) => true,
             (&UniformValue::DepthCubemapArray(_, _), UniformType::SamplerCubeArrayShadow) => true,
+            (&UniformValue::DepthStencilCubemapArray(tex, _), UniformType::SamplerCubeArray) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Depth
+            },
+            (&UniformValue::DepthStencilCubemapArray(tex, _), UniformType::SamplerCubeArrayShadow) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Depth
+            },
+            (&UniformValue::DepthStencilCubemapArray(tex, _), UniformType::USamplerCubeArray) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Stencil
+            },
             (&UniformValue::BufferTexture(tex), UniformType::SamplerBuffer) => {
                 tex.get_texture_type() == texture::buffer_texture::BufferTextureType::Float
             },
@@ -343,11 +447,34 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::BufferTexture(tex), UniformType::USamplerBuffer) => {
                 tex.get_texture_type() == texture::buffer_texture::BufferTextureType::Unsigned
             },
+            (&UniformValue::BufferTextureImage(tex, _), UniformType::ImageBuffer) => {
+                tex.get_texture_type() == texture::buffer_texture::BufferTextureType::Float
+            },
+            (&UniformValue::BufferTextureImage(tex, _), UniformType::IImageBuffer) => {
+                tex.get_texture_type() == texture::buffer_texture::BufferTextureType::Integral
+            },
+            (&UniformValue::BufferTextureImage(tex, _), UniformType::UImageBuffer) => {
+                tex.get_texture_type() == texture::buffer_texture::BufferTextureType::Unsigned
+            },
+            (&UniformValue::TextureImage(ref image), ref ty) => image.is_usable_with(ty),
+            (&UniformValue::BindlessTexture(_), ref ty) => ty.is_sampler(),
             (&UniformValue::Texture2dMultisample(..), UniformType::Sampler2dMultisample) => true,
             (&UniformValue::SrgbTexture2dMultisample(..), UniformType::Sampler2dMultisample) => true,
             (&UniformValue::IntegralTexture2dMultisample(..), UniformType::ISampler2dMultisample) => true,
             (&UniformValue::UnsignedTexture2dMultisample(..), UniformType::USampler2dMultisample) => true,
             (&UniformValue::DepthTexture2dMultisample(..), UniformType::Sampler2dMultisample) => true,
+            (&UniformValue::DepthStencilTexture2dMultisample(tex, _), UniformType::Sampler2dMultisample) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Depth
+            },
+            (&UniformValue::DepthStencilTexture2dMultisample(tex, _), UniformType::USampler2dMultisample) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Stencil
+            },
+            (&UniformValue::DepthStencilTexture2dMultisampleArray(tex, _), UniformType::Sampler2dMultisampleArray) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Depth
+            },
+            (&UniformValue::DepthStencilTexture2dMultisampleArray(tex, _), UniformType::USampler2dMultisampleArray) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilTextureMode::Stencil
+            },
             _ => false,
         }
     }
@@ -42,11 +42,25 @@ impl PartialOrd for Version {
 pub unsafe fn get_gl_version(gl: &gl::Gl) -> Version {
     let version = gl.GetString(gl::VERSION);
     let version = String::from_utf8(CStr::from_ptr(version as *const _).to_bytes().to_vec()).unwrap();
+    parse_gl_version(&version)
+}
 
-    // for the moment we mock WebGL as OpenGL ES 2.0
-    // TODO: handle the differences between WebGL and OpenGL ES
+/// Parses the string returned by `glGetString(GL_VERSION)`.
+///
+/// WebGL 1.0 is based on OpenGL ES 2.0 and WebGL 2.0 on OpenGL ES 3.0, so they are reported as
+/// these versions. The features that WebGL doesn't have, like geometry shaders or shader storage
+/// buffers, are then unavailable like with the corresponding version of OpenGL ES.
+///
+/// # Panic
+///
+/// Panics if the string doesn't contain a version number.
+fn parse_gl_version(version: &str) -> Version {
+    // for example "WebGL 2.0 (OpenGL ES 3.0 Chromium)"
     if version.starts_with("WebGL ") {
-        return Version(Api::GlEs, 2, 0);
+        return match version[6..].split(' ').next() {
+            Some(v) if v.starts_with("1.") => Version(Api::GlEs, 2, 0),
+            _ => Version(Api::GlEs, 3, 0),
+        };
     }
 
     let (version, api) = if version.starts_with("OpenGL ES ") {
@@ -113,7 +127,7 @@ pub fn get_supported_glsl_version(gl_version: &Version) -> Version {
 
 #[cfg(test)]
 mod tests {
-    use super::{Version, Api, get_supported_glsl_version};
+    use super::{Version, Api, get_supported_glsl_version, parse_gl_version};
 
     macro_rules! assert_versions {
         ( $api:path, $gl_major:expr, $gl_minor:expr => $glsl_major:expr, $glsl_minor:expr) => {
@@ -149,6 +163,24 @@ mod tests {
         assert_versions!(Api::GlEs, 3, 1 => 3, 1);
     }
 
+    #[test]
+    fn parse_version_strings() {
+        assert_eq!(parse_gl_version("4.6.0 NVIDIA 455.28"), Version(Api::Gl, 4, 6));
+        assert_eq!(parse_gl_version("3.3 (Core Profile) Mesa 20.2.1"), Version(Api::Gl, 3, 3));
+        assert_eq!(parse_gl_version("OpenGL ES 3.2 Mesa 20.2.1"), Version(Api::GlEs, 3, 2));
+        assert_eq!(parse_gl_version("OpenGL ES-CM 1.1"), Version(Api::GlEs, 1, 1));
+    }
+
+    #[test]
+    fn parse_webgl_version_strings() {
+        assert_eq!(parse_gl_version("WebGL 1.0"), Version(Api::GlEs, 2, 0));
+        assert_eq!(parse_gl_version("WebGL 1.0 (OpenGL ES 2.0 Chromium)"),
+                   Version(Api::GlEs, 2, 0));
+        assert_eq!(parse_gl_version("WebGL 2.0"), Version(Api::GlEs, 3, 0));
+        assert_eq!(parse_gl_version("WebGL 2.0 (OpenGL ES 3.0 Chromium)"),
+                   Version(Api::GlEs, 3, 0));
+    }
+
     #[test]
     #[should_panic]
     fn invalid_gl_version() {
@@ -1,9 +1,14 @@
 use std::error::Error;
+use std::cmp;
 use std::fmt;
+use std::mem;
 use std::ops::{Deref, DerefMut};
 use crate::utils::range::RangeArgument;
 
 use crate::buffer::{Buffer, BufferSlice, BufferMutSlice, BufferAny, BufferType, BufferMode, BufferCreationError, Content};
+use crate::buffer::CopyError;
+use crate::buffer::DetachedBuffer;
+use crate::transfer::Transferable;
 use crate::vertex::{Vertex, VerticesSource, PerInstance};
 use crate::vertex::format::VertexFormat;
 
@@ -87,8 +92,8 @@ impl<'b, T: 'b> VertexBufferSlice<'b, T> where T: Copy + Content {
     #[inline]
     pub fn per_instance(&'b self) -> Result<PerInstance<'_>, InstancingNotSupported> {
         // TODO: don't check this here
-        if !(self.get_context().get_version() >= &Version(Api::Gl, 3, 3)) &&
-            !self.get_context().get_extensions().gl_arb_instanced_arrays
+        if !is_instancing_supported(self.get_context()) &&
+            !self.get_context().is_instancing_emulation_enabled()
         {
             return Err(InstancingNotSupported);
         }
@@ -159,6 +164,71 @@ impl<T> VertexBuffer<T> where T: Vertex {
         VertexBuffer::new_impl(facade, data, BufferMode::Immutable)
     }
 
+    /// Builds a new vertex buffer from the vertices produced by an iterator.
+    ///
+    /// The vertices are written directly to the buffer, without being collected in a temporary
+    /// container first. This is useful for procedurally generated meshes.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the iterator doesn't produce exactly as many elements as its `len()`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #[macro_use]
+    /// # extern crate glium;
+    /// # fn main() {
+    /// #[derive(Copy, Clone)]
+    /// struct Vertex {
+    ///     position: [f32; 2],
+    /// }
+    ///
+    /// implement_vertex!(Vertex, position);
+    ///
+    /// # let display: glium::Display = unsafe { ::std::mem::MaybeUninit::uninit().assume_init() };
+    /// let vertex_buffer = glium::VertexBuffer::from_exact_iter(&display, (0 .. 1000).map(|i| {
+    ///     let angle = i as f32 * 0.01;
+    ///     Vertex { position: [angle.cos(), angle.sin()] }
+    /// }));
+    /// # }
+    /// ```
+    #[inline]
+    pub fn from_exact_iter<F: ?Sized, I>(facade: &F, iter: I) -> Result<VertexBuffer<T>, CreationError>
+                                         where F: Facade, I: IntoIterator<Item = T>,
+                                               I::IntoIter: ExactSizeIterator
+    {
+        if !T::is_supported(facade) {
+            return Err(CreationError::FormatNotSupported);
+        }
+
+        let buffer = Buffer::from_exact_iter(facade, iter, BufferType::ArrayBuffer,
+                                             BufferMode::Default)?;
+        Ok(buffer.into())
+    }
+
+    /// Builds a new vertex buffer from the vertices produced by an iterator.
+    ///
+    /// Contrary to `from_exact_iter`, the iterator doesn't need to know its length in advance.
+    /// If it doesn't, the vertices are first collected in a `Vec`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the lower and upper bounds of the `size_hint` of the iterator are equal but the
+    /// iterator doesn't produce exactly this number of elements.
+    #[inline]
+    pub fn from_iter<F: ?Sized, I>(facade: &F, iter: I) -> Result<VertexBuffer<T>, CreationError>
+                                   where F: Facade, I: IntoIterator<Item = T>
+    {
+        if !T::is_supported(facade) {
+            return Err(CreationError::FormatNotSupported);
+        }
+
+        let buffer = Buffer::from_iter(facade, iter, BufferType::ArrayBuffer,
+                                       BufferMode::Default)?;
+        Ok(buffer.into())
+    }
+
     #[inline]
     fn new_impl<F: ?Sized>(facade: &F, data: &[T], mode: BufferMode)
                    -> Result<VertexBuffer<T>, CreationError>
@@ -239,11 +309,11 @@ impl<T> VertexBuffer<T> where T: Copy {
     /// use std::borrow::Cow;
     ///
     /// let bindings = Cow::Owned(vec![(
-    ///         Cow::Borrowed("position"), 0,
+    ///         Cow::Borrowed("position"), 0, None,
     ///         glium::vertex::AttributeType::F32F32,
     ///         false,
     ///     ), (
-    ///         Cow::Borrowed("color"), 2 * ::std::mem::size_of::<f32>(),
+    ///         Cow::Borrowed("color"), 2 * ::std::mem::size_of::<f32>(), None,
     ///         glium::vertex::AttributeType::F32,
     ///         false,
     ///     ),
@@ -323,8 +393,8 @@ impl<T> VertexBuffer<T> where T: Copy {
     #[inline]
     pub fn per_instance(&self) -> Result<PerInstance<'_>, InstancingNotSupported> {
         // TODO: don't check this here
-        if !(self.buffer.get_context().get_version() >= &Version(Api::Gl, 3, 3)) &&
-            !self.buffer.get_context().get_extensions().gl_arb_instanced_arrays
+        if !is_instancing_supported(self.buffer.get_context()) &&
+            !self.buffer.get_context().is_instancing_emulation_enabled()
         {
             return Err(InstancingNotSupported);
         }
@@ -363,6 +433,25 @@ impl<T> From<Buffer<[T]>> for VertexBuffer<T> where T: Vertex + Copy {
     }
 }
 
+impl<T> Transferable for VertexBuffer<T> where T: Copy {
+    type Detached = (DetachedBuffer, VertexFormat);
+
+    #[inline]
+    fn detach(self) -> (DetachedBuffer, VertexFormat) {
+        (self.buffer.detach(), self.bindings)
+    }
+
+    #[inline]
+    unsafe fn attach<F: ?Sized>(facade: &F, (buffer, bindings): (DetachedBuffer, VertexFormat))
+                                -> VertexBuffer<T> where F: Facade
+    {
+        VertexBuffer {
+            buffer: Buffer::attach(facade, buffer),
+            bindings,
+        }
+    }
+}
+
 impl<T> Deref for VertexBuffer<T> where T: Copy {
     type Target = Buffer<[T]>;
 
@@ -464,10 +553,60 @@ impl VertexBufferAny {
         &self.bindings
     }
 
+    /// Returns true if the content of this buffer can be interpreted as elements of type `T`.
+    ///
+    /// This is the case if the size of the elements matches and if `T::build_bindings()` returns
+    /// the same bindings as the ones of this buffer.
+    #[inline]
+    pub fn is_of_type<T: Vertex>(&self) -> bool {
+        self.buffer.get_elements_size() == mem::size_of::<T>() &&
+        self.bindings == <T as Vertex>::build_bindings()
+    }
+
+    /// Turns the vertex buffer into a `VertexBuffer`.
+    ///
+    /// Gives back the buffer if its content doesn't match `T`. See `is_of_type`.
+    #[inline]
+    pub fn into_vertex_buffer<T>(self) -> Result<VertexBuffer<T>, VertexBufferAny>
+                                 where T: Vertex + Copy + Send + 'static
+    {
+        if !self.is_of_type::<T>() {
+            return Err(self);
+        }
+
+        Ok(unsafe { self.into_vertex_buffer_unchecked() })
+    }
+
     /// Turns the vertex buffer into a `VertexBuffer` without checking the type.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if the size of `T` doesn't match the size of the elements of the buffer.
     #[inline]
-    pub unsafe fn into_vertex_buffer<T: Copy>(self) -> VertexBuffer<T> {
-        unimplemented!();
+    pub unsafe fn into_vertex_buffer_unchecked<T: Copy + Send + 'static>(self) -> VertexBuffer<T> {
+        let VertexBufferAny { buffer, bindings } = self;
+
+        VertexBuffer {
+            buffer: buffer.into_typed(),
+            bindings,
+        }
+    }
+
+    /// Builds a typed slice containing the whole buffer.
+    ///
+    /// Returns `None` if the content of this buffer doesn't match `T`. See `is_of_type`.
+    #[inline]
+    pub fn as_slice_of<T>(&self) -> Option<VertexBufferSlice<'_, T>>
+                          where T: Vertex + Copy + Send + 'static
+    {
+        if !self.is_of_type::<T>() {
+            return None;
+        }
+
+        Some(VertexBufferSlice {
+            buffer: unsafe { self.buffer.as_typed_slice() },
+            bindings: &self.bindings,
+        })
     }
 
     /// Creates a marker that instructs glium to use multiple instances.
@@ -479,8 +618,8 @@ impl VertexBufferAny {
     #[inline]
     pub fn per_instance(&self) -> Result<PerInstance<'_>, InstancingNotSupported> {
         // TODO: don't check this here
-        if !(self.buffer.get_context().get_version() >= &Version(Api::Gl, 3, 3)) &&
-            !self.buffer.get_context().get_extensions().gl_arb_instanced_arrays
+        if !is_instancing_supported(self.buffer.get_context()) &&
+            !self.buffer.get_context().is_instancing_emulation_enabled()
         {
             return Err(InstancingNotSupported);
         }
@@ -530,3 +669,211 @@ impl<'a> Into<VerticesSource<'a>> for &'a VertexBufferAny {
 /// Instancing is not supported by the backend.
 #[derive(Debug, Copy, Clone)]
 pub struct InstancingNotSupported;
+
+/// Returns true if the backend supports per-instance attributes without emulation.
+///
+/// See `Context::set_instancing_emulation`.
+#[inline]
+pub fn is_instancing_supported<C: ?Sized>(caps: &C) -> bool where C: CapabilitiesSource {
+    caps.get_version() >= &Version(Api::Gl, 3, 3) || caps.get_extensions().gl_arb_instanced_arrays
+}
+
+/// Error that can happen when growing a `GrowableVertexBuffer`.
+#[derive(Copy, Clone, Debug)]
+pub enum GrowError {
+    /// Error while creating the new vertex buffer.
+    CreationError(CreationError),
+
+    /// Error while copying the existing vertices to the new vertex buffer.
+    CopyError(CopyError),
+}
+
+impl From<CreationError> for GrowError {
+    #[inline]
+    fn from(err: CreationError) -> GrowError {
+        GrowError::CreationError(err)
+    }
+}
+
+impl From<CopyError> for GrowError {
+    #[inline]
+    fn from(err: CopyError) -> GrowError {
+        GrowError::CopyError(err)
+    }
+}
+
+impl fmt::Display for GrowError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::GrowError::*;
+        let desc = match self {
+            CreationError(_) => "Error while creating the new vertex buffer",
+            CopyError(_) => "Error while copying the existing vertices to the new vertex buffer",
+        };
+        fmt.write_str(desc)
+    }
+}
+
+impl Error for GrowError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use self::GrowError::*;
+        match *self {
+            CreationError(ref error) => Some(error),
+            CopyError(ref error) => Some(error),
+        }
+    }
+}
+
+/// A vertex buffer whose content can grow.
+///
+/// The vertices are stored in a `VertexBuffer` whose size is the capacity of the growable
+/// buffer. When pushing more vertices than the capacity allows, a new buffer at least twice as
+/// large is created and the existing vertices are copied to it on the GPU.
+///
+/// This is useful for immediate-mode style rendering, where the number of vertices changes
+/// from frame to frame. Calling `clear` at the start of each frame keeps the same buffer
+/// around, so that no allocation happens once the buffer is large enough.
+///
+/// Growing requires copying between buffers, which isn't supported by all backends. See
+/// `GrowError::CopyError`.
+///
+/// # Example
+///
+/// ```no_run
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// #[derive(Copy, Clone)]
+/// struct Vertex {
+///     position: [f32; 2],
+/// }
+///
+/// implement_vertex!(Vertex, position);
+///
+/// # let display: glium::Display = unsafe { ::std::mem::MaybeUninit::uninit().assume_init() };
+/// let mut vertices = glium::vertex::GrowableVertexBuffer::new(&display).unwrap();
+///
+/// vertices.clear();
+/// vertices.push_slice(&[
+///     Vertex { position: [0.0, 0.0] },
+///     Vertex { position: [1.0, 0.0] },
+///     Vertex { position: [0.0, 1.0] },
+/// ]).unwrap();
+///
+/// // `vertices.as_slice()` can now be passed to `draw`
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct GrowableVertexBuffer<T> where T: Copy {
+    buffer: VertexBuffer<T>,
+    len: usize,
+}
+
+impl<T> GrowableVertexBuffer<T> where T: Vertex {
+    /// Builds a new empty growable vertex buffer.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F) -> Result<GrowableVertexBuffer<T>, CreationError>
+                  where F: Facade
+    {
+        GrowableVertexBuffer::with_capacity(facade, 0)
+    }
+
+    /// Builds a new empty growable vertex buffer with room for `capacity` vertices.
+    #[inline]
+    pub fn with_capacity<F: ?Sized>(facade: &F, capacity: usize)
+                                    -> Result<GrowableVertexBuffer<T>, CreationError>
+                                    where F: Facade
+    {
+        Ok(GrowableVertexBuffer {
+            buffer: VertexBuffer::empty_dynamic(facade, capacity)?,
+            len: 0,
+        })
+    }
+
+    /// Makes sure that there is room for at least `additional` more vertices.
+    ///
+    /// If the capacity is too small, a new buffer is created and the existing vertices are
+    /// copied to it. The new capacity is at least twice the previous one.
+    pub fn reserve(&mut self, additional: usize) -> Result<(), GrowError> {
+        let required = self.len + additional;
+        if required <= self.capacity() {
+            return Ok(());
+        }
+
+        let new_capacity = cmp::max(required, self.capacity() * 2);
+        let new_buffer = VertexBuffer::empty_dynamic(self.buffer.get_context(), new_capacity)?;
+
+        if self.len != 0 {
+            self.buffer.slice(0 .. self.len).unwrap()
+                       .copy_to(new_buffer.slice(0 .. self.len).unwrap())?;
+        }
+
+        self.buffer = new_buffer;
+        Ok(())
+    }
+
+    /// Appends vertices at the end of the buffer, growing it if necessary.
+    pub fn push_slice(&mut self, data: &[T]) -> Result<(), GrowError> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        self.reserve(data.len())?;
+        self.buffer.slice_mut(self.len .. self.len + data.len()).unwrap().write(data);
+        self.len += data.len();
+        Ok(())
+    }
+}
+
+impl<T> GrowableVertexBuffer<T> where T: Copy {
+    /// Returns the number of vertices that have been pushed.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no vertex has been pushed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of vertices that the buffer can hold without growing.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Removes all the vertices, but keeps the underlying buffer.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Builds a slice containing the vertices that have been pushed.
+    #[inline]
+    pub fn as_slice(&self) -> VertexBufferSlice<'_, T> {
+        self.buffer.slice(0 .. self.len).unwrap()
+    }
+
+    /// Returns the underlying vertex buffer.
+    ///
+    /// Its length is the capacity of the growable buffer. Only the first `len()` vertices
+    /// have been pushed.
+    #[inline]
+    pub fn get_buffer(&self) -> &VertexBuffer<T> {
+        &self.buffer
+    }
+
+    /// Destroys the growable buffer and returns the underlying vertex buffer.
+    #[inline]
+    pub fn into_buffer(self) -> VertexBuffer<T> {
+        self.buffer
+    }
+}
+
+impl<'a, T> Into<VerticesSource<'a>> for &'a GrowableVertexBuffer<T> where T: Copy {
+    #[inline]
+    fn into(self) -> VerticesSource<'a> {
+        self.as_slice().into()
+    }
+}
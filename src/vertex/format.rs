@@ -12,7 +12,7 @@ use cgmath;
 use nalgebra;
 
 #[allow(missing_docs)]
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum AttributeType {
     I8,
     I8I8,
@@ -407,10 +407,15 @@ impl AttributeType {
 ///
 /// The first element is the name of the binding, the second element
 /// is the offset from the start of each vertex to this element, the
-/// third element is the type and the fourth element indicates whether
+/// third element is the explicit location of the attribute, the fourth
+/// element is the type and the fifth element indicates whether
 /// or not the element should use fixed-point normalization when
 /// binding in a VAO.
-pub type VertexFormat = Cow<'static, [(Cow<'static, str>, usize, AttributeType, bool)]>;
+///
+/// If the location is `None`, the attribute is bound by looking up its name in the program.
+/// Otherwise it is bound to the given location regardless of its name, which allows using
+/// shaders whose attribute names differ from the field names or aren't available at all.
+pub type VertexFormat = Cow<'static, [(Cow<'static, str>, usize, Option<u32>, AttributeType, bool)]>;
 
 unsafe impl Attribute for i8 {
     #[inline]
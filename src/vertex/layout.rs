@@ -0,0 +1,93 @@
+use std::mem;
+
+use smallvec::SmallVec;
+
+use crate::CapabilitiesSource;
+use crate::version::{Api, Version};
+use crate::vertex::{Vertex, VertexFormat};
+
+/// Describes the vertex sources of a draw call without the buffers that contain the data.
+///
+/// Each binding of the layout is made of a vertex format, the stride of the elements in the
+/// buffer, and an optional instancing divisor.
+///
+/// If the backend supports `GL_ARB_vertex_attrib_binding`, the vertex array objects that glium
+/// creates are keyed by the layout of the vertex sources and by the program, instead of the
+/// buffers themselves. Drawing with other buffers that have the same layout only rebinds the
+/// buffers. Otherwise a vertex array object is created for each combination of buffers.
+///
+/// # Example
+///
+/// ```no_run
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// # let display: glium::Display = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
+/// #[derive(Copy, Clone)]
+/// struct Vertex { position: [f32; 2] }
+/// implement_vertex!(Vertex, position);
+///
+/// #[derive(Copy, Clone)]
+/// struct Instance { offset: [f32; 2] }
+/// implement_vertex!(Instance, offset);
+///
+/// let layout = glium::vertex::VertexLayout::new()
+///                     .with_vertices::<Vertex>()
+///                     .with_per_instance::<Instance>();
+///
+/// if glium::vertex::VertexLayout::is_supported(&display) {
+///     // every draw call that uses a `VertexBuffer<Vertex>` and a per-instance
+///     // `VertexBuffer<Instance>` with the same program shares a vertex array object
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct VertexLayout {
+    bindings: SmallVec<[(VertexFormat, usize, Option<u32>); 2]>,
+}
+
+impl VertexLayout {
+    /// Builds a layout without any binding.
+    #[inline]
+    pub fn new() -> VertexLayout {
+        VertexLayout {
+            bindings: SmallVec::new(),
+        }
+    }
+
+    /// Returns true if the backend supports separating the vertex formats from the buffers
+    /// (`GL_ARB_vertex_attrib_binding`).
+    #[inline]
+    pub fn is_supported<C: ?Sized>(caps: &C) -> bool where C: CapabilitiesSource {
+        caps.get_version() >= &Version(Api::Gl, 4, 3) ||
+        caps.get_version() >= &Version(Api::GlEs, 3, 1) ||
+        caps.get_extensions().gl_arb_vertex_attrib_binding
+    }
+
+    /// Adds a binding for a buffer of `T` that is read once per vertex.
+    #[inline]
+    pub fn with_vertices<T>(self) -> VertexLayout where T: Vertex {
+        self.with_format(T::build_bindings(), mem::size_of::<T>(), None)
+    }
+
+    /// Adds a binding for a buffer of `T` that is read once per instance.
+    #[inline]
+    pub fn with_per_instance<T>(self) -> VertexLayout where T: Vertex {
+        self.with_format(T::build_bindings(), mem::size_of::<T>(), Some(1))
+    }
+
+    /// Adds a binding with the given format, stride in bytes and instancing divisor.
+    #[inline]
+    pub fn with_format(mut self, format: VertexFormat, stride: usize, divisor: Option<u32>)
+                       -> VertexLayout
+    {
+        self.bindings.push((format, stride, divisor));
+        self
+    }
+
+    /// Returns the list of bindings, in the order of the vertex sources.
+    #[inline]
+    pub fn get_bindings(&self) -> &[(VertexFormat, usize, Option<u32>)] {
+        &self.bindings
+    }
+}
@@ -120,6 +120,10 @@ be the same, or a `DrawError::VerticesSourcesLengthMismatch` will be produced.
 In all situation, the length of all per-instance sources must match, or
 `DrawError::InstancesCountMismatch` will be returned.
 
+Per-instance sources require OpenGL 3.3 or `GL_ARB_instanced_arrays` (see
+`is_instancing_supported`). On older backends, instancing can be emulated with
+`Context::set_instancing_emulation`, at the cost of one draw call per instance.
+
 # Transform feedback
 
 Transform feedback allows you to write in a buffer the list of primitives that are generated by
@@ -137,8 +141,12 @@ use std::option::IntoIter;
 
 pub use self::buffer::{VertexBuffer, VertexBufferAny};
 pub use self::buffer::VertexBufferSlice;
+pub use self::buffer::{GrowableVertexBuffer, GrowError};
+pub use self::buffer::is_instancing_supported;
 pub use self::buffer::CreationError as BufferCreationError;
 pub use self::format::{AttributeType, VertexFormat};
+pub use self::layout::VertexLayout;
+pub use self::packed::{Color8, Normal10_10_10_2};
 pub use self::transform_feedback::{is_transform_feedback_supported, TransformFeedbackSession};
 
 use crate::buffer::BufferAnySlice;
@@ -146,6 +154,8 @@ use crate::CapabilitiesSource;
 
 mod buffer;
 mod format;
+mod layout;
+mod packed;
 mod transform_feedback;
 
 /// Describes the source to use for the vertices when drawing.
@@ -295,7 +305,7 @@ pub trait Vertex: Copy + Sized {
     fn is_supported<C: ?Sized>(caps: &C) -> bool where C: CapabilitiesSource {
         let format = Self::build_bindings();
 
-        for &(_, _, ref ty, _) in format.iter() {
+        for &(_, _, _, ref ty, _) in format.iter() {
             if !ty.is_supported(caps) {
                 return false;
             }
@@ -310,6 +320,17 @@ pub unsafe trait Attribute: Sized {
     /// Get the type of data.
     fn get_type() -> AttributeType;
 
+    /// Returns true if the values of this type must be normalized when they are read by the
+    /// shader. Integers are then converted to floats between `0.0` and `1.0` (or `-1.0` and
+    /// `1.0` for signed integers), instead of being converted to floats as they are.
+    ///
+    /// The default implementation returns `false`. `implement_vertex!` uses this value for
+    /// the fields that are not marked with `normalize(...)`.
+    #[inline]
+    fn is_normalized() -> bool {
+        false
+    }
+
     /// Returns true if the backend supports this type of attribute.
     #[inline]
     fn is_supported<C: ?Sized>(caps: &C) -> bool where C: CapabilitiesSource {
@@ -0,0 +1,225 @@
+use crate::vertex::{Attribute, AttributeType};
+
+/// A color with four 8-bits components, read by the shader as a `vec4` whose components
+/// are between `0.0` and `1.0`.
+///
+/// This is four times smaller than a `[f32; 4]`, which is usually accurate enough for colors.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// use glium::vertex::Color8;
+///
+/// #[derive(Copy, Clone)]
+/// struct Vertex {
+///     position: [f32; 3],
+///     color: Color8,
+/// }
+///
+/// implement_vertex!(Vertex, position, color);
+///
+/// let vertex = Vertex { position: [0.0, 0.0, 0.0], color: [1.0, 0.5, 0.0, 1.0].into() };
+/// assert_eq!(vertex.color, Color8([255, 128, 0, 255]));
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct Color8(pub [u8; 4]);
+
+unsafe impl Attribute for Color8 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U8U8U8U8
+    }
+
+    #[inline]
+    fn is_normalized() -> bool {
+        true
+    }
+}
+
+impl From<[u8; 4]> for Color8 {
+    #[inline]
+    fn from(color: [u8; 4]) -> Color8 {
+        Color8(color)
+    }
+}
+
+impl From<(u8, u8, u8, u8)> for Color8 {
+    #[inline]
+    fn from((r, g, b, a): (u8, u8, u8, u8)) -> Color8 {
+        Color8([r, g, b, a])
+    }
+}
+
+impl From<[f32; 4]> for Color8 {
+    /// Values outside of the `[0.0, 1.0]` range are clamped.
+    #[inline]
+    fn from(color: [f32; 4]) -> Color8 {
+        Color8([unorm8(color[0]), unorm8(color[1]), unorm8(color[2]), unorm8(color[3])])
+    }
+}
+
+impl From<(f32, f32, f32, f32)> for Color8 {
+    #[inline]
+    fn from((r, g, b, a): (f32, f32, f32, f32)) -> Color8 {
+        [r, g, b, a].into()
+    }
+}
+
+impl From<[f32; 3]> for Color8 {
+    /// The alpha component is set to `1.0`.
+    #[inline]
+    fn from(color: [f32; 3]) -> Color8 {
+        [color[0], color[1], color[2], 1.0].into()
+    }
+}
+
+impl From<Color8> for [f32; 4] {
+    #[inline]
+    fn from(color: Color8) -> [f32; 4] {
+        let Color8(c) = color;
+        [c[0] as f32 / 255.0, c[1] as f32 / 255.0, c[2] as f32 / 255.0, c[3] as f32 / 255.0]
+    }
+}
+
+/// A vector whose `x`, `y` and `z` components are stored in 10 bits each and whose `w`
+/// component is stored in 2 bits, read by the shader as a `vec4` whose components are between
+/// `-1.0` and `1.0`.
+///
+/// This is usually used for normals and tangents, and is three times smaller than a
+/// `[f32; 3]`. The `w` component can store the handedness of a tangent basis.
+///
+/// The components are packed in a `u32` with `x` in the least significant bits, which
+/// corresponds to the `GL_INT_2_10_10_10_REV` type. This requires OpenGL 3.0, OpenGL ES 3.0
+/// or `GL_ARB_vertex_type_2_10_10_10_rev`.
+///
+/// ```
+/// use glium::vertex::Normal10_10_10_2;
+///
+/// let normal = Normal10_10_10_2::from([0.0, 1.0, 0.0]);
+/// assert_eq!(normal.to_array(), [0.0, 1.0, 0.0, 0.0]);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct Normal10_10_10_2(pub u32);
+
+impl Normal10_10_10_2 {
+    /// Packs four components. Values outside of the `[-1.0, 1.0]` range are clamped.
+    ///
+    /// Since `w` is stored in 2 bits, it is rounded to `-1.0`, `0.0` or `1.0`.
+    #[inline]
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Normal10_10_10_2 {
+        Normal10_10_10_2(snorm(x, 10) | (snorm(y, 10) << 10) | (snorm(z, 10) << 20) |
+                         (snorm(w, 2) << 30))
+    }
+
+    /// Unpacks the components, in the same way as the GPU does.
+    #[inline]
+    pub fn to_array(&self) -> [f32; 4] {
+        [
+            unpack_snorm(self.0, 10),
+            unpack_snorm(self.0 >> 10, 10),
+            unpack_snorm(self.0 >> 20, 10),
+            unpack_snorm(self.0 >> 30, 2),
+        ]
+    }
+}
+
+unsafe impl Attribute for Normal10_10_10_2 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I2I10I10I10Reversed
+    }
+
+    #[inline]
+    fn is_normalized() -> bool {
+        true
+    }
+}
+
+impl From<[f32; 3]> for Normal10_10_10_2 {
+    /// The `w` component is set to `0.0`.
+    #[inline]
+    fn from(normal: [f32; 3]) -> Normal10_10_10_2 {
+        Normal10_10_10_2::new(normal[0], normal[1], normal[2], 0.0)
+    }
+}
+
+impl From<(f32, f32, f32)> for Normal10_10_10_2 {
+    #[inline]
+    fn from((x, y, z): (f32, f32, f32)) -> Normal10_10_10_2 {
+        Normal10_10_10_2::new(x, y, z, 0.0)
+    }
+}
+
+impl From<[f32; 4]> for Normal10_10_10_2 {
+    #[inline]
+    fn from(normal: [f32; 4]) -> Normal10_10_10_2 {
+        Normal10_10_10_2::new(normal[0], normal[1], normal[2], normal[3])
+    }
+}
+
+impl From<Normal10_10_10_2> for [f32; 4] {
+    #[inline]
+    fn from(normal: Normal10_10_10_2) -> [f32; 4] {
+        normal.to_array()
+    }
+}
+
+/// Converts a value between `0.0` and `1.0` to an 8-bits unsigned normalized integer.
+#[inline]
+fn unorm8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Converts a value between `-1.0` and `1.0` to a signed normalized integer of `bits` bits,
+/// and returns its two's complement representation in the lowest bits.
+#[inline]
+fn snorm(value: f32, bits: u32) -> u32 {
+    let max = ((1 << (bits - 1)) - 1) as f32;
+    let value = (value.clamp(-1.0, 1.0) * max).round() as i32;
+    (value as u32) & ((1 << bits) - 1)
+}
+
+/// Converts the lowest `bits` bits of `value` from a signed normalized integer to a float.
+///
+/// The most negative value is clamped to `-1.0`, like OpenGL does.
+#[inline]
+fn unpack_snorm(value: u32, bits: u32) -> f32 {
+    let max = ((1 << (bits - 1)) - 1) as f32;
+    // moving the sign bit to the most significant bit, then back with an arithmetic shift
+    let value = ((value << (32 - bits)) as i32) >> (32 - bits);
+    (value as f32 / max).max(-1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Color8, Normal10_10_10_2};
+
+    #[test]
+    fn color8_from_floats() {
+        assert_eq!(Color8::from([0.0, 1.0, 0.5, 1.0]), Color8([0, 255, 128, 255]));
+        assert_eq!(Color8::from([-1.0, 2.0, 0.0, 0.0]), Color8([0, 255, 0, 0]));
+        assert_eq!(Color8::from([0.0, 0.0, 0.0]), Color8([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn normal_packing() {
+        assert_eq!(Normal10_10_10_2::new(0.0, 0.0, 0.0, 0.0).0, 0);
+        assert_eq!(Normal10_10_10_2::new(1.0, 0.0, 0.0, 0.0).0, 511);
+        assert_eq!(Normal10_10_10_2::new(-1.0, 0.0, 0.0, 0.0).0, 0x3ff - 510);
+        assert_eq!(Normal10_10_10_2::new(0.0, 0.0, 0.0, -1.0).0, 0b11 << 30);
+        assert_eq!(Normal10_10_10_2::new(0.0, 0.0, 1.0, 1.0).0, (511 << 20) | (1 << 30));
+    }
+
+    #[test]
+    fn normal_round_trip() {
+        let normal = Normal10_10_10_2::new(0.5, -0.25, 1.0, -1.0).to_array();
+        assert!((normal[0] - 0.5).abs() < 0.002);
+        assert!((normal[1] + 0.25).abs() < 0.002);
+        assert_eq!(normal[2], 1.0);
+        assert_eq!(normal[3], -1.0);
+    }
+}
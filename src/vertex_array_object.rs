@@ -7,11 +7,13 @@ use smallvec::SmallVec;
 
 use crate::Handle;
 use crate::buffer::BufferAnySlice;
-use crate::program::Program;
+use crate::program::{Attribute, Program};
 use crate::vertex::AttributeType;
 use crate::vertex::VertexFormat;
+use crate::vertex::VertexLayout;
 use crate::GlObject;
 use crate::BufferExt;
+use crate::DrawError;
 
 use crate::gl;
 use crate::context::CommandContext;
@@ -23,6 +25,10 @@ pub struct VertexAttributesSystem {
     // we maintain a list of VAOs for each vertexbuffer-indexbuffer-program association
     // the key is a (buffers-list-with-offset, program) ; the buffers list must be sorted
     vaos: RefCell<HashMap<(Vec<(gl::types::GLuint, usize)>, Handle), VertexArrayObject>>,
+
+    // if `GL_ARB_vertex_attrib_binding` is supported, we instead maintain a list of VAOs for each
+    // layout-program association, and the buffers are bound every time the VAO is used
+    layout_vaos: RefCell<HashMap<(VertexLayout, Handle), VertexArrayObject>>,
 }
 
 /// Object allowing one to bind vertex attributes to the current context.
@@ -31,6 +37,8 @@ pub struct Binder<'a, 'b, 'c> {
     program: &'a Program,
     element_array_buffer: Option<BufferAnySlice<'a>>,
     vertex_buffers: SmallVec<[(gl::types::GLuint, VertexFormat, usize, usize, Option<u32>); 2]>,
+    // formats of the attributes that are set with `set_constant_attributes` instead of a buffer
+    constant_attributes: SmallVec<[VertexFormat; 1]>,
     base_vertex: bool,
 }
 
@@ -40,6 +48,7 @@ impl VertexAttributesSystem {
     pub fn new() -> VertexAttributesSystem {
         VertexAttributesSystem {
             vaos: RefCell::new(HashMap::with_hasher(Default::default())),
+            layout_vaos: RefCell::new(HashMap::with_hasher(Default::default())),
         }
     }
 
@@ -61,6 +70,7 @@ impl VertexAttributesSystem {
             program,
             element_array_buffer: indices,
             vertex_buffers: SmallVec::new(),
+            constant_attributes: SmallVec::new(),
             base_vertex,
         }
     }
@@ -71,14 +81,27 @@ impl VertexAttributesSystem {
     pub fn purge_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
         VertexAttributesSystem::purge_if(ctxt, |&(ref buffers, _)| {
             buffers.iter().any(|&(b, _)| b == id)
-        })
+        });
+
+        // the VAOs that are keyed by their layout stay alive, but they must no longer
+        // reference the buffer
+        let vaos = ctxt.vertex_array_objects.layout_vaos.borrow();
+        for vao in vaos.values() {
+            vao.unbind_buffer(ctxt, id);
+        }
     }
 
     /// This function *must* be called whenever you destroy a program so that the system can
     /// purge its VAOs cache.
     #[inline]
     pub fn purge_program(ctxt: &mut CommandContext<'_>, program: Handle) {
-        VertexAttributesSystem::purge_if(ctxt, |&(_, p)| p == program)
+        VertexAttributesSystem::purge_if(ctxt, |&(_, p)| p == program);
+
+        let mut vaos = ctxt.vertex_array_objects.layout_vaos.borrow_mut();
+        let keys = vaos.keys().filter(|&&(_, p)| p == program).cloned().collect::<Vec<_>>();
+        for key in keys {
+            vaos.remove(&key).unwrap().destroy(ctxt);
+        }
     }
 
     /// Purges the VAOs cache.
@@ -89,6 +112,13 @@ impl VertexAttributesSystem {
         for (_, vao) in vaos {
             vao.destroy(ctxt);
         }
+
+        let vaos = mem::replace(&mut *ctxt.vertex_array_objects.layout_vaos.borrow_mut(),
+                                HashMap::with_hasher(Default::default()));
+
+        for (_, vao) in vaos {
+            vao.destroy(ctxt);
+        }
     }
 
     /// Purges the VAOs cache. Contrary to `purge_all`, this function expects the system to be
@@ -100,13 +130,21 @@ impl VertexAttributesSystem {
         for (_, vao) in vaos {
             vao.destroy(ctxt);
         }
+
+        let vaos = mem::replace(&mut *ctxt.vertex_array_objects.layout_vaos.borrow_mut(),
+                                HashMap::with_hasher(Default::default()));
+
+        for (_, vao) in vaos {
+            vao.destroy(ctxt);
+        }
     }
 
     /// Tells the VAOs system that the currently bound element array buffer will change.
     pub fn hijack_current_element_array_buffer(ctxt: &mut CommandContext<'_>) {
         let vaos = ctxt.vertex_array_objects.vaos.borrow_mut();
+        let layout_vaos = ctxt.vertex_array_objects.layout_vaos.borrow_mut();
 
-        for (_, vao) in vaos.iter() {
+        for vao in vaos.values().chain(layout_vaos.values()) {
             if vao.id == ctxt.state.vertex_array {
                 vao.element_array_buffer_hijacked.set(true);
                 return;
@@ -156,10 +194,23 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
         self
     }
 
+    /// Declares attributes that are not sourced from a buffer, and whose values are set with
+    /// `set_constant_attributes` after the binding.
+    #[inline]
+    pub fn add_constant(mut self, bindings: &VertexFormat) -> Binder<'a, 'b, 'c> {
+        self.constant_attributes.push(bindings.clone());
+        self
+    }
+
     /// Finish binding the vertex attributes.
     ///
     /// If `base_vertex` was set to true, returns the base vertex to use when drawing.
     pub fn bind(mut self) -> Option<gl::types::GLint> {
+        // all the backends that support `GL_ARB_vertex_attrib_binding` also support VAOs
+        if VertexLayout::is_supported(&*self.context) && self.constant_attributes.is_empty() {
+            return self.bind_with_layout();
+        }
+
         let ctxt = self.context;
 
         if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) ||
@@ -203,7 +254,7 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
 
             // if not found, building a new one
             let new_vao = unsafe {
-                VertexArrayObject::new(ctxt, &self.vertex_buffers,
+                VertexArrayObject::new(ctxt, &self.vertex_buffers, &self.constant_attributes,
                                        self.element_array_buffer, self.program)
             };
 
@@ -240,12 +291,48 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
     }
 }
 
+impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
+    /// Finish binding the vertex attributes by using a VAO whose attribute formats are
+    /// separate from the buffers.
+    fn bind_with_layout(self) -> Option<gl::types::GLint> {
+        let ctxt = self.context;
+
+        let layout = self.vertex_buffers.iter()
+                         .fold(VertexLayout::new(), |layout, &(_, ref format, _, stride, div)| {
+                             layout.with_format(format.clone(), stride, div)
+                         });
+
+        let key = (layout, self.program.get_id());
+
+        let mut vaos = ctxt.vertex_array_objects.layout_vaos.borrow_mut();
+        if !vaos.contains_key(&key) {
+            let new_vao = unsafe { VertexArrayObject::with_layout(ctxt, &key.0, self.program) };
+            vaos.insert(key.clone(), new_vao);
+        }
+
+        // the offsets are passed to `glBindVertexBuffer`, so there is no need for a base vertex
+        vaos.get(&key).unwrap().bind_buffers(ctxt, &self.vertex_buffers,
+                                             self.element_array_buffer);
+
+        if self.base_vertex {
+            Some(0)
+        } else {
+            None
+        }
+    }
+}
+
 /// Stores informations about how to bind a vertex buffer, an index buffer and a program.
+///
+/// If the VAO was built with `with_layout`, it only stores the formats of the attributes and
+/// the buffers are bound with `bind_buffers`.
 struct VertexArrayObject {
     id: gl::types::GLuint,
     destroyed: bool,
-    element_array_buffer: gl::types::GLuint,
+    element_array_buffer: Cell<gl::types::GLuint>,
     element_array_buffer_hijacked: Cell<bool>,
+    // buffer, offset and stride bound to each binding index
+    vertex_buffers: RefCell<SmallVec<[(gl::types::GLuint, usize, usize); 2]>>,
 }
 
 impl VertexArrayObject {
@@ -255,61 +342,18 @@ impl VertexArrayObject {
     /// VAO, and the VB & program attributes must not change.
     unsafe fn new(mut ctxt: &mut CommandContext<'_>,
                   vertex_buffers: &[(gl::types::GLuint, VertexFormat, usize, usize, Option<u32>)],
+                  constant_attributes: &[VertexFormat],
                   index_buffer: Option<BufferAnySlice<'_>>, program: &Program) -> VertexArrayObject
     {
-        // checking the attributes types
-        for &(_, ref bindings, _, _, _) in vertex_buffers {
-            for &(ref name, _, ty, _) in bindings.iter() {
-                let attribute = match program.get_attribute(Borrow::<str>::borrow(name)) {
-                    Some(a) => a,
-                    None => continue
-                };
-
-                if ty.get_num_components() != attribute.ty.get_num_components() ||
-                    attribute.size != 1
-                {
-                    panic!("The program attribute `{}` does not match the vertex format. \
-                            Program expected {:?}, got {:?}.", name, attribute.ty, ty);
-                }
-            }
-        }
-
-        // checking for missing attributes
-        for (&ref name, _) in program.attributes() {
-            let mut found = false;
-            for &(_, ref bindings, _, _, _) in vertex_buffers {
-                if bindings.iter().any(|&(ref n, _, _, _)| n == name) {
-                    found = true;
-                    break;
-                }
-            }
-            if !found {
-                panic!("The program attribute `{}` is missing in the vertex bindings", name);
-            }
-        };
+        check_attributes(vertex_buffers.iter().map(|&(_, ref bindings, _, _, _)| bindings)
+                                       .chain(constant_attributes.iter()),
+                         program);
 
         // TODO: check for collisions between the vertices sources
 
-        // building the VAO
-        let id = {
-            let mut id = 0;
-            if ctxt.version >= &Version(Api::Gl, 3, 0) ||
-                ctxt.version >= &Version(Api::GlEs, 3, 0) ||
-                ctxt.extensions.gl_arb_vertex_array_object
-            {
-                ctxt.gl.GenVertexArrays(1, &mut id);
-            } else if ctxt.extensions.gl_oes_vertex_array_object {
-                ctxt.gl.GenVertexArraysOES(1, &mut id);
-            } else if ctxt.extensions.gl_apple_vertex_array_object {
-                ctxt.gl.GenVertexArraysAPPLE(1, &mut id);
-            } else {
-                unreachable!();
-            };
-            id
-        };
-
         // we don't use DSA as we're going to make multiple calls for this VAO
         // and we're likely going to use the VAO right after it's been created
+        let id = gen_vao(ctxt);
         bind_vao(&mut ctxt, id);
 
         // binding index buffer
@@ -324,8 +368,95 @@ impl VertexArrayObject {
         VertexArrayObject {
             id,
             destroyed: false,
-            element_array_buffer: index_buffer.map(|b| b.get_id()).unwrap_or(0),
+            element_array_buffer: Cell::new(index_buffer.map(|b| b.get_id()).unwrap_or(0)),
+            element_array_buffer_hijacked: Cell::new(false),
+            vertex_buffers: RefCell::new(SmallVec::new()),
+        }
+    }
+
+    /// Builds a new `VertexArrayObject` that contains the formats of the attributes but no
+    /// buffer, with `GL_ARB_vertex_attrib_binding`.
+    ///
+    /// Each binding of the layout corresponds to the binding index of the same number.
+    unsafe fn with_layout(ctxt: &mut CommandContext<'_>, layout: &VertexLayout, program: &Program)
+                          -> VertexArrayObject
+    {
+        check_attributes(layout.get_bindings().iter().map(|&(ref bindings, _, _)| bindings),
+                         program);
+
+        let id = gen_vao(ctxt);
+        bind_vao(ctxt, id);
+
+        for (binding_index, &(ref bindings, _, divisor)) in layout.get_bindings().iter().enumerate() {
+            bind_attribute_format(ctxt, program, binding_index as gl::types::GLuint, bindings);
+
+            if let Some(divisor) = divisor {
+                ctxt.gl.VertexBindingDivisor(binding_index as gl::types::GLuint, divisor);
+            }
+        }
+
+        VertexArrayObject {
+            id,
+            destroyed: false,
+            element_array_buffer: Cell::new(0),
             element_array_buffer_hijacked: Cell::new(false),
+            vertex_buffers: RefCell::new(SmallVec::new()),
+        }
+    }
+
+    /// Sets this VAO as the current VAO and binds the buffers of a VAO built with `with_layout`.
+    fn bind_buffers(&self, ctxt: &mut CommandContext<'_>,
+                    vertex_buffers: &[(gl::types::GLuint, VertexFormat, usize, usize, Option<u32>)],
+                    index_buffer: Option<BufferAnySlice<'_>>)
+    {
+        bind_vao(ctxt, self.id);
+
+        let index_buffer = index_buffer.map(|b| b.get_id()).unwrap_or(0);
+        if self.element_array_buffer_hijacked.get() ||
+           self.element_array_buffer.get() != index_buffer
+        {
+            unsafe { bind_element_array_buffer(ctxt, index_buffer) };
+            self.element_array_buffer.set(index_buffer);
+            self.element_array_buffer_hijacked.set(false);
+        }
+
+        let mut bound = self.vertex_buffers.borrow_mut();
+        if bound.len() < vertex_buffers.len() {
+            bound.resize(vertex_buffers.len(), (0, 0, 0));
+        }
+
+        for (binding_index, &(buffer, _, offset, stride, _)) in vertex_buffers.iter().enumerate() {
+            if bound[binding_index] != (buffer, offset, stride) {
+                unsafe {
+                    ctxt.gl.BindVertexBuffer(binding_index as gl::types::GLuint, buffer,
+                                             offset as gl::types::GLintptr,
+                                             stride as gl::types::GLsizei);
+                }
+                bound[binding_index] = (buffer, offset, stride);
+            }
+        }
+    }
+
+    /// Removes a buffer that is about to be destroyed from the bindings of a VAO built with
+    /// `with_layout`.
+    fn unbind_buffer(&self, ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
+        let mut bound = self.vertex_buffers.borrow_mut();
+
+        for (binding_index, binding) in bound.iter_mut().enumerate() {
+            if binding.0 == id {
+                bind_vao(ctxt, self.id);
+                unsafe {
+                    ctxt.gl.BindVertexBuffer(binding_index as gl::types::GLuint, 0, 0,
+                                             binding.2 as gl::types::GLsizei);
+                }
+                *binding = (0, 0, 0);
+            }
+        }
+
+        if self.element_array_buffer.get() == id {
+            bind_vao(ctxt, self.id);
+            unsafe { bind_element_array_buffer(ctxt, 0) };
+            self.element_array_buffer.set(0);
         }
     }
 
@@ -335,17 +466,7 @@ impl VertexArrayObject {
             bind_vao(ctxt, self.id);
 
             if self.element_array_buffer_hijacked.get() {
-                // TODO: use a proper function
-                if ctxt.version >= &Version(Api::Gl, 1, 5) ||
-                    ctxt.version >= &Version(Api::GlEs, 2, 0)
-                {
-                    ctxt.gl.BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.element_array_buffer);
-                } else if ctxt.extensions.gl_arb_vertex_buffer_object {
-                    ctxt.gl.BindBufferARB(gl::ELEMENT_ARRAY_BUFFER_ARB, self.element_array_buffer);
-                } else {
-                    unreachable!();
-                }
-
+                bind_element_array_buffer(ctxt, self.element_array_buffer.get());
                 self.element_array_buffer_hijacked.set(false);
             }
         }
@@ -475,6 +596,87 @@ fn vertex_binding_type_to_gl(ty: AttributeType) -> (gl::types::GLenum, gl::types
     }
 }
 
+/// Checks that the vertex formats match the attributes of the program.
+///
+/// ## Panic
+///
+/// Panics if an attribute has the wrong type or is missing.
+fn check_attributes<'a, I>(formats: I, program: &Program)
+                           where I: Iterator<Item = &'a VertexFormat> + Clone
+{
+    // checking the attributes types
+    for bindings in formats.clone() {
+        for &(ref name, _, location, ty, _) in bindings.iter() {
+            let attribute = match find_attribute(program, Borrow::<str>::borrow(name), location) {
+                Some(a) => a,
+                None => continue
+            };
+
+            if ty.get_num_components() != attribute.ty.get_num_components() ||
+                attribute.size != 1
+            {
+                panic!("The program attribute `{}` does not match the vertex format. \
+                        Program expected {:?}, got {:?}.", name, attribute.ty, ty);
+            }
+        }
+    }
+
+    // checking for missing attributes
+    for (&ref name, attribute) in program.attributes() {
+        let mut found = false;
+        for bindings in formats.clone() {
+            if bindings.iter().any(|&(ref n, _, l, _, _)| {
+                match l {
+                    Some(l) => l as i32 == attribute.location,
+                    None => n == name,
+                }
+            }) {
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            panic!("The program attribute `{}` is missing in the vertex bindings", name);
+        }
+    };
+}
+
+/// Creates a new vertex array object.
+///
+/// ## Panic
+///
+/// Panics if the backend doesn't support vertex array objects.
+unsafe fn gen_vao(ctxt: &mut CommandContext<'_>) -> gl::types::GLuint {
+    let mut id = 0;
+    if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+        ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+        ctxt.extensions.gl_arb_vertex_array_object
+    {
+        ctxt.gl.GenVertexArrays(1, &mut id);
+    } else if ctxt.extensions.gl_oes_vertex_array_object {
+        ctxt.gl.GenVertexArraysOES(1, &mut id);
+    } else if ctxt.extensions.gl_apple_vertex_array_object {
+        ctxt.gl.GenVertexArraysAPPLE(1, &mut id);
+    } else {
+        unreachable!();
+    };
+    id
+}
+
+/// Binds a buffer to the `GL_ELEMENT_ARRAY_BUFFER` of the current vertex array object.
+unsafe fn bind_element_array_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
+    // TODO: use a proper function
+    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+        ctxt.version >= &Version(Api::GlEs, 2, 0)
+    {
+        ctxt.gl.BindBuffer(gl::ELEMENT_ARRAY_BUFFER, id);
+    } else if ctxt.extensions.gl_arb_vertex_buffer_object {
+        ctxt.gl.BindBufferARB(gl::ELEMENT_ARRAY_BUFFER_ARB, id);
+    } else {
+        unreachable!();
+    }
+}
+
 /// Binds the vertex array object as the current one. Unbinds if `0` is passed.
 ///
 /// ## Panic
@@ -520,19 +722,25 @@ unsafe fn bind_attribute(ctxt: &mut CommandContext<'_>, program: &Program,
     }
 
     // binding attributes
-    for &(ref name, offset, ty, normalize) in bindings.iter() {
+    for &(ref name, offset, location, ty, normalize) in bindings.iter() {
         let (data_type, elements_count, instances_count) = vertex_binding_type_to_gl(ty);
 
-        let attribute = match program.get_attribute(Borrow::<str>::borrow(name)) {
-            Some(a) => a,
-            None => continue
+        // if the location is explicit but the program doesn't report any attribute there, we
+        // trust the user and bind the attribute with the type of the vertex format
+        let (location, attribute_ty) = match find_attribute(program, Borrow::<str>::borrow(name), location) {
+            Some(a) => (a.location, a.ty),
+            None => match location {
+                Some(location) => (location as i32, ty),
+                None => continue
+            },
         };
 
-        if attribute.location != -1 {
-            let (attribute_ty, _, _) = vertex_binding_type_to_gl(attribute.ty);
+        // built-in attributes reported by the program don't have a location
+        if location != -1 {
+            let (attribute_ty, _, _) = vertex_binding_type_to_gl(attribute_ty);
             if normalize {
                 for i in 0..instances_count {
-                    ctxt.gl.VertexAttribPointer((attribute.location + i) as u32,
+                    ctxt.gl.VertexAttribPointer((location + i) as u32,
                                                 elements_count as gl::types::GLint, data_type, 1,
                                                 stride as i32,
                                                 (buffer_offset + offset + (i * elements_count * 4) as usize) as *const _)
@@ -541,14 +749,14 @@ unsafe fn bind_attribute(ctxt: &mut CommandContext<'_>, program: &Program,
                 match attribute_ty {
                     gl::BYTE | gl::UNSIGNED_BYTE | gl::SHORT | gl::UNSIGNED_SHORT |
                     gl::INT | gl::UNSIGNED_INT =>
-                        ctxt.gl.VertexAttribIPointer(attribute.location as u32,
+                        ctxt.gl.VertexAttribIPointer(location as u32,
                                                      elements_count as gl::types::GLint, data_type,
                                                      stride as i32,
                                                      (buffer_offset + offset) as *const _),
 
                     gl::FLOAT => {
                         for i in 0..instances_count {
-                            ctxt.gl.VertexAttribPointer((attribute.location + i) as u32,
+                            ctxt.gl.VertexAttribPointer((location + i) as u32,
                                                         elements_count as gl::types::GLint, data_type, 0,
                                                         stride as i32,
                                                         (buffer_offset + offset + (i * elements_count * 4) as usize) as *const _)
@@ -557,7 +765,7 @@ unsafe fn bind_attribute(ctxt: &mut CommandContext<'_>, program: &Program,
 
                     gl::DOUBLE | gl::INT64_NV | gl::UNSIGNED_INT64_NV => {
                         for i in 0..instances_count {
-                            ctxt.gl.VertexAttribLPointer((attribute.location + i) as u32,
+                            ctxt.gl.VertexAttribLPointer((location + i) as u32,
                                                          elements_count as gl::types::GLint, data_type,
                                                          stride as i32,
                                                          (buffer_offset + offset + (i * elements_count * 8) as usize) as *const _)
@@ -570,10 +778,151 @@ unsafe fn bind_attribute(ctxt: &mut CommandContext<'_>, program: &Program,
 
             for i in 0..instances_count {
                 if let Some(divisor) = divisor {
-                    ctxt.gl.VertexAttribDivisor((attribute.location + i) as u32, divisor);
+                    ctxt.gl.VertexAttribDivisor((location + i) as u32, divisor);
                 }
-                ctxt.gl.EnableVertexAttribArray((attribute.location + i) as u32);
+                ctxt.gl.EnableVertexAttribArray((location + i) as u32);
             }
         }
     }
 }
+
+/// Sets the values of the attributes of a vertex format from the data of a single element,
+/// instead of sourcing them from a buffer. This is used to emulate instancing.
+///
+/// The attributes are disabled in the current VAO, and their values are set with
+/// `glVertexAttrib4fv`. Therefore only attributes whose type is a float in the program are
+/// supported.
+pub unsafe fn set_constant_attributes(ctxt: &mut CommandContext<'_>, program: &Program,
+                                      bindings: &VertexFormat, data: &[u8])
+                                      -> Result<(), DrawError>
+{
+    for &(ref name, offset, location, ty, normalize) in bindings.iter() {
+        let attribute = match find_attribute(program, Borrow::<str>::borrow(name), location) {
+            Some(a) => a,
+            None => continue
+        };
+
+        let (data_type, elements_count, instances_count) = vertex_binding_type_to_gl(ty);
+        let (attribute_ty, _, _) = vertex_binding_type_to_gl(attribute.ty);
+
+        let component_size = match data_type {
+            gl::BYTE | gl::UNSIGNED_BYTE => 1,
+            gl::SHORT | gl::UNSIGNED_SHORT => 2,
+            gl::INT | gl::UNSIGNED_INT | gl::FLOAT => 4,
+            _ => 0
+        };
+
+        if attribute_ty != gl::FLOAT || component_size == 0 {
+            return Err(DrawError::UnsupportedEmulatedInstanceAttribute {
+                name: name.to_string(),
+            });
+        }
+
+        for i in 0..instances_count {
+            // the missing components have the same default values as with a buffer
+            let mut values = [0.0f32, 0.0, 0.0, 1.0];
+            for (j, value) in values.iter_mut().enumerate().take(elements_count as usize) {
+                let start = offset + ((i * elements_count) as usize + j) * component_size;
+                *value = read_component(data_type, normalize, &data[start .. start + component_size]);
+            }
+
+            let attrib = (attribute.location + i) as gl::types::GLuint;
+            ctxt.gl.DisableVertexAttribArray(attrib);
+            ctxt.gl.VertexAttrib4fv(attrib, values.as_ptr());
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts a component of a vertex attribute to a float, like OpenGL does when the attribute
+/// is sourced from a buffer.
+fn read_component(data_type: gl::types::GLenum, normalize: bool, bytes: &[u8]) -> f32 {
+    let (value, max) = match data_type {
+        gl::BYTE => (bytes[0] as i8 as f64, i8::MAX as f64),
+        gl::UNSIGNED_BYTE => (bytes[0] as f64, u8::MAX as f64),
+        gl::SHORT => (i16::from_ne_bytes([bytes[0], bytes[1]]) as f64, i16::MAX as f64),
+        gl::UNSIGNED_SHORT => (u16::from_ne_bytes([bytes[0], bytes[1]]) as f64, u16::MAX as f64),
+        gl::INT => (i32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+                    i32::MAX as f64),
+        gl::UNSIGNED_INT => (u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+                             u32::MAX as f64),
+        gl::FLOAT => return f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        _ => unreachable!()
+    };
+
+    if normalize {
+        (value / max).max(-1.0) as f32
+    } else {
+        value as f32
+    }
+}
+
+/// Sets the formats of the attributes of a vertex format in the current VAO, and associates
+/// them with a binding index.
+unsafe fn bind_attribute_format(ctxt: &mut CommandContext<'_>, program: &Program,
+                                binding_index: gl::types::GLuint, bindings: &VertexFormat)
+{
+    for &(ref name, offset, location, ty, normalize) in bindings.iter() {
+        let (data_type, elements_count, instances_count) = vertex_binding_type_to_gl(ty);
+
+        // if the location is explicit but the program doesn't report any attribute there, we
+        // trust the user and bind the attribute with the type of the vertex format
+        let (location, attribute_ty) = match find_attribute(program, Borrow::<str>::borrow(name), location) {
+            Some(a) => (a.location, a.ty),
+            None => match location {
+                Some(location) => (location as i32, ty),
+                None => continue
+            },
+        };
+
+        // built-in attributes reported by the program don't have a location
+        if location == -1 {
+            continue;
+        }
+
+        let (attribute_ty, _, _) = vertex_binding_type_to_gl(attribute_ty);
+
+        for i in 0..instances_count {
+            let attrib = (location + i) as gl::types::GLuint;
+
+            if normalize {
+                ctxt.gl.VertexAttribFormat(attrib, elements_count, data_type, gl::TRUE,
+                                           (offset + (i * elements_count * 4) as usize) as u32);
+            } else {
+                match attribute_ty {
+                    gl::BYTE | gl::UNSIGNED_BYTE | gl::SHORT | gl::UNSIGNED_SHORT |
+                    gl::INT | gl::UNSIGNED_INT =>
+                        ctxt.gl.VertexAttribIFormat(attrib, elements_count, data_type,
+                                                    offset as u32),
+
+                    gl::FLOAT =>
+                        ctxt.gl.VertexAttribFormat(attrib, elements_count, data_type, gl::FALSE,
+                                                   (offset + (i * elements_count * 4) as usize) as u32),
+
+                    gl::DOUBLE | gl::INT64_NV | gl::UNSIGNED_INT64_NV =>
+                        ctxt.gl.VertexAttribLFormat(attrib, elements_count, data_type,
+                                                    (offset + (i * elements_count * 8) as usize) as u32),
+
+                    _ => unreachable!()
+                }
+            }
+
+            ctxt.gl.VertexAttribBinding(attrib, binding_index);
+            ctxt.gl.EnableVertexAttribArray(attrib);
+        }
+    }
+}
+
+/// Finds the attribute of the program that corresponds to an element of a vertex format.
+///
+/// Elements with an explicit location are matched by location, the others by name.
+fn find_attribute<'a>(program: &'a Program, name: &str, location: Option<u32>)
+                      -> Option<&'a Attribute>
+{
+    match location {
+        Some(location) => program.attributes().map(|(_, attribute)| attribute)
+                                 .find(|a| a.location == location as i32),
+        None => program.get_attribute(name),
+    }
+}
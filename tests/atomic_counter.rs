@@ -61,3 +61,119 @@ fn basic() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn atomic_counter_buffer_offsets_and_reset() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 430
+
+            out vec4 f_color;
+
+            layout(binding = 0, offset = 0) uniform atomic_uint fragments;
+            layout(binding = 0, offset = 4) uniform atomic_uint twice;
+
+            void main() {
+                f_color = vec4(0.0, 0.0, 0.0, 1.0);
+                atomicCounterIncrement(fragments);
+                atomicCounterIncrement(twice);
+                atomicCounterIncrement(twice);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let counters = match glium::buffer::AtomicCounterBuffer::with_values(&display, &[5, 0]) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let uniforms = uniform!{
+        fragments: &counters,
+        twice: &counters,
+    };
+
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+    assert_eq!(counters.read().unwrap(), vec![1024 * 1024 + 5, 2 * 1024 * 1024]);
+
+    counters.reset();
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+    assert_eq!(counters.read().unwrap(), vec![1024 * 1024, 2 * 1024 * 1024]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn atomic_counter_buffer_too_small() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 430
+
+            out vec4 f_color;
+
+            layout(binding = 0, offset = 4) uniform atomic_uint counter;
+
+            void main() {
+                f_color = vec4(0.0, 0.0, 0.0, 1.0);
+                atomicCounterIncrement(counter);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let counters = match glium::buffer::AtomicCounterBuffer::new(&display, 1) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    let uniforms = uniform!{
+        counter: &counters,
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    match texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()) {
+        Err(glium::DrawError::BufferTooSmall { required: 8, obtained: 4, .. }) => (),
+        e => panic!("{:?}", e)
+    }
+
+    display.assert_no_error(None);
+}
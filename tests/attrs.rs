@@ -166,6 +166,54 @@ fn missing_attribute() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn explicit_attribute_location() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, field1 location(0));
+
+    let vertex_buffer = glium::VertexBuffer::new(&display, &[Vertex { field1: [0.0, 0.0] }]).unwrap();
+    let index_buffer = glium::IndexBuffer::new(&display, PrimitiveType::Points,
+                                               &[0u16]).unwrap();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 330
+
+            layout(location = 0) in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+            out vec4 color;
+            void main() {
+                color = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None);
+
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    // drawing a frame
+    let mut target = display.draw();
+    target.draw(&vertex_buffer, &index_buffer, &program, &glium::uniforms::EmptyUniforms,
+                &Default::default()).unwrap();
+    target.finish().unwrap();
+
+    display.assert_no_error(None);
+}
+
 macro_rules! attribute_test(
     ($name:ident, $attr_ty:ty, $glsl_ty:expr, $value:expr, $gl_pos:expr) => (
         #[test]
@@ -307,3 +355,25 @@ attribute_test!(attribute_vec3_i32, [i32; 3], "vec3", [0, 0, 0], "vec4(field1, 1
 attribute_test!(attribute_vec3_tuple_i32, (i32, i32, i32), "vec3", (0, 0, 0), "vec4(field1, 1.0)");
 attribute_test!(attribute_vec4_i32, [i32; 4], "vec4", [0, 0, 0, 0], "field1");
 attribute_test!(attribute_vec4_tuple_i32, (i32, i32, i32, i32), "vec4", (0, 0, 0, 0), "field1");
+
+#[test]
+fn normalized_attribute_types() {
+    use glium::vertex::{Color8, Normal10_10_10_2, Vertex as VertexTrait};
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        color: Color8,
+        normal: Normal10_10_10_2,
+        raw: [u8; 4],
+        forced: Color8,
+    }
+
+    implement_vertex!(Vertex, color, normal, raw, forced normalize(false));
+
+    let bindings = <Vertex as VertexTrait>::build_bindings();
+    let normalized = bindings.iter().map(|&(_, _, _, _, n)| n).collect::<Vec<_>>();
+    assert_eq!(normalized, vec![true, true, false, false]);
+
+    assert_eq!(bindings[0].3, glium::vertex::AttributeType::U8U8U8U8);
+    assert_eq!(bindings[1].3, glium::vertex::AttributeType::I2I10I10I10Reversed);
+}
@@ -56,3 +56,175 @@ fn blit_texture_to_window() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn blit_multioutput_attachments() {
+    let display = support::build_display();
+
+    let rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let target_rect = BlitTarget { left: 0, bottom: 0, width: 2, height: 2 };
+
+    let albedo = support::build_unicolor_texture2d(&display, 1.0, 0.0, 0.0);
+    let normal = support::build_unicolor_texture2d(&display, 0.0, 1.0, 0.0);
+    let target_albedo = support::build_unicolor_texture2d(&display, 0.0, 0.0, 0.0);
+    let target_normal = support::build_unicolor_texture2d(&display, 0.0, 0.0, 0.0);
+
+    let source = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                                    [("albedo", &albedo), ("normal", &normal)].iter().cloned())
+                                    .unwrap();
+    let target = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                                    [("normal", &target_normal), ("albedo", &target_albedo)]
+                                    .iter().cloned()).unwrap();
+
+    match source.blit_attachments(&target, vec!["missing"], &rect, &target_rect,
+                                  glium::uniforms::MagnifySamplerFilter::Nearest)
+    {
+        Err(glium::framebuffer::BlitAttachmentsError::NotFoundInSource(_)) => (),
+        e => panic!("{:?}", e)
+    }
+
+    source.blit_all_attachments(&target, &rect, &target_rect,
+                                glium::uniforms::MagnifySamplerFilter::Nearest).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target_albedo.read();
+    assert_eq!(data[1][1], (255, 0, 0, 255));
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target_normal.read();
+    assert_eq!(data[1][1], (0, 255, 0, 255));
+
+    // matching by index copies `albedo` to `normal`
+    source.blit_attachments(&target, vec![0], &rect, &target_rect,
+                            glium::uniforms::MagnifySamplerFilter::Nearest).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target_normal.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn resolve_multisample_texture() {
+    let display = support::build_display();
+
+    let source = match glium::texture::Texture2dMultisample::empty(&display, 4, 4, 4) {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+    source.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+
+    let target = glium::texture::Texture2d::empty(&display, 4, 4).unwrap();
+    source.resolve_to(&target).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn resolve_multisample_texture_wrong_dimensions() {
+    let display = support::build_display();
+
+    let source = match glium::texture::Texture2dMultisample::empty(&display, 4, 4, 4) {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+
+    let target = glium::texture::Texture2d::empty(&display, 2, 2).unwrap();
+    match source.resolve_to(&target) {
+        Err(glium::texture::CopyImageError::TargetOutOfRange) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_buffers_flipped() {
+    let display = support::build_display();
+
+    let source = glium::texture::Texture2d::new(&display,
+                                                vec![vec![(255u8, 0u8, 0u8, 255u8); 2],
+                                                     vec![(0u8, 255u8, 0u8, 255u8); 2]]).unwrap();
+    let target = glium::texture::Texture2d::empty(&display, 2, 2).unwrap();
+
+    source.as_surface().blit(&Rect { left: 0, bottom: 0, width: 2, height: 2 },
+                             &target.as_surface(),
+                             &BlitTarget { left: 0, bottom: 2, width: 2, height: -2 },
+                             glium::BlitMask { color: true, .. Default::default() },
+                             glium::uniforms::MagnifySamplerFilter::Nearest).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+    assert_eq!(data[1][1], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_buffers_depth() {
+    use glium::framebuffer::{DepthRenderBuffer, SimpleFrameBuffer};
+    use glium::texture::DepthFormat;
+    use glium::BlitError;
+
+    let display = support::build_display();
+
+    let rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let target_rect = BlitTarget { left: 0, bottom: 0, width: 2, height: 2 };
+    let mask = glium::BlitMask { depth: true, .. Default::default() };
+
+    let color1 = support::build_unicolor_texture2d(&display, 0.0, 0.0, 0.0);
+    let color2 = support::build_unicolor_texture2d(&display, 0.0, 0.0, 0.0);
+    let depth1 = DepthRenderBuffer::new(&display, DepthFormat::I24, 2, 2).unwrap();
+    let depth2 = DepthRenderBuffer::new(&display, DepthFormat::I24, 2, 2).unwrap();
+
+    let mut source = SimpleFrameBuffer::with_depth_buffer(&display, &color1, &depth1).unwrap();
+    let target = SimpleFrameBuffer::with_depth_buffer(&display, &color2, &depth2).unwrap();
+    let target_no_depth = SimpleFrameBuffer::new(&display, &color2).unwrap();
+    source.clear_depth(0.5);
+
+    source.blit(&rect, &target, &target_rect, mask,
+                glium::uniforms::MagnifySamplerFilter::Nearest).unwrap();
+
+    match source.blit(&rect, &target, &target_rect, mask,
+                      glium::uniforms::MagnifySamplerFilter::Linear)
+    {
+        Err(BlitError::LinearFilterWithDepthStencil) => (),
+        _ => panic!()
+    };
+
+    match source.blit(&rect, &target_no_depth, &target_rect, mask,
+                      glium::uniforms::MagnifySamplerFilter::Nearest)
+    {
+        Err(BlitError::NoDepthBuffer) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_buffers_multisample_wrong_dimensions() {
+    let display = support::build_display();
+
+    let source = match glium::texture::Texture2dMultisample::empty(&display, 4, 4, 4) {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+    let target = glium::texture::Texture2d::empty(&display, 4, 4).unwrap();
+
+    match source.as_surface().blit(&Rect { left: 0, bottom: 0, width: 4, height: 4 },
+                                   &target.as_surface(),
+                                   &BlitTarget { left: 0, bottom: 0, width: 2, height: 2 },
+                                   glium::BlitMask { color: true, .. Default::default() },
+                                   glium::uniforms::MagnifySamplerFilter::Nearest)
+    {
+        Err(glium::BlitError::MultisampleRectMismatch) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
@@ -976,3 +976,263 @@ fn copy_to_slice() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn buffer_from_exact_iter() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [u8; 2],
+    }
+
+    implement_vertex!(Vertex, field1);
+
+    let vb = glium::VertexBuffer::from_exact_iter(&display,
+        (0 .. 16u8).map(|i| Vertex { field1: [i, i * 2] })
+    ).unwrap();
+
+    assert_eq!(vb.len(), 16);
+
+    let data = match vb.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(data[0].field1, [0, 0]);
+    assert_eq!(data[15].field1, [15, 30]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn buffer_from_iter_unknown_length() {
+    let display = support::build_display();
+
+    let ib = glium::IndexBuffer::from_iter(&display, glium::index::PrimitiveType::Points,
+                                           (0 .. 10u16).filter(|i| i % 2 == 0)).unwrap();
+
+    assert_eq!(ib.len(), 5);
+
+    let data = match ib.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(data, vec![0, 2, 4, 6, 8]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn buffer_dynamic_mapping_write_unsynchronized() {
+    let display = support::build_display();
+
+    let mut buffer = glium::buffer::Buffer::new(&display, &[1u32, 2, 3, 4][..],
+                                                glium::buffer::BufferType::ArrayBuffer,
+                                                BufferMode::Dynamic).unwrap();
+
+    // the buffer hasn't been used by the GPU, so there's nothing to synchronize
+    {
+        let mut mapping = unsafe { buffer.map_write_unsynchronized() }.unwrap();
+        mapping.set(0, 5);
+        mapping.set(3, 8);
+    }
+
+    {
+        let mut mapping = unsafe { buffer.slice_mut(1 .. 3).unwrap().map_write_unsynchronized() }.unwrap();
+        mapping.set(0, 6);
+        mapping.set(1, 7);
+    }
+
+    assert_eq!(buffer.read().unwrap(), vec![5, 6, 7, 8]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn persistent_ring_buffer() {
+    let display = support::build_display();
+
+    let mut ring = glium::buffer::PersistentRingBuffer::<u32>::new(&display,
+                                                        glium::buffer::BufferType::ArrayBuffer,
+                                                        8).unwrap();
+    assert_eq!(ring.capacity(), 8);
+    assert!(ring.allocate(9).is_none());
+
+    let first = {
+        let mut region = ring.allocate(5).unwrap();
+        for i in 0 .. 5 {
+            region.set(i, i as u32);
+        }
+        region.get_range()
+    };
+    assert_eq!(first, 0 .. 5);
+    assert_eq!(ring.slice(first).read().unwrap(), vec![0, 1, 2, 3, 4]);
+
+    // not enough room at the end, so the ring goes back to the start
+    let second = {
+        let mut region = ring.allocate(4).unwrap();
+        for i in 0 .. 4 {
+            region.set(i, 9);
+        }
+        region.get_range()
+    };
+    assert_eq!(second, 0 .. 4);
+    assert_eq!(ring.slice(second).read().unwrap(), vec![9, 9, 9, 9]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn orphan_then_write() {
+    let display = support::build_display();
+
+    let buffer = glium::buffer::Buffer::new(&display, &[1u8, 2, 3, 4][..],
+                                            glium::buffer::BufferType::ArrayBuffer,
+                                            BufferMode::Dynamic).unwrap();
+
+    buffer.orphan();
+    buffer.write(&[5, 6, 7, 8]);
+    assert_eq!(buffer.read().unwrap(), vec![5, 6, 7, 8]);
+
+    buffer.write_discard(&[9, 10, 11, 12]);
+    assert_eq!(buffer.read().unwrap(), vec![9, 10, 11, 12]);
+
+    buffer.slice(1 .. 3).unwrap().write_discard(&[0, 0]);
+    assert_eq!(buffer.read().unwrap(), vec![9, 0, 0, 12]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn buffer_arena_reuse_and_defragment() {
+    let display = support::build_display();
+
+    let mut arena = glium::buffer::BufferArena::<u16>::index(&display, 8).unwrap();
+
+    let a = arena.allocate_with_data(&[1, 2, 3]).unwrap();
+    let b = arena.allocate_with_data(&[4, 5]).unwrap();
+    let c = arena.allocate_with_data(&[6, 7, 8]).unwrap();
+    assert!(arena.allocate(1).is_none());
+
+    arena.free(b);
+    assert_eq!(arena.get_free_len(), 2);
+
+    // the freed range is reused
+    let d = arena.allocate_with_data(&[9]).unwrap();
+    assert_eq!(arena.get_range(&d), 3 .. 4);
+
+    // two free elements, but not contiguous
+    arena.free(a);
+    assert_eq!(arena.get_free_len(), 4);
+    assert_eq!(arena.get_largest_free_len(), 3);
+    assert!(arena.allocate(4).is_none());
+
+    match arena.defragment() {
+        Err(glium::buffer::CopyError::NotSupported) => return,
+        r => r.unwrap(),
+    };
+
+    assert_eq!(arena.get_range(&d), 0 .. 1);
+    assert_eq!(arena.get_range(&c), 1 .. 4);
+    assert_eq!(arena.slice(&d).read().unwrap(), vec![9]);
+    assert_eq!(arena.slice(&c).read().unwrap(), vec![6, 7, 8]);
+    assert_eq!(arena.get_largest_free_len(), 4);
+    assert!(arena.allocate(4).is_some());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn buffer_arena_free_in_other_arena() {
+    let display = support::build_display();
+
+    let mut arena1 = glium::buffer::BufferArena::<u16>::index(&display, 8).unwrap();
+    let mut arena2 = glium::buffer::BufferArena::<u16>::index(&display, 8).unwrap();
+
+    let _ = arena2.allocate(2).unwrap();
+    let allocation = arena1.allocate(2).unwrap();
+    arena2.free(allocation);
+}
+
+#[test]
+fn buffer_arena_draw() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position);
+
+    let mut vertices = glium::buffer::BufferArena::<Vertex>::vertex(&display, 64).unwrap();
+    let mut indices = glium::buffer::BufferArena::<u16>::index(&display, 64).unwrap();
+
+    let quad = vertices.allocate_with_data(&[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+    let quad_indices = indices.allocate_with_data(&[0, 1, 2, 1, 3, 2]).unwrap();
+
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 140
+
+                out vec4 color;
+
+                void main() {
+                    color = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        },
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        }).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(vertices.vertices(&quad),
+                              indices.indices(&quad_indices,
+                                              glium::index::PrimitiveType::TrianglesList),
+                              &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
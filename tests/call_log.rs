@@ -0,0 +1,30 @@
+#![cfg(feature = "gl_call_log")]
+
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+
+mod support;
+
+#[test]
+fn identical_frames_issue_identical_calls() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::empty(&display, 16, 16).unwrap();
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+
+    // warming up the state cache
+    framebuffer.clear_color(0.0, 0.0, 0.0, 1.0);
+
+    glium::call_log::start_recording();
+    framebuffer.clear_color(1.0, 0.0, 0.0, 1.0);
+    let first = glium::call_log::take_frames();
+    framebuffer.clear_color(1.0, 0.0, 0.0, 1.0);
+    let second = glium::call_log::stop_recording();
+
+    assert_eq!(first, second);
+    assert!(first[0].iter().any(|call| call.name == "Clear"));
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,212 @@
+#[macro_use]
+extern crate glium;
+
+use glium::buffer::{Buffer, BufferMode, BufferType};
+use glium::program::{ComputeCommand, ComputeShader};
+use glium::texture::{MipmapsOption, Texture2d, Texture2dArray, UncompressedFloatFormat};
+use glium::uniforms::{ImageUnitAccess, ImageUnitError, ImageUnitFormat};
+
+mod support;
+
+#[derive(Copy, Clone)]
+#[repr(C)]
+struct Counter {
+    count: u32,
+}
+
+implement_uniform_block!(Counter, count);
+
+/// Builds a compute shader that counts its invocations in the `Counter` buffer.
+fn build_counter_program<F: ?Sized>(facade: &F) -> ComputeShader
+                                    where F: glium::backend::Facade
+{
+    ComputeShader::from_source(facade, "
+        #version 430
+
+        layout(local_size_x = 1) in;
+
+        layout(std430) buffer Counter {
+            uint count;
+        };
+
+        void main() {
+            atomicAdd(count, 1u);
+        }
+    ").unwrap()
+}
+
+#[test]
+fn execute_indirect() {
+    let display = support::build_display();
+    if !ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let program = build_counter_program(&display);
+
+    let counter = Buffer::new(&display, &Counter { count: 0 }, BufferType::ShaderStorageBuffer,
+                              BufferMode::Default).unwrap();
+    let command = ComputeCommand { num_groups_x: 3, num_groups_y: 2, num_groups_z: 1 };
+    let commands = Buffer::new(&display, &command, BufferType::DispatchIndirectBuffer,
+                               BufferMode::Default).unwrap();
+
+    program.execute_indirect(uniform! { Counter: &counter }, &commands);
+    assert_eq!(counter.read().unwrap().count, 6);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn execute_indirect_at() {
+    let display = support::build_display();
+    if !ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let program = build_counter_program(&display);
+
+    let counter = Buffer::new(&display, &Counter { count: 0 }, BufferType::ShaderStorageBuffer,
+                              BufferMode::Default).unwrap();
+    let commands = Buffer::new(&display, &[
+        ComputeCommand { num_groups_x: 1, num_groups_y: 1, num_groups_z: 1 },
+        ComputeCommand { num_groups_x: 4, num_groups_y: 1, num_groups_z: 1 },
+    ][..], BufferType::DispatchIndirectBuffer, BufferMode::Default).unwrap();
+
+    program.execute_indirect_at(uniform! { Counter: &counter }, &commands, 1);
+    assert_eq!(counter.read().unwrap().count, 4);
+
+    display.assert_no_error(None);
+}
+
+
+#[test]
+fn work_group_size() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+    if !ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let program = ComputeShader::from_source(&display, r"
+        #version 430
+        layout(local_size_x = 8, local_size_y = 4) in;
+
+        void main() {
+        }
+    ").unwrap();
+
+    assert_eq!(program.work_group_size(), (8, 4, 1));
+
+    let (max_x, max_y, max_z) = display.get_capabilities().max_compute_work_group_size;
+    assert!(max_x >= 8 && max_y >= 4 && max_z >= 1);
+    assert!(display.get_capabilities().max_compute_work_group_invocations >= 32);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn image_unit_validation() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+    if display.get_capabilities().max_image_units.is_none() {
+        return;
+    }
+
+    let texture = Texture2d::empty_with_format(&display, UncompressedFloatFormat::U8U8U8U8,
+                                               MipmapsOption::NoMipmap, 4, 4).unwrap();
+    match texture.image_unit(ImageUnitFormat::RGBA32F) {
+        Err(ImageUnitError::FormatNotCompatible { expected: ImageUnitFormat::RGBA32F, .. }) => (),
+        _ => panic!()
+    }
+    assert!(texture.image_unit(ImageUnitFormat::R32UI).is_ok());
+    assert_eq!(texture.image_unit(ImageUnitFormat::RGBA8).unwrap().set_level(1).unwrap_err(),
+               ImageUnitError::NoMipmapAtLevel(1));
+    match texture.image_unit(ImageUnitFormat::RGBA8).unwrap().set_layer(Some(0)) {
+        Err(ImageUnitError::LayeringNotSupported(_)) => (),
+        _ => panic!()
+    }
+
+    let array = Texture2dArray::empty_with_format(&display, UncompressedFloatFormat::U8U8U8U8,
+                                                  MipmapsOption::NoMipmap, 4, 4, 2).unwrap();
+    let image = array.image_unit(ImageUnitFormat::RGBA8).unwrap();
+    assert!(image.set_layer(Some(1)).is_ok());
+    assert_eq!(image.set_layer(Some(2)).unwrap_err(), ImageUnitError::LayerOutOfBounds(2));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn image_unit_store_layer() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+    if !ComputeShader::is_supported(&display) ||
+       display.get_capabilities().max_image_units.is_none()
+    {
+        return;
+    }
+
+    let program = ComputeShader::from_source(&display, r"
+        #version 430
+        layout(local_size_x = 1, local_size_y = 1) in;
+
+        layout(rgba8) writeonly uniform image2D image;
+
+        void main() {
+            imageStore(image, ivec2(gl_GlobalInvocationID.xy), vec4(1.0, 0.0, 0.0, 1.0));
+        }
+    ").unwrap();
+
+    let array = Texture2dArray::empty_with_format(&display, UncompressedFloatFormat::U8U8U8U8,
+                                                  MipmapsOption::NoMipmap, 4, 4, 2).unwrap();
+    let image = array.image_unit(ImageUnitFormat::RGBA8).unwrap()
+                     .set_layer(Some(1)).unwrap()
+                     .set_access(ImageUnitAccess::Write);
+
+    program.execute(uniform! { image: image }, 4, 4, 1);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn image_unit_store_and_read() {
+    use glium::CapabilitiesSource;
+    use glium::MemoryBarriers;
+
+    let display = support::build_display();
+    if !ComputeShader::is_supported(&display) ||
+       display.get_capabilities().max_image_units.is_none()
+    {
+        return;
+    }
+
+    let program = ComputeShader::from_source(&display, r"
+        #version 430
+        layout(local_size_x = 1, local_size_y = 1) in;
+
+        layout(rgba8) writeonly uniform image2D image;
+
+        void main() {
+            imageStore(image, ivec2(gl_GlobalInvocationID.xy), vec4(1.0, 0.0, 0.0, 1.0));
+        }
+    ").unwrap();
+
+    let texture = Texture2d::empty_with_format(&display, UncompressedFloatFormat::U8U8U8U8,
+                                               MipmapsOption::NoMipmap, 4, 4).unwrap();
+    let image = texture.image_unit(ImageUnitFormat::RGBA8).unwrap()
+                       .set_access(ImageUnitAccess::Write);
+
+    program.execute(uniform! { image: image }, 4, 4, 1);
+    display.memory_barrier(MemoryBarriers::TEXTURE_UPDATE).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "dds")]
+
+#[macro_use]
+extern crate glium;
+
+use glium::texture::dds::{DdsError, DdsTexture};
+
+mod support;
+
+/// Builds a DDS file containing an 8x8 DXT1 texture with mipmaps, optionally with six faces.
+fn build_dxt1_file(cubemap: bool) -> Vec<u8> {
+    let mut data = vec![0; 128];
+    data[.. 4].copy_from_slice(b"DDS ");
+    data[4 .. 8].copy_from_slice(&124u32.to_le_bytes());
+    data[8 .. 12].copy_from_slice(&0x20000u32.to_le_bytes());
+    data[12 .. 16].copy_from_slice(&8u32.to_le_bytes());
+    data[16 .. 20].copy_from_slice(&8u32.to_le_bytes());
+    data[28 .. 32].copy_from_slice(&4u32.to_le_bytes());
+    data[80 .. 84].copy_from_slice(&4u32.to_le_bytes());
+    data[84 .. 88].copy_from_slice(b"DXT1");
+    if cubemap {
+        data[112 .. 116].copy_from_slice(&0xFE00u32.to_le_bytes());
+    }
+
+    // 8x8, 4x4, 2x2 and 1x1 levels
+    let faces = if cubemap { 6 } else { 1 };
+    data.resize(128 + faces * (4 + 1 + 1 + 1) * 8, 0x55);
+    data
+}
+
+#[test]
+fn dxt1_texture_2d() {
+    let display = support::build_display();
+
+    if !glium::texture::CompressedFormat::S3tcDxt1Alpha.is_supported(&display) {
+        return;
+    }
+
+    let texture = glium::texture::CompressedTexture2d::from_dds(&display,
+                                                                &build_dxt1_file(false)).unwrap();
+    assert_eq!(texture.get_width(), 8);
+    assert_eq!(texture.get_height(), Some(8));
+    assert_eq!(texture.get_mipmap_levels(), 4);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn dxt1_cubemap() {
+    let display = support::build_display();
+
+    if !glium::texture::CompressedFormat::S3tcDxt1Alpha.is_supported(&display) ||
+       !glium::texture::is_cubemaps_supported(&display)
+    {
+        return;
+    }
+
+    let texture = glium::texture::CompressedSrgbCubemap::from_dds(&display,
+                                                                  &build_dxt1_file(true)).unwrap();
+    assert_eq!(texture.get_width(), 8);
+
+    match glium::texture::CompressedTexture2d::from_dds(&display, &build_dxt1_file(true)) {
+        Err(DdsError::WrongTextureType) => (),
+        _ => panic!(),
+    }
+
+    display.assert_no_error(None);
+}
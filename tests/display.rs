@@ -94,6 +94,61 @@ fn sync() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn frame_sync() {
+    let display = support::build_display();
+
+    let mut frame_sync = glium::FrameSync::new(2);
+
+    for _ in 0 .. 4 {
+        let mut frame = display.draw();
+        frame.clear_color(0.0, 0.0, 0.0, 0.0);
+        frame.finish().unwrap();
+
+        if frame_sync.end_frame(&display).is_err() {
+            return;
+        }
+
+        assert!(frame_sync.get_frames_in_flight() < 2);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn frame_pacer() {
+    use glium::framepacing::{FramePacer, LatencyMode};
+
+    let display = support::build_display();
+
+    let mut pacer = FramePacer::new(LatencyMode::Finish);
+
+    for i in 0 .. 6 {
+        if i == 3 {
+            pacer.set_latency_mode(LatencyMode::MaxFramesInFlight(1));
+        }
+
+        pacer.begin_frame(&display);
+
+        let mut frame = display.draw();
+        frame.clear_color(0.0, 0.0, 0.0, 0.0);
+        frame.finish().unwrap();
+
+        if pacer.end_frame(&display).is_err() {
+            return;
+        }
+    }
+
+    assert_eq!(pacer.get_latency_mode(), LatencyMode::MaxFramesInFlight(1));
+
+    // the GPU has finished every frame, so the timings of at least one of them are available
+    if glium::debug::TimestampQuery::new(&display).map(|q| q.get()).is_some() {
+        assert!(pacer.get_last_frame_stats().gpu_time.is_some());
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn multiple_displays() {
     let display1 = support::build_display();
@@ -133,6 +188,40 @@ fn multiple_displays() {
     display2.assert_no_error(None);
 }
 
+#[test]
+fn shared_displays() {
+    let display1 = support::build_display();
+    let display2 = support::build_shared_display(&display1);
+    assert!(display1.is_sharing_lists_with(&display2));
+
+    // the objects are created with the first display and used with the second one
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display1);
+    let texture = support::build_renderable_texture(&display1);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    glium::framebuffer::SimpleFrameBuffer::new(&display2, &texture).unwrap()
+        .draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+              &Default::default()).unwrap();
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in read_back.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    // destroying the objects must purge the caches of both displays
+    drop(texture);
+    drop(vb);
+
+    let mut frame = display2.draw();
+    frame.clear_color(0.0, 0.0, 0.0, 0.0);
+    frame.finish().unwrap();
+
+    display1.assert_no_error(None);
+    display2.assert_no_error(None);
+}
+
 #[test]
 fn debug_string() {
     // tests that `insert_debug_marker` doesn't trigger an OpenGL error
@@ -149,3 +238,136 @@ fn is_context_lost() {
     display.is_context_lost();
     display.assert_no_error(None);
 }
+
+#[test]
+fn swapchain_introspection() {
+    let display = support::build_display();
+
+    if let Some(count) = display.get_swapchain_image_count() {
+        assert!(count >= 1);
+    }
+
+    let mut frame = display.draw();
+    frame.clear_color(0.0, 0.0, 0.0, 0.0);
+    frame.finish().unwrap();
+
+    // the age is `0` if the content of the back buffer is undefined
+    let _ = display.get_buffer_age();
+    display.assert_no_error(None);
+}
+
+#[test]
+fn flush_policy() {
+    let display = support::build_display();
+    assert_eq!(display.get_flush_policy(), glium::backend::FlushPolicy::Implicit);
+
+    for &policy in &[glium::backend::FlushPolicy::FlushAfterCommands,
+                     glium::backend::FlushPolicy::FinishAfterSwap]
+    {
+        display.set_flush_policy(policy);
+        assert_eq!(display.get_flush_policy(), policy);
+
+        let mut frame = display.draw();
+        frame.clear_color(0.0, 0.0, 0.0, 0.0);
+        frame.finish().unwrap();
+    }
+
+    display.flush();
+    display.finish();
+    display.assert_no_error(None);
+}
+
+#[test]
+fn frame_back_buffer() {
+    use glium::{BackBuffer, CapabilitiesSource};
+
+    let display = support::build_display();
+    let stereo = display.get_capabilities().stereo;
+
+    let mut frame = display.draw();
+    assert_eq!(frame.get_back_buffer(), BackBuffer::Both);
+
+    assert!(frame.draw_to_buffer(BackBuffer::Left).is_ok());
+    frame.clear_color(1.0, 0.0, 0.0, 1.0);
+
+    assert_eq!(frame.draw_to_buffer(BackBuffer::Right).is_ok(), stereo);
+    frame.clear_color(0.0, 1.0, 0.0, 1.0);
+
+    frame.draw_to_buffer(BackBuffer::Both).unwrap();
+    frame.finish().unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn frame_invalidate() {
+    use glium::{InvalidateBuffers, Rect};
+
+    let display = support::build_display();
+
+    let mut frame = display.draw();
+    frame.clear_color_and_depth((0.0, 0.0, 0.0, 1.0), 1.0);
+    frame.invalidate_rect(&Rect { left: 0, bottom: 0, width: 1, height: 1 },
+                          InvalidateBuffers { depth: true, .. Default::default() });
+    frame.set_invalidate_depth_stencil_on_finish(true);
+    frame.finish().unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn framebuffer_pixel_format() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+
+    let format = display.get_framebuffer_pixel_format();
+    assert_eq!(format.float_color_buffer, display.get_capabilities().float_color_buffer);
+    assert_eq!(format.multisampling, display.get_capabilities().samples);
+
+    if format.color_buffer_format().is_some() {
+        assert!(format.color_bits >= 24);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn set_swap_interval() {
+    use glium::{SwapInterval, SwapIntervalError};
+
+    let display = support::build_display();
+
+    match display.set_swap_interval(SwapInterval::DontWait) {
+        Ok(()) | Err(SwapIntervalError::NotSupported) => (),
+        Err(e) => panic!("{}", e),
+    };
+
+    match display.set_swap_interval(SwapInterval::AdaptiveWait(1)) {
+        Ok(()) | Err(SwapIntervalError::NotSupported) |
+        Err(SwapIntervalError::AdaptiveNotSupported) => (),
+    };
+
+    let _ = display.set_swap_interval(SwapInterval::Wait(1));
+
+    let mut frame = display.draw();
+    frame.clear_color(0.0, 0.0, 0.0, 0.0);
+    frame.finish().unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn finish_with_damage() {
+    let display = support::build_display();
+
+    for _ in 0 .. 2 {
+        let mut frame = display.draw();
+        frame.clear_color(0.0, 0.0, 0.0, 0.0);
+        frame.finish_with_damage(&[glium::Rect { left: 0, bottom: 0, width: 1, height: 1 }])
+             .unwrap();
+    }
+
+    display.swap_buffers_with_damage(&[]).unwrap();
+    display.assert_no_error(None);
+}
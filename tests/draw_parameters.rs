@@ -771,6 +771,86 @@ blending_test!(one_plus_one, glium::BlendingFunction::Addition {
                },
                (0.0, 1.0, 1.0, 0.0), (1.0, 0.0, 0.0, 1.0), (255, 255, 255, 255));
 
+#[test]
+fn advanced_blending_multiply() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display, "
+            #version 140
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 140
+            #extension GL_KHR_blend_equation_advanced : require
+
+            layout(blend_support_multiply) out;
+            out vec4 color;
+
+            void main() {
+                color = vec4(0.5, 0.5, 0.5, 1.0);
+            }
+        ", None)
+    {
+        Ok(p) => p,
+        Err(_) => return,
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(1.0, 0.5, 0.0, 1.0);
+
+    let params = glium::DrawParameters {
+        blend: glium::Blend::advanced(glium::AdvancedBlendEquation::Multiply),
+        .. Default::default()
+    };
+
+    match texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &params) {
+        Err(glium::DrawError::BlendingParameterNotSupported) => {
+            assert!(!glium::draw_parameters::is_advanced_blending_supported(&display));
+            return;
+        },
+        res => res.unwrap(),
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    let (r, g, b, a) = data[0][0];
+    assert!(r >= 126 && r <= 129);
+    assert!(g >= 62 && g <= 65);
+    assert_eq!(b, 0);
+    assert_eq!(a, 255);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn advanced_blending_separate_alpha() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+
+    let params = glium::DrawParameters {
+        blend: glium::Blend {
+            color: glium::BlendingFunction::Advanced(glium::AdvancedBlendEquation::Screen),
+            alpha: glium::BlendingFunction::AlwaysReplace,
+            constant_value: (0.0, 0.0, 0.0, 0.0),
+        },
+        .. Default::default()
+    };
+
+    match texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &params) {
+        Err(glium::DrawError::BlendingParameterNotSupported) => (),
+        _ => panic!(),
+    };
+
+    display.assert_no_error(None);
+}
+
 
 #[test]
 fn provoking_vertex_last() {
@@ -1264,8 +1344,18 @@ fn depth_clamp_far() {
 
 #[test]
 fn primitive_bounding_box() {
+    use glium::{Api, CapabilitiesSource, Version};
+
     let display = support::build_display();
 
+    let expected = *display.get_version() >= Version(Api::GlEs, 3, 2) ||
+                   display.get_extensions().gl_arb_es3_2_compatibility ||
+                   display.get_extensions().gl_oes_primitive_bounding_box ||
+                   display.get_extensions().gl_ext_primitive_bounding_box;
+    assert_eq!(glium::draw_parameters::is_primitive_bounding_box_supported(&display), expected);
+
+    // the bounding box is only a hint, so the draw must succeed whether it is supported or not
+
     let params = glium::DrawParameters {
         primitive_bounding_box: (0.0 .. 1.0, -0.2 .. 0.3, 0.0 .. 1.0, -1.0 .. 1.0),
         .. Default::default()
@@ -1408,3 +1498,613 @@ fn primitive_restart_index() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn primitive_restart_custom_index() {
+    let display = support::build_display();
+
+    // same as `primitive_restart_index`, but with a custom index
+    let vertex_buffer = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            position: (f32, f32, f32),
+        }
+
+        implement_vertex!(Vertex, position);
+
+        glium::VertexBuffer::new(&display, &[
+            Vertex { position: (-0.5, -0.5, 0.0) },
+            Vertex { position: ( 0.5, -0.5, 0.0) },
+            Vertex { position: (-0.5,  0.5, 0.0) },
+            Vertex { position: ( 0.5,  0.5, 0.0) },
+        ]).unwrap()
+    };
+
+    let index_buffer = glium::IndexBuffer::<u16>::new(&display,
+                                                      glium::index::PrimitiveType::LineStrip,
+                                                      &[0, 1, 7, 2, 3]).unwrap();
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec3 position;
+
+                void main() {
+                    gl_Position = vec4(position, 1.0);
+                }
+            ",
+            fragment: "
+                #version 140
+
+                out vec4 color;
+                void main() {
+                    color = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            "
+        },
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec3 position;
+
+                void main() {
+                    gl_Position = vec4(position, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            "
+        },
+    ).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(1.0, 1.0, 1.0, 1.0);
+
+    let options = glium::DrawParameters {
+        primitive_restart_custom_index: Some(7),
+        .. Default::default()
+    };
+
+    let res = texture.as_surface().draw(&vertex_buffer, &index_buffer, &program,
+                                        &glium::uniforms::EmptyUniforms, &options);
+
+    match res {
+        Ok(_) => (),
+        Err(glium::DrawError::CustomIndexRestartingNotSupported) => {
+            display.assert_no_error(None);
+            return;
+        },
+        e => e.unwrap(),
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    let mid_x = texture.get_width() as usize / 2;
+    let mid_y = texture.get_height().unwrap() as usize / 2;
+
+    for row in (mid_y - 2)..(mid_y + 2) {
+        for pixel in (mid_x - 2)..(mid_x + 2){
+            assert_eq!(data[row][pixel], (255, 255, 255, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn patch_default_levels() {
+    let display = support::build_display();
+
+    if !glium::program::is_tessellation_shader_supported(&display) {
+        return;
+    }
+
+    let vertex_buffer = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            position: [f32; 2],
+        }
+
+        implement_vertex!(Vertex, position);
+
+        glium::VertexBuffer::new(&display, &[
+            Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+            Vertex { position: [-1.0, 1.0] }, Vertex { position: [1.0, -1.0] },
+            Vertex { position: [1.0, 1.0] }, Vertex { position: [-1.0, 1.0] },
+        ]).unwrap()
+    };
+
+    // no tessellation control shader
+    let program = glium::Program::new(&display, glium::program::SourceCode {
+        vertex_shader: "
+            #version 400
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: Some("
+            #version 400
+
+            layout(triangles, equal_spacing) in;
+
+            void main() {
+                gl_Position = gl_TessCoord.x * gl_in[0].gl_Position +
+                              gl_TessCoord.y * gl_in[1].gl_Position +
+                              gl_TessCoord.z * gl_in[2].gl_Position;
+            }
+        "),
+        geometry_shader: None,
+        fragment_shader: "
+            #version 400
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+    }).unwrap();
+
+    let indices = glium::index::NoIndices(glium::index::PrimitiveType::Patches {
+        vertices_per_patch: 3,
+    });
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let params = glium::DrawParameters {
+        patch_default_outer_level: [4.0; 4],
+        patch_default_inner_level: [4.0; 2],
+        .. Default::default()
+    };
+
+    texture.as_surface().draw(&vertex_buffer, &indices, &program,
+                              &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn min_sample_shading() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let params = glium::DrawParameters {
+        min_sample_shading: Some(1.0),
+        .. Default::default()
+    };
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                    &params)
+    {
+        Err(glium::DrawError::SampleShadingNotSupported) => return,
+        res => res.unwrap(),
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn color_logic_op_xor() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(1.0, 1.0, 0.0, 1.0);
+
+    let params = glium::DrawParameters {
+        color_logic_op: Some(glium::LogicOp::Xor),
+        .. Default::default()
+    };
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                    &params)
+    {
+        Err(glium::DrawError::LogicOpNotSupported) => return,
+        res => res.unwrap(),
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 0));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn depth_bounds() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    let depth = match glium::texture::DepthTexture2d::empty(&display, 1024, 1024) {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                                   &texture, &depth).unwrap();
+
+    // the depth buffer contains 0.5, which is outside of the bounds
+    framebuffer.clear_color_and_depth((0.0, 0.0, 0.0, 0.0), 0.5);
+
+    let params = glium::DrawParameters {
+        depth_bounds: Some((0.0, 0.25)),
+        .. Default::default()
+    };
+
+    match framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Err(glium::DrawError::DepthBoundsNotSupported) => return,
+        res => res.unwrap(),
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 0, 0, 0));
+        }
+    }
+
+    // now 0.5 is inside the bounds
+    let params = glium::DrawParameters {
+        depth_bounds: Some((0.25, 0.75)),
+        .. Default::default()
+    };
+
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn clip_depth_mode_zero_to_one() {
+    let display = support::build_display();
+
+    let vertex_buffer = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            position: (f32, f32, f32),
+        }
+
+        implement_vertex!(Vertex, position);
+
+        glium::VertexBuffer::new(&display, &[
+            Vertex { position: (-1.0, 1.0, -0.5) },
+            Vertex { position: (1.0, 1.0, -0.5) },
+            Vertex { position: (-1.0, -1.0, -0.5) },
+            Vertex { position: (1.0, -1.0, -0.5) },
+        ]).unwrap()
+    };
+
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec3 position;
+
+                void main() {
+                    gl_Position = vec4(position, 1.0);
+                }
+            ",
+            fragment: "
+                #version 140
+
+                out vec4 color;
+                void main() {
+                    color = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            "
+        },
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec3 position;
+
+                void main() {
+                    gl_Position = vec4(position, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            "
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec3 position;
+
+                void main() {
+                    gl_Position = vec4(position, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            "
+        },
+    ).unwrap();
+
+    // a Z coordinate of -0.5 is outside of the clip volume with `ZeroToOne`
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    let res = texture.as_surface().draw(&vertex_buffer,
+        &glium::index::NoIndices(glium::index::PrimitiveType::TriangleStrip), &program,
+        &glium::uniforms::EmptyUniforms,
+        &glium::DrawParameters {
+            clip_depth_mode: glium::draw_parameters::ClipDepthMode::ZeroToOne,
+            .. Default::default()
+        });
+
+    match res {
+        Ok(_) => (),
+        Err(glium::DrawError::ClipControlNotSupported) => {
+            display.assert_no_error(None);
+            return;
+        },
+        e => e.unwrap(),
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 0, 0, 0));
+        }
+    }
+
+    // the default mode must be restored by the next draw
+    texture.as_surface().draw(&vertex_buffer,
+        &glium::index::NoIndices(glium::index::PrimitiveType::TriangleStrip), &program,
+        &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn clip_distance() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 130
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+                gl_ClipDistance[0] = position.x;
+            }
+        ",
+        "
+            #version 130
+
+            out vec4 color;
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Ok(p) => p,
+        Err(_) => return,
+    };
+
+    if display.get_capabilities().max_clip_distances < 1 {
+        return;
+    }
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &glium::DrawParameters {
+                                  clip_planes_bitmask: 1,
+                                  .. Default::default()
+                              }).unwrap();
+
+    // the left half of the texture has a negative clip distance
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        assert_eq!(row[0], (0, 0, 0, 0));
+        assert_eq!(row[row.len() - 1], (255, 0, 0, 255));
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn polygon_offset_clamp() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    let depth = match glium::texture::DepthTexture2d::empty(&display, 1024, 1024) {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                                   &texture, &depth).unwrap();
+
+    let mut params = glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::DepthTest::IfLess,
+            write: false,
+            .. Default::default()
+        },
+        polygon_offset: glium::draw_parameters::PolygonOffset {
+            units: -1000000.0,
+            fill: true,
+            .. Default::default()
+        },
+        .. Default::default()
+    };
+
+    // the geometry has a depth of 0.5, and the offset brings it in front of the depth buffer
+    framebuffer.clear_color_and_depth((0.0, 0.0, 0.0, 0.0), 0.495);
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    // the clamped offset is not large enough
+    params.polygon_offset.clamp = -0.001;
+    framebuffer.clear_color_and_depth((0.0, 0.0, 0.0, 0.0), 0.495);
+
+    match framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Err(glium::DrawError::PolygonOffsetNotSupported) => return,
+        res => res.unwrap(),
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 0, 0, 0));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn viewport_array() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    if display.get_capabilities().max_viewports < 2 {
+        return;
+    }
+
+    // each triangle is drawn once in each viewport
+    let program = glium::Program::new(&display, glium::program::SourceCode {
+        vertex_shader: "
+            #version 410
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: Some("
+            #version 410
+
+            layout(triangles) in;
+            layout(triangle_strip, max_vertices = 6) out;
+
+            void main() {
+                for (int v = 0; v < 2; ++v) {
+                    for (int i = 0; i < 3; ++i) {
+                        gl_Position = gl_in[i].gl_Position;
+                        gl_ViewportIndex = v;
+                        EmitVertex();
+                    }
+                    EndPrimitive();
+                }
+            }
+        "),
+        fragment_shader: "
+            #version 410
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+    }).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let left = glium::Rect { left: 0, bottom: 0, width: 512, height: 1024 };
+    let right = glium::Rect { left: 512, bottom: 0, width: 512, height: 1024 };
+    let bottom_right = glium::Rect { left: 512, bottom: 0, width: 512, height: 512 };
+
+    let params = glium::DrawParameters {
+        viewports: &[(left, None), (right, Some(bottom_right))],
+        .. Default::default()
+    };
+
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data[1023][0], (255, 0, 0, 255));
+    assert_eq!(data[0][1023], (255, 0, 0, 255));
+    assert_eq!(data[1023][1023], (0, 0, 0, 0));
+
+    // the first viewport has no scissor box, so clearing must enable the scissor test again
+    texture.as_surface().clear(Some(&glium::Rect { left: 0, bottom: 0, width: 1, height: 1 }),
+                               Some((0.0, 0.0, 1.0, 1.0)), false, None, None);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 255, 255));
+    assert_eq!(data[1023][1023], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
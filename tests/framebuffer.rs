@@ -254,6 +254,84 @@ fn multioutput() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn multioutput_blend_per_attachment() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            layout(location = 0) out vec4 color1;
+            layout(location = 1) out vec4 color2;
+
+            void main() {
+                color1 = vec4(1.0, 0.0, 0.0, 1.0);
+                color2 = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Err(glium::CompilationError(..)) => return,
+        Ok(p) => p,
+        e => e.unwrap()
+    };
+
+    let color1 = support::build_renderable_texture(&display);
+    color1.as_surface().clear_color(0.0, 0.0, 1.0, 1.0);
+    let color2 = support::build_renderable_texture(&display);
+    color2.as_surface().clear_color(0.0, 0.0, 1.0, 1.0);
+
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                               [("color1", &color1), ("color2", &color2)].iter().cloned()).unwrap();
+
+    let addition = glium::BlendingFunction::Addition {
+        source: glium::LinearBlendingFactor::One,
+        destination: glium::LinearBlendingFactor::One,
+    };
+    let per_attachment = [None, Some(glium::Blend {
+        color: addition,
+        alpha: addition,
+        constant_value: (0.0, 0.0, 0.0, 0.0),
+    })];
+
+    let params = glium::DrawParameters {
+        blend_per_attachment: &per_attachment,
+        .. Default::default()
+    };
+
+    match framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Err(glium::DrawError::BlendingParameterNotSupported) => return,
+        res => res.unwrap(),
+    };
+
+    let read_back1: Vec<Vec<(u8, u8, u8, u8)>> = color1.read();
+    assert_eq!(read_back1[0][0], (255, 0, 0, 255));
+
+    let read_back2: Vec<Vec<(u8, u8, u8, u8)>> = color2.read();
+    assert_eq!(read_back2[0][0], (255, 0, 255, 255));
+
+    // the next draw without overrides must blend neither attachment
+    color2.as_surface().clear_color(0.0, 0.0, 1.0, 1.0);
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    let read_back2: Vec<Vec<(u8, u8, u8, u8)>> = color2.read();
+    assert_eq!(read_back2[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn array_level() {
     let display = support::build_display();
@@ -447,3 +525,496 @@ fn empty_framebuffer_simple_draw() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn empty_framebuffer_samples_passed() {
+    use glium::framebuffer::EmptyFrameBuffer;
+    use glium::draw_parameters::SamplesPassedQuery;
+
+    let display = support::build_display();
+    let (vertex_buffer, index_buffer, program) = support::build_fullscreen_red_pipeline(&display);
+
+    // ignore the test
+    if !EmptyFrameBuffer::is_supported(&display) {
+        return;
+    }
+
+    let query = match SamplesPassedQuery::new(&display) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    let mut fb = EmptyFrameBuffer::new(&display, 64, 32, None, None, true).unwrap();
+    assert_eq!(fb.get_dimensions(), (64, 32));
+
+    let params = glium::DrawParameters {
+        samples_passed_query: Some((&query).into()),
+        .. Default::default()
+    };
+
+    fb.draw(&vertex_buffer, &index_buffer, &program,
+            &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    assert_eq!(query.get(), 64 * 32);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sample_locations() {
+    use glium::framebuffer::{SampleLocationsError, SimpleFrameBuffer};
+    use glium::texture::Texture2dMultisample;
+
+    let display = support::build_display();
+
+    let texture = match Texture2dMultisample::empty(&display, 64, 64, 4) {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+    let mut fb = SimpleFrameBuffer::new(&display, &texture).unwrap();
+
+    if !glium::framebuffer::is_sample_locations_supported(&display) {
+        match fb.set_sample_locations(&[(0.5, 0.5)]) {
+            Err(SampleLocationsError::NotSupported) => (),
+            _ => panic!(),
+        };
+        return;
+    }
+
+    let info = fb.get_sample_locations_info().unwrap();
+    assert!(info.table_size >= info.samples);
+
+    let locations = (0 .. info.samples).map(|s| (0.25 + s as f32 * 0.1, 0.5))
+                                       .collect::<Vec<_>>();
+    fb.set_sample_locations(&locations).unwrap();
+    fb.clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let too_many = vec![(0.5, 0.5); info.table_size as usize + 1];
+    match fb.set_sample_locations(&too_many) {
+        Err(SampleLocationsError::TooManyLocations { .. }) => (),
+        _ => panic!(),
+    };
+
+    fb.set_sample_locations(&[]).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn copy_region_to_cpu() {
+    use glium::framebuffer::SimpleFrameBuffer;
+    use glium::texture::{MipmapsOption, Texture2d, UncompressedFloatFormat};
+    use glium::{ColorSpace, CopyToCpuOptions, Rect};
+
+    let display = support::build_display();
+
+    let texture = Texture2d::empty_with_format(&display, UncompressedFloatFormat::U8U8U8U8,
+                                               MipmapsOption::NoMipmap, 2, 2).unwrap();
+    let mut framebuffer = SimpleFrameBuffer::new(&display, &texture).unwrap();
+
+    // bottom row is red, top row is half-transparent white
+    framebuffer.clear_color(1.0, 1.0, 1.0, 0.5);
+    framebuffer.clear(Some(&Rect { left: 0, bottom: 0, width: 2, height: 1 }),
+                      Some((1.0, 0.0, 0.0, 1.0)), false, None, None);
+
+    let rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+
+    let image = framebuffer.copy_region_to_cpu(&rect, ColorSpace::Linear).unwrap();
+    assert_eq!((image.width, image.height), (2, 2));
+    assert_eq!(image.data, vec![255, 255, 255, 128, 255, 255, 255, 128,
+                                255, 0, 0, 255, 255, 0, 0, 255]);
+
+    let image = framebuffer.copy_region_to_cpu_with_options(&Rect { width: 1, .. rect },
+                                                             &CopyToCpuOptions {
+        color_space: ColorSpace::Linear,
+        top_to_bottom: false,
+        premultiply_alpha: true,
+    }).unwrap();
+    assert_eq!(image.data, vec![255, 0, 0, 255, 128, 128, 128, 128]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn read_async() {
+    use glium::framebuffer::SimpleFrameBuffer;
+    use glium::texture::{MipmapsOption, Texture2d, UncompressedFloatFormat};
+    use glium::Rect;
+
+    let display = support::build_display();
+
+    let texture = Texture2d::empty_with_format(&display, UncompressedFloatFormat::U8U8U8U8,
+                                               MipmapsOption::NoMipmap, 2, 2).unwrap();
+    let mut framebuffer = SimpleFrameBuffer::new(&display, &texture).unwrap();
+
+    framebuffer.clear_color(0.0, 0.0, 1.0, 1.0);
+    framebuffer.clear(Some(&Rect { left: 0, bottom: 0, width: 2, height: 1 }),
+                      Some((1.0, 0.0, 0.0, 1.0)), false, None, None);
+
+    let pending = framebuffer.read_async(&Rect { left: 1, bottom: 0, width: 1, height: 2 })
+                             .unwrap();
+
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = match pending.read() {
+        Ok(p) => p,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+    assert_eq!(pixels, vec![vec![(255, 0, 0, 255)], vec![(0, 0, 255, 255)]]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn read_async_future_woken_after_swap() {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::task::{Context, Poll, Wake, Waker};
+    use glium::framebuffer::SimpleFrameBuffer;
+    use glium::texture::{MipmapsOption, Texture2d, UncompressedFloatFormat};
+    use glium::Rect;
+
+    struct Flag(AtomicBool);
+    impl Wake for Flag {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    let display = support::build_display();
+
+    let texture = Texture2d::empty_with_format(&display, UncompressedFloatFormat::U8U8U8U8,
+                                               MipmapsOption::NoMipmap, 1, 1).unwrap();
+    let mut framebuffer = SimpleFrameBuffer::new(&display, &texture).unwrap();
+    framebuffer.clear_color(1.0, 0.0, 0.0, 1.0);
+
+    let mut pending = match framebuffer.read_async(&Rect { left: 0, bottom: 0, width: 1,
+                                                           height: 1 }) {
+        Ok(p) => p,
+        Err(glium::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    let flag = Arc::new(Flag(AtomicBool::new(false)));
+    let waker = Waker::from(flag.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = loop {
+        match Pin::new(&mut pending).poll(&mut cx) {
+            Poll::Ready(pixels) => break pixels.unwrap(),
+            Poll::Pending => {
+                // the task must not be woken up before the next frame
+                assert!(!flag.0.load(Ordering::SeqCst));
+                display.swap_buffers().unwrap();
+                assert!(flag.0.swap(false, Ordering::SeqCst));
+            }
+        }
+    };
+    assert_eq!(pixels, vec![vec![(255, 0, 0, 255)]]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn render_target_follows_window() {
+    let display = support::build_display();
+
+    let mut target = glium::framebuffer::RenderTarget::new(&display, 0.5).unwrap();
+
+    let (width, height) = display.get_framebuffer_dimensions();
+    let expected = (((width as f32 * 0.5).round() as u32).max(1),
+                    ((height as f32 * 0.5).round() as u32).max(1));
+    assert_eq!(target.get_dimensions(), expected);
+    assert_eq!(target.resize().unwrap(), false);
+
+    target.as_surface().clear_color_and_depth((0.0, 1.0, 0.0, 1.0), 1.0);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.get_color_texture().read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    target.set_scale(1.0);
+    assert_eq!(target.resize().unwrap(), true);
+    assert_eq!(target.get_dimensions(), (width, height));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn layered_array() {
+    use glium::framebuffer::SimpleFrameBuffer;
+    use glium::texture::{MipmapsOption, Texture2dArray, UncompressedFloatFormat};
+    use glium::{ColorSpace, Rect};
+
+    let display = support::build_display();
+    if !glium::framebuffer::is_layered_attachments_supported(&display) {
+        return;
+    }
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    // the rectangle is only drawn in the second layer
+    let program = match glium::Program::new(&display, glium::program::SourceCode {
+        vertex_shader: "
+            #version 150
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: Some("
+            #version 150
+
+            layout(triangles) in;
+            layout(triangle_strip, max_vertices = 3) out;
+
+            void main() {
+                for (int i = 0; i < 3; ++i) {
+                    gl_Position = gl_in[i].gl_Position;
+                    gl_Layer = 1;
+                    EmitVertex();
+                }
+                EndPrimitive();
+            }
+        "),
+        fragment_shader: "
+            #version 150
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+    }) {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let texture = Texture2dArray::empty_with_format(&display, UncompressedFloatFormat::U8U8U8U8,
+                                                    MipmapsOption::NoMipmap, 2, 2, 2).unwrap();
+
+    let mut framebuffer = SimpleFrameBuffer::layered(&display, texture.main_level()).unwrap();
+    assert_eq!(framebuffer.get_dimensions(), (2, 2));
+
+    // clearing a layered framebuffer clears all the layers
+    framebuffer.clear_color(0.0, 0.0, 1.0, 1.0);
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    let rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let layer = |index| {
+        SimpleFrameBuffer::new(&display, texture.main_level().layer(index).unwrap()).unwrap()
+            .copy_region_to_cpu(&rect, ColorSpace::Linear).unwrap().data
+    };
+
+    assert_eq!(layer(0), vec![0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255, 255]);
+    assert_eq!(layer(1), vec![255, 0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multiview() {
+    use glium::framebuffer::SimpleFrameBuffer;
+    use glium::texture::{MipmapsOption, Texture2dArray, UncompressedFloatFormat};
+    use glium::{ColorSpace, Rect};
+
+    let display = support::build_display();
+    if !glium::framebuffer::is_multiview_supported(&display) {
+        return;
+    }
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    // the first view is red and the second one is green
+    let program = match glium::Program::from_source(&display, "
+            #version 330
+            #extension GL_OVR_multiview2 : require
+            layout(num_views = 2) in;
+
+            in vec2 position;
+            flat out uint view;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+                view = gl_ViewID_OVR;
+            }
+        ",
+        "
+            #version 330
+
+            flat in uint view;
+            out vec4 color;
+
+            void main() {
+                color = view == 0u ? vec4(1.0, 0.0, 0.0, 1.0) : vec4(0.0, 1.0, 0.0, 1.0);
+            }
+        ", None)
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+    assert_eq!(program.get_num_views(), Some(2));
+
+    let texture = Texture2dArray::empty_with_format(&display, UncompressedFloatFormat::U8U8U8U8,
+                                                    MipmapsOption::NoMipmap, 2, 2, 2).unwrap();
+
+    let mut framebuffer = SimpleFrameBuffer::multiview(&display, texture.main_level(), 2).unwrap();
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    // a program without multiview can't draw into a multiview framebuffer
+    let (_, _, regular) = support::build_fullscreen_red_pipeline(&display);
+    match framebuffer.draw(&vb, &ib, &regular, &glium::uniforms::EmptyUniforms,
+                           &Default::default())
+    {
+        Err(glium::DrawError::NumViewsMismatch) => (),
+        e => panic!("{:?}", e)
+    }
+
+    let rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let layer = |index| {
+        SimpleFrameBuffer::new(&display, texture.main_level().layer(index).unwrap()).unwrap()
+            .copy_region_to_cpu(&rect, ColorSpace::Linear).unwrap().data
+    };
+
+    assert_eq!(layer(0), vec![255, 0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255]);
+    assert_eq!(layer(1), vec![0, 255, 0, 255, 0, 255, 0, 255, 0, 255, 0, 255, 0, 255, 0, 255]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn stencil_only_render_buffer() {
+    use glium::framebuffer::{SimpleFrameBuffer, StencilRenderBuffer};
+    use glium::texture::{MipmapsOption, StencilFormat, Texture2d, UncompressedFloatFormat};
+    use glium::{ColorSpace, Rect};
+
+    let display = support::build_display();
+    let (vertex_buffer, index_buffer, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let stencil = match StencilRenderBuffer::new(&display, StencilFormat::I8, 2, 2) {
+        Ok(s) => s,
+        Err(_) => return
+    };
+
+    let texture = Texture2d::empty_with_format(&display, UncompressedFloatFormat::U8U8U8U8,
+                                               MipmapsOption::NoMipmap, 2, 2).unwrap();
+    let mut framebuffer = match SimpleFrameBuffer::with_stencil_buffer(&display, &texture,
+                                                                        &stencil)
+    {
+        Ok(f) => f,
+        Err(_) => return
+    };
+
+    assert_eq!(framebuffer.get_depth_buffer_bits(), None);
+    assert_eq!(framebuffer.get_stencil_buffer_bits(), Some(8));
+
+    // only the bottom row passes the stencil test
+    framebuffer.clear_color(0.0, 0.0, 0.0, 1.0);
+    framebuffer.clear_stencil(0);
+    framebuffer.clear(Some(&Rect { left: 0, bottom: 0, width: 2, height: 1 }), None, false,
+                      None, Some(1));
+
+    let parameters = glium::DrawParameters {
+        stencil: glium::draw_parameters::Stencil {
+            test_clockwise: glium::StencilTest::IfEqual { mask: 0xff },
+            test_counter_clockwise: glium::StencilTest::IfEqual { mask: 0xff },
+            reference_value_clockwise: 1,
+            reference_value_counter_clockwise: 1,
+            write_mask_clockwise: 0,
+            write_mask_counter_clockwise: 0,
+            .. Default::default()
+        },
+        .. Default::default()
+    };
+
+    framebuffer.draw(&vertex_buffer, &index_buffer, &program, &uniform!{}, &parameters).unwrap();
+
+    let rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let data = framebuffer.copy_region_to_cpu(&rect, ColorSpace::Linear).unwrap().data;
+    assert_eq!(data, vec![0, 0, 0, 255, 0, 0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255]);
+
+    // the write mask of the previous draw must not apply to the clear
+    framebuffer.clear_stencil(1);
+    framebuffer.draw(&vertex_buffer, &index_buffer, &program, &uniform!{}, &parameters).unwrap();
+
+    let data = framebuffer.copy_region_to_cpu(&rect, ColorSpace::Linear).unwrap().data;
+    assert_eq!(data, vec![255, 0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn invalidate() {
+    use glium::framebuffer::{DepthRenderBuffer, SimpleFrameBuffer};
+    use glium::texture::{DepthFormat, MipmapsOption, Texture2d, UncompressedFloatFormat};
+    use glium::{ColorSpace, InvalidateBuffers, Rect};
+
+    let display = support::build_display();
+
+    let texture = Texture2d::empty_with_format(&display, UncompressedFloatFormat::U8U8U8U8,
+                                               MipmapsOption::NoMipmap, 2, 2).unwrap();
+    let depth = DepthRenderBuffer::new(&display, DepthFormat::I24, 2, 2).unwrap();
+    let mut framebuffer = SimpleFrameBuffer::with_depth_buffer(&display, &texture,
+                                                               &depth).unwrap();
+
+    framebuffer.clear_color_and_depth((0.0, 0.0, 0.0, 1.0), 1.0);
+    framebuffer.invalidate(InvalidateBuffers { depth: true, .. Default::default() });
+    framebuffer.invalidate_rect(&Rect { left: 0, bottom: 0, width: 1, height: 1 },
+                                InvalidateBuffers { color: true, depth: true, stencil: true });
+
+    // the framebuffer can still be used afterwards
+    framebuffer.clear_color(1.0, 0.0, 0.0, 1.0);
+    let data = framebuffer.copy_region_to_cpu(&Rect { left: 0, bottom: 0, width: 1, height: 1 },
+                                              ColorSpace::Linear).unwrap().data;
+    assert_eq!(data, vec![255, 0, 0, 255]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn color_space_validation() {
+    let display = support::build_display();
+    display.set_color_space_validation(true);
+
+    let program = program!(&display,
+        110 => {
+            outputs_srgb: true,
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        },
+    ).unwrap();
+    let (vertex_buffer, index_buffer) = support::build_rectangle_vb_ib(&display);
+
+    let texture = glium::texture::SrgbTexture2d::empty(&display, 64, 64).unwrap();
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+
+    match framebuffer.draw(&vertex_buffer, &index_buffer, &program,
+                           &glium::uniforms::EmptyUniforms, &Default::default())
+    {
+        Err(glium::DrawError::ColorSpaceMismatch) => (),
+        a => panic!("{:?}", a)
+    };
+
+    display.assert_no_error(None);
+}
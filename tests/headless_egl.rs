@@ -0,0 +1,48 @@
+#![cfg(feature = "headless-egl")]
+
+extern crate glium;
+
+use glium::Surface;
+use glium::backend::headless::Headless;
+
+#[test]
+fn clear_default_framebuffer() {
+    let headless = Headless::new(64, 32).unwrap();
+    if !headless.has_default_framebuffer() {
+        return;
+    }
+
+    assert_eq!(headless.get_framebuffer_dimensions(), (64, 32));
+
+    let mut frame = headless.draw();
+    frame.clear_color(1.0, 0.0, 0.0, 1.0);
+    frame.finish().unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = headless.read_front_buffer().unwrap();
+    assert_eq!(data.len(), 32);
+    for row in data.iter() {
+        assert_eq!(row.len(), 64);
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    headless.assert_no_error(None);
+}
+
+#[test]
+fn draw_on_texture() {
+    let headless = Headless::new(16, 16).unwrap();
+
+    let texture = glium::Texture2d::empty(&headless, 16, 16).unwrap();
+    texture.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    headless.assert_no_error(None);
+}
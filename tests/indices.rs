@@ -414,3 +414,67 @@ fn multidraw_elements() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn multidraw_elements_count() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2, 1, 3, 2]).unwrap();
+
+    let multidraw = glium::index::DrawCommandsIndicesBuffer::empty(&display, 1);
+    let multidraw = match multidraw {
+        Ok(buf) => buf,
+        Err(_) => return
+    };
+
+    multidraw.write(&[
+        glium::index::DrawCommandIndices {
+            count: 6,
+            instance_count: 1,
+            first_index: 0,
+            base_vertex: 0,
+            base_instance: 0,
+        }
+    ]);
+
+    let count = match glium::buffer::ParameterBuffer::new(&display, 0) {
+        Ok(buf) => buf,
+        Err(_) => return
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    match texture.as_surface().draw(&vb, multidraw.with_index_buffer_and_count(&indices, &count, 1),
+                                    &program, &uniform!{}, &Default::default())
+    {
+        Err(glium::DrawError::IndirectCountNotSupported) => return,
+        r => r.unwrap()
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 0, 0, 0));
+        }
+    }
+
+    count.write(&1);
+    texture.as_surface().draw(&vb, multidraw.with_index_buffer_and_count(&indices, &count, 1),
+                              &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
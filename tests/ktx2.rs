@@ -0,0 +1,91 @@
+#![cfg(feature = "ktx2")]
+
+#[macro_use]
+extern crate glium;
+
+use glium::texture::ktx2::{self, Ktx2Error, Ktx2Texture};
+use glium::texture::{Dimensions, MipmapsOption, UncompressedFloatFormat};
+
+mod support;
+
+#[test]
+fn texture_2d_round_trip() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::with_format(&display, vec![
+        vec![(0u8, 1u8, 2u8, 3u8), (4u8, 8u8, 16u8, 32u8)],
+        vec![(32u8, 64u8, 128u8, 255u8), (32u8, 16u8, 4u8, 2u8)],
+    ], UncompressedFloatFormat::U8U8U8U8, MipmapsOption::NoMipmap).unwrap();
+
+    let data = match texture.to_ktx2() {
+        Ok(data) => data,
+        Err(Ktx2Error::ReadNotSupported) => return,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    let info = ktx2::read_info(&data).unwrap();
+    assert_eq!(info.dimensions, Dimensions::Texture2d { width: 2, height: 2 });
+    assert_eq!(info.mipmap_levels, 1);
+
+    let loaded = glium::texture::Texture2d::from_ktx2(&display, &data).unwrap();
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = loaded.read();
+
+    assert_eq!(read_back[0][0], (0, 1, 2, 3));
+    assert_eq!(read_back[0][1], (4, 8, 16, 32));
+    assert_eq!(read_back[1][0], (32, 64, 128, 255));
+    assert_eq!(read_back[1][1], (32, 16, 4, 2));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_array_round_trip_with_mipmaps() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2dArray::empty_with_format(&display,
+                                                UncompressedFloatFormat::U8U8U8U8,
+                                                MipmapsOption::EmptyMipmaps, 16, 8, 3).unwrap();
+
+    let data = match texture.to_ktx2() {
+        Ok(data) => data,
+        Err(Ktx2Error::ReadNotSupported) => return,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    let info = ktx2::read_info(&data).unwrap();
+    assert_eq!(info.dimensions, Dimensions::Texture2dArray { width: 16, height: 8, array_size: 3 });
+    assert_eq!(info.mipmap_levels, texture.get_mipmap_levels());
+
+    let loaded = glium::texture::Texture2dArray::from_ktx2(&display, &data).unwrap();
+    assert_eq!(loaded.get_mipmap_levels(), texture.get_mipmap_levels());
+    assert_eq!(loaded.get_array_size(), Some(3));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn wrong_texture_type() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::empty_with_format(&display,
+                                                UncompressedFloatFormat::U8U8U8U8,
+                                                MipmapsOption::NoMipmap, 4, 4).unwrap();
+
+    let data = match texture.to_ktx2() {
+        Ok(data) => data,
+        Err(Ktx2Error::ReadNotSupported) => return,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    match glium::texture::Texture2dArray::from_ktx2(&display, &data) {
+        Err(Ktx2Error::WrongTextureType) => (),
+        _ => panic!(),
+    }
+
+    match glium::texture::SrgbTexture2d::from_ktx2(&display, &data) {
+        Err(Ktx2Error::WrongTextureType) => (),
+        _ => panic!(),
+    }
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,112 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::backend::Facade;
+use glium::program::ProgramCreationInput;
+
+mod support;
+
+/// Builds a program whose mesh shader outputs two triangles covering the whole target.
+fn build_mesh_program<F: ?Sized>(facade: &F) -> glium::Program where F: Facade {
+    glium::Program::new(facade, ProgramCreationInput::MeshSourceCode {
+        task_shader: None,
+        mesh_shader: "
+            #version 450
+            #extension GL_NV_mesh_shader : require
+
+            layout(local_size_x = 1) in;
+            layout(triangles, max_vertices = 4, max_primitives = 2) out;
+
+            void main() {
+                gl_MeshVerticesNV[0].gl_Position = vec4(-1.0, -1.0, 0.0, 1.0);
+                gl_MeshVerticesNV[1].gl_Position = vec4( 1.0, -1.0, 0.0, 1.0);
+                gl_MeshVerticesNV[2].gl_Position = vec4(-1.0,  1.0, 0.0, 1.0);
+                gl_MeshVerticesNV[3].gl_Position = vec4( 1.0,  1.0, 0.0, 1.0);
+
+                gl_PrimitiveIndicesNV[0] = 0u;
+                gl_PrimitiveIndicesNV[1] = 1u;
+                gl_PrimitiveIndicesNV[2] = 2u;
+                gl_PrimitiveIndicesNV[3] = 2u;
+                gl_PrimitiveIndicesNV[4] = 1u;
+                gl_PrimitiveIndicesNV[5] = 3u;
+
+                gl_PrimitiveCountNV = 2u;
+            }
+        ",
+        fragment_shader: "
+            #version 450
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        outputs_srgb: false,
+    }).unwrap()
+}
+
+#[test]
+fn draw_mesh_tasks() {
+    let display = support::build_display();
+    if !glium::program::is_mesh_shader_supported(&display) {
+        return;
+    }
+
+    let program = build_mesh_program(&display);
+    assert!(program.has_mesh_shader());
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw_mesh_tasks(1, &program, &glium::uniforms::EmptyUniforms,
+                                         &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn draw_mesh_program_with_vertices() {
+    let display = support::build_display();
+    if !glium::program::is_mesh_shader_supported(&display) {
+        return;
+    }
+
+    let program = build_mesh_program(&display);
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                    &Default::default())
+    {
+        Err(glium::DrawError::UnexpectedMeshShader) => (),
+        e => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn draw_mesh_tasks_without_mesh_shader() {
+    let display = support::build_display();
+    let (_, _, program) = support::build_fullscreen_red_pipeline(&display);
+    assert!(!program.has_mesh_shader());
+
+    let texture = support::build_renderable_texture(&display);
+    match texture.as_surface().draw_mesh_tasks(1, &program, &glium::uniforms::EmptyUniforms,
+                                               &Default::default())
+    {
+        Err(glium::DrawError::MeshShaderRequired) |
+        Err(glium::DrawError::MeshShadersNotSupported) => (),
+        e => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
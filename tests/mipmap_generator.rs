@@ -0,0 +1,102 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::texture::mipmap_generator::{MipmapFilter, MipmapGenerator, MipmapGenerationOptions};
+use glium::texture::{MipmapsOption, RawImage2d, Texture2d, UncompressedFloatFormat};
+use glium::{ColorSpace, CopyToCpuOptions, Rect};
+
+mod support;
+
+/// Builds a 4x4 texture whose 2x2 blocks are filled with 64, 128, 192 and 0.
+fn build_texture(display: &glium::Display) -> Texture2d {
+    let texture = Texture2d::empty_with_format(display, UncompressedFloatFormat::U8U8U8U8,
+                                               MipmapsOption::EmptyMipmaps, 4, 4).unwrap();
+
+    let mut data = Vec::new();
+    for y in 0 .. 4 {
+        for x in 0 .. 4 {
+            let value = [[64u8, 128], [192, 0]][y / 2][x / 2];
+            data.extend_from_slice(&[value, value, value, 255]);
+        }
+    }
+
+    texture.write(Rect { left: 0, bottom: 0, width: 4, height: 4 },
+                  RawImage2d::from_raw_rgba(data, (4, 4)));
+    texture
+}
+
+/// Reads the first texel of a mipmap level.
+fn read_level(display: &glium::Display, texture: &Texture2d, level: u32) -> Vec<u8> {
+    let framebuffer = glium::framebuffer::SimpleFrameBuffer::new(display,
+                                                    texture.mipmap(level).unwrap()).unwrap();
+    framebuffer.copy_region_to_cpu_with_options(&Rect { left: 0, bottom: 0, width: 1, height: 1 },
+                                                &CopyToCpuOptions {
+        color_space: ColorSpace::Linear,
+        top_to_bottom: false,
+        premultiply_alpha: false,
+    }).unwrap().data
+}
+
+#[test]
+fn box_filter() {
+    let display = support::build_display();
+
+    let generator = match MipmapGenerator::new(&display) {
+        Ok(generator) => generator,
+        Err(_) => return,
+    };
+
+    let texture = build_texture(&display);
+    generator.generate(&texture, &Default::default()).unwrap();
+
+    assert_eq!(read_level(&display, &texture, 1), vec![64, 64, 64, 255]);
+    assert_eq!(read_level(&display, &texture, 2), vec![96, 96, 96, 255]);
+    assert_eq!(texture.get_base_level(), 0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn kaiser_filter_keeps_uniform_colors() {
+    let display = support::build_display();
+
+    let generator = match MipmapGenerator::new(&display) {
+        Ok(generator) => generator,
+        Err(_) => return,
+    };
+
+    let texture = Texture2d::empty_with_format(&display, UncompressedFloatFormat::U8U8U8U8,
+                                               MipmapsOption::EmptyMipmaps, 8, 8).unwrap();
+    texture.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+
+    generator.generate(&texture, &MipmapGenerationOptions {
+        filter: MipmapFilter::Kaiser { alpha: 4.0 },
+        gamma_correct: true,
+        .. Default::default()
+    }).unwrap();
+
+    assert_eq!(read_level(&display, &texture, 3), vec![0, 255, 0, 255]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn wrong_texture_type() {
+    let display = support::build_display();
+
+    let generator = match MipmapGenerator::new(&display) {
+        Ok(generator) => generator,
+        Err(_) => return,
+    };
+
+    let texture = glium::texture::Texture2dArray::empty_with_mipmaps(&display,
+                                                    MipmapsOption::EmptyMipmaps, 4, 4, 2).unwrap();
+
+    match generator.generate(&texture, &Default::default()) {
+        Err(glium::texture::mipmap_generator::MipmapGenerationError::WrongTextureType) => (),
+        e => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "osmesa")]
+
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::backend::osmesa::OsMesa;
+
+#[test]
+fn clear_default_framebuffer() {
+    let osmesa = OsMesa::new(64, 32).unwrap();
+    assert_eq!(osmesa.get_framebuffer_dimensions(), (64, 32));
+
+    let mut frame = osmesa.draw();
+    frame.clear_color(1.0, 0.0, 0.0, 1.0);
+    frame.finish().unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = osmesa.read_front_buffer().unwrap();
+    assert_eq!(data.len(), 32);
+    for row in data.iter() {
+        assert_eq!(row.len(), 64);
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    osmesa.assert_no_error(None);
+}
+
+#[test]
+fn draw_triangle() {
+    let osmesa = OsMesa::new(16, 16).unwrap();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position);
+
+    let vertex_buffer = glium::VertexBuffer::new(&osmesa, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let program = program!(&osmesa,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(0.0, 1.0, 0.0, 1.0);
+                }
+            ",
+        },
+    ).unwrap();
+
+    let mut frame = osmesa.draw();
+    frame.clear_color(0.0, 0.0, 0.0, 0.0);
+    frame.draw(&vertex_buffer, &glium::index::NoIndices(glium::index::PrimitiveType::TriangleStrip),
+               &program, &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+    frame.finish().unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = osmesa.read_front_buffer().unwrap();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    osmesa.assert_no_error(None);
+}
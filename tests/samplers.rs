@@ -79,3 +79,56 @@ fn magnify_nearest_filtering() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn seamless_cubemap_sampler() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    assert!(glium::uniforms::SamplerBehavior::default().seamless_cubemap);
+
+    let cubemap = match glium::texture::Cubemap::empty(&display, 4) {
+        Ok(c) => c,
+        Err(_) => return
+    };
+    cubemap.clear(0, [1.0, 0.0, 0.0, 1.0]);
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                uniform samplerCube texture;
+
+                void main() {
+                    gl_FragColor = textureCube(texture, vec3(1.0, 1.0, 1.0));
+                }
+            ",
+        }).unwrap();
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let sampler = glium::uniforms::Sampler::new(&cubemap).seamless_cubemap(false);
+    match output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: sampler },
+                                   &Default::default())
+    {
+        Ok(_) => (),
+        Err(glium::DrawError::SamplersNotSupported) => return,
+        e => e.unwrap()
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
@@ -856,3 +856,602 @@ fn array_layout_offsets() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn attribute_locations() {
+    let display = support::build_display();
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+                attribute vec3 color;
+
+                varying vec3 v_color;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 110
+
+                varying vec3 v_color;
+
+                void main() {
+                    gl_FragColor = vec4(v_color, 1.0);
+                }
+            ",
+        },
+    ).unwrap();
+
+    let locations = program.attribute_locations();
+    assert_eq!(locations.len(), 2);
+
+    let position = locations.get("position").unwrap();
+    assert_eq!(position.ty, glium::vertex::AttributeType::F32F32);
+    assert_eq!(position.size, 1);
+    assert_eq!(position.location, program.get_attribute("position").unwrap().location);
+
+    let color = locations.get("color").unwrap();
+    assert_eq!(color.ty, glium::vertex::AttributeType::F32F32F32);
+    assert!(color.location != position.location);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn shader_draw_parameters() {
+    let display = support::build_display();
+
+    let vertex_shader = match glium::program::enable_shader_draw_parameters(&display, "
+        #version 330
+
+        in vec2 position;
+        flat out int draw_id;
+
+        void main() {
+            draw_id = gl_DrawIDARB;
+            gl_Position = vec4(position, 0.0, 1.0);
+        }
+    ") {
+        Some(s) => s,
+        None => return
+    };
+
+    assert!(glium::program::is_shader_draw_parameters_supported(&display));
+
+    glium::Program::from_source(&display, &vertex_shader, "
+        #version 330
+
+        flat in int draw_id;
+        out vec4 color;
+
+        void main() {
+            color = vec4(float(draw_id), 0.0, 0.0, 1.0);
+        }
+    ", None).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn shader_modules() {
+    let display = support::build_display();
+    let registry = display.get_shader_module_registry();
+
+    registry.register("test_color", "
+        vec4 test_color() {
+            return vec4(1.0, 0.0, 0.0, 1.0);
+        }
+    ");
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            void main() {
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            #import test_color
+
+            void main() {
+                gl_FragColor = test_color();
+            }
+        ",
+        None).unwrap();
+
+    assert!(program.get_shader_module_dependencies().depends_on("test_color"));
+    assert!(!program.is_outdated());
+
+    registry.register("test_color", "
+        vec4 test_color() {
+            return vec4(0.0, 1.0, 0.0, 1.0);
+        }
+    ");
+    assert!(program.is_outdated());
+
+    registry.unregister("test_color");
+    match glium::Program::from_source(&display, "", "#import test_color\n", None) {
+        Err(glium::ProgramCreationError::ShaderModuleError(_)) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_reflect() {
+    let display = support::build_display();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 330
+
+            in vec2 position;
+            in vec3 normal;
+
+            uniform MyBlock {
+                mat4 matrix;
+            };
+
+            out vec3 v_normal;
+
+            void main() {
+                v_normal = normal;
+                gl_Position = matrix * vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            uniform sampler2D albedo;
+            uniform float roughness;
+
+            in vec3 v_normal;
+
+            layout(location = 0) out vec4 color;
+            layout(location = 1) out vec4 normal;
+
+            void main() {
+                color = texture(albedo, vec2(0.5, 0.5)) * roughness;
+                normal = vec4(v_normal, 1.0);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let reflection = program.reflect();
+
+    let attributes = reflection.attributes.iter().map(|&(n, _)| n).collect::<Vec<_>>();
+    assert_eq!(attributes, vec!["normal", "position"]);
+
+    let uniforms = reflection.uniforms.iter().map(|&(n, _)| n).collect::<Vec<_>>();
+    assert_eq!(uniforms, vec!["albedo", "roughness"]);
+
+    // the unit is only assigned during a draw call
+    assert_eq!(reflection.samplers, vec![("albedo", None)]);
+
+    assert_eq!(reflection.uniform_blocks.len(), 1);
+    assert_eq!(reflection.uniform_blocks[0].0, "MyBlock");
+    assert_eq!(reflection.uniform_blocks[0].1.variables[0].offset, 0);
+
+    if let Some(outputs) = reflection.outputs {
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0].0, "color");
+        assert_eq!(outputs[0].1.location, 0);
+        assert_eq!(outputs[0].1.ty, glium::vertex::AttributeType::F32F32F32F32);
+        assert_eq!(outputs[1].0, "normal");
+        assert_eq!(outputs[1].1.location, 1);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_cache() {
+    let display = support::build_display();
+
+    let directory = std::env::temp_dir().join(format!("glium-program-cache-{}", std::process::id()));
+    let cache = glium::program::ProgramCache::new(&directory);
+    cache.clear().unwrap();
+
+    let source = || glium::program::SourceCode {
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        geometry_shader: None,
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+    };
+
+    let _program1 = cache.get_or_create(&display, source()).unwrap();
+
+    let files = || std::fs::read_dir(&directory).map(|d| d.count()).unwrap_or(0);
+    if glium::program::is_binary_supported(&display) {
+        assert_eq!(files(), 1);
+    }
+
+    let _program2 = cache.get_or_create(&display, source()).unwrap();
+    assert!(files() <= 1);
+
+    cache.clear().unwrap();
+    assert_eq!(files(), 0);
+    let _ = std::fs::remove_dir(&directory);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_new_async() {
+    let display = support::build_display();
+
+    let pending = glium::Program::new_async(&display, glium::program::SourceCode {
+        vertex_shader: "
+            #version 110
+
+            uniform mat4 matrix;
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = matrix * vec4(position, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        geometry_shader: None,
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+    });
+
+    if !glium::program::is_parallel_compile_supported(&display) {
+        assert!(pending.is_ready());
+    }
+
+    let program = pending.finish().unwrap();
+    assert!(program.get_uniform("matrix").is_some());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_new_async_compilation_error() {
+    let display = support::build_display();
+
+    let pending = glium::Program::new_async(&display, glium::program::SourceCode {
+        vertex_shader: "invalid glsl",
+        fragment_shader: "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        geometry_shader: None,
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+    });
+
+    match pending.finish() {
+        Err(glium::CompilationError(..)) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_pipeline() {
+    use glium::program::{SeparableProgram, ProgramPipeline, PipelineStages, ShaderType};
+
+    let display = support::build_display();
+    if !glium::program::is_separate_shader_objects_supported(&display) {
+        return;
+    }
+
+    let vertex = SeparableProgram::new(&display, ShaderType::Vertex, "
+        #version 410
+
+        uniform float scale;
+        in vec2 position;
+
+        out gl_PerVertex {
+            vec4 gl_Position;
+        };
+
+        void main() {
+            gl_Position = vec4(position * scale, 0.0, 1.0);
+        }
+    ").unwrap();
+
+    let red = SeparableProgram::new(&display, ShaderType::Fragment, "
+        #version 410
+
+        layout(location = 0) out vec4 color;
+
+        void main() {
+            color = vec4(1.0, 0.0, 0.0, 1.0);
+        }
+    ").unwrap();
+
+    let uniform_color = SeparableProgram::new(&display, ShaderType::Fragment, "
+        #version 410
+
+        uniform vec4 tint;
+        layout(location = 0) out vec4 color;
+
+        void main() {
+            color = tint;
+        }
+    ").unwrap();
+
+    assert!(vertex.get_uniform("scale").is_some());
+    assert!(uniform_color.get_uniform("tint").is_some());
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    let pipeline = ProgramPipeline::new(&display, PipelineStages {
+        vertex: &vertex,
+        tessellation_control: None,
+        tessellation_evaluation: None,
+        geometry: None,
+        fragment: &red,
+    }).unwrap();
+
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &pipeline, &uniform!{ scale: 1.0f32 },
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    let pipeline = ProgramPipeline::new(&display, PipelineStages {
+        vertex: &vertex,
+        tessellation_control: None,
+        tessellation_evaluation: None,
+        geometry: None,
+        fragment: &uniform_color,
+    }).unwrap();
+
+    texture.as_surface().draw(&vb, &ib, &pipeline,
+                              &uniform!{ scale: 1.0f32, tint: [0.0, 1.0, 0.0, 1.0f32] },
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_pipeline_wrong_stage() {
+    use glium::program::{SeparableProgram, ProgramPipeline, PipelineStages, ShaderType};
+    use glium::program::ProgramPipelineCreationError;
+
+    let display = support::build_display();
+    if !glium::program::is_separate_shader_objects_supported(&display) {
+        return;
+    }
+
+    let vertex = SeparableProgram::new(&display, ShaderType::Vertex, "
+        #version 410
+
+        in vec2 position;
+
+        out gl_PerVertex {
+            vec4 gl_Position;
+        };
+
+        void main() {
+            gl_Position = vec4(position, 0.0, 1.0);
+        }
+    ").unwrap();
+
+    match ProgramPipeline::new(&display, PipelineStages {
+        vertex: &vertex,
+        tessellation_control: None,
+        tessellation_evaluation: None,
+        geometry: None,
+        fragment: &vertex,
+    }) {
+        Err(ProgramPipelineCreationError::WrongShaderType { .. }) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn preprocessed_includes() {
+    use glium::program::ShaderPreprocessor;
+
+    let display = support::build_display();
+
+    let mut preprocessor = ShaderPreprocessor::new(|path: &str| match path {
+        "lib/color.glsl" => Some("#pragma once\nvec4 color() { return vec4(1.0, 0.0, 0.0, 1.0); }\n".to_owned()),
+        _ => None,
+    });
+
+    let fragment = preprocessor.preprocess("main.frag", "
+        #version 110
+
+        #include \"lib/color.glsl\"
+        #include <lib/color.glsl>
+
+        void main() {
+            gl_FragColor = color();
+        }
+    ").unwrap();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        fragment.get_source(), None);
+
+    let program = match program {
+        Ok(p) => p,
+        Err(glium::CompilationError(log, _)) => panic!("{}", fragment.map_log(&log)),
+        Err(e) => panic!("{}", e),
+    };
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn hot_reload_keeps_program_on_failure() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use glium::program::{HotReload, HotReloadSources, ReloadEvent, SourceProvider};
+
+    let display = support::build_display();
+
+    let fragment_source = |color: &str| format!("
+        #version 110
+
+        void main() {{
+            gl_FragColor = {};
+        }}
+    ", color);
+
+    let next = Rc::new(RefCell::new(Some(fragment_source("vec4(1.0, 0.0, 0.0, 1.0)"))));
+
+    let mut programs = HotReload::new(&display);
+    let id = programs.add(HotReloadSources {
+        vertex_shader: SourceProvider::callback(|| Some("
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ".to_owned())),
+        fragment_shader: {
+            let next = next.clone();
+            SourceProvider::callback(move || next.borrow_mut().take())
+        },
+        geometry_shader: None,
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+    }).unwrap();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    let wait_update = |programs: &mut HotReload| loop {
+        let events = programs.update();
+        if !events.is_empty() {
+            return events;
+        }
+    };
+
+    let draw = |programs: &HotReload| {
+        texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        texture.as_surface().draw(&vb, &ib, programs.get(id), &glium::uniforms::EmptyUniforms,
+                                  &Default::default()).unwrap();
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        data[0][0]
+    };
+
+    assert_eq!(draw(&programs), (255, 0, 0, 255));
+
+    *next.borrow_mut() = Some(fragment_source("vec4(0.0, 1.0, 0.0, 1.0) +"));
+    match wait_update(&mut programs).pop() {
+        Some(ReloadEvent::Failed(i, _)) => assert_eq!(i, id),
+        e => panic!("{:?}", e),
+    }
+    assert_eq!(draw(&programs), (255, 0, 0, 255));
+
+    *next.borrow_mut() = Some(fragment_source("vec4(0.0, 1.0, 0.0, 1.0)"));
+    match wait_update(&mut programs).pop() {
+        Some(ReloadEvent::Reloaded(i)) => assert_eq!(i, id),
+        e => panic!("{:?}", e),
+    }
+    assert_eq!(draw(&programs), (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_validate_assigns_texture_units() {
+    let display = support::build_display();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D texture;
+            uniform samplerCube cubemap;
+
+            void main() {
+                gl_FragColor = texture2D(texture, vec2(0.5)) + textureCube(cubemap, vec3(1.0));
+            }
+        ",
+        None).unwrap();
+
+    // both samplers would point to unit 0, which is invalid since their types differ
+    assert!(program.get_texture_units().is_empty());
+    program.validate().unwrap();
+
+    let units = program.get_texture_units();
+    assert_eq!(units.len(), 2);
+    assert!(units[0].1 != units[1].1);
+
+    display.assert_no_error(None);
+}
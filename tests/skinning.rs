@@ -0,0 +1,137 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::skinning::{self, BonePalette, SkinningMode, SkinningStorage};
+
+mod support;
+
+fn draw_bone_color(display: &glium::Display, palette: &BonePalette, color_expr: &str)
+                   -> Option<Vec<Vec<(u8, u8, u8, u8)>>>
+{
+    let version = match palette.get_storage() {
+        SkinningStorage::ShaderStorageBuffer => "#version 430",
+        _ => "#version 140",
+    };
+
+    let (vb, ib) = support::build_rectangle_vb_ib(display);
+
+    let program = glium::Program::from_source(display,
+        &format!("
+            {}
+
+            {}
+
+            in vec2 position;
+            out vec4 v_color;
+
+            void main() {{
+                v_color = {};
+                gl_Position = vec4(position, 0.0, 1.0);
+            }}
+        ", version, palette.get_glsl_source(), color_expr),
+        &format!("
+            {}
+
+            in vec4 v_color;
+            out vec4 f_color;
+
+            void main() {{
+                f_color = v_color;
+            }}
+        ", version),
+        None);
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return None
+    };
+
+    let output = support::build_renderable_texture(display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ bones: palette },
+                             &Default::default()).unwrap();
+
+    Some(output.read())
+}
+
+#[test]
+fn matrices() {
+    let display = support::build_display();
+
+    let identity = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0],
+                    [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+    let translation = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0],
+                       [0.0, 0.0, 1.0, 0.0], [1.0, 0.0, 1.0, 1.0]];
+
+    for &storage in &[SkinningStorage::UniformBuffer, SkinningStorage::ShaderStorageBuffer,
+                      SkinningStorage::BufferTexture]
+    {
+        let mut palette = match BonePalette::with_storage(&display, SkinningMode::Matrices,
+                                                          storage, 4)
+        {
+            Ok(p) => p,
+            Err(_) => continue
+        };
+
+        palette.upload_matrices(&[identity, translation]);
+        assert_eq!(palette.len(), 2);
+
+        let data = match draw_bone_color(&display, &palette, "get_bone_matrix(1)[3]") {
+            Some(d) => d,
+            None => continue
+        };
+
+        for row in data.iter() {
+            for pixel in row.iter() {
+                assert_eq!(pixel, &(255, 0, 255, 255));
+            }
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn dual_quaternions() {
+    let display = support::build_display();
+
+    let mut palette = match BonePalette::new(&display, SkinningMode::DualQuaternions, 8) {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let translation = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0],
+                       [0.0, 0.0, 1.0, 0.0], [0.5, 0.0, 0.25, 1.0]];
+    palette.upload_dual_quaternions(&[skinning::matrix_to_dual_quaternion(&translation)]);
+
+    let data = match draw_bone_color(&display, &palette,
+                                     "vec4(transform_by_dual_quaternion(\
+                                        get_bone_dual_quaternion(0), vec3(0.5, 0.0, 0.75)), 1.0)")
+    {
+        Some(d) => d,
+        None => return
+    };
+
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 255, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn too_many_bones() {
+    let display = support::build_display();
+
+    let mut palette = match BonePalette::new(&display, SkinningMode::Matrices, 1) {
+        Ok(p) => p,
+        Err(_) => panic!()
+    };
+
+    let identity = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0],
+                    [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+    palette.upload_matrices(&[identity, identity]);
+}
@@ -0,0 +1,130 @@
+#[macro_use]
+extern crate glium;
+
+use glium::buffer::{Buffer, BufferMode, BufferType};
+use glium::sort::BufferSorter;
+
+mod support;
+
+/// Returns pseudo-random keys, with duplicates.
+fn build_keys(count: usize) -> Vec<u32> {
+    let mut state = 12345u32;
+    (0 .. count).map(|_| {
+        state = state.wrapping_mul(1103515245).wrapping_add(12345);
+        (state >> 8) % 1000
+    }).collect()
+}
+
+#[test]
+fn sort_keys() {
+    let display = support::build_display();
+    if !BufferSorter::is_supported(&display) {
+        return;
+    }
+
+    let sorter = BufferSorter::new(&display).unwrap();
+
+    // sizes below and above the number of elements sorted in shared memory, and a size that
+    // is not a power of two
+    for &count in [1, 2, 7, 512, 1000, 4099].iter() {
+        let mut keys = build_keys(count);
+        let buffer = Buffer::new(&display, &keys[..], BufferType::ShaderStorageBuffer,
+                                 BufferMode::Default).unwrap();
+
+        sorter.sort_keys(&buffer);
+
+        keys.sort();
+        assert_eq!(buffer.read().unwrap(), keys);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sort_keys_and_values() {
+    let display = support::build_display();
+    if !BufferSorter::is_supported(&display) {
+        return;
+    }
+
+    let sorter = BufferSorter::new(&display).unwrap();
+
+    let keys = build_keys(3000);
+    let values = (0 .. keys.len() as u32).collect::<Vec<_>>();
+
+    let keys_buffer = Buffer::new(&display, &keys[..], BufferType::ShaderStorageBuffer,
+                                  BufferMode::Default).unwrap();
+    let values_buffer = Buffer::new(&display, &values[..], BufferType::ShaderStorageBuffer,
+                                    BufferMode::Default).unwrap();
+
+    sorter.sort(&keys_buffer, &values_buffer);
+
+    let sorted_keys = keys_buffer.read().unwrap();
+    let sorted_values = values_buffer.read().unwrap();
+
+    // the values must follow their keys
+    for (key, value) in sorted_keys.iter().zip(sorted_values.iter()) {
+        assert_eq!(keys[*value as usize], *key);
+    }
+
+    for window in sorted_keys.windows(2) {
+        assert!(window[0] <= window[1]);
+    }
+
+    let mut values_set = sorted_values.clone();
+    values_set.sort();
+    assert_eq!(values_set, values);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sort_max_keys() {
+    let display = support::build_display();
+    if !BufferSorter::is_supported(&display) {
+        return;
+    }
+
+    let sorter = BufferSorter::new(&display).unwrap();
+
+    // the keys equal to `u32::MAX` must not be mixed up with the elements past the end
+    let keys = (0 .. 300u32).map(|i| if i % 3 == 0 { i } else { u32::MAX }).collect::<Vec<_>>();
+    let values = (0 .. keys.len() as u32).collect::<Vec<_>>();
+
+    let keys_buffer = Buffer::new(&display, &keys[..], BufferType::ShaderStorageBuffer,
+                                  BufferMode::Default).unwrap();
+    let values_buffer = Buffer::new(&display, &values[..], BufferType::ShaderStorageBuffer,
+                                    BufferMode::Default).unwrap();
+
+    sorter.sort(&keys_buffer, &values_buffer);
+
+    let sorted_keys = keys_buffer.read().unwrap();
+    let mut sorted_values = values_buffer.read().unwrap();
+
+    let mut expected_keys = keys.clone();
+    expected_keys.sort();
+    assert_eq!(sorted_keys, expected_keys);
+
+    sorted_values.sort();
+    assert_eq!(sorted_values, values);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic(expected = "The keys and the values must have the same length")]
+fn sort_length_mismatch() {
+    let display = support::build_display();
+    if !BufferSorter::is_supported(&display) {
+        panic!("The keys and the values must have the same length");
+    }
+
+    let sorter = BufferSorter::new(&display).unwrap();
+
+    let keys = Buffer::new(&display, &[1u32, 2, 3][..], BufferType::ShaderStorageBuffer,
+                           BufferMode::Default).unwrap();
+    let values = Buffer::new(&display, &[1u32, 2][..], BufferType::ShaderStorageBuffer,
+                             BufferMode::Default).unwrap();
+
+    sorter.sort(&keys, &values);
+}
@@ -134,3 +134,201 @@ fn custom_bind_point() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn reflection_variables() {
+    let display = support::build_display();
+
+    let program = glium::program::ComputeShader::from_source(&display, r#"
+            #version 430
+            layout(local_size_x = 1) in;
+
+            struct Light {
+                vec4 position;
+                mat4 transform;
+            };
+
+            layout(std430) buffer MyBlock {
+                float scale;
+                Light lights[];
+            };
+
+            void main() {
+                lights[0].position = vec4(scale);
+                lights[0].transform = mat4(scale);
+            }
+        "#);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let block = program.get_shader_storage_blocks().get("MyBlock").unwrap();
+
+    let scale = block.variables.iter().find(|v| v.name == "scale").unwrap();
+    assert_eq!(scale.ty, glium::uniforms::UniformType::Float);
+    assert_eq!(scale.offset, 0);
+    assert_eq!(scale.top_level_array_size, Some(1));
+
+    let transform = block.variables.iter().find(|v| v.name == "lights[0].transform").unwrap();
+    assert_eq!(transform.ty, glium::uniforms::UniformType::FloatMat4);
+    assert_eq!(transform.offset, 32);
+    assert_eq!(transform.matrix_stride, 16);
+    assert_eq!(transform.top_level_array_size, Some(0));
+    assert_eq!(transform.top_level_array_stride, Some(80));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn bind_as_ssbo_slice() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+
+    let program = glium::program::ComputeShader::from_source(&display, r#"
+            #version 430
+            layout(local_size_x = 1) in;
+
+            buffer MyBlock {
+                uint values[];
+            };
+
+            void main() {
+                values[0] = 5u;
+            }
+        "#);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let alignment = display.get_capabilities().shader_storage_buffer_offset_alignment.unwrap();
+    let offset = alignment / 4;
+
+    let buffer = glium::buffer::Buffer::<[u32]>::empty_array(&display,
+                                                            glium::buffer::BufferType::ShaderStorageBuffer,
+                                                            offset + 4,
+                                                            glium::buffer::BufferMode::Default).unwrap();
+    buffer.write(&vec![0; offset + 4]);
+
+    program.execute(buffer.slice(offset ..).unwrap().bind_as_ssbo("MyBlock"), 1, 1, 1);
+
+    let data = buffer.read().unwrap();
+    assert_eq!(data[0], if offset == 0 { 5 } else { 0 });
+    assert_eq!(data[offset], 5);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn bind_as_ssbo_missing_block() {
+    let display = support::build_display();
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let buffer = glium::buffer::Buffer::new(&display, &[0u32; 4][..],
+                                            glium::buffer::BufferType::ArrayBuffer,
+                                            glium::buffer::BufferMode::Default).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    match texture.as_surface().draw(&vb, &ib, &program,
+                                    &buffer.as_slice().bind_as_ssbo("MyBlock"),
+                                    &Default::default())
+    {
+        Err(glium::DrawError::ShaderStorageBlockNotFound { ref name }) if name == "MyBlock" => (),
+        e => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn std430_block_layout() {
+    use glium::uniforms::Std430;
+
+    implement_std430_block! {
+        #[derive(Copy, Clone)]
+        struct Particle {
+            position: [f32; 3],
+            mass: f32,
+            velocity: [f32; 2],
+            weights: [f32; 6],
+        }
+    }
+
+    implement_std430_block! {
+        #[derive(Copy, Clone)]
+        struct System {
+            transform: [[f32; 2]; 2],
+            particles: [Particle; 5],
+            count: u32,
+        }
+    }
+
+    assert_eq!(<Particle as Std430>::ALIGNMENT, 16);
+    assert_eq!(<Particle as Std430>::SIZE, 48);
+    assert_eq!(<System as Std430>::SIZE, 16 + 5 * 48 + 16);
+}
+
+#[test]
+fn std430_block() {
+    let display = support::build_display();
+
+    let program = glium::program::ComputeShader::from_source(&display, r#"
+            #version 430
+            layout(local_size_x = 1) in;
+
+            layout(std430) buffer MyBlock {
+                vec3 position;
+                float mass;
+                vec2 velocity;
+                float weights[6];
+            };
+
+            void main() {
+                position += vec3(velocity, 0.0);
+                mass = weights[5];
+            }
+        "#);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    implement_std430_block! {
+        #[derive(Copy, Clone)]
+        struct Particle {
+            position: [f32; 3],
+            mass: f32,
+            velocity: [f32; 2],
+            weights: [f32; 6],
+        }
+    }
+
+    let particle = Particle {
+        position: [1.0, 2.0, 3.0],
+        mass: 0.0,
+        velocity: [0.5, -1.0],
+        weights: [0.0, 0.0, 0.0, 0.0, 0.0, 4.0],
+    };
+
+    let buffer = match glium::uniforms::UniformBuffer::new(&display, particle) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    program.execute(buffer.as_slice().bind_as_ssbo("MyBlock"), 1, 1, 1);
+
+    let data = buffer.read().unwrap();
+    assert_eq!(data.position, [1.5, 1.0, 3.0]);
+    assert_eq!(data.mass, 4.0);
+
+    display.assert_no_error(None);
+}
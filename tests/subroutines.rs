@@ -370,3 +370,162 @@ fn mismatched_subroutines() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn subroutine_bindings_after_program_switch() {
+    let display = support::build_display();
+    if !is_subroutine_supported(display.get_context()) {
+        println!("Backend does not support subroutines");
+        return
+    };
+
+    let program = build_program_complex(&display);
+    let other = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+
+            fragment: "
+                #version 140
+
+                out vec4 fragColor;
+
+                void main() {
+                    fragColor = vec4(0.0, 1.0, 0.0, 1.0);
+                }
+            "
+        },
+    ).unwrap();
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2, 2, 1, 3]).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+
+    // `ColorRed` is the first subroutine in its source code, so we pick the other one in
+    // order to catch implementations that reset the selection to the first one
+    let uniforms = uniform!(
+        Color: ("ColorBlue", ShaderStage::Fragment),
+        Modify: ("SwapRB", ShaderStage::Fragment)
+    );
+
+    // switching to another program and back resets the subroutine uniforms
+    for _ in 0..2 {
+        texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        texture.as_surface().draw(&vb, &indices, &program, &uniforms,
+                                  &Default::default()).unwrap();
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        assert_eq!(data[0][0], (255, 0, 0, 255));
+
+        texture.as_surface().draw(&vb, &indices, &other, &glium::uniforms::EmptyUniforms,
+                                  &Default::default()).unwrap();
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn subroutine_bindings_program_pipeline() {
+    use glium::program::{SeparableProgram, ProgramPipeline, PipelineStages, ShaderType};
+
+    let display = support::build_display();
+    if !is_subroutine_supported(display.get_context()) ||
+       !glium::program::is_separate_shader_objects_supported(&display)
+    {
+        println!("Backend does not support subroutines or program pipelines");
+        return
+    };
+
+    let vertex = SeparableProgram::new(&display, ShaderType::Vertex, "
+        #version 410
+
+        in vec2 position;
+
+        out gl_PerVertex {
+            vec4 gl_Position;
+        };
+
+        void main() {
+            gl_Position = vec4(position, 0.0, 1.0);
+        }
+    ").unwrap();
+
+    let fragment = SeparableProgram::new(&display, ShaderType::Fragment, "
+        #version 410
+
+        layout(location = 0) out vec4 fragColor;
+        subroutine vec4 color_t();
+
+        subroutine uniform color_t Color;
+
+        subroutine(color_t)
+        vec4 ColorRed()
+        {
+          return vec4(1, 0, 0, 1);
+        }
+
+        subroutine(color_t)
+        vec4 ColorBlue()
+        {
+          return vec4(0, 0, 1, 1);
+        }
+
+        void main()
+        {
+            fragColor = Color();
+        }
+    ").unwrap();
+
+    let pipeline = ProgramPipeline::new(&display, PipelineStages {
+        vertex: &vertex,
+        tessellation_control: None,
+        tessellation_evaluation: None,
+        geometry: None,
+        fragment: &fragment,
+    }).unwrap();
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2, 2, 1, 3]).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+
+    let uniforms = uniform!(
+        Color: ("ColorBlue", ShaderStage::Fragment),
+    );
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &indices, &pipeline, &uniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 255, 255));
+
+    let uniforms = uniform!(
+        Color: ("ColorRed", ShaderStage::Fragment),
+    );
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &indices, &pipeline, &uniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
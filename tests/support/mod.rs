@@ -49,6 +49,25 @@ pub fn rebuild_display(display: &glium::Display) {
     display.rebuild(wb, cb, &event_loop).unwrap();
 }
 
+/// Builds a display whose objects are shared with an existing display.
+pub fn build_shared_display(display: &glium::Display) -> glium::Display {
+    let version = parse_version();
+    let event_loop = glutin::event_loop::EventLoop::new();
+    let wb = glutin::window::WindowBuilder::new().with_visible(false);
+    let cb = glutin::ContextBuilder::new()
+        .with_gl_debug_flag(true)
+        .with_gl(version);
+    display.new_shared(wb, cb, &event_loop).unwrap()
+}
+
+/// Builds a resource context whose objects are shared with an existing display.
+pub fn build_resource_context(display: &glium::Display)
+                              -> glium::backend::glutin::headless::ResourceContext
+{
+    let event_loop = glutin::event_loop::EventLoop::new();
+    display.create_resource_context(&event_loop).unwrap()
+}
+
 fn parse_version() -> glutin::GlRequest {
     match env::var("GLIUM_GL_VERSION") {
         Ok(version) => {
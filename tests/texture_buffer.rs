@@ -2,6 +2,7 @@
 extern crate glium;
 
 use glium::Surface;
+use glium::CapabilitiesSource;
 use glium::texture::buffer_texture::BufferTexture;
 use glium::texture::buffer_texture::BufferTextureType;
 
@@ -125,3 +126,113 @@ fn wrong_type() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn view_sample() {
+    let display = support::build_display();
+
+    // the offset of the view must be a multiple of the alignment
+    let offset = match display.get_capabilities().texture_buffer_offset_alignment {
+        Some(alignment) => alignment / 4,
+        None => return
+    };
+
+    let mut data = vec![(0, 0, 0, 0); offset];
+    data.push((255, 0, 255, 255));
+    let buf_tex = BufferTexture::new(&display, &data, BufferTextureType::Float);
+    let buf_tex: BufferTexture<(u8, u8, u8, u8)> = match buf_tex {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let view = buf_tex.view(offset .. offset + 1).unwrap();
+    assert_eq!(view.len(), 1);
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 140
+
+            uniform samplerBuffer tex;
+
+            void main() {
+                gl_FragColor = texelFetch(tex, 0);
+            }
+        ",
+        None);
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ tex: &view },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 255, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn image_unit_store() {
+    let display = support::build_display();
+
+    let buf_tex = BufferTexture::empty_dynamic(&display, 1, BufferTextureType::Unsigned);
+    let buf_tex: BufferTexture<u32> = match buf_tex {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 420
+
+            layout(r32ui) writeonly uniform uimageBuffer img;
+
+            void main() {
+                imageStore(img, 0, uvec4(12));
+            }
+        ",
+        None);
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().draw(&vb, &ib, &program,
+                             &uniform!{ img: buf_tex.image_unit(glium::uniforms::ImageUnitAccess::Write) },
+                             &Default::default()).unwrap();
+
+    assert_eq!(buf_tex.read().unwrap(), vec![12]);
+
+    display.assert_no_error(None);
+}
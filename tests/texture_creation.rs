@@ -385,3 +385,341 @@ fn upload_from_pixel_buffer_inverted() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn texture_array_allocator_reuses_layers() {
+    let display = support::build_display();
+
+    let allocator = match glium::texture::TextureArrayAllocator::empty(&display, 16, 16, 2) {
+        Ok(a) => a,
+        Err(_) => return
+    };
+
+    assert_eq!(allocator.get_capacity(), 2);
+
+    let first = allocator.allocate().unwrap();
+    let second = allocator.allocate().unwrap();
+    assert!(first.get_layer() != second.get_layer());
+    assert!(allocator.allocate().is_none());
+    assert_eq!(allocator.get_free_layers(), 0);
+
+    let freed = first.get_layer();
+    drop(first);
+    assert_eq!(allocator.get_free_layers(), 1);
+
+    let third = allocator.allocate().unwrap();
+    assert_eq!(third.get_layer(), freed);
+
+    third.write(glium::Rect { left: 0, bottom: 0, width: 1, height: 1 },
+                vec![vec![(255u8, 0u8, 0u8, 255u8)]]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn color_space_helpers() {
+    let display = support::build_display();
+
+    let data = [255u8, 128, 0, 255, 0, 128, 255, 255, 0, 0, 0, 255, 255, 255, 255, 255];
+
+    let linear = glium::texture::Texture2d::from_linear_bytes(&display, &data, (2, 2)).unwrap();
+    assert!(!linear.is_srgb());
+
+    let srgb = match glium::texture::SrgbTexture2d::from_srgb_bytes(&display, &data, (2, 2)) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+    assert!(srgb.is_srgb());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compressed_texture_2d_read_compressed_data_level() {
+    let display = support::build_display();
+
+    // a single 4x4 DXT1 block
+    let data = [0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+    let texture = match glium::texture::CompressedTexture2d::with_compressed_data(&display, &data,
+                       4, 4, glium::texture::CompressedFormat::S3tcDxt1NoAlpha,
+                       glium::texture::CompressedMipmapsOption::NoMipmap)
+    {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+
+    match texture.read_compressed_data_level(0) {
+        Some((format, read)) => {
+            assert_eq!(format, glium::texture::CompressedFormat::S3tcDxt1NoAlpha);
+            assert_eq!(&read[..], &data[..]);
+        },
+        None => return,
+    };
+
+    assert!(texture.read_compressed_data_level(1).is_none());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compressed_texture_2d_etc2_and_astc() {
+    use glium::texture::CompressedFormat;
+
+    let display = support::build_display();
+
+    // a single 4x4 ETC2 block and two 6x6 ASTC blocks covering an 8x4 image
+    let etc2 = [0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0x00, 0x00];
+    let astc = [
+        0xfc, 0xfd, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff,
+        0xfc, 0xfd, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0xff, 0xff, 0x00, 0x00, 0xff, 0xff,
+    ];
+
+    for &(format, width, height, data) in &[(CompressedFormat::Etc2Rgb8, 4, 4, &etc2[..]),
+                                            (CompressedFormat::Astc6x6, 8, 4, &astc[..])]
+    {
+        if !format.is_supported(&display) {
+            continue;
+        }
+
+        let texture = glium::texture::CompressedTexture2d::with_compressed_data(&display, data,
+                            width, height, format, glium::texture::CompressedMipmapsOption::NoMipmap)
+                            .unwrap();
+
+        if let Some((read_format, read)) = texture.read_compressed_data_level(0) {
+            assert_eq!(read_format, format);
+            assert_eq!(&read[..], data);
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compressed_texture_2d_bptc() {
+    use glium::texture::{CompressedFormat, CompressedSrgbFormat};
+
+    let display = support::build_display();
+
+    // a single 4x4 block
+    let data = [0x40, 0xff, 0xff, 0xff, 0xff, 0x0f, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+    for &format in &[CompressedFormat::BptcUnorm4, CompressedFormat::BptcSignedFloat3,
+                     CompressedFormat::BptcUnsignedFloat3]
+    {
+        if !format.is_supported(&display) {
+            continue;
+        }
+
+        let texture = glium::texture::CompressedTexture2d::with_compressed_data(&display, &data,
+                            4, 4, format, glium::texture::CompressedMipmapsOption::NoMipmap)
+                            .unwrap();
+
+        if let Some((read_format, read)) = texture.read_compressed_data_level(0) {
+            assert_eq!(read_format, format);
+            assert_eq!(&read[..], &data[..]);
+        }
+    }
+
+    if CompressedSrgbFormat::Bptc.is_supported(&display) {
+        let texture = glium::texture::CompressedSrgbTexture2d::with_compressed_data(&display,
+                            &data, 4, 4, CompressedSrgbFormat::Bptc,
+                            glium::texture::CompressedMipmapsOption::NoMipmap).unwrap();
+
+        if let Some((read_format, read)) = texture.read_compressed_data_level(0) {
+            assert_eq!(read_format, CompressedSrgbFormat::Bptc);
+            assert_eq!(&read[..], &data[..]);
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn mipmapped_image_validation() {
+    use glium::texture::{MipmappedImage, MipmappedImageError, RawImage2d};
+
+    let level = |w: u32, h: u32| RawImage2d::from_raw_rgba(vec![0u8; (4 * w * h) as usize], (w, h));
+
+    assert!(MipmappedImage::new(vec![level(4, 2), level(2, 1), level(1, 1)]).is_ok());
+
+    match MipmappedImage::<RawImage2d<u8>>::new(vec![]) {
+        Err(MipmappedImageError::NoLevel) => (),
+        _ => panic!()
+    }
+
+    match MipmappedImage::new(vec![level(4, 4), level(3, 2)]) {
+        Err(MipmappedImageError::WrongDimensions { level: 1, expected: (2, Some(2), None),
+                                                  obtained: (3, Some(2), None) }) => (),
+        _ => panic!()
+    }
+
+    match MipmappedImage::new(vec![level(2, 2), level(1, 1), level(1, 1)]) {
+        Err(MipmappedImageError::TooManyLevels { maximum: 2, obtained: 3 }) => (),
+        _ => panic!()
+    }
+
+    let rgb = RawImage2d::from_raw_rgb(vec![0u8; 3], (1, 1));
+    match MipmappedImage::new(vec![level(2, 2), rgb]) {
+        Err(MipmappedImageError::FormatMismatch { level: 1 }) => (),
+        _ => panic!()
+    }
+
+    let short = RawImage2d::from_raw_rgba(vec![0u8; 8], (2, 2));
+    match MipmappedImage::new(vec![short]) {
+        Err(MipmappedImageError::WrongDataSize { level: 0, required: 16, obtained: 8 }) => (),
+        _ => panic!()
+    }
+}
+
+#[test]
+fn texture_2d_with_mipmapped_image() {
+    let display = support::build_display();
+
+    let level = |w: u32, h: u32| {
+        glium::texture::RawImage2d::from_raw_rgba(vec![255u8; (4 * w * h) as usize], (w, h))
+    };
+
+    let image = glium::texture::MipmappedImage::new(vec![level(8, 4), level(4, 2), level(2, 1)])
+                                                    .unwrap();
+    let texture = glium::texture::Texture2d::with_mipmapped_image(&display, image).unwrap();
+
+    assert_eq!(texture.get_mipmap_levels(), 3);
+    assert_eq!(texture.mipmap(2).unwrap().get_width(), 2);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_3d_with_mipmapped_image() {
+    let display = support::build_display();
+
+    let level = |w: u32, h: u32, d: u32| glium::texture::RawImage3d {
+        data: std::borrow::Cow::Owned(vec![255u8; (4 * w * h * d) as usize]),
+        width: w,
+        height: h,
+        depth: d,
+        format: glium::texture::ClientFormat::U8U8U8U8,
+    };
+
+    let image = glium::texture::MipmappedImage::new(vec![level(4, 4, 2), level(2, 2, 1)])
+                                                    .unwrap();
+    let texture = match glium::texture::Texture3d::with_mipmapped_image(&display, image) {
+        Err(_) => return,
+        Ok(t) => t
+    };
+
+    assert_eq!(texture.get_mipmap_levels(), 2);
+    assert_eq!(texture.mipmap(1).unwrap().get_depth(), Some(1));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_array_with_mipmapped_image() {
+    let display = support::build_display();
+
+    let level = |w: u32, h: u32| {
+        glium::texture::RawImage2d::from_raw_rgba(vec![255u8; (4 * w * h) as usize], (w, h))
+    };
+    let layer = || glium::texture::MipmappedImage::new(vec![level(4, 2), level(2, 1)]).unwrap();
+
+    let texture = match glium::texture::Texture2dArray::with_mipmapped_image(&display,
+                                                                              vec![layer(), layer()])
+    {
+        Err(_) => return,
+        Ok(t) => t
+    };
+
+    assert_eq!(texture.get_mipmap_levels(), 2);
+    assert_eq!(texture.get_array_size(), Some(2));
+
+    // the layers must have the same levels
+    let short = glium::texture::MipmappedImage::new(vec![level(4, 2)]).unwrap();
+    match glium::texture::Texture2dArray::with_mipmapped_image(&display, vec![layer(), short]) {
+        Err(glium::texture::TextureCreationError::DimensionsNotSupported) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sparse_texture_2d_commitment() {
+    use glium::texture::sparse::{PageCommitmentError, SparseTexture};
+    use glium::texture::{MipmapsOption, Texture2d, UncompressedFloatFormat};
+
+    let display = support::build_display();
+    let format = UncompressedFloatFormat::U8U8U8U8;
+
+    let page_size = match SparseTexture::<Texture2d>::get_page_sizes(&display, format).first() {
+        Some(&p) => p,
+        None => return
+    };
+
+    let texture = SparseTexture::<Texture2d>::empty(&display, format, MipmapsOption::NoMipmap,
+                                                    page_size, page_size.width * 4,
+                                                    page_size.height * 4).unwrap();
+
+    texture.commit(0, (page_size.width, 0, 0), (page_size.width, page_size.height, 1)).unwrap();
+    texture.decommit(0, (page_size.width, 0, 0), (page_size.width, page_size.height, 1)).unwrap();
+
+    assert_eq!(texture.commit(0, (1, 0, 0), (page_size.width, page_size.height, 1)),
+               Err(PageCommitmentError::NotAligned));
+    assert_eq!(texture.commit(0, (0, 0, 0), (page_size.width * 5, page_size.height, 1)),
+               Err(PageCommitmentError::OutOfRange));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn srgb_texture_linear_view() {
+    let display = support::build_display();
+    if !glium::texture::view::is_texture_view_supported(&display) {
+        return;
+    }
+
+    let data = vec![
+        vec![(10u8, 20u8, 30u8, 255u8), (40, 50, 60, 255)],
+        vec![(70, 80, 90, 255), (100, 110, 120, 255)],
+    ];
+    let texture = glium::texture::SrgbTexture2d::new(&display, data.clone()).unwrap();
+
+    let view = texture.linear_view(0 .. 1).unwrap();
+    assert!(!view.is_srgb());
+    assert_eq!(view.get_width(), 2);
+
+    let read: Vec<Vec<(u8, u8, u8, u8)>> = view.read();
+    assert_eq!(read, data);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_view_validation() {
+    use glium::texture::view::TextureViewError;
+    use glium::texture::{MipmapsOption, Texture2dArray, UncompressedFloatFormat};
+
+    let display = support::build_display();
+    if !glium::texture::view::is_texture_view_supported(&display) {
+        return;
+    }
+
+    let texture = Texture2dArray::empty_with_format(&display, UncompressedFloatFormat::U8U8U8U8,
+                                                    MipmapsOption::EmptyMipmaps, 16, 16, 4)
+                                                    .unwrap();
+
+    let layer = texture.layer_view(UncompressedFloatFormat::F32, 1 .. 3, 2).unwrap();
+    assert_eq!(layer.get_width(), 8);
+    assert_eq!(layer.get_mipmap_levels(), 2);
+
+    assert_eq!(texture.view(UncompressedFloatFormat::U8U8U8U8, 0 .. 6, 0 .. 4).err(),
+               Some(TextureViewError::LevelsOutOfRange));
+    assert_eq!(texture.view(UncompressedFloatFormat::U8U8U8U8, 0 .. 1, 3 .. 5).err(),
+               Some(TextureViewError::LayersOutOfRange));
+    assert_eq!(texture.view(UncompressedFloatFormat::F32F32, 0 .. 1, 0 .. 4).err(),
+               Some(TextureViewError::IncompatibleFormat));
+
+    display.assert_no_error(None);
+}
@@ -40,6 +40,33 @@ fn texture_2d_read_pixelbuffer() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn texture_2d_read_async() {
+    let display = support::build_display();
+
+    // we use only powers of two, in order to avoid float rounding errors
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 1u8, 2u8), (4u8, 8u8, 16u8)],
+        vec![(32u8, 64u8, 128u8), (32u8, 16u8, 4u8)],
+    ]).unwrap();
+
+    let pending = texture.read_async();
+    while !pending.is_ready() {}
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = match pending.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(read_back[0][0], (0, 1, 2, 255));
+    assert_eq!(read_back[0][1], (4, 8, 16, 255));
+    assert_eq!(read_back[1][0], (32, 64, 128, 255));
+    assert_eq!(read_back[1][1], (32, 16, 4, 255));
+
+    display.assert_no_error(None);
+}
+
 macro_rules! read_texture_test {
     ($test_name:ident, $tex_ty:ident, $data_ty:ty, $data:expr) => (
         #[test]
@@ -130,3 +130,305 @@ fn bindless_texture() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn bindless_texture_uniform() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(255, 0, 0, 255), (255, 0, 0, 255)],
+        vec![(255, 0, 0, 255), (255, 0, 0, 255u8)],
+    ]).unwrap();
+
+    let texture = match texture.resident() {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 100
+
+            attribute lowp vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 400
+            #extension GL_ARB_bindless_texture : require
+
+            layout(bindless_sampler) uniform sampler2D tex;
+
+            out vec4 f_color;
+
+            void main() {
+                f_color = texture(tex, vec2(0.0, 0.0));
+            }
+        ",
+        None).unwrap();
+
+    let handle = glium::texture::TextureHandle::new(&texture, &Default::default());
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ tex: handle },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+fn build_sampling_program(display: &glium::Display) -> glium::Program {
+    glium::Program::from_source(display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D texture;
+
+            void main() {
+                gl_FragColor = texture2D(texture, vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap()
+}
+
+#[test]
+fn sample_attached_texture() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+    let program = build_sampling_program(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    match texture.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: &texture },
+                                    &Default::default())
+    {
+        Err(glium::DrawError::TextureFeedbackLoop { ref name }) if name == "texture" => (),
+        e => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sample_other_level_of_attached_texture() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+    let program = build_sampling_program(&display);
+
+    let texture = glium::texture::Texture2d::empty_with_mipmaps(&display,
+                                                  glium::texture::MipmapsOption::EmptyMipmaps,
+                                                  64, 64).unwrap();
+    texture.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+
+    // without mipmapping, only the first level is sampled
+    let mut level = glium::framebuffer::SimpleFrameBuffer::new(&display,
+                                                    texture.mipmap(1).unwrap()).unwrap();
+    let sampler = texture.sampled()
+                         .minify_filter(glium::uniforms::MinifySamplerFilter::Linear);
+    level.draw(&vb, &ib, &program, &uniform!{ texture: sampler },
+               &Default::default()).unwrap();
+
+    let sampler = texture.sampled()
+                         .minify_filter(glium::uniforms::MinifySamplerFilter::LinearMipmapLinear);
+    match level.draw(&vb, &ib, &program, &uniform!{ texture: sampler }, &Default::default())
+    {
+        Err(glium::DrawError::TextureFeedbackLoop { .. }) => (),
+        e => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sample_attached_texture_with_base_level() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+    let program = build_sampling_program(&display);
+
+    let texture = glium::texture::Texture2d::empty_with_mipmaps(&display,
+                                                  glium::texture::MipmapsOption::EmptyMipmaps,
+                                                  64, 64).unwrap();
+    texture.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+    if texture.set_base_level(1).is_err() {
+        return;
+    }
+
+    let sampler = texture.sampled()
+                         .minify_filter(glium::uniforms::MinifySamplerFilter::Linear);
+
+    // the first level is below the base level, so it isn't sampled
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: sampler },
+                              &Default::default()).unwrap();
+
+    let mut level = glium::framebuffer::SimpleFrameBuffer::new(&display,
+                                                    texture.mipmap(1).unwrap()).unwrap();
+    match level.draw(&vb, &ib, &program, &uniform!{ texture: sampler }, &Default::default())
+    {
+        Err(glium::DrawError::TextureFeedbackLoop { .. }) => (),
+        e => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sample_attached_texture_with_barrier() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+    let program = build_sampling_program(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+
+    let params = glium::DrawParameters {
+        texture_barrier: true,
+        .. Default::default()
+    };
+
+    match texture.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: &texture }, &params) {
+        Ok(()) => (),
+        Err(glium::DrawError::TextureBarrierNotSupported) => return,
+        Err(e) => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_swizzle() {
+    use glium::texture::Swizzle;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0, 255, 0, 255), (0, 255, 0, 255)],
+        vec![(0, 255, 0, 255), (0, 255, 0, 255u8)],
+    ]).unwrap();
+
+    if texture.set_swizzle([Swizzle::Green, Swizzle::Zero, Swizzle::Red, Swizzle::One]).is_err() {
+        return;
+    }
+    assert_eq!(texture.get_swizzle(), [Swizzle::Green, Swizzle::Zero, Swizzle::Red, Swizzle::One]);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D texture;
+
+            void main() {
+                gl_FragColor = texture2D(texture, vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: &texture },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn depth_stencil_texture_stencil_view() {
+    use glium::texture::DepthStencilTextureMode;
+    use glium::uniforms::{MagnifySamplerFilter, MinifySamplerFilter};
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    if !glium::texture::is_stencil_texturing_supported(&display) {
+        return;
+    }
+
+    let color = support::build_renderable_texture(&display);
+    let (width, height) = (color.get_width(), color.get_height().unwrap());
+    let depth_stencil = glium::texture::DepthStencilTexture2d::empty(&display, width, height)
+                                                                     .unwrap();
+    glium::framebuffer::SimpleFrameBuffer::with_depth_stencil_buffer(&display, &color,
+                                                                     &depth_stencil).unwrap()
+        .clear_stencil(7);
+
+    let view = match depth_stencil.stencil_view(0 .. 1) {
+        Ok(view) => view,
+        Err(_) => return,
+    };
+    assert_eq!(view.get_depth_stencil_mode(), DepthStencilTextureMode::Stencil);
+    assert_eq!(depth_stencil.get_depth_stencil_mode(), DepthStencilTextureMode::Depth);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 130
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 130
+
+            uniform usampler2D stencil;
+
+            void main() {
+                uint value = texelFetch(stencil, ivec2(0, 0), 0).r;
+                gl_FragColor = value == 7u ? vec4(1.0, 0.0, 0.0, 1.0) : vec4(0.0, 1.0, 0.0, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    let uniforms = uniform!{
+        stencil: view.sampled().minify_filter(MinifySamplerFilter::Nearest)
+                               .magnify_filter(MagnifySamplerFilter::Nearest),
+    };
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
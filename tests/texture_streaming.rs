@@ -0,0 +1,65 @@
+#[macro_use]
+extern crate glium;
+
+use glium::texture::RawImage2d;
+use glium::texture::streaming::TextureStreamer;
+
+mod support;
+
+/// Returns an image filled with the value of the level.
+fn level_image(level: u32, dimension: u32) -> RawImage2d<'static, u8> {
+    let dimension = (dimension >> level).max(1);
+    let data = vec![level as u8; dimension as usize * dimension as usize * 4];
+    RawImage2d::from_raw_rgba(data, (dimension, dimension))
+}
+
+#[test]
+fn levels_are_streamed_progressively() {
+    let display = support::build_display();
+
+    let mut streamer = TextureStreamer::new(&display, 1024 * 1024);
+    streamer.insert(0, 4, 4, |level| level_image(level, 4));
+
+    assert!(streamer.get(&0).is_none());
+    streamer.update(8).unwrap();
+    assert!(streamer.get(&0).is_none());
+
+    for &expected in &[2, 1, 0, 0] {
+        streamer.request(&0, 0);
+        streamer.update(8).unwrap();
+        assert_eq!(streamer.get_resident_level(&0), Some(expected));
+        // the levels that have not been uploaded yet are excluded
+        assert_eq!(streamer.get(&0).unwrap().get_base_level(), expected);
+    }
+
+    let texture = streamer.get(&0).unwrap();
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[0][0], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn least_recently_used_is_evicted() {
+    let display = support::build_display();
+
+    // room for a single 4x4 texture with its mipmaps
+    let mut streamer = TextureStreamer::new(&display, (16 + 4 + 1) * 4);
+    streamer.insert("a", 4, 4, |level| level_image(level, 4));
+    streamer.insert("b", 4, 4, |level| level_image(level, 4));
+
+    streamer.request(&"a", 0);
+    streamer.update(1).unwrap();
+    assert!(streamer.get(&"a").is_some());
+
+    streamer.request(&"b", 0);
+    streamer.update(1).unwrap();
+    assert!(streamer.get(&"a").is_none());
+    assert!(streamer.get(&"b").is_some());
+
+    let info = streamer.get_memory_info();
+    assert_eq!(info.used, (16 + 4 + 1) * 4);
+    assert_eq!(info.resident_textures, 1);
+
+    display.assert_no_error(None);
+}
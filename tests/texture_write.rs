@@ -24,3 +24,142 @@ fn texture_2d_write() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn texture_2d_write_async() {
+    let display = support::build_display();
+
+    let mut uploader = match glium::texture::async_upload::AsyncUploader::new(&display, 2) {
+        Ok(uploader) => uploader,
+        Err(_) => return,
+    };
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 1u8, 2u8), (4u8, 8u8, 16u8)],
+        vec![(32u8, 64u8, 128u8), (32u8, 16u8, 4u8)],
+    ]).unwrap();
+
+    let ticket = texture.write_async(&mut uploader,
+                                     glium::Rect { bottom: 1, left: 1, width: 1, height: 1 },
+                                     vec![vec![(128u8, 64u8, 2u8)]]).unwrap();
+    ticket.wait();
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[0][0], (0, 1, 2, 255));
+    assert_eq!(read_back[0][1], (4, 8, 16, 255));
+    assert_eq!(read_back[1][0], (32, 64, 128, 255));
+    assert_eq!(read_back[1][1], (128, 64, 2, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_write_async_reuses_buffers() {
+    let display = support::build_display();
+
+    let mut uploader = match glium::texture::async_upload::AsyncUploader::new(&display, 1) {
+        Ok(uploader) => uploader,
+        Err(_) => return,
+    };
+
+    let texture = glium::texture::Texture2d::empty(&display, 4, 1).unwrap();
+
+    let mut tickets = Vec::new();
+    for x in 0 .. 4u8 {
+        let ticket = texture.write_async(&mut uploader,
+                                         glium::Rect { bottom: 0, left: x as u32, width: 1, height: 1 },
+                                         vec![vec![(x, x, x, x)]]).unwrap();
+        tickets.push(ticket);
+        assert!(uploader.get_uploads_in_flight() <= 1);
+    }
+
+    for ticket in tickets {
+        ticket.wait();
+    }
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[0], vec![(0, 0, 0, 0), (1, 1, 1, 1), (2, 2, 2, 2), (3, 3, 3, 3)]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_clear() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::empty(&display, 2, 2).unwrap();
+    texture.clear(0, [1.0, 0.0, 0.0, 1.0]);
+    texture.clear_rect(0, glium::Rect { bottom: 1, left: 0, width: 2, height: 1 }, 0 .. 1,
+                       [0.0, 0.0, 1.0, 1.0]);
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[0][0], (255, 0, 0, 255));
+    assert_eq!(read_back[0][1], (255, 0, 0, 255));
+    assert_eq!(read_back[1][0], (0, 0, 255, 255));
+    assert_eq!(read_back[1][1], (0, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_3d_clear_layers() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture3d::empty(&display, 4, 4, 4).unwrap();
+    texture.clear(0, [0.0, 0.0, 0.0, 0.0]);
+    texture.clear_rect(0, glium::Rect { bottom: 0, left: 0, width: 4, height: 4 }, 1 .. 3,
+                       [0.0, 1.0, 0.0, 1.0]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn texture_clear_wrong_kind() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::empty(&display, 2, 2).unwrap();
+    texture.clear(0, [1u32, 0, 0, 1]);
+}
+
+#[test]
+fn texture_2d_copy_sub_image() {
+    let display = support::build_display();
+
+    let source = glium::texture::Texture2d::new(&display, vec![
+        vec![(255u8, 0u8, 0u8, 255u8), (0u8, 255u8, 0u8, 255u8)],
+        vec![(0u8, 0u8, 255u8, 255u8), (255u8, 255u8, 255u8, 255u8)],
+    ]).unwrap();
+
+    let target = glium::texture::Texture2d::empty(&display, 2, 2).unwrap();
+    target.clear(0, [0.0, 0.0, 0.0, 0.0]);
+
+    source.main_level()
+          .copy_sub_image(glium::Rect { left: 1, bottom: 0, width: 1, height: 2 }, 0 .. 1,
+                          &target.main_level(), 0, 0, 0)
+          .unwrap();
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    assert_eq!(read_back[0][0], (0, 255, 0, 255));
+    assert_eq!(read_back[1][0], (255, 255, 255, 255));
+    assert_eq!(read_back[0][1], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_copy_out_of_range() {
+    let display = support::build_display();
+
+    let source = glium::texture::Texture2d::empty(&display, 4, 4).unwrap();
+    let target = glium::texture::Texture2d::empty(&display, 2, 2).unwrap();
+
+    assert_eq!(source.main_level().copy_to(&target.main_level()),
+               Err(glium::texture::CopyImageError::TargetOutOfRange));
+    assert_eq!(target.main_level()
+                     .copy_sub_image(glium::Rect { left: 0, bottom: 0, width: 2, height: 2 },
+                                     0 .. 2, &source.main_level(), 0, 0, 0),
+               Err(glium::texture::CopyImageError::SourceOutOfRange));
+
+    display.assert_no_error(None);
+}
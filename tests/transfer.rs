@@ -0,0 +1,216 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::transfer::TransferTicket;
+
+use std::sync::mpsc;
+use std::thread;
+
+mod support;
+
+#[test]
+fn texture_from_other_thread() {
+    let display = support::build_display();
+    let resource_context = support::build_resource_context(&display);
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let resources = resource_context.make_current().unwrap();
+
+        let data = [255u8, 0, 0, 255].iter().cycle().take(4 * 4 * 4).cloned().collect();
+        let image = glium::texture::RawImage2d::from_raw_rgba(data, (4, 4));
+        let texture = glium::Texture2d::new(&resources, image).unwrap();
+        sender.send(TransferTicket::new(&resources, texture)).unwrap();
+    }).join().unwrap();
+
+    let texture = receiver.recv().unwrap().wait(&display);
+    assert_eq!(texture.get_width(), 4);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn buffers_from_other_thread() {
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position);
+
+    let display = support::build_display();
+    let resource_context = support::build_resource_context(&display);
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let resources = resource_context.make_current().unwrap();
+
+        let vb = glium::VertexBuffer::new(&resources, &[
+            Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+            Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+        ]).unwrap();
+        let ib = glium::IndexBuffer::new(&resources, glium::index::PrimitiveType::TriangleStrip,
+                                         &[0u16, 1, 2, 3]).unwrap();
+
+        sender.send(TransferTicket::new(&resources, (vb, ib))).unwrap();
+    }).join().unwrap();
+
+    let ticket = receiver.recv().unwrap();
+    while !ticket.is_ready(&display) {}
+    let (vb, ib) = ticket.wait(&display);
+    assert_eq!(vb.read().unwrap()[3].position, [1.0, -1.0]);
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        },
+    ).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_from_other_thread() {
+    let display = support::build_display();
+    let resource_context = support::build_resource_context(&display);
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let resources = resource_context.make_current().unwrap();
+
+        let program = glium::Program::from_source(&resources,
+            "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(0.0, 1.0, 0.0, 1.0);
+                }
+            ",
+            None).unwrap();
+
+        sender.send(TransferTicket::new(&resources, program)).unwrap();
+    }).join().unwrap();
+
+    let program = receiver.recv().unwrap().wait(&display);
+    assert!(program.attributes().any(|(name, _)| name == "position"));
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_built_by_compiler_thread() {
+    let display = support::build_display();
+    let resource_context = support::build_resource_context(&display);
+
+    let thread = glium::program::CompilerThread::new(move || {
+        resource_context.make_current().unwrap()
+    });
+    display.set_compiler_thread(Some(thread));
+
+    let pending = glium::Program::new_async(&display,
+        glium::program::ProgramCreationInput::SourceCode {
+            vertex_shader: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment_shader: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(0.0, 0.0, 1.0, 1.0);
+                }
+            ",
+            geometry_shader: None,
+            tessellation_control_shader: None,
+            tessellation_evaluation_shader: None,
+            transform_feedback_varyings: None,
+            outputs_srgb: false,
+            uses_point_size: false,
+        });
+
+    let program = pending.finish().unwrap();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 255, 255));
+
+    display.set_compiler_thread(None);
+    display.assert_no_error(None);
+}
@@ -337,3 +337,365 @@ fn empty_uniform_buffer() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn std140_block_layout() {
+    use glium::uniforms::Std140;
+
+    implement_std140_block! {
+        #[derive(Copy, Clone)]
+        struct Light {
+            position: [f32; 3],
+            intensity: f32,
+            color: [f32; 3],
+            range: f32,
+        }
+    }
+
+    implement_std140_block! {
+        #[derive(Copy, Clone)]
+        struct Scene {
+            lights: [Light; 5],
+            view: [[f32; 4]; 4],
+            count: u32,
+        }
+    }
+
+    assert_eq!(<Light as Std140>::ALIGNMENT, 16);
+    assert_eq!(<Light as Std140>::SIZE, 32);
+    assert_eq!(<Scene as Std140>::SIZE, 5 * 32 + 64 + 16);
+}
+
+#[test]
+fn std140_block() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+            uniform layout(std140);
+
+            uniform MyBlock {
+                mat4 matrix;
+                vec3 color;
+                float intensity;
+                vec2 offset;
+            };
+
+            void main() {
+                gl_FragColor = matrix * vec4(color * intensity + vec3(offset, 0.0), 1.0);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    implement_std140_block! {
+        #[derive(Copy, Clone)]
+        struct Data {
+            matrix: [[f32; 4]; 4],
+            color: [f32; 3],
+            intensity: f32,
+            offset: [f32; 2],
+        }
+    }
+
+    let data = Data {
+        matrix: [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ],
+        color: [2.0, 0.0, 0.0],
+        intensity: 0.5,
+        offset: [0.0, 1.0],
+    };
+
+    let buffer = match glium::uniforms::UniformBuffer::new(&display, data) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    let uniforms = uniform!{
+        MyBlock: &buffer
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn uniform_buffer_slice() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+            uniform layout(std140);
+
+            uniform Object {
+                vec4 color;
+            };
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    #[derive(Copy, Clone)]
+    struct Object {
+        color: [f32; 4],
+        _padding: [f32; 60],
+    }
+
+    implement_uniform_block!(Object, color);
+
+    // each element is 256 bytes, which is the maximum alignment allowed by the specs
+    let alignment = display.get_capabilities().uniform_buffer_offset_alignment.unwrap();
+    assert!(alignment <= 256);
+
+    let buffer = glium::uniforms::UniformBuffer::new_array(&display, &[
+        Object { color: [1.0, 0.0, 0.0, 1.0], _padding: [0.0; 60] },
+        Object { color: [0.0, 0.0, 1.0, 1.0], _padding: [0.0; 60] },
+    ]).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{
+        Object: buffer.slice(1 .. 2).unwrap(),
+    }, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 0, 255, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn uniform_buffer_slice_misaligned() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+            uniform layout(std140);
+
+            uniform Object {
+                vec4 color;
+            };
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    #[derive(Copy, Clone)]
+    struct Object {
+        color: [f32; 4],
+    }
+
+    implement_uniform_block!(Object, color);
+
+    // an offset of 16 bytes is valid if the implementation doesn't require more
+    let alignment = display.get_capabilities().uniform_buffer_offset_alignment.unwrap();
+    if alignment <= 16 {
+        return;
+    }
+
+    let buffer = glium::uniforms::UniformBuffer::new_array(&display, &[
+        Object { color: [1.0, 0.0, 0.0, 1.0] },
+        Object { color: [0.0, 0.0, 1.0, 1.0] },
+    ]).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    match texture.as_surface().draw(&vb, &ib, &program, &uniform!{
+        Object: buffer.slice(1 ..).unwrap(),
+    }, &Default::default()) {
+        Err(glium::DrawError::BufferOffsetMisaligned { ref name, alignment: a })
+            if name == "Object" && a == alignment => (),
+        e => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn raw_block_and_blob() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+            uniform layout(std140);
+
+            uniform MyBlock {
+                vec3 color;
+                float alpha;
+            };
+
+            void main() {
+                gl_FragColor = vec4(color, alpha);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let blob: Vec<u8> = [1.0f32, 1.0, 0.0, 1.0].iter().flat_map(|v| v.to_ne_bytes().to_vec())
+                                                     .collect();
+
+    // checking that the blob can be read with the reflected layout
+    let variables = &program.get_uniform_blocks()["MyBlock"].variables;
+    let uniforms = glium::uniforms::UniformBlob::new(&blob[..], variables).unwrap();
+    let mut names = Vec::new();
+    glium::uniforms::Uniforms::visit_values(&uniforms, |name, _| names.push(name.to_owned()));
+    names.sort();
+    assert_eq!(names, vec!["alpha".to_owned(), "color".to_owned()]);
+
+    let buffer = match glium::uniforms::UniformBuffer::new_array(&display, &blob) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    let uniforms = uniform!{
+        MyBlock: glium::uniforms::RawUniformBlock::new(&buffer)
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn row_major_block_reflection() {
+    let display = support::build_display();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            layout(std140, row_major) uniform MyBlock {
+                mat4 transform;
+            };
+
+            void main() {
+                gl_FragColor = transform[0];
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let variables = &program.get_uniform_blocks()["MyBlock"].variables;
+    assert_eq!(variables.len(), 1);
+    assert!(variables[0].is_row_major);
+
+    display.assert_no_error(None);
+}
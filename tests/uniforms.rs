@@ -358,3 +358,245 @@ uniform_test!(uniform_type_boolarr_boolvec3, "bvec3", [true, true, true]);
 uniform_test!(uniform_type_booltup_boolvec3, "bvec3", (false, false, false));
 uniform_test!(uniform_type_boolarr_boolvec4, "bvec4", [true, false, false, true]);
 uniform_test!(uniform_type_booltup_boolvec4, "bvec4", (false, true, true, false));
+
+#[test]
+fn texture_units_stable_across_draws() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D a;
+            uniform sampler2D b;
+
+            void main() {
+                gl_FragColor = texture2D(a, vec2(0.5, 0.5)) + texture2D(b, vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    assert!(program.get_texture_units().is_empty());
+
+    let red = support::build_unicolor_texture2d(&display, 1.0, 0.0, 0.0);
+    let green = support::build_unicolor_texture2d(&display, 0.0, 1.0, 0.0);
+    let blue = support::build_unicolor_texture2d(&display, 0.0, 0.0, 1.0);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ a: &red, b: &blue },
+                              &Default::default()).unwrap();
+    let units = program.get_texture_units().into_iter()
+        .map(|(name, unit)| (name.to_owned(), unit)).collect::<Vec<_>>();
+    assert_eq!(units.len(), 2);
+    assert!(units[0].1 != units[1].1);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 255, 255));
+
+    // same material
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ a: &red, b: &blue },
+                              &Default::default()).unwrap();
+    assert_eq!(program.get_texture_units(),
+               units.iter().map(|&(ref name, unit)| (&name[..], unit)).collect::<Vec<_>>());
+
+    // different material
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ a: &green, b: &blue },
+                              &Default::default()).unwrap();
+    assert_eq!(program.get_texture_units(),
+               units.iter().map(|&(ref name, unit)| (&name[..], unit)).collect::<Vec<_>>());
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[derive(Copy, Clone)]
+struct Light {
+    color: [f32; 3],
+    intensity: f32,
+}
+
+implement_uniform_struct!(Light, color, intensity);
+
+#[derive(Copy, Clone)]
+struct Scene {
+    lights: [Light; 2],
+    weights: [f32; 2],
+}
+
+implement_uniform_struct!(Scene, lights[..], weights[..]);
+
+#[test]
+fn uniform_struct_names() {
+    use glium::uniforms::Uniforms;
+
+    let light = Light { color: [1.0, 0.0, 0.0], intensity: 1.0 };
+    let scene = Scene { lights: [light, light], weights: [0.5, 0.5] };
+    let lights = [light];
+
+    let uniforms = uniform! {
+        scene: scene,
+        lights: &lights[..],
+    };
+
+    let mut names = Vec::new();
+    uniforms.visit_values(|name, _| names.push(name.to_owned()));
+    names.sort();
+
+    assert_eq!(names, vec![
+        "lights[0].color", "lights[0].intensity",
+        "scene.lights[0].color", "scene.lights[0].intensity",
+        "scene.lights[1].color", "scene.lights[1].intensity",
+        "scene.weights[0]", "scene.weights[1]",
+    ]);
+}
+
+#[test]
+fn uniform_struct_array() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            struct Light {
+                vec3 color;
+                float intensity;
+            };
+
+            uniform Light lights[2];
+            uniform float weights[2];
+
+            void main() {
+                vec3 color = lights[0].color * lights[0].intensity * weights[0] +
+                             lights[1].color * lights[1].intensity * weights[1];
+                gl_FragColor = vec4(color, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    let lights = [
+        Light { color: [1.0, 0.0, 0.0], intensity: 1.0 },
+        Light { color: [0.0, 1.0, 0.0], intensity: 2.0 },
+    ];
+
+    let uniforms = uniform! {
+        lights: &lights[..],
+        weights: &[1.0, 0.5f32][..],
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn owned_uniforms_merge() {
+    use glium::uniforms::Uniforms;
+
+    let mut material = glium::uniforms::OwnedUniforms::new();
+    material.add("roughness", 0.5f32);
+    material.add("metalness", 0.0f32);
+
+    let mut uniforms = glium::uniforms::OwnedUniforms::new();
+    uniforms.add_nested("material", &material);
+    uniforms.add_array("weights", vec![0.25f32, 0.75]);
+
+    let mut overrides = glium::uniforms::OwnedUniforms::new();
+    overrides.add("material.roughness", 0.9f32);
+    uniforms.merge(&overrides);
+
+    let mut values = Vec::new();
+    uniforms.visit_values(|name, value| {
+        match value {
+            glium::uniforms::UniformValue::Float(v) => values.push((name.to_owned(), v)),
+            _ => unreachable!()
+        }
+    });
+    values.sort_by(|a, b| a.0.cmp(&b.0));
+
+    assert_eq!(values, vec![
+        ("material.metalness".to_owned(), 0.0),
+        ("material.roughness".to_owned(), 0.9),
+        ("weights[0]".to_owned(), 0.25),
+        ("weights[1]".to_owned(), 0.75),
+    ]);
+}
+
+#[test]
+fn owned_uniforms_texture() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            struct Material {
+                sampler2D albedo;
+                float intensity;
+            };
+
+            uniform Material material;
+
+            void main() {
+                gl_FragColor = texture2D(material.albedo, vec2(0.5, 0.5)) * material.intensity;
+            }
+        ",
+        None).unwrap();
+
+    let albedo = std::rc::Rc::new(support::build_unicolor_texture2d(&display, 1.0, 1.0, 0.0));
+
+    let mut material = glium::uniforms::OwnedUniforms::new();
+    material.add("albedo", glium::uniforms::OwnedSampler::new(albedo));
+    material.add("intensity", 1.0f32);
+
+    let mut uniforms = glium::uniforms::OwnedUniforms::new();
+    uniforms.add_nested("material", &material);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
@@ -88,3 +88,178 @@ fn transform_feedback() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn vertex_buffer_any_into_typed() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position);
+
+    #[derive(Copy, Clone)]
+    struct OtherVertex {
+        color: [f32; 2],
+    }
+
+    implement_vertex!(OtherVertex, color);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [1.0, 2.0] }, Vertex { position: [3.0, 4.0] }
+    ]).unwrap();
+    let vb: glium::vertex::VertexBufferAny = vb.into();
+
+    assert!(vb.as_slice_of::<OtherVertex>().is_none());
+    assert_eq!(vb.as_slice_of::<Vertex>().unwrap().len(), 2);
+
+    let vb = vb.into_vertex_buffer::<OtherVertex>().err().unwrap();
+    let vb = vb.into_vertex_buffer::<Vertex>().ok().unwrap();
+
+    let data = vb.read().unwrap();
+    assert_eq!(data[0].position, [1.0, 2.0]);
+    assert_eq!(data[1].position, [3.0, 4.0]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn growable_vertex_buffer() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        value: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, value);
+
+    let mut vb = glium::vertex::GrowableVertexBuffer::with_capacity(&display, 2).unwrap();
+    assert_eq!(vb.capacity(), 2);
+    assert!(vb.is_empty());
+
+    vb.push_slice(&[Vertex { value: [1.0, 2.0] }, Vertex { value: [3.0, 4.0] }]).unwrap();
+    assert_eq!(vb.capacity(), 2);
+
+    match vb.push_slice(&[Vertex { value: [5.0, 6.0] }]) {
+        Err(glium::vertex::GrowError::CopyError(_)) => return,
+        e => e.unwrap(),
+    };
+
+    assert_eq!(vb.len(), 3);
+    assert_eq!(vb.capacity(), 4);
+
+    let data = vb.as_slice().read().unwrap();
+    assert_eq!(data[0].value, [1.0, 2.0]);
+    assert_eq!(data[1].value, [3.0, 4.0]);
+    assert_eq!(data[2].value, [5.0, 6.0]);
+
+    vb.clear();
+    assert!(vb.is_empty());
+    assert_eq!(vb.capacity(), 4);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn vertex_layout() {
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position);
+
+    #[derive(Copy, Clone)]
+    struct Instance {
+        color: [f32; 3],
+    }
+
+    implement_vertex!(Instance, color);
+
+    let layout = glium::vertex::VertexLayout::new().with_vertices::<Vertex>()
+                                                   .with_per_instance::<Instance>();
+    assert_eq!(layout, glium::vertex::VertexLayout::new().with_vertices::<Vertex>()
+                                                         .with_per_instance::<Instance>());
+    assert!(layout != glium::vertex::VertexLayout::new().with_vertices::<Vertex>()
+                                                        .with_vertices::<Instance>());
+
+    let bindings = layout.get_bindings();
+    assert_eq!(bindings.len(), 2);
+    assert_eq!(bindings[0].1, 8);
+    assert_eq!(bindings[0].2, None);
+    assert_eq!(bindings[1].1, 12);
+    assert_eq!(bindings[1].2, Some(1));
+}
+
+#[test]
+fn vertex_layout_buffer_swap() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+        color: [f32; 3],
+    }
+
+    implement_vertex!(Vertex, position, color);
+
+    let build = |color| {
+        glium::VertexBuffer::new(&display, &[
+            Vertex { position: [-1.0,  1.0], color }, Vertex { position: [1.0,  1.0], color },
+            Vertex { position: [-1.0, -1.0], color }, Vertex { position: [1.0, -1.0], color },
+        ]).unwrap()
+    };
+
+    let red = build([1.0, 0.0, 0.0]);
+    let blue = build([0.0, 0.0, 1.0]);
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+                attribute vec3 color;
+                varying vec3 v_color;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 110
+
+                varying vec3 v_color;
+
+                void main() {
+                    gl_FragColor = vec4(v_color, 1.0);
+                }
+            ",
+        },
+    ).unwrap();
+
+    let indices = glium::index::NoIndices(glium::index::PrimitiveType::TriangleStrip);
+    let texture = support::build_renderable_texture(&display);
+
+    for &(ref vb, expected) in &[(&red, (255, 0, 0, 255)), (&blue, (0, 0, 255, 255)),
+                                 (&red, (255, 0, 0, 255))]
+    {
+        texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        texture.as_surface().draw(*vb, &indices, &program, &glium::uniforms::EmptyUniforms,
+                                  &Default::default()).unwrap();
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        assert_eq!(data[0][0], expected);
+    }
+
+    // the buffers can be destroyed while the layout is still in use
+    drop(red);
+    texture.as_surface().draw(&blue, &indices, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    display.assert_no_error(None);
+}
@@ -544,6 +544,117 @@ fn instancing() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn instancing_emulation() {
+    let display = support::build_display();
+    display.set_instancing_emulation(true);
+
+    let buffer1 = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            position: [f32; 2],
+        }
+
+        implement_vertex!(Vertex, position);
+
+        glium::VertexBuffer::new(&display,
+            &[
+                Vertex { position: [-1.0,  1.0] },
+                Vertex { position: [ 0.0,  1.0] },
+                Vertex { position: [-1.0, -1.0] },
+                Vertex { position: [ 0.0, -1.0] },
+            ]
+        ).unwrap()
+    };
+
+    let buffer2 = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            offset: [f32; 2],
+            color: [f32; 3],
+        }
+
+        implement_vertex!(Vertex, offset, color);
+
+        glium::vertex::VertexBuffer::new(&display,
+            &[
+                Vertex { offset: [0.0, 0.0], color: [1.0, 0.0, 0.0] },
+                Vertex { offset: [1.0, 0.0], color: [0.0, 1.0, 0.0] },
+            ]
+        ).unwrap()
+    };
+
+    // instancing is either supported or emulated
+    let buffer2 = buffer2.per_instance().unwrap();
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+                attribute vec2 offset;
+                attribute vec3 color;
+
+                varying vec3 v_color;
+
+                void main() {
+                    gl_Position = vec4(position + offset, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 110
+
+                varying vec3 v_color;
+
+                void main() {
+                    gl_FragColor = vec4(v_color, 1.0);
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+                attribute lowp vec2 offset;
+                attribute lowp vec3 color;
+
+                varying lowp vec3 v_color;
+
+                void main() {
+                    gl_Position = vec4(position + offset, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 100
+
+                varying lowp vec3 v_color;
+
+                void main() {
+                    gl_FragColor = vec4(v_color, 1.0);
+                }
+            ",
+        },
+    ).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw((&buffer1, buffer2),
+                              &glium::index::NoIndices(PrimitiveType::TriangleStrip),
+                              &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        assert_eq!(row[0], (255, 0, 0, 255));
+        assert_eq!(row[row.len() - 1], (0, 255, 0, 255));
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn per_instance_length_mismatch() {
     let display = support::build_display();